@@ -0,0 +1,49 @@
+# mouse_look.py - FPS-style mouse look: click to grab the cursor, Escape to let it go
+import math
+import xos
+
+SENSITIVITY = 0.003  # radians per pixel of mouse motion
+STARS = [(i * 0.61803 % 1.0 * math.tau, (i * 0.37 % 1.0 - 0.5) * 1.2) for i in range(120)]
+
+
+class MouseLook(xos.Application):
+    headless: bool = False
+
+    def __init__(self):
+        super().__init__()
+        self.yaw = 0.0
+        self.pitch = 0.0
+
+    def setup(self):
+        xos.window.set_title("mouse look - click to capture")
+
+    def tick(self):
+        dx, dy = xos.window.mouse_motion()
+        if xos.window.is_cursor_locked():
+            self.yaw += dx * SENSITIVITY
+            self.pitch = max(-1.2, min(1.2, self.pitch - dy * SENSITIVITY))
+
+        self.frame.clear(xos.color.BLACK)
+        w, h = self.get_width(), self.get_height()
+        fov = math.pi / 2
+        points = []
+        for angle, elevation in STARS:
+            rel = (angle - self.yaw + math.pi) % math.tau - math.pi
+            if abs(rel) < fov / 2:
+                points.append((w / 2 + rel / fov * w, h / 2 - (elevation - self.pitch) / fov * w))
+        if points:
+            xos.rasterizer.circles(self.frame, points, [3.0] * len(points), (255, 255, 255, 255))
+        xos.rasterizer.circles(self.frame, [(w / 2, h / 2)], [4.0], (255, 60, 60, 255))
+
+    def on_mouse_down(self, x, y):
+        xos.window.lock_cursor()
+        xos.window.set_title("mouse look - Escape to release")
+
+    def on_key_down(self, key, code, repeat):
+        if code == "Escape":
+            xos.window.unlock_cursor()
+            xos.window.set_title("mouse look - click to capture")
+
+
+if __name__ == "__main__":
+    MouseLook().run()
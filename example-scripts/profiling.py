@@ -0,0 +1,34 @@
+# profiling.py - named profiler scopes: press F3 for the timeline, Shift+F3 to save a Chrome trace
+import math
+import xos
+
+PARTICLES = 3000
+
+
+class ProfilingDemo(xos.Application):
+    headless: bool = False
+
+    def __init__(self):
+        super().__init__()
+        self.points = [(i * 0.61803 % 1.0, i * 0.7548 % 1.0) for i in range(PARTICLES)]
+        self.time = 0.0
+
+    @xos.profile("physics")
+    def step(self, dt):
+        self.time += dt
+        self.points = [
+            ((x + 0.02 * dt * math.sin(self.time + y * 6.0)) % 1.0, (y + 0.05 * dt) % 1.0)
+            for x, y in self.points
+        ]
+
+    def tick(self):
+        self.step(self.dt)
+        w, h = self.get_width(), self.get_height()
+        with xos.profile("build draw list"):
+            centers = [(x * w, y * h) for x, y in self.points]
+        self.frame.clear(xos.color.BLACK)
+        xos.rasterizer.circles(self.frame, centers, [2.0], (120, 200, 255, 255))
+
+
+if __name__ == "__main__":
+    ProfilingDemo().run()
@@ -0,0 +1,20 @@
+# captions.py - timed Whisper segments and word timestamps, written out as SRT / WebVTT captions
+import xos
+
+AUDIO = "speech.wav"
+
+wave = xos.audio.load(AUDIO, 16000)
+whisper = xos.ai.whisper.load("tiny")
+result = whisper.transcribe(wave, 16000, language="auto", word_timestamps=True)
+
+print(f"language: {result['language']} ({result['language_probability'] or 0:.0%})")
+for seg in result["segments"]:
+    print(f"[{seg['start']:6.2f} -> {seg['end']:6.2f}] {seg['text']}")
+    for word in seg["words"]:
+        print(f"    {word['start']:6.2f} {word['end']:6.2f} {word['probability']:.2f} {word['text']}")
+
+with open("speech.srt", "w") as f:
+    f.write(xos.ai.whisper.to_srt(result))
+with open("speech.vtt", "w") as f:
+    f.write(xos.ai.whisper.to_vtt(result))
+print("wrote speech.srt and speech.vtt")
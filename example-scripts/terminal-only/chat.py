@@ -0,0 +1,21 @@
+# chat.py - a terminal chat with a local Llama-family model (build xos with --features llama)
+import xos
+
+model = xos.ai.llama.load()  # SmolLM2 135M Instruct; downloaded on first run
+print(f"{model.config['num_layers']} layers, {model.template} template, {model.context_len}-token context")
+print("empty line to quit, /reset to start over\n")
+
+messages = [{"role": "system", "content": "You are a helpful, concise assistant."}]
+while True:
+    line = input("you> ").strip()
+    if not line:
+        break
+    if line == "/reset":
+        messages = messages[:1]
+        model.reset()
+        continue
+    messages.append({"role": "user", "content": line})
+    print("bot> ", end="")
+    reply = model.chat(messages, max_tokens=200, temperature=0.6, on_token=lambda piece: print(piece, end=""))
+    print(f"\n     [{len(reply['tokens'])} tokens, {reply['finish_reason']}]\n")
+    messages.append({"role": "assistant", "content": reply["text"]})
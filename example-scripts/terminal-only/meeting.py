@@ -0,0 +1,20 @@
+# meeting.py - "who said what": Whisper segments labelled by speaker, with one enrolled voice
+import xos
+
+AUDIO = "meeting.wav"
+ALICE = "alice.wav"  # a few seconds of Alice speaking on her own
+
+wave = xos.audio.load(AUDIO, 16000)
+whisper = xos.ai.whisper.load("tiny")
+result = whisper.transcribe(wave, 16000, speakers={"Alice": xos.audio.load(ALICE, 16000)})
+
+speaker = None
+for seg in result["segments"]:
+    if seg["speaker"] != speaker:
+        speaker = seg["speaker"]
+        print(f"\n{speaker or 'Unknown'}:")
+    print(f"  [{seg['start']:6.2f}] {seg['text'].strip()}")
+
+with open("meeting.srt", "w") as f:
+    f.write(xos.ai.whisper.to_srt(result))
+print("\nwrote meeting.srt")
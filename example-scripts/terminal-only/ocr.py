@@ -0,0 +1,20 @@
+# ocr.py - read the text on your screen (or webcam) with on-device OCR (build xos with --features ocr)
+import xos
+
+SOURCE = "monitor"  # or "webcam"
+
+if SOURCE == "webcam":
+    frame = xos.vision.webcam_frame()
+else:
+    frame = xos.system.monitors[0].get_frame()  # macOS / Windows
+
+# CRAFT detector + English recognizer; downloaded on first run and kept loaded between calls.
+for line in xos.vision.ocr(frame, min_confidence=0.3):
+    x1, y1, x2, y2 = line["box"]
+    print(f"[{x1:5.0f} {y1:5.0f} {x2:5.0f} {y2:5.0f}] {line['confidence']:.2f}  {line['text']}")
+
+# The raw model home, for inspecting weights and activations.
+model = xos.ai.ocr.load()
+print(f"\n{model.parameter_count} parameter tensors in {', '.join(model.weights_files)}")
+for name, act in model.forward_layer_by_layer(frame, canvas_size=640):
+    print(f"{name:32s} {act.shape}")
@@ -0,0 +1,21 @@
+# speak.py - read text aloud with a local TTS voice (build xos with --features tts)
+import xos
+
+TEXT = "Hello from x o s. This voice runs entirely on your machine."
+
+# MMS-TTS English voice; downloaded on first run and kept loaded between calls.
+speaker = xos.audio.Speaker()
+xos.audio.speak(TEXT, speaker=speaker, speed=1.1)
+
+# Without a speaker the audio comes back as a tensor (16 kHz mono for MMS voices).
+audio = xos.audio.speak("Same voice, returned as samples.", seed=0)
+print(f"{audio.shape[0]} samples")
+
+# The raw model home, for inspecting weights and activations.
+model = xos.ai.tts.load()
+print(f"{model.parameter_count} parameter tensors at {model.sample_rate} Hz, {model.num_speakers} speaker(s)")
+for name, act in model.forward_layer_by_layer("hello", seed=0):
+    print(f"{name:36s} {act.shape}")
+
+while speaker.buffer.size() > 0:
+    xos.sleep(0.1)
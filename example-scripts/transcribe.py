@@ -1,8 +1,10 @@
 import xos
 
 # Same defaults as xos.ai.whisper.load: tiny + CT2.
-LANGUAGE = "english"  # "english" or "japanese"
-# LANGUAGE = "japanese"  # "english" or "japanese"
+LANGUAGE = "english"  # any Whisper language code or name ("ja", "german", ...)
+# LANGUAGE = "auto"  # detect the spoken language on every decode
+TASK = "transcribe"
+# TASK = "translate"  # captions in English whatever the spoken language
 
 SIZE = "tiny"
 # SIZE = "base"
@@ -10,7 +12,7 @@ SIZE = "tiny"
 
 
 audio = xos.audio.system(buffer_duration=10.0)
-transcriber = xos.audio.transcription(audio, size=SIZE, language=LANGUAGE)
+transcriber = xos.audio.transcription(audio, size=SIZE, language=LANGUAGE, task=TASK)
 if xos.flags.record:
     recorder = xos.audio.recording(audio, "test.mp3")
 
@@ -40,6 +42,9 @@ def render():
     if in_speech_segment and segment_live_text.strip():
         xos.print_color("&8" + segment_live_text.strip())
     raw = transcriber.vad_prob()
+    detected = transcriber.detected_language()
+    if detected:
+        xos.print_color(f"&7LANG {detected[0]} ({detected[1]*100:.0f}%)")
     xos.print_color(
         f"&7RAW {raw*100:5.1f}%  EMA {vad_ema*100:5.1f}%  THR {THRESHOLD*100:5.1f}%  BUF {transcriber.buffered_seconds():.2f}s"
     )
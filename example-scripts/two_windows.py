@@ -0,0 +1,49 @@
+# two_windows.py - a visualization plus a separate inspector window in one process
+import xos
+
+
+class Inspector(xos.Application):
+    """Second window: its own frame, mouse and callbacks, reading state off the main app."""
+
+    def __init__(self, target):
+        super().__init__()
+        self.target = target
+
+    def tick(self):
+        self.frame.clear(xos.color.BLACK)
+        lines = [
+            f"ticks: {self.target.t}",
+            f"fps: {self.target.fps:.0f}",
+            f"clicks: {self.target.clicks}",
+        ]
+        for i, line in enumerate(lines):
+            xos.rasterizer.text(line, 16.0, 20.0 + 28.0 * i, 20.0, (255, 255, 255))
+
+    def on_mouse_down(self, x, y):
+        self.target.clicks = 0  # click the inspector to reset the counter
+
+
+class Visualization(xos.Application):
+    headless: bool = False
+
+    def __init__(self):
+        super().__init__()
+        self.clicks = 0
+        self.inspector = None
+
+    def tick(self):
+        if self.inspector is None:
+            # Beside the main window: on the second monitor when there is one.
+            monitor = 1 if len(xos.system.monitors) > 1 else None
+            self.inspector = self.open_window(
+                Inspector(self), title="Inspector", width=320, height=140, monitor=monitor
+            )
+        shade = (self.t * 2) % 255
+        self.frame.clear((shade, 40, 255 - shade, 255))
+
+    def on_mouse_down(self, x, y):
+        self.clicks += 1
+
+
+if __name__ == "__main__":
+    Visualization().run()
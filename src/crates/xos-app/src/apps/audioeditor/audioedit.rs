@@ -156,6 +156,37 @@ impl AudioEditApp {
         }
     }
 
+    /// Decode `path` for the waveform and start playing it from the beginning.
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    fn open_audio_file(&mut self, path: PathBuf) -> Result<(), String> {
+        xos_core::print(&format!("Selected file: {:?}", path));
+
+        // Store the file path for seeking
+        self.audio_file_path = Some(path.clone());
+
+        // Load all audio samples for visualization (fills mono_pcm)
+        self.load_full_audio_samples(&path)?;
+
+        let sample_rate = self.sample_rate;
+        let buffer_capacity = sample_rate.max(8_000) as usize;
+        let sample_buffer = Arc::new(Mutex::new(VecDeque::with_capacity(buffer_capacity)));
+        self.audio_samples = Some(sample_buffer);
+
+        let out = default_output().ok_or_else(|| "No audio output device found".to_string())?;
+        let player = AudioPlayer::new(&out, sample_rate, 2)
+            .map_err(|e| format!("Failed to open audio output: {e}"))?;
+        self.audio_player = Some(Arc::new(player));
+        self.feed_cursor = 0;
+        self.last_seek_position = 0.0;
+        self.playback_position = 0.0;
+        self.playback_start_position = 0.0;
+        self.playback_start_time = Some(Instant::now());
+        self.zoom_center = 0.0; // Start zoomed at the beginning
+
+        xos_core::print(&format!("Playing audio file: {:?}", path));
+        Ok(())
+    }
+
     /// Load all audio samples from the file for visualization (desktop: Symphonia decode).
     #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
     fn load_full_audio_samples(&mut self, file_path: &PathBuf) -> Result<(), String> {
@@ -619,40 +650,13 @@ impl Application for AudioEditApp {
         #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
         {
             // Open file picker for audio files
-            let file = rfd::FileDialog::new()
-                .add_filter("Audio Files", &["mp3", "wav", "flac", "ogg", "m4a", "aac"])
-                .add_filter("All Files", &["*"])
-                .pick_file();
-
-            if let Some(path) = file {
-                xos_core::print(&format!("Selected file: {:?}", path));
-                
-                // Store the file path for seeking
-                self.audio_file_path = Some(path.clone());
-                
-                // Load all audio samples for visualization (fills mono_pcm)
-                self.load_full_audio_samples(&path)?;
-
-                let sample_rate = self.sample_rate;
-                let buffer_capacity = sample_rate.max(8_000) as usize;
-                let sample_buffer = Arc::new(Mutex::new(VecDeque::with_capacity(buffer_capacity)));
-                self.audio_samples = Some(sample_buffer);
-
-                let out = default_output().ok_or_else(|| "No audio output device found".to_string())?;
-                let player = AudioPlayer::new(&out, sample_rate, 2)
-                    .map_err(|e| format!("Failed to open audio output: {e}"))?;
-                self.audio_player = Some(Arc::new(player));
-                self.feed_cursor = 0;
-                self.last_seek_position = 0.0;
-                self.playback_position = 0.0;
-                self.playback_start_position = 0.0;
-                self.playback_start_time = Some(Instant::now());
-                self.zoom_center = 0.0; // Start zoomed at the beginning
-
-                xos_core::print(&format!("Playing audio file: {:?}", path));
-            } else {
+            let options = xos_core::dialog::FileDialogOptions::default()
+                .filter("Audio Files", &["mp3", "wav", "flac", "ogg", "m4a", "aac"])
+                .filter("All Files", &["*"]);
+            match xos_core::dialog::open_file(&options) {
+                Some(path) => self.open_audio_file(path)?,
                 // No audio file selected - close the app
-                return Err("No audio file selected. Application will close.".to_string());
+                None => return Err("No audio file selected. Application will close.".to_string()),
             }
         }
 
@@ -795,6 +799,16 @@ impl Application for AudioEditApp {
         self.render_play_pause_button(state);
     }
 
+    /// Dropping an audio file on the window replaces the current one.
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    fn on_file_drop(&mut self, _state: &mut EngineState, paths: &[std::path::PathBuf]) {
+        if let Some(path) = paths.first() {
+            if let Err(e) = self.open_audio_file(path.clone()) {
+                xos_core::print(&format!("Could not open {:?}: {e}", path));
+            }
+        }
+    }
+
     fn on_mouse_down(&mut self, _state: &mut EngineState) {
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -1,3 +1,4 @@
+use xos_core::engine::gamepad::{GamepadButton, GamepadEvent, GamepadEventKind};
 use xos_core::engine::{Application, EngineState};
 use xos_core::rasterizer::{circles, fill};
 
@@ -6,6 +7,8 @@ const BALL_RADIUS: f32 = 15.0;
 const SPEED_MULTIPLIER: f32 = 3.45;
 /// Original movement was per-frame at ~60 Hz; scale random [-2,2]*multiplier to px/s.
 const REF_FPS: f32 = 60.0;
+/// Gamepad left stick tilts the box: px/s² at full deflection.
+const STICK_ACCEL: f32 = 900.0;
 
 struct BallState {
     x: f32,
@@ -108,8 +111,16 @@ impl Application for BallGame {
     fn tick(&mut self, state: &mut EngineState) {
         // Clear the frame (no longer auto-cleared)
         fill(&mut state.frame, (0, 0, 0, 255));
-        
+
+        let dt = state.delta_time_seconds;
+        let (ax, ay) = state
+            .gamepads
+            .first()
+            .map(|pad| pad.left_stick())
+            .unwrap_or((0.0, 0.0));
         for ball in &mut self.balls {
+            ball.vx += ax * STICK_ACCEL * dt;
+            ball.vy += ay * STICK_ACCEL * dt;
             ball.update(
                 state.frame.shape()[1] as f32,
                 state.frame.shape()[0] as f32,
@@ -128,4 +139,14 @@ impl Application for BallGame {
         self.balls.push(BallState::new_at_position(state.mouse.x, state.mouse.y, BALL_RADIUS));
         xos_core::print("+1 ball (click spawn)");
     }
+
+    fn on_gamepad(&mut self, state: &mut EngineState, event: &GamepadEvent) {
+        if event.kind == GamepadEventKind::ButtonDown(GamepadButton::South) {
+            let x = state.frame.shape()[1] as f32 / 2.0;
+            let y = state.frame.shape()[0] as f32 / 2.0;
+            self.balls.push(BallState::new_at_position(x, y, BALL_RADIUS));
+            state.gamepads.rumble(event.id, 0.3, 0.6, 80);
+            xos_core::print("+1 ball (gamepad spawn)");
+        }
+    }
 }
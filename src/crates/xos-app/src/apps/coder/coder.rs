@@ -11,6 +11,7 @@ use xos_core::rasterizer::text::fonts;
 use rustpython_vm::{Interpreter, AsObject};
 use include_dir::{include_dir, Dir};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::thread;
 // Embed the entire example-scripts/ directory at compile time
@@ -55,7 +56,6 @@ enum Tab {
 struct PythonFile {
     name: String,
     content: String,
-    #[allow(dead_code)]
     path: String,
 }
 
@@ -227,6 +227,47 @@ impl CoderApp {
         self.explorer_search_focused = false;
     }
 
+    /// Open files from outside the bundled scripts (dropped on the window or picked with Alt+O) as
+    /// editor tabs. They are not listed in the explorer.
+    fn open_external_files(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            let path_str = path.to_string_lossy().into_owned();
+            let content = match xos_core::fs::read_to_string(&path_str) {
+                Ok(content) => content,
+                Err(e) => {
+                    xos_core::print(&format!("Could not open {path_str}: {e}"));
+                    continue;
+                }
+            };
+            let file_index = match self.python_files.iter().position(|f| f.path == path_str) {
+                Some(i) => i,
+                None => {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path_str.clone());
+                    self.python_files.push(PythonFile {
+                        name,
+                        content,
+                        path: path_str,
+                    });
+                    self.python_files.len() - 1
+                }
+            };
+            self.open_or_select_file_from_explorer(file_index);
+        }
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    fn open_file_dialog(&mut self) {
+        let options = xos_core::dialog::FileDialogOptions::default()
+            .filter("Python", &["py"])
+            .filter("All Files", &["*"]);
+        // The browser delivers its picks later through `on_file_drop`.
+        let paths = xos_core::dialog::open_files(&options);
+        self.open_external_files(&paths);
+    }
+
     fn switch_editor_tab_to(&mut self, to: usize) {
         if to >= self.open_editor_tabs.len() || to == self.active_editor_tab {
             return;
@@ -3062,6 +3103,10 @@ impl Application for CoderApp {
                 CoderShortcutAction::ToggleBorderlessFullscreen => {
                     // Handled by native engine host.
                 }
+                CoderShortcutAction::OpenFile => {
+                    self.active_tab = Tab::Code;
+                    self.open_file_dialog();
+                }
             }
             return;
         }
@@ -3114,6 +3159,11 @@ impl Application for CoderApp {
         }
     }
 
+    fn on_file_drop(&mut self, _state: &mut EngineState, paths: &[PathBuf]) {
+        self.active_tab = Tab::Code;
+        self.open_external_files(paths);
+    }
+
     fn on_key_shortcut(&mut self, state: &mut EngineState, shortcut: ShortcutAction) {
         match self.active_tab {
             Tab::Code => self.code_app.on_key_shortcut(state, shortcut),
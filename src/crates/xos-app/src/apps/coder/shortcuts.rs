@@ -20,6 +20,7 @@ pub enum CoderShortcutAction {
     NextEditorTab,
     ToggleViewportTaskbar,
     ToggleBorderlessFullscreen,
+    OpenFile,
 }
 
 pub fn detect_coder_shortcut(event: &SpecialKeyEvent) -> Option<CoderShortcutAction> {
@@ -54,6 +55,7 @@ pub fn detect_coder_shortcut(event: &SpecialKeyEvent) -> Option<CoderShortcutAct
             PhysicalSpecialKey::KeyF => Some(CoderShortcutAction::ToggleViewportTaskbar),
             PhysicalSpecialKey::KeyT => Some(CoderShortcutAction::ShowTerminal),
             PhysicalSpecialKey::KeyR => Some(CoderShortcutAction::ShowViewport),
+            PhysicalSpecialKey::KeyO => Some(CoderShortcutAction::OpenFile),
         };
     }
 
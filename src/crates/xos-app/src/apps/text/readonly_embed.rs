@@ -724,7 +724,7 @@ impl TranscriptTextView {
             let d = dx * dx + dy * dy;
             if d < min_dist_sq {
                 min_dist_sq = d;
-                nearest_idx = if text_x > cx {
+                nearest_idx = if (text_x > cx) != c.rtl {
                     c.char_index + 1
                 } else {
                     c.char_index
@@ -758,10 +758,13 @@ impl TranscriptTextView {
                 .iter()
                 .find(|c| c.char_index == cursor)
             {
-                return (char_at_cursor.x, line.baseline_y);
+                return (
+                    self.text_rasterizer.caret_x_before(char_at_cursor),
+                    line.baseline_y,
+                );
             }
             if let Some(last) = chars_in_line.last() {
-                return (last.x + last.metrics.advance_width, line.baseline_y);
+                return (self.text_rasterizer.caret_x_after(last), line.baseline_y);
             }
         }
         (0.0, self.text_rasterizer.ascent)
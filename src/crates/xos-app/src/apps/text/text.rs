@@ -7,7 +7,7 @@ use xos_core::engine::keyboard::shortcuts::ShortcutAction;
 use xos_core::engine::{Application, EngineState, ScrollWheelUnit};
 use xos_core::rasterizer::text::fonts;
 use xos_core::rasterizer::text::text_rasterization::{
-    line_band_intersects_doc_viewport, TextLayoutAlign, TextRasterizer,
+    line_band_intersects_doc_viewport, GlyphRenderMode, TextLayoutAlign, TextRasterizer,
 };
 use xos_core::rasterizer::text::ui_markup;
 use xos_core::rasterizer::{fill, fill_rect_buffer};
@@ -218,6 +218,8 @@ impl TextApp {
         };
 
         let mut text_rasterizer = TextRasterizer::new(font, font_size);
+        // Pinch / UI-scale zoom re-sizes every frame; let the rasterizer hop onto the SDF atlas meanwhile.
+        text_rasterizer.set_render_mode(GlyphRenderMode::Auto);
 
         // Set default text on iOS
         let initial_cursor_pos = if cfg!(target_os = "ios") {
@@ -2391,7 +2393,8 @@ impl TextApp {
 
             let dist_left = (screen_x - left).abs();
             let dist_right = (screen_x - right).abs();
-            best_index = if dist_right < dist_left {
+            // Right-to-left glyphs: the logical "after" edge is on the left.
+            best_index = if (dist_right < dist_left) != character.rtl {
                 character.char_index + 1
             } else {
                 character.char_index
@@ -2508,18 +2511,21 @@ impl TextApp {
 
                     if let Some(char_at_cursor) = found_char {
                         // Cursor is before this character
-                        (char_at_cursor.x, line.baseline_y)
+                        (
+                            self.text_rasterizer.caret_x_before(char_at_cursor),
+                            line.baseline_y,
+                        )
                     } else if let Some(char_after_cursor) = char_after {
                         // Cursor is before this character (on same line)
-                        (char_after_cursor.x, line.baseline_y)
+                        (
+                            self.text_rasterizer.caret_x_before(char_after_cursor),
+                            line.baseline_y,
+                        )
                     } else {
                         // Cursor is at end of line - find last character's end position
                         if let Some(last_in_line) = chars_in_line.last() {
                             (
-                                last_in_line.x
-                                    + self
-                                        .text_rasterizer
-                                        .advance_with_spacing(last_in_line.metrics.advance_width),
+                                self.text_rasterizer.caret_x_after(last_in_line),
                                 line.baseline_y,
                             )
                         } else {
@@ -2561,10 +2567,7 @@ impl TextApp {
                     )
                 } else if let Some(last_char) = chars_in_last_line.last() {
                     (
-                        last_char.x
-                            + self
-                                .text_rasterizer
-                                .advance_with_spacing(last_char.metrics.advance_width),
+                        self.text_rasterizer.caret_x_after(last_char),
                         last_line.baseline_y,
                     )
                 } else {
@@ -2575,10 +2578,7 @@ impl TextApp {
                 }
             } else if let Some(last) = self.text_rasterizer.characters.last() {
                 (
-                    last.x
-                        + self
-                            .text_rasterizer
-                            .advance_with_spacing(last.metrics.advance_width),
+                    self.text_rasterizer.caret_x_after(last),
                     self.text_rasterizer
                         .lines
                         .last()
@@ -2612,12 +2612,9 @@ impl TextApp {
                 continue;
             }
             if character.char_index >= clamped {
-                return character.x;
+                return self.text_rasterizer.caret_x_before(character);
             }
-            x = character.x
-                + self
-                    .text_rasterizer
-                    .advance_with_spacing(character.metrics.advance_width);
+            x = self.text_rasterizer.caret_x_after(character);
         }
         x
     }
@@ -1,5 +1,6 @@
 use xos_core::ai::transcription::{
-    transcribe_waveform_once_with_language, TranscriptionEngine, WhisperBackend,
+    transcribe_waveform_once_detailed, transcribe_waveform_once_with_language, TranscribeOptions,
+    TranscriptionEngine, WhisperBackend, WhisperTask,
 };
 use crate::apps::text::TranscriptTextView;
 use xos_core::clipboard;
@@ -58,6 +59,8 @@ const HOLD_TO_RECORD_THRESHOLD: Duration = Duration::from_millis(250);
 const DOUBLE_TAP_TIME_MS: u64 = 300;
 const DOUBLE_TAP_DISTANCE: f32 = 50.0;
 const HOLD_FINAL_TEXT_COLOR: (u8, u8, u8) = (92, 230, 142);
+/// English lines from the translate decode, under the original caption.
+const TRANSLATION_TEXT_COLOR: (u8, u8, u8) = (120, 180, 255);
 
 #[derive(Clone, Debug)]
 struct TranscriptEntry {
@@ -72,6 +75,23 @@ fn transcribe_backend_from_env() -> WhisperBackend {
         .unwrap_or(WhisperBackend::Ct2)
 }
 
+/// `XOS_TRANSCRIBE_DIARIZE=1` prefixes each committed line with its speaker (`Speaker 1: ...`).
+fn enable_diarization_from_env(engine: &mut TranscriptionEngine) {
+    if std::env::var("XOS_TRANSCRIBE_DIARIZE").ok().as_deref() != Some("1") {
+        return;
+    }
+    #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+    match xos_core::ai::transcription::diarization::Diarizer::new() {
+        Ok(diarizer) => engine.enable_diarization(diarizer),
+        Err(e) => eprintln!("transcribe: diarization unavailable: {e}"),
+    }
+    #[cfg(not(all(feature = "whisper", not(target_arch = "wasm32"))))]
+    {
+        let _ = engine;
+        eprintln!("transcribe: diarization unavailable in this build");
+    }
+}
+
 fn clamp_threshold(v: f32) -> f32 {
     v.clamp(THRESHOLD_MIN, THRESHOLD_MAX)
 }
@@ -874,6 +894,8 @@ impl VisualCanvas {
 
 pub struct TranscribeApp {
     listener: Option<audio::AudioListener>,
+    /// Live decode; also translates each job to English while
+    /// [`TranscribeLanguageSelector::translate`] is on.
     engine: TranscriptionEngine,
     canvas: VisualCanvas,
     vad_label: TextRasterizer,
@@ -897,7 +919,7 @@ pub struct TranscribeApp {
     /// Left button down in transcript; drag scrolls (same as standalone text).
     transcript_pointer_down: bool,
     audio_selector: AudioInputSelector,
-    /// Transcription language (`en` / `ja`); no-op on builds without the whisper feature.
+    /// Transcription language (Whisper code or `auto`); no-op on builds without the whisper feature.
     lang_selector: TranscribeLanguageSelector,
     live_transcribe_enabled: bool,
     mic_pointer_down: bool,
@@ -923,16 +945,18 @@ impl TranscribeApp {
         let mut state_label = TextRasterizer::new(font.clone(), 24.0);
         state_label.set_text("SILENCE".to_string());
         let transcript_view = TranscriptTextView::new(font.clone(), 30.0);
-        let engine = match TranscriptionEngine::new_with_size_backend_language(
+        let mut engine = match TranscriptionEngine::new_with_size_backend_language(
             None,
             transcribe_backend_from_env(),
             Some("en"),
+            WhisperTask::Transcribe,
         ) {
             Ok(e) => e,
             Err(_) => {
                 TranscriptionEngine::new_with_size_and_backend(None, transcribe_backend_from_env())
             }
         };
+        enable_diarization_from_env(&mut engine);
         Self {
             listener: None,
             engine,
@@ -997,13 +1021,25 @@ impl TranscribeApp {
         self.transcript_view.set_font(new_font);
     }
 
-    /// Replace the live Whisper decode thread with the current [`TranscribeLanguageSelector`] code.
+    /// Replace the live Whisper decode thread with the current [`TranscribeLanguageSelector`]
+    /// code and translate toggle.
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
     fn recreate_transcription_engine(&mut self) {
-        let code = self.lang_selector.current_language_code();
+        let code = Some(self.lang_selector.current_language_code());
         let backend = transcribe_backend_from_env();
-        match TranscriptionEngine::new_with_size_backend_language(None, backend, Some(code)) {
-            Ok(e) => {
+        let engine = if self.lang_selector.translate {
+            TranscriptionEngine::new_with_translation(None, backend, code)
+        } else {
+            TranscriptionEngine::new_with_size_backend_language(
+                None,
+                backend,
+                code,
+                WhisperTask::Transcribe,
+            )
+        };
+        match engine {
+            Ok(mut e) => {
+                enable_diarization_from_env(&mut e);
                 self.engine = e;
                 if let Some(l) = &self.listener {
                     self.engine
@@ -1090,6 +1126,13 @@ impl TranscribeApp {
         }
     }
 
+    /// Commit the translation `engine` decoded for the utterance it just committed.
+    fn commit_translation(&mut self) {
+        for line in self.engine.drain_translation_commits() {
+            self.push_dedup_committed_line(&line, Some(TRANSLATION_TEXT_COLOR));
+        }
+    }
+
     fn transcript_text_size(height: f32, f3_ui_scale_mul: f32) -> f32 {
         let base = (height * 0.039).clamp(TRANSCRIPT_TEXT_SIZE_MIN, TRANSCRIPT_TEXT_SIZE_MAX);
         (base * f3_ui_scale_mul.clamp(0.25, 5.0))
@@ -1278,6 +1321,7 @@ impl Application for TranscribeApp {
             // Force-commit + clip even if silence gating didn't trigger, so segment PCM cannot grow forever.
             self.engine.flush_live_to_stdout_commits();
             self.drain_engine_commits_to_transcript();
+            self.commit_translation();
             self.engine.clip_consumed_audio_cursor();
             self.segment_live_text.clear();
             self.in_speech_segment = false;
@@ -1303,6 +1347,11 @@ impl Application for TranscribeApp {
         } else {
             String::new()
         };
+        let live_translation = if self.live_transcribe_enabled {
+            self.engine.translation().trim().to_string()
+        } else {
+            String::new()
+        };
         if speech_now {
             self.speech_run_frames = self.speech_run_frames.saturating_add(1);
             self.silence_run_frames = 0;
@@ -1341,6 +1390,7 @@ impl Application for TranscribeApp {
                     finalized = live_norm;
                 }
                 self.push_dedup_committed_line(&finalized, None);
+                self.commit_translation();
                 self.segment_live_text.clear();
                 self.in_speech_segment = false;
                 // Critical: clip old audio out of the decode segment so we don't reprocess it.
@@ -1380,8 +1430,21 @@ impl Application for TranscribeApp {
             if !live.is_empty() {
                 if !full_text.is_empty() {
                     full_text.push('\n');
+                    char_cursor += 1;
                 }
                 full_text.push_str(&live);
+                char_cursor += live.chars().count();
+            }
+            let translated = Self::normalize_text(&live_translation);
+            if !translated.is_empty() {
+                if !full_text.is_empty() {
+                    full_text.push('\n');
+                    char_cursor += 1;
+                }
+                let start = char_cursor;
+                full_text.push_str(&translated);
+                let end = start + translated.chars().count();
+                color_spans.push((start, end, TRANSLATION_TEXT_COLOR));
             }
         }
 
@@ -1578,6 +1641,9 @@ impl Application for TranscribeApp {
                     }
                     self.lang_selector.show_menu = false;
                 }
+                TranscribeLangMenuDown::ToggleTranslate { .. } => {
+                    self.recreate_transcription_engine();
+                }
             }
             self.transcript_pointer_down = false;
             self.slider_dragging = false;
@@ -1718,6 +1784,28 @@ impl Application for TranscribeApp {
                             eprintln!("transcribe: hold full-clip decode failed: {e}");
                         }
                     }
+                    if self.lang_selector.translate {
+                        let options = TranscribeOptions {
+                            language: hold_lang,
+                            task: WhisperTask::Translate,
+                            ..TranscribeOptions::default()
+                        };
+                        match transcribe_waveform_once_detailed(
+                            None,
+                            &self.ptt_hold_pcm,
+                            self.ptt_hold_sample_rate,
+                            transcribe_backend_from_env(),
+                            &options,
+                        ) {
+                            Ok(out) => {
+                                let t = Self::normalize_text(&out.text);
+                                self.push_dedup_committed_line(&t, Some(TRANSLATION_TEXT_COLOR));
+                            }
+                            Err(e) => {
+                                eprintln!("transcribe: hold translation failed: {e}");
+                            }
+                        }
+                    }
                 }
                 self.ptt_hold_pcm.clear();
                 self.ptt_hold_last_ingested = None;
@@ -1791,6 +1879,11 @@ impl Application for TranscribeApp {
         delta_y: f32,
         unit: ScrollWheelUnit,
     ) {
+        #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+        if self.lang_selector.show_menu {
+            self.lang_selector.on_menu_scroll(delta_y, unit);
+            return;
+        }
         let Some((x0, y0, x1, y1)) = self.ui_bounds.transcript else {
             return;
         };
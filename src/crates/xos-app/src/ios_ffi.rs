@@ -29,12 +29,16 @@ use xos_core::engine::engine::CursorStyleSetter;
 use xos_core::ios_log::log_to_ios;
 #[cfg(target_os = "ios")]
 use xos_core::engine::{
-    apply_frame_view_zoom, f3_menu_handle_mouse_down, f3_menu_handle_mouse_move,
-    f3_menu_handle_mouse_up, tick_f3_menu, tick_frame_delta, tick_frame_view_zoom, Application,
-    EngineState, F3Menu, FrameState, KeyboardModifiers, KeyboardState, MouseState,
-    SafeRegionBoundingRectangle,
+    advance_app_frame, apply_frame_view_zoom, f3_menu_handle_mouse_down, f3_menu_handle_mouse_move,
+    f3_menu_handle_mouse_up, poll_gamepads, release_all_keys, route_key_event, route_pointer_event,
+    step_app_tick, tick_f3_menu, tick_frame_view_zoom, Application, EngineState, F3Menu,
+    FrameState, KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle,
 };
 #[cfg(target_os = "ios")]
+use xos_core::engine::keyboard::keys::KeyEvent;
+#[cfg(target_os = "ios")]
+use xos_core::engine::pointer::{PointerEvent, PointerKind, PointerPhase};
+#[cfg(target_os = "ios")]
 use xos_mesh::{MeshMode, MeshSession};
 #[cfg(target_os = "ios")]
 use serde_json::json;
@@ -182,15 +186,21 @@ pub extern "C" fn xos_engine_init(app_name: *const c_char, width: u32, height: u
             is_right_clicking: false,
             style: CursorStyleSetter::new(),
         },
+        pointers: xos_core::engine::pointer::PointerState::default(),
         keyboard: KeyboardState {
             onscreen: xos_core::ui::onscreen_keyboard::OnScreenKeyboard::new(),
             modifiers: KeyboardModifiers::default(),
+            keys_down: xos_core::engine::keyboard::keys::KeysDown::default(),
         },
+        gamepads: xos_core::engine::gamepad::Gamepads::default(),
         f3_menu: F3Menu::new(),
         ui_scale_percent: 100,
         delta_time_seconds: 1.0 / 60.0,
         paused: false,
         pending_step_ticks: 0,
+        timestep: xos_core::engine::timestep::FixedTimestep::default(),
+        capture: xos_core::engine::capture::FrameCapture::default(),
+        window: xos_core::engine::window_control::WindowControl::default(),
         frame_view_zoom: 1.0,
         frame_view_zoom_target: 1.0,
         frame_view_zoom_velocity: 0.0,
@@ -249,6 +259,7 @@ pub extern "C" fn xos_engine_tick() -> i32 {
     };
 
     if let Some(ref mut ios_state) = *state {
+        xos_core::engine::profiler::frame_boundary();
         // Finger/touch coordinates from Swift arrive before tick; mesh remote writes the same mouse
         // for app hit-testing below. Restore local x/y before keyboard/F3 so the on-device pointer
         // (highlights, F3 taps) stays aligned with physical touch rather than Mac cursor position.
@@ -260,25 +271,33 @@ pub extern "C" fn xos_engine_tick() -> i32 {
         // We use AssertUnwindSafe because we know the FFI boundary is safe
         // and we're catching panics to prevent them from crossing the boundary unsafely
         let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            // Virtual pads only; controllers over GameController.framework aren't bridged yet.
+            poll_gamepads(ios_state.app.as_mut(), &mut ios_state.engine_state, None);
             if ios_state.engine_state.paused {
                 if ios_state.engine_state.pending_step_ticks > 0 {
                     ios_state.engine_state.pending_step_ticks =
                         ios_state.engine_state.pending_step_ticks.saturating_sub(1);
-                    tick_frame_delta(
+                    step_app_tick(
+                        ios_state.app.as_mut(),
                         &mut ios_state.engine_state,
                         &mut ios_state.last_tick_instant,
                     );
-                    ios_state.app.tick(&mut ios_state.engine_state);
+                    ios_state.app.render(&mut ios_state.engine_state);
                 } else {
                     ios_state.last_tick_instant = Some(std::time::Instant::now());
                 }
             } else {
-                tick_frame_delta(
+                advance_app_frame(
+                    ios_state.app.as_mut(),
                     &mut ios_state.engine_state,
                     &mut ios_state.last_tick_instant,
                 );
-                ios_state.app.tick(&mut ios_state.engine_state);
+                ios_state.app.render(&mut ios_state.engine_state);
             }
+            ios_state
+                .engine_state
+                .capture
+                .on_frame(&mut ios_state.engine_state.frame);
         }));
 
         tick_frame_view_zoom(&mut ios_state.engine_state);
@@ -325,6 +344,8 @@ pub extern "C" fn xos_engine_tick() -> i32 {
         }
 
         tick_f3_menu(&mut ios_state.engine_state);
+        ios_state.engine_state.window.discard_commands();
+        xos_core::engine::windows::discard_window_requests();
         tick_ios_remote_frame_push(ios_state);
 
         // Swap R and B channels in-place for iOS Metal compatibility (RGBA -> BGRA)
@@ -664,6 +685,46 @@ pub extern "C" fn xos_engine_mouse_down() -> i32 {
     }
 }
 
+/// One `UITouch` transition for the multi-pointer model (`Application::on_pointer_*`, gestures).
+/// `kind`: 0 touch, 1 pencil. `phase`: 0 began, 1 moved, 2 ended, 3 cancelled. `x`/`y` in frame px,
+/// `pressure` = `force / maximumPossibleForce` (0 when unsupported). The legacy single cursor is still
+/// driven by `xos_engine_mouse_*` for the primary touch.
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn xos_engine_pointer_event(
+    id: u64,
+    kind: u8,
+    phase: u8,
+    x: f32,
+    y: f32,
+    pressure: f32,
+) -> i32 {
+    let mut state = match ENGINE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return 1,
+    };
+
+    if let Some(ref mut ios_state) = *state {
+        let kind = if kind == 1 {
+            PointerKind::Pen
+        } else {
+            PointerKind::Touch
+        };
+        let phase = match phase {
+            0 => PointerPhase::Down,
+            1 => PointerPhase::Move,
+            2 => PointerPhase::Up,
+            _ => PointerPhase::Cancel,
+        };
+        // Offset past the mouse id so a touch can never alias it.
+        let event = PointerEvent::contact(id.wrapping_add(1).max(1), kind, phase, x, y, pressure);
+        route_pointer_event(ios_state.app.as_mut(), &mut ios_state.engine_state, event);
+        0
+    } else {
+        1
+    }
+}
+
 /// Toggle the global F3 overlay (FPS / UI scale), same as the F3 key on desktop.
 #[cfg(target_os = "ios")]
 #[no_mangle]
@@ -700,6 +761,69 @@ pub extern "C" fn xos_engine_mouse_up() -> i32 {
     }
 }
 
+/// Borrowed UTF-8 C string, or `""` for null / invalid input.
+#[cfg(target_os = "ios")]
+unsafe fn ffi_str<'a>(ptr: *const c_char) -> &'a str {
+    if ptr.is_null() {
+        return "";
+    }
+    std::ffi::CStr::from_ptr(ptr).to_str().unwrap_or("")
+}
+
+/// Hardware key press (`UIPress.key`): `key` is the logical name (`"w"`, `"ArrowLeft"`), `code` the
+/// physical name in `KeyboardEvent.code` form (`"KeyW"`); either may be null.
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn xos_engine_key_down(key: *const c_char, code: *const c_char, repeat: bool) -> i32 {
+    let mut state = match ENGINE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return 1,
+    };
+
+    if let Some(ref mut ios_state) = *state {
+        let event = unsafe { KeyEvent::down(ffi_str(key), ffi_str(code), repeat) };
+        route_key_event(ios_state.app.as_mut(), &mut ios_state.engine_state, event);
+        0
+    } else {
+        1
+    }
+}
+
+/// Hardware key release (`pressesEnded` / `pressesCancelled`).
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn xos_engine_key_up(key: *const c_char, code: *const c_char) -> i32 {
+    let mut state = match ENGINE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return 1,
+    };
+
+    if let Some(ref mut ios_state) = *state {
+        let event = unsafe { KeyEvent::up(ffi_str(key), ffi_str(code)) };
+        route_key_event(ios_state.app.as_mut(), &mut ios_state.engine_state, event);
+        0
+    } else {
+        1
+    }
+}
+
+/// Release every held key (view resigned first responder / app backgrounded).
+#[cfg(target_os = "ios")]
+#[no_mangle]
+pub extern "C" fn xos_engine_release_all_keys() -> i32 {
+    let mut state = match ENGINE_STATE.lock() {
+        Ok(s) => s,
+        Err(_) => return 1,
+    };
+
+    if let Some(ref mut ios_state) = *state {
+        release_all_keys(ios_state.app.as_mut(), &mut ios_state.engine_state);
+        0
+    } else {
+        1
+    }
+}
+
 /// Host-driven safe rectangle in normalized `[0,1]` frame space (`x2`/`y2` right/bottom), e.g. from
 /// `UIView.safeAreaInsets` / bounds. Used by layout, OSK placement, Python `Application.safe_region`.
 #[cfg(target_os = "ios")]
@@ -196,6 +196,7 @@ pub(crate) fn dispatch_text_widget(id: u64, kind: PyUiEventKind, state: &mut Eng
     if t.python_viewport.is_some() {
         let skip = match &kind {
             PyUiEventKind::Key(_) | PyUiEventKind::Shortcut(_) => !t.py_input_focused,
+            PyUiEventKind::Gesture(_) => true,
             PyUiEventKind::Scroll { .. } => {
                 if captured_id == Some(id) {
                     ptr_in_osk
@@ -255,6 +256,7 @@ pub(crate) fn dispatch_text_widget(id: u64, kind: PyUiEventKind, state: &mut Eng
         PyUiEventKind::Scroll { dx, dy, unit } => t.on_scroll(state, dx, dy, unit),
         PyUiEventKind::Key(ch) => t.on_key_char(state, ch),
         PyUiEventKind::Shortcut(sa) => t.apply_keyboard_shortcut(sa, state),
+        PyUiEventKind::Gesture(_) => {}
     }
 }
 
@@ -19,11 +19,14 @@ use xos_python::rasterizer::{
     CURRENT_FRAME_BUFFER, CURRENT_FRAME_HEIGHT, CURRENT_FRAME_WIDTH,
 };
 use xos_core::rasterizer::text::fonts;
+use xos_core::rasterizer::text::rich_text::{
+    layout_rich_text, RichAlign, RichLayoutOptions, RichTextLayout, TextRun, TextStyle,
+};
 use xos_core::rasterizer::text::ui_markup;
 use xos_core::ui::{Button, UiText};
 use rustpython_vm::{
-    builtins::PyDict, builtins::PyModule, function::FuncArgs, PyObjectRef, PyRef, PyResult,
-    VirtualMachine,
+    builtins::PyDict, builtins::PyList, builtins::PyModule, builtins::PyStr, builtins::PyTuple,
+    function::FuncArgs, PyObjectRef, PyRef, PyResult, VirtualMachine,
 };
 
 fn frame_wh_from_app(vm: &VirtualMachine, app: PyObjectRef) -> PyResult<(u32, u32)> {
@@ -610,6 +613,275 @@ fn text_render(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     Ok(state.into())
 }
 
+fn parse_py_rgba_tuple(
+    vm: &VirtualMachine,
+    obj: &PyObjectRef,
+    name: &str,
+) -> PyResult<(u8, u8, u8, u8)> {
+    let items = obj
+        .downcast_ref::<PyTuple>()
+        .map(|t| t.as_slice().to_vec())
+        .or_else(|| obj.downcast_ref::<PyList>().map(|l| l.borrow_vec().to_vec()))
+        .ok_or_else(|| vm.new_type_error(format!("{name} must be (r, g, b) or (r, g, b, a)")))?;
+    if items.len() != 3 && items.len() != 4 {
+        return Err(vm.new_type_error(format!("{name} must be (r, g, b) or (r, g, b, a)")));
+    }
+    let mut c = [255u8; 4];
+    for (slot, item) in c.iter_mut().zip(&items) {
+        let v: i32 = item.clone().try_into_value(vm)?;
+        *slot = v.clamp(0, 255) as u8;
+    }
+    Ok((c[0], c[1], c[2], c[3]))
+}
+
+fn rich_font_family(vm: &VirtualMachine, obj: &PyObjectRef) -> PyResult<fonts::FontFamily> {
+    let name: String = obj.clone().try_into_value(vm)?;
+    fonts::family_by_name(&name).ok_or_else(|| vm.new_value_error(format!("unknown font '{name}'")))
+}
+
+/// Weight from an int (CSS 100–900) or a name (`"bold"`, `"normal"`, …).
+fn rich_font_weight(vm: &VirtualMachine, obj: &PyObjectRef) -> PyResult<u16> {
+    if let Ok(v) = obj.clone().try_into_value::<i64>(vm) {
+        return Ok(v.clamp(1, 1000) as u16);
+    }
+    let name: String = obj.clone().try_into_value(vm)?;
+    match name.to_ascii_lowercase().as_str() {
+        "thin" => Ok(100),
+        "light" => Ok(300),
+        "normal" | "regular" => Ok(400),
+        "medium" => Ok(500),
+        "semibold" => Ok(600),
+        "bold" => Ok(700),
+        "black" | "heavy" => Ok(900),
+        _ => Err(vm.new_value_error(format!("unknown font weight '{name}'"))),
+    }
+}
+
+/// One `rich=` entry: a plain string (inherits `base`) or a dict with `text` plus style overrides.
+fn parse_rich_run(vm: &VirtualMachine, item: &PyObjectRef, base: &TextStyle) -> PyResult<TextRun> {
+    if let Some(s) = item.downcast_ref::<PyStr>() {
+        return Ok(TextRun::new(s.as_str(), base.clone()));
+    }
+    let dict = item.downcast_ref::<PyDict>().ok_or_else(|| {
+        vm.new_type_error("rich entries must be str or dict with a 'text' key".to_string())
+    })?;
+    let text: String = dict
+        .get_item_opt("text", vm)?
+        .ok_or_else(|| vm.new_type_error("rich dict entries need a 'text' key".to_string()))?
+        .try_into_value(vm)?;
+    let mut style = base.clone();
+    let get = |key: &str| -> PyResult<Option<PyObjectRef>> {
+        Ok(dict.get_item_opt(key, vm)?.filter(|v| !vm.is_none(v)))
+    };
+    if let Some(v) = get("font")? {
+        style.family = rich_font_family(vm, &v)?;
+    }
+    if let Some(v) = get("size")?.or(get("font_size")?) {
+        style.size = (py_number_to_f64(v, vm, "size")? as f32).max(1.0);
+    }
+    if let Some(v) = get("weight")? {
+        style.weight = rich_font_weight(vm, &v)?;
+    }
+    if let Some(v) = get("bold")? {
+        if v.try_into_value::<bool>(vm)? {
+            style.weight = style.weight.max(700);
+        }
+    }
+    if let Some(v) = get("color")? {
+        style.color = parse_py_rgba_tuple(vm, &v, "color")?;
+    }
+    if let Some(v) = get("underline")? {
+        style.underline = v.try_into_value(vm)?;
+    }
+    if let Some(v) = get("background")? {
+        style.background = Some(parse_py_rgba_tuple(vm, &v, "background")?);
+    }
+    Ok(TextRun::new(text, style))
+}
+
+/// Shared by `_rich_text_render` / `_rich_text_hit_test`: lays out `args[0]` (the `rich=` list) inside the
+/// normalized box `args[1..5]` on a `frame_w × frame_h` frame. Returns the layout and its pixel box.
+fn rich_layout_from_args(
+    args: &FuncArgs,
+    vm: &VirtualMachine,
+    frame_w: usize,
+    frame_h: usize,
+) -> PyResult<(RichTextLayout, (i32, i32, i32, i32))> {
+    let a = &args.args;
+    if a.len() < 5 {
+        return Err(vm.new_type_error(format!(
+            "rich text takes (runs, x1, y1, x2, y2) ({} given)",
+            a.len()
+        )));
+    }
+    let x1 = py_number_to_f64(a[1].clone(), vm, "x1")?.clamp(0.0, 1.0);
+    let y1 = py_number_to_f64(a[2].clone(), vm, "y1")?.clamp(0.0, 1.0);
+    let x2 = py_number_to_f64(a[3].clone(), vm, "x2")?.clamp(0.0, 1.0);
+    let y2 = py_number_to_f64(a[4].clone(), vm, "y2")?.clamp(0.0, 1.0);
+    let px_box = (
+        (x1 * frame_w as f64).round() as i32,
+        (y1 * frame_h as f64).round() as i32,
+        (x2 * frame_w as f64).round() as i32,
+        (y2 * frame_h as f64).round() as i32,
+    );
+
+    let kw = |key: &str| args.kwargs.get(key).filter(|v| !vm.is_none(v)).cloned();
+    let mut base = TextStyle::default();
+    if let Some(v) = kw("color") {
+        base.color = parse_py_rgba_tuple(vm, &v, "color")?;
+    }
+    if let Some(v) = kw("size").or(kw("font_size")) {
+        base.size = (py_number_to_f64(v, vm, "size")? as f32).max(1.0);
+    }
+    if let Some(v) = kw("font") {
+        base.family = rich_font_family(vm, &v)?;
+    }
+    if let Some(v) = kw("weight") {
+        base.weight = rich_font_weight(vm, &v)?;
+    }
+
+    let items: Vec<PyObjectRef> = if let Some(list) = a[0].downcast_ref::<PyList>() {
+        list.borrow_vec().to_vec()
+    } else if let Some(tuple) = a[0].downcast_ref::<PyTuple>() {
+        tuple.as_slice().to_vec()
+    } else {
+        vec![a[0].clone()]
+    };
+    let runs = items
+        .iter()
+        .map(|item| parse_rich_run(vm, item, &base))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let mut options = RichLayoutOptions {
+        max_width: (px_box.2 - px_box.0).max(1) as f32,
+        ..RichLayoutOptions::default()
+    };
+    if let Some(v) = kw("align") {
+        let name: String = v.try_into_value(vm)?;
+        options.align = RichAlign::from_name(&name).ok_or_else(|| {
+            vm.new_value_error(format!(
+                "align must be 'left', 'center', 'right' or 'justify' (got '{name}')"
+            ))
+        })?;
+    }
+    if let Some(v) = kw("max_lines") {
+        let n: i64 = v.try_into_value(vm)?;
+        options.max_lines = (n > 0).then_some(n as usize);
+    }
+    if let Some(v) = kw("ellipsis") {
+        options.ellipsis = v.try_into_value(vm)?;
+    }
+    if let Some(v) = kw("line_spacing") {
+        options.line_spacing = py_number_to_f64(v, vm, "line_spacing")? as f32;
+    }
+    Ok((layout_rich_text(&runs, &options), px_box))
+}
+
+/// `xos.ui._rich_text_render(runs, x1, y1, x2, y2, color=, size=, font=, weight=, align=, max_lines=,
+/// ellipsis=, line_spacing=, render=True)` — lays out and paints attributed runs into the current
+/// frame, clipped to the box. Returns the same state dict as `_text_render` plus `truncated`.
+fn rich_text_render(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let buffer_ptr = CURRENT_FRAME_BUFFER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ptr| ptr.as_ptr())
+        .ok_or_else(|| {
+            vm.new_runtime_error(
+                "No frame buffer context set. UI must be called during tick().".to_string(),
+            )
+        })?;
+    let canvas_width = *CURRENT_FRAME_WIDTH.lock().unwrap();
+    let canvas_height = *CURRENT_FRAME_HEIGHT.lock().unwrap();
+    let (layout, px_box) = rich_layout_from_args(&args, vm, canvas_width, canvas_height)?;
+    let should_render = match args.kwargs.get("render") {
+        Some(v) => v.clone().try_into_value(vm)?,
+        None => true,
+    };
+    let origin = (px_box.0 as f32, px_box.1 as f32);
+    if should_render && canvas_width > 0 && canvas_height > 0 {
+        let buffer =
+            unsafe { std::slice::from_raw_parts_mut(buffer_ptr, canvas_width * canvas_height * 4) };
+        layout.render(buffer, canvas_width, canvas_height, origin, Some(px_box));
+    }
+
+    let fw = canvas_width.max(1) as f64;
+    let fh = canvas_height.max(1) as f64;
+    let point = |x: f32, y: f32| -> PyObjectRef {
+        vm.ctx
+            .new_list(vec![
+                vm.ctx.new_float((origin.0 + x) as f64 / fw).into(),
+                vm.ctx.new_float((origin.1 + y) as f64 / fh).into(),
+            ])
+            .into()
+    };
+    let lines_py = vm.ctx.new_list(
+        layout
+            .lines
+            .iter()
+            .map(|l| vm.ctx.new_int(l.end - l.start).into())
+            .collect(),
+    );
+    let hitboxes_py = vm.ctx.new_list(
+        layout
+            .char_boxes
+            .iter()
+            .map(|b| {
+                let line = layout.lines[b.line];
+                vm.ctx
+                    .new_list(vec![point(b.x0, line.top), point(b.x1, line.bottom)])
+                    .into()
+            })
+            .collect(),
+    );
+    let hitbox_indices_py = vm.ctx.new_list(
+        layout
+            .char_boxes
+            .iter()
+            .map(|b| vm.ctx.new_int(b.char_index).into())
+            .collect(),
+    );
+    let baselines_py = vm.ctx.new_list(
+        layout
+            .lines
+            .iter()
+            .map(|l| {
+                vm.ctx
+                    .new_list(vec![point(l.x, l.baseline_y), point(l.x + l.width, l.baseline_y)])
+                    .into()
+            })
+            .collect(),
+    );
+
+    let state = vm.ctx.new_dict();
+    state.set_item("lines", lines_py.into(), vm)?;
+    state.set_item("hitboxes", hitboxes_py.into(), vm)?;
+    state.set_item("hitbox_char_indices", hitbox_indices_py.into(), vm)?;
+    state.set_item("baselines", baselines_py.into(), vm)?;
+    state.set_item("truncated", vm.ctx.new_bool(layout.truncated).into(), vm)?;
+    state.set_item("width", vm.ctx.new_float(layout.width as f64).into(), vm)?;
+    state.set_item("height", vm.ctx.new_float(layout.height as f64).into(), vm)?;
+    Ok(state.into())
+}
+
+/// `xos.ui._rich_text_hit_test(runs, x1, y1, x2, y2, px, py, frame_w, frame_h, **style)` — caret index
+/// (char offset into the concatenated run text) nearest to frame pixel `(px, py)`.
+fn rich_text_hit_test(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    if args.args.len() < 9 {
+        return Err(vm.new_type_error(format!(
+            "_rich_text_hit_test() takes 9 arguments ({} given)",
+            args.args.len()
+        )));
+    }
+    let px = py_number_to_f64(args.args[5].clone(), vm, "px")? as f32;
+    let py = py_number_to_f64(args.args[6].clone(), vm, "py")? as f32;
+    let frame_w: usize = args.args[7].clone().try_into_value(vm)?;
+    let frame_h: usize = args.args[8].clone().try_into_value(vm)?;
+    let (layout, px_box) = rich_layout_from_args(&args, vm, frame_w, frame_h)?;
+    let index = layout.hit_test(px - px_box.0 as f32, py - px_box.1 as f32);
+    Ok(vm.ctx.new_int(index).into())
+}
+
 /// Native [`TextApp`] registration — returns integer widget id (`_native_id`).
 fn text_widget_register(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let av = args.args.as_slice();
@@ -1278,6 +1550,20 @@ pub fn make_ui_module(vm: &VirtualMachine, coordinates: PyRef<PyModule>) -> PyRe
             vm,
         )
         .unwrap();
+    module
+        .set_attr(
+            "_rich_text_render",
+            vm.new_function("_rich_text_render", rich_text_render),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "_rich_text_hit_test",
+            vm.new_function("_rich_text_hit_test", rich_text_hit_test),
+            vm,
+        )
+        .unwrap();
     module
         .set_attr(
             "_onscreen_keyboard_tick",
@@ -1613,6 +1899,11 @@ class OnScreenKeyboard:
         except RuntimeError:
             self._pending_visible = False
 
+    def is_down(self, key):
+        """Hardware key held (``"w"``, ``"space"``, ``"KeyW"``) — same table as ``xos.Keyboard.is_down``."""
+        import xos
+        return bool(xos._key_is_down(str(key)))
+
     def tick(self, app):
         import xos
         pv = self._pending_visible
@@ -2400,6 +2691,99 @@ class UiRect(_NormRect):
         return None
 
 
+class RichText(_NormRect):
+    """Attributed runs laid out natively: word/CJK wrapping, ``align`` (left/center/right/justify),
+    ``max_lines`` with ellipsis, and ``hit_test`` for caret placement.
+
+    ``rich`` is a list of ``str`` or dicts with ``text`` plus any of ``font``, ``size``, ``weight``
+    (int or ``"bold"``), ``bold``, ``color``, ``underline``, ``background``; missing keys inherit the
+    widget's ``color`` / ``size`` / ``font``.
+    """
+
+    __slots__ = (
+        "rich",
+        "color",
+        "size",
+        "font",
+        "align",
+        "max_lines",
+        "ellipsis",
+        "line_spacing",
+        "_last_state",
+    )
+
+    def __init__(
+        self,
+        rich,
+        x1=0.0,
+        y1=0.0,
+        x2=1.0,
+        y2=1.0,
+        color=(255, 255, 255),
+        size=24.0,
+        font=None,
+        align="left",
+        max_lines=None,
+        ellipsis=True,
+        line_spacing=1.0,
+        coordinate_system=None,
+    ):
+        super().__init__(x1, y1, x2, y2, coordinate_system=coordinate_system)
+        self.rich = [rich] if isinstance(rich, (str, dict)) else list(rich)
+        self.color = tuple(color)
+        self.size = float(size)
+        self.font = font
+        self.align = str(align)
+        self.max_lines = max_lines
+        self.ellipsis = bool(ellipsis)
+        self.line_spacing = float(line_spacing)
+        self._last_state = None
+
+    def _native_kwargs(self):
+        return {
+            "color": tuple(self.color),
+            "size": float(self.size),
+            "font": self.font,
+            "align": self.align,
+            "max_lines": None if self.max_lines is None else int(self.max_lines),
+            "ellipsis": bool(self.ellipsis),
+            "line_spacing": float(self.line_spacing),
+        }
+
+    def tick(self, app):
+        return None
+
+    def on_events(self, app):
+        pass
+
+    def render(self, app=None):
+        import xos
+
+        if app is not None:
+            nx1, ny1, nx2, ny2 = self._norm_rect_xyxy(app)
+        else:
+            nx1, ny1, nx2, ny2 = _coef_to_normalized_rect(
+                self.x1, self.y1, self.x2, self.y2, self.coordinate_system, 1.0, 1.0
+            )
+        state = xos.ui._rich_text_render(self.rich, nx1, ny1, nx2, ny2, **self._native_kwargs())
+        self._last_state = TextRenderState(state)
+        self._last_state.truncated = bool(state["truncated"])
+        return self._last_state
+
+    def hit_test(self, app, x, y):
+        """Caret index (into the joined run text) nearest to frame pixel ``(x, y)``."""
+        import xos
+
+        fw, fh = _frame_wh_from_any(app)
+        nx1, ny1, nx2, ny2 = self._norm_rect_xyxy(app)
+        return int(
+            xos.ui._rich_text_hit_test(
+                self.rich, nx1, ny1, nx2, ny2, float(x), float(y), int(fw), int(fh),
+                **self._native_kwargs()
+            )
+        )
+
+
 class UiButton(_NormRect):
     """Invisible hit-target; invokes ``on_press`` on completed left clicks inside ``verts``."""
 
@@ -2597,11 +2981,23 @@ class TextRenderState:
         self.hitbox_char_indices = xos.tensor(hi, dtype=xos.int32)
         self.baselines = xos.tensor(bl, (n_bl, 2, 2), dtype=xos.float32)
 
-def text(text="", x1=0.0, y1=0.0, x2=1.0, y2=1.0, color=(255, 255, 255), hitboxes=False, baselines=False, size=24.0, font=None, **kwargs):
+def text(text="", x1=0.0, y1=0.0, x2=1.0, y2=1.0, color=(255, 255, 255), hitboxes=False, baselines=False, size=24.0, font=None, rich=None, **kwargs):
     if "font_size" in kwargs:
         size = kwargs.pop("font_size")
     if "fontsize" in kwargs:
         size = kwargs.pop("fontsize")
+    if rich is not None:
+        return RichText(
+            rich,
+            x1=x1,
+            y1=y1,
+            x2=x2,
+            y2=y2,
+            color=color,
+            size=size,
+            font=font,
+            **kwargs
+        )
     return Text(
         text,
         x1=x1,
@@ -2656,6 +3052,9 @@ def onscreen_keyboard():
     if let Ok(ui_rect) = scope.globals.get_item("UiRect", vm) {
         module.set_attr("UiRect", ui_rect, vm).unwrap();
     }
+    if let Ok(rich_cls) = scope.globals.get_item("RichText", vm) {
+        module.set_attr("RichText", rich_cls, vm).unwrap();
+    }
     if let Ok(ui_btn) = scope.globals.get_item("UiButton", vm) {
         module.set_attr("UiButton", ui_btn, vm).unwrap();
     }
@@ -0,0 +1,211 @@
+//! FFT of any length: iterative radix-2 for powers of two, Bluestein's chirp-z transform (three
+//! power-of-two FFTs) for everything else. Plans keep their twiddles, so build one per size and
+//! reuse it. Data is `f64` so the same plans serve `xos.math.fft`; audio paths convert at the edges.
+
+use std::f64::consts::PI;
+
+pub struct Fft {
+    n: usize,
+    kind: Kind,
+}
+
+enum Kind {
+    /// `exp(-2πik/n)` for `k < n/2`.
+    Radix2 { tw_re: Vec<f64>, tw_im: Vec<f64> },
+    Bluestein {
+        inner: Box<Fft>,
+        /// `exp(-iπk²/n)` for `k < n`.
+        chirp_re: Vec<f64>,
+        chirp_im: Vec<f64>,
+        /// FFT of the conjugate chirp laid out circularly over the inner size.
+        kernel_re: Vec<f64>,
+        kernel_im: Vec<f64>,
+    },
+}
+
+impl Fft {
+    pub fn new(n: usize) -> Self {
+        if n <= 1 || n.is_power_of_two() {
+            let (tw_re, tw_im) = (0..n / 2)
+                .map(|k| {
+                    let a = -2.0 * PI * k as f64 / n as f64;
+                    (a.cos(), a.sin())
+                })
+                .unzip();
+            return Self {
+                n,
+                kind: Kind::Radix2 { tw_re, tw_im },
+            };
+        }
+        let m = (2 * n - 1).next_power_of_two();
+        let inner = Box::new(Fft::new(m));
+        // k² mod 2n keeps the angle small (and exact) for long transforms.
+        let (chirp_re, chirp_im): (Vec<f64>, Vec<f64>) = (0..n)
+            .map(|k| {
+                let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+                let a = -PI * k2 / n as f64;
+                (a.cos(), a.sin())
+            })
+            .unzip();
+        let mut kernel_re = vec![0.0; m];
+        let mut kernel_im = vec![0.0; m];
+        for k in 0..n {
+            kernel_re[k] = chirp_re[k];
+            kernel_im[k] = -chirp_im[k];
+            if k > 0 {
+                kernel_re[m - k] = chirp_re[k];
+                kernel_im[m - k] = -chirp_im[k];
+            }
+        }
+        inner.forward(&mut kernel_re, &mut kernel_im);
+        Self {
+            n,
+            kind: Kind::Bluestein {
+                inner,
+                chirp_re,
+                chirp_im,
+                kernel_re,
+                kernel_im,
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// In-place forward transform (no scaling). Both slices must have the plan's length.
+    pub fn forward(&self, re: &mut [f64], im: &mut [f64]) {
+        assert!(
+            re.len() == self.n && im.len() == self.n,
+            "fft plan is for {} points, got {} / {}",
+            self.n,
+            re.len(),
+            im.len()
+        );
+        match &self.kind {
+            Kind::Radix2 { tw_re, tw_im } => radix2(re, im, tw_re, tw_im),
+            Kind::Bluestein {
+                inner,
+                chirp_re,
+                chirp_im,
+                kernel_re,
+                kernel_im,
+            } => {
+                let m = inner.len();
+                let mut a_re = vec![0.0; m];
+                let mut a_im = vec![0.0; m];
+                for k in 0..self.n {
+                    a_re[k] = re[k] * chirp_re[k] - im[k] * chirp_im[k];
+                    a_im[k] = re[k] * chirp_im[k] + im[k] * chirp_re[k];
+                }
+                inner.forward(&mut a_re, &mut a_im);
+                for k in 0..m {
+                    let (r, i) = (a_re[k], a_im[k]);
+                    a_re[k] = r * kernel_re[k] - i * kernel_im[k];
+                    a_im[k] = r * kernel_im[k] + i * kernel_re[k];
+                }
+                inner.inverse(&mut a_re, &mut a_im);
+                for k in 0..self.n {
+                    re[k] = a_re[k] * chirp_re[k] - a_im[k] * chirp_im[k];
+                    im[k] = a_re[k] * chirp_im[k] + a_im[k] * chirp_re[k];
+                }
+            }
+        }
+    }
+
+    /// In-place inverse transform, scaled by `1/n` so `inverse(forward(x)) == x`.
+    pub fn inverse(&self, re: &mut [f64], im: &mut [f64]) {
+        im.iter_mut().for_each(|v| *v = -*v);
+        self.forward(re, im);
+        let scale = 1.0 / self.n.max(1) as f64;
+        re.iter_mut().for_each(|v| *v *= scale);
+        im.iter_mut().for_each(|v| *v *= -scale);
+    }
+}
+
+fn radix2(re: &mut [f64], im: &mut [f64], tw_re: &[f64], tw_im: &[f64]) {
+    let n = re.len();
+    if n <= 1 {
+        return;
+    }
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for j in 0..half {
+                let (wr, wi) = (tw_re[j * step], tw_im[j * step]);
+                let (a, b) = (start + j, start + j + half);
+                let tr = wr * re[b] - wi * im[b];
+                let ti = wr * im[b] + wi * re[b];
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// One-off forward FFT of any length (builds a plan; use [`Fft`] when transforming repeatedly).
+pub fn fft(re: &mut [f64], im: &mut [f64]) {
+    Fft::new(re.len()).forward(re, im);
+}
+
+/// One-off inverse FFT, scaled by `1/n`.
+pub fn ifft(re: &mut [f64], im: &mut [f64]) {
+    Fft::new(re.len()).inverse(re, im);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dft(re: &[f64], im: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let n = re.len();
+        (0..n)
+            .map(|k| {
+                (0..n).fold((0.0, 0.0), |(sr, si), t| {
+                    let a = -2.0 * PI * (k * t) as f64 / n as f64;
+                    (
+                        sr + re[t] * a.cos() - im[t] * a.sin(),
+                        si + re[t] * a.sin() + im[t] * a.cos(),
+                    )
+                })
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn matches_a_direct_dft_for_any_length() {
+        for n in [1, 2, 8, 12, 400, 401] {
+            let re: Vec<f64> = (0..n).map(|i| ((i * 7 + 3) % 11) as f64 - 5.0).collect();
+            let im: Vec<f64> = (0..n).map(|i| ((i * 5) % 13) as f64 * 0.1).collect();
+            let (want_re, want_im) = dft(&re, &im);
+            let (mut got_re, mut got_im) = (re.clone(), im.clone());
+            fft(&mut got_re, &mut got_im);
+            for k in 0..n {
+                assert!((got_re[k] - want_re[k]).abs() < 1e-7, "n={n} re[{k}]");
+                assert!((got_im[k] - want_im[k]).abs() < 1e-7, "n={n} im[{k}]");
+            }
+            ifft(&mut got_re, &mut got_im);
+            for k in 0..n {
+                assert!((got_re[k] - re[k]).abs() < 1e-9 && (got_im[k] - im[k]).abs() < 1e-9);
+            }
+        }
+    }
+}
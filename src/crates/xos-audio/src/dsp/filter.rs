@@ -0,0 +1,380 @@
+//! IIR biquads (RBJ "Audio EQ Cookbook" designs, transposed direct form II) and windowed-sinc FIR
+//! filters. Both keep their state between calls, so a live stream can be filtered chunk by chunk.
+
+use std::f64::consts::PI;
+
+use super::window::kaiser;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BiquadKind {
+    Lowpass,
+    Highpass,
+    /// Constant 0 dB peak gain.
+    Bandpass,
+    Notch,
+    Allpass,
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+impl BiquadKind {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "lowpass" | "low_pass" | "lp" => Some(Self::Lowpass),
+            "highpass" | "high_pass" | "hp" => Some(Self::Highpass),
+            "bandpass" | "band_pass" | "bp" => Some(Self::Bandpass),
+            "notch" | "bandstop" | "band_stop" => Some(Self::Notch),
+            "allpass" | "all_pass" => Some(Self::Allpass),
+            "peaking" | "peak" | "bell" => Some(Self::Peaking),
+            "lowshelf" | "low_shelf" => Some(Self::LowShelf),
+            "highshelf" | "high_shelf" => Some(Self::HighShelf),
+            _ => None,
+        }
+    }
+}
+
+/// Q of a maximally flat (Butterworth) second-order section.
+pub const BUTTERWORTH_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// `gain_db` only matters for the peaking and shelf kinds.
+    pub fn design(
+        kind: BiquadKind,
+        freq_hz: f64,
+        sample_rate: u32,
+        q: f64,
+        gain_db: f64,
+    ) -> Result<Self, String> {
+        let nyquist = sample_rate as f64 / 2.0;
+        if !(freq_hz > 0.0 && freq_hz < nyquist) {
+            return Err(format!(
+                "filter frequency must be in (0, {nyquist}) Hz, got {freq_hz}"
+            ));
+        }
+        if !(q > 0.0 && q.is_finite()) {
+            return Err(format!("q must be > 0, got {q}"));
+        }
+        let w0 = 2.0 * PI * freq_hz / sample_rate as f64;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a = 10f64.powf(gain_db / 40.0);
+        let (b0, b1, b2, a0, a1, a2) = match kind {
+            BiquadKind::Lowpass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Highpass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Bandpass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Notch => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BiquadKind::Allpass => (
+                1.0 - alpha,
+                -2.0 * cos,
+                1.0 + alpha,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BiquadKind::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            BiquadKind::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                )
+            }
+            BiquadKind::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                )
+            }
+        };
+        Ok(Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        })
+    }
+
+    pub fn lowpass(freq_hz: f64, sample_rate: u32) -> Result<Self, String> {
+        Self::design(
+            BiquadKind::Lowpass,
+            freq_hz,
+            sample_rate,
+            BUTTERWORTH_Q,
+            0.0,
+        )
+    }
+
+    pub fn highpass(freq_hz: f64, sample_rate: u32) -> Result<Self, String> {
+        Self::design(
+            BiquadKind::Highpass,
+            freq_hz,
+            sample_rate,
+            BUTTERWORTH_Q,
+            0.0,
+        )
+    }
+
+    /// Normalized `(b0, b1, b2, a1, a2)` with `a0 = 1`.
+    pub fn coefficients(&self) -> [f64; 5] {
+        [self.b0, self.b1, self.b2, self.a1, self.a2]
+    }
+
+    pub fn process_sample(&mut self, x: f32) -> f32 {
+        let x = x as f64;
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y as f32
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for s in samples {
+            *s = self.process_sample(*s);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// Streaming FIR filter (direct form; fine for the few hundred taps audio filters need).
+#[derive(Debug, Clone)]
+pub struct Fir {
+    taps: Vec<f32>,
+    /// The last `taps.len() - 1` inputs, oldest first.
+    history: Vec<f32>,
+}
+
+impl Fir {
+    pub fn new(taps: Vec<f32>) -> Result<Self, String> {
+        if taps.is_empty() {
+            return Err("FIR filter needs at least one tap".to_string());
+        }
+        let history = vec![0.0; taps.len() - 1];
+        Ok(Self { taps, history })
+    }
+
+    pub fn taps(&self) -> &[f32] {
+        &self.taps
+    }
+
+    /// Delay in samples of this (linear-phase) filter.
+    pub fn group_delay(&self) -> usize {
+        (self.taps.len() - 1) / 2
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        let n = self.taps.len();
+        let mut line = std::mem::take(&mut self.history);
+        line.extend_from_slice(samples);
+        for (i, s) in samples.iter_mut().enumerate() {
+            let window = &line[i..i + n];
+            *s = window
+                .iter()
+                .zip(self.taps.iter().rev())
+                .map(|(x, t)| x * t)
+                .sum();
+        }
+        line.drain(..line.len() + 1 - n);
+        self.history = line;
+    }
+
+    pub fn reset(&mut self) {
+        self.history.iter_mut().for_each(|v| *v = 0.0);
+    }
+}
+
+/// Kaiser `beta` for a windowed-sinc design with `attenuation_db` of stopband.
+pub fn kaiser_beta(attenuation_db: f64) -> f64 {
+    if attenuation_db > 50.0 {
+        0.1102 * (attenuation_db - 8.7)
+    } else if attenuation_db >= 21.0 {
+        0.5842 * (attenuation_db - 21.0).powf(0.4) + 0.07886 * (attenuation_db - 21.0)
+    } else {
+        0.0
+    }
+}
+
+fn sinc_lowpass(num_taps: usize, cutoff: f64, beta: f64) -> Vec<f64> {
+    let window = kaiser(num_taps, beta);
+    let mid = (num_taps - 1) as f64 / 2.0;
+    let taps: Vec<f64> = (0..num_taps)
+        .map(|i| {
+            let x = i as f64 - mid;
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * PI * cutoff * x).sin() / (PI * x)
+            };
+            sinc * window[i] as f64
+        })
+        .collect();
+    // Exact unity gain at DC, whatever the window did to the tails.
+    let sum: f64 = taps.iter().sum();
+    taps.into_iter().map(|t| t / sum).collect()
+}
+
+/// Linear-phase FIR design (Kaiser-windowed sinc, ~80 dB stopband). `kind` is lowpass,
+/// highpass, bandpass or notch (band-stop); bands take `(low_hz, high_hz)`, the others use
+/// `low_hz` alone. Highpass and band-stop designs need an odd `num_taps`.
+pub fn design_fir(
+    kind: BiquadKind,
+    num_taps: usize,
+    low_hz: f64,
+    high_hz: Option<f64>,
+    sample_rate: u32,
+) -> Result<Vec<f32>, String> {
+    if num_taps == 0 {
+        return Err("num_taps must be > 0".to_string());
+    }
+    let nyquist = sample_rate as f64 / 2.0;
+    let edge = |hz: f64| -> Result<f64, String> {
+        if hz > 0.0 && hz < nyquist {
+            Ok(hz / sample_rate as f64)
+        } else {
+            Err(format!("cutoff must be in (0, {nyquist}) Hz, got {hz}"))
+        }
+    };
+    let beta = kaiser_beta(80.0);
+    let impulse = |n: usize| {
+        let mut d = vec![0.0; n];
+        d[(n - 1) / 2] = 1.0;
+        d
+    };
+    let taps: Vec<f64> = match kind {
+        BiquadKind::Lowpass => sinc_lowpass(num_taps, edge(low_hz)?, beta),
+        BiquadKind::Highpass | BiquadKind::Notch if num_taps.is_multiple_of(2) => {
+            return Err("highpass / band-stop FIR filters need an odd number of taps".to_string())
+        }
+        BiquadKind::Highpass => {
+            let lp = sinc_lowpass(num_taps, edge(low_hz)?, beta);
+            impulse(num_taps)
+                .iter()
+                .zip(&lp)
+                .map(|(d, l)| d - l)
+                .collect()
+        }
+        BiquadKind::Bandpass | BiquadKind::Notch => {
+            let high = high_hz.ok_or("band filters need both low and high cutoffs")?;
+            if high <= low_hz {
+                return Err(format!(
+                    "high cutoff {high} must exceed low cutoff {low_hz}"
+                ));
+            }
+            let hi = sinc_lowpass(num_taps, edge(high)?, beta);
+            let lo = sinc_lowpass(num_taps, edge(low_hz)?, beta);
+            let band: Vec<f64> = hi.iter().zip(&lo).map(|(h, l)| h - l).collect();
+            if kind == BiquadKind::Bandpass {
+                band
+            } else {
+                impulse(num_taps)
+                    .iter()
+                    .zip(&band)
+                    .map(|(d, b)| d - b)
+                    .collect()
+            }
+        }
+        other => return Err(format!("no FIR design for {other:?} filters")),
+    };
+    Ok(taps.into_iter().map(|t| t as f32).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain_at(process: &mut dyn FnMut(&mut [f32]), hz: f64, rate: u32) -> f32 {
+        let mut x: Vec<f32> = (0..rate as usize)
+            .map(|i| (2.0 * PI * hz * i as f64 / rate as f64).sin() as f32)
+            .collect();
+        process(&mut x);
+        let tail = &x[x.len() / 2..];
+        (tail.iter().map(|v| v * v).sum::<f32>() / tail.len() as f32).sqrt() * 2f32.sqrt()
+    }
+
+    #[test]
+    fn biquads_pass_and_stop_where_designed() {
+        let mut lp = Biquad::lowpass(1_000.0, 16_000).unwrap();
+        assert!((gain_at(&mut |x| lp.process(x), 100.0, 16_000) - 1.0).abs() < 0.01);
+        lp.reset();
+        assert!(gain_at(&mut |x| lp.process(x), 6_000.0, 16_000) < 0.05);
+
+        let mut hp = Biquad::highpass(300.0, 16_000).unwrap();
+        assert!(gain_at(&mut |x| hp.process(x), 30.0, 16_000) < 0.02);
+        hp.reset();
+        assert!((gain_at(&mut |x| hp.process(x), 3_000.0, 16_000) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn fir_streams_like_one_block() {
+        let taps = design_fir(BiquadKind::Lowpass, 101, 2_000.0, None, 16_000).unwrap();
+        let signal: Vec<f32> = (0..1_000).map(|i| ((i * 37) % 19) as f32 - 9.0).collect();
+        let mut whole = signal.clone();
+        Fir::new(taps.clone()).unwrap().process(&mut whole);
+        let mut fir = Fir::new(taps).unwrap();
+        let mut chunked = signal.clone();
+        for chunk in chunked.chunks_mut(77) {
+            fir.process(chunk);
+        }
+        assert!(whole
+            .iter()
+            .zip(&chunked)
+            .all(|(a, b)| (a - b).abs() < 1e-4));
+
+        let mut fir =
+            Fir::new(design_fir(BiquadKind::Lowpass, 101, 2_000.0, None, 16_000).unwrap()).unwrap();
+        assert!(gain_at(&mut |x| fir.process(x), 5_000.0, 16_000) < 1e-3);
+    }
+}
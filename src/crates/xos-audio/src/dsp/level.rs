@@ -0,0 +1,182 @@
+//! Levels: metering, gain normalization, automatic gain control, and channel downmix.
+
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+pub fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+}
+
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Gain in dB (`-inf` for silence).
+pub fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.log10()
+}
+
+/// Scale so the largest sample sits at `target_dbfs` (e.g. `-1.0`). Silence is left alone.
+pub fn normalize_peak(samples: &mut [f32], target_dbfs: f32) {
+    let p = peak(samples);
+    if p > 0.0 {
+        let g = db_to_gain(target_dbfs) / p;
+        samples.iter_mut().for_each(|s| *s *= g);
+    }
+}
+
+/// Scale to an RMS of `target_dbfs` (e.g. `-20.0`), but never so far that the peak would pass
+/// 0 dBFS. Silence is left alone.
+pub fn normalize_rms(samples: &mut [f32], target_dbfs: f32) {
+    let (r, p) = (rms(samples), peak(samples));
+    if r > 0.0 {
+        let g = (db_to_gain(target_dbfs) / r).min(1.0 / p);
+        samples.iter_mut().for_each(|s| *s *= g);
+    }
+}
+
+/// Automatic gain control: follows the signal's RMS envelope and steers a smoothed gain toward
+/// `target_dbfs`, bounded by `max_gain_db` so room noise is not pumped up in pauses, with a hard
+/// ceiling just under full scale. Gain only moves while the envelope is above `gate_dbfs`.
+#[derive(Debug, Clone)]
+pub struct Agc {
+    target: f32,
+    max_gain: f32,
+    gate: f32,
+    /// Per-sample smoothing for the envelope and for gain going down / up.
+    env_coef: f32,
+    attack_coef: f32,
+    release_coef: f32,
+    envelope: f32,
+    gain: f32,
+}
+
+impl Agc {
+    pub fn new(sample_rate: u32, target_dbfs: f32, max_gain_db: f32) -> Self {
+        let coef = |ms| smoothing_coef(ms, sample_rate);
+        Self {
+            target: db_to_gain(target_dbfs),
+            max_gain: db_to_gain(max_gain_db),
+            gate: db_to_gain(-60.0),
+            env_coef: coef(50.0),
+            attack_coef: coef(10.0),
+            release_coef: coef(500.0),
+            envelope: 0.0,
+            gain: 1.0,
+        }
+    }
+
+    /// Attack (gain falling on a loud onset) and release (gain rising again), in ms.
+    pub fn with_times(mut self, sample_rate: u32, attack_ms: f32, release_ms: f32) -> Self {
+        self.attack_coef = smoothing_coef(attack_ms, sample_rate);
+        self.release_coef = smoothing_coef(release_ms, sample_rate);
+        self
+    }
+
+    pub fn with_gate(mut self, gate_dbfs: f32) -> Self {
+        self.gate = db_to_gain(gate_dbfs);
+        self
+    }
+
+    /// Current gain in dB.
+    pub fn gain_db(&self) -> f32 {
+        gain_to_db(self.gain)
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for s in samples.iter_mut() {
+            let x = *s;
+            self.envelope = self.env_coef * self.envelope + (1.0 - self.env_coef) * x * x;
+            let level = self.envelope.sqrt();
+            if level > self.gate {
+                let want = (self.target / level).min(self.max_gain);
+                let coef = if want < self.gain {
+                    self.attack_coef
+                } else {
+                    self.release_coef
+                };
+                self.gain = coef * self.gain + (1.0 - coef) * want;
+            }
+            *s = (x * self.gain).clamp(-0.999, 0.999);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.envelope = 0.0;
+        self.gain = 1.0;
+    }
+}
+
+/// One-pole coefficient with a time constant of `ms`.
+fn smoothing_coef(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms.max(0.01) * 0.001 * sample_rate.max(1) as f32)).exp()
+}
+
+/// Channels more than this far below the loudest are left out of [`downmix`].
+const DOWNMIX_SILENT_CHANNEL_DB: f32 = 40.0;
+
+/// Mono downmix over the shortest channel. Plain averaging halves a voice that is only on one
+/// side (a mic on the left input of a stereo interface) and mixes in that side's noise floor,
+/// so channels that are effectively silent next to the loudest are skipped before averaging.
+pub fn downmix(channels: &[Vec<f32>]) -> Vec<f32> {
+    let n = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    if n == 0 {
+        return Vec::new();
+    }
+    if channels.len() == 1 {
+        return channels[0][..n].to_vec();
+    }
+    let levels: Vec<f32> = channels.iter().map(|c| rms(&c[..n])).collect();
+    let loudest = levels.iter().copied().fold(0.0f32, f32::max);
+    let floor = loudest * db_to_gain(-DOWNMIX_SILENT_CHANNEL_DB);
+    let active: Vec<&Vec<f32>> = channels
+        .iter()
+        .zip(&levels)
+        .filter(|(_, &l)| loudest == 0.0 || l >= floor)
+        .map(|(c, _)| c)
+        .collect();
+    let inv = 1.0 / active.len() as f32;
+    let mut out = vec![0.0f32; n];
+    for ch in active {
+        for (o, s) in out.iter_mut().zip(&ch[..n]) {
+            *o += s * inv;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downmix_skips_a_dead_channel() {
+        let voice: Vec<f32> = (0..1_000).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let hiss: Vec<f32> = (0..1_000)
+            .map(|i| if i % 2 == 0 { 1e-4 } else { -1e-4 })
+            .collect();
+        assert_eq!(downmix(&[voice.clone(), hiss]), voice);
+        let both = downmix(&[voice.clone(), voice.iter().map(|v| v * 0.5).collect()]);
+        assert!((both[10] - voice[10] * 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn agc_brings_quiet_speech_up_to_target() {
+        let rate = 16_000;
+        let mut x: Vec<f32> = (0..rate * 3)
+            .map(|i| (i as f32 * 0.07).sin() * 0.01)
+            .collect();
+        let mut agc = Agc::new(rate as u32, -20.0, 30.0);
+        agc.process(&mut x);
+        let level = gain_to_db(rms(&x[rate * 2..]));
+        assert!((level + 20.0).abs() < 1.0, "settled at {level} dBFS");
+
+        let mut y = vec![0.2f32, -0.4, 0.1];
+        normalize_peak(&mut y, 0.0);
+        assert!((peak(&y) - 1.0).abs() < 1e-6);
+    }
+}
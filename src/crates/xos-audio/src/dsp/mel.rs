@@ -0,0 +1,225 @@
+//! Mel filterbanks and spectrograms, matching `librosa.filters.mel` (Slaney scale and area
+//! normalization by default, the bank Whisper was trained on) with an HTK option.
+
+use super::stft::Stft;
+use super::window::hann;
+
+/// Hz → mel. Slaney: linear below 1 kHz, logarithmic above; HTK: `2595 · log10(1 + f / 700)`.
+pub fn hz_to_mel(hz: f64, htk: bool) -> f64 {
+    if htk {
+        return 2595.0 * (1.0 + hz / 700.0).log10();
+    }
+    let f_sp = 200.0 / 3.0;
+    let min_log_hz = 1000.0;
+    let min_log_mel = min_log_hz / f_sp;
+    let logstep = 6.4f64.ln() / 27.0;
+    if hz >= min_log_hz {
+        min_log_mel + (hz / min_log_hz).ln() / logstep
+    } else {
+        hz / f_sp
+    }
+}
+
+pub fn mel_to_hz(mel: f64, htk: bool) -> f64 {
+    if htk {
+        return 700.0 * (10f64.powf(mel / 2595.0) - 1.0);
+    }
+    let f_sp = 200.0 / 3.0;
+    let min_log_hz = 1000.0;
+    let min_log_mel = min_log_hz / f_sp;
+    let logstep = 6.4f64.ln() / 27.0;
+    if mel >= min_log_mel {
+        min_log_hz * (logstep * (mel - min_log_mel)).exp()
+    } else {
+        mel * f_sp
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MelOptions {
+    pub n_mels: usize,
+    pub f_min: f64,
+    /// `None`: the Nyquist rate.
+    pub f_max: Option<f64>,
+    pub htk: bool,
+    /// Scale each triangle to unit area (librosa `norm="slaney"`) instead of unit peak.
+    pub slaney_norm: bool,
+}
+
+impl Default for MelOptions {
+    fn default() -> Self {
+        Self {
+            n_mels: 80,
+            f_min: 0.0,
+            f_max: None,
+            htk: false,
+            slaney_norm: true,
+        }
+    }
+}
+
+/// Triangular filters, row-major `[n_mels][n_fft / 2 + 1]`.
+pub fn mel_filters(
+    sample_rate: u32,
+    n_fft: usize,
+    options: &MelOptions,
+) -> Result<Vec<f32>, String> {
+    let nyquist = sample_rate as f64 / 2.0;
+    let f_max = options.f_max.unwrap_or(nyquist);
+    if options.n_mels == 0 || n_fft == 0 {
+        return Err("n_mels and n_fft must be > 0".to_string());
+    }
+    if !(options.f_min >= 0.0 && options.f_min < f_max && f_max <= nyquist) {
+        return Err(format!(
+            "mel range must satisfy 0 <= f_min < f_max <= {nyquist}, got {} .. {f_max}",
+            options.f_min
+        ));
+    }
+    let bins = n_fft / 2 + 1;
+    let fft_hz: Vec<f64> = (0..bins)
+        .map(|k| k as f64 * sample_rate as f64 / n_fft as f64)
+        .collect();
+    let (lo, hi) = (
+        hz_to_mel(options.f_min, options.htk),
+        hz_to_mel(f_max, options.htk),
+    );
+    let points: Vec<f64> = (0..options.n_mels + 2)
+        .map(|i| {
+            mel_to_hz(
+                lo + (hi - lo) * i as f64 / (options.n_mels + 1) as f64,
+                options.htk,
+            )
+        })
+        .collect();
+    let mut out = Vec::with_capacity(options.n_mels * bins);
+    for m in 0..options.n_mels {
+        let (left, centre, right) = (points[m], points[m + 1], points[m + 2]);
+        let scale = if options.slaney_norm {
+            2.0 / (right - left)
+        } else {
+            1.0
+        };
+        out.extend(fft_hz.iter().map(|&f| {
+            let rise = (f - left) / (centre - left);
+            let fall = (right - f) / (right - centre);
+            (rise.min(fall).max(0.0) * scale) as f32
+        }));
+    }
+    Ok(out)
+}
+
+/// Power (or magnitude) mel spectrogram: periodic Hann STFT, centred, then the mel bank.
+pub struct MelSpectrogram {
+    stft: Stft,
+    filters: Vec<f32>,
+    n_mels: usize,
+    power: f32,
+}
+
+impl MelSpectrogram {
+    /// `power` 2.0 sums power, 1.0 sums magnitude.
+    pub fn new(
+        sample_rate: u32,
+        n_fft: usize,
+        hop: usize,
+        options: &MelOptions,
+        power: f32,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            stft: Stft::new(n_fft, hop, hann(n_fft, true), true)?,
+            filters: mel_filters(sample_rate, n_fft, options)?,
+            n_mels: options.n_mels,
+            power,
+        })
+    }
+
+    /// Whisper's front end: 25 ms windows every 10 ms at 16 kHz into `n_mels` (80 or 128) bands.
+    pub fn whisper(n_mels: usize) -> Result<Self, String> {
+        let options = MelOptions {
+            n_mels,
+            ..MelOptions::default()
+        };
+        Self::new(16_000, 400, 160, &options, 2.0)
+    }
+
+    pub fn n_mels(&self) -> usize {
+        self.n_mels
+    }
+
+    pub fn filters(&self) -> &[f32] {
+        &self.filters
+    }
+
+    /// Row-major `[n_mels][frames]`; returns the frame count alongside.
+    pub fn compute(&self, signal: &[f32]) -> (Vec<f32>, usize) {
+        let spec = self.stft.forward(signal);
+        let mag: Vec<f32> = if self.power == 2.0 {
+            spec.power()
+        } else {
+            spec.magnitude()
+                .into_iter()
+                .map(|m| m.powf(self.power))
+                .collect()
+        };
+        let bins = spec.bins;
+        let mut out = vec![0.0f32; self.n_mels * spec.frames];
+        for m in 0..self.n_mels {
+            let filter = &self.filters[m * bins..(m + 1) * bins];
+            for t in 0..spec.frames {
+                let frame = &mag[t * bins..(t + 1) * bins];
+                out[m * spec.frames + t] = filter.iter().zip(frame).map(|(w, p)| w * p).sum();
+            }
+        }
+        (out, spec.frames)
+    }
+}
+
+/// `10 · log10(max(x, 1e-10))`, floored `top_db` below the loudest value when given.
+pub fn power_to_db(values: &mut [f32], top_db: Option<f32>) {
+    for v in values.iter_mut() {
+        *v = 10.0 * v.max(1e-10).log10();
+    }
+    if let Some(top) = top_db {
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let floor = max - top;
+        values.iter_mut().for_each(|v| *v = v.max(floor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mel_scale_round_trips() {
+        for hz in [0.0, 440.0, 999.0, 1000.0, 4000.0, 7999.0] {
+            for htk in [false, true] {
+                assert!((mel_to_hz(hz_to_mel(hz, htk), htk) - hz).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn whisper_bank_matches_librosa_shape() {
+        let mel = MelSpectrogram::whisper(80).unwrap();
+        let f = mel.filters();
+        assert_eq!(f.len(), 80 * 201);
+        // librosa.filters.mel(sr=16000, n_fft=400, n_mels=80)[0, 1] and [79, 200].
+        assert!((f[1] - 0.024_862_05).abs() < 1e-5, "{}", f[1]);
+        assert!(f[79 * 201 + 200].abs() < 1e-6);
+        // Every band covers at least one FFT bin.
+        assert!((0..80).all(|m| f[m * 201..(m + 1) * 201].iter().any(|&w| w > 0.0)));
+
+        let tone: Vec<f32> = (0..16_000)
+            .map(|i| (2.0 * std::f32::consts::PI * 1_000.0 * i as f32 / 16_000.0).sin())
+            .collect();
+        let (spec, frames) = mel.compute(&tone);
+        assert_eq!(frames, 101);
+        let t = 50;
+        let loudest = (0..80)
+            .max_by(|&a, &b| spec[a * frames + t].total_cmp(&spec[b * frames + t]))
+            .unwrap();
+        let centre = |m: usize| mel_to_hz(hz_to_mel(8_000.0, false) * (m + 1) as f64 / 81.0, false);
+        assert!((centre(loudest) - 1_000.0).abs() < 80.0, "band {loudest}");
+    }
+}
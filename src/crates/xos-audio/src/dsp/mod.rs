@@ -0,0 +1,18 @@
+//! Signal processing shared by capture, transcription, speech and Python (`xos.audio.dsp`):
+//! FFT of any length, band-limited resampling, biquad / FIR filters, STFT / iSTFT, mel
+//! spectrograms, and level normalization / AGC. Pure Rust, no platform code, mono `f32` slices.
+
+pub mod fft;
+pub mod filter;
+pub mod level;
+pub mod mel;
+pub mod resample;
+pub mod stft;
+pub mod window;
+
+pub use fft::{fft, ifft, Fft};
+pub use filter::{design_fir, Biquad, BiquadKind, Fir};
+pub use level::{downmix, normalize_peak, normalize_rms, peak, rms, Agc};
+pub use mel::{mel_filters, power_to_db, MelOptions, MelSpectrogram};
+pub use resample::{resample, Resampler};
+pub use stft::{Spectrogram, Stft};
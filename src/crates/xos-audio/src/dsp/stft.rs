@@ -0,0 +1,242 @@
+//! Short-time Fourier transform with the `torch.stft` / librosa conventions: frame `t` starts at
+//! `t · hop`, and with `center` the signal is reflect-padded by `n_fft / 2` on both sides so
+//! frame `t` is centred on sample `t · hop`. [`Stft::inverse`] is weighted overlap-add, exact
+//! wherever the squared windows overlap (any Hann window with `hop ≤ n_fft / 2`).
+
+use super::fft::Fft;
+
+/// One-sided spectrum per frame, row-major `[frames][bins]` with `bins = n_fft / 2 + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrogram {
+    pub frames: usize,
+    pub bins: usize,
+    pub re: Vec<f32>,
+    pub im: Vec<f32>,
+}
+
+impl Spectrogram {
+    pub fn power(&self) -> Vec<f32> {
+        self.re
+            .iter()
+            .zip(&self.im)
+            .map(|(r, i)| r * r + i * i)
+            .collect()
+    }
+
+    pub fn magnitude(&self) -> Vec<f32> {
+        self.re
+            .iter()
+            .zip(&self.im)
+            .map(|(r, i)| (r * r + i * i).sqrt())
+            .collect()
+    }
+}
+
+pub struct Stft {
+    n_fft: usize,
+    hop: usize,
+    window: Vec<f32>,
+    center: bool,
+    fft: Fft,
+}
+
+impl Stft {
+    /// `window` may be shorter than `n_fft`; it is zero-padded on both sides, like `torch.stft`.
+    pub fn new(n_fft: usize, hop: usize, window: Vec<f32>, center: bool) -> Result<Self, String> {
+        if n_fft == 0 || hop == 0 {
+            return Err(format!("n_fft and hop must be > 0, got {n_fft} and {hop}"));
+        }
+        if window.len() > n_fft {
+            return Err(format!(
+                "window has {} samples, more than n_fft = {n_fft}",
+                window.len()
+            ));
+        }
+        let left = (n_fft - window.len()) / 2;
+        let mut padded = vec![0.0; n_fft];
+        padded[left..left + window.len()].copy_from_slice(&window);
+        Ok(Self {
+            n_fft,
+            hop,
+            window: padded,
+            center,
+            fft: Fft::new(n_fft),
+        })
+    }
+
+    pub fn n_fft(&self) -> usize {
+        self.n_fft
+    }
+
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    pub fn bins(&self) -> usize {
+        self.n_fft / 2 + 1
+    }
+
+    /// Frames produced for `len` input samples.
+    pub fn frames_for(&self, len: usize) -> usize {
+        let padded = if self.center {
+            len + self.n_fft / 2 * 2
+        } else {
+            len
+        };
+        if padded < self.n_fft {
+            0
+        } else {
+            1 + (padded - self.n_fft) / self.hop
+        }
+    }
+
+    pub fn forward(&self, signal: &[f32]) -> Spectrogram {
+        let padded;
+        let x: &[f32] = if self.center {
+            padded = reflect_pad(signal, self.n_fft / 2);
+            &padded
+        } else {
+            signal
+        };
+        let frames = self.frames_for(signal.len());
+        let bins = self.bins();
+        let mut re = Vec::with_capacity(frames * bins);
+        let mut im = Vec::with_capacity(frames * bins);
+        let mut buf_re = vec![0.0f64; self.n_fft];
+        let mut buf_im = vec![0.0f64; self.n_fft];
+        for t in 0..frames {
+            let frame = &x[t * self.hop..t * self.hop + self.n_fft];
+            for (k, (s, w)) in frame.iter().zip(&self.window).enumerate() {
+                buf_re[k] = (s * w) as f64;
+                buf_im[k] = 0.0;
+            }
+            self.fft.forward(&mut buf_re, &mut buf_im);
+            re.extend(buf_re[..bins].iter().map(|&v| v as f32));
+            im.extend(buf_im[..bins].iter().map(|&v| v as f32));
+        }
+        Spectrogram {
+            frames,
+            bins,
+            re,
+            im,
+        }
+    }
+
+    /// Overlap-add resynthesis; `length` trims or zero-pads the result (default: the longest
+    /// signal these frames could have come from).
+    pub fn inverse(&self, spec: &Spectrogram, length: Option<usize>) -> Result<Vec<f32>, String> {
+        if spec.bins != self.bins() {
+            return Err(format!(
+                "spectrogram has {} bins, n_fft = {} needs {}",
+                spec.bins,
+                self.n_fft,
+                self.bins()
+            ));
+        }
+        if spec.frames == 0 {
+            return Ok(vec![0.0; length.unwrap_or(0)]);
+        }
+        let total = self.n_fft + (spec.frames - 1) * self.hop;
+        let mut out = vec![0.0f64; total];
+        let mut norm = vec![0.0f64; total];
+        let mut buf_re = vec![0.0f64; self.n_fft];
+        let mut buf_im = vec![0.0f64; self.n_fft];
+        for t in 0..spec.frames {
+            let row = t * spec.bins;
+            for k in 0..spec.bins {
+                buf_re[k] = spec.re[row + k] as f64;
+                buf_im[k] = spec.im[row + k] as f64;
+            }
+            // Hermitian mirror: the signal is real.
+            for k in spec.bins..self.n_fft {
+                buf_re[k] = buf_re[self.n_fft - k];
+                buf_im[k] = -buf_im[self.n_fft - k];
+            }
+            self.fft.inverse(&mut buf_re, &mut buf_im);
+            let at = t * self.hop;
+            for (k, &w) in self.window.iter().enumerate() {
+                out[at + k] += buf_re[k] * w as f64;
+                norm[at + k] += (w * w) as f64;
+            }
+        }
+        let start = if self.center { self.n_fft / 2 } else { 0 };
+        let natural = if self.center {
+            total.saturating_sub(2 * start)
+        } else {
+            total
+        };
+        let len = length.unwrap_or(natural);
+        Ok((0..len)
+            .map(|i| match (out.get(start + i), norm.get(start + i)) {
+                (Some(&v), Some(&n)) if n > 1e-11 => (v / n) as f32,
+                _ => 0.0,
+            })
+            .collect())
+    }
+}
+
+/// Mirror `pad` samples onto each end without repeating the edge sample (numpy `reflect`);
+/// falls back to zeros where the signal is too short to mirror.
+fn reflect_pad(x: &[f32], pad: usize) -> Vec<f32> {
+    let n = x.len() as i64;
+    let at = |i: i64| -> f32 {
+        if n == 0 {
+            return 0.0;
+        }
+        let j = if i < 0 {
+            -i
+        } else if i >= n {
+            2 * (n - 1) - i
+        } else {
+            i
+        };
+        if (0..n).contains(&j) {
+            x[j as usize]
+        } else {
+            0.0
+        }
+    };
+    (-(pad as i64)..n + pad as i64).map(at).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsp::window::hann;
+
+    #[test]
+    fn inverse_undoes_forward() {
+        let signal: Vec<f32> = (0..3_001)
+            .map(|i| ((i as f32) * 0.031).sin() + 0.3 * ((i as f32) * 0.47).cos())
+            .collect();
+        for (n_fft, hop) in [(400, 160), (512, 128), (256, 64)] {
+            let stft = Stft::new(n_fft, hop, hann(n_fft, true), true).unwrap();
+            let spec = stft.forward(&signal);
+            assert_eq!(spec.frames, 1 + signal.len() / hop);
+            let back = stft.inverse(&spec, Some(signal.len())).unwrap();
+            let err = back
+                .iter()
+                .zip(&signal)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0f32, f32::max);
+            assert!(err < 1e-4, "n_fft={n_fft} hop={hop}: max error {err}");
+        }
+    }
+
+    #[test]
+    fn a_bin_centred_tone_lands_in_its_bin() {
+        let n_fft = 256;
+        let signal: Vec<f32> = (0..4_096)
+            .map(|i| (2.0 * std::f32::consts::PI * 16.0 * i as f32 / n_fft as f32).cos())
+            .collect();
+        let spec = Stft::new(n_fft, 64, hann(n_fft, true), false)
+            .unwrap()
+            .forward(&signal);
+        let power = spec.power();
+        let row = &power[5 * spec.bins..6 * spec.bins];
+        let peak = (0..spec.bins)
+            .max_by(|&a, &b| row[a].total_cmp(&row[b]))
+            .unwrap();
+        assert_eq!(peak, 16);
+    }
+}
@@ -0,0 +1,75 @@
+//! Analysis / design windows. `periodic` windows (length `n`, period `n`) are the ones STFTs want
+//! (librosa and Whisper use a periodic Hann); symmetric ones suit FIR design.
+
+use std::f64::consts::PI;
+
+fn cosine_sum(n: usize, periodic: bool, a0: f64) -> Vec<f32> {
+    if n <= 1 {
+        return vec![1.0; n];
+    }
+    let denom = if periodic { n } else { n - 1 } as f64;
+    (0..n)
+        .map(|i| (a0 - (1.0 - a0) * (2.0 * PI * i as f64 / denom).cos()) as f32)
+        .collect()
+}
+
+pub fn hann(n: usize, periodic: bool) -> Vec<f32> {
+    cosine_sum(n, periodic, 0.5)
+}
+
+pub fn hamming(n: usize, periodic: bool) -> Vec<f32> {
+    cosine_sum(n, periodic, 0.54)
+}
+
+/// Symmetric Kaiser window; larger `beta` trades a wider main lobe for lower side lobes
+/// (`beta = 8.6` gives roughly 90 dB of stopband in FIR design).
+pub fn kaiser(n: usize, beta: f64) -> Vec<f32> {
+    if n <= 1 {
+        return vec![1.0; n];
+    }
+    let norm = bessel_i0(beta);
+    let half = (n - 1) as f64 / 2.0;
+    (0..n)
+        .map(|i| {
+            let r = (i as f64 - half) / half;
+            (bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / norm) as f32
+        })
+        .collect()
+}
+
+/// Kaiser window evaluated at offset `x` from the centre of a window of half-width `half`
+/// (zero outside); what the resampler uses for its fractional tap positions.
+pub(crate) fn kaiser_at(x: f64, half: f64, beta: f64) -> f64 {
+    let r = x / half;
+    if r.abs() >= 1.0 {
+        return 0.0;
+    }
+    bessel_i0(beta * (1.0 - r * r).sqrt()) / bessel_i0(beta)
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series).
+pub fn bessel_i0(x: f64) -> f64 {
+    let q = x * x / 4.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for k in 1..200 {
+        term *= q / (k * k) as f64;
+        sum += term;
+        if term < sum * 1e-17 {
+            break;
+        }
+    }
+    sum
+}
+
+/// Window by name, as accepted from Python (`"hann"`, `"hamming"`, `"rect"`).
+pub fn by_name(name: &str, n: usize, periodic: bool) -> Result<Vec<f32>, String> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "hann" | "hanning" => Ok(hann(n, periodic)),
+        "hamming" => Ok(hamming(n, periodic)),
+        "rect" | "rectangular" | "boxcar" | "none" => Ok(vec![1.0; n]),
+        other => Err(format!(
+            "unknown window {other:?} (expected hann, hamming or rect)"
+        )),
+    }
+}
@@ -11,6 +11,7 @@
 //!   On **macOS**, use a virtual loopback driver (e.g. BlackHole) for system capture until
 //!   a native loopback backend is wired in.
 //! - [`speakers`] ([`output`]) — playback to output devices.
+//! - [`dsp`] — resampling, filters, STFT, mel spectrograms and gain control (all platforms).
 //!
 //! Each submodule handles platform-specific implementations (native/iOS/WASM) internally
 //! using conditional compilation.
@@ -33,6 +34,8 @@ use std::fmt;
 pub mod microphone;
 pub mod speakers;
 
+pub mod dsp;
+
 #[cfg(all(
     not(target_arch = "wasm32"),
     not(target_os = "ios"),
@@ -154,6 +154,11 @@ pub fn whisper_model_cache_dir(model_key: &str) -> Result<PathBuf, AuthError> {
         .join(model_key))
 }
 
+/// Model registry root: `{data_dir}/models/` (each registry entry names a folder below it).
+pub fn model_store_dir() -> Result<PathBuf, AuthError> {
+    Ok(auth_data_dir()?.join("models"))
+}
+
 /// Move legacy `authentication.json` / `node_identity.json` from the data root into `auth/`.
 fn ensure_auth_subdir_migrated() -> Result<(), AuthError> {
     let base = auth_data_dir()?;
@@ -8,3 +8,9 @@ pub mod daemon_remote;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod daemon;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod models_cli;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transcribe_cli;
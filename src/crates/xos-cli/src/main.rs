@@ -23,8 +23,17 @@ use std::time::{Duration, Instant};
 use tiny_http::{Method, Response, Server};
 #[cfg(not(target_arch = "wasm32"))]
 use uuid::Uuid;
+use xos::engine::capture::CaptureConfig;
+use xos::engine::replay::{InputCapture, ReplayLog};
+use xos::ai::transcription::{WhisperBackend, WhisperTask};
+use xos::python_api::testing::{collect_visual_test_files, run_visual_tests, VisualTestOptions};
+use xos_cli::models_cli::{run_models_command, ModelsCommands};
+use xos_cli::transcribe_cli::{
+    collect_audio_files, load_diarizer, run_transcribe_batch, OutputFormat, TranscribeBatchOptions,
+};
 use xos::python_api::{
-    parse_script_cli_flags, run_python_app, run_python_file, run_python_interactive,
+    parse_script_cli_flags, run_python_app_with_options, run_python_file, run_python_interactive,
+    PythonAppOptions,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -166,10 +175,87 @@ enum Commands {
         /// Launch the Python file in the current precompiled wasm browser runtime.
         #[arg(long = "wasm", alias = "web")]
         wasm: bool,
+        /// Record mouse / keyboard / scroll / resize input and frame timing to a file for `--replay`.
+        #[arg(long = "record-input", value_name = "FILE")]
+        record_input: Option<PathBuf>,
+        /// Replay a `--record-input` file frame for frame (the file defaults to the recorded script).
+        #[arg(long, value_name = "FILE", conflicts_with = "record_input")]
+        replay: Option<PathBuf>,
+        /// Run headless and save frames to FILE (`.mp4` / `.webm` via ffmpeg, `.gif`, or `.png` / a
+        /// directory for a PNG sequence).
+        #[arg(long, value_name = "FILE")]
+        capture: Option<PathBuf>,
+        /// Stop (and exit) after this many captured frames.
+        #[arg(long, value_name = "N")]
+        frames: Option<u32>,
+        /// Frame size, e.g. `1280x720`: the headless / captured frame, or the window's initial size.
+        #[arg(long, value_name = "WxH", value_parser = parse_frame_size)]
+        size: Option<(u32, u32)>,
         /// Script flags after the file (e.g. `--record` → `xos.flags.record`)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         rest: Vec<String>,
     },
+    /// Run golden-image tests: `test_*` functions in `test_*.py` files that script an app with
+    /// `xos.testing.Script` and compare frames against PNGs under `golden/`.
+    #[command(name = "test")]
+    Test {
+        /// Test files or directories to search (default: `tests`)
+        #[arg(default_value = "tests")]
+        paths: Vec<PathBuf>,
+        /// Rewrite goldens from the current output instead of comparing
+        #[arg(long)]
+        update: bool,
+        /// Only run tests whose name contains this text
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+    },
+    /// Transcribe audio files (or directories of them) with Whisper, writing TXT / JSON / SRT /
+    /// WebVTT next to each input. Long recordings are chunked at pauses with Silero VAD; inputs
+    /// whose outputs are already newer are skipped, so reruns resume.
+    #[command(name = "transcribe")]
+    Transcribe {
+        /// Audio files or directories to search recursively
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Whisper model size (`tiny`, `base`, `small`, ...)
+        #[arg(long, short)]
+        model: Option<String>,
+        /// `ct2` (default) or `burn`
+        #[arg(long, default_value = "ct2", value_parser = parse_whisper_backend)]
+        backend: WhisperBackend,
+        /// Spoken language: code, English name or `auto` (default: English)
+        #[arg(long, short)]
+        language: Option<String>,
+        /// `transcribe`, or `translate` to English (multilingual models only)
+        #[arg(long, default_value = "transcribe", value_parser = parse_whisper_task)]
+        task: WhisperTask,
+        /// Outputs to write, comma-separated (default: all of txt,json,srt,vtt)
+        #[arg(long, value_delimiter = ',', value_parser = parse_output_format)]
+        format: Vec<OutputFormat>,
+        /// Align words to the audio (adds `words` to the JSON output)
+        #[arg(long)]
+        word_timestamps: bool,
+        /// Files transcribed in parallel; each worker loads its own model
+        #[arg(long, short, default_value_t = 1)]
+        jobs: usize,
+        /// Transcribe even when the outputs are up to date
+        #[arg(long)]
+        force: bool,
+        /// Label segments by speaker (`Speaker 1`, `Speaker 2`, ... or enrolled names)
+        #[arg(long)]
+        diarize: bool,
+        /// Enroll a known voice from a clip of them speaking, as NAME=FILE (repeatable; implies
+        /// --diarize)
+        #[arg(long = "speaker", value_name = "NAME=FILE", value_parser = parse_speaker)]
+        speakers: Vec<(String, PathBuf)>,
+    },
+    /// List, download, remove and offline-import the models xos uses (Whisper, VAD, speaker
+    /// embeddings), cached under `xos path --data`/models.
+    #[command(name = "models")]
+    Models {
+        #[command(subcommand)]
+        action: ModelsCommands,
+    },
     /// Print git repo root, app data dir (credentials, etc.), and this CLI binary path.
     /// With `--code`, `--data`, or `--cli-exe`, print only that path (plain, no colors) for shell use, e.g. `cd "$(xos path --data)"`.
     Path {
@@ -373,6 +459,59 @@ fn version_git_second_line(color_uncommitted: bool) -> String {
     }
 }
 
+/// `1280x720` → `(1280, 720)`.
+fn parse_frame_size(s: &str) -> Result<(u32, u32), String> {
+    let (w, h) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got '{s}'"))?;
+    let parse = |v: &str| v.trim().parse::<u32>().ok().filter(|n| *n > 0);
+    match (parse(w), parse(h)) {
+        (Some(w), Some(h)) => Ok((w, h)),
+        _ => Err(format!("expected WIDTHxHEIGHT, got '{s}'")),
+    }
+}
+
+fn parse_whisper_backend(s: &str) -> Result<WhisperBackend, String> {
+    WhisperBackend::from_str(s).ok_or_else(|| format!("unknown backend '{s}' (use ct2 or burn)"))
+}
+
+fn parse_whisper_task(s: &str) -> Result<WhisperTask, String> {
+    WhisperTask::from_str(s)
+        .ok_or_else(|| format!("unknown task '{s}' (use transcribe or translate)"))
+}
+
+fn parse_speaker(s: &str) -> Result<(String, PathBuf), String> {
+    match s.split_once('=') {
+        Some((name, path)) if !name.trim().is_empty() && !path.is_empty() => {
+            Ok((name.trim().to_string(), PathBuf::from(path)))
+        }
+        _ => Err(format!("expected NAME=FILE, got '{s}'")),
+    }
+}
+
+fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
+    OutputFormat::from_str(s)
+        .ok_or_else(|| format!("unknown format '{s}' (use txt, json, srt or vtt)"))
+}
+
+/// Remove `--name VALUE` / `--name=VALUE` from script args (engine flags given after the file).
+fn take_flag_value(rest: &mut Vec<String>, name: &str) -> Option<String> {
+    let prefix = format!("{name}=");
+    let idx = rest
+        .iter()
+        .position(|arg| arg == name || arg.starts_with(&prefix))?;
+    let arg = rest.remove(idx);
+    if let Some(value) = arg.strip_prefix(&prefix) {
+        return Some(value.to_string());
+    }
+    if idx < rest.len() {
+        Some(rest.remove(idx))
+    } else {
+        eprintln!("❌ {name} needs a value");
+        std::process::exit(1);
+    }
+}
+
 fn resolve_python_file_path(file: &Path) -> Option<PathBuf> {
     if file.exists() {
         return Some(file.to_path_buf());
@@ -923,8 +1062,28 @@ fn main() {
         }) => {
             run_path_command(code, data, cli_exe);
         }
-        Some(Commands::Py { file, wasm, rest }) => {
-            if let Some(file_path) = file {
+        Some(Commands::Py {
+            file,
+            wasm,
+            record_input,
+            replay,
+            capture,
+            frames,
+            size,
+            rest,
+        }) => {
+            let replay_log = replay.map(|path| match ReplayLog::load(&path) {
+                Ok(log) => log,
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    std::process::exit(1);
+                }
+            });
+            let recorded_script = replay_log
+                .as_ref()
+                .and_then(|log| log.header.script.as_ref())
+                .map(PathBuf::from);
+            if let Some(file_path) = file.or(recorded_script) {
                 let path_to_run = resolved_python_file.unwrap_or(file_path);
                 let mut rest = rest;
                 let wasm = wasm
@@ -936,16 +1095,160 @@ fn main() {
                             true
                         })
                         .unwrap_or(false);
+                let capture =
+                    capture.or_else(|| take_flag_value(&mut rest, "--capture").map(Into::into));
+                let frames = frames.or_else(|| {
+                    take_flag_value(&mut rest, "--frames").map(|v| match v.parse::<u32>() {
+                        Ok(n) if n > 0 => n,
+                        _ => {
+                            eprintln!("❌ --frames expects a positive number, got '{v}'");
+                            std::process::exit(1);
+                        }
+                    })
+                });
+                let size = size.or_else(|| {
+                    take_flag_value(&mut rest, "--size").map(|v| match parse_frame_size(&v) {
+                        Ok(size) => size,
+                        Err(e) => {
+                            eprintln!("❌ --size: {e}");
+                            std::process::exit(1);
+                        }
+                    })
+                });
+                if frames.is_some() && capture.is_none() {
+                    eprintln!("❌ --frames needs --capture");
+                    std::process::exit(1);
+                }
+                // A replay reuses the recorded script flags unless new ones are given.
+                if rest.is_empty() {
+                    if let Some(log) = &replay_log {
+                        rest = log.header.args.clone();
+                    }
+                }
                 let flags = parse_script_cli_flags(&rest);
+                let input = match (record_input, replay_log) {
+                    (_, Some(log)) => Some(InputCapture::Replay(log)),
+                    (Some(path), None) => Some(InputCapture::Record {
+                        path,
+                        script: Some(path_to_run.to_string_lossy().into_owned()),
+                        args: rest.clone(),
+                    }),
+                    (None, None) => None,
+                };
+                let capture = capture.map(|path| {
+                    let mut config = CaptureConfig::new(path);
+                    config.max_frames = frames;
+                    config.offline = true;
+                    config.exit_when_done = frames.is_some();
+                    config
+                });
                 if wasm {
+                    if input.is_some() || capture.is_some() {
+                        eprintln!(
+                            "❌ --record-input / --replay / --capture are not supported with --wasm"
+                        );
+                        std::process::exit(1);
+                    }
                     xos::run_python_wasm_file(&path_to_run, &flags);
                 } else {
-                    run_python_app(&path_to_run, &flags);
+                    let options = PythonAppOptions {
+                        input,
+                        capture,
+                        size,
+                    };
+                    run_python_app_with_options(&path_to_run, &flags, options);
                 }
+            } else if replay_log.is_some() {
+                eprintln!("❌ recording has no script path; pass the Python file too");
+                std::process::exit(1);
             } else {
                 run_python_interactive();
             }
         }
+        Some(Commands::Test {
+            paths,
+            update,
+            filter,
+        }) => {
+            let files = collect_visual_test_files(&paths);
+            if files.is_empty() {
+                eprintln!("❌ no test_*.py files found");
+                std::process::exit(1);
+            }
+            let summary = run_visual_tests(
+                &files,
+                &VisualTestOptions {
+                    update,
+                    filter,
+                    tolerance: None,
+                },
+            );
+            println!(
+                "\n{} passed, {} failed, {} goldens written",
+                summary.passed, summary.failed, summary.written
+            );
+            if summary.failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Transcribe {
+            paths,
+            model,
+            backend,
+            language,
+            task,
+            format,
+            word_timestamps,
+            jobs,
+            force,
+            diarize,
+            speakers,
+        }) => {
+            let files = collect_audio_files(&paths);
+            if files.is_empty() {
+                eprintln!("❌ no audio files found");
+                std::process::exit(1);
+            }
+            let diarize = diarize || !speakers.is_empty();
+            // Fail before any file if the speaker model or an enrollment clip is unusable.
+            if diarize {
+                if let Err(e) = load_diarizer(&speakers) {
+                    eprintln!("❌ diarization: {e}");
+                    std::process::exit(1);
+                }
+            }
+            let formats = if format.is_empty() {
+                OutputFormat::ALL.to_vec()
+            } else {
+                format
+            };
+            let options = TranscribeBatchOptions {
+                model,
+                backend,
+                language,
+                task,
+                word_timestamps,
+                formats,
+                jobs,
+                force,
+                diarize,
+                speakers,
+            };
+            let summary = run_transcribe_batch(&files, &options);
+            println!(
+                "\n{} transcribed, {} skipped, {} failed",
+                summary.transcribed, summary.skipped, summary.failed
+            );
+            if summary.failed > 0 {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Models { action }) => {
+            if let Err(e) = run_models_command(action) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+        }
         Some(Commands::Login { delete, reset }) => {
             if delete && reset {
                 eprintln!("❌ use either --delete or --reset, not both");
@@ -0,0 +1,183 @@
+//! `xos models` — inspect and manage the model registry cache (`{data_dir}/models/`): list what is
+//! installed, pull or remove models, import them offline from a folder or tarball, clean up
+//! partial downloads, and pin upstream checksums into the registry.
+
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use xos_core::ai::models::{self, ModelEntry, ModelStatus};
+
+#[derive(Subcommand)]
+pub enum ModelsCommands {
+    /// Show every registry model with its licence, install status and size on disk
+    #[command(name = "list", visible_alias = "ls")]
+    List {
+        /// Also print each file's URL and SHA-256 (for fetching on another machine)
+        #[arg(long)]
+        files: bool,
+    },
+    /// Download models (resuming partial downloads) and verify their checksums
+    #[command(name = "pull")]
+    Pull {
+        /// Registry names, e.g. `whisper-tiny-ct2` (see `xos models list`)
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Accept files the registry has no SHA-256 for yet (unchecked; for development)
+        #[arg(long)]
+        allow_unpinned: bool,
+    },
+    /// Delete installed models, including files converted from them
+    #[command(name = "rm", visible_alias = "remove")]
+    Rm {
+        /// Registry names
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// Install models from a directory or `.tar.gz` without network access: either a copy of
+    /// another machine's `models/` folder or files named as in `xos models list --files`
+    #[command(name = "import")]
+    Import {
+        path: PathBuf,
+        /// Accept files the registry has no SHA-256 for yet (unchecked; for development)
+        #[arg(long)]
+        allow_unpinned: bool,
+    },
+    /// Remove partial downloads, extraction leftovers and models the registry no longer lists
+    #[command(name = "gc")]
+    Gc {
+        /// Only print what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Download files that have no SHA-256 yet and write their hashes into the source checkout's
+    /// registry.json (for maintainers; needs network access)
+    #[command(name = "pin")]
+    Pin {
+        /// Registry names; defaults to every model with an unpinned file
+        names: Vec<String>,
+    },
+}
+
+pub fn run_models_command(command: ModelsCommands) -> Result<(), String> {
+    match command {
+        ModelsCommands::List { files } => list(files),
+        ModelsCommands::Pull {
+            names,
+            allow_unpinned,
+        } => {
+            models::allow_unpinned(allow_unpinned);
+            for entry in resolve(&names)? {
+                let dir = models::pull(entry)?;
+                println!("✅ {} → {}", entry.name, dir.display());
+            }
+            Ok(())
+        }
+        ModelsCommands::Rm { names } => {
+            for entry in resolve(&names)? {
+                let bytes = models::remove(entry)?;
+                println!("🗑️  {} ({} freed)", entry.name, format_bytes(bytes));
+            }
+            Ok(())
+        }
+        ModelsCommands::Import {
+            path,
+            allow_unpinned,
+        } => {
+            models::allow_unpinned(allow_unpinned);
+            for name in models::import(&path)? {
+                println!("✅ imported {name}");
+            }
+            Ok(())
+        }
+        ModelsCommands::Gc { dry_run } => {
+            let report = models::gc(dry_run)?;
+            for path in &report.removed {
+                println!("{}", path.display());
+            }
+            let verb = if dry_run { "would free" } else { "freed" };
+            println!(
+                "{} item(s), {verb} {}",
+                report.removed.len(),
+                format_bytes(report.bytes)
+            );
+            Ok(())
+        }
+        ModelsCommands::Pin { names } => pin(&names),
+    }
+}
+
+fn pin(names: &[String]) -> Result<(), String> {
+    let entries = if names.is_empty() {
+        models::registry()?
+            .iter()
+            .filter(|e| e.files.iter().any(|f| f.sha256.is_none()))
+            .collect()
+    } else {
+        resolve(names)?
+    };
+    let path = xos_core::find_xos_project_root()?.join(models::REGISTRY_PATH);
+    let mut json =
+        std::fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let mut pinned = 0;
+    for entry in entries {
+        for (url, sha) in models::hash_unpinned(entry)? {
+            // Entries sharing a URL were pinned together by an earlier file.
+            if let Ok(updated) = models::pin_in_registry(&json, &url, &sha) {
+                json = updated;
+                pinned += 1;
+                println!("📌 {} {sha}", entry.name);
+            }
+        }
+        // Written after every model so an interrupted run keeps what it hashed.
+        std::fs::write(&path, &json).map_err(|e| format!("write {}: {e}", path.display()))?;
+    }
+    println!("{pinned} file(s) pinned in {}", path.display());
+    Ok(())
+}
+
+/// Look every name up before touching anything, so a typo fails the whole command.
+fn resolve(names: &[String]) -> Result<Vec<&'static ModelEntry>, String> {
+    names.iter().map(|n| models::find(n)).collect()
+}
+
+fn list(files: bool) -> Result<(), String> {
+    let entries = models::registry()?;
+    let width = entries.iter().map(|e| e.name.len()).max().unwrap_or(0);
+    for entry in entries {
+        let status = match models::status(entry)? {
+            ModelStatus::Missing => "-".to_string(),
+            ModelStatus::Installed {
+                version: Some(_),
+                bytes,
+            } => format!("installed, {}", format_bytes(bytes)),
+            ModelStatus::Installed {
+                version: None,
+                bytes,
+            } => format!("installed (unverified), {}", format_bytes(bytes)),
+            ModelStatus::Outdated { version, bytes } => {
+                format!("outdated (v{version}), {}", format_bytes(bytes))
+            }
+        };
+        println!(
+            "{:<width$}  v{:<4} {:<12} {status}",
+            entry.name, entry.version, entry.license
+        );
+        if files {
+            println!("    {}", models::model_dir(entry)?.display());
+            for file in &entry.files {
+                let sha = file.sha256.as_deref().unwrap_or("(not pinned)");
+                println!("    {}  {}\n      sha256 {sha}", file.name, file.url);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    match bytes as f64 {
+        b if b >= 1024.0 * MB => format!("{:.1} GB", b / (1024.0 * MB)),
+        b if b >= MB => format!("{:.1} MB", b / MB),
+        b => format!("{:.0} KB", b / 1024.0),
+    }
+}
@@ -0,0 +1,381 @@
+//! `xos transcribe` — batch Whisper transcription of audio files and directories. Each input is
+//! decoded to mono, chunked at pauses with Silero VAD and transcribed by a pool of workers; TXT /
+//! JSON / SRT / WebVTT land next to the input (`meeting.m4a.srt`). Inputs whose outputs are all
+//! newer than the audio are skipped, so rerunning after an interruption resumes where it stopped.
+//! With `--diarize`, segments are labelled by speaker (enrolled names, else `Speaker N`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use serde_json::{json, Value};
+use xos_core::ai::transcription::batch::{transcribe_long, LongTranscript};
+use xos_core::ai::transcription::diarization::Diarizer;
+use xos_core::ai::transcription::timestamps::{to_srt, to_vtt};
+use xos_core::ai::transcription::{TranscribeOptions, WhisperBackend, WhisperTask};
+use xos_core::engine::audio::decode_path_to_mono_f32;
+
+/// Extensions the Symphonia decoder is built with.
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "oga", "m4a", "mp4", "aac"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Txt,
+    Json,
+    Srt,
+    Vtt,
+}
+
+impl OutputFormat {
+    pub const ALL: [Self; 4] = [Self::Txt, Self::Json, Self::Srt, Self::Vtt];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "txt" | "text" => Some(Self::Txt),
+            "json" => Some(Self::Json),
+            "srt" => Some(Self::Srt),
+            "vtt" | "webvtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Json => "json",
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscribeBatchOptions {
+    /// Whisper size (`tiny`, `base`, ...); `None` uses the backend's default.
+    pub model: Option<String>,
+    pub backend: WhisperBackend,
+    /// Code, English name or `"auto"`; `None` means English.
+    pub language: Option<String>,
+    pub task: WhisperTask,
+    pub word_timestamps: bool,
+    pub formats: Vec<OutputFormat>,
+    /// Worker threads; each loads its own copy of the model.
+    pub jobs: usize,
+    /// Transcribe even when the outputs are up to date.
+    pub force: bool,
+    /// Label segments by speaker.
+    pub diarize: bool,
+    /// Known voices to enroll before diarizing: name and a clip of them speaking.
+    pub speakers: Vec<(String, PathBuf)>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TranscribeBatchSummary {
+    pub transcribed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Audio files under `paths`: files as given, directories searched recursively (sorted, skipping
+/// hidden entries).
+pub fn collect_audio_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    fn is_audio(path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+    }
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                walk(&path, out);
+            } else if is_audio(&path) {
+                out.push(path);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, &mut out);
+        } else {
+            out.push(path.clone());
+        }
+    }
+    out
+}
+
+/// `meeting.m4a` → `meeting.m4a.srt`. Keyed on the whole file name so `meeting.wav` and
+/// `meeting.m4a` in one folder don't overwrite (or skip) each other.
+pub fn output_path(input: &Path, format: OutputFormat) -> PathBuf {
+    let mut name = input.as_os_str().to_owned();
+    name.push(".");
+    name.push(format.extension());
+    PathBuf::from(name)
+}
+
+/// Every requested output exists and was written after the input last changed.
+fn is_up_to_date(input: &Path, formats: &[OutputFormat]) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    let Some(input_time) = modified(input) else {
+        return false;
+    };
+    formats.iter().all(|&format| {
+        modified(&output_path(input, format)).is_some_and(|output_time| output_time >= input_time)
+    })
+}
+
+fn transcript_json(input: &Path, result: &LongTranscript) -> Value {
+    let transcript = &result.transcript;
+    let segments: Vec<Value> = transcript
+        .segments
+        .iter()
+        .map(|segment| {
+            json!({
+                "start": segment.start,
+                "end": segment.end,
+                "text": segment.text,
+                "speaker": segment.speaker,
+                "no_speech_prob": segment.no_speech_prob,
+                "tokens": segment.tokens.iter().map(|t| json!({
+                    "id": t.id,
+                    "text": t.text,
+                    "start": t.start,
+                    "end": t.end,
+                    "probability": t.probability,
+                })).collect::<Vec<_>>(),
+                "words": segment.words.iter().map(|w| json!({
+                    "text": w.text,
+                    "start": w.start,
+                    "end": w.end,
+                    "probability": w.probability,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({
+        "file": input.display().to_string(),
+        "duration": result.duration,
+        "text": transcript.text,
+        "language": transcript.detected_language.map(|d| d.code),
+        "language_probability": transcript.detected_language.map(|d| d.probability),
+        "segments": segments,
+    })
+}
+
+/// Write through a temporary file and rename, so an interrupted run never leaves a truncated
+/// output that looks up to date.
+fn write_output(path: &Path, contents: &str) -> Result<(), String> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".part");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, contents).map_err(|e| format!("write {}: {e}", tmp.display()))?;
+    fs::rename(&tmp, path).map_err(|e| format!("rename to {}: {e}", path.display()))
+}
+
+/// Plain text, or one `Name: ...` line per speaker turn when segments are labelled.
+fn transcript_text(result: &LongTranscript) -> String {
+    let segments = &result.transcript.segments;
+    if segments.iter().all(|s| s.speaker.is_none()) {
+        return format!("{}\n", result.transcript.text);
+    }
+    let mut lines: Vec<(Option<&str>, String)> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let speaker = segment.speaker.as_deref();
+        match lines.last_mut() {
+            Some((last, line)) if *last == speaker => {
+                line.push(' ');
+                line.push_str(text);
+            }
+            _ => lines.push((speaker, text.to_string())),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(speaker, line)| match speaker {
+            Some(name) => format!("{name}: {line}\n"),
+            None => format!("{line}\n"),
+        })
+        .collect()
+}
+
+/// Load the speaker-embedding model and enroll `speakers` from their clips.
+pub fn load_diarizer(speakers: &[(String, PathBuf)]) -> Result<Diarizer, String> {
+    let mut diarizer = Diarizer::new()?;
+    for (name, path) in speakers {
+        let (sample_rate, _duration, mono) = decode_path_to_mono_f32(path)
+            .map_err(|e| format!("speaker '{name}' ({}): {e}", path.display()))?;
+        diarizer.enroll(name, &mono, sample_rate)?;
+    }
+    Ok(diarizer)
+}
+
+fn transcribe_file(
+    input: &Path,
+    options: &TranscribeBatchOptions,
+    diarizer: Option<&mut Diarizer>,
+) -> Result<LongTranscript, String> {
+    let (sample_rate, _duration, mono) = decode_path_to_mono_f32(input)?;
+    let decode = TranscribeOptions {
+        language: options.language.as_deref(),
+        task: options.task,
+        timestamps: true,
+        word_timestamps: options.word_timestamps,
+        tokens: false,
+    };
+    let result = transcribe_long(
+        options.model.as_deref(),
+        &mono,
+        sample_rate,
+        options.backend,
+        &decode,
+        diarizer,
+    )?;
+    for &format in &options.formats {
+        let contents = match format {
+            OutputFormat::Txt => transcript_text(&result),
+            OutputFormat::Json => {
+                let value = transcript_json(input, &result);
+                serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?
+            }
+            OutputFormat::Srt => to_srt(&result.transcript.segments),
+            OutputFormat::Vtt => to_vtt(&result.transcript.segments),
+        };
+        write_output(&output_path(input, format), &contents)?;
+    }
+    Ok(result)
+}
+
+/// Transcribe `files` with `options.jobs` workers, printing one line per file as it finishes.
+pub fn run_transcribe_batch(
+    files: &[PathBuf],
+    options: &TranscribeBatchOptions,
+) -> TranscribeBatchSummary {
+    let total = files.len();
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let transcribed = AtomicUsize::new(0);
+    let skipped = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let vad_warned = AtomicBool::new(false);
+
+    let worker = || {
+        // Each worker enrolls its own copy; voices found in one file are forgotten before the next.
+        let mut diarizer = options
+            .diarize
+            .then(|| load_diarizer(&options.speakers))
+            .transpose();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(input) = files.get(index) else {
+                break;
+            };
+            if !options.force && is_up_to_date(input, &options.formats) {
+                skipped.fetch_add(1, Ordering::Relaxed);
+                let n = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                println!("[{n}/{total}] {} — up to date, skipped", input.display());
+                continue;
+            }
+            let started = Instant::now();
+            let outcome = match &mut diarizer {
+                Ok(diarizer) => {
+                    if let Some(diarizer) = diarizer {
+                        diarizer.forget_unknown();
+                    }
+                    transcribe_file(input, options, diarizer.as_mut())
+                }
+                Err(e) => Err(e.clone()),
+            };
+            let n = finished.fetch_add(1, Ordering::Relaxed) + 1;
+            match outcome {
+                Ok(result) => {
+                    transcribed.fetch_add(1, Ordering::Relaxed);
+                    if let Some(note) = &result.vad_note {
+                        if !vad_warned.swap(true, Ordering::Relaxed) {
+                            eprintln!("⚠️  {note}; using fixed 30 s windows");
+                        }
+                    }
+                    let language = result
+                        .transcript
+                        .detected_language
+                        .map(|d| format!(", {}", d.code))
+                        .unwrap_or_default();
+                    println!(
+                        "[{n}/{total}] {} ({:.1}s audio, {} chunk{}{language}) in {:.1}s ✓",
+                        input.display(),
+                        result.duration,
+                        result.chunks,
+                        if result.chunks == 1 { "" } else { "s" },
+                        started.elapsed().as_secs_f64()
+                    );
+                }
+                Err(e) => {
+                    failed.fetch_add(1, Ordering::Relaxed);
+                    eprintln!("[{n}/{total}] {} ❌ {e}", input.display());
+                }
+            }
+        }
+    };
+
+    let jobs = options.jobs.clamp(1, total.max(1));
+    std::thread::scope(|scope| {
+        for _ in 1..jobs {
+            scope.spawn(worker);
+        }
+        worker();
+    });
+
+    TranscribeBatchSummary {
+        transcribed: transcribed.into_inner(),
+        skipped: skipped.into_inner(),
+        failed: failed.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_sharing_a_stem_get_separate_outputs() {
+        let dir = std::env::temp_dir().join(format!("xos-transcribe-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (wav, m4a) = (dir.join("meeting.wav"), dir.join("meeting.m4a"));
+        fs::write(&wav, b"").unwrap();
+        fs::write(&m4a, b"").unwrap();
+
+        assert_eq!(
+            collect_audio_files(std::slice::from_ref(&dir)),
+            [m4a.clone(), wav.clone()]
+        );
+        assert_eq!(
+            output_path(&m4a, OutputFormat::Srt),
+            dir.join("meeting.m4a.srt")
+        );
+        assert_ne!(
+            output_path(&wav, OutputFormat::Srt),
+            output_path(&m4a, OutputFormat::Srt)
+        );
+
+        // Transcribing one input must not mark the other as done.
+        let formats = [OutputFormat::Txt];
+        fs::write(output_path(&wav, OutputFormat::Txt), b"hi").unwrap();
+        assert!(is_up_to_date(&wav, &formats));
+        assert!(!is_up_to_date(&m4a, &formats));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -16,7 +16,7 @@ use std::path::Path;
 use anyhow::{anyhow, Result};
 use mel_spec::mel::{log_mel_spectrogram, mel, norm_mel};
 use mel_spec::stft::Spectrogram;
-use ndarray::{s, stack, Array2, Axis};
+use ndarray::{s, stack, Array2, Array3, Axis};
 use serde::Deserialize;
 
 pub use super::sys::WhisperOptions;
@@ -25,6 +25,36 @@ use super::{sys, Config, Tokenizer};
 
 const PREPROCESSOR_CONFIG_FILE: &str = "preprocessor_config.json";
 
+/// Median filter width of the DTW token alignment (same as OpenAI Whisper).
+const ALIGNMENT_MEDIAN_FILTER_WIDTH: i64 = 7;
+
+/// A timed part of a transcription returned by [`Whisper::generate_segments`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WhisperSegment {
+    /// Start time in seconds from the beginning of the samples.
+    pub start: f32,
+    /// End time in seconds from the beginning of the samples.
+    pub end: f32,
+    pub text: String,
+    /// Probability of the no speech token for the 30 second window of this segment.
+    pub no_speech_prob: f32,
+    /// Text tokens with their probabilities (empty unless tokens were requested); times are the
+    /// segment's unless token timestamps were requested too.
+    pub tokens: Vec<WhisperToken>,
+}
+
+/// A text token of a [`WhisperSegment`], optionally aligned to the audio by cross-attention.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WhisperToken {
+    pub id: usize,
+    pub text: String,
+    /// Start time in seconds from the beginning of the samples.
+    pub start: f32,
+    /// End time in seconds from the beginning of the samples.
+    pub end: f32,
+    pub probability: f32,
+}
+
 /// A speach transcriber using the Whisper speech recognition model published by OpenAI.
 ///
 /// # Example
@@ -93,35 +123,7 @@ impl Whisper {
         timestamp: bool,
         options: &WhisperOptions,
     ) -> Result<Vec<String>> {
-        let mut stft = Spectrogram::new(self.config.n_fft, self.config.hop_length);
-
-        let mut mel_spectrogram_vec = vec![];
-        for chunk in samples.chunks(self.config.n_samples) {
-            let mut mel_spectrogram_per_chunk =
-                Array2::zeros((self.config.feature_size, self.config.nb_max_frames));
-            for (i, flame) in chunk.chunks(self.config.hop_length).enumerate() {
-                if let Some(fft_frame) = stft.add(flame) {
-                    let mel = norm_mel(&log_mel_spectrogram(&fft_frame, &self.config.mel_filters))
-                        .mapv(|v| v as f32);
-                    mel_spectrogram_per_chunk
-                        .slice_mut(s![.., i])
-                        .assign(&mel.slice(s![.., 0]));
-                }
-            }
-            mel_spectrogram_vec.push(mel_spectrogram_per_chunk);
-        }
-
-        let mut mel_spectrogram = stack(
-            Axis(0),
-            &mel_spectrogram_vec
-                .iter()
-                .map(|a| a.view())
-                .collect::<Vec<_>>(),
-        )?;
-        if !mel_spectrogram.is_standard_layout() {
-            mel_spectrogram = mel_spectrogram.as_standard_layout().into_owned()
-        }
-
+        let (mut mel_spectrogram, chunks) = self.mel_spectrogram(samples)?;
         let shape = mel_spectrogram.shape().to_vec();
         let storage_view = sys::StorageView::new(
             &shape,
@@ -153,11 +155,7 @@ impl Whisper {
             prompt.push("<|notimestamps|>");
         }
         self.whisper
-            .generate(
-                &storage_view,
-                &vec![prompt; mel_spectrogram_vec.len()],
-                options,
-            )?
+            .generate(&storage_view, &vec![prompt; chunks], options)?
             .into_iter()
             .map(|res| {
                 let r = res
@@ -170,6 +168,279 @@ impl Whisper {
             .collect()
     }
 
+    /// Detect the spoken language from the first segment of the given samples.
+    ///
+    /// # Arguments
+    /// * `samples` - Samples of the source audio, as for [`generate`][Whisper::generate]. Only
+    ///   the first [`n_samples`][Whisper::n_samples] samples are used.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing `(language code, probability)` pairs ordered from best to
+    /// worst, e.g. `("en", 0.97)`, or an error if the model is not multilingual.
+    pub fn detect_language(&self, samples: &[f32]) -> Result<Vec<(String, f32)>> {
+        let head = &samples[..samples.len().min(self.config.n_samples)];
+        let (mut mel_spectrogram, _) = self.mel_spectrogram(head)?;
+        let shape = mel_spectrogram.shape().to_vec();
+        let storage_view = sys::StorageView::new(
+            &shape,
+            mel_spectrogram.as_slice_mut().unwrap(),
+            Default::default(),
+        )?;
+        Ok(self
+            .whisper
+            .detect_language(&storage_view)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("failed to detect language"))?
+            .into_iter()
+            .map(|res| {
+                let code = res
+                    .language
+                    .trim_start_matches("<|")
+                    .trim_end_matches("|>")
+                    .to_string();
+                (code, res.probability)
+            })
+            .collect())
+    }
+
+    /// Transcribe the given samples into timed segments.
+    ///
+    /// # Arguments
+    /// * `samples` - Samples of the source audio, as for [`generate`][Whisper::generate].
+    /// * `language` - Language code of the speech (e.g. `"en"`).
+    /// * `task` - `"transcribe"` to keep the spoken language or `"translate"` to output English.
+    /// * `timestamps` - If `true`, segments are split at the predicted timestamp tokens;
+    ///   otherwise each 30 second window is one segment.
+    /// * `tokens` - If `true`, fills each segment's text tokens with their probability from an
+    ///   alignment pass over the decoder; each token spans its whole segment.
+    /// * `token_timestamps` - If `true`, also times each text token by DTW over the decoder
+    ///   cross-attention. Implies `tokens`.
+    /// * `options` - Settings.
+    ///
+    /// # Returns
+    /// Returns a `Result` containing the segments in order, or an error if the transcription
+    /// fails.
+    pub fn generate_segments(
+        &self,
+        samples: &[f32],
+        language: &str,
+        task: &str,
+        timestamps: bool,
+        tokens: bool,
+        token_timestamps: bool,
+        options: &WhisperOptions,
+    ) -> Result<Vec<WhisperSegment>> {
+        let tokens = tokens || token_timestamps;
+        let (mut mel_spectrogram, chunks) = self.mel_spectrogram(samples)?;
+        let shape = mel_spectrogram.shape().to_vec();
+        let features = sys::StorageView::new(
+            &shape,
+            mel_spectrogram.as_slice_mut().unwrap(),
+            Default::default(),
+        )?;
+
+        let lang_token = format!("<|{}|>", language);
+        let task_token = format!("<|{}|>", task);
+        let mut prompt = vec!["<|startoftranscript|>", &lang_token, &task_token];
+        let start_sequence_len = prompt.len();
+        if !timestamps {
+            prompt.push("<|notimestamps|>");
+        }
+        let prompts = vec![prompt.clone(); chunks];
+
+        let mut options = options.clone();
+        options.return_no_speech_prob = true;
+        // Encode once so the alignment pass can reuse the encoder output.
+        let encoder_output = if tokens {
+            Some(self.whisper.encode(&features, false)?)
+        } else {
+            None
+        };
+        let results = match &encoder_output {
+            Some(encoded) => self.whisper.generate(encoded, &prompts, &options)?,
+            None => self.whisper.generate(&features, &prompts, &options)?,
+        };
+
+        let sampling_rate = self.config.sampling_rate as f32;
+        let eot = self
+            .tokenizer
+            .token_to_id("<|endoftext|>")
+            .map(|id| id as usize)
+            .unwrap_or(usize::MAX);
+        let mut segments = Vec::new();
+        let mut chunk_segments = Vec::with_capacity(chunks);
+        let mut chunk_text_ids = Vec::with_capacity(chunks);
+        for (index, (res, chunk)) in results
+            .iter()
+            .zip(samples.chunks(self.config.n_samples))
+            .enumerate()
+        {
+            let offset = (index * self.config.n_samples) as f32 / sampling_rate;
+            let duration = chunk.len() as f32 / sampling_rate;
+            let pieces = res
+                .sequences
+                .first()
+                .ok_or_else(|| anyhow!("failed to transcribe samples"))?;
+            let ids = res
+                .sequences_ids
+                .first()
+                .ok_or_else(|| anyhow!("failed to transcribe samples"))?;
+
+            let first_segment = segments.len();
+            let mut text_ids = Vec::new();
+            let mut pending: Vec<(usize, String)> = Vec::new();
+            let mut start = 0.0;
+            for (piece, &id) in pieces.iter().zip(ids) {
+                if let Some(time) = parse_timestamp_token(piece) {
+                    if !pending.is_empty() {
+                        segments.push(self.segment(
+                            offset + start,
+                            offset + time,
+                            std::mem::take(&mut pending),
+                            res.no_speech_prob,
+                            tokens,
+                        )?);
+                    }
+                    start = time;
+                } else if id < eot {
+                    text_ids.push(id);
+                    pending.push((id, piece.clone()));
+                }
+            }
+            if !pending.is_empty() {
+                segments.push(self.segment(
+                    offset + start,
+                    offset + duration,
+                    pending,
+                    res.no_speech_prob,
+                    tokens,
+                )?);
+            }
+            chunk_segments.push(first_segment..segments.len());
+            chunk_text_ids.push(text_ids);
+        }
+
+        if let Some(encoded) = &encoder_output {
+            let start_sequence = prompt[..start_sequence_len]
+                .iter()
+                .map(|token| {
+                    self.tokenizer
+                        .token_to_id(token)
+                        .map(|id| id as usize)
+                        .ok_or_else(|| anyhow!("unknown token {token}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let num_frames = samples
+                .chunks(self.config.n_samples)
+                .map(|chunk| chunk.len() / self.config.hop_length)
+                .collect::<Vec<_>>();
+            let alignments = self.whisper.align(
+                encoded,
+                &start_sequence,
+                &chunk_text_ids,
+                &num_frames,
+                ALIGNMENT_MEDIAN_FILTER_WIDTH,
+            )?;
+            // Encoder frames are two mel hops long.
+            let frames_per_second = sampling_rate / (self.config.hop_length * 2) as f32;
+            for (index, (alignment, range)) in alignments.iter().zip(chunk_segments).enumerate() {
+                let offset = (index * self.config.n_samples) as f32 / sampling_rate;
+                // Without token timestamps, tokens keep their segment's times.
+                let times = if token_timestamps {
+                    token_jump_times(alignment, frames_per_second)
+                } else {
+                    Vec::new()
+                };
+                fill_tokens(
+                    &mut segments[range],
+                    offset,
+                    &times,
+                    &alignment.text_token_probs,
+                );
+            }
+        }
+        Ok(segments)
+    }
+
+    fn segment(
+        &self,
+        start: f32,
+        end: f32,
+        pieces: Vec<(usize, String)>,
+        no_speech_prob: f32,
+        with_tokens: bool,
+    ) -> Result<WhisperSegment> {
+        let tokens = if with_tokens {
+            pieces
+                .iter()
+                .map(|(id, piece)| {
+                    Ok(WhisperToken {
+                        id: *id,
+                        text: self.tokenizer.decode(vec![piece.clone()])?,
+                        start,
+                        end,
+                        probability: 0.0,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            Vec::new()
+        };
+        Ok(WhisperSegment {
+            start,
+            end,
+            text: self
+                .tokenizer
+                .decode(pieces.into_iter().map(|(_, piece)| piece).collect())?,
+            no_speech_prob,
+            tokens,
+        })
+    }
+
+    /// Decode token ids into text, skipping special tokens.
+    pub fn decode_ids(&self, ids: &[usize]) -> Result<String> {
+        let inner: &tokenizers::Tokenizer = &self.tokenizer;
+        let ids = ids.iter().map(|&id| id as u32).collect::<Vec<_>>();
+        inner
+            .decode(&ids, true)
+            .map_err(|err| anyhow!("failed to decode: {err}"))
+    }
+
+    /// Mel spectrogram of `samples` in `n_samples` segments: `[segments, n_mels, frames]` in
+    /// standard layout, plus the segment count.
+    fn mel_spectrogram(&self, samples: &[f32]) -> Result<(Array3<f32>, usize)> {
+        let mut stft = Spectrogram::new(self.config.n_fft, self.config.hop_length);
+
+        let mut mel_spectrogram_vec = vec![];
+        for chunk in samples.chunks(self.config.n_samples) {
+            let mut mel_spectrogram_per_chunk =
+                Array2::zeros((self.config.feature_size, self.config.nb_max_frames));
+            for (i, flame) in chunk.chunks(self.config.hop_length).enumerate() {
+                if let Some(fft_frame) = stft.add(flame) {
+                    let mel = norm_mel(&log_mel_spectrogram(&fft_frame, &self.config.mel_filters))
+                        .mapv(|v| v as f32);
+                    mel_spectrogram_per_chunk
+                        .slice_mut(s![.., i])
+                        .assign(&mel.slice(s![.., 0]));
+                }
+            }
+            mel_spectrogram_vec.push(mel_spectrogram_per_chunk);
+        }
+
+        let mut mel_spectrogram = stack(
+            Axis(0),
+            &mel_spectrogram_vec
+                .iter()
+                .map(|a| a.view())
+                .collect::<Vec<_>>(),
+        )?;
+        if !mel_spectrogram.is_standard_layout() {
+            mel_spectrogram = mel_spectrogram.as_standard_layout().into_owned()
+        }
+        Ok((mel_spectrogram, mel_spectrogram_vec.len()))
+    }
+
     /// Returns the expected sampling rate.
     pub fn sampling_rate(&self) -> usize {
         self.config.sampling_rate
@@ -217,6 +488,38 @@ impl Debug for Whisper {
     }
 }
 
+/// `<|1.24|>` → `1.24`; `None` for every other token.
+fn parse_timestamp_token(piece: &str) -> Option<f32> {
+    piece.strip_prefix("<|")?.strip_suffix("|>")?.parse().ok()
+}
+
+/// Set the probability of the text tokens of one window's `segments` in order, and their times
+/// (offset by the window's start) when `times` has them.
+fn fill_tokens(segments: &mut [WhisperSegment], offset: f32, times: &[f32], probs: &[f32]) {
+    let tokens = segments.iter_mut().flat_map(|segment| &mut segment.tokens);
+    for (k, token) in tokens.enumerate() {
+        if let Some(&start) = times.get(k) {
+            token.start = offset + start;
+            token.end = offset + times.get(k + 1).copied().unwrap_or(start);
+        }
+        token.probability = probs.get(k).copied().unwrap_or(0.0);
+    }
+}
+
+/// Time (seconds within the window) at which the DTW path enters each token, including the
+/// trailing end-of-text token.
+fn token_jump_times(alignment: &sys::WhisperAlignmentResult, frames_per_second: f32) -> Vec<f32> {
+    let mut times = Vec::new();
+    let mut last_token = None;
+    for step in &alignment.alignments {
+        if last_token != Some(step.token_x) {
+            last_token = Some(step.token_x);
+            times.push(step.frame_x as f32 / frames_per_second);
+        }
+    }
+    times
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 struct PreprocessorConfig {
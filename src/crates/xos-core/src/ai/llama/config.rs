@@ -0,0 +1,189 @@
+//! Llama hyper-parameters, read from a Hugging Face `config.json` or GGUF `llama.*` metadata.
+
+use serde::Deserialize;
+
+use super::gguf::{GgufFile, GgufValue};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlamaConfig {
+    pub vocab_size: usize,
+    pub hidden_size: usize,
+    pub intermediate_size: usize,
+    pub num_layers: usize,
+    pub num_heads: usize,
+    pub num_kv_heads: usize,
+    pub rms_norm_eps: f64,
+    pub rope_theta: f64,
+    /// Longest prompt + reply the KV cache accepts.
+    pub max_seq_len: usize,
+    pub tie_word_embeddings: bool,
+    /// End-of-sequence ids from the checkpoint (the chat template may add its end-of-turn token).
+    pub eos_token_ids: Vec<u32>,
+}
+
+impl LlamaConfig {
+    pub fn head_dim(&self) -> usize {
+        self.hidden_size / self.num_heads
+    }
+
+    fn validate(self) -> Result<Self, String> {
+        if self.num_heads == 0
+            || self.num_kv_heads == 0
+            || !self.hidden_size.is_multiple_of(self.num_heads)
+            || !self.num_heads.is_multiple_of(self.num_kv_heads)
+            || !self.head_dim().is_multiple_of(2)
+        {
+            return Err(format!(
+                "unsupported attention layout: hidden {} / heads {} / kv heads {}",
+                self.hidden_size, self.num_heads, self.num_kv_heads
+            ));
+        }
+        Ok(self)
+    }
+
+    /// `config.json` of a `LlamaForCausalLM` (or Mistral-style) checkpoint.
+    pub fn from_hf_json(text: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Ids {
+            One(u32),
+            Many(Vec<u32>),
+        }
+        #[derive(Deserialize)]
+        struct Hf {
+            #[serde(default)]
+            model_type: Option<String>,
+            vocab_size: usize,
+            hidden_size: usize,
+            intermediate_size: usize,
+            num_hidden_layers: usize,
+            num_attention_heads: usize,
+            num_key_value_heads: Option<usize>,
+            #[serde(default = "default_eps")]
+            rms_norm_eps: f64,
+            #[serde(default = "default_theta")]
+            rope_theta: f64,
+            #[serde(default = "default_ctx")]
+            max_position_embeddings: usize,
+            #[serde(default)]
+            tie_word_embeddings: bool,
+            eos_token_id: Option<Ids>,
+        }
+        fn default_eps() -> f64 {
+            1e-5
+        }
+        fn default_theta() -> f64 {
+            10_000.0
+        }
+        fn default_ctx() -> usize {
+            2048
+        }
+        let hf: Hf = serde_json::from_str(text).map_err(|e| format!("config.json: {e}"))?;
+        if let Some(kind) = hf.model_type.as_deref() {
+            if kind != "llama" && kind != "mistral" {
+                return Err(format!(
+                    "config.json model_type '{kind}' is not a Llama-family model"
+                ));
+            }
+        }
+        Self {
+            vocab_size: hf.vocab_size,
+            hidden_size: hf.hidden_size,
+            intermediate_size: hf.intermediate_size,
+            num_layers: hf.num_hidden_layers,
+            num_heads: hf.num_attention_heads,
+            num_kv_heads: hf.num_key_value_heads.unwrap_or(hf.num_attention_heads),
+            rms_norm_eps: hf.rms_norm_eps,
+            rope_theta: hf.rope_theta,
+            max_seq_len: hf.max_position_embeddings,
+            tie_word_embeddings: hf.tie_word_embeddings,
+            eos_token_ids: match hf.eos_token_id {
+                Some(Ids::One(id)) => vec![id],
+                Some(Ids::Many(ids)) => ids,
+                None => Vec::new(),
+            },
+        }
+        .validate()
+    }
+
+    /// `llama.*` keys of a GGUF file; `has_output` is whether it stores a separate `output.weight`.
+    pub fn from_gguf(file: &GgufFile, has_output: bool) -> Result<Self, String> {
+        let arch = file
+            .get("general.architecture")
+            .and_then(GgufValue::as_str)
+            .unwrap_or("llama");
+        if arch != "llama" {
+            return Err(format!(
+                "GGUF architecture '{arch}' is not supported (only llama)"
+            ));
+        }
+        let int = |key: &str| {
+            file.get(&format!("llama.{key}"))
+                .and_then(GgufValue::as_u64)
+                .map(|v| v as usize)
+        };
+        let need = |key: &str| int(key).ok_or_else(|| format!("GGUF is missing llama.{key}"));
+        let float = |key: &str, default: f64| {
+            file.get(&format!("llama.{key}"))
+                .and_then(GgufValue::as_f64)
+                .unwrap_or(default)
+        };
+        let num_heads = need("attention.head_count")?;
+        let vocab_size = match int("vocab_size") {
+            Some(n) => n,
+            None => file
+                .get("tokenizer.ggml.tokens")
+                .and_then(GgufValue::as_array)
+                .map(|t| t.len())
+                .ok_or_else(|| "GGUF has neither llama.vocab_size nor a vocabulary".to_string())?,
+        };
+        Self {
+            vocab_size,
+            hidden_size: need("embedding_length")?,
+            intermediate_size: need("feed_forward_length")?,
+            num_layers: need("block_count")?,
+            num_heads,
+            num_kv_heads: int("attention.head_count_kv").unwrap_or(num_heads),
+            rms_norm_eps: float("attention.layer_norm_rms_epsilon", 1e-5),
+            rope_theta: float("rope.freq_base", 10_000.0),
+            max_seq_len: int("context_length").unwrap_or(2048),
+            tie_word_embeddings: !has_output,
+            eos_token_ids: file
+                .get("tokenizer.ggml.eos_token_id")
+                .and_then(GgufValue::as_u64)
+                .map(|id| vec![id as u32])
+                .unwrap_or_default(),
+        }
+        .validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_hf_config_with_defaults() {
+        let cfg = LlamaConfig::from_hf_json(
+            r#"{"model_type": "llama", "vocab_size": 49152, "hidden_size": 576,
+                "intermediate_size": 1536, "num_hidden_layers": 30, "num_attention_heads": 9,
+                "num_key_value_heads": 3, "rope_theta": 100000.0, "tie_word_embeddings": true,
+                "eos_token_id": [2, 0]}"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.head_dim(), 64);
+        assert_eq!(cfg.num_kv_heads, 3);
+        assert_eq!(cfg.rms_norm_eps, 1e-5);
+        assert_eq!(cfg.max_seq_len, 2048);
+        assert_eq!(cfg.eos_token_ids, vec![2, 0]);
+        assert!(cfg.tie_word_embeddings);
+
+        let bad = r#"{"model_type": "gpt2", "vocab_size": 1, "hidden_size": 8,
+            "intermediate_size": 8, "num_hidden_layers": 1, "num_attention_heads": 2}"#;
+        assert!(LlamaConfig::from_hf_json(bad).is_err());
+        let odd = bad
+            .replace("gpt2", "llama")
+            .replace("\"num_attention_heads\": 2", "\"num_attention_heads\": 3");
+        assert!(LlamaConfig::from_hf_json(&odd).is_err());
+    }
+}
@@ -0,0 +1,534 @@
+//! GGUF reader (llama.cpp's single-file format): metadata, tensor directory, and dequantization of
+//! the tensor types chat checkpoints ship in (F32 / F16 / BF16, Q8_0, Q4_0, Q4_1, Q4_K, Q6_K).
+
+use std::collections::BTreeMap;
+
+const MAGIC: &[u8; 4] = b"GGUF";
+const DEFAULT_ALIGNMENT: usize = 32;
+const QK: usize = 32;
+const QK_K: usize = 256;
+/// Arrays of arrays nest at most a level or two in real files; a crafted header could otherwise
+/// recurse until the stack overflows.
+const MAX_ARRAY_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GgufValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Array(Vec<GgufValue>),
+}
+
+impl GgufValue {
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Int(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(v) => Some(*v),
+            Self::Int(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[GgufValue]> {
+        match self {
+            Self::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// ggml tensor element types this reader can dequantize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GgmlType {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q8_0,
+    Q4K,
+    Q6K,
+    BF16,
+}
+
+impl GgmlType {
+    fn from_id(id: u32) -> Result<Self, String> {
+        Ok(match id {
+            0 => Self::F32,
+            1 => Self::F16,
+            2 => Self::Q4_0,
+            3 => Self::Q4_1,
+            8 => Self::Q8_0,
+            12 => Self::Q4K,
+            14 => Self::Q6K,
+            30 => Self::BF16,
+            other => {
+                return Err(format!(
+                    "unsupported GGUF tensor type {other} (use an F16, Q8_0, Q4_0, Q4_1, Q4_K_M or Q6_K file)"
+                ))
+            }
+        })
+    }
+
+    /// (elements per block, bytes per block)
+    fn block(self) -> (usize, usize) {
+        match self {
+            Self::F32 => (1, 4),
+            Self::F16 | Self::BF16 => (1, 2),
+            Self::Q4_0 => (QK, 2 + QK / 2),
+            Self::Q4_1 => (QK, 4 + QK / 2),
+            Self::Q8_0 => (QK, 2 + QK),
+            Self::Q4K => (QK_K, 4 + 12 + QK_K / 2),
+            Self::Q6K => (QK_K, QK_K / 2 + QK_K / 4 + QK_K / 16 + 2),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::F32 => "F32",
+            Self::F16 => "F16",
+            Self::Q4_0 => "Q4_0",
+            Self::Q4_1 => "Q4_1",
+            Self::Q8_0 => "Q8_0",
+            Self::Q4K => "Q4_K",
+            Self::Q6K => "Q6_K",
+            Self::BF16 => "BF16",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GgufTensorInfo {
+    pub name: String,
+    /// Row-major shape (outermost first), i.e. ggml's `ne` reversed.
+    pub shape: Vec<usize>,
+    pub ggml_type: GgmlType,
+    offset: usize,
+}
+
+impl GgufTensorInfo {
+    pub fn numel(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+pub struct GgufFile {
+    pub metadata: BTreeMap<String, GgufValue>,
+    pub tensors: Vec<GgufTensorInfo>,
+    bytes: Vec<u8>,
+    data_start: usize,
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|e| *e <= self.bytes.len())
+            .ok_or_else(|| "GGUF header is truncated".to_string())?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        usize::try_from(self.u64()?).map_err(|_| "GGUF length overflows usize".to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let n = self.len()?;
+        String::from_utf8(self.take(n)?.to_vec()).map_err(|e| format!("GGUF string: {e}"))
+    }
+
+    fn value(&mut self, ty: u32, depth: usize) -> Result<GgufValue, String> {
+        let b = |c: &mut Self, n| c.take(n).map(|s| s.to_vec());
+        Ok(match ty {
+            0 => GgufValue::Int(b(self, 1)?[0] as i64),
+            1 => GgufValue::Int(b(self, 1)?[0] as i8 as i64),
+            2 => GgufValue::Int(u16::from_le_bytes(b(self, 2)?.try_into().unwrap()) as i64),
+            3 => GgufValue::Int(i16::from_le_bytes(b(self, 2)?.try_into().unwrap()) as i64),
+            4 => GgufValue::Int(self.u32()? as i64),
+            5 => GgufValue::Int(self.u32()? as i32 as i64),
+            6 => GgufValue::Float(f32::from_bits(self.u32()?) as f64),
+            7 => GgufValue::Bool(b(self, 1)?[0] != 0),
+            8 => GgufValue::Str(self.string()?),
+            9 => {
+                if depth >= MAX_ARRAY_DEPTH {
+                    return Err(format!(
+                        "GGUF arrays nested deeper than {MAX_ARRAY_DEPTH} levels"
+                    ));
+                }
+                let item_ty = self.u32()?;
+                let n = self.len()?;
+                let mut items = Vec::with_capacity(n.min(1 << 20));
+                for _ in 0..n {
+                    items.push(self.value(item_ty, depth + 1)?);
+                }
+                GgufValue::Array(items)
+            }
+            10 => GgufValue::Int(self.u64()? as i64),
+            11 => GgufValue::Int(self.u64()? as i64),
+            12 => GgufValue::Float(f64::from_bits(self.u64()?)),
+            other => return Err(format!("unknown GGUF metadata type {other}")),
+        })
+    }
+}
+
+impl GgufFile {
+    pub fn parse(bytes: Vec<u8>) -> Result<Self, String> {
+        let mut c = Cursor {
+            bytes: &bytes,
+            pos: 0,
+        };
+        if c.take(4)? != MAGIC {
+            return Err("not a GGUF file (bad magic)".to_string());
+        }
+        let version = c.u32()?;
+        if !(2..=3).contains(&version) {
+            return Err(format!("unsupported GGUF version {version} (need 2 or 3)"));
+        }
+        let n_tensors = c.len()?;
+        let n_kv = c.len()?;
+        let mut metadata = BTreeMap::new();
+        for _ in 0..n_kv {
+            let key = c.string()?;
+            let ty = c.u32()?;
+            metadata.insert(key, c.value(ty, 0)?);
+        }
+        let mut tensors = Vec::with_capacity(n_tensors.min(1 << 20));
+        for _ in 0..n_tensors {
+            let name = c.string()?;
+            let n_dims = c.u32()? as usize;
+            let mut ne = Vec::with_capacity(n_dims.min(1 << 20));
+            for _ in 0..n_dims {
+                ne.push(c.len()?);
+            }
+            let ggml_type = GgmlType::from_id(c.u32()?).map_err(|e| format!("{name}: {e}"))?;
+            let offset = c.len()?;
+            ne.reverse();
+            tensors.push(GgufTensorInfo {
+                name,
+                shape: ne,
+                ggml_type,
+                offset,
+            });
+        }
+        let alignment = metadata
+            .get("general.alignment")
+            .and_then(GgufValue::as_u64)
+            .map_or(DEFAULT_ALIGNMENT, |a| a as usize)
+            .max(1);
+        let data_start = c.pos.div_ceil(alignment) * alignment;
+        let file = Self {
+            metadata,
+            tensors,
+            bytes,
+            data_start,
+        };
+        for t in &file.tensors {
+            file.raw(t)?;
+        }
+        Ok(file)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+
+    fn raw(&self, info: &GgufTensorInfo) -> Result<&[u8], String> {
+        let (per_block, block_bytes) = info.ggml_type.block();
+        let numel = info
+            .shape
+            .iter()
+            .try_fold(1usize, |n, &d| n.checked_mul(d))
+            .ok_or_else(|| format!("{}: element count overflows usize", info.name))?;
+        if !numel.is_multiple_of(per_block) {
+            return Err(format!(
+                "{}: {} elements is not a whole number of {} blocks",
+                info.name,
+                numel,
+                info.ggml_type.name()
+            ));
+        }
+        let past_end = || format!("{}: tensor data runs past the end of the file", info.name);
+        let start = self
+            .data_start
+            .checked_add(info.offset)
+            .ok_or_else(past_end)?;
+        let end = (numel / per_block)
+            .checked_mul(block_bytes)
+            .and_then(|len| start.checked_add(len))
+            .ok_or_else(past_end)?;
+        self.bytes.get(start..end).ok_or_else(past_end)
+    }
+
+    /// Dequantize one tensor to `f32` (row-major, `info.shape`).
+    pub fn dequantize(&self, info: &GgufTensorInfo) -> Result<Vec<f32>, String> {
+        let raw = self.raw(info)?;
+        let mut out = Vec::with_capacity(info.numel());
+        dequantize_into(info.ggml_type, raw, &mut out);
+        Ok(out)
+    }
+}
+
+fn f16(bytes: &[u8]) -> f32 {
+    half::f16::from_le_bytes([bytes[0], bytes[1]]).to_f32()
+}
+
+fn dequantize_into(ty: GgmlType, raw: &[u8], out: &mut Vec<f32>) {
+    let (_, block_bytes) = ty.block();
+    match ty {
+        GgmlType::F32 => out.extend(
+            raw.chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap())),
+        ),
+        GgmlType::F16 => out.extend(raw.chunks_exact(2).map(f16)),
+        GgmlType::BF16 => out.extend(
+            raw.chunks_exact(2)
+                .map(|b| f32::from_bits((u16::from_le_bytes([b[0], b[1]]) as u32) << 16)),
+        ),
+        GgmlType::Q8_0 => {
+            for block in raw.chunks_exact(block_bytes) {
+                let d = f16(block);
+                out.extend(block[2..].iter().map(|&q| d * q as i8 as f32));
+            }
+        }
+        GgmlType::Q4_0 | GgmlType::Q4_1 => {
+            let with_min = ty == GgmlType::Q4_1;
+            for block in raw.chunks_exact(block_bytes) {
+                let d = f16(block);
+                let (m, qs) = if with_min {
+                    (f16(&block[2..]), &block[4..])
+                } else {
+                    (-8.0 * d, &block[2..])
+                };
+                out.extend(qs.iter().map(|&q| d * (q & 0x0F) as f32 + m));
+                out.extend(qs.iter().map(|&q| d * (q >> 4) as f32 + m));
+            }
+        }
+        GgmlType::Q4K => {
+            for block in raw.chunks_exact(block_bytes) {
+                let d = f16(block);
+                let dmin = f16(&block[2..]);
+                let scales = &block[4..16];
+                let qs = &block[16..];
+                for (j, q) in qs.chunks_exact(32).enumerate() {
+                    let (sc1, m1) = scale_min_k4(2 * j, scales);
+                    let (sc2, m2) = scale_min_k4(2 * j + 1, scales);
+                    let (d1, m1) = (d * sc1 as f32, dmin * m1 as f32);
+                    let (d2, m2) = (d * sc2 as f32, dmin * m2 as f32);
+                    out.extend(q.iter().map(|&b| d1 * (b & 0x0F) as f32 - m1));
+                    out.extend(q.iter().map(|&b| d2 * (b >> 4) as f32 - m2));
+                }
+            }
+        }
+        GgmlType::Q6K => {
+            for block in raw.chunks_exact(block_bytes) {
+                let (ql, rest) = block.split_at(QK_K / 2);
+                let (qh, rest) = rest.split_at(QK_K / 4);
+                let (sc, d) = rest.split_at(QK_K / 16);
+                let d = f16(d);
+                for half in 0..2 {
+                    let ql = &ql[64 * half..];
+                    let qh = &qh[32 * half..];
+                    let sc = &sc[8 * half..];
+                    let mut y = [0f32; 128];
+                    for l in 0..32 {
+                        let is = l / 16;
+                        let q1 = ((ql[l] & 0x0F) | ((qh[l] & 3) << 4)) as i32 - 32;
+                        let q2 = ((ql[l + 32] & 0x0F) | (((qh[l] >> 2) & 3) << 4)) as i32 - 32;
+                        let q3 = ((ql[l] >> 4) | (((qh[l] >> 4) & 3) << 4)) as i32 - 32;
+                        let q4 = ((ql[l + 32] >> 4) | (((qh[l] >> 6) & 3) << 4)) as i32 - 32;
+                        y[l] = d * (sc[is] as i8 as f32) * q1 as f32;
+                        y[l + 32] = d * (sc[is + 2] as i8 as f32) * q2 as f32;
+                        y[l + 64] = d * (sc[is + 4] as i8 as f32) * q3 as f32;
+                        y[l + 96] = d * (sc[is + 6] as i8 as f32) * q4 as f32;
+                    }
+                    out.extend_from_slice(&y);
+                }
+            }
+        }
+    }
+}
+
+/// 6-bit scale / min pair `j` of a Q4_K super-block (ggml's `get_scale_min_k4`).
+fn scale_min_k4(j: usize, q: &[u8]) -> (u8, u8) {
+    if j < 4 {
+        (q[j] & 63, q[j + 4] & 63)
+    } else {
+        (
+            (q[j + 4] & 0x0F) | ((q[j - 4] >> 6) << 4),
+            (q[j + 4] >> 4) | ((q[j] >> 6) << 4),
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn put_str(out: &mut Vec<u8>, s: &str) {
+        out.extend((s.len() as u64).to_le_bytes());
+        out.extend(s.as_bytes());
+    }
+
+    /// Minimal GGUF v3: `(key, u32 value)` metadata and `(name, shape, type id, raw bytes)` tensors.
+    pub(crate) fn build(
+        meta: &[(&str, u32)],
+        tensors: &[(&str, &[usize], u32, Vec<u8>)],
+    ) -> Vec<u8> {
+        let mut out = b"GGUF".to_vec();
+        out.extend(3u32.to_le_bytes());
+        out.extend((tensors.len() as u64).to_le_bytes());
+        out.extend((meta.len() as u64).to_le_bytes());
+        for (key, value) in meta {
+            put_str(&mut out, key);
+            out.extend(4u32.to_le_bytes());
+            out.extend(value.to_le_bytes());
+        }
+        let mut offset = 0usize;
+        for (name, shape, ty, data) in tensors {
+            put_str(&mut out, name);
+            out.extend((shape.len() as u32).to_le_bytes());
+            for d in shape.iter().rev() {
+                out.extend((*d as u64).to_le_bytes());
+            }
+            out.extend(ty.to_le_bytes());
+            out.extend((offset as u64).to_le_bytes());
+            offset += data.len().div_ceil(32) * 32;
+        }
+        out.resize(out.len().div_ceil(32) * 32, 0);
+        for (_, _, _, data) in tensors {
+            let start = out.len();
+            out.extend(data);
+            out.resize(start + data.len().div_ceil(32) * 32, 0);
+        }
+        out
+    }
+
+    #[test]
+    fn parses_metadata_and_dequantizes() {
+        let f32s: Vec<u8> = [1.5f32, -2.0, 0.25, 4.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        // Q8_0: d = 0.5, q = 0..32
+        let mut q8 = half::f16::from_f32(0.5).to_le_bytes().to_vec();
+        q8.extend((0..32).map(|i| i as i8 as u8));
+        // Q4_0: d = 2, low nibbles 0..16 then high nibbles 15 → values (q - 8) * 2
+        let mut q4 = half::f16::from_f32(2.0).to_le_bytes().to_vec();
+        q4.extend((0..16u8).map(|i| i | 0xF0));
+        let bytes = build(
+            &[("llama.block_count", 2)],
+            &[
+                ("a", &[2, 2], 0, f32s),
+                ("b", &[32], 8, q8),
+                ("c", &[32], 2, q4),
+            ],
+        );
+        let file = GgufFile::parse(bytes).unwrap();
+        assert_eq!(file.get("llama.block_count").unwrap().as_u64(), Some(2));
+        assert_eq!(file.tensors[0].shape, vec![2, 2]);
+        assert_eq!(
+            file.dequantize(&file.tensors[0]).unwrap(),
+            vec![1.5, -2.0, 0.25, 4.0]
+        );
+        let b = file.dequantize(&file.tensors[1]).unwrap();
+        assert_eq!(b[0], 0.0);
+        assert_eq!(b[31], 15.5);
+        let c = file.dequantize(&file.tensors[2]).unwrap();
+        assert_eq!(c[0], -16.0);
+        assert_eq!(c[15], 14.0);
+        assert!(c[16..].iter().all(|v| *v == 14.0));
+    }
+
+    #[test]
+    fn rejects_truncated_data_and_unknown_types() {
+        let mut bytes = build(&[], &[("a", &[8], 0, vec![0; 32])]);
+        bytes.truncate(bytes.len() - 4);
+        assert!(GgufFile::parse(bytes).is_err());
+        let bytes = build(&[], &[("a", &[32], 99, vec![0; 32])]);
+        assert!(GgufFile::parse(bytes).is_err_and(|e| e.contains("unsupported")));
+        assert!(GgufFile::parse(b"GGML....".to_vec()).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_and_truncated_headers() {
+        // Claims u64::MAX tensors but ends right after the counts.
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend(3u32.to_le_bytes());
+        bytes.extend(u64::MAX.to_le_bytes());
+        bytes.extend(0u64.to_le_bytes());
+        assert!(GgufFile::parse(bytes).is_err());
+
+        // One tensor with u32::MAX dimensions, cut off after the first.
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend(3u32.to_le_bytes());
+        bytes.extend(1u64.to_le_bytes());
+        bytes.extend(0u64.to_le_bytes());
+        put_str(&mut bytes, "a");
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend(8u64.to_le_bytes());
+        assert!(GgufFile::parse(bytes).is_err());
+
+        // Shape product and offset that overflow usize must not panic.
+        let mut bytes = build(&[], &[("a", &[1 << 40, 1 << 40], 0, vec![0; 32])]);
+        assert!(GgufFile::parse(bytes.clone()).is_err_and(|e| e.contains("overflows")));
+        bytes = build(&[], &[("a", &[8], 0, vec![0; 32])]);
+        // magic, version, counts, name, n_dims, ne[0], type id, then the offset
+        let offset_at = 4 + 4 + 8 + 8 + (8 + 1) + 4 + 8 + 4;
+        bytes[offset_at..offset_at + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(GgufFile::parse(bytes).is_err_and(|e| e.contains("past the end")));
+    }
+
+    #[test]
+    fn rejects_deeply_nested_arrays() {
+        // One key whose value is `depth` arrays of one array each, ending in a u32.
+        let header = |depth: usize| {
+            let mut bytes = b"GGUF".to_vec();
+            bytes.extend(3u32.to_le_bytes());
+            bytes.extend(0u64.to_le_bytes());
+            bytes.extend(1u64.to_le_bytes());
+            put_str(&mut bytes, "k");
+            bytes.extend(9u32.to_le_bytes());
+            for level in 1..=depth {
+                bytes.extend((if level < depth { 9u32 } else { 4 }).to_le_bytes());
+                bytes.extend(1u64.to_le_bytes());
+            }
+            bytes.extend(7u32.to_le_bytes());
+            bytes
+        };
+        let file = GgufFile::parse(header(2)).unwrap();
+        let inner = file.get("k").unwrap().as_array().unwrap()[0]
+            .as_array()
+            .unwrap();
+        assert_eq!(inner[0].as_u64(), Some(7));
+        assert!(GgufFile::parse(header(100_000)).is_err_and(|e| e.contains("nested")));
+    }
+}
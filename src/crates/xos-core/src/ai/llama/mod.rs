@@ -0,0 +1,394 @@
+//! Local chat inference for Llama-family decoders (Llama 2/3, Mistral, TinyLlama, SmolLM) on Burn:
+//! NdArray on the CPU or WGPU on the GPU.
+//!
+//! Weights come from a GGUF file (F32 / F16 / BF16 / Q8_0 / Q4_0 / Q4_1 / Q4_K / Q6_K, dequantized
+//! on load) or a Hugging Face safetensors folder, either given as a path or pulled by registry name
+//! (`xos models list`). Generation keeps a KV cache across calls, so a chat that grows turn by turn
+//! only runs the new tokens.
+
+mod config;
+mod gguf;
+mod model;
+mod sampling;
+mod template;
+mod tokenizer;
+mod weights;
+
+use std::path::{Path, PathBuf};
+
+use burn::backend::{ndarray::NdArrayDevice, wgpu::WgpuDevice, NdArray, Wgpu};
+
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+use model::{LlamaNet, LlamaRuntime};
+use tokenizer::Tokenizer;
+
+pub use config::LlamaConfig;
+pub use sampling::SamplingOptions;
+pub use template::{ChatMessage, ChatTemplate};
+
+/// Registry model used when none is named.
+pub const DEFAULT_MODEL: &str = "smollm2-135m-instruct";
+
+/// Values kept per parameter for inspection (`LlamaParameter::values`).
+const PARAMETER_HEAD: usize = 128;
+
+/// Tokens the repetition penalty looks back over.
+const REPEAT_WINDOW: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LlamaDevice {
+    #[default]
+    Cpu,
+    Gpu,
+}
+
+impl LlamaDevice {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" | "ndarray" => Some(Self::Cpu),
+            "gpu" | "wgpu" => Some(Self::Gpu),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub max_tokens: usize,
+    pub sampling: SamplingOptions,
+    /// Generation ends before any of these strings; they are not part of the returned text.
+    pub stop: Vec<String>,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            max_tokens: 256,
+            sampling: SamplingOptions::default(),
+            stop: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinishReason {
+    /// End-of-sequence / end-of-turn token or a stop string.
+    Stop,
+    /// `max_tokens` or the context length was reached.
+    Length,
+    /// The token callback asked to stop.
+    Cancelled,
+}
+
+impl FinishReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stop => "stop",
+            Self::Length => "length",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Generation {
+    pub text: String,
+    /// Generated token ids (without the end-of-turn token).
+    pub tokens: Vec<u32>,
+    pub prompt_tokens: usize,
+    pub finish_reason: FinishReason,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlamaParameter {
+    /// Hugging Face name, e.g. `model.layers.0.self_attn.q_proj.weight`.
+    pub name: String,
+    pub shape: Vec<usize>,
+    /// Storage type in the checkpoint; inference always runs in `f32`.
+    pub dtype: String,
+    /// The first values (row-major), widened to `f32`.
+    pub values: Vec<f32>,
+    pub stats: Option<TensorDebugStats>,
+}
+
+pub struct LlamaModel {
+    config: LlamaConfig,
+    tokenizer: Tokenizer,
+    template: ChatTemplate,
+    net: Box<dyn LlamaNet>,
+    parameters: Vec<LlamaParameter>,
+    weights_files: Vec<PathBuf>,
+    /// Token ids whose keys / values the net currently caches.
+    context: Vec<u32>,
+    /// End-of-sequence ids plus the template's end-of-turn token.
+    stop_ids: Vec<u32>,
+}
+
+impl LlamaModel {
+    /// `source` is a registry name (downloaded on first use) or a path to a `.gguf` file or a
+    /// safetensors folder with `config.json` and `tokenizer.json`.
+    pub fn load(source: &str, device: LlamaDevice) -> Result<Self, String> {
+        let path = Path::new(source);
+        let path = if path.exists() {
+            path.to_path_buf()
+        } else {
+            crate::ai::models::ensure(source)?
+        };
+        let mut ckpt = weights::load_checkpoint(&path)?;
+        let tokenizer = match ckpt.spm.take() {
+            Some(spm) => Tokenizer::Spm(spm),
+            None => {
+                let dir = if path.is_dir() {
+                    path.as_path()
+                } else {
+                    path.parent().unwrap_or(Path::new("."))
+                };
+                let file = dir.join("tokenizer.json");
+                if !file.is_file() {
+                    return Err(format!(
+                        "{} not found (needed for checkpoints without a SentencePiece vocabulary)",
+                        file.display()
+                    ));
+                }
+                Tokenizer::from_file(&file)?
+            }
+        };
+        let template = ChatTemplate::detect(ckpt.chat_template.as_deref(), |t| {
+            tokenizer.token_id(t).is_some()
+        });
+        let parameters = ckpt
+            .tensors
+            .iter()
+            .map(|(name, t)| LlamaParameter {
+                name: name.clone(),
+                shape: t.shape.clone(),
+                dtype: t.dtype.clone(),
+                values: t.data.iter().take(PARAMETER_HEAD).copied().collect(),
+                stats: model::stats(&t.data),
+            })
+            .collect();
+        let config = ckpt.config.clone();
+        let weights_files = ckpt.files.clone();
+        let net: Box<dyn LlamaNet> = match device {
+            LlamaDevice::Cpu => Box::new(LlamaRuntime::<NdArray>::load(
+                &mut ckpt,
+                NdArrayDevice::Cpu,
+            )?),
+            LlamaDevice::Gpu => Box::new(LlamaRuntime::<Wgpu>::load(
+                &mut ckpt,
+                WgpuDevice::default(),
+            )?),
+        };
+        let mut model = Self {
+            config,
+            tokenizer,
+            template,
+            net,
+            parameters,
+            weights_files,
+            context: Vec::new(),
+            stop_ids: Vec::new(),
+        };
+        model.set_template(template);
+        Ok(model)
+    }
+
+    pub fn config(&self) -> &LlamaConfig {
+        &self.config
+    }
+
+    pub fn template(&self) -> ChatTemplate {
+        self.template
+    }
+
+    /// Override the detected chat layout.
+    pub fn set_template(&mut self, template: ChatTemplate) {
+        self.template = template;
+        self.stop_ids = self.config.eos_token_ids.clone();
+        if let Some(id) = self.tokenizer.token_id(template.end_of_turn()) {
+            if !self.stop_ids.contains(&id) {
+                self.stop_ids.push(id);
+            }
+        }
+    }
+
+    pub fn parameters(&self) -> &[LlamaParameter] {
+        &self.parameters
+    }
+
+    pub fn weights_files(&self) -> &[PathBuf] {
+        &self.weights_files
+    }
+
+    /// Longest prompt + reply, in tokens.
+    pub fn context_len(&self) -> usize {
+        self.net.context_len()
+    }
+
+    pub fn tokenize(&self, text: &str, add_bos: bool) -> Result<Vec<u32>, String> {
+        self.tokenizer.encode(text, add_bos)
+    }
+
+    pub fn detokenize(&self, ids: &[u32]) -> Result<String, String> {
+        self.tokenizer.decode(ids)
+    }
+
+    /// Logits for every position of `ids`, `[ids.len(), vocab_size]` row-major. Does not touch the
+    /// generation cache.
+    pub fn forward(&self, ids: &[u32]) -> Result<Vec<f32>, String> {
+        self.net.forward(ids)
+    }
+
+    /// The embedding, each decoder block's output and the logits for `ids`.
+    pub fn forward_layer_by_layer(&self, ids: &[u32]) -> Result<Vec<ActivationStep>, String> {
+        self.net.forward_layer_by_layer(ids)
+    }
+
+    /// Forget the cached conversation (the next call re-reads its whole prompt).
+    pub fn reset(&mut self) {
+        self.net.truncate(0);
+        self.context.clear();
+    }
+
+    /// Continue raw `prompt` text (BOS is prepended). `on_token` receives the reply as it grows,
+    /// a piece at a time; returning `false` stops generation.
+    pub fn generate(
+        &mut self,
+        prompt: &str,
+        options: &GenerateOptions,
+        on_token: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Generation, String> {
+        let ids = self.tokenizer.encode(prompt, true)?;
+        self.generate_ids(ids, options, on_token)
+    }
+
+    /// Reply to a conversation as the assistant, using the checkpoint's chat template.
+    pub fn chat(
+        &mut self,
+        messages: &[ChatMessage],
+        options: &GenerateOptions,
+        on_token: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Generation, String> {
+        let prompt = self.template.render(messages, true);
+        let ids = self.tokenizer.encode(&prompt, self.template.add_bos())?;
+        self.generate_ids(ids, options, on_token)
+    }
+
+    fn generate_ids(
+        &mut self,
+        prompt: Vec<u32>,
+        options: &GenerateOptions,
+        on_token: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Generation, String> {
+        if prompt.is_empty() {
+            return Err("prompt is empty".to_string());
+        }
+        let context_len = self.net.context_len();
+        if prompt.len() >= context_len {
+            return Err(format!(
+                "prompt is {} tokens; the model's context is {context_len}",
+                prompt.len()
+            ));
+        }
+        // Reuse the cached prefix; at least one prompt token must run to produce logits.
+        let shared = self
+            .context
+            .iter()
+            .zip(&prompt)
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(prompt.len() - 1);
+        self.net.truncate(shared);
+        self.context.truncate(shared);
+        let mut logits = self.net.next_logits(&prompt[shared..])?;
+        self.context = prompt.clone();
+
+        let mut sampler = sampling::Sampler::new(options.sampling.clone());
+        let mut tokens = Vec::new();
+        let mut emitted = 0;
+        let finish_reason = loop {
+            if tokens.len() >= options.max_tokens {
+                break FinishReason::Length;
+            }
+            let window = &self.context[self.context.len().saturating_sub(REPEAT_WINDOW)..];
+            let id = sampler.sample(&mut logits, window);
+            if self.stop_ids.contains(&id) {
+                break FinishReason::Stop;
+            }
+            tokens.push(id);
+            let text = self.tokenizer.decode(&tokens)?;
+            if stop_position(&text, &options.stop).is_some() {
+                break FinishReason::Stop;
+            }
+            let ready = streamable_len(&text, &options.stop);
+            if ready > emitted {
+                if !on_token(&text[emitted..ready]) {
+                    break FinishReason::Cancelled;
+                }
+                emitted = ready;
+            }
+            if self.context.len() + 1 >= context_len {
+                break FinishReason::Length;
+            }
+            logits = self.net.next_logits(&[id])?;
+            self.context.push(id);
+        };
+
+        let mut text = self.tokenizer.decode(&tokens)?;
+        if let Some(at) = stop_position(&text, &options.stop) {
+            text.truncate(at);
+        }
+        if finish_reason != FinishReason::Cancelled && text.len() > emitted {
+            if let Some(rest) = text.get(emitted..) {
+                on_token(rest);
+            }
+        }
+        Ok(Generation {
+            text,
+            tokens,
+            prompt_tokens: prompt.len(),
+            finish_reason,
+        })
+    }
+}
+
+fn stop_position(text: &str, stops: &[String]) -> Option<usize> {
+    stops
+        .iter()
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| text.find(s.as_str()))
+        .min()
+}
+
+/// Length of `text` safe to stream: holds back a trailing U+FFFD (a multi-byte character split
+/// across tokens) and any suffix that could be the start of a stop string.
+fn streamable_len(text: &str, stops: &[String]) -> usize {
+    let text_end = text.trim_end_matches('\u{FFFD}').len();
+    let held = stops
+        .iter()
+        .flat_map(|stop| {
+            stop.char_indices()
+                .skip(1)
+                .map(|(i, _)| &stop[..i])
+                .filter(|prefix| text[..text_end].ends_with(prefix))
+                .map(|prefix| prefix.len())
+        })
+        .max()
+        .unwrap_or(0);
+    text_end - held
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_holds_back_partial_characters_and_stop_strings() {
+        let stops = vec!["\nUser:".to_string()];
+        assert_eq!(streamable_len("Hello", &stops), 5);
+        assert_eq!(streamable_len("Hello\nUs", &stops), 5);
+        assert_eq!(streamable_len("caf\u{FFFD}", &stops), 3);
+        assert_eq!(stop_position("Hi\nUser: more", &stops), Some(2));
+        assert_eq!(stop_position("Hi", &[String::new()]), None);
+    }
+}
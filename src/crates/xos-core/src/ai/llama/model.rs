@@ -0,0 +1,547 @@
+//! The Llama decoder in Burn: RMSNorm, rotary attention with grouped-query KV heads and a KV cache,
+//! and a SwiGLU MLP. Generic over the backend so the same code runs on NdArray (CPU) and WGPU.
+
+use burn::{
+    module::{Module, Param},
+    tensor::{
+        activation::{silu, softmax},
+        backend::Backend,
+        module::embedding,
+        Int, Tensor, TensorData,
+    },
+};
+
+use super::config::LlamaConfig;
+use super::weights::{Checkpoint, WeightTensor};
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+
+/// Rotary tables are built up front for this many positions at most (Llama 3 advertises 128k).
+const MAX_CONTEXT: usize = 8192;
+
+#[derive(Module, Debug)]
+struct Linear<B: Backend> {
+    /// `[in, out]` (the transpose of the checkpoint's `[out, in]`).
+    weight: Param<Tensor<B, 2>>,
+}
+
+impl<B: Backend> Linear<B> {
+    fn load(t: WeightTensor, device: &B::Device) -> Result<Self, String> {
+        let [rows, cols] = dims2(&t)?;
+        let mut data = vec![0.0; t.data.len()];
+        for r in 0..rows {
+            for c in 0..cols {
+                data[c * rows + r] = t.data[r * cols + c];
+            }
+        }
+        Ok(Self {
+            weight: Param::from_tensor(Tensor::from_data(
+                TensorData::new(data, [cols, rows]),
+                device,
+            )),
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        x.matmul(self.weight.val().unsqueeze())
+    }
+}
+
+#[derive(Module, Debug)]
+struct RmsNorm<B: Backend> {
+    gamma: Param<Tensor<B, 1>>,
+    eps: f64,
+}
+
+impl<B: Backend> RmsNorm<B> {
+    fn load(t: WeightTensor, eps: f64, device: &B::Device) -> Self {
+        let n = t.data.len();
+        Self {
+            gamma: Param::from_tensor(Tensor::from_data(TensorData::new(t.data, [n]), device)),
+            eps,
+        }
+    }
+
+    fn forward(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let rms = (x.clone().powf_scalar(2.0).mean_dim(2) + self.eps).sqrt();
+        x / rms * self.gamma.val().unsqueeze()
+    }
+}
+
+#[derive(Module, Debug)]
+struct Attention<B: Backend> {
+    q_proj: Linear<B>,
+    k_proj: Linear<B>,
+    v_proj: Linear<B>,
+    o_proj: Linear<B>,
+    num_heads: usize,
+    num_kv_heads: usize,
+    head_dim: usize,
+}
+
+type Kv<B> = Option<(Tensor<B, 4>, Tensor<B, 4>)>;
+
+impl<B: Backend> Attention<B> {
+    /// `x` holds positions `start..start + seq` of the sequence; `cache` the keys / values before.
+    fn forward(
+        &self,
+        x: Tensor<B, 3>,
+        rope: &Rope<B>,
+        start: usize,
+        cache: &mut Kv<B>,
+    ) -> Tensor<B, 3> {
+        let [batch, seq, hidden] = x.dims();
+        let heads =
+            |t: Tensor<B, 3>, n: usize| t.reshape([batch, seq, n, self.head_dim]).swap_dims(1, 2);
+        let q = rope.apply(heads(self.q_proj.forward(x.clone()), self.num_heads), start);
+        let k = rope.apply(
+            heads(self.k_proj.forward(x.clone()), self.num_kv_heads),
+            start,
+        );
+        let v = heads(self.v_proj.forward(x), self.num_kv_heads);
+        let (k, v) = match cache.take() {
+            Some((pk, pv)) => (Tensor::cat(vec![pk, k], 2), Tensor::cat(vec![pv, v], 2)),
+            None => (k, v),
+        };
+        *cache = Some((k.clone(), v.clone()));
+
+        let total = start + seq;
+        let groups = self.num_heads / self.num_kv_heads;
+        let expand = |t: Tensor<B, 4>| {
+            if groups == 1 {
+                return t;
+            }
+            t.reshape([batch, self.num_kv_heads, 1, total, self.head_dim])
+                .repeat_dim(2, groups)
+                .reshape([batch, self.num_heads, total, self.head_dim])
+        };
+        let scale = (self.head_dim as f64).sqrt().recip();
+        let mut scores = q.matmul(expand(k).swap_dims(2, 3)) * scale;
+        if seq > 1 {
+            let mut mask = vec![0.0f32; seq * total];
+            for i in 0..seq {
+                for j in start + i + 1..total {
+                    mask[i * total + j] = f32::NEG_INFINITY;
+                }
+            }
+            let mask =
+                Tensor::<B, 2>::from_data(TensorData::new(mask, [seq, total]), &scores.device());
+            scores = scores + mask.unsqueeze();
+        }
+        let out = softmax(scores, 3).matmul(expand(v));
+        self.o_proj
+            .forward(out.swap_dims(1, 2).reshape([batch, seq, hidden]))
+    }
+}
+
+#[derive(Module, Debug)]
+struct Mlp<B: Backend> {
+    gate_proj: Linear<B>,
+    up_proj: Linear<B>,
+    down_proj: Linear<B>,
+}
+
+impl<B: Backend> Mlp<B> {
+    fn forward(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let gate = silu(self.gate_proj.forward(x.clone()));
+        self.down_proj.forward(gate * self.up_proj.forward(x))
+    }
+}
+
+#[derive(Module, Debug)]
+struct Block<B: Backend> {
+    input_layernorm: RmsNorm<B>,
+    self_attn: Attention<B>,
+    post_attention_layernorm: RmsNorm<B>,
+    mlp: Mlp<B>,
+}
+
+impl<B: Backend> Block<B> {
+    fn forward(
+        &self,
+        x: Tensor<B, 3>,
+        rope: &Rope<B>,
+        start: usize,
+        cache: &mut Kv<B>,
+    ) -> Tensor<B, 3> {
+        let h = x.clone()
+            + self
+                .self_attn
+                .forward(self.input_layernorm.forward(x), rope, start, cache);
+        h.clone() + self.mlp.forward(self.post_attention_layernorm.forward(h))
+    }
+}
+
+#[derive(Module, Debug)]
+pub struct Llama<B: Backend> {
+    embed_tokens: Param<Tensor<B, 2>>,
+    layers: Vec<Block<B>>,
+    norm: RmsNorm<B>,
+    /// `None` when the output projection is tied to `embed_tokens`.
+    lm_head: Option<Linear<B>>,
+}
+
+impl<B: Backend> Llama<B> {
+    /// Move the checkpoint's tensors onto `device` (they are removed from `ckpt` as they go).
+    fn load(ckpt: &mut Checkpoint, device: &B::Device) -> Result<Self, String> {
+        let cfg = ckpt.config.clone();
+        let embed = ckpt.take("model.embed_tokens.weight")?;
+        let [vocab, hidden] = dims2(&embed)?;
+        if vocab != cfg.vocab_size || hidden != cfg.hidden_size {
+            return Err(format!(
+                "embed_tokens is {vocab}x{hidden}, config says {}x{}",
+                cfg.vocab_size, cfg.hidden_size
+            ));
+        }
+        let embed_tokens = Param::from_tensor(Tensor::from_data(
+            TensorData::new(embed.data, [vocab, hidden]),
+            device,
+        ));
+        let mut layers = Vec::with_capacity(cfg.num_layers);
+        for i in 0..cfg.num_layers {
+            let p = format!("model.layers.{i}");
+            let mut linear =
+                |name: &str| Linear::load(ckpt.take(&format!("{p}.{name}.weight"))?, device);
+            let self_attn = Attention {
+                q_proj: linear("self_attn.q_proj")?,
+                k_proj: linear("self_attn.k_proj")?,
+                v_proj: linear("self_attn.v_proj")?,
+                o_proj: linear("self_attn.o_proj")?,
+                num_heads: cfg.num_heads,
+                num_kv_heads: cfg.num_kv_heads,
+                head_dim: cfg.head_dim(),
+            };
+            let mlp = Mlp {
+                gate_proj: linear("mlp.gate_proj")?,
+                up_proj: linear("mlp.up_proj")?,
+                down_proj: linear("mlp.down_proj")?,
+            };
+            let mut norm = |name: &str| -> Result<RmsNorm<B>, String> {
+                Ok(RmsNorm::load(
+                    ckpt.take(&format!("{p}.{name}.weight"))?,
+                    cfg.rms_norm_eps,
+                    device,
+                ))
+            };
+            layers.push(Block {
+                input_layernorm: norm("input_layernorm")?,
+                self_attn,
+                post_attention_layernorm: norm("post_attention_layernorm")?,
+                mlp,
+            });
+        }
+        let norm = RmsNorm::load(ckpt.take("model.norm.weight")?, cfg.rms_norm_eps, device);
+        let lm_head = match ckpt.tensors.remove("lm_head.weight") {
+            Some(t) if !cfg.tie_word_embeddings => Some(Linear::load(t, device)?),
+            Some(_) => None,
+            None if cfg.tie_word_embeddings => None,
+            None => return Err("checkpoint has no lm_head.weight".to_string()),
+        };
+        Ok(Self {
+            embed_tokens,
+            layers,
+            norm,
+            lm_head,
+        })
+    }
+
+    fn embed(&self, ids: &[u32], device: &B::Device) -> Tensor<B, 3> {
+        let ids: Vec<i64> = ids.iter().map(|&id| id as i64).collect();
+        let n = ids.len();
+        let ids = Tensor::<B, 2, Int>::from_data(TensorData::new(ids, [1, n]), device);
+        embedding(self.embed_tokens.val(), ids)
+    }
+
+    fn head(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let x = self.norm.forward(x);
+        match &self.lm_head {
+            Some(head) => head.forward(x),
+            None => x.matmul(self.embed_tokens.val().transpose().unsqueeze()),
+        }
+    }
+}
+
+/// HF-style rotary embedding (`rotate_half`): the first and second half of each head form the pairs.
+struct Rope<B: Backend> {
+    cos: Tensor<B, 2>,
+    sin: Tensor<B, 2>,
+}
+
+impl<B: Backend> Rope<B> {
+    fn new(head_dim: usize, theta: f64, positions: usize, device: &B::Device) -> Self {
+        let half = head_dim / 2;
+        let mut cos = Vec::with_capacity(positions * head_dim);
+        let mut sin = Vec::with_capacity(positions * head_dim);
+        for p in 0..positions {
+            for i in 0..head_dim {
+                let freq = theta.powf(-2.0 * (i % half) as f64 / head_dim as f64);
+                let angle = p as f64 * freq;
+                cos.push(angle.cos() as f32);
+                sin.push(angle.sin() as f32);
+            }
+        }
+        let table = |v| Tensor::from_data(TensorData::new(v, [positions, head_dim]), device);
+        Self {
+            cos: table(cos),
+            sin: table(sin),
+        }
+    }
+
+    /// `x`: `[batch, heads, seq, head_dim]` at positions `start..start + seq`.
+    fn apply(&self, x: Tensor<B, 4>, start: usize) -> Tensor<B, 4> {
+        let [_, _, seq, dim] = x.dims();
+        let half = dim / 2;
+        let cos = self.cos.clone().narrow(0, start, seq).unsqueeze::<4>();
+        let sin = self.sin.clone().narrow(0, start, seq).unsqueeze::<4>();
+        let rotated = Tensor::cat(
+            vec![
+                x.clone().narrow(3, half, half).neg(),
+                x.clone().narrow(3, 0, half),
+            ],
+            3,
+        );
+        x * cos + rotated * sin
+    }
+}
+
+/// Backend-erased interface [`super::LlamaModel`] drives.
+pub trait LlamaNet {
+    /// Run `ids` after the cached prefix and return the logits of the last position.
+    fn next_logits(&mut self, ids: &[u32]) -> Result<Vec<f32>, String>;
+    /// Drop cached positions from `len` on.
+    fn truncate(&mut self, len: usize);
+    /// Longest sequence the rotary tables cover.
+    fn context_len(&self) -> usize;
+    /// Uncached forward pass: logits for every position, `[seq, vocab]` row-major.
+    fn forward(&self, ids: &[u32]) -> Result<Vec<f32>, String>;
+    /// Uncached forward pass recording the embedding, every block's output and the logits.
+    fn forward_layer_by_layer(&self, ids: &[u32]) -> Result<Vec<ActivationStep>, String>;
+}
+
+pub struct LlamaRuntime<B: Backend> {
+    model: Llama<B>,
+    rope: Rope<B>,
+    cache: Vec<Kv<B>>,
+    cached: usize,
+    device: B::Device,
+}
+
+impl<B: Backend> LlamaRuntime<B> {
+    pub fn load(ckpt: &mut Checkpoint, device: B::Device) -> Result<Self, String> {
+        let cfg: &LlamaConfig = &ckpt.config;
+        let context = cfg.max_seq_len.clamp(1, MAX_CONTEXT);
+        let rope = Rope::new(cfg.head_dim(), cfg.rope_theta, context, &device);
+        let model = Llama::load(ckpt, &device)?;
+        Ok(Self {
+            cache: vec![None; model.layers.len()],
+            model,
+            rope,
+            cached: 0,
+            device,
+        })
+    }
+
+    fn check_len(&self, start: usize, ids: &[u32]) -> Result<(), String> {
+        if ids.is_empty() {
+            return Err("no tokens to run".to_string());
+        }
+        let context = self.context_len();
+        if start + ids.len() > context {
+            return Err(format!(
+                "sequence of {} tokens exceeds the {context}-token context",
+                start + ids.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<B: Backend> LlamaNet for LlamaRuntime<B> {
+    fn next_logits(&mut self, ids: &[u32]) -> Result<Vec<f32>, String> {
+        self.check_len(self.cached, ids)?;
+        let mut x = self.model.embed(ids, &self.device);
+        for (block, cache) in self.model.layers.iter().zip(self.cache.iter_mut()) {
+            x = block.forward(x, &self.rope, self.cached, cache);
+        }
+        self.cached += ids.len();
+        let [_, seq, _] = x.dims();
+        to_host(self.model.head(x.narrow(1, seq - 1, 1)))
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len >= self.cached {
+            return;
+        }
+        for kv in self.cache.iter_mut() {
+            *kv = match kv.take() {
+                Some((k, v)) if len > 0 => Some((k.narrow(2, 0, len), v.narrow(2, 0, len))),
+                _ => None,
+            };
+        }
+        self.cached = len;
+    }
+
+    fn context_len(&self) -> usize {
+        self.rope.cos.dims()[0]
+    }
+
+    fn forward(&self, ids: &[u32]) -> Result<Vec<f32>, String> {
+        self.check_len(0, ids)?;
+        let mut x = self.model.embed(ids, &self.device);
+        for block in &self.model.layers {
+            x = block.forward(x, &self.rope, 0, &mut None);
+        }
+        to_host(self.model.head(x))
+    }
+
+    fn forward_layer_by_layer(&self, ids: &[u32]) -> Result<Vec<ActivationStep>, String> {
+        self.check_len(0, ids)?;
+        let mut steps = Vec::with_capacity(self.model.layers.len() + 2);
+        let mut x = self.model.embed(ids, &self.device);
+        steps.push(step("model.embed_tokens", &x)?);
+        for (i, block) in self.model.layers.iter().enumerate() {
+            x = block.forward(x, &self.rope, 0, &mut None);
+            steps.push(step(&format!("model.layers.{i}"), &x)?);
+        }
+        steps.push(step("lm_head", &self.model.head(x))?);
+        Ok(steps)
+    }
+}
+
+fn dims2(t: &WeightTensor) -> Result<[usize; 2], String> {
+    match t.shape[..] {
+        [rows, cols] => Ok([rows, cols]),
+        _ => Err(format!("expected a 2-D weight, got shape {:?}", t.shape)),
+    }
+}
+
+fn to_host<B: Backend, const D: usize>(t: Tensor<B, D>) -> Result<Vec<f32>, String> {
+    t.into_data()
+        .convert::<f32>()
+        .to_vec::<f32>()
+        .map_err(|e| format!("read back tensor: {e:?}"))
+}
+
+fn step<B: Backend>(name: &str, x: &Tensor<B, 3>) -> Result<ActivationStep, String> {
+    let values = to_host(x.clone())?;
+    Ok(ActivationStep {
+        name: Some(name.to_string()),
+        shape: x.dims().to_vec(),
+        dtype: "float32".to_string(),
+        full_stats: stats(&values),
+        values,
+        device_preflight: None,
+    })
+}
+
+pub(super) fn stats(values: &[f32]) -> Option<TensorDebugStats> {
+    let finite: Vec<f64> = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .map(|&v| v as f64)
+        .collect();
+    if finite.is_empty() {
+        return None;
+    }
+    let n = finite.len() as f64;
+    let mean = finite.iter().sum::<f64>() / n;
+    let var = finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    Some(TensorDebugStats {
+        mean: mean as f32,
+        std: var.sqrt() as f32,
+        min: finite.iter().copied().fold(f64::INFINITY, f64::min) as f32,
+        max: finite.iter().copied().fold(f64::NEG_INFINITY, f64::max) as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use burn::backend::{ndarray::NdArrayDevice, NdArray};
+
+    use super::*;
+
+    fn tiny_checkpoint() -> Checkpoint {
+        let config = LlamaConfig {
+            vocab_size: 11,
+            hidden_size: 8,
+            intermediate_size: 12,
+            num_layers: 2,
+            num_heads: 2,
+            num_kv_heads: 1,
+            rms_norm_eps: 1e-5,
+            rope_theta: 10_000.0,
+            max_seq_len: 16,
+            tie_word_embeddings: true,
+            eos_token_ids: vec![0],
+        };
+        let mut seed = 1u32;
+        let mut tensor = |shape: &[usize]| {
+            let data = (0..shape.iter().product::<usize>())
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+                })
+                .collect();
+            WeightTensor {
+                shape: shape.to_vec(),
+                dtype: "F32".to_string(),
+                data,
+            }
+        };
+        let mut tensors = BTreeMap::new();
+        tensors.insert("model.embed_tokens.weight".to_string(), tensor(&[11, 8]));
+        tensors.insert("model.norm.weight".to_string(), tensor(&[8]));
+        for i in 0..2 {
+            for (name, shape) in [
+                ("input_layernorm", &[8][..]),
+                ("post_attention_layernorm", &[8]),
+                ("self_attn.q_proj", &[8, 8]),
+                ("self_attn.k_proj", &[4, 8]),
+                ("self_attn.v_proj", &[4, 8]),
+                ("self_attn.o_proj", &[8, 8]),
+                ("mlp.gate_proj", &[12, 8]),
+                ("mlp.up_proj", &[12, 8]),
+                ("mlp.down_proj", &[8, 12]),
+            ] {
+                tensors.insert(format!("model.layers.{i}.{name}.weight"), tensor(shape));
+            }
+        }
+        Checkpoint {
+            config,
+            tensors,
+            files: Vec::new(),
+            spm: None,
+            chat_template: None,
+        }
+    }
+
+    #[test]
+    fn cached_decoding_matches_full_forward() {
+        let mut net =
+            LlamaRuntime::<NdArray>::load(&mut tiny_checkpoint(), NdArrayDevice::Cpu).unwrap();
+        let ids = [3, 1, 4, 1, 5, 9];
+        let full = net.forward(&ids).unwrap();
+        assert_eq!(full.len(), ids.len() * 11);
+
+        let mut last = net.next_logits(&ids[..4]).unwrap();
+        for &id in &ids[4..] {
+            last = net.next_logits(&[id]).unwrap();
+        }
+        let expected = &full[5 * 11..];
+        assert!(last.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-4));
+
+        net.truncate(2);
+        assert_eq!(net.cached, 2);
+        let again = net.next_logits(&ids[2..]).unwrap();
+        assert!(again
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| (a - b).abs() < 1e-4));
+        assert!(net.next_logits(&[0; 16]).is_err());
+
+        let steps = net.forward_layer_by_layer(&ids).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[3].shape, vec![1, 6, 11]);
+    }
+}
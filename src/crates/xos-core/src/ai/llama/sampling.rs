@@ -0,0 +1,151 @@
+//! Next-token selection from logits: repetition penalty, temperature, top-k and top-p (nucleus).
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone)]
+pub struct SamplingOptions {
+    /// `<= 0` picks the most likely token every step (greedy, deterministic).
+    pub temperature: f32,
+    /// Keep only the `k` most likely tokens; `0` disables.
+    pub top_k: usize,
+    /// Keep the smallest set of tokens whose probability sums to `top_p`; `1.0` disables.
+    pub top_p: f32,
+    /// Divide (or multiply, for negative logits) the logits of tokens already in the context;
+    /// `1.0` disables.
+    pub repetition_penalty: f32,
+    /// Fixed seed for reproducible sampling; `None` seeds from the OS.
+    pub seed: Option<u64>,
+}
+
+impl Default for SamplingOptions {
+    fn default() -> Self {
+        Self {
+            temperature: 0.7,
+            top_k: 40,
+            top_p: 0.9,
+            repetition_penalty: 1.1,
+            seed: None,
+        }
+    }
+}
+
+pub struct Sampler {
+    options: SamplingOptions,
+    rng: StdRng,
+}
+
+impl Sampler {
+    pub fn new(options: SamplingOptions) -> Self {
+        let rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self { options, rng }
+    }
+
+    /// Choose a token id from `logits`; `history` is the context the repetition penalty applies to.
+    pub fn sample(&mut self, logits: &mut [f32], history: &[u32]) -> u32 {
+        let penalty = self.options.repetition_penalty;
+        if penalty != 1.0 && penalty > 0.0 {
+            for &id in history {
+                if let Some(l) = logits.get_mut(id as usize) {
+                    *l = if *l > 0.0 { *l / penalty } else { *l * penalty };
+                }
+            }
+        }
+        if self.options.temperature <= 0.0 {
+            return argmax(logits);
+        }
+
+        let mut ranked: Vec<(u32, f32)> = logits
+            .iter()
+            .enumerate()
+            .map(|(i, &l)| (i as u32, l / self.options.temperature))
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+        if self.options.top_k > 0 {
+            ranked.truncate(self.options.top_k);
+        }
+        let max = ranked[0].1;
+        let mut total = 0.0;
+        for (_, l) in ranked.iter_mut() {
+            *l = (*l - max).exp();
+            total += *l;
+        }
+        let mut cumulative = 0.0;
+        let candidates = ranked.len();
+        let mut keep = candidates;
+        for (i, (_, p)) in ranked.iter_mut().enumerate() {
+            *p /= total;
+            cumulative += *p;
+            if cumulative >= self.options.top_p && keep == candidates {
+                keep = i + 1;
+            }
+        }
+        ranked.truncate(keep);
+
+        let mut draw = self.rng.random::<f32>() * ranked.iter().map(|(_, p)| p).sum::<f32>();
+        for &(id, p) in &ranked {
+            if draw < p {
+                return id;
+            }
+            draw -= p;
+        }
+        ranked[ranked.len() - 1].0
+    }
+}
+
+fn argmax(logits: &[f32]) -> u32 {
+    logits
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(i, _)| i as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greedy_applies_repetition_penalty() {
+        let mut sampler = Sampler::new(SamplingOptions {
+            temperature: 0.0,
+            repetition_penalty: 2.0,
+            ..Default::default()
+        });
+        assert_eq!(sampler.sample(&mut [1.0, 3.0, 2.0], &[]), 1);
+        assert_eq!(sampler.sample(&mut [1.0, 3.0, 2.0], &[1]), 2);
+    }
+
+    #[test]
+    fn top_k_and_top_p_restrict_candidates_and_seed_is_reproducible() {
+        let logits = [0.0, 5.0, 4.9, -2.0, 1.0];
+        let options = SamplingOptions {
+            temperature: 1.0,
+            top_k: 2,
+            top_p: 1.0,
+            repetition_penalty: 1.0,
+            seed: Some(7),
+        };
+        let mut sampler = Sampler::new(options.clone());
+        let drawn: Vec<u32> = (0..50)
+            .map(|_| sampler.sample(&mut logits.clone(), &[]))
+            .collect();
+        assert!(drawn.iter().all(|&id| id == 1 || id == 2));
+        assert!(drawn.contains(&1) && drawn.contains(&2));
+        let mut again = Sampler::new(options.clone());
+        let redrawn: Vec<u32> = (0..50)
+            .map(|_| again.sample(&mut logits.clone(), &[]))
+            .collect();
+        assert_eq!(drawn, redrawn);
+
+        let mut nucleus = Sampler::new(SamplingOptions {
+            top_k: 0,
+            top_p: 0.1,
+            ..options
+        });
+        assert!((0..20).all(|_| nucleus.sample(&mut logits.clone(), &[]) == 1));
+    }
+}
@@ -0,0 +1,193 @@
+//! Chat prompt formats. Checkpoints ship a Jinja `chat_template`; rather than evaluate Jinja we
+//! recognise which of the common layouts it implements and render that layout natively.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    /// `system`, `user` or `assistant`.
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn new(role: &str, content: &str) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatTemplate {
+    /// `<|im_start|>role\n...<|im_end|>` (SmolLM, Qwen, many fine-tunes).
+    ChatMl,
+    /// `<s>[INST] <<SYS>>...<</SYS>> ... [/INST]` (Llama 2 chat, Mistral instruct).
+    Llama2,
+    /// `<|start_header_id|>role<|end_header_id|>...<|eot_id|>` (Llama 3).
+    Llama3,
+    /// `<|user|>\n...</s>` (Zephyr, TinyLlama chat).
+    Zephyr,
+}
+
+impl ChatTemplate {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "chatml" => Ok(Self::ChatMl),
+            "llama2" | "mistral" => Ok(Self::Llama2),
+            "llama3" => Ok(Self::Llama3),
+            "zephyr" => Ok(Self::Zephyr),
+            _ => Err(format!(
+                "unknown chat template '{name}' (expected chatml, llama2, llama3 or zephyr)"
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ChatMl => "chatml",
+            Self::Llama2 => "llama2",
+            Self::Llama3 => "llama3",
+            Self::Zephyr => "zephyr",
+        }
+    }
+
+    /// Pick the layout a checkpoint's Jinja template implements, falling back on which marker
+    /// tokens the vocabulary has.
+    pub fn detect(jinja: Option<&str>, has_token: impl Fn(&str) -> bool) -> Self {
+        let markers = [
+            ("<|im_start|>", Self::ChatMl),
+            ("<|start_header_id|>", Self::Llama3),
+            ("<|user|>", Self::Zephyr),
+            ("[INST]", Self::Llama2),
+        ];
+        if let Some(jinja) = jinja {
+            if let Some((_, t)) = markers.iter().find(|(m, _)| jinja.contains(m)) {
+                return *t;
+            }
+        }
+        markers
+            .iter()
+            .take(2)
+            .find(|(m, _)| has_token(m))
+            .map_or(Self::Llama2, |(_, t)| *t)
+    }
+
+    /// Token that ends an assistant turn; generation stops on it.
+    pub fn end_of_turn(self) -> &'static str {
+        match self {
+            Self::ChatMl => "<|im_end|>",
+            Self::Llama3 => "<|eot_id|>",
+            Self::Llama2 | Self::Zephyr => "</s>",
+        }
+    }
+
+    /// Whether the tokenizer should prepend BOS (the other layouts spell it out, or have none).
+    pub fn add_bos(self) -> bool {
+        self == Self::Zephyr
+    }
+
+    /// Render `messages`; with `add_generation_prompt` the text ends where the assistant's reply
+    /// begins.
+    pub fn render(self, messages: &[ChatMessage], add_generation_prompt: bool) -> String {
+        let mut out = String::new();
+        match self {
+            Self::ChatMl => {
+                for m in messages {
+                    out += &format!("<|im_start|>{}\n{}<|im_end|>\n", m.role, m.content);
+                }
+                if add_generation_prompt {
+                    out += "<|im_start|>assistant\n";
+                }
+            }
+            Self::Llama3 => {
+                out += "<|begin_of_text|>";
+                for m in messages {
+                    out += &format!(
+                        "<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>",
+                        m.role,
+                        m.content.trim()
+                    );
+                }
+                if add_generation_prompt {
+                    out += "<|start_header_id|>assistant<|end_header_id|>\n\n";
+                }
+            }
+            Self::Zephyr => {
+                for m in messages {
+                    out += &format!("<|{}|>\n{}</s>\n", m.role, m.content);
+                }
+                if add_generation_prompt {
+                    out += "<|assistant|>\n";
+                }
+            }
+            Self::Llama2 => {
+                // The system prompt is folded into the first user turn; the prompt always ends
+                // after `[/INST]`, so there is no separate generation prompt.
+                let (system, rest) = match messages.split_first() {
+                    Some((first, rest)) if first.role == "system" => (Some(first), rest),
+                    _ => (None, messages),
+                };
+                let mut system = system.map(|m| m.content.trim());
+                for m in rest {
+                    if m.role == "assistant" {
+                        out += &format!(" {} </s>", m.content.trim());
+                        continue;
+                    }
+                    out += "<s>[INST] ";
+                    if let Some(sys) = system.take() {
+                        out += &format!("<<SYS>>\n{sys}\n<</SYS>>\n\n");
+                    }
+                    out += &format!("{} [/INST]", m.content.trim());
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convo() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::new("system", "Be brief."),
+            ChatMessage::new("user", "Hi"),
+            ChatMessage::new("assistant", "Hello!"),
+            ChatMessage::new("user", "Bye"),
+        ]
+    }
+
+    #[test]
+    fn detects_layout_from_jinja_or_vocabulary() {
+        let smol = "{% for message in messages %}{{'<|im_start|>' + message['role'] }}";
+        assert_eq!(
+            ChatTemplate::detect(Some(smol), |_| false),
+            ChatTemplate::ChatMl
+        );
+        let tiny = "{{ '<|user|>\n' + message['content'] + eos_token }}";
+        assert_eq!(
+            ChatTemplate::detect(Some(tiny), |_| false),
+            ChatTemplate::Zephyr
+        );
+        assert_eq!(
+            ChatTemplate::detect(None, |t| t == "<|start_header_id|>"),
+            ChatTemplate::Llama3
+        );
+        assert_eq!(ChatTemplate::detect(None, |_| false), ChatTemplate::Llama2);
+    }
+
+    #[test]
+    fn renders_chatml_and_llama2() {
+        assert_eq!(
+            ChatTemplate::ChatMl.render(&convo(), true),
+            "<|im_start|>system\nBe brief.<|im_end|>\n<|im_start|>user\nHi<|im_end|>\n\
+             <|im_start|>assistant\nHello!<|im_end|>\n<|im_start|>user\nBye<|im_end|>\n\
+             <|im_start|>assistant\n"
+        );
+        assert_eq!(
+            ChatTemplate::Llama2.render(&convo(), true),
+            "<s>[INST] <<SYS>>\nBe brief.\n<</SYS>>\n\nHi [/INST] Hello! </s><s>[INST] Bye [/INST]"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Text ↔ token ids. Safetensors checkpoints ship a Hugging Face `tokenizer.json`; GGUF files embed
+//! a SentencePiece vocabulary (`tokenizer.ggml.*`) that [`SpmTokenizer`] encodes directly.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::gguf::{GgufFile, GgufValue};
+
+pub enum Tokenizer {
+    Hf(Box<tokenizers::Tokenizer>),
+    Spm(SpmTokenizer),
+}
+
+impl Tokenizer {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        tokenizers::Tokenizer::from_file(path)
+            .map(|t| Self::Hf(Box::new(t)))
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Special tokens written literally in `text` (`<|im_start|>`, `</s>`, ...) encode to their ids.
+    /// `add_bos` prepends the beginning-of-sequence token when the vocabulary has one.
+    pub fn encode(&self, text: &str, add_bos: bool) -> Result<Vec<u32>, String> {
+        match self {
+            Self::Hf(t) => t
+                .encode(text, add_bos)
+                .map(|e| e.get_ids().to_vec())
+                .map_err(|e| format!("tokenize: {e}")),
+            Self::Spm(t) => Ok(t.encode(text, add_bos)),
+        }
+    }
+
+    /// Special tokens are dropped from the text.
+    pub fn decode(&self, ids: &[u32]) -> Result<String, String> {
+        match self {
+            Self::Hf(t) => t.decode(ids, true).map_err(|e| format!("detokenize: {e}")),
+            Self::Spm(t) => Ok(t.decode(ids)),
+        }
+    }
+
+    pub fn token_id(&self, token: &str) -> Option<u32> {
+        match self {
+            Self::Hf(t) => t.token_to_id(token),
+            Self::Spm(t) => t.ids.get(token).copied(),
+        }
+    }
+}
+
+const NORMAL: i64 = 1;
+const CONTROL: i64 = 3;
+const USER_DEFINED: i64 = 4;
+const BYTE: i64 = 6;
+
+/// llama.cpp-compatible SentencePiece BPE over a score-ranked vocabulary, with `<0xNN>` byte
+/// fallback for characters the vocabulary lacks.
+pub struct SpmTokenizer {
+    tokens: Vec<String>,
+    scores: Vec<f32>,
+    kinds: Vec<i64>,
+    ids: HashMap<String, u32>,
+    /// Control / user-defined tokens, longest first, matched literally before BPE.
+    specials: Vec<(String, u32)>,
+    bos: Option<u32>,
+    unk: Option<u32>,
+    add_bos: bool,
+    add_space_prefix: bool,
+}
+
+impl SpmTokenizer {
+    /// `None` when the GGUF has no vocabulary or uses a non-SentencePiece (e.g. GPT-2 BPE) one.
+    pub fn from_gguf(file: &GgufFile) -> Result<Option<Self>, String> {
+        if file.get("tokenizer.ggml.model").and_then(GgufValue::as_str) != Some("llama") {
+            return Ok(None);
+        }
+        let tokens: Vec<String> = file
+            .get("tokenizer.ggml.tokens")
+            .and_then(GgufValue::as_array)
+            .ok_or_else(|| "GGUF has no tokenizer.ggml.tokens".to_string())?
+            .iter()
+            .map(|v| v.as_str().unwrap_or_default().to_string())
+            .collect();
+        let floats = |key: &str| -> Vec<f32> {
+            file.get(key)
+                .and_then(GgufValue::as_array)
+                .map(|a| a.iter().map(|v| v.as_f64().unwrap_or(0.0) as f32).collect())
+                .unwrap_or_else(|| vec![0.0; tokens.len()])
+        };
+        let scores = floats("tokenizer.ggml.scores");
+        let kinds = floats("tokenizer.ggml.token_type")
+            .into_iter()
+            .map(|k| k as i64)
+            .collect();
+        let id = |key: &str| file.get(key).and_then(GgufValue::as_u64).map(|v| v as u32);
+        let flag = |key: &str| match file.get(key) {
+            Some(GgufValue::Bool(b)) => *b,
+            _ => true,
+        };
+        Ok(Some(Self::new(
+            tokens,
+            scores,
+            kinds,
+            id("tokenizer.ggml.bos_token_id"),
+            id("tokenizer.ggml.unknown_token_id"),
+            flag("tokenizer.ggml.add_bos_token"),
+            flag("tokenizer.ggml.add_space_prefix"),
+        )))
+    }
+
+    fn new(
+        tokens: Vec<String>,
+        scores: Vec<f32>,
+        kinds: Vec<i64>,
+        bos: Option<u32>,
+        unk: Option<u32>,
+        add_bos: bool,
+        add_space_prefix: bool,
+    ) -> Self {
+        let ids = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.clone(), i as u32))
+            .collect();
+        let mut specials: Vec<(String, u32)> = tokens
+            .iter()
+            .zip(&kinds)
+            .enumerate()
+            .filter(|(_, (t, k))| (**k == CONTROL || **k == USER_DEFINED) && !t.is_empty())
+            .map(|(i, (t, _))| (t.clone(), i as u32))
+            .collect();
+        specials.sort_by_key(|(t, _)| std::cmp::Reverse(t.len()));
+        Self {
+            tokens,
+            scores,
+            kinds,
+            ids,
+            specials,
+            bos,
+            unk,
+            add_bos,
+            add_space_prefix,
+        }
+    }
+
+    pub fn encode(&self, text: &str, add_bos: bool) -> Vec<u32> {
+        let mut out = Vec::new();
+        if add_bos && self.add_bos {
+            out.extend(self.bos);
+        }
+        // Like llama.cpp, every run of plain text (each starts the input or follows a special
+        // token) gets the SentencePiece space prefix.
+        let prefix = if self.add_space_prefix { " " } else { "" };
+        let mut rest = text;
+        while !rest.is_empty() {
+            let next = (0..rest.len())
+                .filter(|&i| rest.is_char_boundary(i))
+                .find_map(|i| {
+                    self.specials
+                        .iter()
+                        .find(|(t, _)| rest[i..].starts_with(t.as_str()))
+                        .map(|(t, id)| (i, t.len(), *id))
+                });
+            let (plain, special) = match next {
+                Some((at, len, id)) => (&rest[..at], Some((len, id, at))),
+                None => (rest, None),
+            };
+            if !plain.is_empty() {
+                self.bpe(&format!("{prefix}{plain}").replace(' ', "▁"), &mut out);
+            }
+            match special {
+                Some((len, id, at)) => {
+                    out.push(id);
+                    rest = &rest[at + len..];
+                }
+                None => break,
+            }
+        }
+        out
+    }
+
+    fn bpe(&self, text: &str, out: &mut Vec<u32>) {
+        let mut symbols: Vec<String> = text.chars().map(String::from).collect();
+        loop {
+            let best = symbols
+                .windows(2)
+                .enumerate()
+                .filter_map(|(i, pair)| {
+                    let id = *self.ids.get(&format!("{}{}", pair[0], pair[1]))?;
+                    (self.kinds[id as usize] == NORMAL || self.kinds[id as usize] == USER_DEFINED)
+                        .then(|| (i, self.scores[id as usize]))
+                })
+                // Highest score wins; ties go to the leftmost pair.
+                .fold(None, |best: Option<(usize, f32)>, (i, s)| match best {
+                    Some((_, b)) if b >= s => best,
+                    _ => Some((i, s)),
+                });
+            let Some((i, _)) = best else {
+                break;
+            };
+            let right = symbols.remove(i + 1);
+            symbols[i].push_str(&right);
+        }
+        for symbol in symbols {
+            if let Some(&id) = self.ids.get(&symbol) {
+                out.push(id);
+                continue;
+            }
+            for byte in symbol.bytes() {
+                match self.ids.get(&format!("<0x{byte:02X}>")) {
+                    Some(&id) => out.push(id),
+                    None => out.extend(self.unk),
+                }
+            }
+        }
+    }
+
+    pub fn decode(&self, ids: &[u32]) -> String {
+        let mut bytes = Vec::new();
+        for &id in ids {
+            let Some(token) = self.tokens.get(id as usize) else {
+                continue;
+            };
+            match self.kinds.get(id as usize).copied().unwrap_or(NORMAL) {
+                BYTE => {
+                    let hex = token.trim_start_matches("<0x").trim_end_matches('>');
+                    bytes.extend(u8::from_str_radix(hex, 16).ok());
+                }
+                CONTROL => {}
+                _ => bytes.extend(token.replace('▁', " ").into_bytes()),
+            }
+        }
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        match text.strip_prefix(' ') {
+            Some(stripped) if self.add_space_prefix => stripped.to_string(),
+            _ => text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab() -> SpmTokenizer {
+        let entries: &[(&str, f32, i64)] = &[
+            ("<unk>", 0.0, 2),
+            ("<s>", 0.0, CONTROL),
+            ("</s>", 0.0, CONTROL),
+            ("<0x21>", 0.0, BYTE),
+            ("<0xC3>", 0.0, BYTE),
+            ("<0xA9>", 0.0, BYTE),
+            ("▁", -1.0, NORMAL),
+            ("h", -5.0, NORMAL),
+            ("i", -5.0, NORMAL),
+            ("▁h", -2.0, NORMAL),
+            ("hi", -1.5, NORMAL),
+            ("▁hi", -0.5, NORMAL),
+        ];
+        SpmTokenizer::new(
+            entries.iter().map(|e| e.0.to_string()).collect(),
+            entries.iter().map(|e| e.1).collect(),
+            entries.iter().map(|e| e.2).collect(),
+            Some(1),
+            Some(0),
+            true,
+            true,
+        )
+    }
+
+    #[test]
+    fn encodes_with_merges_specials_and_byte_fallback() {
+        let spm = vocab();
+        assert_eq!(spm.encode("hi hi", true), vec![1, 11, 11]);
+        assert_eq!(spm.encode("hi!</s>hi", false), vec![11, 3, 2, 11]);
+        assert_eq!(spm.encode("é", false), vec![6, 4, 5]);
+    }
+
+    #[test]
+    fn decodes_bytes_and_drops_control_tokens() {
+        let spm = vocab();
+        assert_eq!(spm.decode(&[1, 11, 11, 3]), "hi hi!");
+        assert_eq!(spm.decode(&[6, 4, 5, 2]), "é");
+        let ids = spm.encode("hi é!", true);
+        assert_eq!(spm.decode(&ids), "hi é!");
+    }
+}
@@ -0,0 +1,233 @@
+//! Checkpoint loading: a `.gguf` file, or a folder of `config.json` + `*.safetensors` (sharded or
+//! not). Tensors are widened to `f32` and keyed by their Hugging Face names, whatever the source.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::config::LlamaConfig;
+use super::gguf::{GgufFile, GgufValue};
+use super::tokenizer::SpmTokenizer;
+use crate::ai::safetensors::SafetensorsFile;
+
+pub struct WeightTensor {
+    pub shape: Vec<usize>,
+    /// Storage type in the checkpoint (`BF16`, `Q8_0`, ...).
+    pub dtype: String,
+    pub data: Vec<f32>,
+}
+
+pub struct Checkpoint {
+    pub config: LlamaConfig,
+    pub tensors: BTreeMap<String, WeightTensor>,
+    /// Weight files read, for `weights_file` in the Python API.
+    pub files: Vec<PathBuf>,
+    /// Vocabulary embedded in a GGUF (SentencePiece models only).
+    pub spm: Option<SpmTokenizer>,
+    /// Jinja chat template shipped with the checkpoint, used to pick a [`super::ChatTemplate`].
+    pub chat_template: Option<String>,
+}
+
+impl Checkpoint {
+    pub fn take(&mut self, name: &str) -> Result<WeightTensor, String> {
+        self.tensors
+            .remove(name)
+            .ok_or_else(|| format!("checkpoint has no tensor {name}"))
+    }
+}
+
+/// Load `path`: a `.gguf` file, a `.safetensors` file (its folder is used), or a folder holding
+/// either.
+pub fn load_checkpoint(path: &Path) -> Result<Checkpoint, String> {
+    if path.is_file() {
+        return match path.extension().and_then(|e| e.to_str()) {
+            Some("gguf") => load_gguf(path),
+            Some("safetensors") => load_safetensors_dir(path.parent().unwrap_or(Path::new("."))),
+            _ => Err(format!(
+                "{}: expected a .gguf or .safetensors file",
+                path.display()
+            )),
+        };
+    }
+    if !path.is_dir() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| format!("read_dir {}: {e}", path.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    files.sort();
+    let has = |ext: &str| {
+        files
+            .iter()
+            .any(|f| f.extension().is_some_and(|e| e == ext))
+    };
+    if has("safetensors") {
+        load_safetensors_dir(path)
+    } else if let Some(gguf) = files
+        .iter()
+        .find(|f| f.extension().is_some_and(|e| e == "gguf"))
+    {
+        load_gguf(gguf)
+    } else {
+        Err(format!(
+            "no .gguf or .safetensors weights in {}",
+            path.display()
+        ))
+    }
+}
+
+fn load_safetensors_dir(dir: &Path) -> Result<Checkpoint, String> {
+    let config_path = dir.join("config.json");
+    let text = fs::read_to_string(&config_path)
+        .map_err(|e| format!("read {}: {e}", config_path.display()))?;
+    let config = LlamaConfig::from_hf_json(&text)?;
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("read_dir {}: {e}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "safetensors"))
+        .collect();
+    files.sort();
+    let mut tensors = BTreeMap::new();
+    for file in &files {
+        let bytes = fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+        let st = SafetensorsFile::parse(bytes).map_err(|e| format!("{}: {e}", file.display()))?;
+        for info in &st.tensors {
+            tensors.insert(
+                info.name.clone(),
+                WeightTensor {
+                    shape: info.shape.clone(),
+                    dtype: info.dtype.clone(),
+                    data: st.to_f32(info),
+                },
+            );
+        }
+    }
+    let chat_template = fs::read_to_string(dir.join("tokenizer_config.json"))
+        .ok()
+        .and_then(|t| serde_json::from_str::<serde_json::Value>(&t).ok())
+        .and_then(|v| v.get("chat_template")?.as_str().map(str::to_string));
+    Ok(Checkpoint {
+        config,
+        tensors,
+        files,
+        spm: None,
+        chat_template,
+    })
+}
+
+fn load_gguf(path: &Path) -> Result<Checkpoint, String> {
+    let bytes = fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let file = GgufFile::parse(bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+    let has_output = file.tensors.iter().any(|t| t.name == "output.weight");
+    let config = LlamaConfig::from_gguf(&file, has_output)?;
+    let mut tensors = BTreeMap::new();
+    for info in &file.tensors {
+        let Some(name) = hf_name(&info.name) else {
+            continue;
+        };
+        let mut data = file.dequantize(info)?;
+        // llama.cpp stores Q / K rows interleaved for its rotary kernel; undo that so one RoPE
+        // implementation serves both formats.
+        if name.ends_with("q_proj.weight") {
+            data = unpermute_rows(&data, &info.shape, config.num_heads);
+        } else if name.ends_with("k_proj.weight") {
+            data = unpermute_rows(&data, &info.shape, config.num_kv_heads);
+        }
+        tensors.insert(
+            name,
+            WeightTensor {
+                shape: info.shape.clone(),
+                dtype: info.ggml_type.name().to_string(),
+                data,
+            },
+        );
+    }
+    Ok(Checkpoint {
+        config,
+        tensors,
+        files: vec![path.to_path_buf()],
+        spm: SpmTokenizer::from_gguf(&file)?,
+        chat_template: file
+            .get("tokenizer.chat_template")
+            .and_then(GgufValue::as_str)
+            .map(str::to_string),
+    })
+}
+
+/// GGUF tensor name → Hugging Face name (`None` for tensors the model does not use).
+fn hf_name(gguf: &str) -> Option<String> {
+    let global = match gguf {
+        "token_embd.weight" => Some("model.embed_tokens.weight"),
+        "output_norm.weight" => Some("model.norm.weight"),
+        "output.weight" => Some("lm_head.weight"),
+        _ => None,
+    };
+    if let Some(name) = global {
+        return Some(name.to_string());
+    }
+    let rest = gguf.strip_prefix("blk.")?;
+    let (layer, part) = rest.split_once('.')?;
+    let layer: usize = layer.parse().ok()?;
+    let hf = match part {
+        "attn_norm.weight" => "input_layernorm.weight",
+        "attn_q.weight" => "self_attn.q_proj.weight",
+        "attn_k.weight" => "self_attn.k_proj.weight",
+        "attn_v.weight" => "self_attn.v_proj.weight",
+        "attn_output.weight" => "self_attn.o_proj.weight",
+        "ffn_norm.weight" => "post_attention_layernorm.weight",
+        "ffn_gate.weight" => "mlp.gate_proj.weight",
+        "ffn_up.weight" => "mlp.up_proj.weight",
+        "ffn_down.weight" => "mlp.down_proj.weight",
+        _ => return None,
+    };
+    Some(format!("model.layers.{layer}.{hf}"))
+}
+
+/// Inverse of llama.cpp's `permute(w, n_head)`: GGUF row `(head, i, t)` back to HF row
+/// `(head, t, i)` for `t` in 0..2 and `i` in 0..head_dim/2.
+fn unpermute_rows(data: &[f32], shape: &[usize], n_head: usize) -> Vec<f32> {
+    let (rows, cols) = (shape[0], shape.get(1).copied().unwrap_or(1));
+    let head_dim = rows / n_head;
+    let half = head_dim / 2;
+    let mut out = vec![0.0; data.len()];
+    for h in 0..n_head {
+        for t in 0..2 {
+            for i in 0..half {
+                let dst = (h * head_dim + t * half + i) * cols;
+                let src = (h * head_dim + 2 * i + t) * cols;
+                out[dst..dst + cols].copy_from_slice(&data[src..src + cols]);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_gguf_names() {
+        assert_eq!(
+            hf_name("blk.3.attn_q.weight").as_deref(),
+            Some("model.layers.3.self_attn.q_proj.weight")
+        );
+        assert_eq!(hf_name("output.weight").as_deref(), Some("lm_head.weight"));
+        assert_eq!(hf_name("rope_freqs.weight"), None);
+        assert_eq!(hf_name("blk.x.attn_q.weight"), None);
+    }
+
+    #[test]
+    fn unpermute_inverts_llama_cpp_permute() {
+        // 2 heads of dim 4, 1 column: HF rows [h0: a0 a1 b0 b1, h1: ...] become [a0 b0 a1 b1, ...].
+        let hf: Vec<f32> = (0..8).map(|v| v as f32).collect();
+        let gguf = vec![0.0, 2.0, 1.0, 3.0, 4.0, 6.0, 5.0, 7.0];
+        assert_eq!(unpermute_rows(&gguf, &[8, 1], 2), hf);
+        let wide: Vec<f32> = gguf.iter().flat_map(|v| [*v, -v]).collect();
+        let back = unpermute_rows(&wide, &[8, 2], 2);
+        assert_eq!(back[2..4], [1.0, -1.0]);
+    }
+}
@@ -1,2 +1,20 @@
-//! AI / ML features (speech recognition, etc.).
+//! AI / ML features (speech recognition, local chat models, OCR, text-to-speech, etc.).
+#[cfg(all(feature = "models", not(target_arch = "wasm32")))]
+pub mod models;
+#[cfg(all(
+    feature = "llama",
+    not(target_arch = "wasm32"),
+    not(target_os = "ios")
+))]
+pub mod llama;
+#[cfg(all(feature = "ocr", not(target_arch = "wasm32"), not(target_os = "ios")))]
+pub mod ocr;
+#[cfg(all(
+    any(feature = "llama", feature = "tts"),
+    not(target_arch = "wasm32"),
+    not(target_os = "ios")
+))]
+mod safetensors;
 pub mod transcription;
+#[cfg(all(feature = "tts", not(target_arch = "wasm32"), not(target_os = "ios")))]
+pub mod tts;
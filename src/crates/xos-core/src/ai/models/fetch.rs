@@ -0,0 +1,207 @@
+//! HTTP download into `{file}.part` (resumed with a `Range` request after an interruption) and
+//! SHA-256 verification against the registry.
+
+use std::fs::{self, OpenOptions};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sha2::{Digest, Sha256};
+
+use super::manifest::ModelFile;
+
+const USER_AGENT: &str = "xos-models/1.0";
+const MB: f64 = 1024.0 * 1024.0;
+/// Set to `1` to accept registry files without a pinned SHA-256 (same as `--allow-unpinned`).
+pub const ALLOW_UNPINNED_ENV: &str = "XOS_MODELS_ALLOW_UNPINNED";
+
+static ALLOW_UNPINNED: AtomicBool = AtomicBool::new(false);
+
+/// Accept registry files that have no pinned SHA-256 yet (dev override; off by default).
+pub fn allow_unpinned(on: bool) {
+    ALLOW_UNPINNED.store(on, Ordering::Relaxed);
+}
+
+fn unpinned_allowed() -> bool {
+    ALLOW_UNPINNED.load(Ordering::Relaxed)
+        || std::env::var(ALLOW_UNPINNED_ENV).is_ok_and(|v| v == "1")
+}
+
+pub(crate) fn hex_lower(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut f = fs::File::open(path).map_err(|e| format!("open {}: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = f
+            .read(&mut buf)
+            .map_err(|e| format!("read {}: {e}", path.display()))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex_lower(&hasher.finalize()))
+}
+
+pub(crate) fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Hash `path` and check it against `file.sha256`; returns the hash. Unpinned files are refused
+/// unless [`allow_unpinned`] (or [`ALLOW_UNPINNED_ENV`]) is set.
+pub(crate) fn verify(file: &ModelFile, path: &Path) -> Result<String, String> {
+    let got = sha256_file(path)?;
+    match &file.sha256 {
+        Some(expected) if !got.eq_ignore_ascii_case(expected) => Err(format!(
+            "{} sha256 mismatch: got {got}, expected {expected}",
+            path.display()
+        )),
+        Some(_) => Ok(got),
+        None if unpinned_allowed() => Ok(got),
+        None => Err(format!(
+            "{} has no pinned sha256 in the registry (run `xos models pin`, or pass \
+             --allow-unpinned / set {ALLOW_UNPINNED_ENV}=1 to accept it unchecked)",
+            file.name
+        )),
+    }
+}
+
+/// Download `file` to `dest` and verify it. A corrupt result is deleted so the next attempt
+/// starts over instead of resuming it.
+pub(crate) fn fetch_verified(file: &ModelFile, dest: &Path, model: &str) -> Result<String, String> {
+    download_resumable(&file.url, dest, &format!("{model} / {}", file.name))?;
+    verify(file, dest).inspect_err(|_| {
+        let _ = fs::remove_file(dest);
+    })
+}
+
+/// Download `file` to `dest` and return its hash without checking it, for pinning.
+pub(crate) fn fetch_hashed(file: &ModelFile, dest: &Path, model: &str) -> Result<String, String> {
+    download_resumable(&file.url, dest, &format!("{model} / {}", file.name))?;
+    sha256_file(dest)
+}
+
+fn download_resumable(url: &str, dest: &Path, label: &str) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create {}: {e}", parent.display()))?;
+    }
+    let part = part_path(dest);
+    let have = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let mut request = ureq::get(url).set("User-Agent", USER_AGENT);
+    if have > 0 {
+        request = request.set("Range", &format!("bytes={have}-"));
+    }
+    let response = match request.call() {
+        Ok(r) => r,
+        // The partial file already holds the whole body.
+        Err(ureq::Error::Status(416, _)) if have > 0 => {
+            return fs::rename(&part, dest)
+                .map_err(|e| format!("rename to {}: {e}", dest.display()));
+        }
+        Err(e) => return Err(format!("GET {url}: {e}")),
+    };
+    let resumed = have > 0 && response.status() == 206;
+    let start = if resumed { have } else { 0 };
+    let total = response
+        .header("Content-Length")
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|n| n + start);
+    if resumed {
+        eprintln!(
+            "[xos-models] Resuming {label} at {:.1} MB…",
+            start as f64 / MB
+        );
+    } else {
+        eprintln!("[xos-models] Downloading {label}…");
+    }
+
+    let mut out = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .map_err(|e| format!("open {}: {e}", part.display()))?;
+    let mut reader = response.into_reader();
+    let mut buf = vec![0u8; 1 << 16];
+    let mut done = start;
+    let meter = std::io::stderr().is_terminal();
+    let mut shown_percent = u64::MAX;
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .map_err(|e| format!("read body {url}: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        out.write_all(&buf[..n])
+            .map_err(|e| format!("write {}: {e}", part.display()))?;
+        done += n as u64;
+        if let (true, Some(total)) = (meter, total.filter(|t| *t > 0)) {
+            let percent = done * 100 / total;
+            if percent != shown_percent {
+                shown_percent = percent;
+                eprint!(
+                    "\r[xos-models] {label}: {percent:>3}% ({:.1} / {:.1} MB)",
+                    done as f64 / MB,
+                    total as f64 / MB
+                );
+            }
+        }
+    }
+    if meter && shown_percent != u64::MAX {
+        eprintln!();
+    }
+    out.sync_all().ok();
+    drop(out);
+    if let Some(total) = total {
+        if done != total {
+            return Err(format!(
+                "GET {url}: connection closed at {done} of {total} bytes (rerun to resume)"
+            ));
+        }
+    }
+    fs::rename(&part, dest).map_err(|e| format!("rename to {}: {e}", dest.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_and_verifies_files() {
+        let path =
+            std::env::temp_dir().join(format!("xos-registry-test-{}-hash", std::process::id()));
+        fs::write(&path, b"abc").unwrap();
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(sha256_file(&path).unwrap(), abc);
+
+        let mut file = ModelFile {
+            name: "f".into(),
+            url: String::new(),
+            sha256: None,
+            unpack: None,
+        };
+        let err = verify(&file, &path).unwrap_err();
+        assert!(err.contains("no pinned sha256"), "{err}");
+        allow_unpinned(true);
+        assert_eq!(verify(&file, &path).unwrap(), abc);
+        allow_unpinned(false);
+        file.sha256 = Some(abc.to_ascii_uppercase());
+        assert!(verify(&file, &path).is_ok());
+        file.sha256 = Some("0".repeat(64));
+        assert!(verify(&file, &path).is_err());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            part_path(Path::new("/m/tiny.pt")),
+            Path::new("/m/tiny.pt.part")
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Offline install for air-gapped machines: copy models in from a local directory or tarball
+//! instead of their URLs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::fetch::verify;
+use super::manifest::{registry, ModelEntry};
+use super::store::{model_dir, read_receipt, write_receipt};
+use super::unpack::{copy_dir_all, extract_tar, is_tarball};
+
+/// Install every registry model found in `source`, returning their names. `source` is a directory,
+/// a `.tar` / `.tar.gz` / `.tgz`, or a single file, holding either model folders laid out like
+/// `{data_dir}/models/` (e.g. `tar czf models.tgz -C ~/.xos models`) or loose files named as in
+/// the registry (`xos models list --files`). Pinned checksums are verified before anything is
+/// copied.
+pub fn import(source: &Path) -> Result<Vec<String>, String> {
+    if !source.exists() {
+        return Err(format!("{} does not exist", source.display()));
+    }
+    if is_tarball(source) {
+        let staging =
+            std::env::temp_dir().join(format!("xos-models-import-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&staging);
+        extract_tar(source, &staging)?;
+        let result = import_dir(&staging);
+        let _ = fs::remove_dir_all(&staging);
+        return result.map_err(|e| format!("{e} (in {})", source.display()));
+    }
+    import_dir(source)
+}
+
+fn import_dir(source: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    if source.is_dir() {
+        walk_files(source, &mut files);
+    } else {
+        files.push(source.to_path_buf());
+    }
+    let mut imported = Vec::new();
+    for entry in registry()? {
+        if let Some(tree) = model_tree(source, entry) {
+            import_tree(&tree, entry, &model_dir(entry)?)?;
+            imported.push(entry.name.clone());
+            continue;
+        }
+        let found: Option<Vec<&PathBuf>> = entry
+            .files
+            .iter()
+            .map(|f| {
+                files
+                    .iter()
+                    .find(|p| p.file_name().is_some_and(|n| *n == *f.name))
+            })
+            .collect();
+        if let Some(found) = found {
+            let dir = model_dir(entry)?;
+            let mut hashes = BTreeMap::new();
+            for (file, path) in entry.files.iter().zip(found) {
+                let sha = super::install_file(entry, file, &dir, Some(path))?;
+                hashes.insert(file.name.clone(), Some(sha));
+            }
+            write_receipt(&dir, entry, hashes)?;
+            imported.push(entry.name.clone());
+        }
+    }
+    if imported.is_empty() {
+        return Err(format!(
+            "no registry models found in {} (expected model folders like whisper/tiny-ct2/ or files named as in `xos models list --files`)",
+            source.display()
+        ));
+    }
+    Ok(imported)
+}
+
+fn walk_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(items) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in items.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            walk_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+/// `{source}/{dir}` or `{source}/models/{dir}` holding a complete copy of `entry`.
+fn model_tree(source: &Path, entry: &ModelEntry) -> Option<PathBuf> {
+    [source.to_path_buf(), source.join("models")]
+        .into_iter()
+        .map(|base| base.join(&entry.dir))
+        .find(|dir| entry.ready_files().iter().all(|f| dir.join(f).is_file()))
+}
+
+/// Copy a complete model folder into `dir`, keeping its receipt when it is for this registry
+/// version and writing a fresh one otherwise.
+fn import_tree(tree: &Path, entry: &ModelEntry, dir: &Path) -> Result<(), String> {
+    // A receipt from another machine is only kept when it is for this registry version; an older
+    // copy would otherwise be recorded as current and never re-pulled.
+    let receipt = read_receipt(tree).filter(|r| r.name == entry.name);
+    if let Some(r) = receipt.as_ref().filter(|r| r.version != entry.version) {
+        return Err(format!(
+            "{} holds {} v{}, but the registry has v{} (pull it again on the source machine)",
+            tree.display(),
+            entry.name,
+            r.version,
+            entry.version
+        ));
+    }
+    let mut hashes = BTreeMap::new();
+    for file in entry.files.iter().filter(|f| f.unpack.is_none()) {
+        let path = tree.join(&file.name);
+        hashes.insert(file.name.clone(), Some(verify(file, &path)?));
+    }
+    copy_dir_all(tree, dir)?;
+    match receipt {
+        Some(_) => Ok(()),
+        None => write_receipt(dir, entry, hashes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::manifest::parse_registry;
+
+    #[test]
+    fn import_tree_rejects_a_receipt_from_another_version() {
+        let entries = parse_registry(
+            r#"{"models": [{"name": "vad", "version": "2", "license": "MIT", "dir": "vad",
+                "files": [{"name": "vad.onnx", "url": "https://x",
+                "sha256": "03ac674216f3e15c761ee1a5e255f067953623c8b388b4459e13f978d7c846f4"}]}]}"#,
+        )
+        .unwrap();
+        let entry = &entries[0];
+        let root =
+            std::env::temp_dir().join(format!("xos-registry-test-{}-import", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (tree, dest) = (root.join("src"), root.join("dest"));
+        fs::create_dir_all(&tree).unwrap();
+        fs::write(tree.join("vad.onnx"), b"1234").unwrap();
+        let mut old = entry.clone();
+        old.version = "1".into();
+        write_receipt(&tree, &old, BTreeMap::new()).unwrap();
+
+        let err = import_tree(&tree, entry, &dest).unwrap_err();
+        assert!(err.contains("v1") && err.contains("v2"), "{err}");
+        assert!(!dest.exists());
+
+        write_receipt(&tree, entry, BTreeMap::new()).unwrap();
+        import_tree(&tree, entry, &dest).unwrap();
+        assert_eq!(read_receipt(&dest).unwrap().version, "2");
+        assert_eq!(fs::read(dest.join("vad.onnx")).unwrap(), b"1234");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+//! [`registry.json`]: every model xos can fetch, with the files that make it up.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+const REGISTRY: &str = include_str!("registry.json");
+/// Where `registry.json` lives in a source checkout, relative to the repo root.
+pub const REGISTRY_PATH: &str = "src/crates/xos-core/src/ai/models/registry.json";
+
+/// Archive format of a registry file that is extracted into the model folder instead of kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unpack {
+    Zip,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelFile {
+    /// Name inside the model folder; [`super::import`] also finds loose files by this name.
+    pub name: String,
+    pub url: String,
+    /// Lowercase hex. `None` while the upstream file is not pinned yet (refused unless
+    /// `--allow-unpinned`); `xos models pin` fills these in.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub unpack: Option<Unpack>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelEntry {
+    pub name: String,
+    /// Bumped when the files change; installs from an older version are re-pulled.
+    pub version: String,
+    pub license: String,
+    #[serde(default)]
+    pub description: String,
+    /// Folder under `{data_dir}/models/`.
+    pub dir: String,
+    pub files: Vec<ModelFile>,
+    /// Files present once installed; defaults to every file that is not an archive.
+    #[serde(default)]
+    ready: Vec<String>,
+}
+
+impl ModelEntry {
+    pub fn ready_files(&self) -> Vec<&str> {
+        if !self.ready.is_empty() {
+            return self.ready.iter().map(String::as_str).collect();
+        }
+        self.files
+            .iter()
+            .filter(|f| f.unpack.is_none())
+            .map(|f| f.name.as_str())
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct Registry {
+    models: Vec<ModelEntry>,
+}
+
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+pub(crate) fn parse_registry(json: &str) -> Result<Vec<ModelEntry>, String> {
+    let registry: Registry =
+        serde_json::from_str(json).map_err(|e| format!("registry.json: {e}"))?;
+    let mut names = HashSet::new();
+    for entry in &registry.models {
+        if !is_plain_name(&entry.name) || !names.insert(entry.name.as_str()) {
+            return Err(format!(
+                "registry.json: duplicate or invalid name '{}'",
+                entry.name
+            ));
+        }
+        if entry.dir.is_empty() || !entry.dir.split('/').all(is_plain_name) {
+            return Err(format!(
+                "registry.json: '{}' dir must be a relative path",
+                entry.name
+            ));
+        }
+        if entry.files.is_empty() || entry.ready_files().is_empty() {
+            return Err(format!("registry.json: '{}' lists no files", entry.name));
+        }
+        for file in &entry.files {
+            if !is_plain_name(&file.name) {
+                return Err(format!(
+                    "registry.json: '{}' has invalid file name '{}'",
+                    entry.name, file.name
+                ));
+            }
+            if let Some(sha) = &file.sha256 {
+                if sha.len() != 64 || !sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(format!(
+                        "registry.json: '{}' / {} sha256 is not 64 hex digits",
+                        entry.name, file.name
+                    ));
+                }
+            }
+        }
+    }
+    Ok(registry.models)
+}
+
+/// Every registry entry, in manifest order.
+pub fn registry() -> Result<&'static [ModelEntry], String> {
+    static PARSED: OnceLock<Result<Vec<ModelEntry>, String>> = OnceLock::new();
+    PARSED
+        .get_or_init(|| parse_registry(REGISTRY))
+        .as_deref()
+        .map_err(Clone::clone)
+}
+
+pub fn find(name: &str) -> Result<&'static ModelEntry, String> {
+    registry()?
+        .iter()
+        .find(|e| e.name == name)
+        .ok_or_else(|| format!("unknown model '{name}' (see `xos models list`)"))
+}
+
+/// Set the `"sha256": null` that follows `"url": {url}` in registry text (every entry listing that
+/// URL), leaving the rest of the file byte for byte. Errors when no unpinned file has `url`.
+pub fn pin_in_registry(json: &str, url: &str, sha256: &str) -> Result<String, String> {
+    let url_field = format!("\"url\": {}", serde_json::Value::from(url));
+    let mut out = String::with_capacity(json.len());
+    let mut after_url = false;
+    let mut pinned = 0;
+    for line in json.split_inclusive('\n') {
+        if after_url && line.trim_start().starts_with("\"sha256\": null") {
+            out.push_str(&line.replacen("null", &format!("\"{sha256}\""), 1));
+            pinned += 1;
+        } else {
+            out.push_str(line);
+        }
+        after_url = line.trim_start().starts_with(&url_field);
+    }
+    if pinned == 0 {
+        return Err(format!("registry.json: no unpinned file has url {url}"));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_registry_is_valid() {
+        let models = registry().unwrap();
+        for name in [
+            "whisper-tiny-ct2",
+            "whisper-small-burn",
+            "silero-vad",
+            "wespeaker-resnet34",
+        ] {
+            assert!(find(name).is_ok(), "{name}");
+        }
+        let ct2 = find("whisper-base-ct2").unwrap();
+        assert_eq!(ct2.dir, "whisper/base-ct2");
+        assert!(ct2.ready_files().contains(&"model.bin"));
+        assert_eq!(
+            find("whisper-tiny-burn").unwrap().ready_files(),
+            ["tiny.pt", "tiny-tokenizer.json"]
+        );
+        let dirs: HashSet<&str> = models.iter().map(|m| m.dir.as_str()).collect();
+        assert_eq!(dirs.len(), models.len());
+        assert!(find("nope").is_err());
+    }
+
+    #[test]
+    #[ignore = "registry.json still has unpinned files; run `xos models pin` and drop this"]
+    fn every_registry_file_is_pinned() {
+        let unpinned: Vec<String> = registry()
+            .unwrap()
+            .iter()
+            .flat_map(|m| {
+                m.files
+                    .iter()
+                    .filter(|f| f.sha256.is_none())
+                    .map(move |f| format!("{} / {}", m.name, f.name))
+            })
+            .collect();
+        assert!(unpinned.is_empty(), "unpinned: {unpinned:#?}");
+    }
+
+    #[test]
+    fn rejects_unsafe_paths_and_bad_hashes() {
+        let entry = |dir: &str, file: &str, sha: &str| {
+            format!(
+                r#"{{"models": [{{"name": "m", "version": "1", "license": "MIT", "dir": "{dir}",
+                "files": [{{"name": "{file}", "url": "https://x", "sha256": {sha}}}]}}]}}"#
+            )
+        };
+        assert!(parse_registry(&entry("a/b", "f.bin", "null")).is_ok());
+        assert!(parse_registry(&entry("../b", "f.bin", "null")).is_err());
+        assert!(parse_registry(&entry("a", "../f.bin", "null")).is_err());
+        assert!(parse_registry(&entry("a", "f.bin", "\"abc\"")).is_err());
+        assert!(parse_registry(&entry("a", "f.bin", &format!("\"{}\"", "0".repeat(64)))).is_ok());
+    }
+
+    #[test]
+    fn pin_fills_only_the_matching_unpinned_file() {
+        let json = r#"{"models": [{"name": "m", "version": "1", "license": "MIT", "dir": "m",
+  "files": [
+    {
+      "name": "a.bin",
+      "url": "https://x/a",
+      "sha256": null
+    },
+    {
+      "name": "b.bin",
+      "url": "https://x/b",
+      "sha256": null
+    }
+  ]}]}
+"#;
+        let sha = "ab".repeat(32);
+        let pinned = pin_in_registry(json, "https://x/b", &sha).unwrap();
+        assert_eq!(
+            pinned,
+            json.replacen(
+                "\"https://x/b\",\n      \"sha256\": null",
+                &format!("\"https://x/b\",\n      \"sha256\": \"{sha}\""),
+                1
+            )
+        );
+        let files = &parse_registry(&pinned).unwrap()[0].files;
+        assert_eq!(files[0].sha256, None);
+        assert_eq!(files[1].sha256.as_deref(), Some(sha.as_str()));
+        assert!(pin_in_registry(&pinned, "https://x/b", &sha).is_err());
+        assert!(pin_in_registry(json, "https://x/c", &sha).is_err());
+    }
+}
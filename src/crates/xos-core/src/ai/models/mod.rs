@@ -0,0 +1,132 @@
+//! Model registry: [`registry.json`] lists every model xos downloads (name, version, licence,
+//! files, SHA-256) and where it lives under `{data_dir}/models/` (see `xos path --data`).
+//! Backends call [`ensure`]; `xos models list|pull|rm|import|gc` manage the cache by hand.
+//! Downloads resume from `*.part` files, and [`import`] installs from a local directory or
+//! tarball on machines without network access.
+
+mod fetch;
+mod import;
+mod manifest;
+mod store;
+mod unpack;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use fetch::{allow_unpinned, ALLOW_UNPINNED_ENV};
+pub use import::import;
+pub use manifest::{find, pin_in_registry, registry, ModelEntry, ModelFile, Unpack, REGISTRY_PATH};
+pub use store::{dir_size, gc, model_dir, remove, status, GcReport, ModelStatus};
+
+/// Install `name` unless a complete, current copy is cached, and return its folder.
+pub fn ensure(name: &str) -> Result<PathBuf, String> {
+    let entry = find(name)?;
+    match status(entry)? {
+        ModelStatus::Installed { .. } => model_dir(entry),
+        _ => pull(entry),
+    }
+}
+
+/// Download (or resume) every file of `entry`, verify and unpack it, and record the install.
+/// Files already on disk (e.g. from before the registry) are kept when they check out.
+pub fn pull(entry: &ModelEntry) -> Result<PathBuf, String> {
+    let dir = model_dir(entry)?;
+    let outdated = match store::status_in(&dir, entry) {
+        ModelStatus::Installed {
+            version: Some(_), ..
+        } => return Ok(dir),
+        ModelStatus::Outdated { .. } => true,
+        _ => false,
+    };
+    let ready = entry.ready_files();
+    let mut hashes = BTreeMap::new();
+    for file in &entry.files {
+        let dest = dir.join(&file.name);
+        let sha = match file.unpack {
+            Some(_) if !outdated && ready.iter().all(|f| dir.join(f).is_file()) => None,
+            None if !outdated && dest.is_file() => match fetch::verify(file, &dest) {
+                Ok(sha) => Some(sha),
+                Err(_) => Some(install_file(entry, file, &dir, None)?),
+            },
+            _ => Some(install_file(entry, file, &dir, None)?),
+        };
+        hashes.insert(file.name.clone(), sha);
+    }
+    if let Some(missing) = ready.iter().find(|f| !dir.join(f).is_file()) {
+        return Err(format!(
+            "{} is incomplete under {} (missing {missing})",
+            entry.name,
+            dir.display()
+        ));
+    }
+    store::write_receipt(&dir, entry, hashes)?;
+    Ok(dir)
+}
+
+/// Download every file of `entry` that has no pinned SHA-256 into a scratch folder and return
+/// `(url, sha256)` for each, for [`pin_in_registry`]. Nothing is installed.
+pub fn hash_unpinned(entry: &ModelEntry) -> Result<Vec<(String, String)>, String> {
+    let staging = std::env::temp_dir().join(format!("xos-models-pin-{}", std::process::id()));
+    let mut pins = Vec::new();
+    for file in entry.files.iter().filter(|f| f.sha256.is_none()) {
+        let dest = staging.join(&file.name);
+        let sha = fetch::fetch_hashed(file, &dest, &entry.name);
+        let _ = fs::remove_file(&dest);
+        pins.push((file.url.clone(), sha?));
+    }
+    let _ = fs::remove_dir_all(&staging);
+    Ok(pins)
+}
+
+/// Put one registry file into `dir` from `local` (import) or its URL, extracting archives;
+/// returns the file's SHA-256.
+pub(crate) fn install_file(
+    entry: &ModelEntry,
+    file: &ModelFile,
+    dir: &Path,
+    local: Option<&Path>,
+) -> Result<String, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+    let dest = dir.join(&file.name);
+    let sha = match local {
+        Some(src) => fetch::verify(file, src)?,
+        None => fetch::fetch_verified(file, &dest, &entry.name)?,
+    };
+    match (file.unpack, local) {
+        (Some(Unpack::Zip), _) => {
+            let archive = local.unwrap_or(&dest);
+            let safe: String = entry
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            // Staging in the system temp dir: iOS can refuse dot-prefixed dirs next to the data tree.
+            let staging = std::env::temp_dir().join(format!("xos-models-{safe}"));
+            let _ = fs::remove_dir_all(&staging);
+            unpack::extract_zip(archive, &staging)?;
+            // Entries may ship several archives; each must carry at least one ready file.
+            let ready = entry.ready_files();
+            unpack::lift_single_subdirectory(&staging, &ready)?;
+            if !ready.iter().any(|f| staging.join(f).is_file()) {
+                return Err(format!(
+                    "{} has none of {} (check the archive layout)",
+                    file.name,
+                    ready.join(", ")
+                ));
+            }
+            unpack::move_contents(&staging, dir)?;
+            let _ = fs::remove_dir_all(&staging);
+            if local.is_none() {
+                let _ = fs::remove_file(&dest);
+            }
+        }
+        (None, Some(src)) => {
+            let tmp = fetch::part_path(&dest);
+            fs::copy(src, &tmp).map_err(|e| format!("copy {}: {e}", src.display()))?;
+            fs::rename(&tmp, &dest).map_err(|e| format!("rename to {}: {e}", dest.display()))?;
+        }
+        (None, None) => {}
+    }
+    Ok(sha)
+}
@@ -0,0 +1,270 @@
+//! Cache layout under `{data_dir}/models/`: one folder per registry entry, with a
+//! `.xos-model.json` receipt recording the installed version and file hashes.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::manifest::{registry, ModelEntry};
+
+const RECEIPT: &str = ".xos-model.json";
+/// Temp-dir staging prefixes (this module, and the old CT2 downloader).
+const STAGING_PREFIXES: [&str; 2] = ["xos-models-", "xos-ct2-"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Receipt {
+    pub name: String,
+    pub version: String,
+    /// File name → SHA-256 (`None` when not hashed, e.g. copied in as an unpacked tree).
+    #[serde(default)]
+    pub files: BTreeMap<String, Option<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelStatus {
+    Missing,
+    /// `version` is `None` for a complete folder written before the registry existed.
+    Installed {
+        version: Option<String>,
+        bytes: u64,
+    },
+    /// Complete, but from an older registry version; [`super::ensure`] pulls it again.
+    Outdated {
+        version: String,
+        bytes: u64,
+    },
+}
+
+pub(crate) fn store_root() -> Result<PathBuf, String> {
+    xos_auth::model_store_dir().map_err(|e| e.to_string())
+}
+
+pub fn model_dir(entry: &ModelEntry) -> Result<PathBuf, String> {
+    Ok(store_root()?.join(&entry.dir))
+}
+
+pub(crate) fn read_receipt(dir: &Path) -> Option<Receipt> {
+    let text = fs::read_to_string(dir.join(RECEIPT)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+pub(crate) fn write_receipt(
+    dir: &Path,
+    entry: &ModelEntry,
+    files: BTreeMap<String, Option<String>>,
+) -> Result<(), String> {
+    let receipt = Receipt {
+        name: entry.name.clone(),
+        version: entry.version.clone(),
+        files,
+    };
+    let text = serde_json::to_string_pretty(&receipt).map_err(|e| e.to_string())?;
+    let tmp = dir.join(format!("{RECEIPT}.tmp"));
+    fs::write(&tmp, text).map_err(|e| format!("write {}: {e}", tmp.display()))?;
+    fs::rename(&tmp, dir.join(RECEIPT)).map_err(|e| format!("write receipt: {e}"))
+}
+
+/// Total size of the files under `path`.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    };
+    entries
+        .flatten()
+        .map(|e| {
+            let p = e.path();
+            if p.is_dir() {
+                dir_size(&p)
+            } else {
+                e.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+pub(crate) fn status_in(dir: &Path, entry: &ModelEntry) -> ModelStatus {
+    if !entry.ready_files().iter().all(|f| dir.join(f).is_file()) {
+        return ModelStatus::Missing;
+    }
+    let bytes = dir_size(dir);
+    match read_receipt(dir) {
+        Some(r) if r.version != entry.version => ModelStatus::Outdated {
+            version: r.version,
+            bytes,
+        },
+        Some(r) => ModelStatus::Installed {
+            version: Some(r.version),
+            bytes,
+        },
+        None => ModelStatus::Installed {
+            version: None,
+            bytes,
+        },
+    }
+}
+
+pub fn status(entry: &ModelEntry) -> Result<ModelStatus, String> {
+    Ok(status_in(&model_dir(entry)?, entry))
+}
+
+/// Delete a model's folder (converted artifacts included); returns the bytes freed.
+pub fn remove(entry: &ModelEntry) -> Result<u64, String> {
+    let root = store_root()?;
+    let dir = root.join(&entry.dir);
+    if !dir.exists() {
+        return Err(format!("{} is not installed", entry.name));
+    }
+    let bytes = dir_size(&dir);
+    fs::remove_dir_all(&dir).map_err(|e| format!("remove {}: {e}", dir.display()))?;
+    // Drop now-empty parents such as `whisper/`.
+    let mut parent = dir.parent();
+    while let Some(p) = parent.filter(|p| *p != root) {
+        if fs::remove_dir(p).is_err() {
+            break;
+        }
+        parent = p.parent();
+    }
+    Ok(bytes)
+}
+
+#[derive(Debug, Default)]
+pub struct GcReport {
+    pub removed: Vec<PathBuf>,
+    pub bytes: u64,
+}
+
+/// Collect garbage under `root`: partial downloads (`*.part`, `*.tmp`) and folders whose receipt
+/// names a model the registry no longer has.
+pub(crate) fn gc_in(root: &Path, entries: &[ModelEntry], dry_run: bool) -> GcReport {
+    fn walk(dir: &Path, entries: &[ModelEntry], out: &mut Vec<PathBuf>) {
+        let Ok(items) = fs::read_dir(dir) else {
+            return;
+        };
+        for path in items.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                match read_receipt(&path) {
+                    Some(r) if !entries.iter().any(|e| e.name == r.name) => out.push(path),
+                    _ => walk(&path, entries, out),
+                }
+            } else if path.extension().is_some_and(|e| e == "part" || e == "tmp") {
+                out.push(path);
+            }
+        }
+    }
+    let mut report = GcReport::default();
+    walk(root, entries, &mut report.removed);
+    for path in &report.removed {
+        report.bytes += dir_size(path);
+        if !dry_run {
+            let _ = if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+        }
+    }
+    report
+}
+
+/// [`gc_in`] over the model store, plus extraction staging left in the temp dir by interrupted
+/// installs. With `dry_run`, only report what would go.
+pub fn gc(dry_run: bool) -> Result<GcReport, String> {
+    let mut report = gc_in(&store_root()?, registry()?, dry_run);
+    if let Ok(items) = fs::read_dir(std::env::temp_dir()) {
+        for path in items.flatten().map(|e| e.path()) {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if path.is_dir() && STAGING_PREFIXES.iter().any(|p| name.starts_with(p)) {
+                report.bytes += dir_size(&path);
+                if !dry_run {
+                    let _ = fs::remove_dir_all(&path);
+                }
+                report.removed.push(path);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::manifest::parse_registry;
+
+    fn test_entries() -> Vec<ModelEntry> {
+        parse_registry(
+            r#"{"models": [{"name": "vad", "version": "2", "license": "MIT", "dir": "vad",
+                "files": [{"name": "vad.onnx", "url": "https://x"}]}]}"#,
+        )
+        .unwrap()
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("xos-registry-test-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn status_follows_files_and_receipt() {
+        let entries = test_entries();
+        let entry = &entries[0];
+        let dir = scratch("status");
+        assert_eq!(status_in(&dir, entry), ModelStatus::Missing);
+
+        fs::write(dir.join("vad.onnx"), b"1234").unwrap();
+        assert_eq!(
+            status_in(&dir, entry),
+            ModelStatus::Installed {
+                version: None,
+                bytes: 4
+            }
+        );
+        write_receipt(&dir, entry, BTreeMap::new()).unwrap();
+        assert!(matches!(
+            status_in(&dir, entry),
+            ModelStatus::Installed { version: Some(v), .. } if v == "2"
+        ));
+        let mut old = entry.clone();
+        old.version = "1".into();
+        write_receipt(&dir, &old, BTreeMap::new()).unwrap();
+        assert!(matches!(
+            status_in(&dir, entry),
+            ModelStatus::Outdated { version, .. } if version == "1"
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn gc_removes_partials_and_orphans_only() {
+        let entries = test_entries();
+        let root = scratch("gc");
+        let keep = root.join("vad");
+        fs::create_dir_all(&keep).unwrap();
+        fs::write(keep.join("vad.onnx"), b"model").unwrap();
+        write_receipt(&keep, &entries[0], BTreeMap::new()).unwrap();
+        fs::write(keep.join("vad.onnx.part"), b"par").unwrap();
+
+        let orphan = root.join("retired");
+        fs::create_dir_all(&orphan).unwrap();
+        let mut retired = entries[0].clone();
+        retired.name = "retired".into();
+        write_receipt(&orphan, &retired, BTreeMap::new()).unwrap();
+        let untracked = root.join("mine/notes.txt");
+        fs::create_dir_all(untracked.parent().unwrap()).unwrap();
+        fs::write(&untracked, b"keep me").unwrap();
+
+        let dry = gc_in(&root, &entries, true);
+        assert_eq!(dry.removed.len(), 2);
+        assert!(orphan.exists());
+
+        let report = gc_in(&root, &entries, false);
+        assert_eq!(report.removed.len(), 2);
+        assert!(!orphan.exists() && !keep.join("vad.onnx.part").exists());
+        assert!(keep.join("vad.onnx").is_file() && untracked.is_file());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
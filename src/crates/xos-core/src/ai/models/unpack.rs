@@ -0,0 +1,156 @@
+//! Archive extraction (ZIP model packs, tarballs for offline import) and moving trees into place.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use tar::Archive;
+use zip::ZipArchive;
+
+/// `.tar`, `.tar.gz` or `.tgz`.
+pub(crate) fn is_tarball(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    path.is_file()
+        && [".tar", ".tar.gz", ".tgz"]
+            .iter()
+            .any(|s| name.ends_with(s))
+}
+
+pub(crate) fn extract_tar(archive: &Path, out_dir: &Path) -> Result<(), String> {
+    let f = fs::File::open(archive).map_err(|e| format!("open {}: {e}", archive.display()))?;
+    fs::create_dir_all(out_dir).map_err(|e| format!("create {}: {e}", out_dir.display()))?;
+    let name = archive.to_string_lossy().to_ascii_lowercase();
+    let result = if name.ends_with(".tar") {
+        Archive::new(f).unpack(out_dir)
+    } else {
+        Archive::new(GzDecoder::new(f)).unpack(out_dir)
+    };
+    result.map_err(|e| format!("extract {}: {e}", archive.display()))
+}
+
+pub(crate) fn extract_zip(archive: &Path, out_dir: &Path) -> Result<(), String> {
+    let f = fs::File::open(archive).map_err(|e| format!("open {}: {e}", archive.display()))?;
+    let mut zip = ZipArchive::new(f).map_err(|e| format!("open zip archive: {e}"))?;
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(|e| format!("zip entry {i}: {e}"))?;
+        let Some(rel) = file.enclosed_name() else {
+            continue;
+        };
+        let outpath = out_dir.join(rel);
+        if file.is_dir() {
+            fs::create_dir_all(&outpath)
+                .map_err(|e| format!("mkdir {}: {e}", outpath.display()))?;
+            continue;
+        }
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("mkdir {}: {e}", parent.display()))?;
+        }
+        let mut out =
+            fs::File::create(&outpath).map_err(|e| format!("create {}: {e}", outpath.display()))?;
+        std::io::copy(&mut file, &mut out)
+            .map_err(|e| format!("write {}: {e}", outpath.display()))?;
+    }
+    Ok(())
+}
+
+fn is_junk(name: &str) -> bool {
+    name == "__MACOSX" || name.starts_with('.')
+}
+
+/// Archives often wrap the model in one folder (plus `__MACOSX`); hoist its contents to `dir`
+/// unless one of the `ready` files is already at the top level.
+pub(crate) fn lift_single_subdirectory(dir: &Path, ready: &[&str]) -> Result<(), String> {
+    let holds_ready = |d: &Path| ready.iter().any(|f| d.join(f).exists());
+    if holds_ready(dir) {
+        return Ok(());
+    }
+    let _ = fs::remove_dir_all(dir.join("__MACOSX"));
+    let subdirs: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("read_dir {}: {e}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !is_junk(n))
+        })
+        .collect();
+    let inner = match subdirs.as_slice() {
+        [only] => only.clone(),
+        _ => match subdirs.iter().find(|d| holds_ready(d)) {
+            Some(found) => found.clone(),
+            None => return Ok(()),
+        },
+    };
+    move_contents(&inner, dir)?;
+    fs::remove_dir_all(&inner).map_err(|e| format!("remove {}: {e}", inner.display()))
+}
+
+/// Recursive copy (merging into an existing `dst`), skipping partial downloads.
+pub(crate) fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), String> {
+    fs::create_dir_all(dst).map_err(|e| format!("create {}: {e}", dst.display()))?;
+    for ent in fs::read_dir(src).map_err(|e| format!("read_dir {}: {e}", src.display()))? {
+        let ent = ent.map_err(|e| e.to_string())?;
+        let (s, t) = (ent.path(), dst.join(ent.file_name()));
+        if s.is_dir() {
+            copy_dir_all(&s, &t)?;
+        } else if s.extension().is_none_or(|e| e != "part") {
+            fs::copy(&s, &t)
+                .map_err(|e| format!("copy {} -> {}: {e}", s.display(), t.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Move each entry of `src` into `dst`, replacing what is there. Renames when possible and falls
+/// back to copying (e.g. EXDEV between the temp dir and the data dir on iOS).
+pub(crate) fn move_contents(src: &Path, dst: &Path) -> Result<(), String> {
+    fs::create_dir_all(dst).map_err(|e| format!("create {}: {e}", dst.display()))?;
+    for ent in fs::read_dir(src).map_err(|e| format!("read_dir {}: {e}", src.display()))? {
+        let ent = ent.map_err(|e| e.to_string())?;
+        let (from, to) = (ent.path(), dst.join(ent.file_name()));
+        if to.is_dir() {
+            fs::remove_dir_all(&to).map_err(|e| format!("remove {}: {e}", to.display()))?;
+        }
+        if fs::rename(&from, &to).is_err() {
+            if from.is_dir() {
+                copy_dir_all(&from, &to)?;
+            } else {
+                fs::copy(&from, &to)
+                    .map_err(|e| format!("copy {} -> {}: {e}", from.display(), to.display()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifts_a_wrapping_folder() {
+        let dir =
+            std::env::temp_dir().join(format!("xos-registry-test-{}-lift", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("__MACOSX")).unwrap();
+        fs::create_dir_all(dir.join("whisper-tiny-ct2/sub")).unwrap();
+        fs::write(dir.join("whisper-tiny-ct2/model.bin"), b"m").unwrap();
+        fs::write(dir.join("whisper-tiny-ct2/sub/x"), b"x").unwrap();
+
+        lift_single_subdirectory(&dir, &["model.bin"]).unwrap();
+        assert!(dir.join("model.bin").is_file());
+        assert!(dir.join("sub/x").is_file());
+        assert!(!dir.join("whisper-tiny-ct2").exists());
+        assert!(!dir.join("__MACOSX").exists());
+        // Already flat: nothing moves.
+        lift_single_subdirectory(&dir, &["model.bin"]).unwrap();
+        assert!(dir.join("sub/x").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+//! The RGB image OCR reads, built from whatever pixel buffer the caller has: xos frames (RGBA),
+//! webcam frames (RGB), monitor captures (RGBA) or a grayscale tensor.
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major RGB, 3 bytes per pixel.
+    pub rgb: Vec<u8>,
+}
+
+impl Image {
+    /// `channels` is 1 (gray), 3 (RGB) or 4 (RGBA, alpha ignored).
+    pub fn from_pixels(
+        width: usize,
+        height: usize,
+        channels: usize,
+        pixels: &[u8],
+    ) -> Result<Self, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("empty image ({width}x{height})"));
+        }
+        if !matches!(channels, 1 | 3 | 4) {
+            return Err(format!(
+                "expected 1, 3 or 4 channels per pixel, got {channels}"
+            ));
+        }
+        let need = width * height * channels;
+        if pixels.len() != need {
+            return Err(format!(
+                "{width}x{height}x{channels} image needs {need} bytes, got {}",
+                pixels.len()
+            ));
+        }
+        let rgb = match channels {
+            1 => pixels.iter().flat_map(|&v| [v, v, v]).collect(),
+            3 => pixels.to_vec(),
+            _ => pixels
+                .chunks_exact(4)
+                .flat_map(|p| [p[0], p[1], p[2]])
+                .collect(),
+        };
+        Ok(Self { width, height, rgb })
+    }
+
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Result<Self, String> {
+        Self::from_pixels(width, height, 4, rgba)
+    }
+
+    pub fn from_rgb(width: usize, height: usize, rgb: &[u8]) -> Result<Self, String> {
+        Self::from_pixels(width, height, 3, rgb)
+    }
+
+    /// The latest frame of the camera opened with `video::webcam::init_camera`.
+    pub fn from_webcam() -> Result<Self, String> {
+        let (w, h) = crate::video::webcam::get_resolution();
+        Self::from_rgb(w as usize, h as usize, &crate::video::webcam::get_frame())
+    }
+
+    /// A fresh capture of desktop monitor `index` (macOS / Windows app builds).
+    pub fn from_monitor(index: usize) -> Result<Self, String> {
+        let (rgba, w, h) = crate::monitors::system_monitor_capture_scaled_rgba(index)
+            .ok_or_else(|| format!("monitor {index} capture failed (bad index or no driver)"))?;
+        Self::from_rgba(w as usize, h as usize, &rgba)
+    }
+
+    /// ITU-R BT.601 luma in `0..=255`, as EasyOCR's recognizer was trained on.
+    pub(crate) fn gray(&self) -> Vec<f32> {
+        self.rgb
+            .chunks_exact(3)
+            .map(|p| 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32)
+            .collect()
+    }
+}
+
+/// Source taps `(lo, hi, weight of hi)` for each of `out` samples resampling `len` with pixel
+/// centres aligned (OpenCV `INTER_LINEAR`, PyTorch `align_corners=False`).
+pub(crate) fn interpolation(out: usize, len: usize) -> Vec<(usize, usize, f32)> {
+    let scale = len as f32 / out as f32;
+    (0..out)
+        .map(|o| {
+            let x = ((o as f32 + 0.5) * scale - 0.5).clamp(0.0, (len - 1) as f32);
+            let lo = x.floor() as usize;
+            (lo, (lo + 1).min(len - 1), x - lo as f32)
+        })
+        .collect()
+}
+
+/// Bilinear resize of a planar `channels x h x w` buffer.
+pub(crate) fn resize(
+    src: &[f32],
+    channels: usize,
+    (h, w): (usize, usize),
+    (out_h, out_w): (usize, usize),
+) -> Vec<f32> {
+    let ys = interpolation(out_h, h);
+    let xs = interpolation(out_w, w);
+    let mut out = Vec::with_capacity(channels * out_h * out_w);
+    for c in 0..channels {
+        let plane = &src[c * h * w..(c + 1) * h * w];
+        for &(y0, y1, fy) in &ys {
+            for &(x0, x1, fx) in &xs {
+                let top = plane[y0 * w + x0] * (1.0 - fx) + plane[y0 * w + x1] * fx;
+                let bottom = plane[y1 * w + x0] * (1.0 - fx) + plane[y1 * w + x1] * fx;
+                out.push(top * (1.0 - fy) + bottom * fy);
+            }
+        }
+    }
+    out
+}
+
+/// Input height of the recognizer.
+pub(crate) const RECOGNIZER_HEIGHT: usize = 64;
+
+const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+/// CRAFT input: the image scaled so its long side is at most `canvas` pixels, ImageNet-normalized
+/// (planar RGB) and zero-padded to multiples of 32. Returns `(input, h, w, scale)`, where `scale`
+/// maps padded-input pixels back to image pixels.
+pub(crate) fn detector_input(img: &Image, canvas: usize) -> (Vec<f32>, usize, usize, f32) {
+    let long = img.width.max(img.height);
+    let ratio = (canvas as f32 / long as f32).min(1.0);
+    let th = ((img.height as f32 * ratio) as usize).max(1);
+    let tw = ((img.width as f32 * ratio) as usize).max(1);
+    let planar: Vec<f32> = (0..3)
+        .flat_map(|c| img.rgb.iter().skip(c).step_by(3).map(|&v| v as f32))
+        .collect();
+    let scaled = resize(&planar, 3, (img.height, img.width), (th, tw));
+    let (h, w) = (th.next_multiple_of(32), tw.next_multiple_of(32));
+    let mut out = Vec::with_capacity(3 * h * w);
+    for c in 0..3 {
+        let (mean, std) = (MEAN[c] * 255.0, STD[c] * 255.0);
+        for y in 0..h {
+            for x in 0..w {
+                let v = if y < th && x < tw {
+                    scaled[(c * th + y) * tw + x]
+                } else {
+                    0.0
+                };
+                out.push((v - mean) / std);
+            }
+        }
+    }
+    (out, h, w, 1.0 / ratio)
+}
+
+/// Recognizer input for the `[x1, y1, x2, y2]` crop of `gray`: 64 pixels high, width by aspect
+/// ratio (at least `min_width`, padded by repeating the last column), scaled to `-1..1`.
+pub(crate) fn recognizer_input(
+    gray: &[f32],
+    (h, w): (usize, usize),
+    rect: [f32; 4],
+    min_width: usize,
+) -> Option<(Vec<f32>, usize)> {
+    let x1 = (rect[0].max(0.0) as usize).min(w);
+    let y1 = (rect[1].max(0.0) as usize).min(h);
+    let x2 = (rect[2].ceil().max(0.0) as usize).min(w);
+    let y2 = (rect[3].ceil().max(0.0) as usize).min(h);
+    if x2 <= x1 || y2 <= y1 {
+        return None;
+    }
+    let (ch, cw) = (y2 - y1, x2 - x1);
+    let crop: Vec<f32> = (y1..y2)
+        .flat_map(|y| gray[y * w + x1..y * w + x2].iter().copied())
+        .collect();
+    let rw = ((RECOGNIZER_HEIGHT * cw) as f32 / ch as f32)
+        .ceil()
+        .max(1.0) as usize;
+    let resized = resize(&crop, 1, (ch, cw), (RECOGNIZER_HEIGHT, rw));
+    let width = rw.max(min_width);
+    let mut out = Vec::with_capacity(RECOGNIZER_HEIGHT * width);
+    for row in resized.chunks_exact(rw) {
+        out.extend(row.iter().map(|&v| (v / 255.0 - 0.5) / 0.5));
+        let last = out[out.len() - 1];
+        out.extend(std::iter::repeat_n(last, width - rw));
+    }
+    Some((out, width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_channels_and_checks_sizes() {
+        let img = Image::from_rgba(2, 1, &[1, 2, 3, 255, 4, 5, 6, 0]).unwrap();
+        assert_eq!(img.rgb, vec![1, 2, 3, 4, 5, 6]);
+        let gray = Image::from_pixels(2, 1, 1, &[7, 9]).unwrap();
+        assert_eq!(gray.rgb, vec![7, 7, 7, 9, 9, 9]);
+        assert!(Image::from_rgb(2, 2, &[0; 11]).is_err());
+        assert!(Image::from_pixels(1, 1, 2, &[0; 2]).is_err());
+    }
+
+    #[test]
+    fn detector_input_is_padded_and_scaled() {
+        let img = Image::from_rgb(100, 40, &vec![255; 100 * 40 * 3]).unwrap();
+        let (input, h, w, scale) = detector_input(&img, 50);
+        assert_eq!((h, w, scale), (32, 64, 2.0));
+        assert_eq!(input.len(), 3 * 32 * 64);
+        // Inside the 20x50 image: (255 - 0.485*255) / (0.229*255); padding: -0.485/0.229.
+        assert!((input[0] - 0.515 / 0.229).abs() < 1e-4);
+        assert!((input[63] + 0.485 / 0.229).abs() < 1e-4);
+        let (crop, width) =
+            recognizer_input(&img.gray(), (40, 100), [10.0, 10.0, 30.0, 42.0], 80).unwrap();
+        // 20x30 crop → 43 wide at height 64, padded to 80.
+        assert_eq!((crop.len(), width), (64 * 80, 80));
+        assert!(crop.iter().all(|&v| (v - 1.0).abs() < 1e-4));
+        assert!(recognizer_input(&img.gray(), (40, 100), [5.0, 5.0, 5.0, 9.0], 8).is_none());
+    }
+
+    #[test]
+    fn resize_keeps_constants_and_interpolates() {
+        let flat = resize(&[3.0; 6], 1, (2, 3), (5, 7));
+        assert!(flat.iter().all(|&v| (v - 3.0).abs() < 1e-6));
+        let ramp = resize(&[0.0, 10.0], 1, (1, 2), (1, 4));
+        assert_eq!(ramp, vec![0.0, 2.5, 7.5, 10.0]);
+    }
+}
@@ -0,0 +1,277 @@
+//! On-device OCR on Burn (NdArray on the CPU or WGPU on the GPU), running EasyOCR's checkpoints:
+//! the CRAFT detector finds words, neighbouring words are merged into lines, and the
+//! generation-2 recognizer reads each line.
+//!
+//! Models come from the registry (`easyocr-en`, downloaded on first use) or a folder holding
+//! `craft_mlt_25k.pth` and `english_g2.pth`. Input is an [`Image`] built from a frame, a webcam
+//! frame, a monitor capture or raw pixels.
+
+mod image;
+mod model;
+mod postprocess;
+mod weights;
+
+use std::path::{Path, PathBuf};
+
+use burn::backend::{ndarray::NdArrayDevice, wgpu::WgpuDevice, NdArray, Wgpu};
+
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+use model::{OcrNet, OcrRuntime};
+use weights::Checkpoint;
+
+pub use self::image::Image;
+pub use postprocess::Rect;
+
+/// Registry model used when none is named.
+pub const DEFAULT_MODEL: &str = "easyocr-en";
+
+const DETECTOR_FILE: &str = "craft_mlt_25k.pth";
+const RECOGNIZER_FILE: &str = "english_g2.pth";
+
+/// `english_g2`'s characters in class order (class 0 is the CTC blank).
+const ENGLISH_G2: &str = "0123456789!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~ €ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Values kept per parameter for inspection (`OcrParameter::values`).
+const PARAMETER_HEAD: usize = 128;
+
+/// Narrowest crop the recognizer is given; it needs at least 8 columns to emit one step.
+const MIN_CROP_WIDTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OcrDevice {
+    #[default]
+    Cpu,
+    Gpu,
+}
+
+impl OcrDevice {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" | "ndarray" => Some(Self::Cpu),
+            "gpu" | "wgpu" => Some(Self::Gpu),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrOptions {
+    /// Images are scaled down so their long side fits (EasyOCR uses 2560; smaller is faster and
+    /// misses small print).
+    pub canvas_size: usize,
+    /// Minimum peak character score for a word.
+    pub text_threshold: f32,
+    /// Affinity score that links neighbouring characters into a word.
+    pub link_threshold: f32,
+    /// Character score that counts as text when growing a word.
+    pub low_text: f32,
+    /// Merge words into lines before reading (EasyOCR's default); `false` reads word by word.
+    pub merge_lines: bool,
+    /// Padding around each line, as a fraction of its height.
+    pub margin: f32,
+    /// Results below this confidence are dropped.
+    pub min_confidence: f32,
+}
+
+impl Default for OcrOptions {
+    fn default() -> Self {
+        Self {
+            canvas_size: 1280,
+            text_threshold: 0.7,
+            link_threshold: 0.4,
+            low_text: 0.4,
+            merge_lines: true,
+            margin: 0.1,
+            min_confidence: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBox {
+    /// `[x1, y1, x2, y2]` in image pixels.
+    pub bbox: Rect,
+    pub text: String,
+    /// `0..=1`.
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrParameter {
+    /// `detector.` or `recognizer.` followed by the PyTorch name, e.g.
+    /// `detector.basenet.slice1.0.weight`.
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub dtype: String,
+    /// The first values (row-major); BatchNorm is not folded in here.
+    pub values: Vec<f32>,
+    pub stats: Option<TensorDebugStats>,
+}
+
+pub struct OcrModel {
+    net: Box<dyn OcrNet>,
+    charset: Vec<char>,
+    parameters: Vec<OcrParameter>,
+    weights_files: Vec<PathBuf>,
+}
+
+impl OcrModel {
+    /// `source` is a registry name (downloaded on first use) or a folder with
+    /// `craft_mlt_25k.pth` and `english_g2.pth`.
+    pub fn load(source: &str, device: OcrDevice) -> Result<Self, String> {
+        let path = Path::new(source);
+        let dir = if path.is_dir() {
+            path.to_path_buf()
+        } else if path.exists() {
+            return Err(format!(
+                "{source}: expected a folder with {DETECTOR_FILE} and {RECOGNIZER_FILE}"
+            ));
+        } else {
+            crate::ai::models::ensure(source)?
+        };
+        let mut detector = Checkpoint::load(&dir.join(DETECTOR_FILE))?;
+        let mut recognizer = Checkpoint::load(&dir.join(RECOGNIZER_FILE))?;
+        let parameters = [("detector", &detector), ("recognizer", &recognizer)]
+            .into_iter()
+            .flat_map(|(prefix, ckpt)| {
+                ckpt.tensors.iter().map(move |(name, t)| OcrParameter {
+                    name: format!("{prefix}.{name}"),
+                    shape: t.shape.clone(),
+                    dtype: t.dtype.clone(),
+                    values: t.data.iter().take(PARAMETER_HEAD).copied().collect(),
+                    stats: model::stats(&t.data),
+                })
+            })
+            .collect();
+        let weights_files = vec![detector.file.clone(), recognizer.file.clone()];
+        let net: Box<dyn OcrNet> = match device {
+            OcrDevice::Cpu => Box::new(OcrRuntime::<NdArray>::load(
+                &mut detector,
+                &mut recognizer,
+                NdArrayDevice::Cpu,
+            )?),
+            OcrDevice::Gpu => Box::new(OcrRuntime::<Wgpu>::load(
+                &mut detector,
+                &mut recognizer,
+                WgpuDevice::default(),
+            )?),
+        };
+        let charset: Vec<char> = ENGLISH_G2.chars().collect();
+        if net.classes() != charset.len() + 1 {
+            return Err(format!(
+                "{RECOGNIZER_FILE} predicts {} classes; the english_g2 character set needs {}",
+                net.classes(),
+                charset.len() + 1
+            ));
+        }
+        Ok(Self {
+            net,
+            charset,
+            parameters,
+            weights_files,
+        })
+    }
+
+    pub fn parameters(&self) -> &[OcrParameter] {
+        &self.parameters
+    }
+
+    /// Detector then recognizer checkpoint.
+    pub fn weights_files(&self) -> &[PathBuf] {
+        &self.weights_files
+    }
+
+    /// Characters the recognizer can produce.
+    pub fn charset(&self) -> String {
+        self.charset.iter().collect()
+    }
+
+    /// Text boxes in `img` (lines, or words without `merge_lines`), in image pixels.
+    pub fn detect(&self, img: &Image, options: &OcrOptions) -> Result<Vec<Rect>, String> {
+        let (input, h, w, scale) = image::detector_input(img, options.canvas_size);
+        let (region, affinity) = self.net.detect(input, h, w)?;
+        let words = postprocess::find_boxes(
+            &region,
+            &affinity,
+            (h / 2, w / 2),
+            options.text_threshold,
+            options.link_threshold,
+            options.low_text,
+        );
+        // Score maps are half the input resolution.
+        let words = words
+            .into_iter()
+            .map(|b| b.map(|v| v * 2.0 * scale))
+            .collect();
+        let boxes = if options.merge_lines {
+            postprocess::merge_lines(words, options.margin)
+        } else {
+            words
+        };
+        let (iw, ih) = (img.width as f32, img.height as f32);
+        Ok(boxes
+            .into_iter()
+            .map(|[x1, y1, x2, y2]| [x1.min(iw), y1.min(ih), x2.min(iw), y2.min(ih)])
+            .filter(|[x1, y1, x2, y2]| x2 > x1 && y2 > y1)
+            .collect())
+    }
+
+    /// Read the text inside `bbox`: `(text, confidence)`.
+    pub fn recognize(&self, img: &Image, bbox: Rect) -> Result<(String, f32), String> {
+        self.recognize_gray(&img.gray(), img, bbox)
+    }
+
+    fn recognize_gray(
+        &self,
+        gray: &[f32],
+        img: &Image,
+        bbox: Rect,
+    ) -> Result<(String, f32), String> {
+        let Some((input, w)) =
+            image::recognizer_input(gray, (img.height, img.width), bbox, MIN_CROP_WIDTH)
+        else {
+            return Ok((String::new(), 0.0));
+        };
+        let probs = self.net.recognize(input, w)?;
+        Ok(postprocess::ctc_greedy(
+            &probs,
+            self.net.classes(),
+            &self.charset,
+        ))
+    }
+
+    /// Detect and read all text in `img`, top to bottom.
+    pub fn read(&self, img: &Image, options: &OcrOptions) -> Result<Vec<TextBox>, String> {
+        let gray = img.gray();
+        let mut out = Vec::new();
+        for bbox in self.detect(img, options)? {
+            let (text, confidence) = self.recognize_gray(&gray, img, bbox)?;
+            let text = text.trim().to_string();
+            if !text.is_empty() && confidence >= options.min_confidence {
+                out.push(TextBox {
+                    bbox,
+                    text,
+                    confidence,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Every detector stage on `img`, then every recognizer stage on the first box found.
+    pub fn forward_layer_by_layer(
+        &self,
+        img: &Image,
+        options: &OcrOptions,
+    ) -> Result<Vec<ActivationStep>, String> {
+        let (input, h, w, _) = image::detector_input(img, options.canvas_size);
+        let mut steps = self.net.detect_layer_by_layer(input, h, w)?;
+        let first = self.detect(img, options)?.into_iter().next();
+        if let Some((input, w)) = first.and_then(|bbox| {
+            image::recognizer_input(&img.gray(), (img.height, img.width), bbox, MIN_CROP_WIDTH)
+        }) {
+            steps.extend(self.net.recognize_layer_by_layer(input, w)?);
+        }
+        Ok(steps)
+    }
+}
@@ -0,0 +1,768 @@
+//! The two EasyOCR networks in Burn, generic over the backend (NdArray on the CPU, WGPU on the GPU):
+//!
+//! - CRAFT, the text detector: a VGG16-BN trunk and a U-Net decoder that score every half-resolution
+//!   pixel for "inside a character" (region) and "between two characters" (affinity).
+//! - The generation-2 recognizer: a small VGG over a 64-pixel-high grayscale crop, two
+//!   bidirectional LSTMs and a per-column classifier decoded with CTC.
+//!
+//! BatchNorm layers are folded into the preceding convolutions on load (inference only), and
+//! channel counts come from the checkpoint's shapes.
+
+use burn::{
+    module::{Module, Param},
+    tensor::{
+        activation::{relu, sigmoid, softmax, tanh},
+        backend::Backend,
+        module::{conv2d, max_pool2d},
+        ops::ConvOptions,
+        Tensor, TensorData,
+    },
+};
+
+use super::image::{interpolation, RECOGNIZER_HEIGHT};
+use super::weights::{Checkpoint, WeightTensor};
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+
+const BATCH_NORM_EPS: f32 = 1e-5;
+
+#[derive(Module, Debug)]
+struct Conv<B: Backend> {
+    weight: Param<Tensor<B, 4>>,
+    bias: Param<Tensor<B, 1>>,
+    padding: usize,
+    dilation: usize,
+}
+
+impl<B: Backend> Conv<B> {
+    /// `{name}.weight` (+ `{name}.bias`), with the BatchNorm at `bn` folded in.
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        bn: Option<&str>,
+        padding: usize,
+        dilation: usize,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let w = ckpt.take(&format!("{name}.weight"))?;
+        let [out_c, in_c, kh, kw] = match w.shape[..] {
+            [o, i, h, w] => [o, i, h, w],
+            _ => return Err(format!("{name}.weight: expected 4-D, got {:?}", w.shape)),
+        };
+        let mut weight = w.data;
+        let bias_name = format!("{name}.bias");
+        let mut bias = if ckpt.has(&bias_name) {
+            ckpt.take(&bias_name)?.data
+        } else {
+            vec![0.0; out_c]
+        };
+        if let Some(bn) = bn {
+            let mut vec = |p: &str| ckpt.take(&format!("{bn}.{p}")).map(|t| t.data);
+            let (gamma, beta) = (vec("weight")?, vec("bias")?);
+            let (mean, var) = (vec("running_mean")?, vec("running_var")?);
+            let per_out = in_c * kh * kw;
+            for o in 0..out_c {
+                let scale = gamma[o] / (var[o] + BATCH_NORM_EPS).sqrt();
+                weight[o * per_out..(o + 1) * per_out]
+                    .iter_mut()
+                    .for_each(|v| *v *= scale);
+                bias[o] = (bias[o] - mean[o]) * scale + beta[o];
+            }
+        }
+        Ok(Self {
+            weight: Param::from_tensor(Tensor::from_data(
+                TensorData::new(weight, [out_c, in_c, kh, kw]),
+                device,
+            )),
+            bias: Param::from_tensor(Tensor::from_data(TensorData::new(bias, [out_c]), device)),
+            padding,
+            dilation,
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 4>) -> Tensor<B, 4> {
+        let options = ConvOptions::new(
+            [1, 1],
+            [self.padding, self.padding],
+            [self.dilation, self.dilation],
+            1,
+        );
+        conv2d(x, self.weight.val(), Some(self.bias.val()), options)
+    }
+}
+
+fn pool<B: Backend>(x: Tensor<B, 4>, kernel: [usize; 2]) -> Tensor<B, 4> {
+    max_pool2d(x, kernel, kernel, [0, 0], [1, 1], false)
+}
+
+/// `F.interpolate(x, size, mode="bilinear", align_corners=False)` as two matrix products.
+fn upsample<B: Backend>(x: Tensor<B, 4>, [out_h, out_w]: [usize; 2]) -> Tensor<B, 4> {
+    let [_, _, h, w] = x.dims();
+    let device = x.device();
+    let matrix = |out: usize, len: usize| {
+        let mut m = vec![0.0; out * len];
+        for (o, (lo, hi, f)) in interpolation(out, len).into_iter().enumerate() {
+            m[o * len + lo] += 1.0 - f;
+            m[o * len + hi] += f;
+        }
+        m
+    };
+    let rows = Tensor::<B, 2>::from_data(TensorData::new(matrix(out_h, h), [out_h, h]), &device);
+    let cols = Tensor::<B, 2>::from_data(TensorData::new(matrix(out_w, w), [out_w, w]), &device);
+    rows.unsqueeze::<4>()
+        .matmul(x.matmul(cols.transpose().unsqueeze::<4>()))
+}
+
+/// CRAFT's decoder block: 1x1 conv over (upsampled, skip) features, then 3x3 conv.
+#[derive(Module, Debug)]
+struct DoubleConv<B: Backend> {
+    reduce: Conv<B>,
+    conv: Conv<B>,
+}
+
+impl<B: Backend> DoubleConv<B> {
+    fn load(ckpt: &mut Checkpoint, name: &str, device: &B::Device) -> Result<Self, String> {
+        Ok(Self {
+            reduce: Conv::load(
+                ckpt,
+                &format!("{name}.conv.0"),
+                Some(&format!("{name}.conv.1")),
+                0,
+                1,
+                device,
+            )?,
+            conv: Conv::load(
+                ckpt,
+                &format!("{name}.conv.3"),
+                Some(&format!("{name}.conv.4")),
+                1,
+                1,
+                device,
+            )?,
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 4>) -> Tensor<B, 4> {
+        relu(self.conv.forward(relu(self.reduce.forward(x))))
+    }
+}
+
+#[derive(Module, Debug)]
+pub struct Craft<B: Backend> {
+    /// The VGG16-BN convolutions the trunk keeps, in order.
+    vgg: Vec<Conv<B>>,
+    fc6: Conv<B>,
+    fc7: Conv<B>,
+    up: Vec<DoubleConv<B>>,
+    cls: Vec<Conv<B>>,
+}
+
+/// `(slice, conv index)` of each VGG convolution; its BatchNorm is the next index.
+const VGG_CONVS: [(usize, usize); 12] = [
+    (1, 0),
+    (1, 3),
+    (1, 7),
+    (1, 10),
+    (2, 14),
+    (2, 17),
+    (3, 20),
+    (3, 24),
+    (3, 27),
+    (4, 30),
+    (4, 34),
+    (4, 37),
+];
+
+impl<B: Backend> Craft<B> {
+    pub fn load(ckpt: &mut Checkpoint, device: &B::Device) -> Result<Self, String> {
+        let vgg = VGG_CONVS
+            .iter()
+            .map(|&(slice, i)| {
+                Conv::load(
+                    ckpt,
+                    &format!("basenet.slice{slice}.{i}"),
+                    Some(&format!("basenet.slice{slice}.{}", i + 1)),
+                    1,
+                    1,
+                    device,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        let up = (1..=4)
+            .map(|i| DoubleConv::load(ckpt, &format!("upconv{i}"), device))
+            .collect::<Result<_, _>>()?;
+        let cls = [(0, 1), (2, 1), (4, 1), (6, 0), (8, 0)]
+            .iter()
+            .map(|&(i, pad)| Conv::load(ckpt, &format!("conv_cls.{i}"), None, pad, 1, device))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            vgg,
+            fc6: Conv::load(ckpt, "basenet.slice5.1", None, 6, 6, device)?,
+            fc7: Conv::load(ckpt, "basenet.slice5.2", None, 0, 1, device)?,
+            up,
+            cls,
+        })
+    }
+
+    /// `x`: `[1, 3, h, w]` (`h`, `w` multiples of 32) → named stages, the last being the
+    /// `[1, 2, h/2, w/2]` region / affinity scores.
+    fn stages(&self, x: Tensor<B, 4>) -> Vec<(String, Tensor<B, 4>)> {
+        let v = &self.vgg;
+        let mut out = Vec::new();
+        // Slices end on a BatchNorm (the ReLU opens the next slice), as in CRAFT's vgg16_bn.
+        let s1 = v[3].forward(relu(
+            v[2].forward(pool(relu(v[1].forward(relu(v[0].forward(x)))), [2, 2])),
+        ));
+        let s2 = v[5].forward(relu(v[4].forward(pool(relu(s1.clone()), [2, 2]))));
+        let s3 = v[8].forward(relu(
+            v[7].forward(pool(relu(v[6].forward(relu(s2.clone()))), [2, 2])),
+        ));
+        let s4 = v[11].forward(relu(
+            v[10].forward(pool(relu(v[9].forward(relu(s3.clone()))), [2, 2])),
+        ));
+        let s5 = self.fc7.forward(self.fc6.forward(max_pool2d(
+            s4.clone(),
+            [3, 3],
+            [1, 1],
+            [1, 1],
+            [1, 1],
+            false,
+        )));
+        let skips = [s4.clone(), s3.clone(), s2.clone(), s1.clone()];
+        for (i, s) in [s1, s2, s3, s4, s5.clone()].into_iter().enumerate() {
+            out.push((format!("basenet.slice{}", i + 1), s));
+        }
+        let mut y = s5;
+        for (i, (block, skip)) in self.up.iter().zip(&skips).enumerate() {
+            if i > 0 {
+                let [_, _, h, w] = skip.dims();
+                y = upsample(y, [h, w]);
+            }
+            y = block.forward(Tensor::cat(vec![y, skip.clone()], 1));
+            out.push((format!("upconv{}", i + 1), y.clone()));
+        }
+        let last = self.cls.len() - 1;
+        for (i, conv) in self.cls.iter().enumerate() {
+            y = conv.forward(y);
+            if i < last {
+                y = relu(y);
+            }
+        }
+        out.push(("conv_cls".to_string(), y));
+        out
+    }
+}
+
+/// `nn.Linear`, weight stored transposed as `[in, out]`.
+#[derive(Module, Debug)]
+struct Linear<B: Backend> {
+    weight: Param<Tensor<B, 2>>,
+    bias: Param<Tensor<B, 1>>,
+}
+
+impl<B: Backend> Linear<B> {
+    fn load(weight: WeightTensor, bias: WeightTensor, device: &B::Device) -> Result<Self, String> {
+        let [rows, cols] = match weight.shape[..] {
+            [r, c] => [r, c],
+            _ => return Err(format!("expected a 2-D weight, got {:?}", weight.shape)),
+        };
+        Ok(Self {
+            weight: Param::from_tensor(
+                Tensor::<B, 2>::from_data(TensorData::new(weight.data, [rows, cols]), device)
+                    .transpose(),
+            ),
+            bias: Param::from_tensor(Tensor::from_data(
+                TensorData::new(bias.data, [rows]),
+                device,
+            )),
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        x.matmul(self.weight.val()) + self.bias.val().unsqueeze()
+    }
+}
+
+/// One direction of `nn.LSTM` (gates `i, f, g, o`).
+#[derive(Module, Debug)]
+struct Lstm<B: Backend> {
+    input: Linear<B>,
+    hidden: Linear<B>,
+}
+
+impl<B: Backend> Lstm<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        prefix: &str,
+        suffix: &str,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let mut take = |p: &str| ckpt.take(&format!("{prefix}.{p}_l0{suffix}"));
+        Ok(Self {
+            input: Linear::load(take("weight_ih")?, take("bias_ih")?, device)?,
+            hidden: Linear::load(take("weight_hh")?, take("bias_hh")?, device)?,
+        })
+    }
+
+    /// `x`: `[steps, in]` → `[steps, hidden]`, run back to front when `reverse`.
+    fn forward(&self, x: Tensor<B, 2>, reverse: bool) -> Tensor<B, 2> {
+        let [steps, _] = x.dims();
+        let [_, gates] = self.hidden.weight.dims();
+        let n = gates / 4;
+        let projected = self.input.forward(x);
+        let mut h = Tensor::<B, 2>::zeros([1, n], &projected.device());
+        let mut c = h.clone();
+        let mut outputs = vec![h.clone(); steps];
+        for t in 0..steps {
+            let t = if reverse { steps - 1 - t } else { t };
+            let z = projected.clone().narrow(0, t, 1) + self.hidden.forward(h);
+            let i = sigmoid(z.clone().narrow(1, 0, n));
+            let f = sigmoid(z.clone().narrow(1, n, n));
+            let g = tanh(z.clone().narrow(1, 2 * n, n));
+            let o = sigmoid(z.narrow(1, 3 * n, n));
+            c = f * c + i * g;
+            h = o * tanh(c.clone());
+            outputs[t] = h.clone();
+        }
+        Tensor::cat(outputs, 0)
+    }
+}
+
+#[derive(Module, Debug)]
+struct BidirectionalLstm<B: Backend> {
+    forward: Lstm<B>,
+    backward: Lstm<B>,
+    linear: Linear<B>,
+}
+
+impl<B: Backend> BidirectionalLstm<B> {
+    fn load(ckpt: &mut Checkpoint, name: &str, device: &B::Device) -> Result<Self, String> {
+        let rnn = format!("{name}.rnn");
+        Ok(Self {
+            forward: Lstm::load(ckpt, &rnn, "", device)?,
+            backward: Lstm::load(ckpt, &rnn, "_reverse", device)?,
+            linear: Linear::load(
+                ckpt.take(&format!("{name}.linear.weight"))?,
+                ckpt.take(&format!("{name}.linear.bias"))?,
+                device,
+            )?,
+        })
+    }
+
+    fn run(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        let both = Tensor::cat(
+            vec![
+                self.forward.forward(x.clone(), false),
+                self.backward.forward(x, true),
+            ],
+            1,
+        );
+        self.linear.forward(both)
+    }
+}
+
+#[derive(Module, Debug)]
+pub struct Recognizer<B: Backend> {
+    convs: Vec<Conv<B>>,
+    sequence: Vec<BidirectionalLstm<B>>,
+    prediction: Linear<B>,
+}
+
+impl<B: Backend> Recognizer<B> {
+    pub fn load(ckpt: &mut Checkpoint, device: &B::Device) -> Result<Self, String> {
+        let mut convs = [
+            (0, None),
+            (3, None),
+            (6, None),
+            (8, None),
+            (11, Some(12)),
+            (14, Some(15)),
+        ]
+        .iter()
+        .map(|&(i, bn)| {
+            let bn = bn.map(|b| format!("FeatureExtraction.ConvNet.{b}"));
+            Conv::load(
+                ckpt,
+                &format!("FeatureExtraction.ConvNet.{i}"),
+                bn.as_deref(),
+                1,
+                1,
+                device,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+        // The last conv (2x2) is unpadded.
+        convs.push(Conv::load(
+            ckpt,
+            "FeatureExtraction.ConvNet.18",
+            None,
+            0,
+            1,
+            device,
+        )?);
+        let sequence = (0..2)
+            .map(|i| BidirectionalLstm::load(ckpt, &format!("SequenceModeling.{i}"), device))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            convs,
+            sequence,
+            prediction: Linear::load(
+                ckpt.take("Prediction.weight")?,
+                ckpt.take("Prediction.bias")?,
+                device,
+            )?,
+        })
+    }
+
+    pub fn classes(&self) -> usize {
+        self.prediction.weight.dims()[1]
+    }
+
+    /// `x`: `[1, 1, RECOGNIZER_HEIGHT, w]` → named stages, the last being `[steps, classes]` probabilities.
+    fn stages(&self, x: Tensor<B, 4>) -> Vec<(String, Tensor<B, 3>)> {
+        let c = &self.convs;
+        let mut out = Vec::new();
+        let x = pool(relu(c[0].forward(x)), [2, 2]);
+        let x = pool(relu(c[1].forward(x)), [2, 2]);
+        let x = pool(relu(c[3].forward(relu(c[2].forward(x)))), [2, 1]);
+        let x = pool(relu(c[5].forward(relu(c[4].forward(x)))), [2, 1]);
+        let x = relu(c[6].forward(x));
+        let [_, channels, _, steps] = x.dims();
+        out.push((
+            "FeatureExtraction".to_string(),
+            x.clone().squeeze_dim::<3>(0),
+        ));
+        // AdaptiveAvgPool2d((None, 1)) over the permuted map: average the rows of each column.
+        let mut y = x.mean_dim(2).reshape([channels, steps]).transpose();
+        out.push(("AdaptiveAvgPool".to_string(), y.clone().unsqueeze()));
+        for (i, block) in self.sequence.iter().enumerate() {
+            y = block.run(y);
+            out.push((format!("SequenceModeling.{i}"), y.clone().unsqueeze()));
+        }
+        let probs = softmax(self.prediction.forward(y), 1);
+        out.push(("Prediction".to_string(), probs.unsqueeze()));
+        out
+    }
+}
+
+/// Backend-erased access to a loaded detector + recognizer pair.
+pub trait OcrNet {
+    /// `input`: normalized RGB, planar `[3, h, w]` → region and affinity scores, `[h/2, w/2]` each.
+    fn detect(&self, input: Vec<f32>, h: usize, w: usize) -> Result<(Vec<f32>, Vec<f32>), String>;
+    /// `input`: normalized gray `[64, w]` → per-column class probabilities `[steps, classes]`.
+    fn recognize(&self, input: Vec<f32>, w: usize) -> Result<Vec<f32>, String>;
+    fn classes(&self) -> usize;
+    fn detect_layer_by_layer(
+        &self,
+        input: Vec<f32>,
+        h: usize,
+        w: usize,
+    ) -> Result<Vec<ActivationStep>, String>;
+    fn recognize_layer_by_layer(
+        &self,
+        input: Vec<f32>,
+        w: usize,
+    ) -> Result<Vec<ActivationStep>, String>;
+}
+
+pub struct OcrRuntime<B: Backend> {
+    detector: Craft<B>,
+    recognizer: Recognizer<B>,
+    device: B::Device,
+}
+
+impl<B: Backend> OcrRuntime<B> {
+    pub fn load(
+        detector: &mut Checkpoint,
+        recognizer: &mut Checkpoint,
+        device: B::Device,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            detector: Craft::load(detector, &device)?,
+            recognizer: Recognizer::load(recognizer, &device)?,
+            device,
+        })
+    }
+
+    fn image(&self, input: Vec<f32>, shape: [usize; 4]) -> Tensor<B, 4> {
+        Tensor::from_data(TensorData::new(input, shape), &self.device)
+    }
+}
+
+impl<B: Backend> OcrNet for OcrRuntime<B> {
+    fn detect(&self, input: Vec<f32>, h: usize, w: usize) -> Result<(Vec<f32>, Vec<f32>), String> {
+        let x = self.image(input, [1, 3, h, w]);
+        let (_, scores) = self
+            .detector
+            .stages(x)
+            .pop()
+            .ok_or_else(|| "detector produced no output".to_string())?;
+        let mut scores = to_host(scores)?;
+        let affinity = scores.split_off(scores.len() / 2);
+        Ok((scores, affinity))
+    }
+
+    fn recognize(&self, input: Vec<f32>, w: usize) -> Result<Vec<f32>, String> {
+        let x = self.image(input, [1, 1, RECOGNIZER_HEIGHT, w]);
+        let (_, probs) = self
+            .recognizer
+            .stages(x)
+            .pop()
+            .ok_or_else(|| "recognizer produced no output".to_string())?;
+        to_host(probs)
+    }
+
+    fn classes(&self) -> usize {
+        self.recognizer.classes()
+    }
+
+    fn detect_layer_by_layer(
+        &self,
+        input: Vec<f32>,
+        h: usize,
+        w: usize,
+    ) -> Result<Vec<ActivationStep>, String> {
+        let x = self.image(input, [1, 3, h, w]);
+        self.detector
+            .stages(x)
+            .into_iter()
+            .map(|(name, t)| step(&format!("detector.{name}"), t))
+            .collect()
+    }
+
+    fn recognize_layer_by_layer(
+        &self,
+        input: Vec<f32>,
+        w: usize,
+    ) -> Result<Vec<ActivationStep>, String> {
+        let x = self.image(input, [1, 1, RECOGNIZER_HEIGHT, w]);
+        self.recognizer
+            .stages(x)
+            .into_iter()
+            .map(|(name, t)| step(&format!("recognizer.{name}"), t))
+            .collect()
+    }
+}
+
+fn to_host<B: Backend, const D: usize>(t: Tensor<B, D>) -> Result<Vec<f32>, String> {
+    t.into_data()
+        .convert::<f32>()
+        .to_vec::<f32>()
+        .map_err(|e| format!("read back tensor: {e:?}"))
+}
+
+fn step<B: Backend, const D: usize>(name: &str, x: Tensor<B, D>) -> Result<ActivationStep, String> {
+    let shape = x.dims().to_vec();
+    let values = to_host(x)?;
+    Ok(ActivationStep {
+        name: Some(name.to_string()),
+        shape,
+        dtype: "float32".to_string(),
+        full_stats: stats(&values),
+        values,
+        device_preflight: None,
+    })
+}
+
+pub(super) fn stats(values: &[f32]) -> Option<TensorDebugStats> {
+    let finite: Vec<f64> = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .map(|&v| v as f64)
+        .collect();
+    if finite.is_empty() {
+        return None;
+    }
+    let n = finite.len() as f64;
+    let mean = finite.iter().sum::<f64>() / n;
+    let var = finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    Some(TensorDebugStats {
+        mean: mean as f32,
+        std: var.sqrt() as f32,
+        min: finite.iter().copied().fold(f64::INFINITY, f64::min) as f32,
+        max: finite.iter().copied().fold(f64::NEG_INFINITY, f64::max) as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use burn::backend::{ndarray::NdArrayDevice, NdArray};
+
+    use super::*;
+
+    /// A checkpoint with EasyOCR's tensor names but tiny channel counts.
+    fn checkpoint(shapes: &[(String, Vec<usize>)]) -> Checkpoint {
+        let mut seed = 7u32;
+        let mut tensors = BTreeMap::new();
+        for (name, shape) in shapes {
+            let data = (0..shape.iter().product::<usize>())
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    let r = (seed >> 8) as f32 / (1u32 << 24) as f32;
+                    // Keep BatchNorm variances positive.
+                    if name.ends_with("running_var") {
+                        0.5 + r
+                    } else {
+                        r - 0.5
+                    }
+                })
+                .collect();
+            let tensor = WeightTensor {
+                shape: shape.clone(),
+                dtype: "f32".to_string(),
+                data,
+            };
+            tensors.insert(name.clone(), tensor);
+        }
+        Checkpoint {
+            tensors,
+            file: PathBuf::from("test.pth"),
+        }
+    }
+
+    fn conv(out: &mut Vec<(String, Vec<usize>)>, name: &str, shape: [usize; 4], bn: Option<&str>) {
+        out.push((format!("{name}.weight"), shape.to_vec()));
+        out.push((format!("{name}.bias"), vec![shape[0]]));
+        if let Some(bn) = bn {
+            for p in ["weight", "bias", "running_mean", "running_var"] {
+                out.push((format!("{bn}.{p}"), vec![shape[0]]));
+            }
+        }
+    }
+
+    fn tiny_craft() -> Checkpoint {
+        let mut s = Vec::new();
+        let chans = [4, 4, 4, 4, 6, 6, 8, 8, 8, 8, 8, 8];
+        let mut prev = 3;
+        for (&(slice, i), &c) in VGG_CONVS.iter().zip(&chans) {
+            let bn = format!("basenet.slice{slice}.{}", i + 1);
+            conv(
+                &mut s,
+                &format!("basenet.slice{slice}.{i}"),
+                [c, prev, 3, 3],
+                Some(&bn),
+            );
+            prev = c;
+        }
+        conv(&mut s, "basenet.slice5.1", [10, 8, 3, 3], None);
+        conv(&mut s, "basenet.slice5.2", [10, 10, 1, 1], None);
+        // (input, mid, out) per block: fc7 + slice4, then + slice3, slice2, slice1.
+        for (i, (inp, mid, out)) in [(18, 8, 6), (14, 6, 5), (11, 5, 4), (8, 4, 3)]
+            .into_iter()
+            .enumerate()
+        {
+            let name = format!("upconv{}", i + 1);
+            conv(
+                &mut s,
+                &format!("{name}.conv.0"),
+                [mid, inp, 1, 1],
+                Some(&format!("{name}.conv.1")),
+            );
+            conv(
+                &mut s,
+                &format!("{name}.conv.3"),
+                [out, mid, 3, 3],
+                Some(&format!("{name}.conv.4")),
+            );
+        }
+        for (i, shape) in [
+            [3, 3, 3, 3],
+            [3, 3, 3, 3],
+            [2, 3, 3, 3],
+            [2, 2, 1, 1],
+            [2, 2, 1, 1],
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            conv(&mut s, &format!("conv_cls.{}", 2 * i), shape, None);
+        }
+        checkpoint(&s)
+    }
+
+    fn tiny_recognizer(classes: usize) -> Checkpoint {
+        let mut s = Vec::new();
+        let f = "FeatureExtraction.ConvNet";
+        conv(&mut s, &format!("{f}.0"), [2, 1, 3, 3], None);
+        conv(&mut s, &format!("{f}.3"), [3, 2, 3, 3], None);
+        conv(&mut s, &format!("{f}.6"), [4, 3, 3, 3], None);
+        conv(&mut s, &format!("{f}.8"), [4, 4, 3, 3], None);
+        conv(
+            &mut s,
+            &format!("{f}.11"),
+            [5, 4, 3, 3],
+            Some(&format!("{f}.12")),
+        );
+        conv(
+            &mut s,
+            &format!("{f}.14"),
+            [5, 5, 3, 3],
+            Some(&format!("{f}.15")),
+        );
+        conv(&mut s, &format!("{f}.18"), [5, 5, 2, 2], None);
+        for (i, inp) in [5, 3].into_iter().enumerate() {
+            let rnn = format!("SequenceModeling.{i}.rnn");
+            for dir in ["", "_reverse"] {
+                s.push((format!("{rnn}.weight_ih_l0{dir}"), vec![12, inp]));
+                s.push((format!("{rnn}.weight_hh_l0{dir}"), vec![12, 3]));
+                s.push((format!("{rnn}.bias_ih_l0{dir}"), vec![12]));
+                s.push((format!("{rnn}.bias_hh_l0{dir}"), vec![12]));
+            }
+            s.push((format!("SequenceModeling.{i}.linear.weight"), vec![3, 6]));
+            s.push((format!("SequenceModeling.{i}.linear.bias"), vec![3]));
+        }
+        s.push(("Prediction.weight".to_string(), vec![classes, 3]));
+        s.push(("Prediction.bias".to_string(), vec![classes]));
+        checkpoint(&s)
+    }
+
+    fn ramp(n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| ((i * 37) % 101) as f32 / 50.0 - 1.0)
+            .collect()
+    }
+
+    #[test]
+    fn upsample_matches_pytorch_bilinear() {
+        let x = Tensor::<NdArray, 4>::from_data(
+            TensorData::new(vec![0.0, 1.0, 2.0], [1, 1, 1, 3]),
+            &NdArrayDevice::Cpu,
+        );
+        let y = to_host(upsample(x, [2, 6])).unwrap();
+        let row = [0.0, 0.25, 0.75, 1.25, 1.75, 2.0];
+        assert_eq!(y, [row, row].concat());
+    }
+
+    #[test]
+    fn tiny_networks_produce_scores_and_probabilities() {
+        let runtime = OcrRuntime::<NdArray>::load(
+            &mut tiny_craft(),
+            &mut tiny_recognizer(4),
+            NdArrayDevice::Cpu,
+        )
+        .unwrap();
+        let (region, affinity) = runtime.detect(ramp(3 * 64 * 32), 64, 32).unwrap();
+        assert_eq!((region.len(), affinity.len()), (32 * 16, 32 * 16));
+        let steps = runtime
+            .detect_layer_by_layer(ramp(3 * 64 * 32), 64, 32)
+            .unwrap();
+        assert_eq!(steps.len(), 10);
+        assert_eq!(steps[9].shape, vec![1, 2, 32, 16]);
+        let last = steps[9].values.split_at(32 * 16);
+        assert!(last
+            .0
+            .iter()
+            .zip(&region)
+            .all(|(a, b)| (a - b).abs() < 1e-5));
+
+        assert_eq!(runtime.classes(), 4);
+        let probs = runtime.recognize(ramp(64 * 40), 40).unwrap();
+        // Width 40 → 10 columns after two 2x2 pools, 9 after the final 2x2 valid conv.
+        assert_eq!(probs.len(), 9 * 4);
+        for row in probs.chunks(4) {
+            assert!((row.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        }
+    }
+}
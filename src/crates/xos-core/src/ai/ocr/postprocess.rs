@@ -0,0 +1,230 @@
+//! Turning network outputs into results: CRAFT score maps into word boxes (EasyOCR's
+//! `getDetBoxes`, axis-aligned), words into lines, and recognizer logits into text (greedy CTC).
+
+/// An axis-aligned box `[x1, y1, x2, y2]`, end-exclusive.
+pub type Rect = [f32; 4];
+
+/// Word boxes in score-map pixels. A word is a 4-connected blob of `region > low_text` or
+/// `affinity > link_threshold` whose peak region score reaches `text_threshold`.
+pub fn find_boxes(
+    region: &[f32],
+    affinity: &[f32],
+    (h, w): (usize, usize),
+    text_threshold: f32,
+    link_threshold: f32,
+    low_text: f32,
+) -> Vec<Rect> {
+    let text: Vec<bool> = region.iter().map(|&v| v > low_text).collect();
+    let link: Vec<bool> = affinity.iter().map(|&v| v > link_threshold).collect();
+    let mut label = vec![0u32; h * w];
+    let mut boxes = Vec::new();
+    let mut stack = Vec::new();
+    let mut next = 0;
+    for start in 0..h * w {
+        if label[start] != 0 || !(text[start] || link[start]) {
+            continue;
+        }
+        next += 1;
+        label[start] = next;
+        stack.push(start);
+        let mut pixels = Vec::new();
+        while let Some(i) = stack.pop() {
+            pixels.push(i);
+            let (y, x) = (i / w, i % w);
+            let mut visit = |j: usize| {
+                if label[j] == 0 && (text[j] || link[j]) {
+                    label[j] = next;
+                    stack.push(j);
+                }
+            };
+            if x > 0 {
+                visit(i - 1);
+            }
+            if x + 1 < w {
+                visit(i + 1);
+            }
+            if y > 0 {
+                visit(i - w);
+            }
+            if y + 1 < h {
+                visit(i + w);
+            }
+        }
+        let size = pixels.len();
+        let peak = pixels.iter().map(|&i| region[i]).fold(f32::MIN, f32::max);
+        if size < 10 || peak < text_threshold {
+            continue;
+        }
+        let bounds = |keep: &dyn Fn(usize) -> bool| {
+            pixels.iter().filter(|&&i| keep(i)).fold(
+                [usize::MAX, usize::MAX, 0, 0],
+                |[x1, y1, x2, y2], &i| {
+                    let (y, x) = (i / w, i % w);
+                    [x1.min(x), y1.min(y), x2.max(x + 1), y2.max(y + 1)]
+                },
+            )
+        };
+        let [bx1, by1, bx2, by2] = bounds(&|_| true);
+        // Pixels that are only link (between words' characters) do not extend the word.
+        let [x1, y1, x2, y2] = bounds(&|i| text[i] || !link[i]);
+        if x1 >= x2 || y1 >= y2 {
+            continue;
+        }
+        // Grow by a margin that scales with the blob's thickness, as EasyOCR's dilation does.
+        let (bw, bh) = (bx2 - bx1, by2 - by1);
+        let niter = (((size * bw.min(bh)) as f32 / (bw * bh) as f32).sqrt() * 2.0) as usize;
+        let before = niter.div_ceil(2);
+        let after = niter - before;
+        boxes.push([
+            x1.saturating_sub(before) as f32,
+            y1.saturating_sub(before) as f32,
+            (x2 + after).min(w) as f32,
+            (y2 + after).min(h) as f32,
+        ]);
+    }
+    boxes
+}
+
+/// Merge word boxes into text lines: boxes whose vertical centres and heights roughly agree form a
+/// line, and neighbours on a line closer than half a line height join. Each result is padded by
+/// `margin` times its height.
+pub fn merge_lines(mut boxes: Vec<Rect>, margin: f32) -> Vec<Rect> {
+    let height = |b: &Rect| b[3] - b[1];
+    let centre = |b: &Rect| (b[1] + b[3]) / 2.0;
+    boxes.sort_by(|a, b| centre(a).total_cmp(&centre(b)));
+    let mut lines: Vec<Vec<Rect>> = Vec::new();
+    for b in boxes {
+        let joins = lines.last().is_some_and(|line| {
+            let n = line.len() as f32;
+            let mean_h = line.iter().map(height).sum::<f32>() / n;
+            let mean_c = line.iter().map(centre).sum::<f32>() / n;
+            (centre(&b) - mean_c).abs() < 0.5 * mean_h && (height(&b) - mean_h).abs() < 0.5 * mean_h
+        });
+        match lines.last_mut() {
+            Some(line) if joins => line.push(b),
+            _ => lines.push(vec![b]),
+        }
+    }
+    let mut out = Vec::new();
+    for mut line in lines {
+        line.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        let mut merged: Vec<Rect> = Vec::new();
+        for b in line {
+            match merged.last_mut() {
+                Some(m) if b[0] - m[2] < 0.5 * height(m).max(height(&b)) => {
+                    *m = [m[0], m[1].min(b[1]), m[2].max(b[2]), m[3].max(b[3])];
+                }
+                _ => merged.push(b),
+            }
+        }
+        out.extend(merged.into_iter().map(|b| {
+            let pad = margin * height(&b);
+            [
+                (b[0] - pad).max(0.0),
+                (b[1] - pad).max(0.0),
+                b[2] + pad,
+                b[3] + pad,
+            ]
+        }));
+    }
+    out
+}
+
+/// Greedy CTC decoding of softmax rows (`[steps, classes]`, class 0 the blank): best class per
+/// step, repeats collapsed, blanks dropped. Confidence is EasyOCR's: the product of the per-step
+/// maxima raised to `2 / sqrt(steps)`.
+pub fn ctc_greedy(probs: &[f32], classes: usize, charset: &[char]) -> (String, f32) {
+    let mut text = String::new();
+    let mut log_conf = 0.0;
+    let mut prev = 0;
+    let steps = probs.len() / classes;
+    for row in probs.chunks_exact(classes) {
+        let (best, p) = row
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or((0, 0.0), |(i, &p)| (i, p));
+        log_conf += p.max(f32::MIN_POSITIVE).ln();
+        if best != 0 && best != prev {
+            text.extend(charset.get(best - 1));
+        }
+        prev = best;
+    }
+    let confidence = if steps == 0 {
+        0.0
+    } else {
+        (log_conf * 2.0 / (steps as f32).sqrt()).exp()
+    };
+    (text, confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_linked_words_and_drops_weak_blobs() {
+        let (h, w) = (8, 20);
+        let mut region = vec![0.0; h * w];
+        let mut affinity = vec![0.0; h * w];
+        // Two characters joined by a link → one word; a faint blob that never reaches 0.7.
+        for y in 2..6 {
+            for x in 2..5 {
+                region[y * w + x] = 0.9;
+            }
+            for x in 7..10 {
+                region[y * w + x] = 0.8;
+            }
+            for x in 5..7 {
+                affinity[y * w + x] = 0.6;
+            }
+            for x in 14..18 {
+                region[y * w + x] = 0.5;
+            }
+        }
+        let boxes = find_boxes(&region, &affinity, (h, w), 0.7, 0.4, 0.4);
+        assert_eq!(boxes.len(), 1);
+        let [x1, y1, x2, y2] = boxes[0];
+        assert!(x1 <= 2.0 && y1 <= 2.0 && x2 >= 10.0 && y2 >= 6.0);
+        assert!(x2 <= w as f32 && y2 <= h as f32);
+    }
+
+    #[test]
+    fn merges_words_on_a_line_but_not_across_lines() {
+        let words = vec![
+            [50.0, 10.0, 80.0, 30.0],
+            [0.0, 11.0, 45.0, 31.0],
+            [200.0, 10.0, 230.0, 30.0],
+            [0.0, 50.0, 40.0, 70.0],
+        ];
+        let lines = merge_lines(words, 0.0);
+        assert_eq!(
+            lines,
+            vec![
+                [0.0, 10.0, 80.0, 31.0],
+                [200.0, 10.0, 230.0, 30.0],
+                [0.0, 50.0, 40.0, 70.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn ctc_collapses_repeats_and_blanks() {
+        let charset = ['a', 'b'];
+        // Steps: a a blank a b b
+        let rows = [
+            [0.1, 0.8, 0.1],
+            [0.1, 0.8, 0.1],
+            [0.9, 0.05, 0.05],
+            [0.2, 0.7, 0.1],
+            [0.1, 0.1, 0.8],
+            [0.1, 0.1, 0.8],
+        ];
+        let probs: Vec<f32> = rows.concat();
+        let (text, conf) = ctc_greedy(&probs, 3, &charset);
+        assert_eq!(text, "aab");
+        let expected = (0.8f32 * 0.8 * 0.9 * 0.7 * 0.8 * 0.8).powf(2.0 / 6f32.sqrt());
+        assert!((conf - expected).abs() < 1e-5);
+        assert_eq!(ctc_greedy(&[], 3, &charset), (String::new(), 0.0));
+    }
+}
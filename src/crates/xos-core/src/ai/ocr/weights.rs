@@ -0,0 +1,67 @@
+//! EasyOCR checkpoints are PyTorch `state_dict` pickles (`*.pth`), saved from `DataParallel` so
+//! every key carries a `module.` prefix. They are read with burn-store and widened to `f32`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use burn_store::pytorch::PytorchReader;
+
+pub struct WeightTensor {
+    pub shape: Vec<usize>,
+    /// Storage type in the checkpoint (`f32`, `f16`, ...).
+    pub dtype: String,
+    pub data: Vec<f32>,
+}
+
+pub struct Checkpoint {
+    pub tensors: BTreeMap<String, WeightTensor>,
+    pub file: PathBuf,
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let reader = PytorchReader::new(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let mut tensors = BTreeMap::new();
+        for (key, snapshot) in reader.into_tensors() {
+            // BatchNorm's step counter is bookkeeping, not a weight.
+            if key.ends_with("num_batches_tracked") {
+                continue;
+            }
+            let data = snapshot
+                .to_data()
+                .map_err(|e| format!("{key}: {e:?}"))?
+                .convert::<f32>()
+                .to_vec::<f32>()
+                .map_err(|e| format!("{key}: {e:?}"))?;
+            let name = key.strip_prefix("module.").unwrap_or(&key).to_string();
+            tensors.insert(
+                name,
+                WeightTensor {
+                    shape: snapshot.shape.to_vec(),
+                    dtype: format!("{:?}", snapshot.dtype).to_ascii_lowercase(),
+                    data,
+                },
+            );
+        }
+        if tensors.is_empty() {
+            return Err(format!("{} holds no tensors", path.display()));
+        }
+        Ok(Self {
+            tensors,
+            file: path.to_path_buf(),
+        })
+    }
+
+    pub fn take(&mut self, name: &str) -> Result<WeightTensor, String> {
+        self.tensors.remove(name).ok_or_else(|| {
+            format!(
+                "{} has no tensor {name} (not an EasyOCR checkpoint?)",
+                self.file.display()
+            )
+        })
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tensors.contains_key(name)
+    }
+}
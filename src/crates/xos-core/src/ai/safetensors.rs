@@ -0,0 +1,115 @@
+//! Hugging Face safetensors reader: an 8-byte header length, a JSON tensor directory, then raw
+//! little-endian data. F32, F16 and BF16 tensors are widened to `f32`.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: [usize; 2],
+}
+
+#[derive(Debug, Clone)]
+pub struct SafetensorsInfo {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub dtype: String,
+    range: std::ops::Range<usize>,
+}
+
+pub struct SafetensorsFile {
+    pub tensors: Vec<SafetensorsInfo>,
+    bytes: Vec<u8>,
+}
+
+impl SafetensorsFile {
+    pub fn parse(bytes: Vec<u8>) -> Result<Self, String> {
+        let header_len = bytes
+            .get(..8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as usize)
+            .ok_or_else(|| "safetensors file is too short".to_string())?;
+        let header = bytes
+            .get(8..8usize.saturating_add(header_len))
+            .ok_or_else(|| "safetensors header is truncated".to_string())?;
+        let mut entries: BTreeMap<String, serde_json::Value> =
+            serde_json::from_slice(header).map_err(|e| format!("safetensors header: {e}"))?;
+        entries.remove("__metadata__");
+        let data_start = 8 + header_len;
+        let mut tensors = Vec::with_capacity(entries.len());
+        for (name, value) in entries {
+            let entry: Entry =
+                serde_json::from_value(value).map_err(|e| format!("safetensors {name}: {e}"))?;
+            let width = match entry.dtype.as_str() {
+                "F32" => 4,
+                "F16" | "BF16" => 2,
+                other => return Err(format!("{name}: unsupported safetensors dtype {other}")),
+            };
+            let [start, end] = entry.data_offsets;
+            let numel: usize = entry.shape.iter().product();
+            if end < start || end - start != numel * width {
+                return Err(format!(
+                    "{name}: data size does not match shape {:?}",
+                    entry.shape
+                ));
+            }
+            let range = data_start + start..data_start + end;
+            if range.end > bytes.len() {
+                return Err(format!("{name}: tensor data runs past the end of the file"));
+            }
+            tensors.push(SafetensorsInfo {
+                name,
+                shape: entry.shape,
+                dtype: entry.dtype,
+                range,
+            });
+        }
+        Ok(Self { tensors, bytes })
+    }
+
+    pub fn to_f32(&self, info: &SafetensorsInfo) -> Vec<f32> {
+        let raw = &self.bytes[info.range.clone()];
+        match info.dtype.as_str() {
+            "F32" => raw
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect(),
+            "F16" => raw
+                .chunks_exact(2)
+                .map(|b| half::f16::from_le_bytes([b[0], b[1]]).to_f32())
+                .collect(),
+            _ => raw
+                .chunks_exact(2)
+                .map(|b| f32::from_bits((u16::from_le_bytes([b[0], b[1]]) as u32) << 16))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_f32_and_bf16_tensors() {
+        let header = r#"{"__metadata__":{"format":"pt"},
+            "w":{"dtype":"F32","shape":[2],"data_offsets":[0,8]},
+            "b":{"dtype":"BF16","shape":[1,2],"data_offsets":[8,12]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header.as_bytes());
+        bytes.extend(1.0f32.to_le_bytes());
+        bytes.extend((-3.5f32).to_le_bytes());
+        bytes.extend(((2.0f32.to_bits() >> 16) as u16).to_le_bytes());
+        bytes.extend(((0.5f32.to_bits() >> 16) as u16).to_le_bytes());
+        let file = SafetensorsFile::parse(bytes.clone()).unwrap();
+        let names: Vec<&str> = file.tensors.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["b", "w"]);
+        assert_eq!(file.to_f32(&file.tensors[0]), vec![2.0, 0.5]);
+        assert_eq!(file.to_f32(&file.tensors[1]), vec![1.0, -3.5]);
+
+        bytes.truncate(bytes.len() - 1);
+        assert!(SafetensorsFile::parse(bytes).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! Long-form (file) transcription: split the audio into chunks of at most one Whisper window at
+//! pauses found by **Silero VAD** (fixed 30 s windows when VAD is unavailable), decode each chunk
+//! one-shot and shift its timed segments back onto the file's timeline.
+
+use std::ops::Range;
+
+use super::diarization::Diarizer;
+use super::timestamps::{assign_speakers, TranscriptSegment};
+use super::{
+    sample, transcribe_waveform_once_detailed, TranscribeOptions, WhisperBackend, WhisperTranscript,
+};
+
+/// Whisper's input window; no chunk is longer than this.
+pub const MAX_CHUNK_SECONDS: u32 = 30;
+/// One Silero frame at 16 kHz (32 ms).
+pub const VAD_FRAME_SAMPLES: usize = 512;
+
+/// How per-frame speech probabilities become decode chunks. Sample counts are at 16 kHz.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkPlanOptions {
+    pub threshold: f32,
+    /// Pauses shorter than this stay inside one speech region.
+    pub min_silence_samples: usize,
+    /// Regions shorter than this (clicks, breaths) are dropped.
+    pub min_speech_samples: usize,
+    /// Audio kept on either side of a region so word edges are not clipped.
+    pub pad_samples: usize,
+    pub max_chunk_samples: usize,
+}
+
+impl Default for ChunkPlanOptions {
+    fn default() -> Self {
+        let hz = sample::WHISPER_HZ as usize;
+        Self {
+            threshold: 0.5,
+            min_silence_samples: hz / 2,
+            min_speech_samples: hz / 4,
+            pad_samples: hz / 5,
+            max_chunk_samples: hz * MAX_CHUNK_SECONDS as usize,
+        }
+    }
+}
+
+/// A whole-file decode: the stitched transcript plus how it was chunked.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LongTranscript {
+    /// `text` joins the chunks' text; `segments` are in seconds from the start of the file.
+    pub transcript: WhisperTranscript,
+    pub duration: f64,
+    pub chunks: usize,
+    /// Why fixed windows were used instead of VAD chunks, if they were.
+    pub vad_note: Option<String>,
+}
+
+/// Speech in a `total_samples` waveform, given Silero's probability for each
+/// [`VAD_FRAME_SAMPLES`] frame: regions are bridged across short pauses, blips are dropped and the
+/// rest padded (overlapping padding merges).
+pub fn speech_regions(
+    probs: &[f32],
+    total_samples: usize,
+    options: &ChunkPlanOptions,
+) -> Vec<Range<usize>> {
+    let frame = VAD_FRAME_SAMPLES;
+    let mut regions: Vec<Range<usize>> = Vec::new();
+    let mut start = None;
+    for (i, &p) in probs.iter().chain(std::iter::once(&0.0)).enumerate() {
+        match (p >= options.threshold, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                let r = s * frame..(i * frame).min(total_samples);
+                match regions.last_mut() {
+                    Some(last) if r.start - last.end < options.min_silence_samples => {
+                        last.end = r.end
+                    }
+                    _ => regions.push(r),
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let mut padded: Vec<Range<usize>> = Vec::new();
+    for r in regions
+        .into_iter()
+        .filter(|r| r.len() >= options.min_speech_samples)
+    {
+        let r = r.start.saturating_sub(options.pad_samples)
+            ..(r.end + options.pad_samples).min(total_samples);
+        match padded.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ if r.is_empty() => {}
+            _ => padded.push(r),
+        }
+    }
+    padded
+}
+
+/// Sample ranges to decode: [`speech_regions`] split at their quietest frame when longer than a
+/// window, then packed with their neighbours into as few chunks as fit. Silence between chunks is
+/// never decoded.
+pub fn plan_speech_chunks(
+    probs: &[f32],
+    total_samples: usize,
+    options: &ChunkPlanOptions,
+) -> Vec<Range<usize>> {
+    let max = options.max_chunk_samples.max(2);
+    let mut chunks: Vec<Range<usize>> = Vec::new();
+    let mut push = |piece: Range<usize>| match chunks.last_mut() {
+        Some(chunk) if piece.end - chunk.start <= max => chunk.end = piece.end,
+        _ => chunks.push(piece),
+    };
+    for r in speech_regions(probs, total_samples, options) {
+        let mut start = r.start;
+        while r.end - start > max {
+            let cut = quietest_cut(probs, start + max / 2, start + max);
+            push(start..cut);
+            start = cut;
+        }
+        push(start..r.end);
+    }
+    chunks
+}
+
+/// Frame boundary in `from..=to` whose following frame has the lowest speech probability (the
+/// latest one on ties, keeping chunks long).
+fn quietest_cut(probs: &[f32], from: usize, to: usize) -> usize {
+    let prob = |f: usize| probs.get(f).copied().unwrap_or(0.0);
+    (from.div_ceil(VAD_FRAME_SAMPLES)..=to / VAD_FRAME_SAMPLES)
+        .min_by(|&a, &b| prob(a).total_cmp(&prob(b)).then(b.cmp(&a)))
+        .map(|f| f * VAD_FRAME_SAMPLES)
+        .unwrap_or(to)
+}
+
+/// Back-to-back windows of `max_chunk_samples` covering the whole waveform.
+pub fn fixed_chunks(total_samples: usize, max_chunk_samples: usize) -> Vec<Range<usize>> {
+    (0..total_samples)
+        .step_by(max_chunk_samples.max(1))
+        .map(|start| start..(start + max_chunk_samples).min(total_samples))
+        .collect()
+}
+
+/// Silero's speech probability for each [`VAD_FRAME_SAMPLES`] frame of a 16 kHz waveform.
+#[cfg(all(feature = "silero_vad", not(target_os = "ios")))]
+pub(super) fn speech_probabilities(wave: &[f32]) -> Result<Vec<f32>, String> {
+    let mut session = super::silero::open_silero_session()?;
+    let mut frame = [0.0f32; VAD_FRAME_SAMPLES];
+    wave.chunks(VAD_FRAME_SAMPLES)
+        .map(|chunk| {
+            frame.fill(0.0);
+            frame[..chunk.len()].copy_from_slice(chunk);
+            session.predict_chunk(&frame)
+        })
+        .collect()
+}
+
+#[cfg(not(all(feature = "silero_vad", not(target_os = "ios"))))]
+pub(super) fn speech_probabilities(_wave: &[f32]) -> Result<Vec<f32>, String> {
+    Err("Silero VAD is unavailable in this build (enable silero_vad)".to_string())
+}
+
+/// Shift a chunk's segment onto the file timeline, clamping times to the chunk's end (Whisper can
+/// place a final timestamp past the audio it was given).
+fn shift_segment(mut segment: TranscriptSegment, offset: f64, limit: f64) -> TranscriptSegment {
+    let shift = |t: f64| (t + offset).min(limit);
+    segment.start = shift(segment.start);
+    segment.end = shift(segment.end);
+    for token in &mut segment.tokens {
+        token.start = shift(token.start);
+        token.end = shift(token.end);
+    }
+    for word in &mut segment.words {
+        word.start = shift(word.start);
+        word.end = shift(word.end);
+    }
+    segment
+}
+
+/// Transcribe a whole recording of any length. Timestamps are always on; `options.word_timestamps`
+/// adds words. With `language="auto"`, the first chunk's detected language is used for the rest.
+/// A `diarizer` labels segments with speakers, reusing the VAD pass that found the chunks.
+pub fn transcribe_long(
+    size: Option<&str>,
+    waveform: &[f32],
+    sample_rate: u32,
+    backend: WhisperBackend,
+    options: &TranscribeOptions,
+    diarizer: Option<&mut Diarizer>,
+) -> Result<LongTranscript, String> {
+    let resampled;
+    let wave: &[f32] = if sample_rate == sample::WHISPER_HZ {
+        waveform
+    } else {
+        resampled = xos_audio::dsp::resample(waveform, sample_rate, sample::WHISPER_HZ);
+        &resampled
+    };
+    let plan = ChunkPlanOptions::default();
+    let (chunks, regions, vad_note) = match speech_probabilities(wave) {
+        Ok(probs) => (
+            plan_speech_chunks(&probs, wave.len(), &plan),
+            speech_regions(&probs, wave.len(), &plan),
+            None,
+        ),
+        Err(e) => (
+            fixed_chunks(wave.len(), plan.max_chunk_samples),
+            std::iter::once(0..wave.len()).collect(),
+            Some(e),
+        ),
+    };
+
+    let hz = sample::WHISPER_HZ as f64;
+    let mut options = TranscribeOptions {
+        timestamps: true,
+        ..*options
+    };
+    let mut transcript = WhisperTranscript::default();
+    let mut texts: Vec<String> = Vec::new();
+    for chunk in &chunks {
+        let part = transcribe_waveform_once_detailed(
+            size,
+            &wave[chunk.clone()],
+            sample::WHISPER_HZ,
+            backend,
+            &options,
+        )?;
+        if let (None, Some(detected)) = (transcript.detected_language, part.detected_language) {
+            transcript.detected_language = Some(detected);
+            options.language = Some(detected.code);
+        }
+        let text = part.text.trim();
+        if !text.is_empty() {
+            texts.push(text.to_string());
+        }
+        let (offset, limit) = (chunk.start as f64 / hz, chunk.end as f64 / hz);
+        transcript.segments.extend(
+            part.segments
+                .into_iter()
+                .map(|s| shift_segment(s, offset, limit)),
+        );
+    }
+    transcript.text = texts.join(" ");
+    if let Some(diarizer) = diarizer {
+        let turns = diarizer.diarize_regions(wave, &regions)?;
+        assign_speakers(&mut transcript.segments, &turns);
+    }
+
+    Ok(LongTranscript {
+        transcript,
+        duration: wave.len() as f64 / hz,
+        chunks: chunks.len(),
+        vad_note,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const F: usize = VAD_FRAME_SAMPLES;
+
+    fn options(max_frames: usize) -> ChunkPlanOptions {
+        ChunkPlanOptions {
+            threshold: 0.5,
+            min_silence_samples: 4 * F,
+            min_speech_samples: 2 * F,
+            pad_samples: F,
+            max_chunk_samples: max_frames * F,
+        }
+    }
+
+    /// `#` speech, `.` silence, `-` a dip that is still silence but louder than `.`.
+    fn probs(pattern: &str) -> Vec<f32> {
+        pattern
+            .chars()
+            .map(|c| match c {
+                '#' => 0.9,
+                '-' => 0.3,
+                _ => 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn short_pauses_bridge_and_blips_drop() {
+        let p = probs("..####..###.......#.........");
+        let chunks = plan_speech_chunks(&p, p.len() * F, &options(100));
+        // Regions 2..6 and 8..11 bridge (pause 2 < 4) and pad by a frame; the lone blip at 18
+        // is shorter than min_speech.
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0], F..12 * F);
+    }
+
+    #[test]
+    fn neighbours_pack_up_to_the_window() {
+        let p = probs("###......###......###");
+        let chunks = plan_speech_chunks(&p, p.len() * F, &options(16));
+        assert_eq!(chunks, [0..13 * F, 17 * F..21 * F]);
+    }
+
+    #[test]
+    fn long_speech_splits_at_the_quietest_frame() {
+        let p = probs("##########-#.#######");
+        let chunks = plan_speech_chunks(&p, p.len() * F, &options(14));
+        assert_eq!(chunks, [0..12 * F, 12 * F..20 * F]);
+    }
+
+    #[test]
+    fn fixed_windows_cover_everything() {
+        assert_eq!(fixed_chunks(25, 10), [0..10, 10..20, 20..25]);
+        assert!(fixed_chunks(0, 10).is_empty());
+    }
+}
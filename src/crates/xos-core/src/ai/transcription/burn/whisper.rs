@@ -20,10 +20,18 @@ use burn_store::{BurnpackStore, ModuleSnapshot};
 use crate::ai::transcription::burn::whisper_burn::model::{Whisper, WhisperConfig};
 use crate::ai::transcription::burn::whisper_burn::token::{Gpt2Tokenizer, SpecialToken};
 use crate::ai::transcription::burn::whisper_burn::transcribe::{
-    compute_mel_cpu, transcribe as fw_transcribe, WhisperParams,
+    compute_mel_cpu, detect_language as fw_detect_language, transcribe as fw_transcribe,
+    TranscriptionSegment, WhisperParams,
 };
 use crate::ai::transcription::burn::whisper_burn::MixedPrecisionAdapter;
-use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+use crate::ai::transcription::languages::{self, AUTO_DETECT};
+use crate::ai::transcription::timestamps::{
+    group_words, language_splits_on_spaces, TranscriptSegment, TranscriptToken,
+};
+use crate::ai::transcription::{
+    ActivationStep, DetectedLanguage, TensorDebugStats, TranscribeOptions, WhisperTask,
+    WhisperTranscript,
+};
 
 type WgpuF32 = Wgpu<f32>;
 
@@ -103,20 +111,116 @@ fn whisper_encoder_trace_from_env() -> bool {
     )
 }
 
-fn normalize_language(language: Option<&str>) -> Result<&'static str, String> {
-    let Some(raw) = language.map(|s| s.trim().to_ascii_lowercase()) else {
-        return Ok("en");
-    };
-    if raw.is_empty() {
-        return Ok("en");
+/// English-only checkpoints have no `<|translate|>` token.
+fn check_task_supported(bpe: &Gpt2Tokenizer, translate: bool) -> Result<(), String> {
+    if translate && !bpe.is_multilingual() {
+        return Err("Whisper translate needs a multilingual model (not a .en one)".to_string());
     }
-    match raw.as_str() {
-        "english" | "en" => Ok("en"),
-        "japanese" | "ja" => Ok("ja"),
-        _ => Err("language must be 'english' or 'japanese'".to_string()),
+    Ok(())
+}
+
+/// Burn times are 10 ms frames.
+const FRAME_SECONDS: f64 = 0.01;
+
+/// Burn segment → [`TranscriptSegment`], grouping its tokens into words when they were aligned.
+fn transcript_segment(
+    bpe: &Gpt2Tokenizer,
+    segment: &TranscriptionSegment,
+    splits_on_spaces: bool,
+    word_timestamps: bool,
+) -> TranscriptSegment {
+    let aligned = &segment.token_timestamps;
+    let tokens: Vec<TranscriptToken> = aligned
+        .iter()
+        .map(|t| TranscriptToken {
+            id: t.token_id as u32,
+            text: t.text.clone(),
+            start: t.t0 as f64 * FRAME_SECONDS,
+            end: t.t1 as f64 * FRAME_SECONDS,
+            probability: t.p,
+        })
+        .collect();
+    let decode = |range: std::ops::Range<usize>| {
+        let ids: Vec<usize> = aligned[range].iter().map(|t| t.token_id).collect();
+        bpe.decode(&ids, true).unwrap_or_default()
+    };
+    let words = if word_timestamps {
+        group_words(&tokens, decode, splits_on_spaces)
+    } else {
+        Vec::new()
+    };
+    TranscriptSegment {
+        start: segment.t0 as f64 * FRAME_SECONDS,
+        end: segment.t1 as f64 * FRAME_SECONDS,
+        text: cleanup_whisper_text(&segment.text),
+        no_speech_prob: segment.no_speech_prob,
+        tokens,
+        words,
+        speaker: None,
     }
 }
 
+/// Decode `waveform` in `decode_lang`; for [`AUTO_DETECT`], run the language-ID pass first and
+/// decode in its pick (English-only models always decode English). Segments are filled when
+/// `params.no_timestamps` is off or `params.token_probs` is set.
+fn decode_with_language(
+    whisper: &Whisper<WgpuF32>,
+    bpe: &Gpt2Tokenizer,
+    waveform: &[f32],
+    sample_rate: usize,
+    params: &mut WhisperParams,
+    decode_lang: &'static str,
+) -> Result<WhisperTranscript, String> {
+    check_task_supported(bpe, params.translate)?;
+    let detected_language = if decode_lang == AUTO_DETECT && bpe.is_multilingual() {
+        let det = fw_detect_language(whisper, bpe, waveform, sample_rate, params.use_f16_compute)
+            .map_err(|e| format!("whisper language detection: {e}"))?;
+        Some(DetectedLanguage {
+            code: languages::resolve_language(det.language.as_str()).unwrap_or("en"),
+            probability: det.probability,
+        })
+    } else {
+        None
+    };
+    params.language = match (&detected_language, decode_lang) {
+        (Some(det), _) => det.code.to_string(),
+        (None, AUTO_DETECT) => "en".to_string(),
+        (None, code) => code.to_string(),
+    };
+    let result = fw_transcribe(
+        whisper,
+        bpe,
+        waveform,
+        sample_rate,
+        params,
+        None::<fn(usize, usize) -> bool>,
+    )
+    .map_err(|e| format!("whisper forward: {e}"))?;
+    let segments = if params.no_timestamps && !params.token_probs {
+        Vec::new()
+    } else {
+        let output_lang = if params.translate {
+            "en"
+        } else {
+            params.language.as_str()
+        };
+        let splits_on_spaces = language_splits_on_spaces(output_lang);
+        result
+            .segments
+            .iter()
+            .map(|segment| {
+                transcript_segment(bpe, segment, splits_on_spaces, params.token_timestamps)
+            })
+            .collect()
+    };
+    Ok(WhisperTranscript {
+        text: cleanup_whisper_text(&result.text),
+        detected_language,
+        segments,
+        translation: None,
+    })
+}
+
 /// Host values for debugging: uses [`TensorData::iter`] so Flex32 and F32 both round-trip like
 /// `transcribe` / the Burn CLI (avoids relying on `convert` + `to_vec` alone).
 fn tensor_data_to_f32_vec(data: TensorData) -> Vec<f32> {
@@ -173,7 +277,9 @@ fn tensor_debug_stats(vals: &[f32]) -> Option<TensorDebugStats> {
 pub fn spawn_decode_thread(
     size: Option<&str>,
     language: Option<&str>,
-) -> Result<(SyncSender<Vec<f32>>, Receiver<String>), String> {
+    task: WhisperTask,
+    translate_too: bool,
+) -> Result<(SyncSender<Vec<f32>>, Receiver<WhisperTranscript>), String> {
     use crate::ai::transcription::burn::whisper_burn::transcribe::SamplingStrategy;
 
     let sel = parse_whisper_model_arg(size)?;
@@ -187,40 +293,54 @@ pub fn spawn_decode_thread(
         &device,
     )?;
     let use_f16_compute = sel.use_f16_compute;
-    let decode_lang = normalize_language(language)?;
+    let decode_lang = languages::normalize_language(language)?;
+    let translate = task == WhisperTask::Translate;
+    check_task_supported(&bpe, translate || translate_too)?;
     let (job_tx, job_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(1);
-    let (result_tx, result_rx) = std::sync::mpsc::channel::<String>();
+    let (result_tx, result_rx) = std::sync::mpsc::channel::<WhisperTranscript>();
 
     thread::Builder::new()
         .name("xos-whisper-decode".into())
         .spawn(move || {
             let mut params = WhisperParams::default();
-            params.language = decode_lang.to_string();
             params.strategy = SamplingStrategy::Greedy { best_of: 1 };
             params.use_f16_compute = use_f16_compute;
             params.debug_mode = whisper_decode_trace_from_env();
             params.encoder_trace = whisper_encoder_trace_from_env();
             params.no_timestamps = true;
             params.single_segment = true;
+            params.translate = translate;
             params.detect_language = false;
             params.print_special = false;
             params.no_speech_thold = 1.0;
             params.logprob_thold = -5.0;
             params.suppress_blank = false;
+            let mut translate_params = params.clone();
+            translate_params.translate = true;
 
             while let Ok(buf) = job_rx.recv() {
-                let line = match fw_transcribe(
-                    &whisper,
-                    &bpe,
-                    &buf,
-                    16_000,
-                    &params,
-                    None::<fn(usize, usize) -> bool>,
-                ) {
-                    Ok(r) => cleanup_whisper_text(&r.text),
-                    Err(e) => format!("(Whisper error: {e})"),
-                };
-                if result_tx.send(line).is_err() {
+                let mut out =
+                    decode_with_language(&whisper, &bpe, &buf, 16_000, &mut params, decode_lang)
+                        .unwrap_or_else(|e| WhisperTranscript {
+                            text: format!("(Whisper error: {e})"),
+                            ..WhisperTranscript::default()
+                        });
+                if translate_too {
+                    // Same buffer, and the language is already known: no second detection pass.
+                    let lang = out.detected_language.map_or(decode_lang, |d| d.code);
+                    out.translation = Some(
+                        decode_with_language(
+                            &whisper,
+                            &bpe,
+                            &buf,
+                            16_000,
+                            &mut translate_params,
+                            lang,
+                        )
+                        .map_or_else(|e| format!("(Whisper error: {e})"), |t| t.text),
+                    );
+                }
+                if result_tx.send(out).is_err() {
                     break;
                 }
             }
@@ -233,38 +353,39 @@ pub fn transcribe_waveform_once(
     size: Option<&str>,
     waveform: &[f32],
     sample_rate: u32,
-    language: Option<&str>,
-) -> Result<String, String> {
+    options: &TranscribeOptions,
+) -> Result<WhisperTranscript, String> {
     use crate::ai::transcription::burn::whisper_burn::transcribe::SamplingStrategy;
 
     let sel = parse_whisper_model_arg(size)?;
-    let decode_lang = normalize_language(language)?;
+    let decode_lang = languages::normalize_language(options.language)?;
+    let timestamps = options.timestamps || options.word_timestamps;
     let models_root = prepare_whisper_models_root(&sel.canonical)?;
     validate_artifacts(&models_root, &sel.canonical)?;
     with_cached_model(&models_root, &sel, |bpe, whisper| {
         let mut params = WhisperParams::default();
-        params.language = decode_lang.to_string();
         params.strategy = SamplingStrategy::Greedy { best_of: 1 };
         params.use_f16_compute = sel.use_f16_compute;
         params.debug_mode = whisper_decode_trace_from_env();
         params.encoder_trace = whisper_encoder_trace_from_env();
-        params.no_timestamps = true;
-        params.single_segment = true;
+        params.no_timestamps = !timestamps;
+        params.single_segment = !timestamps;
+        params.token_timestamps = options.word_timestamps;
+        params.token_probs = options.tokens;
+        params.translate = options.task == WhisperTask::Translate;
         params.detect_language = false;
         params.print_special = false;
         params.no_speech_thold = 1.0;
         params.logprob_thold = -5.0;
         params.suppress_blank = false;
-        let result = fw_transcribe(
+        decode_with_language(
             whisper,
             bpe,
             waveform,
             sample_rate as usize,
-            &params,
-            None::<fn(usize, usize) -> bool>,
+            &mut params,
+            decode_lang,
         )
-        .map_err(|e| format!("whisper forward: {e}"))?;
-        Ok(cleanup_whisper_text(&result.text))
     })
 }
 
@@ -203,6 +203,9 @@ pub struct WhisperParams {
     pub single_segment: bool,
     pub print_special: bool,
     pub token_timestamps: bool,
+    /// Per-token text and probability without alignment: each token spans its whole segment.
+    /// Ignored when `token_timestamps` is set (aligned tokens carry probabilities too).
+    pub token_probs: bool,
     pub thold_pt: f32,
     pub thold_ptsum: f32,
     pub max_len: usize,
@@ -244,6 +247,7 @@ impl Default for WhisperParams {
             single_segment: false,
             print_special: false,
             token_timestamps: false,
+            token_probs: false,
             thold_pt: 0.01,
             thold_ptsum: 0.01,
             max_len: 0,
@@ -283,7 +287,7 @@ pub struct TranscriptionSegment {
     pub t1: i64,
     pub text: String,
     pub no_speech_prob: f32,
-    /// Per-token timestamps (only populated when token_timestamps=true)
+    /// Per-token data (only populated when token_timestamps or token_probs is set)
     pub token_timestamps: Vec<TokenTimestamp>,
     /// [TDRZ] Indicates the decoder predicted a speaker turn after this segment.
     pub speaker_turn_next: bool,
@@ -297,6 +301,8 @@ pub struct TokenTimestamp {
     pub t1: i64,
     /// Peak cross-attention weight used for alignment.
     pub pt: f32,
+    /// Sampling probability of the token.
+    pub p: f32,
 }
 
 impl TranscriptionSegment {
@@ -308,6 +314,29 @@ impl TranscriptionSegment {
     }
 }
 
+/// Decoder prompt: `<|startoftranscript|>`, then the language and task tokens on multilingual
+/// models, then `<|notimestamps|>` when timestamps are off.
+fn sot_sequence(
+    bpe: &Gpt2Tokenizer,
+    multilingual: bool,
+    lang: Language,
+    params: &WhisperParams,
+) -> Vec<usize> {
+    let mut sot_sequence = vec![bpe.special_token(SpecialToken::StartofTranscript).unwrap()];
+    if multilingual {
+        sot_sequence.push(bpe.special_token(SpecialToken::Language(lang)).unwrap());
+        if params.translate {
+            sot_sequence.push(bpe.special_token(SpecialToken::Translate).unwrap());
+        } else {
+            sot_sequence.push(bpe.special_token(SpecialToken::Transcribe).unwrap());
+        }
+    }
+    if params.no_timestamps {
+        sot_sequence.push(bpe.special_token(SpecialToken::NoTimeStamps).unwrap());
+    }
+    sot_sequence
+}
+
 /// Result of language detection
 #[derive(Debug, Clone)]
 pub struct LanguageDetectionResult {
@@ -535,18 +564,7 @@ fn transcribe_inner<B: CustomKernelsBackend, F: FnMut(usize, usize) -> bool>(
     let is_multilingual = bpe.is_multilingual();
 
     // Build SOT sequence
-    let mut sot_sequence = vec![bpe.special_token(SpecialToken::StartofTranscript).unwrap()];
-    if is_multilingual {
-        sot_sequence.push(bpe.special_token(SpecialToken::Language(lang)).unwrap());
-        if params.translate {
-            sot_sequence.push(bpe.special_token(SpecialToken::Translate).unwrap());
-        } else {
-            sot_sequence.push(bpe.special_token(SpecialToken::Transcribe).unwrap());
-        }
-    }
-    if params.no_timestamps {
-        sot_sequence.push(bpe.special_token(SpecialToken::NoTimeStamps).unwrap());
-    }
+    let sot_sequence = sot_sequence(bpe, is_multilingual, lang, params);
 
     // Important token IDs
     let token_eot = bpe.special_token(SpecialToken::EndofText).unwrap();
@@ -893,6 +911,16 @@ fn transcribe_inner<B: CustomKernelsBackend, F: FnMut(usize, usize) -> bool>(
                                 token_beg,
                                 token_eot,
                             )
+                        } else if params.token_probs {
+                            compute_segment_tokens(
+                                bpe,
+                                &tokens_cur,
+                                &seg_token_indices,
+                                adjusted_t0,
+                                t1,
+                                token_beg,
+                                token_eot,
+                            )
                         } else {
                             Vec::new()
                         };
@@ -948,6 +976,16 @@ fn transcribe_inner<B: CustomKernelsBackend, F: FnMut(usize, usize) -> bool>(
                         token_beg,
                         token_eot,
                     )
+                } else if params.token_probs {
+                    compute_segment_tokens(
+                        bpe,
+                        &tokens_cur,
+                        &seg_token_indices,
+                        adjusted_t0,
+                        t1,
+                        token_beg,
+                        token_eot,
+                    )
                 } else {
                     Vec::new()
                 };
@@ -1111,18 +1149,7 @@ pub fn transcribe_regions_batched<B: CustomKernelsBackend, F: FnMut(usize, usize
     };
 
     // SOT sequence
-    let mut sot_sequence = vec![bpe.special_token(SpecialToken::StartofTranscript).unwrap()];
-    if is_multilingual {
-        sot_sequence.push(bpe.special_token(SpecialToken::Language(lang)).unwrap());
-        if params.translate {
-            sot_sequence.push(bpe.special_token(SpecialToken::Translate).unwrap());
-        } else {
-            sot_sequence.push(bpe.special_token(SpecialToken::Transcribe).unwrap());
-        }
-    }
-    if params.no_timestamps {
-        sot_sequence.push(bpe.special_token(SpecialToken::NoTimeStamps).unwrap());
-    }
+    let sot_sequence = sot_sequence(bpe, is_multilingual, lang, params);
 
     let token_eot = bpe.special_token(SpecialToken::EndofText).unwrap();
     let token_beg = bpe.special_token(SpecialToken::Timestamp(0.0)).unwrap();
@@ -1900,6 +1927,16 @@ fn build_segments_from_tokens(
                         token_beg,
                         token_eot,
                     )
+                } else if params.token_probs {
+                    compute_segment_tokens(
+                        bpe,
+                        tokens_cur,
+                        &seg_token_indices,
+                        t0,
+                        t1,
+                        token_beg,
+                        token_eot,
+                    )
                 } else {
                     Vec::new()
                 };
@@ -1949,6 +1986,16 @@ fn build_segments_from_tokens(
                 token_beg,
                 token_eot,
             )
+        } else if params.token_probs {
+            compute_segment_tokens(
+                bpe,
+                tokens_cur,
+                &seg_token_indices,
+                t0,
+                t1,
+                token_beg,
+                token_eot,
+            )
         } else {
             Vec::new()
         };
@@ -3327,13 +3374,14 @@ fn compute_token_timestamps_for_segment(
         .iter()
         .enumerate()
         .map(|(index, &idx)| {
-            let (token_id, _, _) = tokens_cur[idx];
+            let (token_id, log_prob, _) = tokens_cur[idx];
             TokenTimestamp {
                 token_id,
                 text: bpe.decode(&[token_id], true).unwrap_or_default(),
                 t0: boundaries[index],
                 t1: boundaries[index + 1],
                 pt: peaks[index],
+                p: log_prob.exp() as f32,
             }
         })
         .collect()
@@ -3369,7 +3417,7 @@ fn compute_uniform_token_timestamps_for_segment(
         .iter()
         .enumerate()
         .map(|(index, &idx)| {
-            let (token_id, _, _) = tokens_cur[idx];
+            let (token_id, log_prob, _) = tokens_cur[idx];
             let tok_t0 = t0 + index as i64 * step;
             let tok_t1 = if index as i64 == n - 1 {
                 t1
@@ -3382,11 +3430,38 @@ fn compute_uniform_token_timestamps_for_segment(
                 t0: tok_t0,
                 t1: tok_t1,
                 pt: 0.0,
+                p: log_prob.exp() as f32,
             }
         })
         .collect()
 }
 
+/// Text tokens of a segment with their sampling probabilities, each spanning the whole segment
+/// (`token_probs` without `token_timestamps`).
+fn compute_segment_tokens(
+    bpe: &Gpt2Tokenizer,
+    tokens_cur: &[(usize, f64, usize)],
+    seg_indices: &[usize],
+    t0: i64,
+    t1: i64,
+    token_beg: usize,
+    token_eot: usize,
+) -> Vec<TokenTimestamp> {
+    seg_indices
+        .iter()
+        .map(|&idx| tokens_cur[idx])
+        .filter(|&(id, _, _)| id < token_eot && id < token_beg)
+        .map(|(token_id, log_prob, _)| TokenTimestamp {
+            token_id,
+            text: bpe.decode(&[token_id], true).unwrap_or_default(),
+            t0,
+            t1,
+            pt: 0.0,
+            p: log_prob.exp() as f32,
+        })
+        .collect()
+}
+
 /// Extract cross-attention alignment for a single token.
 ///
 /// Uses only the **last decoder layer** and takes the **element-wise max across
@@ -3567,3 +3642,104 @@ pub fn format_timestamp(frames: i64) -> String {
     let h = m / 60;
     format!("{:02}:{:02}:{:02},{:03}", h, m % 60, s % 60, ms % 1000)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Word-level vocab: `a`, `b`, end-of-text, then three timestamp tokens from `<|0.00|>`.
+    const TOKENIZER_JSON: &str = r#"{
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": [],
+        "normalizer": null,
+        "pre_tokenizer": {"type": "Whitespace"},
+        "post_processor": null,
+        "decoder": null,
+        "model": {
+            "type": "WordLevel",
+            "vocab": {"a": 0, "b": 1, "<|endoftext|>": 2, "<|0.00|>": 3, "<|0.02|>": 4, "<|0.04|>": 5},
+            "unk_token": "<|endoftext|>"
+        }
+    }"#;
+    const EOT: usize = 2;
+    const BEG: usize = 3;
+
+    fn segments(params: &WhisperParams) -> Vec<TranscriptionSegment> {
+        let bpe = Gpt2Tokenizer::new_from_data(TOKENIZER_JSON).unwrap();
+        // `<|0.00|> a b <|0.04|>`: one segment over frames 0..4.
+        let tokens = [
+            (BEG, 0.0, BEG),
+            (0, 0.5f64.ln(), BEG),
+            (1, 0.25f64.ln(), BEG),
+            (5, 0.0, 5),
+        ];
+        let mut out = Vec::new();
+        build_segments_from_tokens(
+            &bpe,
+            &tokens,
+            &[],
+            EOT,
+            BEG,
+            0,
+            0,
+            0,
+            0,
+            0.0,
+            params,
+            &mut out,
+        );
+        out
+    }
+
+    #[test]
+    fn token_probs_fill_tokens_without_token_timestamps() {
+        let params = WhisperParams {
+            token_probs: true,
+            ..WhisperParams::default()
+        };
+        let segments = segments(&params);
+        assert_eq!(segments.len(), 1);
+        let tokens = &segments[0].token_timestamps;
+        let ids: Vec<usize> = tokens.iter().map(|t| t.token_id).collect();
+        assert_eq!(ids, [0, 1]);
+        assert_eq!(tokens[0].text, "a");
+        assert!((tokens[0].p - 0.5).abs() < 1e-6);
+        assert!((tokens[1].p - 0.25).abs() < 1e-6);
+        assert!(tokens.iter().all(|t| (t.t0, t.t1) == (0, 4)));
+
+        let plain = segments(&WhisperParams::default());
+        assert!(plain[0].token_timestamps.is_empty());
+    }
+
+    #[test]
+    fn prompt_carries_the_task_token() {
+        let bpe = Gpt2Tokenizer::new_from_data(&TOKENIZER_JSON.replace(
+            r#""<|0.04|>": 5}"#,
+            r#""<|0.04|>": 5, "<|startoftranscript|>": 6, "<|ja|>": 7,
+                "<|transcribe|>": 8, "<|translate|>": 9, "<|notimestamps|>": 10}"#,
+        ))
+        .unwrap();
+        let mut params = WhisperParams::default();
+        assert_eq!(
+            sot_sequence(&bpe, true, Language::Japanese, &params),
+            [6, 7, 8]
+        );
+        params.translate = true;
+        assert_eq!(
+            sot_sequence(&bpe, true, Language::Japanese, &params),
+            [6, 7, 9]
+        );
+        params.no_timestamps = true;
+        assert_eq!(
+            sot_sequence(&bpe, true, Language::Japanese, &params),
+            [6, 7, 9, 10]
+        );
+        // English-only models take neither a language nor a task token.
+        assert_eq!(
+            sot_sequence(&bpe, false, Language::Japanese, &params),
+            [6, 10]
+        );
+    }
+}
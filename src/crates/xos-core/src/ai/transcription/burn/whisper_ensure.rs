@@ -1,11 +1,9 @@
-//! Download OpenAI `.pt` + Hugging Face `tokenizer.json`, then convert to Burnpack (same pipeline as
-//! the upstream convert pipeline) into `auth_data_dir()/models/whisper/{size}-burn/`.
+//! Fetch OpenAI `.pt` + Hugging Face `tokenizer.json` through the model registry
+//! (`whisper-{size}-burn`), then convert to Burnpack (same pipeline as the upstream convert
+//! pipeline) into `auth_data_dir()/models/whisper/{size}-burn/`.
 
-use std::collections::HashMap;
 use std::error::Error;
-use std::fs;
-use std::io::{Read, Write};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use crate::ai::transcription::burn::whisper_burn::custom_kernels::CustomKernelsBackend;
 use crate::ai::transcription::burn::whisper_burn::model::{
@@ -16,60 +14,6 @@ use burn::backend::wgpu::Wgpu;
 use burn::config::Config;
 use burn_store::pytorch::PytorchReader;
 use burn_store::{BurnpackStore, ModuleSnapshot, PytorchStore};
-use serde::Deserialize;
-use sha2::{Digest, Sha256};
-
-const DOWNLOAD_MANIFEST: &str = include_str!("burn_whisper_download_links.json");
-
-#[derive(Debug, Deserialize)]
-struct Manifest {
-    pytorch: HashMap<String, PytorchEntry>,
-    tokenizer: HashMap<String, String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PytorchEntry {
-    url: String,
-    sha256: String,
-}
-
-fn hex_lower(bytes: &[u8]) -> String {
-    bytes.iter().map(|b| format!("{:02x}", b)).collect()
-}
-
-fn sha256_file(path: &Path) -> Result<String, String> {
-    let data = fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
-    let mut hasher = Sha256::new();
-    hasher.update(&data);
-    Ok(hex_lower(&hasher.finalize()))
-}
-
-fn download_bytes(url: &str) -> Result<Vec<u8>, String> {
-    let resp = ureq::get(url)
-        .set("User-Agent", "xos-whisper/1.0")
-        .call()
-        .map_err(|e| format!("GET {url}: {e}"))?;
-    let mut reader = resp.into_reader();
-    let mut buf = Vec::new();
-    reader
-        .read_to_end(&mut buf)
-        .map_err(|e| format!("read body {url}: {e}"))?;
-    Ok(buf)
-}
-
-fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("create_dir_all: {e}"))?;
-    }
-    let tmp = path.with_extension("tmp");
-    let mut f = fs::File::create(&tmp).map_err(|e| format!("create {}: {e}", tmp.display()))?;
-    f.write_all(bytes)
-        .map_err(|e| format!("write {}: {e}", tmp.display()))?;
-    f.sync_all().ok();
-    drop(f);
-    fs::rename(&tmp, path).map_err(|e| format!("rename to {}: {e}", path.display()))?;
-    Ok(())
-}
 
 fn save_whisper_artifacts<B: CustomKernelsBackend>(
     whisper: &Whisper<B>,
@@ -207,61 +151,14 @@ pub(crate) fn artifacts_ready(dir: &Path, model_key: &str) -> bool {
 
 /// Populate `{data}/models/whisper/{model_key}-burn/` (see `xos path --data`).
 pub(crate) fn ensure_whisper_artifacts(model_key: &str) -> Result<(), String> {
-    let dir: PathBuf = xos_auth::whisper_model_backend_cache_dir(model_key, "burn")
-        .map_err(|e| e.to_string())?;
-    fs::create_dir_all(&dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
-
+    let dir =
+        xos_auth::whisper_model_backend_cache_dir(model_key, "burn").map_err(|e| e.to_string())?;
     if artifacts_ready(&dir, model_key) {
         return Ok(());
     }
 
-    let manifest: Manifest =
-        serde_json::from_str(DOWNLOAD_MANIFEST).map_err(|e| format!("manifest json: {e}"))?;
-
-    let pt_entry = manifest
-        .pytorch
-        .get(model_key)
-        .ok_or_else(|| {
-            format!(
-                "no PyTorch URL in burn_whisper_download_links.json for model '{model_key}' (supported: tiny, small, …)"
-            )
-        })?;
-    let tok_url = manifest.tokenizer.get(model_key).ok_or_else(|| {
-        format!("no tokenizer URL in burn_whisper_download_links.json for '{model_key}'")
-    })?;
-
-    let pt_path = dir.join(format!("{model_key}.pt"));
-    let tok_path = dir.join(format!("{model_key}-tokenizer.json"));
-
-    if !tok_path.is_file() {
-        eprintln!("[xos-whisper] Downloading tokenizer…");
-        let bytes = download_bytes(tok_url)?;
-        write_atomic(&tok_path, &bytes)?;
-    }
-
-    let need_convert = !dir.join(format!("{model_key}.cfg")).is_file()
-        || (!dir.join(format!("{model_key}.bpk")).is_file()
-            && !dir.join(format!("{model_key}-f16.bpk")).is_file());
-
-    if need_convert {
-        if !pt_path.is_file() {
-            eprintln!("[xos-whisper] Downloading PyTorch checkpoint…");
-            let bytes = download_bytes(&pt_entry.url)?;
-            write_atomic(&pt_path, &bytes)?;
-        }
-
-        let got = sha256_file(&pt_path)?;
-        if got != pt_entry.sha256 {
-            eprintln!(
-                "[xos-whisper] warning: SHA-256 of {} does not match manifest (expected {}, got {}); continuing",
-                pt_path.display(),
-                pt_entry.sha256,
-                got
-            );
-        }
-
-        convert_pt_to_burnpack(&dir, model_key)?;
-    }
+    let dir = crate::ai::models::ensure(&format!("whisper-{model_key}-burn"))?;
+    convert_pt_to_burnpack(&dir, model_key)?;
 
     if !artifacts_ready(&dir, model_key) {
         return Err(format!(
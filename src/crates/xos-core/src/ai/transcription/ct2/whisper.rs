@@ -12,14 +12,19 @@
 #![cfg(all(feature = "whisper_ct2", not(target_arch = "wasm32")))]
 
 use std::cell::RefCell;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, sync_channel, Receiver, SyncSender};
 use std::thread;
 
 use ct2rs::sys::WhisperOptions;
-use ct2rs::{Config, Whisper as Ct2Whisper};
+use ct2rs::{Config, Whisper as Ct2Whisper, WhisperSegment};
 
+use super::super::languages::{self, AUTO_DETECT};
 use super::super::sample;
+use super::super::timestamps::{
+    group_words, language_splits_on_spaces, TranscriptSegment, TranscriptToken,
+};
+use super::super::{DetectedLanguage, TranscribeOptions, WhisperTask, WhisperTranscript};
 
 const MODELS_SUBDIR: &str = "src/crates/xos-core/src/ai/transcription/models/ct2";
 
@@ -52,18 +57,127 @@ fn with_cached_whisper<T>(
         f(&model.whisper)
     })
 }
-fn normalize_language(language: Option<&str>) -> Result<&'static str, String> {
-    let Some(raw) = language.map(|s| s.trim().to_ascii_lowercase()) else {
-        return Ok("en");
+
+/// CT2 segment → [`TranscriptSegment`], grouping its tokens into words when they were aligned.
+fn transcript_segment(
+    whisper: &Ct2Whisper,
+    segment: &WhisperSegment,
+    splits_on_spaces: bool,
+    word_timestamps: bool,
+) -> TranscriptSegment {
+    let tokens: Vec<TranscriptToken> = segment
+        .tokens
+        .iter()
+        .map(|t| TranscriptToken {
+            id: t.id as u32,
+            text: t.text.clone(),
+            start: f64::from(t.start),
+            end: f64::from(t.end),
+            probability: t.probability,
+        })
+        .collect();
+    let decode = |range: std::ops::Range<usize>| {
+        let ids: Vec<usize> = segment.tokens[range].iter().map(|t| t.id).collect();
+        whisper.decode_ids(&ids).unwrap_or_default()
+    };
+    let words = if word_timestamps {
+        group_words(&tokens, decode, splits_on_spaces)
+    } else {
+        Vec::new()
     };
-    if raw.is_empty() {
-        return Ok("en");
+    TranscriptSegment {
+        start: f64::from(segment.start),
+        end: f64::from(segment.end),
+        text: cleanup_whisper_text(&segment.text),
+        no_speech_prob: segment.no_speech_prob,
+        tokens,
+        words,
+        speaker: None,
     }
-    match raw.as_str() {
-        "english" | "en" => Ok("en"),
-        "japanese" | "ja" => Ok("ja"),
-        _ => Err("language must be 'english' or 'japanese'".to_string()),
+}
+
+/// `generate` in `decode_lang`; for [`AUTO_DETECT`], CT2's language-ID pass over the first 30 s
+/// picks the language (English-only models always decode English). [`WhisperTask::Translate`]
+/// outputs English. With `timestamps` the decode keeps Whisper's timestamp tokens and fills
+/// segments; `tokens` fills their tokens and `word_timestamps` also aligns them.
+/// `options.language` is ignored in favour of the normalized `decode_lang`.
+fn generate_with_language(
+    whisper: &Ct2Whisper,
+    pcm_16k: &[f32],
+    decode_lang: &'static str,
+    options: &TranscribeOptions,
+    opts: &WhisperOptions,
+) -> Result<WhisperTranscript, String> {
+    let task = options.task;
+    if task == WhisperTask::Translate && !whisper.is_multilingual() {
+        return Err("Whisper translate needs a multilingual model (not a .en one)".to_string());
     }
+    let detected_language = if decode_lang == AUTO_DETECT && whisper.is_multilingual() {
+        let (code, probability) = whisper
+            .detect_language(pcm_16k)
+            .map_err(|e| format!("Whisper CT2 language detection: {e}"))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Whisper CT2 language detection returned nothing".to_string())?;
+        Some(DetectedLanguage {
+            code: languages::resolve_language(&code).unwrap_or("en"),
+            probability,
+        })
+    } else {
+        None
+    };
+    let lang = match (&detected_language, decode_lang) {
+        (Some(det), _) => det.code,
+        (None, AUTO_DETECT) => "en",
+        (None, code) => code,
+    };
+    if task == WhisperTask::Transcribe && !options.wants_segments() {
+        let parts = whisper
+            .generate(pcm_16k, Some(lang), false, opts)
+            .map_err(|e| format!("Whisper CT2 generate: {e}"))?;
+        return Ok(WhisperTranscript {
+            text: cleanup_whisper_text(&parts.join(" ")),
+            detected_language,
+            segments: Vec::new(),
+            translation: None,
+        });
+    }
+    let output_lang = match task {
+        WhisperTask::Transcribe => lang,
+        WhisperTask::Translate => "en",
+    };
+    let splits_on_spaces = language_splits_on_spaces(output_lang);
+    let segments: Vec<TranscriptSegment> = whisper
+        .generate_segments(
+            pcm_16k,
+            lang,
+            task.as_str(),
+            options.timestamps || options.word_timestamps,
+            options.tokens,
+            options.word_timestamps,
+            opts,
+        )
+        .map_err(|e| format!("Whisper CT2 generate: {e}"))?
+        .iter()
+        .map(|segment| {
+            transcript_segment(whisper, segment, splits_on_spaces, options.word_timestamps)
+        })
+        .collect();
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok(WhisperTranscript {
+        text: cleanup_whisper_text(&text),
+        detected_language,
+        segments: if options.wants_segments() {
+            segments
+        } else {
+            Vec::new()
+        },
+        translation: None,
+    })
 }
 
 /// Stem for [`xos_auth::whisper_model_backend_cache_dir`] (e.g. `tiny` → `…/tiny-ct2/`).
@@ -81,14 +195,12 @@ fn ct2_size_stem(size: Option<&str>) -> Result<&'static str, String> {
     }
 }
 
-/// Key in `whisper_ct2_download_links.json` (matches folder name `{stem}-ct2`).
-fn ct2_manifest_key(size: Option<&str>) -> Result<&'static str, String> {
-    match ct2_size_stem(size)? {
-        "tiny" => Ok("tiny-ct2"),
-        "small" => Ok("small-ct2"),
-        "base" => Ok("base-ct2"),
-        _ => Err("ct2_manifest_key: unsupported stem".to_string()),
-    }
+/// Files every converted CT2 Whisper folder has.
+fn model_ready(dir: &Path) -> bool {
+    dir.join("model.bin").is_file()
+        && dir.join("config.json").is_file()
+        && dir.join("tokenizer.json").is_file()
+        && dir.join("preprocessor_config.json").is_file()
 }
 
 fn legacy_transcription_ct2_dir(old_name: &str) -> Result<PathBuf, String> {
@@ -102,24 +214,24 @@ fn legacy_transcription_ct2_dir(old_name: &str) -> Result<PathBuf, String> {
 
 fn resolve_model_dir(size: Option<&str>) -> Result<PathBuf, String> {
     let stem = ct2_size_stem(size)?;
-    let manifest_key = ct2_manifest_key(size)?;
+    let folder = format!("{stem}-ct2");
     let cache =
         xos_auth::whisper_model_backend_cache_dir(stem, "ct2").map_err(|e| e.to_string())?;
-    if super::whisper_ensure::model_ready(&cache) {
+    if model_ready(&cache) {
         return Ok(cache);
     }
 
     if let Ok(root) = crate::find_xos_project_root() {
-        for sub in [manifest_key, "whisper-tiny-ct2"] {
+        for sub in [folder.as_str(), "whisper-tiny-ct2"] {
             let bundled = root.join(MODELS_SUBDIR).join(sub);
-            if super::whisper_ensure::model_ready(&bundled) {
+            if model_ready(&bundled) {
                 return Ok(bundled);
             }
         }
         let legacy_bundled = root
             .join("src/crates/xos-core/src/ai/transcription/models")
             .join("whisper-tiny-ct2");
-        if super::whisper_ensure::model_ready(&legacy_bundled) {
+        if model_ready(&legacy_bundled) {
             return Ok(legacy_bundled);
         }
     }
@@ -133,37 +245,18 @@ fn resolve_model_dir(size: Option<&str>) -> Result<PathBuf, String> {
         "base-ct2",
     ] {
         let legacy_cache = legacy_transcription_ct2_dir(legacy_name)?;
-        if super::whisper_ensure::model_ready(&legacy_cache) {
+        if model_ready(&legacy_cache) {
             return Ok(legacy_cache);
         }
     }
 
-    super::whisper_ensure::ensure_ct2_artifacts(manifest_key, &cache)?;
-    if super::whisper_ensure::model_ready(&cache) {
-        return Ok(cache);
-    }
-
-    let bundled_hint = crate::find_xos_project_root()
-        .map(|r| {
-            r.join(MODELS_SUBDIR)
-                .join(manifest_key)
-                .to_string_lossy()
-                .to_string()
-        })
-        .unwrap_or_else(|_| {
-            format!(
-                "<repo>/{}/{} (no repo on this platform — use first-run download to {})",
-                MODELS_SUBDIR,
-                manifest_key,
-                cache.display()
-            )
-        });
-    Err(format!(
-        "Whisper CT2 setup failed for '{manifest_key}' under {}. Fix whisper_ct2_download_links.json (zip_url), \
-         or place a converted tree at {}.",
-        cache.display(),
-        bundled_hint
-    ))
+    crate::ai::models::ensure(&format!("whisper-{stem}-ct2")).map_err(|e| {
+        format!(
+            "Whisper CT2 setup failed for '{folder}': {e}. Import it offline with `xos models import`, \
+             or place a converted tree at {}.",
+            cache.display()
+        )
+    })
 }
 
 fn cleanup_whisper_text(s: &str) -> String {
@@ -193,11 +286,13 @@ fn cleanup_whisper_text(s: &str) -> String {
 pub fn spawn_decode_thread(
     size: Option<&str>,
     language: Option<&str>,
-) -> Result<(SyncSender<Vec<f32>>, Receiver<String>), String> {
+    task: WhisperTask,
+    translate_too: bool,
+) -> Result<(SyncSender<Vec<f32>>, Receiver<WhisperTranscript>), String> {
     let dir = resolve_model_dir(size)?;
-    let decode_lang = normalize_language(language)?;
+    let decode_lang = languages::normalize_language(language)?;
     let (job_tx, job_rx) = sync_channel::<Vec<f32>>(1);
-    let (result_tx, result_rx) = channel::<String>();
+    let (result_tx, result_rx) = channel::<WhisperTranscript>();
 
     thread::Builder::new()
         .name("xos-whisper-ct2-decode".into())
@@ -208,18 +303,38 @@ pub fn spawn_decode_thread(
             let whisper = match Ct2Whisper::new(&dir, cfg) {
                 Ok(w) => w,
                 Err(e) => {
-                    let _ = result_tx.send(format!("(Whisper CT2 load error: {e})"));
+                    let _ = result_tx.send(WhisperTranscript {
+                        text: format!("(Whisper CT2 load error: {e})"),
+                        ..WhisperTranscript::default()
+                    });
                     return;
                 }
             };
             let mut opts = WhisperOptions::default();
             opts.beam_size = 1;
+            let options = TranscribeOptions {
+                task,
+                ..TranscribeOptions::default()
+            };
+            let translate = TranscribeOptions {
+                task: WhisperTask::Translate,
+                ..TranscribeOptions::default()
+            };
             while let Ok(buf) = job_rx.recv() {
-                let line = match whisper.generate(&buf, Some(decode_lang), false, &opts) {
-                    Ok(parts) => cleanup_whisper_text(&parts.join(" ")),
-                    Err(e) => format!("(Whisper CT2 error: {e})"),
-                };
-                if result_tx.send(line).is_err() {
+                let mut out = generate_with_language(&whisper, &buf, decode_lang, &options, &opts)
+                    .unwrap_or_else(|e| WhisperTranscript {
+                        text: format!("(Whisper CT2 error: {e})"),
+                        ..WhisperTranscript::default()
+                    });
+                if translate_too {
+                    // Same buffer, and the language is already known: no second detection pass.
+                    let lang = out.detected_language.map_or(decode_lang, |d| d.code);
+                    out.translation = Some(
+                        generate_with_language(&whisper, &buf, lang, &translate, &opts)
+                            .map_or_else(|e| format!("(Whisper CT2 error: {e})"), |t| t.text),
+                    );
+                }
+                if result_tx.send(out).is_err() {
                     break;
                 }
             }
@@ -233,25 +348,22 @@ pub fn transcribe_waveform_once(
     size: Option<&str>,
     waveform: &[f32],
     sample_rate: u32,
-    language: Option<&str>,
-) -> Result<String, String> {
+    options: &TranscribeOptions,
+) -> Result<WhisperTranscript, String> {
     let dir = resolve_model_dir(size)?;
-    let decode_lang = normalize_language(language)?;
+    let decode_lang = languages::normalize_language(options.language)?;
     if waveform.is_empty() {
-        return Ok(String::new());
+        return Ok(WhisperTranscript::default());
     }
     let pcm_16k = if sample_rate == sample::WHISPER_HZ as u32 {
         waveform.to_vec()
     } else {
-        sample::resample_linear(waveform, sample_rate, sample::WHISPER_HZ)
+        xos_audio::dsp::resample(waveform, sample_rate, sample::WHISPER_HZ)
     };
     with_cached_whisper(&dir, |whisper| {
         let mut opts = WhisperOptions::default();
         // One-shot path prioritizes accuracy over streaming latency.
         opts.beam_size = 5;
-        let parts = whisper
-            .generate(&pcm_16k, Some(decode_lang), false, &opts)
-            .map_err(|e| format!("Whisper CT2 generate: {e}"))?;
-        Ok(cleanup_whisper_text(&parts.join(" ")))
+        generate_with_language(whisper, &pcm_16k, decode_lang, options, &opts)
     })
 }
@@ -0,0 +1,163 @@
+//! Online speaker clustering over embeddings: each embedding joins the most similar known speaker
+//! (cosine similarity of the running centroid) or starts a new one. Enrolled speakers are seeded
+//! with a name and reference embedding and are matched the same way.
+
+/// Cosine similarity; 0 when either vector is all zeros.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom > 0.0 {
+        dot / denom
+    } else {
+        0.0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Speaker {
+    name: Option<String>,
+    /// Sum of the unit-length embeddings assigned so far (direction = centroid).
+    sum: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpeakerRegistry {
+    speakers: Vec<Speaker>,
+    /// Minimum cosine similarity for an embedding to join an existing speaker.
+    pub threshold: f32,
+}
+
+fn unit(embedding: &[f32]) -> Vec<f32> {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        embedding.iter().map(|x| x / norm).collect()
+    } else {
+        embedding.to_vec()
+    }
+}
+
+impl SpeakerRegistry {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            speakers: Vec::new(),
+            threshold,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.speakers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.speakers.is_empty()
+    }
+
+    /// Add a reference embedding for `name`; enrolling the same name again refines its centroid.
+    pub fn enroll(&mut self, name: &str, embedding: &[f32]) {
+        let embedding = unit(embedding);
+        match self
+            .speakers
+            .iter_mut()
+            .find(|s| s.name.as_deref() == Some(name))
+        {
+            Some(speaker) => add_into(&mut speaker.sum, &embedding),
+            None => self.speakers.push(Speaker {
+                name: Some(name.to_string()),
+                sum: embedding,
+            }),
+        }
+    }
+
+    /// Most similar speaker and its similarity, without updating anything.
+    pub fn nearest(&self, embedding: &[f32]) -> Option<(usize, f32)> {
+        self.speakers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, cosine_similarity(&s.sum, embedding)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Index of the speaker this embedding belongs to, creating an unnamed speaker when none is
+    /// similar enough. The matched speaker's centroid absorbs the embedding.
+    pub fn identify(&mut self, embedding: &[f32]) -> usize {
+        let embedding = unit(embedding);
+        match self.nearest(&embedding) {
+            Some((index, similarity)) if similarity >= self.threshold => {
+                add_into(&mut self.speakers[index].sum, &embedding);
+                index
+            }
+            _ => {
+                self.speakers.push(Speaker {
+                    name: None,
+                    sum: embedding,
+                });
+                self.speakers.len() - 1
+            }
+        }
+    }
+
+    /// Enrolled name, or `Speaker N` numbering unnamed speakers in order of first appearance.
+    pub fn label(&self, index: usize) -> String {
+        match &self.speakers[index].name {
+            Some(name) => name.clone(),
+            None => {
+                let n = self.speakers[..=index]
+                    .iter()
+                    .filter(|s| s.name.is_none())
+                    .count();
+                format!("Speaker {n}")
+            }
+        }
+    }
+
+    /// Drop unnamed speakers (e.g. before the next recording); enrolled speakers stay.
+    pub fn forget_unknown(&mut self) {
+        self.speakers.retain(|s| s.name.is_some());
+    }
+
+    /// Drop every speaker, enrolled ones included.
+    pub fn clear(&mut self) {
+        self.speakers.clear();
+    }
+}
+
+fn add_into(sum: &mut [f32], embedding: &[f32]) {
+    for (s, e) in sum.iter_mut().zip(embedding) {
+        *s += e;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similar_embeddings_share_a_speaker() {
+        let mut registry = SpeakerRegistry::new(0.6);
+        let a = registry.identify(&[1.0, 0.1, 0.0]);
+        let b = registry.identify(&[0.0, 1.0, 0.1]);
+        assert_ne!(a, b);
+        assert_eq!(registry.identify(&[2.0, 0.3, 0.1]), a);
+        assert_eq!(registry.identify(&[0.1, 0.9, 0.0]), b);
+        assert_eq!(
+            (registry.label(a), registry.label(b)),
+            ("Speaker 1".into(), "Speaker 2".into())
+        );
+    }
+
+    #[test]
+    fn enrolled_names_survive_forgetting() {
+        let mut registry = SpeakerRegistry::new(0.6);
+        registry.enroll("Alice", &[0.0, 0.0, 1.0]);
+        let unknown = registry.identify(&[1.0, 0.0, 0.0]);
+        assert_eq!(registry.label(unknown), "Speaker 1");
+        let alice = registry.identify(&[0.1, 0.0, 0.8]);
+        assert_eq!(registry.label(alice), "Alice");
+
+        registry.forget_unknown();
+        assert_eq!(registry.len(), 1);
+        let alice = registry.identify(&[0.0, 0.2, 1.0]);
+        assert_eq!(registry.label(alice), "Alice");
+    }
+}
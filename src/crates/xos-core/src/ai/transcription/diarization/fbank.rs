@@ -0,0 +1,168 @@
+//! Kaldi-compatible 80-bin log mel filterbank (`torchaudio.compliance.kaldi.fbank` with WeSpeaker's
+//! settings: 25 ms Hamming frames every 10 ms, pre-emphasis 0.97, no dither, no energy), which is
+//! what the speaker-embedding model was trained on.
+
+use std::f32::consts::PI;
+
+use xos_audio::dsp::Fft;
+
+pub const FBANK_BINS: usize = 80;
+const SAMPLE_RATE: f32 = 16_000.0;
+const FRAME_LENGTH: usize = 400;
+const FRAME_SHIFT: usize = 160;
+const FFT_SIZE: usize = 512;
+const PREEMPHASIS: f32 = 0.97;
+const LOW_FREQ: f32 = 20.0;
+
+fn mel_scale(hz: f32) -> f32 {
+    1127.0 * (1.0 + hz / 700.0).ln()
+}
+
+/// Window, FFT plan and mel weights, computed once per extractor.
+pub struct Fbank {
+    window: Vec<f32>,
+    fft: Fft,
+    /// Per mel bin: first FFT bin and the triangle's weights from there.
+    filters: Vec<(usize, Vec<f32>)>,
+}
+
+impl Default for Fbank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fbank {
+    pub fn new() -> Self {
+        let window = (0..FRAME_LENGTH)
+            .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f32 / (FRAME_LENGTH - 1) as f32).cos())
+            .collect();
+
+        let mel_low = mel_scale(LOW_FREQ);
+        let mel_high = mel_scale(SAMPLE_RATE / 2.0);
+        let delta = (mel_high - mel_low) / (FBANK_BINS + 1) as f32;
+        let bin_hz = SAMPLE_RATE / FFT_SIZE as f32;
+        let filters = (0..FBANK_BINS)
+            .map(|b| {
+                let left = mel_low + b as f32 * delta;
+                let center = left + delta;
+                let right = center + delta;
+                let weights: Vec<(usize, f32)> = (0..FFT_SIZE / 2)
+                    .filter_map(|i| {
+                        let mel = mel_scale(i as f32 * bin_hz);
+                        if mel <= left || mel >= right {
+                            None
+                        } else if mel <= center {
+                            Some((i, (mel - left) / (center - left)))
+                        } else {
+                            Some((i, (right - mel) / (right - center)))
+                        }
+                    })
+                    .collect();
+                let first = weights.first().map_or(0, |(i, _)| *i);
+                (first, weights.into_iter().map(|(_, w)| w).collect())
+            })
+            .collect();
+
+        Self {
+            window,
+            fft: Fft::new(FFT_SIZE),
+            filters,
+        }
+    }
+
+    /// Log mel energies for each full frame of 16 kHz audio in \[-1, 1\] (scaled to the int16
+    /// range first, as Kaldi expects).
+    pub fn compute(&self, wave: &[f32]) -> Vec<[f32; FBANK_BINS]> {
+        if wave.len() < FRAME_LENGTH {
+            return Vec::new();
+        }
+        let frames = 1 + (wave.len() - FRAME_LENGTH) / FRAME_SHIFT;
+        let mut samples = vec![0.0f32; FRAME_LENGTH];
+        let mut re = vec![0.0f64; FFT_SIZE];
+        let mut im = vec![0.0f64; FFT_SIZE];
+        let mut power = vec![0.0f32; FFT_SIZE / 2 + 1];
+        let mut out = Vec::with_capacity(frames);
+        for f in 0..frames {
+            let frame = &wave[f * FRAME_SHIFT..f * FRAME_SHIFT + FRAME_LENGTH];
+            let mean = frame.iter().sum::<f32>() * 32768.0 / FRAME_LENGTH as f32;
+            for (dst, &x) in samples.iter_mut().zip(frame) {
+                *dst = x * 32768.0 - mean;
+            }
+            for i in (1..FRAME_LENGTH).rev() {
+                samples[i] -= PREEMPHASIS * samples[i - 1];
+            }
+            samples[0] -= PREEMPHASIS * samples[0];
+            for ((dst, x), w) in re.iter_mut().zip(&samples).zip(&self.window) {
+                *dst = (x * w) as f64;
+            }
+            re[FRAME_LENGTH..].fill(0.0);
+            im.fill(0.0);
+            self.fft.forward(&mut re, &mut im);
+            for (i, p) in power.iter_mut().enumerate() {
+                *p = (re[i] * re[i] + im[i] * im[i]) as f32;
+            }
+
+            let mut bins = [0.0f32; FBANK_BINS];
+            for (bin, (first, weights)) in bins.iter_mut().zip(&self.filters) {
+                let energy: f32 = weights
+                    .iter()
+                    .zip(&power[*first..])
+                    .map(|(w, p)| w * p)
+                    .sum();
+                *bin = energy.max(f32::EPSILON).ln();
+            }
+            out.push(bins);
+        }
+        out
+    }
+}
+
+/// Subtract each bin's mean over time (cepstral mean normalisation).
+pub fn normalize_mean(frames: &mut [[f32; FBANK_BINS]]) {
+    if frames.is_empty() {
+        return;
+    }
+    let mut mean = [0.0f32; FBANK_BINS];
+    for frame in frames.iter() {
+        for (m, x) in mean.iter_mut().zip(frame) {
+            *m += x;
+        }
+    }
+    for m in &mut mean {
+        *m /= frames.len() as f32;
+    }
+    for frame in frames.iter_mut() {
+        for (x, m) in frame.iter_mut().zip(&mean) {
+            *x -= m;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_count_and_tone_peak() {
+        let tone: Vec<f32> = (0..16_000)
+            .map(|i| 0.5 * (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        let frames = Fbank::new().compute(&tone);
+        assert_eq!(frames.len(), 1 + (16_000 - FRAME_LENGTH) / FRAME_SHIFT);
+        // 1 kHz is ~1000 mel, the centre of bin 27 with Kaldi's 20 Hz – 8 kHz layout.
+        let peak = (0..FBANK_BINS)
+            .max_by(|&a, &b| frames[50][a].total_cmp(&frames[50][b]))
+            .unwrap();
+        assert_eq!(peak, 27);
+        assert!(Fbank::new().compute(&tone[..FRAME_LENGTH - 1]).is_empty());
+    }
+
+    #[test]
+    fn mean_normalisation_zeroes_each_bin() {
+        let mut frames = vec![[1.0; FBANK_BINS], [3.0; FBANK_BINS]];
+        normalize_mean(&mut frames);
+        assert_eq!(frames[0][5], -1.0);
+        assert_eq!(frames[1][79], 1.0);
+    }
+}
@@ -0,0 +1,250 @@
+//! Speaker diarization ("who spoke when"). Speech regions from **Silero VAD** are cut into ~1.5 s
+//! windows, each window gets a **WeSpeaker** embedding (ONNX; `wespeaker-resnet34` in the model
+//! registry), and windows are clustered online by cosine similarity — enrolled voices by name,
+//! the rest as `Speaker N`. The resulting [`SpeakerTurn`]s label transcript segments via
+//! [`assign_speakers`].
+
+mod cluster;
+#[cfg(any(test, all(feature = "diarization", not(target_os = "ios"))))]
+mod fbank;
+#[cfg(all(feature = "diarization", not(target_os = "ios")))]
+mod onnx;
+
+use std::ops::Range;
+
+pub use cluster::{cosine_similarity, SpeakerRegistry};
+
+use super::batch::{speech_probabilities, speech_regions, ChunkPlanOptions};
+use super::sample;
+use super::timestamps::{assign_speakers, SpeakerTurn, TranscriptSegment};
+
+/// Cosine similarity an embedding needs to join an existing speaker.
+pub const DEFAULT_SPEAKER_THRESHOLD: f32 = 0.5;
+/// Embedding window (1.5 s at 16 kHz) and the stretch each window speaks for (0.75 s).
+const WINDOW_SAMPLES: usize = 24_000;
+const HOP_SAMPLES: usize = 12_000;
+/// Shorter audio (0.25 s) gives unreliable embeddings and is left unlabelled.
+const MIN_EMBED_SAMPLES: usize = 4_000;
+
+/// Speaker-embedding model plus the speakers seen (or enrolled) so far. Labels stay stable across
+/// calls until [`Diarizer::forget_unknown`].
+pub struct Diarizer {
+    registry: SpeakerRegistry,
+    #[cfg(all(feature = "diarization", not(target_os = "ios")))]
+    session: onnx::SpeakerEmbeddingSession,
+}
+
+impl Diarizer {
+    /// Load the embedding model (downloading it on first use).
+    pub fn new() -> Result<Self, String> {
+        #[cfg(all(feature = "diarization", not(target_os = "ios")))]
+        {
+            let path =
+                crate::ai::models::ensure("wespeaker-resnet34")?.join("voxceleb_resnet34_LM.onnx");
+            return Ok(Self {
+                registry: SpeakerRegistry::new(DEFAULT_SPEAKER_THRESHOLD),
+                session: onnx::SpeakerEmbeddingSession::from_path(&path)?,
+            });
+        }
+        #[cfg(not(all(feature = "diarization", not(target_os = "ios"))))]
+        {
+            Err("Speaker diarization is unavailable in this build (enable diarization)".to_string())
+        }
+    }
+
+    /// Lower merges more voices into one speaker; higher splits one voice into several.
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.registry.threshold = threshold;
+    }
+
+    fn embed(&mut self, wave: &[f32]) -> Result<Vec<f32>, String> {
+        #[cfg(all(feature = "diarization", not(target_os = "ios")))]
+        {
+            return self.session.embed(wave);
+        }
+        #[cfg(not(all(feature = "diarization", not(target_os = "ios"))))]
+        {
+            let _ = wave;
+            Err("Speaker diarization is unavailable in this build (enable diarization)".to_string())
+        }
+    }
+
+    /// Register a known voice from a clip of that person speaking (a few seconds or more).
+    pub fn enroll(&mut self, name: &str, waveform: &[f32], sample_rate: u32) -> Result<(), String> {
+        let wave = xos_audio::dsp::resample(waveform, sample_rate, sample::WHISPER_HZ);
+        if wave.len() < MIN_EMBED_SAMPLES {
+            return Err(format!("enrollment audio for '{name}' is too short"));
+        }
+        let embedding = self.embed(&wave)?;
+        self.registry.enroll(name, &embedding);
+        Ok(())
+    }
+
+    /// Speaker of one utterance (live commits); `None` when it is too short to tell.
+    pub fn identify(
+        &mut self,
+        waveform: &[f32],
+        sample_rate: u32,
+    ) -> Result<Option<String>, String> {
+        let wave = xos_audio::dsp::resample(waveform, sample_rate, sample::WHISPER_HZ);
+        if wave.len() < MIN_EMBED_SAMPLES {
+            return Ok(None);
+        }
+        let embedding = self.embed(&wave)?;
+        let index = self.registry.identify(&embedding);
+        Ok(Some(self.registry.label(index)))
+    }
+
+    /// Speaker turns over a whole recording (VAD, then windowed embeddings).
+    pub fn diarize(
+        &mut self,
+        waveform: &[f32],
+        sample_rate: u32,
+    ) -> Result<Vec<SpeakerTurn>, String> {
+        let wave = xos_audio::dsp::resample(waveform, sample_rate, sample::WHISPER_HZ);
+        let probs = speech_probabilities(&wave)?;
+        let regions = speech_regions(&probs, wave.len(), &ChunkPlanOptions::default());
+        self.diarize_regions(&wave, &regions)
+    }
+
+    /// Speaker turns for speech `regions` (sample ranges) of 16 kHz audio. A first pass grows the
+    /// speakers window by window; a second assigns every window to its nearest settled speaker, so
+    /// early windows are not stuck with a half-formed centroid.
+    pub fn diarize_regions(
+        &mut self,
+        wave: &[f32],
+        regions: &[Range<usize>],
+    ) -> Result<Vec<SpeakerTurn>, String> {
+        let pieces: Vec<(Range<usize>, Range<usize>)> = regions
+            .iter()
+            .flat_map(|r| embedding_windows(r.clone()))
+            .filter(|(_, window)| window.len() >= MIN_EMBED_SAMPLES)
+            .collect();
+        let mut embeddings = Vec::with_capacity(pieces.len());
+        for (_, window) in &pieces {
+            let embedding = self.embed(&wave[window.clone()])?;
+            self.registry.identify(&embedding);
+            embeddings.push(embedding);
+        }
+        let labelled: Vec<(Range<usize>, usize)> = pieces
+            .into_iter()
+            .zip(&embeddings)
+            .filter_map(|((owned, _), e)| Some((owned, self.registry.nearest(e)?.0)))
+            .collect();
+        Ok(merge_turns(&labelled, |i| self.registry.label(i)))
+    }
+
+    /// [`Diarizer::diarize`] the audio and label `segments` (times in seconds from its start).
+    pub fn label_segments(
+        &mut self,
+        waveform: &[f32],
+        sample_rate: u32,
+        segments: &mut [TranscriptSegment],
+    ) -> Result<(), String> {
+        let turns = self.diarize(waveform, sample_rate)?;
+        assign_speakers(segments, &turns);
+        Ok(())
+    }
+
+    /// Forget voices found so far (e.g. before the next recording); enrolled speakers stay.
+    pub fn forget_unknown(&mut self) {
+        self.registry.forget_unknown();
+    }
+
+    /// Forget every speaker, enrolled ones included.
+    pub fn clear_speakers(&mut self) {
+        self.registry.clear();
+    }
+}
+
+/// Split a speech region into consecutive pieces one hop long (the last absorbs the remainder),
+/// each paired with the embedding window centred on it and kept inside the region.
+fn embedding_windows(region: Range<usize>) -> Vec<(Range<usize>, Range<usize>)> {
+    if region.len() <= WINDOW_SAMPLES {
+        return vec![(region.clone(), region)];
+    }
+    let mut out = Vec::new();
+    let mut start = region.start;
+    while start < region.end {
+        let end = if region.end - start < 2 * HOP_SAMPLES {
+            region.end
+        } else {
+            start + HOP_SAMPLES
+        };
+        let window_start = ((start + end) / 2)
+            .saturating_sub(WINDOW_SAMPLES / 2)
+            .clamp(region.start, region.end - WINDOW_SAMPLES);
+        out.push((start..end, window_start..window_start + WINDOW_SAMPLES));
+        start = end;
+    }
+    out
+}
+
+/// Join touching pieces with the same speaker into turns (seconds at 16 kHz).
+fn merge_turns(
+    pieces: &[(Range<usize>, usize)],
+    label: impl Fn(usize) -> String,
+) -> Vec<SpeakerTurn> {
+    let hz = sample::WHISPER_HZ as f64;
+    let mut merged: Vec<(Range<usize>, usize)> = Vec::new();
+    for (range, speaker) in pieces {
+        match merged.last_mut() {
+            Some((last, last_speaker)) if *last_speaker == *speaker && last.end == range.start => {
+                last.end = range.end
+            }
+            _ => merged.push((range.clone(), *speaker)),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(range, speaker)| SpeakerTurn {
+            start: range.start as f64 / hz,
+            end: range.end as f64 / hz,
+            speaker: label(speaker),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_tile_long_regions() {
+        assert_eq!(embedding_windows(100..5_100), [(100..5_100, 100..5_100)]);
+
+        let region = 0..50_000;
+        let pieces = embedding_windows(region.clone());
+        let owned: Vec<Range<usize>> = pieces.iter().map(|(o, _)| o.clone()).collect();
+        assert_eq!(
+            owned,
+            [0..12_000, 12_000..24_000, 24_000..36_000, 36_000..50_000]
+        );
+        for (owned, window) in &pieces {
+            assert_eq!(window.len(), WINDOW_SAMPLES);
+            assert!(window.start >= region.start && window.end <= region.end);
+            assert!(window.contains(&((owned.start + owned.end) / 2)));
+        }
+        assert_eq!(pieces[1].1, 6_000..30_000);
+        assert_eq!(pieces[3].1, 26_000..50_000);
+    }
+
+    #[test]
+    fn touching_pieces_of_one_speaker_merge() {
+        let pieces = [
+            (0..16_000, 0),
+            (16_000..32_000, 0),
+            (32_000..40_000, 1),
+            (48_000..56_000, 1),
+        ];
+        let turns = merge_turns(&pieces, |i| format!("S{i}"));
+        let spans: Vec<(f64, f64, &str)> = turns
+            .iter()
+            .map(|t| (t.start, t.end, t.speaker.as_str()))
+            .collect();
+        assert_eq!(
+            spans,
+            [(0.0, 2.0, "S0"), (2.0, 2.5, "S1"), (3.0, 3.5, "S1")]
+        );
+    }
+}
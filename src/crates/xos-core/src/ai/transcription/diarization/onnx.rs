@@ -0,0 +1,54 @@
+//! WeSpeaker ResNet34 speaker embeddings (ONNX, `feats` \[1, T, 80\] → `embs` \[1, 256\]) via ONNX
+//! Runtime.
+
+use std::path::Path;
+
+use ort::session::{Session, SessionOutputs};
+use ort::value::Tensor;
+
+use super::fbank::{normalize_mean, Fbank, FBANK_BINS};
+
+pub struct SpeakerEmbeddingSession {
+    session: Session,
+    fbank: Fbank,
+}
+
+impl SpeakerEmbeddingSession {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let session = Session::builder()
+            .map_err(|e| e.to_string())?
+            .with_intra_threads(1)
+            .map_err(|e| e.to_string())?
+            .with_inter_threads(1)
+            .map_err(|e| e.to_string())?
+            .commit_from_file(path)
+            .map_err(|e| format!("speaker embedding ONNX load {}: {e}", path.display()))?;
+        Ok(Self {
+            session,
+            fbank: Fbank::new(),
+        })
+    }
+
+    /// Embedding of 16 kHz mono audio (at least one 25 ms frame).
+    pub fn embed(&mut self, wave: &[f32]) -> Result<Vec<f32>, String> {
+        let mut frames = self.fbank.compute(wave);
+        if frames.is_empty() {
+            return Err("audio too short for a speaker embedding".to_string());
+        }
+        normalize_mean(&mut frames);
+        let t = frames.len();
+        let feats: Vec<f32> = frames.into_iter().flatten().collect();
+        let input = Tensor::from_array(([1i64, t as i64, FBANK_BINS as i64], feats))
+            .map_err(|e| e.to_string())?;
+
+        let outputs: SessionOutputs = self
+            .session
+            .run(ort::inputs!["feats" => input])
+            .map_err(|e| e.to_string())?;
+        let out = outputs
+            .get("embs")
+            .ok_or_else(|| "speaker embedding ONNX missing output 'embs'".to_string())?;
+        let (_, embedding) = out.try_extract_tensor::<f32>().map_err(|e| e.to_string())?;
+        Ok(embedding.to_vec())
+    }
+}
@@ -0,0 +1,200 @@
+//! Whisper's language set, shared by both backends, the Python bindings and the transcribe app's
+//! language menu. Requests accept a code (`ja`), an English name (`Japanese`) or [`AUTO_DETECT`].
+
+/// Request value that runs Whisper's language-ID pass before decoding.
+pub const AUTO_DETECT: &str = "auto";
+
+/// `(code, name)` in Whisper's token order (`<|en|>` is the first language token).
+pub const WHISPER_LANGUAGES: [(&str, &str); 100] = [
+    ("en", "English"),
+    ("zh", "Chinese"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("ru", "Russian"),
+    ("ko", "Korean"),
+    ("fr", "French"),
+    ("ja", "Japanese"),
+    ("pt", "Portuguese"),
+    ("tr", "Turkish"),
+    ("pl", "Polish"),
+    ("ca", "Catalan"),
+    ("nl", "Dutch"),
+    ("ar", "Arabic"),
+    ("sv", "Swedish"),
+    ("it", "Italian"),
+    ("id", "Indonesian"),
+    ("hi", "Hindi"),
+    ("fi", "Finnish"),
+    ("vi", "Vietnamese"),
+    ("he", "Hebrew"),
+    ("uk", "Ukrainian"),
+    ("el", "Greek"),
+    ("ms", "Malay"),
+    ("cs", "Czech"),
+    ("ro", "Romanian"),
+    ("da", "Danish"),
+    ("hu", "Hungarian"),
+    ("ta", "Tamil"),
+    ("no", "Norwegian"),
+    ("th", "Thai"),
+    ("ur", "Urdu"),
+    ("hr", "Croatian"),
+    ("bg", "Bulgarian"),
+    ("lt", "Lithuanian"),
+    ("la", "Latin"),
+    ("mi", "Maori"),
+    ("ml", "Malayalam"),
+    ("cy", "Welsh"),
+    ("sk", "Slovak"),
+    ("te", "Telugu"),
+    ("fa", "Persian"),
+    ("lv", "Latvian"),
+    ("bn", "Bengali"),
+    ("sr", "Serbian"),
+    ("az", "Azerbaijani"),
+    ("sl", "Slovenian"),
+    ("kn", "Kannada"),
+    ("et", "Estonian"),
+    ("mk", "Macedonian"),
+    ("br", "Breton"),
+    ("eu", "Basque"),
+    ("is", "Icelandic"),
+    ("hy", "Armenian"),
+    ("ne", "Nepali"),
+    ("mn", "Mongolian"),
+    ("bs", "Bosnian"),
+    ("kk", "Kazakh"),
+    ("sq", "Albanian"),
+    ("sw", "Swahili"),
+    ("gl", "Galician"),
+    ("mr", "Marathi"),
+    ("pa", "Punjabi"),
+    ("si", "Sinhala"),
+    ("km", "Khmer"),
+    ("sn", "Shona"),
+    ("yo", "Yoruba"),
+    ("so", "Somali"),
+    ("af", "Afrikaans"),
+    ("oc", "Occitan"),
+    ("ka", "Georgian"),
+    ("be", "Belarusian"),
+    ("tg", "Tajik"),
+    ("sd", "Sindhi"),
+    ("gu", "Gujarati"),
+    ("am", "Amharic"),
+    ("yi", "Yiddish"),
+    ("lo", "Lao"),
+    ("uz", "Uzbek"),
+    ("fo", "Faroese"),
+    ("ht", "Haitian Creole"),
+    ("ps", "Pashto"),
+    ("tk", "Turkmen"),
+    ("nn", "Nynorsk"),
+    ("mt", "Maltese"),
+    ("sa", "Sanskrit"),
+    ("lb", "Luxembourgish"),
+    ("my", "Myanmar"),
+    ("bo", "Tibetan"),
+    ("tl", "Tagalog"),
+    ("mg", "Malagasy"),
+    ("as", "Assamese"),
+    ("tt", "Tatar"),
+    ("haw", "Hawaiian"),
+    ("ln", "Lingala"),
+    ("ha", "Hausa"),
+    ("ba", "Bashkir"),
+    ("jw", "Javanese"),
+    ("su", "Sundanese"),
+    ("yue", "Cantonese"),
+];
+
+/// Other spellings Whisper's own tokenizer accepts, plus `jp` / `jv` for habit's sake.
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("burmese", "my"),
+    ("valencian", "ca"),
+    ("flemish", "nl"),
+    ("haitian", "ht"),
+    ("letzeburgesch", "lb"),
+    ("pushto", "ps"),
+    ("panjabi", "pa"),
+    ("moldavian", "ro"),
+    ("moldovan", "ro"),
+    ("sinhalese", "si"),
+    ("castilian", "es"),
+    ("mandarin", "zh"),
+    ("jp", "ja"),
+    ("jv", "jw"),
+];
+
+/// Canonical code for a language code, English name or alias (case-insensitive); `"auto"` stays
+/// [`AUTO_DETECT`].
+pub fn resolve_language(raw: &str) -> Result<&'static str, String> {
+    let key = raw.trim().to_ascii_lowercase();
+    if key == AUTO_DETECT {
+        return Ok(AUTO_DETECT);
+    }
+    WHISPER_LANGUAGES
+        .iter()
+        .find(|(code, name)| *code == key || name.eq_ignore_ascii_case(&key))
+        .map(|(code, _)| *code)
+        .or_else(|| {
+            LANGUAGE_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == key)
+                .map(|(_, code)| *code)
+        })
+        .ok_or_else(|| {
+            format!(
+                "unknown Whisper language '{}' (use a code like 'en' or 'ja', an English name like 'german', or 'auto')",
+                raw.trim()
+            )
+        })
+}
+
+/// Backend decode language: unset / empty means English (the historical default).
+pub fn normalize_language(language: Option<&str>) -> Result<&'static str, String> {
+    match language.map(str::trim) {
+        None | Some("") => Ok("en"),
+        Some(raw) => resolve_language(raw),
+    }
+}
+
+/// English name for a canonical code (`"ja"` → `"Japanese"`).
+pub fn language_name(code: &str) -> Option<&'static str> {
+    WHISPER_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_codes_names_and_aliases() {
+        assert_eq!(resolve_language("ja"), Ok("ja"));
+        assert_eq!(resolve_language(" Japanese "), Ok("ja"));
+        assert_eq!(resolve_language("JP"), Ok("ja"));
+        assert_eq!(resolve_language("haitian creole"), Ok("ht"));
+        assert_eq!(resolve_language("Mandarin"), Ok("zh"));
+        assert_eq!(resolve_language("yue"), Ok("yue"));
+        assert_eq!(resolve_language("Auto"), Ok(AUTO_DETECT));
+        assert!(resolve_language("klingon").is_err());
+    }
+
+    #[test]
+    fn unset_language_defaults_to_english() {
+        assert_eq!(normalize_language(None), Ok("en"));
+        assert_eq!(normalize_language(Some("  ")), Ok("en"));
+        assert_eq!(normalize_language(Some("german")), Ok("de"));
+    }
+
+    #[test]
+    fn table_codes_are_unique() {
+        for (i, (code, _)) in WHISPER_LANGUAGES.iter().enumerate() {
+            assert!(WHISPER_LANGUAGES[i + 1..].iter().all(|(c, _)| c != code));
+            assert!(language_name(code).is_some());
+        }
+    }
+}
@@ -3,6 +3,13 @@
 //! optional **Silero VAD** (ONNX) to gate Whisper decodes during silence, plus ~100 Hz partial decode
 //! scheduling.
 
+#[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+pub mod batch;
+#[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+pub mod diarization;
+pub mod languages;
+pub mod timestamps;
+
 #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
 mod sample;
 
@@ -46,23 +53,101 @@ impl WhisperBackend {
     }
 }
 
+/// Whisper's decode task: `Transcribe` keeps the spoken language, `Translate` outputs English
+/// (multilingual models only).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WhisperTask {
+    #[default]
+    Transcribe,
+    Translate,
+}
+
+impl WhisperTask {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "transcribe" => Some(Self::Transcribe),
+            "translate" => Some(Self::Translate),
+            _ => None,
+        }
+    }
+
+    /// Name of the task's prompt token (`<|transcribe|>` / `<|translate|>`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transcribe => "transcribe",
+            Self::Translate => "translate",
+        }
+    }
+}
+
+/// Language Whisper's language-ID pass picked for a `language="auto"` decode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedLanguage {
+    /// Canonical code from [`languages::WHISPER_LANGUAGES`].
+    pub code: &'static str,
+    pub probability: f32,
+}
+
+/// One decode: the text, plus the detected language when the request was `"auto"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WhisperTranscript {
+    pub text: String,
+    pub detected_language: Option<DetectedLanguage>,
+    /// Timed segments; empty unless [`TranscribeOptions::wants_segments`].
+    pub segments: Vec<timestamps::TranscriptSegment>,
+    /// English translation of the same audio from a live decoder started with
+    /// [`TranscriptionEngine::new_with_translation`]; `None` otherwise.
+    pub translation: Option<String>,
+}
+
+/// What a one-shot decode should produce beyond plain text.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TranscribeOptions<'a> {
+    /// Code, English name or `"auto"`; unset means English.
+    pub language: Option<&'a str>,
+    pub task: WhisperTask,
+    /// Fill [`WhisperTranscript::segments`] from Whisper's timestamp tokens.
+    pub timestamps: bool,
+    /// Also align each text token to the audio (cross-attention DTW) and group tokens into words.
+    /// Implies `timestamps`.
+    pub word_timestamps: bool,
+    /// Fill each segment's tokens with their text and probability; without `word_timestamps`
+    /// every token spans its segment. Fills segments like `timestamps` does.
+    pub tokens: bool,
+}
+
+impl TranscribeOptions<'_> {
+    /// Whether the result carries [`WhisperTranscript::segments`].
+    pub fn wants_segments(&self) -> bool {
+        self.timestamps || self.word_timestamps || self.tokens
+    }
+}
+
 #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
 /// Live caption pipeline: **CT2** (default) or **Burn** (WGPU), matching `xos.ai.whisper.load(..., backend=...)`.
 /// On iOS, only **CT2** is available (models under `xos path --data` — iOS: `Documents/xos`, macOS: `~/.xos`).
+/// With `translate_too`, each job is also decoded with [`WhisperTask::Translate`] on the same thread.
 fn spawn_live_decode_thread(
     preferred_size: Option<&str>,
     backend: WhisperBackend,
     language: Option<&str>,
-) -> Result<(SyncSender<Vec<f32>>, Receiver<String>), String> {
+    task: WhisperTask,
+    translate_too: bool,
+) -> Result<(SyncSender<Vec<f32>>, Receiver<WhisperTranscript>), String> {
     match backend {
         WhisperBackend::Ct2 => {
             #[cfg(feature = "whisper_ct2")]
             {
-                return ct2::whisper::spawn_decode_thread(preferred_size, language);
+                return ct2::whisper::spawn_decode_thread(
+                    preferred_size,
+                    language,
+                    task,
+                    translate_too,
+                );
             }
             #[cfg(not(feature = "whisper_ct2"))]
             {
-                let _ = (preferred_size, language);
+                let _ = (preferred_size, language, task, translate_too);
                 Err(
                     "Whisper CT2 backend is unavailable in this build (enable whisper_ct2)"
                         .to_string(),
@@ -72,11 +157,16 @@ fn spawn_live_decode_thread(
         WhisperBackend::Burn => {
             #[cfg(all(feature = "whisper_burn", not(target_os = "ios")))]
             {
-                return burn::whisper::spawn_decode_thread(preferred_size, language);
+                return burn::whisper::spawn_decode_thread(
+                    preferred_size,
+                    language,
+                    task,
+                    translate_too,
+                );
             }
             #[cfg(all(not(feature = "whisper_burn"), not(target_os = "ios")))]
             {
-                let _ = (preferred_size, language);
+                let _ = (preferred_size, language, task, translate_too);
                 Err(
                     "Whisper Burn backend is unavailable in this build (enable whisper_burn)."
                         .to_string(),
@@ -84,7 +174,7 @@ fn spawn_live_decode_thread(
             }
             #[cfg(target_os = "ios")]
             {
-                let _ = (preferred_size, language);
+                let _ = (preferred_size, language, task, translate_too);
                 Err(
                     "Whisper Burn (WGPU) backend is unavailable on iOS; use backend='ct2' (default)."
                         .to_string(),
@@ -104,7 +194,7 @@ pub fn transcribe_waveform_once(
     transcribe_waveform_once_with_language(size, waveform, sample_rate, backend, None)
 }
 
-/// One-shot whisper transcription with optional explicit language code (`en`/`ja`).
+/// One-shot whisper transcription with an optional language (code, English name, or `"auto"`).
 pub fn transcribe_waveform_once_with_language(
     size: Option<&str>,
     waveform: &[f32],
@@ -112,6 +202,23 @@ pub fn transcribe_waveform_once_with_language(
     backend: WhisperBackend,
     language: Option<&str>,
 ) -> Result<String, String> {
+    let options = TranscribeOptions {
+        language,
+        ..TranscribeOptions::default()
+    };
+    transcribe_waveform_once_detailed(size, waveform, sample_rate, backend, &options)
+        .map(|t| t.text)
+}
+
+/// One-shot decode returning the detected language (`language="auto"`) and, per `options`, timed
+/// segments, tokens and words.
+pub fn transcribe_waveform_once_detailed(
+    size: Option<&str>,
+    waveform: &[f32],
+    sample_rate: u32,
+    backend: WhisperBackend,
+    options: &TranscribeOptions,
+) -> Result<WhisperTranscript, String> {
     match backend {
         WhisperBackend::Burn => {
             #[cfg(all(
@@ -124,7 +231,7 @@ pub fn transcribe_waveform_once_with_language(
                     size,
                     waveform,
                     sample_rate,
-                    language,
+                    options,
                 );
             }
             #[cfg(not(all(
@@ -133,7 +240,7 @@ pub fn transcribe_waveform_once_with_language(
                 not(target_os = "ios")
             )))]
             {
-                let _ = (size, waveform, sample_rate, language);
+                let _ = (size, waveform, sample_rate, options);
                 Err("Whisper Burn backend is unavailable on this build/target".to_string())
             }
         }
@@ -144,12 +251,12 @@ pub fn transcribe_waveform_once_with_language(
                     size,
                     waveform,
                     sample_rate,
-                    language,
+                    options,
                 );
             }
             #[cfg(not(all(feature = "whisper_ct2", not(target_arch = "wasm32"))))]
             {
-                let _ = (size, waveform, sample_rate, language);
+                let _ = (size, waveform, sample_rate, options);
                 Err("Whisper CT2 backend is unavailable on this build/target".to_string())
             }
         }
@@ -233,13 +340,25 @@ pub struct TranscriptionEngine {
     device_hint: String,
     pending_stdout: Vec<String>,
     pending_iter_events: Vec<Option<String>>,
+    /// Translation of the current utterance ([`Self::new_with_translation`] only).
+    live_translation: String,
+    /// Translations of committed lines, one per line that had one.
+    pending_translations: Vec<String>,
+    /// Latest language-ID result from the live decode thread (`language="auto"` only).
+    detected_language: Option<DetectedLanguage>,
+    /// Speaker of the last committed line when diarization is enabled.
+    last_speaker: Option<String>,
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
     decode_job_tx: Option<SyncSender<Vec<f32>>>,
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
-    decode_result_rx: Option<Receiver<String>>,
+    decode_result_rx: Option<Receiver<WhisperTranscript>>,
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+    /// `segment_pcm` at 16 kHz, minus the resampler's look-ahead (see [`Self::segment_16k`]).
     resample_buf: Vec<f32>,
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+    /// Band-limited `segment_input_rate` → 16 kHz converter feeding `resample_buf` as audio arrives.
+    segment_resampler: xos_audio::dsp::Resampler,
+    #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
     /// Mono PCM (input device rate), grown continuously while capturing (no phrase segmentation).
     segment_pcm: Vec<f32>,
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
@@ -260,6 +379,9 @@ pub struct TranscriptionEngine {
     #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
     /// Only append mono samples whose global frame index is **≥** this (skip ring audio already seen after a buffer reset).
     pcm_first_frame_inclusive: u64,
+    #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+    /// Labels committed lines with their speaker ([`TranscriptionEngine::enable_diarization`]).
+    diarizer: Option<diarization::Diarizer>,
     #[cfg(all(
         feature = "silero_vad",
         feature = "whisper",
@@ -274,6 +396,14 @@ pub struct TranscriptionEngine {
         not(target_arch = "wasm32"),
         not(target_os = "ios")
     ))]
+    /// Device rate → 16 kHz for the Silero feed (streaming, so chunk edges do not click).
+    vad_resampler: xos_audio::dsp::Resampler,
+    #[cfg(all(
+        feature = "silero_vad",
+        feature = "whisper",
+        not(target_arch = "wasm32"),
+        not(target_os = "ios")
+    ))]
     vad_session: Option<silero::SileroVadSession>,
     #[cfg(all(
         feature = "silero_vad",
@@ -308,17 +438,11 @@ pub struct TranscriptionEngine {
 }
 
 impl TranscriptionEngine {
-    fn normalize_decode_language(language: Option<&str>) -> Result<Option<String>, String> {
-        let Some(raw) = language.map(|s| s.trim().to_ascii_lowercase()) else {
-            return Ok(None);
-        };
-        if raw.is_empty() {
-            return Ok(None);
-        }
-        match raw.as_str() {
-            "english" | "en" => Ok(Some("en".to_string())),
-            "japanese" | "ja" | "jp" => Ok(Some("ja".to_string())),
-            _ => Err("language must be 'english' or 'japanese' (codes en, ja, jp)".to_string()),
+    /// Any Whisper language code or English name, or `"auto"`; unset keeps the backend default.
+    fn normalize_decode_language(language: Option<&str>) -> Result<Option<&'static str>, String> {
+        match language.map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(raw) => languages::resolve_language(raw).map(Some),
         }
     }
 
@@ -334,7 +458,13 @@ impl TranscriptionEngine {
         preferred_size: Option<&str>,
         backend: WhisperBackend,
     ) -> Self {
-        Self::new_with_size_backend_language(preferred_size, backend, None).unwrap_or_else(|e| {
+        let engine = Self::new_with_size_backend_language(
+            preferred_size,
+            backend,
+            None,
+            WhisperTask::Transcribe,
+        );
+        engine.unwrap_or_else(|e| {
             #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
             {
                 Self {
@@ -343,9 +473,17 @@ impl TranscriptionEngine {
                     device_hint: String::new(),
                     pending_stdout: Vec::new(),
                     pending_iter_events: Vec::new(),
+                    live_translation: String::new(),
+                    pending_translations: Vec::new(),
+                    detected_language: None,
+                    last_speaker: None,
                     decode_job_tx: None,
                     decode_result_rx: None,
                     resample_buf: Vec::new(),
+                    segment_resampler: xos_audio::dsp::Resampler::new(
+                        sample::WHISPER_HZ,
+                        sample::WHISPER_HZ,
+                    ),
                     segment_pcm: Vec::new(),
                     segment_input_rate: sample::WHISPER_HZ,
                     last_partial_decode: Instant::now()
@@ -356,6 +494,7 @@ impl TranscriptionEngine {
                     last_ingested_frames: None,
                     ingested_cursor_watermark: 0,
                     pcm_first_frame_inclusive: 0,
+                    diarizer: None,
                     #[cfg(all(
                         feature = "silero_vad",
                         feature = "whisper",
@@ -369,6 +508,16 @@ impl TranscriptionEngine {
                         not(target_arch = "wasm32"),
                         not(target_os = "ios")
                     ))]
+                    vad_resampler: xos_audio::dsp::Resampler::new(
+                        sample::WHISPER_HZ,
+                        sample::WHISPER_HZ,
+                    ),
+                    #[cfg(all(
+                        feature = "silero_vad",
+                        feature = "whisper",
+                        not(target_arch = "wasm32"),
+                        not(target_os = "ios")
+                    ))]
                     vad_session: None,
                     #[cfg(all(
                         feature = "silero_vad",
@@ -409,24 +558,62 @@ impl TranscriptionEngine {
                     device_hint: String::new(),
                     pending_stdout: Vec::new(),
                     pending_iter_events: Vec::new(),
+                    live_translation: String::new(),
+                    pending_translations: Vec::new(),
+                    detected_language: None,
+                    last_speaker: None,
                 }
             }
         })
     }
 
+    /// Live engine decoding in `language` (code, English name or `"auto"`); with
+    /// [`WhisperTask::Translate`] captions come out in English.
     pub fn new_with_size_backend_language(
         preferred_size: Option<&str>,
         backend: WhisperBackend,
         language: Option<&str>,
+        task: WhisperTask,
+    ) -> Result<Self, String> {
+        Self::new_live(preferred_size, backend, language, task, false)
+    }
+
+    /// Live engine transcribing in `language` whose decode thread also translates every job to
+    /// English, so [`Self::translation`] always covers the same audio as [`Self::caption`].
+    pub fn new_with_translation(
+        preferred_size: Option<&str>,
+        backend: WhisperBackend,
+        language: Option<&str>,
+    ) -> Result<Self, String> {
+        Self::new_live(
+            preferred_size,
+            backend,
+            language,
+            WhisperTask::Transcribe,
+            true,
+        )
+    }
+
+    fn new_live(
+        preferred_size: Option<&str>,
+        backend: WhisperBackend,
+        language: Option<&str>,
+        task: WhisperTask,
+        translate_too: bool,
     ) -> Result<Self, String> {
         let language = Self::normalize_decode_language(language)?;
         #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
         {
-            let (decode_job_tx, decode_result_rx, load_note) =
-                match spawn_live_decode_thread(preferred_size, backend, language.as_deref()) {
-                    Ok((tx, rx)) => (Some(tx), Some(rx), None),
-                    Err(e) => (None, None, Some(e)),
-                };
+            let (decode_job_tx, decode_result_rx, load_note) = match spawn_live_decode_thread(
+                preferred_size,
+                backend,
+                language,
+                task,
+                translate_too,
+            ) {
+                Ok((tx, rx)) => (Some(tx), Some(rx), None),
+                Err(e) => (None, None, Some(e)),
+            };
             let caption = if decode_job_tx.is_some() {
                 String::new()
             } else {
@@ -440,9 +627,17 @@ impl TranscriptionEngine {
                 device_hint: String::new(),
                 pending_stdout: Vec::new(),
                 pending_iter_events: Vec::new(),
+                live_translation: String::new(),
+                pending_translations: Vec::new(),
+                detected_language: None,
+                last_speaker: None,
                 decode_job_tx,
                 decode_result_rx,
                 resample_buf: Vec::new(),
+                segment_resampler: xos_audio::dsp::Resampler::new(
+                    sample::WHISPER_HZ,
+                    sample::WHISPER_HZ,
+                ),
                 segment_pcm: Vec::new(),
                 segment_input_rate: sample::WHISPER_HZ,
                 last_partial_decode: Instant::now()
@@ -453,6 +648,7 @@ impl TranscriptionEngine {
                 last_ingested_frames: None,
                 ingested_cursor_watermark: 0,
                 pcm_first_frame_inclusive: 0,
+                diarizer: None,
                 #[cfg(all(
                     feature = "silero_vad",
                     feature = "whisper",
@@ -466,6 +662,16 @@ impl TranscriptionEngine {
                     not(target_arch = "wasm32"),
                     not(target_os = "ios")
                 ))]
+                vad_resampler: xos_audio::dsp::Resampler::new(
+                    sample::WHISPER_HZ,
+                    sample::WHISPER_HZ,
+                ),
+                #[cfg(all(
+                    feature = "silero_vad",
+                    feature = "whisper",
+                    not(target_arch = "wasm32"),
+                    not(target_os = "ios")
+                ))]
                 vad_session: None,
                 #[cfg(all(
                     feature = "silero_vad",
@@ -499,13 +705,17 @@ impl TranscriptionEngine {
         }
         #[cfg(not(all(feature = "whisper", not(target_arch = "wasm32"))))]
         {
-            let _ = (preferred_size, backend, language);
+            let _ = (preferred_size, backend, language, task, translate_too);
             Ok(Self {
                 transcript_epoch: 0,
                 caption: String::new(),
                 device_hint: String::new(),
                 pending_stdout: Vec::new(),
                 pending_iter_events: Vec::new(),
+                live_translation: String::new(),
+                pending_translations: Vec::new(),
+                detected_language: None,
+                last_speaker: None,
             })
         }
     }
@@ -531,6 +741,23 @@ impl TranscriptionEngine {
         &self.device_hint
     }
 
+    /// Language the live decoder last detected when created with `language="auto"`.
+    pub fn detected_language(&self) -> Option<DetectedLanguage> {
+        self.detected_language
+    }
+
+    /// Speaker of the last committed line when diarization is enabled.
+    pub fn last_speaker(&self) -> Option<&str> {
+        self.last_speaker.as_deref()
+    }
+
+    /// Prefix each committed line with its speaker (`Alice: ...`, or `Speaker N` for voices not
+    /// enrolled in `diarizer`), identified from the utterance's audio at commit time.
+    #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
+    pub fn enable_diarization(&mut self, diarizer: diarization::Diarizer) {
+        self.diarizer = Some(diarizer);
+    }
+
     pub fn caption(&self) -> &str {
         #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
         if self.decode_job_tx.is_some() {
@@ -539,6 +766,12 @@ impl TranscriptionEngine {
         &self.caption
     }
 
+    /// English translation of the current caption; empty unless created with
+    /// [`Self::new_with_translation`].
+    pub fn translation(&self) -> &str {
+        &self.live_translation
+    }
+
     pub fn last_level_rms(&self) -> f32 {
         #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
         {
@@ -589,6 +822,11 @@ impl TranscriptionEngine {
         std::mem::take(&mut self.pending_stdout)
     }
 
+    /// Translations committed alongside [`Self::drain_stdout_commits`] lines.
+    pub fn drain_translation_commits(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_translations)
+    }
+
     pub fn drain_iter_events(&mut self) -> Vec<Option<String>> {
         std::mem::take(&mut self.pending_iter_events)
     }
@@ -607,9 +845,26 @@ impl TranscriptionEngine {
             if t.is_empty() {
                 return;
             }
+            let speaker = self
+                .diarizer
+                .as_mut()
+                .and_then(|d| d.identify(&self.segment_pcm, self.segment_input_rate).ok())
+                .flatten();
+            let t = match speaker {
+                Some(speaker) => {
+                    let line = format!("{speaker}: {t}");
+                    self.last_speaker = Some(speaker);
+                    line
+                }
+                None => t,
+            };
             self.pending_stdout.push(t.clone());
             self.pending_iter_events.push(Some(t));
             self.pending_iter_events.push(None);
+            let translation = normalize_transcript_ws(&self.live_translation);
+            if !translation.is_empty() {
+                self.pending_translations.push(translation);
+            }
             self.transcript_epoch = self.transcript_epoch.saturating_add(1);
         }
     }
@@ -622,9 +877,9 @@ impl TranscriptionEngine {
         #[cfg(all(feature = "whisper", not(target_arch = "wasm32")))]
         {
             self.pcm_first_frame_inclusive = self.ingested_cursor_watermark;
-            self.segment_pcm.clear();
-            self.resample_buf.clear();
+            self.segment_clear();
             self.live_transcript.clear();
+            self.live_translation.clear();
             self.last_partial_decode =
                 Instant::now() - Duration::from_millis(sample::GROWING_CLIP_PARTIAL_DECODE_MS);
             if let Some(rx) = &self.decode_result_rx {
@@ -689,7 +944,8 @@ const SILERO_VAD_HANGOVER: Duration = Duration::from_millis(280);
 impl TranscriptionEngine {
     fn reset_utterance_state(&mut self) {
         self.live_transcript.clear();
-        self.segment_pcm.clear();
+        self.live_translation.clear();
+        self.segment_clear();
         self.last_partial_decode =
             Instant::now() - Duration::from_millis(sample::GROWING_CLIP_PARTIAL_DECODE_MS);
         self.pcm_first_frame_inclusive = 0;
@@ -705,6 +961,7 @@ impl TranscriptionEngine {
         ))]
         {
             self.vad_16k_pending.clear();
+            self.vad_resampler.reset();
             self.vad_evaluated_once = false;
             if let Some(s) = self.vad_session.as_mut() {
                 s.reset();
@@ -732,17 +989,15 @@ impl TranscriptionEngine {
             return true;
         }
 
+        if first_snapshot || self.vad_resampler.rates().0 != sample_rate {
+            self.vad_resampler = xos_audio::dsp::Resampler::new(sample_rate, sample::WHISPER_HZ);
+        }
         let delta_16k: Vec<f32> = if first_snapshot {
-            sample::resample_linear(mono, sample_rate, sample::WHISPER_HZ)
+            self.vad_resampler.process(mono)
         } else if ingested_frames > prev_g {
             let delta = (ingested_frames - prev_g) as usize;
             let n = delta.min(mono.len());
-            if n == 0 {
-                Vec::new()
-            } else {
-                let tail = &mono[mono.len() - n..];
-                sample::resample_linear(tail, sample_rate, sample::WHISPER_HZ)
-            }
+            self.vad_resampler.process(&mono[mono.len() - n..])
         } else {
             Vec::new()
         };
@@ -821,7 +1076,7 @@ impl TranscriptionEngine {
             .saturating_sub(tail_first_frame)) as usize;
         let skip = skip.min(n);
         if skip < n {
-            self.segment_pcm.extend_from_slice(&tail[skip..]);
+            self.segment_extend(&tail[skip..]);
         }
     }
 
@@ -837,7 +1092,34 @@ impl TranscriptionEngine {
             .pcm_first_frame_inclusive
             .saturating_sub(oldest_in_buffer)) as usize;
         let skip = skip.min(l);
-        self.segment_pcm.extend_from_slice(&mono[skip..]);
+        self.segment_extend(&mono[skip..]);
+    }
+
+    /// Start an empty segment at `segment_input_rate`.
+    fn segment_clear(&mut self) {
+        self.segment_pcm.clear();
+        self.resample_buf.clear();
+        if self.segment_resampler.rates() == (self.segment_input_rate, sample::WHISPER_HZ) {
+            self.segment_resampler.reset();
+        } else {
+            self.segment_resampler =
+                xos_audio::dsp::Resampler::new(self.segment_input_rate, sample::WHISPER_HZ);
+        }
+    }
+
+    /// Append device-rate PCM to the segment, resampling only the new audio.
+    fn segment_extend(&mut self, pcm: &[f32]) {
+        self.segment_pcm.extend_from_slice(pcm);
+        let out = self.segment_resampler.process(pcm);
+        self.resample_buf.extend(out);
+    }
+
+    /// The whole segment at 16 kHz: the settled samples plus the last few milliseconds rendered
+    /// as if the segment ended here (they are redone once the following audio arrives).
+    fn segment_16k(&self) -> Vec<f32> {
+        let mut out = self.resample_buf.clone();
+        out.extend(self.segment_resampler.tail());
+        out
     }
 
     fn apply_partial_decode_line(&mut self, line: &str) {
@@ -856,21 +1138,18 @@ impl TranscriptionEngine {
         }
     }
 
-    /// Full growing-buffer resample + queue one decode job (`try_send` drops if the CT2 thread is busy).
+    /// Queue one decode job over the full growing segment at 16 kHz (`try_send` drops if the CT2
+    /// thread is busy).
     fn queue_segment_decode(&mut self) -> bool {
         if self.segment_pcm.is_empty() {
             return false;
         }
-        sample::resample_to_whisper_rate(
-            self.segment_input_rate,
-            &self.segment_pcm,
-            &mut self.resample_buf,
-        );
-        if self.resample_buf.len() < sample::MIN_DECODE_SAMPLES {
+        let job = self.segment_16k();
+        if job.len() < sample::MIN_DECODE_SAMPLES {
             return false;
         }
         let tx = self.decode_job_tx.as_ref().expect("checked");
-        tx.try_send(self.resample_buf.clone()).is_ok()
+        tx.try_send(job).is_ok()
     }
 
     fn process_snapshot_live(
@@ -900,7 +1179,7 @@ impl TranscriptionEngine {
         }
         let prev_g = self.last_ingested_frames.expect("checked");
 
-        let mono = sample::downmix_mono(channels);
+        let mono = xos_audio::dsp::downmix(channels);
         let n = mono.len().max(1);
         let tail_slow =
             ((sample_rate as usize).saturating_mul(sample::LEVEL_METER_TAIL_MS as usize) / 1000)
@@ -908,20 +1187,29 @@ impl TranscriptionEngine {
                 .min(n);
         self.last_level_rms = sample::rms_tail(&mono, tail_slow);
 
-        let mut decoded: Vec<String> = Vec::new();
+        let mut decoded: Vec<WhisperTranscript> = Vec::new();
         if let Some(rx) = &self.decode_result_rx {
-            while let Ok(line) = rx.try_recv() {
-                decoded.push(line);
+            while let Ok(out) = rx.try_recv() {
+                decoded.push(out);
             }
         }
-        for line in decoded {
-            self.apply_partial_decode_line(&line);
+        for out in decoded {
+            if out.detected_language.is_some() {
+                self.detected_language = out.detected_language;
+            }
+            self.apply_partial_decode_line(&out.text);
+            if let Some(translation) = out.translation {
+                let translation = normalize_transcript_ws(&translation);
+                if !translation.is_empty() && !translation.starts_with("(Whisper") {
+                    self.live_translation = translation;
+                }
+            }
         }
 
         if first_snapshot {
-            self.segment_pcm.clear();
-            self.seed_mono_respecting_pcm_watermark(&mono, ingested_frames);
             self.segment_input_rate = sample_rate;
+            self.segment_clear();
+            self.seed_mono_respecting_pcm_watermark(&mono, ingested_frames);
             self.last_partial_decode =
                 Instant::now() - Duration::from_millis(sample::GROWING_CLIP_PARTIAL_DECODE_MS);
         } else if ingested_frames > prev_g {
@@ -975,3 +1263,21 @@ impl Default for TranscriptionEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_task_names() {
+        assert_eq!(WhisperTask::from_str(""), Some(WhisperTask::Transcribe));
+        assert_eq!(
+            WhisperTask::from_str(" Translate "),
+            Some(WhisperTask::Translate)
+        );
+        assert_eq!(WhisperTask::from_str("summarize"), None);
+        for task in [WhisperTask::Transcribe, WhisperTask::Translate] {
+            assert_eq!(WhisperTask::from_str(task.as_str()), Some(task));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-//! Silero VAD (ONNX, 16 kHz) for gating live Whisper work. Weights: see [`silero_vad_download_links.json`].
+//! Silero VAD (ONNX, 16 kHz) for gating live Whisper work. Weights: `silero-vad` in the model registry.
 
 mod ensure;
 mod onnx;
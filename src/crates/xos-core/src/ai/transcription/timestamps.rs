@@ -0,0 +1,306 @@
+//! Timed transcription results shared by both backends: segments from Whisper's timestamp tokens,
+//! tokens (with probabilities) and words from cross-attention alignment, and their SRT / WebVTT
+//! renderings for captioning, optionally labelled with diarized speakers. All times are seconds
+//! from the start of the decoded waveform.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptToken {
+    pub id: u32,
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    pub probability: f32,
+}
+
+/// Consecutive tokens of one word; `text` keeps Whisper's leading space (`" world"`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptWord {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    /// Mean probability of the word's tokens.
+    pub probability: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptSegment {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// Probability of the no-speech token for the window this segment was decoded in.
+    pub no_speech_prob: f32,
+    /// Text tokens; empty unless tokens or word timestamps were requested.
+    pub tokens: Vec<TranscriptToken>,
+    /// Empty unless word timestamps were requested.
+    pub words: Vec<TranscriptWord>,
+    /// Speaker label from diarization ([`assign_speakers`]); `None` when not diarized.
+    pub speaker: Option<String>,
+}
+
+/// A stretch of audio attributed to one speaker by diarization.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeakerTurn {
+    pub start: f64,
+    pub end: f64,
+    /// Enrolled name, or `Speaker N` for voices that were not enrolled.
+    pub speaker: String,
+}
+
+/// Languages written without spaces between words get one "word" per decodable unit instead.
+pub fn language_splits_on_spaces(code: &str) -> bool {
+    !matches!(code, "zh" | "ja" | "th" | "lo" | "my" | "yue")
+}
+
+/// Group aligned tokens into words. `decode` turns a range of `tokens` into text; byte-level BPE
+/// can split one character across tokens, so a unit only ends once its text decodes without
+/// U+FFFD. With `splits_on_spaces`, a unit starting with a space opens a new word and anything
+/// else (including punctuation) joins the previous one.
+pub fn group_words(
+    tokens: &[TranscriptToken],
+    decode: impl Fn(Range<usize>) -> String,
+    splits_on_spaces: bool,
+) -> Vec<TranscriptWord> {
+    let mut units: Vec<(String, Range<usize>)> = Vec::new();
+    let mut unit_start = 0;
+    for end in 1..=tokens.len() {
+        let text = decode(unit_start..end);
+        if !text.contains('\u{FFFD}') || end == tokens.len() {
+            units.push((text, unit_start..end));
+            unit_start = end;
+        }
+    }
+
+    let mut groups: Vec<(String, Range<usize>)> = Vec::new();
+    for (text, range) in units {
+        match groups.last_mut() {
+            Some((word, word_range)) if splits_on_spaces && !text.starts_with(' ') => {
+                word.push_str(&text);
+                word_range.end = range.end;
+            }
+            _ => groups.push((text, range)),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(text, range)| {
+            let members = &tokens[range];
+            let probability =
+                members.iter().map(|t| t.probability).sum::<f32>() / members.len() as f32;
+            TranscriptWord {
+                text,
+                start: members[0].start,
+                end: members[members.len() - 1].end,
+                probability,
+            }
+        })
+        .collect()
+}
+
+/// Label each segment with the speaker whose turns overlap it most; a segment overlapping no turn
+/// (e.g. speech the VAD missed) takes the nearest one.
+pub fn assign_speakers(segments: &mut [TranscriptSegment], turns: &[SpeakerTurn]) {
+    for segment in segments.iter_mut() {
+        let mut overlap_by_speaker: Vec<(&str, f64)> = Vec::new();
+        for turn in turns {
+            let overlap = segment.end.min(turn.end) - segment.start.max(turn.start);
+            if overlap <= 0.0 {
+                continue;
+            }
+            match overlap_by_speaker
+                .iter_mut()
+                .find(|(speaker, _)| *speaker == turn.speaker)
+            {
+                Some((_, total)) => *total += overlap,
+                None => overlap_by_speaker.push((&turn.speaker, overlap)),
+            }
+        }
+        let best = overlap_by_speaker
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(speaker, _)| speaker)
+            .or_else(|| {
+                let distance = |t: &SpeakerTurn| (t.start - segment.end).max(segment.start - t.end);
+                turns
+                    .iter()
+                    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                    .map(|t| t.speaker.as_str())
+            });
+        segment.speaker = best.map(str::to_string);
+    }
+}
+
+/// `HH:MM:SS<sep>mmm` (`,` for SRT, `.` for WebVTT).
+fn format_timestamp(seconds: f64, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Segments with non-blank text.
+fn cues(segments: &[TranscriptSegment]) -> impl Iterator<Item = &TranscriptSegment> {
+    segments.iter().filter(|s| !s.text.trim().is_empty())
+}
+
+/// SubRip captions, one cue per non-empty segment; speakers prefix the text (`Alice: ...`).
+pub fn to_srt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::new();
+    for (index, segment) in cues(segments).enumerate() {
+        let speaker = match &segment.speaker {
+            Some(name) => format!("{name}: "),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{speaker}{}\n\n",
+            index + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ','),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+/// WebVTT captions, one cue per non-empty segment; speakers become voice spans (`<v Alice>`).
+pub fn to_vtt(segments: &[TranscriptSegment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in cues(segments) {
+        let speaker = match &segment.speaker {
+            Some(name) => format!("<v {name}>"),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "{} --> {}\n{speaker}{}\n\n",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.'),
+            segment.text.trim()
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start: f64, end: f64, probability: f32) -> TranscriptToken {
+        TranscriptToken {
+            id: 0,
+            text: text.to_string(),
+            start,
+            end,
+            probability,
+        }
+    }
+
+    #[test]
+    fn words_split_on_spaces_and_keep_punctuation() {
+        let tokens = [
+            token(" Hel", 0.0, 0.2, 0.8),
+            token("lo", 0.2, 0.4, 0.6),
+            token(",", 0.4, 0.5, 1.0),
+            token(" world", 0.5, 1.0, 0.5),
+        ];
+        let decode = |r: Range<usize>| tokens[r].iter().map(|t| t.text.as_str()).collect();
+        let words = group_words(&tokens, decode, true);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, [" Hello,", " world"]);
+        assert_eq!((words[0].start, words[0].end), (0.0, 0.5));
+        assert!((words[0].probability - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unspaced_languages_split_per_decodable_unit() {
+        // "日本" where "日" takes two byte-level tokens.
+        let tokens = [
+            token("", 0.0, 0.1, 1.0),
+            token("", 0.1, 0.2, 1.0),
+            token("", 0.2, 0.4, 1.0),
+        ];
+        let decode = |r: Range<usize>| match (r.start, r.end) {
+            (0, 1) => "\u{FFFD}".to_string(),
+            (0, 2) => "日".to_string(),
+            _ => "本".to_string(),
+        };
+        let words = group_words(&tokens, decode, language_splits_on_spaces("ja"));
+        let spans: Vec<(&str, f64, f64)> = words
+            .iter()
+            .map(|w| (w.text.as_str(), w.start, w.end))
+            .collect();
+        assert_eq!(spans, [("日", 0.0, 0.2), ("本", 0.2, 0.4)]);
+    }
+
+    #[test]
+    fn srt_and_vtt_cues() {
+        let segments = [
+            TranscriptSegment {
+                start: 0.0,
+                end: 2.5,
+                text: " Hello there.".to_string(),
+                ..Default::default()
+            },
+            TranscriptSegment {
+                start: 2.5,
+                end: 3.0,
+                text: " ".to_string(),
+                ..Default::default()
+            },
+            TranscriptSegment {
+                start: 3725.0,
+                end: 3726.0456,
+                text: "Bye.".to_string(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            to_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello there.\n\n\
+             2\n01:02:05,000 --> 01:02:06,046\nBye.\n\n"
+        );
+        assert_eq!(
+            to_vtt(&segments),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:02.500\nHello there.\n\n\
+             01:02:05.000 --> 01:02:06.046\nBye.\n\n"
+        );
+    }
+
+    #[test]
+    fn speakers_follow_the_largest_overlap() {
+        let segment = |start, end| TranscriptSegment {
+            start,
+            end,
+            text: "hi".to_string(),
+            ..Default::default()
+        };
+        let turn = |start, end, speaker: &str| SpeakerTurn {
+            start,
+            end,
+            speaker: speaker.to_string(),
+        };
+        let mut segments = [segment(0.0, 4.0), segment(4.0, 6.0), segment(9.0, 9.5)];
+        let turns = [
+            turn(0.0, 1.5, "Alice"),
+            turn(1.5, 3.0, "Speaker 1"),
+            turn(3.0, 5.5, "Alice"),
+            turn(5.5, 8.0, "Speaker 1"),
+        ];
+        assign_speakers(&mut segments, &turns);
+        let speakers: Vec<Option<&str>> = segments.iter().map(|s| s.speaker.as_deref()).collect();
+        assert_eq!(speakers, [Some("Alice"), Some("Alice"), Some("Speaker 1")]);
+
+        segments[1].speaker = None;
+        assert_eq!(
+            to_srt(&segments[..2]),
+            "1\n00:00:00,000 --> 00:00:04,000\nAlice: hi\n\n\
+             2\n00:00:04,000 --> 00:00:06,000\nhi\n\n"
+        );
+        assert!(to_vtt(&segments[..1]).ends_with("<v Alice>hi\n\n"));
+    }
+}
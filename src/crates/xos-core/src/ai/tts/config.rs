@@ -0,0 +1,121 @@
+//! VITS hyper-parameters from a Hugging Face `config.json` (`VitsModel`, e.g. the MMS-TTS voices).
+//! Missing keys take the `transformers` defaults.
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct VitsConfig {
+    pub vocab_size: usize,
+    pub hidden_size: usize,
+    pub num_hidden_layers: usize,
+    pub num_attention_heads: usize,
+    /// Relative-position window of the text encoder's attention (`None`: absolute only).
+    pub window_size: Option<usize>,
+    pub ffn_kernel_size: usize,
+    pub flow_size: usize,
+    pub layer_norm_eps: f64,
+    pub use_stochastic_duration_prediction: bool,
+    pub num_speakers: usize,
+    pub speaker_embedding_size: usize,
+    pub upsample_rates: Vec<usize>,
+    pub upsample_kernel_sizes: Vec<usize>,
+    pub resblock_kernel_sizes: Vec<usize>,
+    pub resblock_dilation_sizes: Vec<Vec<usize>>,
+    pub leaky_relu_slope: f32,
+    pub depth_separable_num_layers: usize,
+    pub duration_predictor_kernel_size: usize,
+    pub duration_predictor_flow_bins: usize,
+    pub duration_predictor_tail_bound: f32,
+    pub duration_predictor_num_flows: usize,
+    pub prior_encoder_num_flows: usize,
+    pub prior_encoder_num_wavenet_layers: usize,
+    pub wavenet_kernel_size: usize,
+    pub wavenet_dilation_rate: usize,
+    /// Default speed; `SpeakOptions::speed` multiplies it.
+    pub speaking_rate: f32,
+    /// Default sampling temperature of the acoustic latents.
+    pub noise_scale: f32,
+    /// Default sampling temperature of the durations.
+    pub noise_scale_duration: f32,
+    pub sampling_rate: u32,
+}
+
+impl Default for VitsConfig {
+    fn default() -> Self {
+        Self {
+            vocab_size: 38,
+            hidden_size: 192,
+            num_hidden_layers: 6,
+            num_attention_heads: 2,
+            window_size: Some(4),
+            ffn_kernel_size: 3,
+            flow_size: 192,
+            layer_norm_eps: 1e-5,
+            use_stochastic_duration_prediction: true,
+            num_speakers: 1,
+            speaker_embedding_size: 0,
+            upsample_rates: vec![8, 8, 2, 2],
+            upsample_kernel_sizes: vec![16, 16, 4, 4],
+            resblock_kernel_sizes: vec![3, 7, 11],
+            resblock_dilation_sizes: vec![vec![1, 3, 5]; 3],
+            leaky_relu_slope: 0.1,
+            depth_separable_num_layers: 3,
+            duration_predictor_kernel_size: 3,
+            duration_predictor_flow_bins: 10,
+            duration_predictor_tail_bound: 5.0,
+            duration_predictor_num_flows: 4,
+            prior_encoder_num_flows: 4,
+            prior_encoder_num_wavenet_layers: 4,
+            wavenet_kernel_size: 5,
+            wavenet_dilation_rate: 1,
+            speaking_rate: 1.0,
+            noise_scale: 0.667,
+            noise_scale_duration: 0.8,
+            sampling_rate: 16_000,
+        }
+    }
+}
+
+impl VitsConfig {
+    pub fn from_hf_json(text: &str) -> Result<Self, String> {
+        let config: Self = serde_json::from_str(text).map_err(|e| format!("config.json: {e}"))?;
+        let heads = config.num_attention_heads;
+        if heads == 0 || !config.hidden_size.is_multiple_of(heads) {
+            return Err(format!(
+                "unsupported attention layout: hidden {} / heads {heads}",
+                config.hidden_size
+            ));
+        }
+        if config.upsample_rates.len() != config.upsample_kernel_sizes.len()
+            || config.resblock_kernel_sizes.len() != config.resblock_dilation_sizes.len()
+        {
+            return Err("config.json: decoder kernel and rate lists differ in length".to_string());
+        }
+        if !config.flow_size.is_multiple_of(2) {
+            return Err(format!("config.json: odd flow_size {}", config.flow_size));
+        }
+        Ok(config)
+    }
+
+    /// Output samples per spectrogram frame.
+    pub fn hop_length(&self) -> usize {
+        self.upsample_rates.iter().product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_defaults_and_rejects_bad_layouts() {
+        let config =
+            VitsConfig::from_hf_json(r#"{"vocab_size": 40, "num_speakers": 3, "foo": 1}"#).unwrap();
+        assert_eq!((config.vocab_size, config.num_speakers), (40, 3));
+        assert_eq!(config.hop_length(), 256);
+        assert_eq!(config.window_size, Some(4));
+        assert!(VitsConfig::from_hf_json(r#"{"num_attention_heads": 5}"#).is_err());
+        assert!(VitsConfig::from_hf_json(r#"{"upsample_rates": [8, 8]}"#).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Local text-to-speech on Burn (NdArray on the CPU or WGPU on the GPU), running VITS voices in the
+//! Hugging Face layout (`VitsModel`, e.g. Meta's MMS-TTS: one character-level voice per language).
+//!
+//! Voices come from the registry (`mms-tts-eng`, downloaded on first use) or a folder holding
+//! `config.json`, `*.safetensors` and `vocab.json`. Text is split into sentences and synthesized
+//! one at a time, so playback can start after the first sentence ([`TtsModel::stream`]).
+
+mod config;
+mod model;
+mod spline;
+mod text;
+mod weights;
+
+use std::path::{Path, PathBuf};
+
+use burn::backend::{ndarray::NdArrayDevice, wgpu::WgpuDevice, NdArray, Wgpu};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+use model::{Controls, TtsNet, VitsRuntime};
+use text::VitsTokenizer;
+use weights::Checkpoint;
+
+pub use config::VitsConfig;
+pub use text::split_sentences;
+
+/// Registry voice used when none is named.
+pub const DEFAULT_MODEL: &str = "mms-tts-eng";
+
+/// Values kept per parameter for inspection (`TtsParameter::values`).
+const PARAMETER_HEAD: usize = 128;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TtsDevice {
+    #[default]
+    Cpu,
+    Gpu,
+}
+
+impl TtsDevice {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpu" | "ndarray" => Some(Self::Cpu),
+            "gpu" | "wgpu" => Some(Self::Gpu),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpeakOptions {
+    /// Speaking rate relative to the voice's own (`2.0` is twice as fast).
+    pub speed: f32,
+    /// Speaker id of a multi-speaker voice (`None`: speaker 0).
+    pub speaker: Option<usize>,
+    /// Variation of the voice (`None`: the voice's `noise_scale`, usually 0.667; 0 is flat).
+    pub noise_scale: Option<f32>,
+    /// Variation of the timing (`None`: the voice's `noise_scale_duration`, usually 0.8).
+    pub noise_scale_duration: Option<f32>,
+    /// Silence between sentences, in seconds.
+    pub sentence_pause: f32,
+    /// Sentences longer than this many characters are split at commas or spaces.
+    pub max_sentence_chars: usize,
+    /// Fixed seed for reproducible audio; `None` seeds from the OS.
+    pub seed: Option<u64>,
+}
+
+impl Default for SpeakOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            speaker: None,
+            noise_scale: None,
+            noise_scale_duration: None,
+            sentence_pause: 0.2,
+            max_sentence_chars: 300,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TtsParameter {
+    /// Hugging Face name, e.g. `text_encoder.embed_tokens.weight`; weight-normed convolutions keep
+    /// their split `weight_g` / `weight_v` names here.
+    pub name: String,
+    pub shape: Vec<usize>,
+    /// Storage type in the checkpoint; inference always runs in `f32`.
+    pub dtype: String,
+    /// The first values (row-major), widened to `f32`.
+    pub values: Vec<f32>,
+    pub stats: Option<TensorDebugStats>,
+}
+
+pub struct TtsModel {
+    config: VitsConfig,
+    tokenizer: VitsTokenizer,
+    net: Box<dyn TtsNet>,
+    parameters: Vec<TtsParameter>,
+    weights_files: Vec<PathBuf>,
+}
+
+impl TtsModel {
+    /// `source` is a registry name (downloaded on first use) or a folder with `config.json`,
+    /// `*.safetensors` and `vocab.json`.
+    pub fn load(source: &str, device: TtsDevice) -> Result<Self, String> {
+        let path = Path::new(source);
+        let dir = if path.is_dir() {
+            path.to_path_buf()
+        } else if path.exists() {
+            return Err(format!(
+                "{source}: expected a folder with config.json, *.safetensors and vocab.json"
+            ));
+        } else {
+            crate::ai::models::ensure(source)?
+        };
+        let tokenizer = VitsTokenizer::load(&dir)?;
+        let mut ckpt = Checkpoint::load(&dir)?;
+        let parameters = ckpt
+            .tensors
+            .iter()
+            .map(|(name, t)| TtsParameter {
+                name: name.clone(),
+                shape: t.shape.clone(),
+                dtype: t.dtype.clone(),
+                values: t.data.iter().take(PARAMETER_HEAD).copied().collect(),
+                stats: model::stats(&t.data),
+            })
+            .collect();
+        let config = ckpt.config.clone();
+        let weights_files = ckpt.files.clone();
+        let net: Box<dyn TtsNet> = match device {
+            TtsDevice::Cpu => {
+                Box::new(VitsRuntime::<NdArray>::load(&mut ckpt, NdArrayDevice::Cpu)?)
+            }
+            TtsDevice::Gpu => {
+                Box::new(VitsRuntime::<Wgpu>::load(&mut ckpt, WgpuDevice::default())?)
+            }
+        };
+        Ok(Self {
+            config,
+            tokenizer,
+            net,
+            parameters,
+            weights_files,
+        })
+    }
+
+    pub fn config(&self) -> &VitsConfig {
+        &self.config
+    }
+
+    /// Output rate in Hz (16 kHz for the MMS voices).
+    pub fn sample_rate(&self) -> u32 {
+        self.config.sampling_rate
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.net.num_speakers()
+    }
+
+    pub fn parameters(&self) -> &[TtsParameter] {
+        &self.parameters
+    }
+
+    pub fn weights_files(&self) -> &[PathBuf] {
+        &self.weights_files
+    }
+
+    /// Token ids the voice reads for `text` (characters it cannot say are dropped).
+    pub fn tokenize(&self, text: &str) -> Vec<u32> {
+        self.tokenizer.encode(text)
+    }
+
+    /// The whole of `text` as one mono waveform at [`Self::sample_rate`], in `[-1, 1]`.
+    pub fn synthesize(&self, text: &str, options: &SpeakOptions) -> Result<Vec<f32>, String> {
+        let mut out = Vec::new();
+        self.stream(text, options, &mut |chunk| {
+            out.extend_from_slice(chunk);
+            true
+        })?;
+        Ok(out)
+    }
+
+    /// Synthesize `text` sentence by sentence, handing each chunk (with the pause that follows
+    /// it) to `on_chunk` as soon as it is ready. Returning `false` stops early.
+    pub fn stream(
+        &self,
+        text: &str,
+        options: &SpeakOptions,
+        on_chunk: &mut dyn FnMut(&[f32]) -> bool,
+    ) -> Result<(), String> {
+        let controls = self.controls(options)?;
+        let mut rng = rng(options.seed);
+        let pause = (options.sentence_pause.max(0.0) * self.sample_rate() as f32) as usize;
+        let sentences = split_sentences(text, options.max_sentence_chars);
+        let last = sentences.len().saturating_sub(1);
+        for (i, sentence) in sentences.iter().enumerate() {
+            let ids = self.tokenizer.encode(sentence);
+            if ids.iter().all(|&id| id == 0) {
+                continue;
+            }
+            let mut wave = self.net.synthesize(&ids, &controls, &mut rng)?;
+            if i < last {
+                wave.resize(wave.len() + pause, 0.0);
+            }
+            if !on_chunk(&wave) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every stage (text encoder layers, durations, prior, flows, vocoder) for `text` read as one
+    /// utterance.
+    pub fn forward_layer_by_layer(
+        &self,
+        text: &str,
+        options: &SpeakOptions,
+    ) -> Result<Vec<ActivationStep>, String> {
+        let ids = self.tokenizer.encode(text);
+        if ids.iter().all(|&id| id == 0) {
+            return Err("no speakable characters in text".to_string());
+        }
+        let controls = self.controls(options)?;
+        self.net
+            .layer_by_layer(&ids, &controls, &mut rng(options.seed))
+    }
+
+    fn controls(&self, options: &SpeakOptions) -> Result<Controls, String> {
+        let speed = options.speed * self.config.speaking_rate;
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(format!("speed must be > 0, got {}", options.speed));
+        }
+        Ok(Controls {
+            length_scale: 1.0 / speed,
+            noise_scale: options.noise_scale.unwrap_or(self.config.noise_scale),
+            noise_scale_duration: options
+                .noise_scale_duration
+                .unwrap_or(self.config.noise_scale_duration),
+            speaker: options.speaker,
+        })
+    }
+}
+
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    }
+}
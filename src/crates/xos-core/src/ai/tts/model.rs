@@ -0,0 +1,1354 @@
+//! VITS in Burn, generic over the backend (NdArray on the CPU, WGPU on the GPU), with the layout of
+//! Hugging Face's `VitsModel`:
+//!
+//! - a transformer text encoder (relative-position attention) that predicts a prior over the
+//!   acoustic latents for every token;
+//! - a duration predictor (stochastic: spline flows sampled from noise, or a plain conv stack)
+//!   that decides how many frames each token lasts;
+//! - residual coupling flows that map the sampled prior to the decoder's latent space;
+//! - a HiFi-GAN decoder that upsamples latents to a waveform.
+//!
+//! Only the inference path is built; the posterior encoder (training only) is never loaded.
+
+use burn::{
+    module::{Module, Param},
+    tensor::{
+        activation::{gelu, leaky_relu, relu, sigmoid, softmax, tanh},
+        backend::Backend,
+        module::{conv1d, conv_transpose1d, embedding},
+        ops::{ConvOptions, ConvTransposeOptions},
+        Int, Tensor, TensorData,
+    },
+};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use super::config::VitsConfig;
+use super::spline;
+use super::weights::{Checkpoint, WeightTensor};
+use crate::ai::transcription::{ActivationStep, TensorDebugStats};
+
+/// LayerNorm epsilon of the duration predictor (PyTorch's default; the text encoder's comes from
+/// the config).
+const NORM_EPS: f32 = 1e-5;
+
+/// Slope of the activation before HiFi-GAN's last conv (PyTorch's `leaky_relu` default).
+const FINAL_SLOPE: f64 = 0.01;
+
+/// Longest audio one call may produce, in seconds (a guard against runaway durations).
+const MAX_SECONDS: usize = 120;
+
+fn param<B: Backend, const D: usize>(
+    t: WeightTensor,
+    shape: [usize; D],
+    device: &B::Device,
+) -> Result<Param<Tensor<B, D>>, String> {
+    if t.data.len() != shape.iter().product::<usize>() {
+        return Err(format!("expected shape {shape:?}, got {:?}", t.shape));
+    }
+    Ok(Param::from_tensor(Tensor::from_data(
+        TensorData::new(t.data, shape),
+        device,
+    )))
+}
+
+fn dims3(t: &WeightTensor, name: &str) -> Result<[usize; 3], String> {
+    match t.shape[..] {
+        [a, b, c] => Ok([a, b, c]),
+        _ => Err(format!("{name}: expected a 3-D weight, got {:?}", t.shape)),
+    }
+}
+
+/// Conv1d that keeps the length ("same" padding, split left / right like PyTorch for even spans).
+#[derive(Module, Debug)]
+struct Conv<B: Backend> {
+    weight: Param<Tensor<B, 3>>,
+    bias: Option<Param<Tensor<B, 1>>>,
+    pad_left: usize,
+    pad_right: usize,
+    dilation: usize,
+    groups: usize,
+}
+
+impl<B: Backend> Conv<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        dilation: usize,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        Self::load_grouped(ckpt, name, dilation, false, device)
+    }
+
+    /// One filter per channel (`groups == channels`).
+    fn depthwise(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        dilation: usize,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        Self::load_grouped(ckpt, name, dilation, true, device)
+    }
+
+    fn load_grouped(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        dilation: usize,
+        depthwise: bool,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let w = ckpt.take_weight(name)?;
+        let [out_c, in_c, k] = dims3(&w, name)?;
+        let bias_name = format!("{name}.bias");
+        let bias = if ckpt.has(&bias_name) {
+            Some(param(ckpt.take(&bias_name)?, [out_c], device)?)
+        } else {
+            None
+        };
+        let span = dilation * (k - 1);
+        Ok(Self {
+            weight: param(w, [out_c, in_c, k], device)?,
+            bias,
+            pad_left: span / 2,
+            pad_right: span - span / 2,
+            dilation,
+            groups: if depthwise { out_c } else { 1 },
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let bias = self.bias.as_ref().map(|b| b.val());
+        let (x, padding) = if self.pad_left == self.pad_right {
+            (x, self.pad_left)
+        } else {
+            let [b, c, _] = x.dims();
+            let device = x.device();
+            let zeros = |n| Tensor::zeros([b, c, n], &device);
+            (
+                Tensor::cat(vec![zeros(self.pad_left), x, zeros(self.pad_right)], 2),
+                0,
+            )
+        };
+        let options = ConvOptions::new([1], [padding], [self.dilation], self.groups);
+        conv1d(x, self.weight.val(), bias, options)
+    }
+}
+
+/// HiFi-GAN's transposed-conv upsampler.
+#[derive(Module, Debug)]
+struct Upsample<B: Backend> {
+    weight: Param<Tensor<B, 3>>,
+    bias: Param<Tensor<B, 1>>,
+    stride: usize,
+    padding: usize,
+}
+
+impl<B: Backend> Upsample<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        stride: usize,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let w = ckpt.take_weight(name)?;
+        let [in_c, out_c, k] = dims3(&w, name)?;
+        Ok(Self {
+            weight: param(w, [in_c, out_c, k], device)?,
+            bias: param(ckpt.take(&format!("{name}.bias"))?, [out_c], device)?,
+            stride,
+            padding: k.saturating_sub(stride) / 2,
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let options = ConvTransposeOptions::new([self.stride], [self.padding], [0], [1], 1);
+        conv_transpose1d(x, self.weight.val(), Some(self.bias.val()), options)
+    }
+}
+
+#[derive(Module, Debug)]
+struct Linear<B: Backend> {
+    /// `[in, out]` (transposed from PyTorch).
+    weight: Param<Tensor<B, 2>>,
+    bias: Param<Tensor<B, 1>>,
+}
+
+impl<B: Backend> Linear<B> {
+    fn load(ckpt: &mut Checkpoint, name: &str, device: &B::Device) -> Result<Self, String> {
+        let w = ckpt.take(&format!("{name}.weight"))?;
+        let [out_f, in_f] = match w.shape[..] {
+            [o, i] => [o, i],
+            _ => return Err(format!("{name}.weight: expected 2-D, got {:?}", w.shape)),
+        };
+        let weight: Tensor<B, 2> = param(w, [out_f, in_f], device)?.val();
+        Ok(Self {
+            weight: Param::from_tensor(weight.transpose()),
+            bias: param(ckpt.take(&format!("{name}.bias"))?, [out_f], device)?,
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        x.matmul(self.weight.val()) + self.bias.val().unsqueeze()
+    }
+}
+
+/// LayerNorm over the channel axis.
+#[derive(Module, Debug)]
+struct Norm<B: Backend> {
+    gamma: Param<Tensor<B, 1>>,
+    beta: Param<Tensor<B, 1>>,
+    eps: f32,
+}
+
+impl<B: Backend> Norm<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        eps: f32,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let gamma = ckpt.take(&format!("{name}.weight"))?;
+        let c = gamma.data.len();
+        Ok(Self {
+            gamma: param(gamma, [c], device)?,
+            beta: param(ckpt.take(&format!("{name}.bias"))?, [c], device)?,
+            eps,
+        })
+    }
+
+    fn normalize<const D: usize>(&self, x: Tensor<B, D>, dim: usize) -> Tensor<B, D> {
+        let mut shape = [1; D];
+        shape[dim] = self.gamma.dims()[0];
+        let centered = x.clone() - x.mean_dim(dim);
+        let var = centered.clone().powf_scalar(2.0).mean_dim(dim);
+        centered / var.add_scalar(self.eps).sqrt() * self.gamma.val().reshape(shape)
+            + self.beta.val().reshape(shape)
+    }
+
+    /// `x`: `[1, channels, time]`.
+    fn channels(&self, x: Tensor<B, 3>) -> Tensor<B, 3> {
+        self.normalize(x, 1)
+    }
+
+    /// `x`: `[time, channels]`.
+    fn rows(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        self.normalize(x, 1)
+    }
+}
+
+/// Multi-head self-attention with learned relative-position embeddings for offsets within
+/// `window` (VITS's encoder; no absolute positions).
+#[derive(Module, Debug)]
+struct Attention<B: Backend> {
+    q: Linear<B>,
+    k: Linear<B>,
+    v: Linear<B>,
+    out: Linear<B>,
+    /// `[2 * window + 1, head_dim]`, offset `-window` first.
+    rel_k: Option<Param<Tensor<B, 2>>>,
+    rel_v: Option<Param<Tensor<B, 2>>>,
+    heads: usize,
+    window: usize,
+}
+
+impl<B: Backend> Attention<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        heads: usize,
+        window: Option<usize>,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let q = Linear::load(ckpt, &format!("{name}.q_proj"), device)?;
+        let head_dim = q.weight.dims()[1] / heads;
+        let mut rel = |which: &str| -> Result<Option<Param<Tensor<B, 2>>>, String> {
+            match window {
+                Some(w) => Ok(Some(param(
+                    ckpt.take(&format!("{name}.emb_rel_{which}"))?,
+                    [2 * w + 1, head_dim],
+                    device,
+                )?)),
+                None => Ok(None),
+            }
+        };
+        let (rel_k, rel_v) = (rel("k")?, rel("v")?);
+        Ok(Self {
+            q,
+            k: Linear::load(ckpt, &format!("{name}.k_proj"), device)?,
+            v: Linear::load(ckpt, &format!("{name}.v_proj"), device)?,
+            out: Linear::load(ckpt, &format!("{name}.out_proj"), device)?,
+            rel_k,
+            rel_v,
+            heads,
+            window: window.unwrap_or(0),
+        })
+    }
+
+    /// `(embedding row, [1, t, t] indicator of key = query + offset)` for each offset in reach.
+    fn bands(&self, t: usize, device: &B::Device) -> Vec<(usize, Tensor<B, 3>)> {
+        let w = self.window as isize;
+        (-w..=w)
+            .filter(|r| r.unsigned_abs() < t)
+            .map(|r| {
+                let mut m = vec![0.0f32; t * t];
+                for i in 0..t {
+                    let j = i as isize + r;
+                    if (0..t as isize).contains(&j) {
+                        m[i * t + j as usize] = 1.0;
+                    }
+                }
+                let band = Tensor::from_data(TensorData::new(m, [1, t, t]), device);
+                ((r + w) as usize, band)
+            })
+            .collect()
+    }
+
+    /// `x`: `[time, hidden]`.
+    fn forward(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        let [t, hidden] = x.dims();
+        let (heads, head_dim) = (self.heads, hidden / self.heads);
+        let split = |y: Tensor<B, 2>| y.reshape([t, heads, head_dim]).swap_dims(0, 1);
+        let q = split(
+            self.q
+                .forward(x.clone())
+                .mul_scalar((head_dim as f32).powf(-0.5)),
+        );
+        let k = split(self.k.forward(x.clone()));
+        let v = split(self.v.forward(x.clone()));
+        let bands = self.bands(t, &x.device());
+        let mut logits = q.clone().matmul(k.swap_dims(1, 2));
+        if let Some(rel_k) = &self.rel_k {
+            let rel = q.matmul(rel_k.val().transpose().unsqueeze::<3>());
+            for (row, band) in &bands {
+                logits = logits + rel.clone().narrow(2, *row, 1) * band.clone();
+            }
+        }
+        let probs = softmax(logits, 2);
+        let mut out = probs.clone().matmul(v);
+        if let Some(rel_v) = &self.rel_v {
+            for (row, band) in &bands {
+                let weight = (probs.clone() * band.clone()).sum_dim(2);
+                out = out + weight * rel_v.val().narrow(0, *row, 1).unsqueeze::<3>();
+            }
+        }
+        self.out.forward(out.swap_dims(0, 1).reshape([t, hidden]))
+    }
+}
+
+/// Post-norm transformer layer with a convolutional feed-forward.
+#[derive(Module, Debug)]
+struct EncoderLayer<B: Backend> {
+    attention: Attention<B>,
+    norm: Norm<B>,
+    conv_1: Conv<B>,
+    conv_2: Conv<B>,
+    final_norm: Norm<B>,
+}
+
+impl<B: Backend> EncoderLayer<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let eps = config.layer_norm_eps as f32;
+        Ok(Self {
+            attention: Attention::load(
+                ckpt,
+                &format!("{name}.attention"),
+                config.num_attention_heads,
+                config.window_size,
+                device,
+            )?,
+            norm: Norm::load(ckpt, &format!("{name}.layer_norm"), eps, device)?,
+            conv_1: Conv::load(ckpt, &format!("{name}.feed_forward.conv_1"), 1, device)?,
+            conv_2: Conv::load(ckpt, &format!("{name}.feed_forward.conv_2"), 1, device)?,
+            final_norm: Norm::load(ckpt, &format!("{name}.final_layer_norm"), eps, device)?,
+        })
+    }
+
+    fn forward(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        let x = self.norm.rows(x.clone() + self.attention.forward(x));
+        let ff = x.clone().transpose().unsqueeze::<3>();
+        let ff = self.conv_2.forward(relu(self.conv_1.forward(ff)));
+        self.final_norm.rows(x + ff.squeeze_dim::<2>(0).transpose())
+    }
+}
+
+/// Dilated depthwise-separable conv stack (duration predictor and its flows).
+#[derive(Module, Debug)]
+struct Dds<B: Backend> {
+    dilated: Vec<Conv<B>>,
+    pointwise: Vec<Conv<B>>,
+    norms_1: Vec<Norm<B>>,
+    norms_2: Vec<Norm<B>>,
+}
+
+impl<B: Backend> Dds<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let kernel = config.duration_predictor_kernel_size;
+        let mut dds = Self {
+            dilated: Vec::new(),
+            pointwise: Vec::new(),
+            norms_1: Vec::new(),
+            norms_2: Vec::new(),
+        };
+        for i in 0..config.depth_separable_num_layers {
+            let dilation = kernel.pow(i as u32);
+            dds.dilated.push(Conv::depthwise(
+                ckpt,
+                &format!("{name}.convs_dilated.{i}"),
+                dilation,
+                device,
+            )?);
+            dds.pointwise.push(Conv::load(
+                ckpt,
+                &format!("{name}.convs_pointwise.{i}"),
+                1,
+                device,
+            )?);
+            dds.norms_1.push(Norm::load(
+                ckpt,
+                &format!("{name}.norms_1.{i}"),
+                NORM_EPS,
+                device,
+            )?);
+            dds.norms_2.push(Norm::load(
+                ckpt,
+                &format!("{name}.norms_2.{i}"),
+                NORM_EPS,
+                device,
+            )?);
+        }
+        Ok(dds)
+    }
+
+    fn forward(&self, mut x: Tensor<B, 3>, cond: Option<Tensor<B, 3>>) -> Tensor<B, 3> {
+        if let Some(cond) = cond {
+            x = x + cond;
+        }
+        for i in 0..self.dilated.len() {
+            let h = gelu(self.norms_1[i].channels(self.dilated[i].forward(x.clone())));
+            let h = gelu(self.norms_2[i].channels(self.pointwise[i].forward(h)));
+            x = x + h;
+        }
+        x
+    }
+}
+
+/// Spline coupling flow of the stochastic duration predictor.
+#[derive(Module, Debug)]
+struct ConvFlow<B: Backend> {
+    conv_pre: Conv<B>,
+    conv_dds: Dds<B>,
+    conv_proj: Conv<B>,
+}
+
+impl<B: Backend> ConvFlow<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            conv_pre: Conv::load(ckpt, &format!("{name}.conv_pre"), 1, device)?,
+            conv_dds: Dds::load(ckpt, &format!("{name}.conv_dds"), config, device)?,
+            conv_proj: Conv::load(ckpt, &format!("{name}.conv_proj"), 1, device)?,
+        })
+    }
+
+    /// `z`: `[1, 2, t]`; channel 0 conditions the spline that inverts channel 1.
+    fn reverse(
+        &self,
+        z: Tensor<B, 3>,
+        cond: Tensor<B, 3>,
+        bins: usize,
+        bound: f32,
+    ) -> Result<Tensor<B, 3>, String> {
+        let t = z.dims()[2];
+        let first = z.clone().narrow(1, 0, 1);
+        let hidden = self.conv_pre.forward(first.clone());
+        let filters = hidden.dims()[1] as f32;
+        let params = to_host(
+            self.conv_proj
+                .forward(self.conv_dds.forward(hidden, Some(cond))),
+        )?;
+        if params.len() != (3 * bins - 1) * t {
+            return Err(format!(
+                "duration flow predicts {} spline parameters per step, expected {}",
+                params.len() / t.max(1),
+                3 * bins - 1
+            ));
+        }
+        let second = to_host(z.narrow(1, 1, 1))?;
+        let scale = filters.sqrt();
+        let inverted: Vec<f32> = (0..t)
+            .map(|i| {
+                let at = |j: usize| params[j * t + i];
+                let widths: Vec<f32> = (0..bins).map(|j| at(j) / scale).collect();
+                let heights: Vec<f32> = (bins..2 * bins).map(|j| at(j) / scale).collect();
+                let derivs: Vec<f32> = (2 * bins..3 * bins - 1).map(at).collect();
+                spline::inverse(second[i], &widths, &heights, &derivs, bound)
+            })
+            .collect();
+        let second = Tensor::from_data(TensorData::new(inverted, [1, 1, t]), &first.device());
+        Ok(Tensor::cat(vec![first, second], 1))
+    }
+}
+
+#[derive(Module, Debug)]
+struct StochasticDurationPredictor<B: Backend> {
+    conv_pre: Conv<B>,
+    conv_dds: Dds<B>,
+    conv_proj: Conv<B>,
+    cond: Option<Conv<B>>,
+    translate: Param<Tensor<B, 3>>,
+    log_scale: Param<Tensor<B, 3>>,
+    /// The flows inference runs, in checkpoint order (`flows.1` is skipped, as upstream does).
+    flows: Vec<ConvFlow<B>>,
+    bins: usize,
+    bound: f32,
+}
+
+impl<B: Backend> StochasticDurationPredictor<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let name = "duration_predictor";
+        let cond = if ckpt.has(&format!("{name}.cond.weight")) {
+            Some(Conv::load(ckpt, &format!("{name}.cond"), 1, device)?)
+        } else {
+            None
+        };
+        let flows = (2..=config.duration_predictor_num_flows)
+            .map(|i| ConvFlow::load(ckpt, &format!("{name}.flows.{i}"), config, device))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            conv_pre: Conv::load(ckpt, &format!("{name}.conv_pre"), 1, device)?,
+            conv_dds: Dds::load(ckpt, &format!("{name}.conv_dds"), config, device)?,
+            conv_proj: Conv::load(ckpt, &format!("{name}.conv_proj"), 1, device)?,
+            cond,
+            translate: param(
+                ckpt.take(&format!("{name}.flows.0.translate"))?,
+                [1, 2, 1],
+                device,
+            )?,
+            log_scale: param(
+                ckpt.take(&format!("{name}.flows.0.log_scale"))?,
+                [1, 2, 1],
+                device,
+            )?,
+            flows,
+            bins: config.duration_predictor_flow_bins,
+            bound: config.duration_predictor_tail_bound,
+        })
+    }
+
+    /// Log durations `[1, 1, t]` sampled from `noise` (`[1, 2, t]`, already temperature-scaled).
+    fn reverse(
+        &self,
+        hidden: Tensor<B, 3>,
+        speaker: Option<Tensor<B, 3>>,
+        noise: Tensor<B, 3>,
+    ) -> Result<Tensor<B, 3>, String> {
+        let mut x = self.conv_pre.forward(hidden);
+        if let (Some(cond), Some(g)) = (&self.cond, speaker) {
+            x = x + cond.forward(g);
+        }
+        let x = self.conv_proj.forward(self.conv_dds.forward(x, None));
+        let mut z = noise;
+        for flow in self.flows.iter().rev() {
+            z = flow.reverse(z.flip([1]), x.clone(), self.bins, self.bound)?;
+        }
+        let z = (z.flip([1]) - self.translate.val()) * self.log_scale.val().neg().exp();
+        Ok(z.narrow(1, 0, 1))
+    }
+}
+
+/// Deterministic duration predictor (`use_stochastic_duration_prediction: false`).
+#[derive(Module, Debug)]
+struct DurationPredictor<B: Backend> {
+    conv_1: Conv<B>,
+    norm_1: Norm<B>,
+    conv_2: Conv<B>,
+    norm_2: Norm<B>,
+    proj: Conv<B>,
+    cond: Option<Conv<B>>,
+}
+
+impl<B: Backend> DurationPredictor<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let name = "duration_predictor";
+        let eps = config.layer_norm_eps as f32;
+        let cond = if ckpt.has(&format!("{name}.cond.weight")) {
+            Some(Conv::load(ckpt, &format!("{name}.cond"), 1, device)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            conv_1: Conv::load(ckpt, &format!("{name}.conv_1"), 1, device)?,
+            norm_1: Norm::load(ckpt, &format!("{name}.norm_1"), eps, device)?,
+            conv_2: Conv::load(ckpt, &format!("{name}.conv_2"), 1, device)?,
+            norm_2: Norm::load(ckpt, &format!("{name}.norm_2"), eps, device)?,
+            proj: Conv::load(ckpt, &format!("{name}.proj"), 1, device)?,
+            cond,
+        })
+    }
+
+    fn forward(&self, mut x: Tensor<B, 3>, speaker: Option<Tensor<B, 3>>) -> Tensor<B, 3> {
+        if let (Some(cond), Some(g)) = (&self.cond, speaker) {
+            x = x + cond.forward(g);
+        }
+        let x = self.norm_1.channels(relu(self.conv_1.forward(x)));
+        let x = self.norm_2.channels(relu(self.conv_2.forward(x)));
+        self.proj.forward(x)
+    }
+}
+
+/// Gated dilated conv stack of the coupling flows.
+#[derive(Module, Debug)]
+struct WaveNet<B: Backend> {
+    in_layers: Vec<Conv<B>>,
+    res_skip_layers: Vec<Conv<B>>,
+    cond_layer: Option<Conv<B>>,
+    hidden: usize,
+}
+
+impl<B: Backend> WaveNet<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let cond_layer = if config.speaker_embedding_size > 0 {
+            Some(Conv::load(ckpt, &format!("{name}.cond_layer"), 1, device)?)
+        } else {
+            None
+        };
+        let layers = config.prior_encoder_num_wavenet_layers;
+        let mut in_layers = Vec::with_capacity(layers);
+        let mut res_skip_layers = Vec::with_capacity(layers);
+        for i in 0..layers {
+            let dilation = config.wavenet_dilation_rate.pow(i as u32);
+            in_layers.push(Conv::load(
+                ckpt,
+                &format!("{name}.in_layers.{i}"),
+                dilation,
+                device,
+            )?);
+            res_skip_layers.push(Conv::load(
+                ckpt,
+                &format!("{name}.res_skip_layers.{i}"),
+                1,
+                device,
+            )?);
+        }
+        Ok(Self {
+            in_layers,
+            res_skip_layers,
+            cond_layer,
+            hidden: config.hidden_size,
+        })
+    }
+
+    fn forward(&self, mut x: Tensor<B, 3>, speaker: Option<Tensor<B, 3>>) -> Tensor<B, 3> {
+        let h = self.hidden;
+        let cond = match (&self.cond_layer, speaker) {
+            (Some(layer), Some(g)) => Some(layer.forward(g)),
+            _ => None,
+        };
+        let mut out = x.zeros_like();
+        let last = self.in_layers.len().saturating_sub(1);
+        for (i, (input, res_skip)) in self.in_layers.iter().zip(&self.res_skip_layers).enumerate() {
+            let mut acts = input.forward(x.clone());
+            if let Some(cond) = &cond {
+                acts = acts + cond.clone().narrow(1, i * 2 * h, 2 * h);
+            }
+            let acts = tanh(acts.clone().narrow(1, 0, h)) * sigmoid(acts.narrow(1, h, h));
+            let res = res_skip.forward(acts);
+            if i < last {
+                x = x + res.clone().narrow(1, 0, h);
+                out = out + res.narrow(1, h, h);
+            } else {
+                out = out + res;
+            }
+        }
+        out
+    }
+}
+
+/// Mean-only affine coupling: shifts the second half of the channels by a WaveNet of the first.
+#[derive(Module, Debug)]
+struct Coupling<B: Backend> {
+    conv_pre: Conv<B>,
+    wavenet: WaveNet<B>,
+    conv_post: Conv<B>,
+}
+
+impl<B: Backend> Coupling<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            conv_pre: Conv::load(ckpt, &format!("{name}.conv_pre"), 1, device)?,
+            wavenet: WaveNet::load(ckpt, &format!("{name}.wavenet"), config, device)?,
+            conv_post: Conv::load(ckpt, &format!("{name}.conv_post"), 1, device)?,
+        })
+    }
+
+    fn reverse(&self, x: Tensor<B, 3>, speaker: Option<Tensor<B, 3>>) -> Tensor<B, 3> {
+        let half = x.dims()[1] / 2;
+        let first = x.clone().narrow(1, 0, half);
+        let hidden = self
+            .wavenet
+            .forward(self.conv_pre.forward(first.clone()), speaker);
+        let second = x.narrow(1, half, half) - self.conv_post.forward(hidden);
+        Tensor::cat(vec![first, second], 1)
+    }
+}
+
+#[derive(Module, Debug)]
+struct ResBlock<B: Backend> {
+    convs1: Vec<Conv<B>>,
+    convs2: Vec<Conv<B>>,
+    slope: f32,
+}
+
+impl<B: Backend> ResBlock<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        name: &str,
+        dilations: &[usize],
+        slope: f32,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let mut block = Self {
+            convs1: Vec::new(),
+            convs2: Vec::new(),
+            slope,
+        };
+        for (j, &dilation) in dilations.iter().enumerate() {
+            block.convs1.push(Conv::load(
+                ckpt,
+                &format!("{name}.convs1.{j}"),
+                dilation,
+                device,
+            )?);
+            block
+                .convs2
+                .push(Conv::load(ckpt, &format!("{name}.convs2.{j}"), 1, device)?);
+        }
+        Ok(block)
+    }
+
+    fn forward(&self, mut x: Tensor<B, 3>) -> Tensor<B, 3> {
+        let slope = self.slope as f64;
+        for (c1, c2) in self.convs1.iter().zip(&self.convs2) {
+            let h = c1.forward(leaky_relu(x.clone(), slope));
+            x = x + c2.forward(leaky_relu(h, slope));
+        }
+        x
+    }
+}
+
+/// HiFi-GAN vocoder.
+#[derive(Module, Debug)]
+struct Decoder<B: Backend> {
+    conv_pre: Conv<B>,
+    upsampler: Vec<Upsample<B>>,
+    resblocks: Vec<ResBlock<B>>,
+    conv_post: Conv<B>,
+    cond: Option<Conv<B>>,
+    slope: f32,
+}
+
+impl<B: Backend> Decoder<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let cond = if config.speaker_embedding_size > 0 {
+            Some(Conv::load(ckpt, "decoder.cond", 1, device)?)
+        } else {
+            None
+        };
+        let upsampler = config
+            .upsample_rates
+            .iter()
+            .enumerate()
+            .map(|(i, &rate)| Upsample::load(ckpt, &format!("decoder.upsampler.{i}"), rate, device))
+            .collect::<Result<Vec<_>, _>>()?;
+        let kernels = config.resblock_kernel_sizes.len();
+        let resblocks = (0..upsampler.len() * kernels)
+            .map(|k| {
+                ResBlock::load(
+                    ckpt,
+                    &format!("decoder.resblocks.{k}"),
+                    &config.resblock_dilation_sizes[k % kernels],
+                    config.leaky_relu_slope,
+                    device,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            conv_pre: Conv::load(ckpt, "decoder.conv_pre", 1, device)?,
+            upsampler,
+            resblocks,
+            conv_post: Conv::load(ckpt, "decoder.conv_post", 1, device)?,
+            cond,
+            slope: config.leaky_relu_slope,
+        })
+    }
+
+    /// `latents`: `[1, flow_size, frames]` → named stages, the last being the waveform `[1, 1, n]`.
+    fn stages(
+        &self,
+        latents: Tensor<B, 3>,
+        speaker: Option<Tensor<B, 3>>,
+    ) -> Vec<(String, Tensor<B, 3>)> {
+        let mut out = Vec::new();
+        let mut x = self.conv_pre.forward(latents);
+        if let (Some(cond), Some(g)) = (&self.cond, speaker) {
+            x = x + cond.forward(g);
+        }
+        out.push(("decoder.conv_pre".to_string(), x.clone()));
+        let kernels = self.resblocks.len() / self.upsampler.len().max(1);
+        for (i, up) in self.upsampler.iter().enumerate() {
+            let y = up.forward(leaky_relu(x, self.slope as f64));
+            let blocks = &self.resblocks[i * kernels..(i + 1) * kernels];
+            let mut sum = blocks[0].forward(y.clone());
+            for block in &blocks[1..] {
+                sum = sum + block.forward(y.clone());
+            }
+            x = sum.div_scalar(kernels as f32);
+            out.push((format!("decoder.upsampler.{i}"), x.clone()));
+        }
+        let wave = tanh(self.conv_post.forward(leaky_relu(x, FINAL_SLOPE)));
+        out.push(("decoder".to_string(), wave));
+        out
+    }
+}
+
+#[derive(Module, Debug)]
+struct TextEncoder<B: Backend> {
+    embed_tokens: Param<Tensor<B, 2>>,
+    layers: Vec<EncoderLayer<B>>,
+    project: Conv<B>,
+}
+
+impl<B: Backend> TextEncoder<B> {
+    fn load(
+        ckpt: &mut Checkpoint,
+        config: &VitsConfig,
+        device: &B::Device,
+    ) -> Result<Self, String> {
+        let embed = ckpt.take("text_encoder.embed_tokens.weight")?;
+        let shape = match embed.shape[..] {
+            [v, h] => [v, h],
+            _ => return Err(format!("embed_tokens: expected 2-D, got {:?}", embed.shape)),
+        };
+        let layers = (0..config.num_hidden_layers)
+            .map(|i| {
+                EncoderLayer::load(
+                    ckpt,
+                    &format!("text_encoder.encoder.layers.{i}"),
+                    config,
+                    device,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            embed_tokens: param(embed, shape, device)?,
+            layers,
+            project: Conv::load(ckpt, "text_encoder.project", 1, device)?,
+        })
+    }
+}
+
+/// Sampling settings for one utterance.
+#[derive(Debug, Clone, Copy)]
+pub struct Controls {
+    /// Frames per predicted duration unit (`1 / speed`).
+    pub length_scale: f32,
+    pub noise_scale: f32,
+    pub noise_scale_duration: f32,
+    pub speaker: Option<usize>,
+}
+
+#[derive(Module, Debug)]
+pub struct Vits<B: Backend> {
+    text_encoder: TextEncoder<B>,
+    stochastic_duration: Option<StochasticDurationPredictor<B>>,
+    duration: Option<DurationPredictor<B>>,
+    flows: Vec<Coupling<B>>,
+    decoder: Decoder<B>,
+    embed_speaker: Option<Param<Tensor<B, 2>>>,
+    flow_size: usize,
+    max_frames: usize,
+}
+
+impl<B: Backend> Vits<B> {
+    pub fn load(ckpt: &mut Checkpoint, device: &B::Device) -> Result<Self, String> {
+        let config = ckpt.config.clone();
+        let (stochastic_duration, duration) = if config.use_stochastic_duration_prediction {
+            (
+                Some(StochasticDurationPredictor::load(ckpt, &config, device)?),
+                None,
+            )
+        } else {
+            (None, Some(DurationPredictor::load(ckpt, &config, device)?))
+        };
+        let embed_speaker = if config.num_speakers > 1 && config.speaker_embedding_size > 0 {
+            Some(param(
+                ckpt.take("embed_speaker.weight")?,
+                [config.num_speakers, config.speaker_embedding_size],
+                device,
+            )?)
+        } else {
+            None
+        };
+        let flows = (0..config.prior_encoder_num_flows)
+            .map(|i| Coupling::load(ckpt, &format!("flow.flows.{i}"), &config, device))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            text_encoder: TextEncoder::load(ckpt, &config, device)?,
+            stochastic_duration,
+            duration,
+            flows,
+            decoder: Decoder::load(ckpt, &config, device)?,
+            embed_speaker,
+            flow_size: config.flow_size,
+            max_frames: MAX_SECONDS * config.sampling_rate as usize / config.hop_length().max(1),
+        })
+    }
+
+    pub fn num_speakers(&self) -> usize {
+        self.embed_speaker
+            .as_ref()
+            .map_or(1, |table| table.dims()[0])
+    }
+
+    /// `ids` → named stages, the last being the waveform `[1, 1, samples]`.
+    pub fn stages(
+        &self,
+        ids: &[u32],
+        controls: &Controls,
+        rng: &mut StdRng,
+    ) -> Result<Vec<(String, Tensor<B, 3>)>, String> {
+        let device = self.text_encoder.embed_tokens.device();
+        let t = ids.len();
+        let mut out = Vec::new();
+        let speaker = match (&self.embed_speaker, controls.speaker) {
+            (Some(table), Some(id)) if id < self.num_speakers() => {
+                Some(table.val().narrow(0, id, 1).transpose().unsqueeze::<3>())
+            }
+            (Some(_), Some(id)) => {
+                return Err(format!(
+                    "speaker {id} out of range (this voice has {})",
+                    self.num_speakers()
+                ))
+            }
+            (Some(table), None) => Some(table.val().narrow(0, 0, 1).transpose().unsqueeze::<3>()),
+            (None, _) => None,
+        };
+
+        // Text encoder.
+        let enc = &self.text_encoder;
+        let hidden_size = enc.embed_tokens.dims()[1];
+        let ids_i64: Vec<i64> = ids.iter().map(|&id| id as i64).collect();
+        let ids_t = Tensor::<B, 2, Int>::from_data(TensorData::new(ids_i64, [1, t]), &device);
+        let mut x = embedding(enc.embed_tokens.val(), ids_t)
+            .reshape([t, hidden_size])
+            .mul_scalar((hidden_size as f32).sqrt());
+        out.push((
+            "text_encoder.embed_tokens".to_string(),
+            x.clone().unsqueeze(),
+        ));
+        for (i, layer) in enc.layers.iter().enumerate() {
+            x = layer.forward(x);
+            out.push((
+                format!("text_encoder.encoder.layers.{i}"),
+                x.clone().unsqueeze(),
+            ));
+        }
+        let hidden = x.transpose().unsqueeze::<3>();
+        let stats = enc.project.forward(hidden.clone());
+        out.push(("text_encoder.project".to_string(), stats.clone()));
+        let means = stats.clone().narrow(1, 0, self.flow_size);
+        let log_std = stats.narrow(1, self.flow_size, self.flow_size);
+
+        // Durations.
+        let log_duration = match (&self.stochastic_duration, &self.duration) {
+            (Some(sdp), _) => {
+                let noise = gaussian(rng, 2 * t, controls.noise_scale_duration);
+                let noise = Tensor::from_data(TensorData::new(noise, [1, 2, t]), &device);
+                sdp.reverse(hidden, speaker.clone(), noise)?
+            }
+            (None, Some(dp)) => dp.forward(hidden, speaker.clone()),
+            (None, None) => return Err("no duration predictor loaded".to_string()),
+        };
+        out.push(("duration_predictor".to_string(), log_duration.clone()));
+        let durations: Vec<usize> = to_host(log_duration)?
+            .iter()
+            .map(|ld| (ld.exp() * controls.length_scale).ceil().max(0.0) as usize)
+            .collect();
+        let frames = durations.iter().sum::<usize>().max(1);
+        if frames > self.max_frames {
+            return Err(format!(
+                "predicted {frames} frames (over {MAX_SECONDS} s); split the text into sentences"
+            ));
+        }
+
+        // Expand each token's prior over its frames and sample it.
+        let mut align = vec![0.0f32; t * frames];
+        let mut start = 0;
+        for (i, &d) in durations.iter().enumerate() {
+            for f in start..(start + d).min(frames) {
+                align[i * frames + f] = 1.0;
+            }
+            start += d;
+        }
+        let align = Tensor::<B, 2>::from_data(TensorData::new(align, [t, frames]), &device);
+        let expand = |s: Tensor<B, 3>| s.squeeze_dim::<2>(0).matmul(align.clone()).unsqueeze::<3>();
+        let noise = gaussian(rng, self.flow_size * frames, controls.noise_scale);
+        let noise = Tensor::from_data(TensorData::new(noise, [1, self.flow_size, frames]), &device);
+        let prior = expand(means) + noise * expand(log_std).exp();
+        out.push(("prior".to_string(), prior.clone()));
+
+        // Flows, then vocoder.
+        let mut latents = prior;
+        for flow in self.flows.iter().rev() {
+            latents = flow.reverse(latents.flip([1]), speaker.clone());
+        }
+        out.push(("flow".to_string(), latents.clone()));
+        out.extend(self.decoder.stages(latents, speaker));
+        Ok(out)
+    }
+}
+
+/// `n` samples of N(0, scale²) (Box–Muller).
+fn gaussian(rng: &mut StdRng, n: usize, scale: f32) -> Vec<f32> {
+    (0..n)
+        .map(|_| {
+            let u1: f32 = rng.random::<f32>().max(f32::MIN_POSITIVE);
+            let u2: f32 = rng.random();
+            (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos() * scale
+        })
+        .collect()
+}
+
+/// Backend-erased access to a loaded voice.
+pub trait TtsNet {
+    /// Waveform samples for `ids`, in `[-1, 1]`.
+    fn synthesize(
+        &self,
+        ids: &[u32],
+        controls: &Controls,
+        rng: &mut StdRng,
+    ) -> Result<Vec<f32>, String>;
+    fn layer_by_layer(
+        &self,
+        ids: &[u32],
+        controls: &Controls,
+        rng: &mut StdRng,
+    ) -> Result<Vec<ActivationStep>, String>;
+    fn num_speakers(&self) -> usize;
+}
+
+pub struct VitsRuntime<B: Backend> {
+    net: Vits<B>,
+}
+
+impl<B: Backend> VitsRuntime<B> {
+    pub fn load(ckpt: &mut Checkpoint, device: B::Device) -> Result<Self, String> {
+        Ok(Self {
+            net: Vits::load(ckpt, &device)?,
+        })
+    }
+}
+
+impl<B: Backend> TtsNet for VitsRuntime<B> {
+    fn synthesize(
+        &self,
+        ids: &[u32],
+        controls: &Controls,
+        rng: &mut StdRng,
+    ) -> Result<Vec<f32>, String> {
+        let (_, wave) = self
+            .net
+            .stages(ids, controls, rng)?
+            .pop()
+            .ok_or_else(|| "decoder produced no output".to_string())?;
+        to_host(wave)
+    }
+
+    fn layer_by_layer(
+        &self,
+        ids: &[u32],
+        controls: &Controls,
+        rng: &mut StdRng,
+    ) -> Result<Vec<ActivationStep>, String> {
+        self.net
+            .stages(ids, controls, rng)?
+            .into_iter()
+            .map(|(name, t)| step(&name, t))
+            .collect()
+    }
+
+    fn num_speakers(&self) -> usize {
+        self.net.num_speakers()
+    }
+}
+
+fn to_host<B: Backend, const D: usize>(t: Tensor<B, D>) -> Result<Vec<f32>, String> {
+    t.into_data()
+        .convert::<f32>()
+        .to_vec::<f32>()
+        .map_err(|e| format!("read back tensor: {e:?}"))
+}
+
+fn step<B: Backend, const D: usize>(name: &str, x: Tensor<B, D>) -> Result<ActivationStep, String> {
+    let shape = x.dims().to_vec();
+    let values = to_host(x)?;
+    Ok(ActivationStep {
+        name: Some(name.to_string()),
+        shape,
+        dtype: "float32".to_string(),
+        full_stats: stats(&values),
+        values,
+        device_preflight: None,
+    })
+}
+
+pub(super) fn stats(values: &[f32]) -> Option<TensorDebugStats> {
+    let finite: Vec<f64> = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .map(|&v| v as f64)
+        .collect();
+    if finite.is_empty() {
+        return None;
+    }
+    let n = finite.len() as f64;
+    let mean = finite.iter().sum::<f64>() / n;
+    let var = finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    Some(TensorDebugStats {
+        mean: mean as f32,
+        std: var.sqrt() as f32,
+        min: finite.iter().copied().fold(f64::INFINITY, f64::min) as f32,
+        max: finite.iter().copied().fold(f64::NEG_INFINITY, f64::max) as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use burn::backend::{ndarray::NdArrayDevice, NdArray};
+    use rand::SeedableRng;
+
+    use super::*;
+
+    type Specs = Vec<(String, Vec<usize>)>;
+
+    fn conv(specs: &mut Specs, name: &str, out_c: usize, in_c: usize, k: usize) {
+        specs.push((format!("{name}.weight"), vec![out_c, in_c, k]));
+        specs.push((format!("{name}.bias"), vec![out_c]));
+    }
+
+    fn norm(specs: &mut Specs, name: &str) {
+        specs.push((format!("{name}.weight"), vec![4]));
+        specs.push((format!("{name}.bias"), vec![4]));
+    }
+
+    fn dds(specs: &mut Specs, name: &str) {
+        for i in 0..2 {
+            conv(specs, &format!("{name}.convs_dilated.{i}"), 4, 1, 3);
+            conv(specs, &format!("{name}.convs_pointwise.{i}"), 4, 4, 1);
+            norm(specs, &format!("{name}.norms_1.{i}"));
+            norm(specs, &format!("{name}.norms_2.{i}"));
+        }
+    }
+
+    /// Randomly initialised VITS: hidden 4, flow 4, two ×2 upsamplers (4 samples per frame).
+    fn tiny_checkpoint(stochastic: bool) -> Checkpoint {
+        let config = VitsConfig {
+            vocab_size: 6,
+            hidden_size: 4,
+            num_hidden_layers: 1,
+            window_size: Some(1),
+            flow_size: 4,
+            use_stochastic_duration_prediction: stochastic,
+            upsample_rates: vec![2, 2],
+            upsample_kernel_sizes: vec![4, 4],
+            resblock_kernel_sizes: vec![3],
+            resblock_dilation_sizes: vec![vec![1, 3]],
+            depth_separable_num_layers: 2,
+            duration_predictor_flow_bins: 4,
+            duration_predictor_num_flows: 2,
+            prior_encoder_num_flows: 1,
+            prior_encoder_num_wavenet_layers: 2,
+            wavenet_kernel_size: 3,
+            ..VitsConfig::default()
+        };
+        let mut specs = Specs::new();
+        let s = &mut specs;
+
+        let layer = "text_encoder.encoder.layers.0";
+        s.push(("text_encoder.embed_tokens.weight".to_string(), vec![6, 4]));
+        for proj in ["q_proj", "k_proj", "v_proj", "out_proj"] {
+            s.push((format!("{layer}.attention.{proj}.weight"), vec![4, 4]));
+            s.push((format!("{layer}.attention.{proj}.bias"), vec![4]));
+        }
+        s.push((format!("{layer}.attention.emb_rel_k"), vec![3, 2]));
+        s.push((format!("{layer}.attention.emb_rel_v"), vec![3, 2]));
+        norm(s, &format!("{layer}.layer_norm"));
+        conv(s, &format!("{layer}.feed_forward.conv_1"), 8, 4, 3);
+        conv(s, &format!("{layer}.feed_forward.conv_2"), 4, 8, 3);
+        norm(s, &format!("{layer}.final_layer_norm"));
+        conv(s, "text_encoder.project", 8, 4, 1);
+
+        let dp = "duration_predictor";
+        if stochastic {
+            conv(s, &format!("{dp}.conv_pre"), 4, 4, 1);
+            dds(s, &format!("{dp}.conv_dds"));
+            conv(s, &format!("{dp}.conv_proj"), 4, 4, 1);
+            s.push((format!("{dp}.flows.0.translate"), vec![1, 2, 1]));
+            s.push((format!("{dp}.flows.0.log_scale"), vec![1, 2, 1]));
+            conv(s, &format!("{dp}.flows.2.conv_pre"), 4, 1, 1);
+            dds(s, &format!("{dp}.flows.2.conv_dds"));
+            conv(s, &format!("{dp}.flows.2.conv_proj"), 11, 4, 1);
+        } else {
+            conv(s, &format!("{dp}.conv_1"), 4, 4, 3);
+            norm(s, &format!("{dp}.norm_1"));
+            conv(s, &format!("{dp}.conv_2"), 4, 4, 3);
+            norm(s, &format!("{dp}.norm_2"));
+            conv(s, &format!("{dp}.proj"), 1, 4, 1);
+        }
+
+        let flow = "flow.flows.0";
+        conv(s, &format!("{flow}.conv_pre"), 4, 2, 1);
+        for i in 0..2 {
+            conv(s, &format!("{flow}.wavenet.in_layers.{i}"), 8, 4, 3);
+        }
+        conv(s, &format!("{flow}.wavenet.res_skip_layers.0"), 8, 4, 1);
+        conv(s, &format!("{flow}.wavenet.res_skip_layers.1"), 4, 4, 1);
+        conv(s, &format!("{flow}.conv_post"), 2, 4, 1);
+
+        conv(s, "decoder.conv_pre", 8, 4, 7);
+        for (i, (in_c, out_c)) in [(8, 4), (4, 2)].into_iter().enumerate() {
+            // Transposed conv weights are `[in, out, k]`; the bias has `out` entries.
+            s.push((
+                format!("decoder.upsampler.{i}.weight"),
+                vec![in_c, out_c, 4],
+            ));
+            s.push((format!("decoder.upsampler.{i}.bias"), vec![out_c]));
+            for j in 0..2 {
+                conv(
+                    s,
+                    &format!("decoder.resblocks.{i}.convs1.{j}"),
+                    out_c,
+                    out_c,
+                    3,
+                );
+                conv(
+                    s,
+                    &format!("decoder.resblocks.{i}.convs2.{j}"),
+                    out_c,
+                    out_c,
+                    3,
+                );
+            }
+        }
+        conv(s, "decoder.conv_post", 1, 2, 7);
+
+        let mut seed = 1u32;
+        let mut tensors: BTreeMap<String, WeightTensor> = specs
+            .into_iter()
+            .map(|(name, shape)| {
+                let data = (0..shape.iter().product::<usize>())
+                    .map(|_| {
+                        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                        (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+                    })
+                    .collect();
+                let tensor = WeightTensor {
+                    shape,
+                    dtype: "F32".to_string(),
+                    data,
+                };
+                (name, tensor)
+            })
+            .collect();
+        if !stochastic {
+            // About four frames per token at speed 1, so speed changes show up in the length.
+            tensors.get_mut(&format!("{dp}.proj.bias")).unwrap().data = vec![4f32.ln()];
+        }
+        Checkpoint {
+            config,
+            tensors,
+            files: Vec::new(),
+        }
+    }
+
+    fn controls(speed: f32) -> Controls {
+        Controls {
+            length_scale: 1.0 / speed,
+            noise_scale: 0.667,
+            noise_scale_duration: 0.8,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn output_length_follows_speed() {
+        let mut ckpt = tiny_checkpoint(false);
+        let hop = ckpt.config.hop_length();
+        let net = VitsRuntime::<NdArray>::load(&mut ckpt, NdArrayDevice::Cpu).unwrap();
+        assert!(ckpt.tensors.is_empty(), "unused: {:?}", ckpt.tensors.keys());
+        let ids = [1, 2, 3, 4, 5];
+        let len = |speed| {
+            let mut rng = StdRng::seed_from_u64(0);
+            let wave = net.synthesize(&ids, &controls(speed), &mut rng).unwrap();
+            assert!(wave.iter().all(|v| v.is_finite() && v.abs() <= 1.0));
+            assert_eq!(wave.len() % hop, 0);
+            wave.len()
+        };
+        let (normal, fast, slow) = (len(1.0), len(2.0), len(0.5));
+        assert!(fast < normal && normal < slow, "{fast} {normal} {slow}");
+    }
+
+    #[test]
+    fn same_seed_same_audio() {
+        for stochastic in [false, true] {
+            let mut ckpt = tiny_checkpoint(stochastic);
+            let net = VitsRuntime::<NdArray>::load(&mut ckpt, NdArrayDevice::Cpu).unwrap();
+            assert!(ckpt.tensors.is_empty(), "unused: {:?}", ckpt.tensors.keys());
+            let ids = [1, 2, 3, 4, 5];
+            let run = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                net.synthesize(&ids, &controls(1.0), &mut rng).unwrap()
+            };
+            assert_eq!(run(7), run(7));
+            assert_ne!(run(7), run(8));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! The inverse rational-quadratic spline of VITS's stochastic duration predictor (neural spline
+//! flows, Durkan et al. 2019), evaluated on the host: it is a handful of scalar ops per token.
+
+const MIN_BIN_WIDTH: f32 = 1e-3;
+const MIN_BIN_HEIGHT: f32 = 1e-3;
+const MIN_DERIVATIVE: f32 = 1e-3;
+
+fn softplus(x: f32) -> f32 {
+    if x > 20.0 {
+        x
+    } else {
+        x.exp().ln_1p()
+    }
+}
+
+/// Bin edges from unnormalized sizes: softmax, a minimum share per bin, then scaled to
+/// `[-bound, bound]`.
+fn edges(unnormalized: &[f32], min_size: f32, bound: f32) -> Vec<f32> {
+    let max = unnormalized
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max);
+    let exp: Vec<f32> = unnormalized.iter().map(|v| (v - max).exp()).collect();
+    let total: f32 = exp.iter().sum();
+    let bins = unnormalized.len();
+    let mut out = Vec::with_capacity(bins + 1);
+    out.push(-bound);
+    let mut cum = 0.0;
+    for e in &exp {
+        cum += min_size + (1.0 - min_size * bins as f32) * e / total;
+        out.push(2.0 * bound * cum - bound);
+    }
+    out[bins] = bound;
+    out
+}
+
+/// Knot derivatives: the outer two are pinned to 1 (the identity tails).
+fn derivatives(unnormalized: &[f32]) -> Vec<f32> {
+    let constant = ((1.0 - MIN_DERIVATIVE).exp() - 1.0).ln();
+    std::iter::once(constant)
+        .chain(unnormalized.iter().copied())
+        .chain(std::iter::once(constant))
+        .map(|v| MIN_DERIVATIVE + softplus(v))
+        .collect()
+}
+
+/// Invert the spline at `y`: `widths` / `heights` have one value per bin and `derivs` one per
+/// inner knot (`bins - 1`). Outside `[-bound, bound]` the map is the identity.
+pub fn inverse(y: f32, widths: &[f32], heights: &[f32], derivs: &[f32], bound: f32) -> f32 {
+    if !(-bound..=bound).contains(&y) {
+        return y;
+    }
+    let xs = edges(widths, MIN_BIN_WIDTH, bound);
+    let ys = edges(heights, MIN_BIN_HEIGHT, bound);
+    let d = derivatives(derivs);
+    let bins = widths.len();
+    // `y == bound` falls in the last bin.
+    let k = (0..bins).rev().find(|&k| y >= ys[k]).unwrap_or(0);
+    let (w, h) = (xs[k + 1] - xs[k], ys[k + 1] - ys[k]);
+    let delta = h / w;
+    let (d0, d1) = (d[k], d[k + 1]);
+    let mid = d0 + d1 - 2.0 * delta;
+    let dy = y - ys[k];
+    let a = h * (delta - d0) + dy * mid;
+    let b = h * d0 - dy * mid;
+    let c = -delta * dy;
+    let discriminant = (b * b - 4.0 * a * c).max(0.0);
+    let theta = 2.0 * c / (-b - discriminant.sqrt());
+    theta * w + xs[k]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The forward map, to check `inverse` against.
+    fn forward(x: f32, widths: &[f32], heights: &[f32], derivs: &[f32], bound: f32) -> f32 {
+        if !(-bound..=bound).contains(&x) {
+            return x;
+        }
+        let xs = edges(widths, MIN_BIN_WIDTH, bound);
+        let ys = edges(heights, MIN_BIN_HEIGHT, bound);
+        let d = derivatives(derivs);
+        let k = (0..widths.len()).rev().find(|&k| x >= xs[k]).unwrap_or(0);
+        let (w, h) = (xs[k + 1] - xs[k], ys[k + 1] - ys[k]);
+        let delta = h / w;
+        let theta = (x - xs[k]) / w;
+        let t1 = theta * (1.0 - theta);
+        let numerator = h * (delta * theta * theta + d[k] * t1);
+        let denominator = delta + (d[k] + d[k + 1] - 2.0 * delta) * t1;
+        ys[k] + numerator / denominator
+    }
+
+    #[test]
+    fn uniform_spline_with_unit_slopes_is_the_identity() {
+        let constant = ((1.0 - MIN_DERIVATIVE).exp() - 1.0).ln();
+        let (w, h, d) = ([0.0; 10], [0.0; 10], [constant; 9]);
+        for y in [-4.9, -1.0, 0.0, 0.3, 4.99, 7.0, -12.0] {
+            assert!((inverse(y, &w, &h, &d, 5.0) - y).abs() < 1e-4, "{y}");
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_forward() {
+        let w = [0.3, -1.0, 2.0, 0.5, 0.0, -0.2, 1.1, 0.7, -0.4, 0.9];
+        let h = [1.0, 0.2, -0.5, 0.4, 1.5, -1.2, 0.0, 0.3, 0.8, -0.6];
+        let d = [0.5, -0.3, 1.2, 0.0, -1.0, 0.7, 0.2, -0.1, 2.0];
+        for x in [-4.95, -3.3, -0.7, 0.0, 0.01, 1.9, 3.3, 4.9] {
+            let y = forward(x, &w, &h, &d, 5.0);
+            assert!((inverse(y, &w, &h, &d, 5.0) - x).abs() < 1e-3, "{x} -> {y}");
+        }
+    }
+}
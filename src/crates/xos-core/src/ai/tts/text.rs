@@ -0,0 +1,186 @@
+//! Text front end: the character tokenizer of the Hugging Face VITS voices (`vocab.json` +
+//! `tokenizer_config.json`) and the sentence splitter that lets speech stream chunk by chunk.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+pub struct VitsTokenizer {
+    vocab: HashMap<char, u32>,
+    /// Put id 0 between every character and at both ends (what the voices were trained on).
+    add_blank: bool,
+    /// Lowercase characters the vocabulary does not have as-is.
+    normalize: bool,
+}
+
+impl VitsTokenizer {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join("vocab.json");
+        let text =
+            fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+        let entries: HashMap<String, u32> =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        #[derive(Deserialize)]
+        #[serde(default)]
+        struct Options {
+            add_blank: bool,
+            normalize: bool,
+            phonemize: bool,
+        }
+        impl Default for Options {
+            fn default() -> Self {
+                Self {
+                    add_blank: true,
+                    normalize: true,
+                    phonemize: false,
+                }
+            }
+        }
+        let options: Options = match fs::read_to_string(dir.join("tokenizer_config.json")) {
+            Ok(text) => {
+                serde_json::from_str(&text).map_err(|e| format!("tokenizer_config.json: {e}"))?
+            }
+            Err(_) => Options::default(),
+        };
+        if options.phonemize {
+            return Err(
+                "this voice reads espeak phonemes, not text; use a character voice (MMS-TTS)"
+                    .to_string(),
+            );
+        }
+        Ok(Self::new(entries, options.add_blank, options.normalize))
+    }
+
+    /// Multi-character entries (`<pad>`, `<unk>`) are never produced from text and are skipped.
+    pub fn new(entries: HashMap<String, u32>, add_blank: bool, normalize: bool) -> Self {
+        let vocab = entries
+            .into_iter()
+            .filter_map(|(token, id)| {
+                let mut chars = token.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some((c, id)),
+                    _ => None,
+                }
+            })
+            .collect();
+        Self {
+            vocab,
+            add_blank,
+            normalize,
+        }
+    }
+
+    /// Token ids for `text`; characters outside the voice's alphabet (often digits and most
+    /// punctuation) are dropped, so spell numbers out.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        let mut chars: Vec<char> = Vec::with_capacity(text.len());
+        for c in text.chars() {
+            if !self.normalize || self.vocab.contains_key(&c) {
+                chars.push(c);
+            } else {
+                chars.extend(c.to_lowercase());
+            }
+        }
+        chars.retain(|c| self.vocab.contains_key(c));
+        let kept: String = chars.into_iter().collect();
+        let ids = kept.trim().chars().map(|c| self.vocab[&c]);
+        if !self.add_blank {
+            return ids.collect();
+        }
+        let mut out = vec![0];
+        for id in ids {
+            out.extend([id, 0]);
+        }
+        out
+    }
+}
+
+/// Split `text` into sentences of at most about `max_chars` characters, breaking long ones at
+/// commas or spaces. Chunks are trimmed and never empty.
+pub fn split_sentences(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let ends = matches!(c, '.' | '!' | '?' | ';' | '\n')
+            && chars.peek().is_none_or(|n| n.is_whitespace());
+        if ends {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+
+    let mut out = Vec::new();
+    for sentence in sentences {
+        let mut rest = sentence.trim();
+        while rest.chars().count() > max_chars {
+            let limit = rest
+                .char_indices()
+                .nth(max_chars)
+                .map_or(rest.len(), |(i, _)| i);
+            let head = &rest[..limit];
+            let cut = head
+                .rfind(',')
+                .map(|i| i + 1)
+                .or_else(|| head.rfind(char::is_whitespace))
+                .filter(|&i| i > 0)
+                .unwrap_or(limit);
+            out.push(rest[..cut].trim().to_string());
+            rest = rest[cut..].trim_start();
+        }
+        if !rest.is_empty() {
+            out.push(rest.to_string());
+        }
+    }
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenizer(add_blank: bool) -> VitsTokenizer {
+        let entries = [
+            ("h", 3),
+            ("i", 4),
+            (" ", 5),
+            ("'", 6),
+            ("<pad>", 0),
+            ("k", 0),
+        ]
+        .into_iter()
+        .map(|(t, id)| (t.to_string(), id))
+        .collect();
+        VitsTokenizer::new(entries, add_blank, true)
+    }
+
+    #[test]
+    fn lowercases_drops_unknown_and_intersperses_blanks() {
+        assert_eq!(
+            tokenizer(false).encode("  Hi, h'i 42! "),
+            vec![3, 4, 5, 3, 6, 4]
+        );
+        assert_eq!(tokenizer(true).encode("HI"), vec![0, 3, 0, 4, 0]);
+        assert_eq!(tokenizer(true).encode("123"), vec![0]);
+    }
+
+    #[test]
+    fn splits_sentences_and_long_runs() {
+        assert_eq!(
+            split_sentences("Hello there. How are you?\nFine! 3.5 apples", 100),
+            ["Hello there.", "How are you?", "Fine!", "3.5 apples"]
+        );
+        assert_eq!(
+            split_sentences("one two three, four five six", 16),
+            ["one two three,", "four five six"]
+        );
+        assert_eq!(split_sentences("abcdefgh", 3), ["abc", "def", "gh"]);
+        assert!(split_sentences("  \n ", 10).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! VITS checkpoints: a Hugging Face folder with `config.json` and `*.safetensors`. Weight-normed
+//! convolutions (`weight_g` / `weight_v`, or the newer `parametrizations.weight.original0/1`) are
+//! folded into plain weights on load.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::config::VitsConfig;
+use crate::ai::safetensors::SafetensorsFile;
+
+pub struct WeightTensor {
+    pub shape: Vec<usize>,
+    /// Storage type in the checkpoint (`F32`, `F16`, ...).
+    pub dtype: String,
+    pub data: Vec<f32>,
+}
+
+pub struct Checkpoint {
+    pub config: VitsConfig,
+    pub tensors: BTreeMap<String, WeightTensor>,
+    pub files: Vec<PathBuf>,
+}
+
+impl Checkpoint {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let config_path = dir.join("config.json");
+        let text = fs::read_to_string(&config_path)
+            .map_err(|e| format!("read {}: {e}", config_path.display()))?;
+        let config = VitsConfig::from_hf_json(&text)?;
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map_err(|e| format!("read_dir {}: {e}", dir.display()))?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "safetensors"))
+            .collect();
+        files.sort();
+        if files.is_empty() {
+            return Err(format!("no .safetensors weights in {}", dir.display()));
+        }
+        let mut tensors = BTreeMap::new();
+        for file in &files {
+            let bytes = fs::read(file).map_err(|e| format!("read {}: {e}", file.display()))?;
+            let st =
+                SafetensorsFile::parse(bytes).map_err(|e| format!("{}: {e}", file.display()))?;
+            for info in &st.tensors {
+                tensors.insert(
+                    info.name.clone(),
+                    WeightTensor {
+                        shape: info.shape.clone(),
+                        dtype: info.dtype.clone(),
+                        data: st.to_f32(info),
+                    },
+                );
+            }
+        }
+        Ok(Self {
+            config,
+            tensors,
+            files,
+        })
+    }
+
+    pub fn take(&mut self, name: &str) -> Result<WeightTensor, String> {
+        self.tensors
+            .remove(name)
+            .ok_or_else(|| format!("checkpoint has no tensor {name} (not a VITS checkpoint?)"))
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tensors.contains_key(name)
+    }
+
+    /// `{layer}.weight`, with weight norm folded in when the checkpoint stores it split.
+    pub fn take_weight(&mut self, layer: &str) -> Result<WeightTensor, String> {
+        let plain = format!("{layer}.weight");
+        if self.has(&plain) {
+            return self.take(&plain);
+        }
+        let (g, v) = if self.has(&format!("{layer}.weight_g")) {
+            ("weight_g", "weight_v")
+        } else {
+            (
+                "parametrizations.weight.original0",
+                "parametrizations.weight.original1",
+            )
+        };
+        let g = self.take(&format!("{layer}.{g}"))?;
+        let v = self.take(&format!("{layer}.{v}"))?;
+        Ok(fold_weight_norm(&g, v))
+    }
+}
+
+/// `g * v / ||v||`, the norm taken per slice along dim 0 (PyTorch's `weight_norm(dim=0)`).
+fn fold_weight_norm(g: &WeightTensor, mut v: WeightTensor) -> WeightTensor {
+    let rows = v.shape.first().copied().unwrap_or(1).max(1);
+    let per_row = v.data.len() / rows;
+    for (row, scale) in v.data.chunks_mut(per_row).zip(&g.data) {
+        let norm = row.iter().map(|x| x * x).sum::<f32>().sqrt().max(1e-12);
+        row.iter_mut().for_each(|x| *x *= scale / norm);
+    }
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_weight_norm_per_output_channel() {
+        let tensor = |shape: Vec<usize>, data: Vec<f32>| WeightTensor {
+            shape,
+            dtype: "F32".to_string(),
+            data,
+        };
+        let mut ckpt = Checkpoint {
+            config: VitsConfig::default(),
+            tensors: BTreeMap::new(),
+            files: Vec::new(),
+        };
+        ckpt.tensors.insert(
+            "conv.weight_g".to_string(),
+            tensor(vec![2, 1, 1], vec![2.0, 10.0]),
+        );
+        ckpt.tensors.insert(
+            "conv.weight_v".to_string(),
+            tensor(vec![2, 1, 2], vec![3.0, 4.0, 0.0, -1.0]),
+        );
+        let w = ckpt.take_weight("conv").unwrap();
+        assert_eq!(w.shape, vec![2, 1, 2]);
+        assert_eq!(w.data, vec![1.2, 1.6, 0.0, -10.0]);
+        assert!(ckpt.tensors.is_empty());
+        assert!(ckpt.take_weight("conv").is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! OS file pickers. Desktop uses the native open / save / folder dialogs and blocks until the user
+//! answers. The browser cannot block: [`open_file`] / [`open_files`] show an `<input type=file>`
+//! (call them from an input callback so the page allows it), copy the picks into the
+//! `localStorage` VFS of [`crate::fs`] and deliver their paths later through
+//! [`crate::engine::Application::on_file_drop`]. iOS has no picker yet.
+
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileFilter {
+    pub name: String,
+    /// Without dots (`"wav"`); `"*"` matches anything.
+    pub extensions: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileDialogOptions {
+    pub title: Option<String>,
+    pub filters: Vec<FileFilter>,
+    /// Folder the dialog starts in.
+    pub directory: Option<PathBuf>,
+    /// Suggested file name for [`save_file`].
+    pub file_name: Option<String>,
+}
+
+impl FileDialogOptions {
+    pub fn filter(mut self, name: &str, extensions: &[&str]) -> Self {
+        self.filters.push(FileFilter {
+            name: name.to_string(),
+            extensions: extensions
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect(),
+        });
+        self
+    }
+
+    /// `accept` attribute for `<input type=file>` (`".wav,.mp3"`); empty when anything goes.
+    pub fn accept(&self) -> String {
+        let extensions: Vec<&String> = self.filters.iter().flat_map(|f| &f.extensions).collect();
+        if extensions.is_empty() || extensions.iter().any(|e| e.as_str() == "*") {
+            return String::new();
+        }
+        extensions
+            .iter()
+            .map(|e| format!(".{e}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// One file, or `None` if cancelled (always `None` in the browser; see the module docs).
+pub fn open_file(options: &FileDialogOptions) -> Option<PathBuf> {
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    {
+        native_dialog(options).pick_file()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        browser::show_picker(&options.accept(), false);
+        None
+    }
+    #[cfg(target_os = "ios")]
+    {
+        let _ = options;
+        None
+    }
+}
+
+/// Several files; empty if cancelled (and in the browser).
+pub fn open_files(options: &FileDialogOptions) -> Vec<PathBuf> {
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    {
+        native_dialog(options).pick_files().unwrap_or_default()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        browser::show_picker(&options.accept(), true);
+        Vec::new()
+    }
+    #[cfg(target_os = "ios")]
+    {
+        let _ = options;
+        Vec::new()
+    }
+}
+
+/// Where to save, or `None` if cancelled. The browser has no save dialog: this is
+/// `<data dir>/<file_name>` in the VFS, so writes there persist across reloads.
+pub fn save_file(options: &FileDialogOptions) -> Option<PathBuf> {
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    {
+        native_dialog(options).save_file()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let dir = crate::fs::data_dir_string().ok()?;
+        let name = options.file_name.as_deref().unwrap_or("untitled");
+        Some(PathBuf::from(dir).join(name))
+    }
+    #[cfg(target_os = "ios")]
+    {
+        let _ = options;
+        None
+    }
+}
+
+/// A folder, or `None` if cancelled (always `None` in the browser and on iOS).
+pub fn pick_folder(options: &FileDialogOptions) -> Option<PathBuf> {
+    #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+    {
+        native_dialog(options).pick_folder()
+    }
+    #[cfg(any(target_arch = "wasm32", target_os = "ios"))]
+    {
+        let _ = options;
+        None
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+fn native_dialog(options: &FileDialogOptions) -> rfd::FileDialog {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(title) = &options.title {
+        dialog = dialog.set_title(title);
+    }
+    for filter in &options.filters {
+        dialog = dialog.add_filter(&filter.name, filter.extensions.as_slice());
+    }
+    if let Some(dir) = &options.directory {
+        dialog = dialog.set_directory(dir);
+    }
+    if let Some(name) = &options.file_name {
+        dialog = dialog.set_file_name(name);
+    }
+    dialog
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use browser::{import_browser_files, take_imported_files};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    /// VFS folder for dropped and picked files.
+    const UPLOAD_DIR: &str = ".xos/uploads";
+
+    thread_local! {
+        static IMPORTED: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Files copied into the VFS since the last call (the web host hands them to `on_file_drop`).
+    pub fn take_imported_files() -> Vec<PathBuf> {
+        IMPORTED.with(|f| std::mem::take(&mut *f.borrow_mut()))
+    }
+
+    /// Read each file and store it under [`UPLOAD_DIR`]; paths queue up as reads finish.
+    pub fn import_browser_files(files: &web_sys::FileList) {
+        for i in 0..files.length() {
+            if let Some(file) = files.get(i) {
+                if let Err(e) = import_file(file) {
+                    crate::print(&format!("xos.dialog: reading a file failed: {e:?}"));
+                }
+            }
+        }
+    }
+
+    fn import_file(file: web_sys::File) -> Result<(), JsValue> {
+        let reader = web_sys::FileReader::new()?;
+        let name = file.name().replace(['/', '\\'], "_");
+        let reader_clone = reader.clone();
+        let onload = Closure::once(move |_event: web_sys::Event| {
+            let Ok(buffer) = reader_clone.result() else {
+                return;
+            };
+            let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+            let path = format!("{UPLOAD_DIR}/{name}");
+            match crate::fs::write(&path, &bytes) {
+                Ok(()) => IMPORTED.with(|f| f.borrow_mut().push(PathBuf::from(path))),
+                Err(e) => crate::print(&format!("xos.dialog: {e}")),
+            }
+        });
+        reader.set_onload(Some(onload.as_ref().unchecked_ref()));
+        onload.forget();
+        reader.read_as_array_buffer(&file)
+    }
+
+    pub fn show_picker(accept: &str, multiple: bool) {
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return;
+        };
+        let Ok(input) = document.create_element("input").and_then(|el| {
+            el.dyn_into::<web_sys::HtmlInputElement>()
+                .map_err(Into::into)
+        }) else {
+            return;
+        };
+        input.set_type("file");
+        input.set_multiple(multiple);
+        if !accept.is_empty() {
+            input.set_accept(accept);
+        }
+        let input_clone = input.clone();
+        let onchange = Closure::once(move |_event: web_sys::Event| {
+            if let Some(files) = input_clone.files() {
+                import_browser_files(&files);
+            }
+        });
+        input.set_onchange(Some(onchange.as_ref().unchecked_ref()));
+        onchange.forget();
+        input.click();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_lists_every_filter_extension() {
+        let options = FileDialogOptions::default()
+            .filter("Audio", &["wav", ".mp3"])
+            .filter("Text", &["txt"]);
+        assert_eq!(options.accept(), ".wav,.mp3,.txt");
+        assert_eq!(FileDialogOptions::default().accept(), "");
+        let any = FileDialogOptions::default().filter("All", &["*"]);
+        assert_eq!(any.accept(), "");
+    }
+}
@@ -0,0 +1,597 @@
+//! Frame capture: dump the app-rendered [`FrameState`] to a PNG sequence, an animated GIF, or an
+//! MP4 / WebM (encoded by piping raw RGBA into `ffmpeg`).
+//!
+//! Each [`EngineState`](crate::engine::EngineState) owns a [`FrameCapture`]; hosts call
+//! [`FrameCapture::on_frame`] after the app's ticks and `render`, before zoom and overlays, so
+//! the F3 menu and on-screen keyboard never end up in the output. Start / stop requests made
+//! outside the engine (CLI, Python `app.capture`) go through [`request_capture`] and are picked
+//! up on the next frame.
+
+use crate::engine::engine::FrameState;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Mutex;
+
+pub const DEFAULT_CAPTURE_FPS: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// One PNG per captured frame (`out.png` → `out_000000.png`, a directory → `frame_000000.png`).
+    PngSequence,
+    Gif,
+    Mp4,
+    WebM,
+}
+
+impl CaptureFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("gif") => CaptureFormat::Gif,
+            Some("mp4") | Some("m4v") | Some("mov") => CaptureFormat::Mp4,
+            Some("webm") => CaptureFormat::WebM,
+            _ => CaptureFormat::PngSequence,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptureConfig {
+    pub path: PathBuf,
+    /// Keep one of every `every` frames (1 = all).
+    pub every: u32,
+    /// Stop after writing this many frames.
+    pub max_frames: Option<u32>,
+    /// Playback rate written into GIF / video files.
+    pub fps: f32,
+    /// Headless hosts simulate exactly `1 / (fps * every)` seconds per frame instead of wall-clock
+    /// time, so the output plays back at real speed however long encoding takes.
+    pub offline: bool,
+    /// Ask the host to quit once `max_frames` have been written.
+    pub exit_when_done: bool,
+}
+
+impl CaptureConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            every: 1,
+            max_frames: None,
+            fps: DEFAULT_CAPTURE_FPS,
+            offline: false,
+            exit_when_done: false,
+        }
+    }
+
+    pub fn format(&self) -> CaptureFormat {
+        CaptureFormat::from_path(&self.path)
+    }
+}
+
+/// Default output for the F3 record button: `<data dir>/captures/capture-<unix secs>.mp4`, or a
+/// PNG sequence directory when `ffmpeg` isn't installed.
+pub fn default_capture_path() -> PathBuf {
+    let base = crate::fs::data_dir_string()
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("captures");
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if ffmpeg_available() {
+        base.join(format!("capture-{stamp}.mp4"))
+    } else {
+        base.join(format!("capture-{stamp}"))
+    }
+}
+
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone)]
+pub enum CaptureCommand {
+    Start(CaptureConfig),
+    Stop,
+}
+
+/// Snapshot of the most recently active capture, readable from any thread (Python, CLI).
+#[derive(Debug, Clone, Default)]
+pub struct CaptureStatus {
+    pub active: bool,
+    pub frames_written: u32,
+    pub path: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+static CAPTURE_COMMANDS: Mutex<Vec<CaptureCommand>> = Mutex::new(Vec::new());
+static CAPTURE_STATUS: Mutex<Option<CaptureStatus>> = Mutex::new(None);
+
+/// Queue a start / stop for the running engine (applied before its next captured frame).
+pub fn request_capture(command: CaptureCommand) {
+    if let Ok(mut queue) = CAPTURE_COMMANDS.lock() {
+        queue.push(command);
+    }
+}
+
+pub fn capture_status() -> CaptureStatus {
+    CAPTURE_STATUS
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_default()
+}
+
+fn publish_status(status: CaptureStatus) {
+    if let Ok(mut slot) = CAPTURE_STATUS.lock() {
+        *slot = Some(status);
+    }
+}
+
+enum Sink {
+    Png {
+        dir: PathBuf,
+        stem: String,
+    },
+    Gif(image::codecs::gif::GifEncoder<BufWriter<File>>),
+    Ffmpeg {
+        child: Child,
+        stdin: Option<ChildStdin>,
+    },
+}
+
+impl Sink {
+    fn open(config: &CaptureConfig, width: u32, height: u32) -> Result<Self, String> {
+        let path = &config.path;
+        let format = config.format();
+        if format == CaptureFormat::PngSequence {
+            let (dir, stem) = if path.extension().is_some() {
+                let stem = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "frame".to_string());
+                let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                (dir, stem)
+            } else {
+                (path.clone(), "frame".to_string())
+            };
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(&dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            return Ok(Sink::Png { dir, stem });
+        }
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        if format == CaptureFormat::Gif {
+            let file = File::create(path)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            let mut encoder =
+                image::codecs::gif::GifEncoder::new_with_speed(BufWriter::new(file), 10);
+            encoder
+                .set_repeat(image::codecs::gif::Repeat::Infinite)
+                .map_err(|e| format!("GIF encoder error: {}", e))?;
+            return Ok(Sink::Gif(encoder));
+        }
+
+        let codec_args: &[&str] = match format {
+            CaptureFormat::WebM => &["-c:v", "libvpx-vp9", "-b:v", "0", "-crf", "32"],
+            _ => &["-c:v", "libx264", "-preset", "medium", "-crf", "18"],
+        };
+        let mut child = Command::new("ffmpeg")
+            .args([
+                "-y",
+                "-loglevel",
+                "error",
+                "-f",
+                "rawvideo",
+                "-pix_fmt",
+                "rgba",
+            ])
+            .args(["-s", &format!("{width}x{height}")])
+            .args(["-framerate", &format!("{}", config.fps)])
+            .args(["-i", "-"])
+            // yuv420p needs even dimensions.
+            .args([
+                "-vf",
+                "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+                "-pix_fmt",
+                "yuv420p",
+            ])
+            .args(codec_args)
+            .arg(path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("MP4 / WebM capture needs ffmpeg on PATH ({})", e))?;
+        let stdin = child.stdin.take();
+        Ok(Sink::Ffmpeg { child, stdin })
+    }
+
+    fn write(
+        &mut self,
+        index: u32,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        fps: f32,
+    ) -> Result<(), String> {
+        match self {
+            Sink::Png { dir, stem } => {
+                let path = dir.join(format!("{stem}_{index:06}.png"));
+                write_png(&path, rgba, width, height)
+            }
+            Sink::Gif(encoder) => {
+                let image = image::RgbaImage::from_raw(width, height, rgba.to_vec())
+                    .ok_or_else(|| "frame buffer size mismatch".to_string())?;
+                let delay = image::Delay::from_numer_denom_ms(1000, fps.max(1.0).round() as u32);
+                encoder
+                    .encode_frame(image::Frame::from_parts(image, 0, 0, delay))
+                    .map_err(|e| format!("GIF encoder error: {}", e))
+            }
+            Sink::Ffmpeg { stdin, .. } => stdin
+                .as_mut()
+                .ok_or_else(|| "ffmpeg input closed".to_string())?
+                .write_all(rgba)
+                .map_err(|e| format!("ffmpeg stopped accepting frames: {}", e)),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        match self {
+            Sink::Png { .. } | Sink::Gif(_) => Ok(()),
+            Sink::Ffmpeg { child, stdin } => {
+                // Closing stdin signals end of stream; ffmpeg then writes the container trailer.
+                drop(stdin.take());
+                let status = child
+                    .wait()
+                    .map_err(|e| format!("ffmpeg did not finish: {}", e))?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(format!("ffmpeg exited with {}", status))
+                }
+            }
+        }
+    }
+}
+
+/// Write an RGBA8 buffer as a PNG.
+pub fn write_png(path: &Path, rgba: &[u8], width: u32, height: u32) -> Result<(), String> {
+    image::save_buffer(path, rgba, width, height, image::ExtendedColorType::Rgba8)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+struct ActiveCapture {
+    config: CaptureConfig,
+    sink: Option<Sink>,
+    /// Output size, fixed by the first frame (video streams can't change size).
+    size: Option<(u32, u32)>,
+    frames_seen: u64,
+    frames_written: u32,
+    scratch: Vec<u8>,
+}
+
+impl ActiveCapture {
+    fn status(&self) -> CaptureStatus {
+        CaptureStatus {
+            active: true,
+            frames_written: self.frames_written,
+            path: Some(self.config.path.clone()),
+            error: None,
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        match self.sink.take() {
+            Some(mut sink) => sink.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ActiveCapture {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            eprintln!("⚠️  capture: {}", e);
+        }
+    }
+}
+
+/// Per-engine capture state; idle until started.
+#[derive(Default)]
+pub struct FrameCapture {
+    active: Option<ActiveCapture>,
+}
+
+impl std::fmt::Debug for FrameCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameCapture")
+            .field("active", &self.is_active())
+            .finish()
+    }
+}
+
+impl FrameCapture {
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn frames_written(&self) -> u32 {
+        self.active.as_ref().map(|a| a.frames_written).unwrap_or(0)
+    }
+
+    /// Begin a new capture, finishing any current one first.
+    pub fn start(&mut self, mut config: CaptureConfig) {
+        self.stop();
+        config.every = config.every.max(1);
+        if !(config.fps.is_finite() && config.fps > 0.0) {
+            config.fps = DEFAULT_CAPTURE_FPS;
+        }
+        eprintln!("⏺  capturing to {}", config.path.display());
+        let active = ActiveCapture {
+            config,
+            sink: None,
+            size: None,
+            frames_seen: 0,
+            frames_written: 0,
+            scratch: Vec::new(),
+        };
+        publish_status(active.status());
+        self.active = Some(active);
+    }
+
+    /// Finish the current capture (flushing / finalizing the file). Returns the output path.
+    pub fn stop(&mut self) -> Option<PathBuf> {
+        let mut active = self.active.take()?;
+        let result = active.finish();
+        let path = active.config.path.clone();
+        let error = match result {
+            Ok(()) => {
+                eprintln!(
+                    "⏹  captured {} frames to {}",
+                    active.frames_written,
+                    path.display()
+                );
+                None
+            }
+            Err(e) => {
+                eprintln!("❌ capture failed: {}", e);
+                Some(e)
+            }
+        };
+        publish_status(CaptureStatus {
+            active: false,
+            frames_written: active.frames_written,
+            path: Some(path.clone()),
+            error,
+        });
+        Some(path)
+    }
+
+    pub fn toggle(&mut self) {
+        if self.is_active() {
+            self.stop();
+        } else {
+            self.start(CaptureConfig::new(default_capture_path()));
+        }
+    }
+
+    /// Apply queued [`request_capture`] commands.
+    pub fn sync_requests(&mut self) {
+        let commands = match CAPTURE_COMMANDS.lock() {
+            Ok(mut queue) if !queue.is_empty() => std::mem::take(&mut *queue),
+            _ => return,
+        };
+        for command in commands {
+            match command {
+                CaptureCommand::Start(config) => self.start(config),
+                CaptureCommand::Stop => {
+                    self.stop();
+                }
+            }
+        }
+    }
+
+    /// Simulated seconds per frame for an offline (headless) capture.
+    pub fn offline_step_seconds(&mut self) -> Option<f32> {
+        self.sync_requests();
+        self.active
+            .as_ref()
+            .filter(|a| a.config.offline)
+            .map(|a| 1.0 / (a.config.fps * a.config.every.max(1) as f32))
+    }
+
+    /// Record `frame` if a capture is running and this frame is due.
+    pub fn on_frame(&mut self, frame: &mut FrameState) {
+        self.sync_requests();
+        if !self.next_frame_due() {
+            return;
+        }
+        let shape = frame.shape();
+        let (fw, fh) = (shape[1] as u32, shape[0] as u32);
+        self.record(frame.buffer_mut(), fw, fh);
+    }
+
+    /// Count one frame toward `every`; true when it should be written. Checked before touching
+    /// the frame buffer so skipped frames never pay for a GPU read-back.
+    fn next_frame_due(&mut self) -> bool {
+        let Some(active) = self.active.as_mut() else {
+            return false;
+        };
+        let due = active.frames_seen % active.config.every as u64 == 0;
+        active.frames_seen += 1;
+        due
+    }
+
+    /// Write one due `fw`×`fh` RGBA frame, stopping once `max_frames` are out.
+    fn record(&mut self, src: &[u8], fw: u32, fh: u32) {
+        let Some(active) = self.active.as_mut() else {
+            return;
+        };
+        let (w, h) = *active.size.get_or_insert((fw, fh));
+        if w == 0 || h == 0 {
+            return;
+        }
+        // Copy into a fixed-size opaque buffer: crops / pads if the window was resized mid-capture.
+        active.scratch.clear();
+        active.scratch.resize((w * h * 4) as usize, 0);
+        let copy_w = w.min(fw) as usize * 4;
+        for y in 0..h.min(fh) as usize {
+            let s = y * fw as usize * 4;
+            let d = y * w as usize * 4;
+            active.scratch[d..d + copy_w].copy_from_slice(&src[s..s + copy_w]);
+        }
+        for px in active.scratch.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+
+        let index = active.frames_written;
+        let mut written = Ok(());
+        if active.sink.is_none() {
+            match Sink::open(&active.config, w, h) {
+                Ok(sink) => active.sink = Some(sink),
+                Err(e) => written = Err(e),
+            }
+        }
+        if let Some(sink) = active.sink.as_mut() {
+            written = sink.write(index, &active.scratch, w, h, active.config.fps);
+        }
+        if let Err(e) = written {
+            active.sink = None;
+            let path = active.config.path.clone();
+            let exit = active.config.exit_when_done;
+            self.active = None;
+            eprintln!("❌ capture failed: {}", e);
+            publish_status(CaptureStatus {
+                active: false,
+                frames_written: index,
+                path: Some(path),
+                error: Some(e),
+            });
+            if exit {
+                // A `--frames` run would otherwise keep going with nothing to record.
+                #[cfg(not(target_arch = "wasm32"))]
+                crate::engine::native_engine::request_exit();
+            }
+            return;
+        }
+        active.frames_written += 1;
+        publish_status(active.status());
+
+        if active
+            .config
+            .max_frames
+            .is_some_and(|max| active.frames_written >= max)
+        {
+            let exit = active.config.exit_when_done;
+            self.stop();
+            if exit {
+                #[cfg(not(target_arch = "wasm32"))]
+                crate::engine::native_engine::request_exit();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(
+            CaptureFormat::from_path(Path::new("a.MP4")),
+            CaptureFormat::Mp4
+        );
+        assert_eq!(
+            CaptureFormat::from_path(Path::new("a.webm")),
+            CaptureFormat::WebM
+        );
+        assert_eq!(
+            CaptureFormat::from_path(Path::new("a.gif")),
+            CaptureFormat::Gif
+        );
+        assert_eq!(
+            CaptureFormat::from_path(Path::new("shots/a.png")),
+            CaptureFormat::PngSequence
+        );
+        assert_eq!(
+            CaptureFormat::from_path(Path::new("shots")),
+            CaptureFormat::PngSequence
+        );
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xos-capture-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn solid(w: u32, h: u32, red: u8) -> Vec<u8> {
+        [red, 0, 0, 128].repeat((w * h) as usize)
+    }
+
+    fn pixel(path: &Path, x: u32, y: u32) -> [u8; 4] {
+        image::open(path).unwrap().to_rgba8().get_pixel(x, y).0
+    }
+
+    #[test]
+    fn every_and_max_frames_pick_the_written_frames() {
+        let dir = temp_dir("range");
+        let mut config = CaptureConfig::new(&dir);
+        config.every = 2;
+        config.max_frames = Some(3);
+        let mut capture = FrameCapture::default();
+        capture.start(config);
+        for i in 0..10u8 {
+            if capture.next_frame_due() {
+                capture.record(&solid(4, 3, i * 10), 4, 3);
+            }
+        }
+        assert!(!capture.is_active(), "stops after max_frames");
+
+        // Frames 0, 2 and 4 were kept, written opaque.
+        for (n, red) in [(0, 0), (1, 20), (2, 40)] {
+            let path = dir.join(format!("frame_{n:06}.png"));
+            assert_eq!(pixel(&path, 1, 1), [red, 0, 0, 255]);
+        }
+        assert!(!dir.join("frame_000003.png").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn output_keeps_the_first_frame_size() {
+        let dir = temp_dir("size");
+        let mut capture = FrameCapture::default();
+        capture.start(CaptureConfig::new(dir.join("shot.png")));
+        capture.record(&solid(4, 3, 200), 4, 3);
+        // A resize mid-capture is cropped / padded back to 4x3.
+        capture.record(&solid(6, 2, 100), 6, 2);
+        capture.stop();
+
+        for n in 0..2 {
+            let image = image::open(dir.join(format!("shot_{n:06}.png"))).unwrap();
+            assert_eq!((image.width(), image.height()), (4, 3));
+        }
+        let second = dir.join("shot_000001.png");
+        assert_eq!(pixel(&second, 3, 1), [100, 0, 0, 255]);
+        assert_eq!(pixel(&second, 0, 2), [0, 0, 0, 255]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
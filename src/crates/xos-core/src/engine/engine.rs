@@ -347,7 +347,7 @@ pub struct MouseState {
 }
 
 /// Last-known host modifier keys (desktop / Web). Synced by each platform host before key routing.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct KeyboardModifiers {
     pub shift: bool,
     /// Command on macOS, Control on Windows/Linux — same notion as shortcut detection.
@@ -359,6 +359,8 @@ pub struct KeyboardModifiers {
 pub struct KeyboardState {
     pub onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard,
     pub modifiers: KeyboardModifiers,
+    /// Physical keys currently held; hosts update it through [`route_key_event`].
+    pub keys_down: crate::engine::keyboard::keys::KeysDown,
 }
 
 #[derive(Debug)]
@@ -366,18 +368,30 @@ pub struct EngineState {
     /// Frame state containing pixel array and safe region boundaries
     pub frame: FrameState,
     pub mouse: MouseState,
+    /// Every held mouse / touch / pen contact; `mouse` mirrors the primary one.
+    pub pointers: crate::engine::pointer::PointerState,
     pub keyboard: KeyboardState,
+    /// Connected controllers (host and virtual), refreshed by [`poll_gamepads`] before each tick.
+    pub gamepads: crate::engine::gamepad::Gamepads,
     /// Global F3 menu (FPS + UI scale; drawn by the engine after each app tick).
     pub f3_menu: F3Menu,
     /// F3 UI scale (25–500%). Default 100% → multiplier [`EngineState::f3_ui_scale_multiplier`] is 1.0 (`percent/100`).
     pub ui_scale_percent: u16,
     /// Seconds since the previous `Application::tick` (set by the host immediately before each tick).
     /// The first tick uses `1.0 / 60.0` as a nominal step so simulations can use `delta_time_seconds` safely.
+    /// In fixed-timestep mode every tick sees the fixed step; outside ticks it holds the frame time.
     pub delta_time_seconds: f32,
     /// Global simulation pause controlled by the F3 menu play/pause button.
     pub paused: bool,
     /// Number of one-tick step requests queued while paused.
     pub pending_step_ticks: u32,
+    /// Variable (default) or fixed-rate ticking; see [`advance_app_frame`].
+    pub timestep: crate::engine::timestep::FixedTimestep,
+    /// Frame recorder (PNG / GIF / MP4 / WebM); hosts feed it after `render`, before overlays.
+    pub capture: crate::engine::capture::FrameCapture,
+    /// Title / size / fullscreen / cursor requests for the host, and what it applied; see
+    /// [`crate::engine::window_control`].
+    pub window: crate::engine::window_control::WindowControl,
     /// View zoom applied to the app-rendered frame before overlays (1.0 = full frame).
     pub frame_view_zoom: f32,
     /// Target view zoom used by smoothing.
@@ -425,6 +439,50 @@ impl EngineState {
         f3_ui_scale_multiplier(self.ui_scale_percent)
     }
 
+    /// Offscreen state at `width`×`height` with default input, F3 and timing; used by the headless
+    /// host and the test harness.
+    pub fn headless(width: u32, height: u32) -> Self {
+        Self {
+            frame: FrameState::new(
+                width.max(1),
+                height.max(1),
+                SafeRegionBoundingRectangle::full_screen(),
+            ),
+            mouse: MouseState {
+                x: 0.0,
+                y: 0.0,
+                dx: 0.0,
+                dy: 0.0,
+                is_left_clicking: false,
+                is_right_clicking: false,
+                style: CursorStyleSetter::new(),
+            },
+            pointers: crate::engine::pointer::PointerState::default(),
+            keyboard: KeyboardState {
+                onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard::new(),
+                modifiers: KeyboardModifiers::default(),
+                keys_down: crate::engine::keyboard::keys::KeysDown::default(),
+            },
+            gamepads: crate::engine::gamepad::Gamepads::default(),
+            f3_menu: F3Menu::new(),
+            ui_scale_percent: 100,
+            delta_time_seconds: 1.0 / 60.0,
+            paused: false,
+            pending_step_ticks: 0,
+            timestep: crate::engine::timestep::FixedTimestep::default(),
+            capture: crate::engine::capture::FrameCapture::default(),
+            window: crate::engine::window_control::WindowControl::default(),
+            frame_view_zoom: 1.0,
+            frame_view_zoom_target: 1.0,
+            frame_view_zoom_velocity: 0.0,
+            frame_view_center_x: 0.5,
+            frame_view_center_y: 0.5,
+            f3_fps_label_override: None,
+            embed_last_plain_click_screen: None,
+            embed_synthetic_click_screen: None,
+        }
+    }
+
     /// Get mutable access to the frame buffer (zero-copy for CPU arrays)
     /// Panics if the array is on a non-CPU device
     pub fn frame_buffer_mut(&mut self) -> &mut [u8] {
@@ -452,6 +510,61 @@ pub fn tick_frame_delta(engine_state: &mut EngineState, last_instant: &mut Optio
     *last_instant = Some(now);
 }
 
+/// Runs the app's ticks for one displayed frame and returns how many ran.
+///
+/// Variable mode ticks once with the wall-clock delta. With [`EngineState::timestep`] fixed, it
+/// ticks zero or more times at the fixed step and leaves `timestep.alpha` for
+/// [`Application::render`]. Hosts should reuse the previous app frame when this returns 0.
+/// An offline [`capture`](crate::engine::capture) replaces wall-clock time with its frame step.
+pub fn advance_app_frame<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    last_instant: &mut Option<Instant>,
+) -> u32 {
+    tick_frame_delta(state, last_instant);
+    let frame_seconds = state
+        .capture
+        .offline_step_seconds()
+        .unwrap_or(state.delta_time_seconds);
+    advance_app_frame_by(app, state, frame_seconds)
+}
+
+/// [`advance_app_frame`] for a given frame duration instead of wall-clock time (test harness,
+/// offline rendering).
+pub fn advance_app_frame_by<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    frame_seconds: f32,
+) -> u32 {
+    state.delta_time_seconds = frame_seconds;
+    let steps = state.timestep.advance(frame_seconds);
+    if let Some(step) = state.timestep.step_seconds() {
+        state.delta_time_seconds = step;
+    }
+    for _ in 0..steps {
+        crate::profile_scope!("tick");
+        app.tick(state);
+        state.window.info.mouse_motion = (0.0, 0.0);
+    }
+    state.delta_time_seconds = frame_seconds;
+    steps
+}
+
+/// One F3 "step" while paused: a single tick, at the fixed step when one is set.
+pub fn step_app_tick<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    last_instant: &mut Option<Instant>,
+) {
+    tick_frame_delta(state, last_instant);
+    if let Some(step) = state.timestep.step_seconds() {
+        state.delta_time_seconds = step;
+    }
+    crate::profile_scope!("tick");
+    app.tick(state);
+    state.window.info.mouse_motion = (0.0, 0.0);
+}
+
 #[inline]
 fn clamp_center_for_zoom(center: f32, zoom: f32) -> f32 {
     let view_span = (1.0 / zoom.max(FRAME_VIEW_ZOOM_MIN)).clamp(0.0, 1.0);
@@ -560,7 +673,7 @@ pub fn frame_view_pan_by_pixels(
 }
 
 /// How [`Application::on_scroll`] reported vertical deltas should be interpreted.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum ScrollWheelUnit {
     /// Discrete line steps (mouse wheel notch): deltas are multiplied to pixel-like distances.
     Line,
@@ -643,8 +756,153 @@ pub trait Application {
         _shortcut: crate::engine::keyboard::shortcuts::ShortcutAction,
     ) {
     }
+    /// Any contact pressed (mouse button, finger, pen). Fires alongside `on_mouse_down` for the primary one.
+    fn on_pointer_down(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::pointer::PointerEvent,
+    ) {
+    }
+    fn on_pointer_move(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::pointer::PointerEvent,
+    ) {
+    }
+    /// Release or cancel (`event.phase`) of a contact.
+    fn on_pointer_up(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::pointer::PointerEvent,
+    ) {
+    }
+    /// Two-finger pinch / rotate / pan recognized from touch contacts.
+    fn on_gesture(
+        &mut self,
+        _state: &mut EngineState,
+        _gesture: &crate::engine::pointer::GestureEvent,
+    ) {
+    }
+    /// Raw key press (`event.repeat` on auto-repeat). Fires before `on_key_char` / `on_special_key`.
+    fn on_key_down(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::keyboard::keys::KeyEvent,
+    ) {
+    }
+    fn on_key_up(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::keyboard::keys::KeyEvent,
+    ) {
+    }
+    /// Controller connect / disconnect, button down / up, or stick motion. State is also readable
+    /// any time from `state.gamepads`.
+    fn on_gamepad(
+        &mut self,
+        _state: &mut EngineState,
+        _event: &crate::engine::gamepad::GamepadEvent,
+    ) {
+    }
+    /// Files dragged over the window. In the browser the names are only known on drop, so `paths`
+    /// is empty there.
+    fn on_file_hover(&mut self, _state: &mut EngineState, _paths: &[std::path::PathBuf]) {}
+    /// The hovered files left the window without being dropped.
+    fn on_file_hover_cancel(&mut self, _state: &mut EngineState) {}
+    /// Files dropped on the window. In the browser this also delivers files picked with
+    /// [`crate::dialog`]; their paths point into the `localStorage` VFS of [`crate::fs`].
+    fn on_file_drop(&mut self, _state: &mut EngineState, _paths: &[std::path::PathBuf]) {}
     fn on_screen_size_change(&mut self, _state: &mut EngineState, _width: u32, _height: u32) {}
+    /// Called once per displayed frame after that frame's ticks (possibly none in fixed-timestep
+    /// mode). Draw interpolated state here using `state.timestep.alpha`.
+    fn render(&mut self, _state: &mut EngineState) {}
 
     /// Called when the window is closing or Ctrl+C requested exit — stop I/O that can block drop.
     fn prepare_shutdown(&mut self, _state: &mut EngineState) {}
 }
+
+/// Update [`KeyboardState::keys_down`] for a raw key transition, then call
+/// [`Application::on_key_down`] / [`Application::on_key_up`]. Releases of keys that were never
+/// recorded as held are dropped so apps always see balanced pairs.
+pub fn route_key_event<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    mut event: crate::engine::keyboard::keys::KeyEvent,
+) {
+    if !state.keyboard.keys_down.apply(&mut event) {
+        return;
+    }
+    if event.pressed {
+        app.on_key_down(state, &event);
+    } else {
+        app.on_key_up(state, &event);
+    }
+}
+
+/// Focus loss: release every held key (hosts don't deliver key-up events to unfocused windows).
+pub fn release_all_keys<A: Application + ?Sized>(app: &mut A, state: &mut EngineState) {
+    for event in state.keyboard.keys_down.release_all() {
+        app.on_key_up(state, &event);
+    }
+}
+
+/// Update [`EngineState::pointers`] for a pointer transition, then call the matching
+/// `Application::on_pointer_*` and, when two touches move, [`Application::on_gesture`]. While the
+/// F3 menu is open, pinches zoom / pan the frame view instead of reaching the app.
+pub fn route_pointer_event<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    mut event: crate::engine::pointer::PointerEvent,
+) {
+    use crate::engine::pointer::PointerPhase;
+
+    let mut gesture = None;
+    if !state.pointers.apply(&mut event, &mut gesture) {
+        return;
+    }
+    match event.phase {
+        PointerPhase::Down => app.on_pointer_down(state, &event),
+        PointerPhase::Move => app.on_pointer_move(state, &event),
+        PointerPhase::Up | PointerPhase::Cancel => app.on_pointer_up(state, &event),
+    }
+    if let Some(g) = gesture {
+        if !super::f3_menu::f3_menu_handle_frame_pinch(state, &g) {
+            app.on_gesture(state, &g);
+        }
+    }
+}
+
+/// Focus loss: cancel every held pointer so apps never see a stuck contact. Each cancel goes through
+/// [`route_pointer_event`], which removes it from [`EngineState::pointers`]; the table ends up empty.
+pub fn cancel_all_pointers<A: Application + ?Sized>(app: &mut A, state: &mut EngineState) {
+    for event in state.pointers.cancel_all() {
+        route_pointer_event(app, state, event);
+    }
+}
+
+/// Drain `source` (the host's controllers, if any) and queued [`crate::engine::gamepad::VirtualGamepad`]
+/// input into [`EngineState::gamepads`], call [`Application::on_gamepad`] for each transition, then
+/// hand rumble requests made during the previous tick back to the source. Hosts call this once per
+/// frame, before `tick`.
+pub fn poll_gamepads<A: Application + ?Sized>(
+    app: &mut A,
+    state: &mut EngineState,
+    mut source: Option<&mut (dyn crate::engine::gamepad::GamepadSource + '_)>,
+) {
+    if let Some(src) = source.as_deref_mut() {
+        for request in state.gamepads.take_rumble_requests() {
+            src.rumble(&request);
+        }
+    }
+    state.gamepads.begin_frame();
+    let mut events = Vec::new();
+    if let Some(src) = source {
+        src.poll(&mut events);
+    }
+    crate::engine::gamepad::drain_virtual_gamepad_events(&mut events);
+    for event in &events {
+        if let Some(delivered) = state.gamepads.apply(event) {
+            app.on_gamepad(state, &delivered);
+        }
+    }
+}
@@ -1,10 +1,15 @@
 //! Global F3 menu (top-right): FPS, UI scale slider, opaque backing for readability.
 //! Desktop: toggle with **F3** (or host binding). iOS: **three-finger long-press** on the
 //! main viewport (same idea as Expo’s dev gesture); implemented in `XosViewportView.swift`.
+//! While open, a two-finger pinch zooms / pans the frame view ([`f3_menu_handle_frame_pinch`]) and
+//! the [`profiler`] records; its timeline is drawn along the bottom edge. **Shift+F3** saves the
+//! recorded frames as a Chrome trace.
 
+use crate::engine::pointer::GestureEvent;
+use crate::engine::profiler::{self, FrameProfile, PROFILE_HISTORY_FRAMES};
 use crate::engine::{
-    frame_view_rect_norm, EngineState, F3_UI_SCALE_MAX_PERCENT, F3_UI_SCALE_MIN_PERCENT,
-    FRAME_VIEW_ZOOM_MAX, FRAME_VIEW_ZOOM_MIN,
+    frame_view_pan_by_pixels, frame_view_rect_norm, EngineState, F3_UI_SCALE_MAX_PERCENT,
+    F3_UI_SCALE_MIN_PERCENT, FRAME_VIEW_ZOOM_MAX, FRAME_VIEW_ZOOM_MIN,
 };
 use crate::rasterizer::text::fonts::{self, FontFamily};
 use crate::rasterizer::text::text_rasterization::TextRasterizer;
@@ -24,6 +29,21 @@ const FONT_OPTION_BASE_SIZE: f32 = 19.0;
 const FONT_HEADER_BASE_SIZE: f32 = 17.0;
 #[cfg(target_os = "ios")]
 const IOS_MESH_TOGGLE_LABEL_BASE_SIZE: f32 = 16.0;
+const PROFILE_LABEL_BASE_SIZE: f32 = 16.0;
+/// Frame time at the top of the profiler history graph (two 60 FPS frames).
+const PROFILE_GRAPH_MAX_MS: f64 = 1000.0 / 30.0;
+const PROFILE_FLAME_ROWS: usize = 4;
+/// Scope colors, picked by name hash so a scope keeps its color across frames.
+const PROFILE_PALETTE: [(u8, u8, u8); 8] = [
+    (86, 156, 214),
+    (220, 120, 60),
+    (106, 190, 110),
+    (200, 90, 170),
+    (230, 200, 70),
+    (80, 190, 190),
+    (170, 130, 230),
+    (220, 80, 90),
+];
 
 pub struct F3Menu {
     /// When false, FPS is still tracked but the menu is not drawn. Toggle with F3 (desktop) or
@@ -32,8 +52,11 @@ pub struct F3Menu {
     fps_rasterizer: TextRasterizer,
     scale_rasterizer: TextRasterizer,
     font_header_rasterizer: TextRasterizer,
+    profile_rasterizer: TextRasterizer,
     font_option_rasterizers: Vec<TextRasterizer>,
     font_option_families: Vec<FontFamily>,
+    /// [`fonts::registry_version`] the option list was built against (user fonts appear when it moves).
+    font_options_version: u64,
     active_font_family: FontFamily,
     #[cfg(target_os = "ios")]
     ios_mesh_toggle_rasterizer: TextRasterizer,
@@ -62,24 +85,15 @@ impl F3Menu {
     pub fn new() -> Self {
         let font = fonts::default_font();
         let active_font_family = fonts::default_font_family();
-        let font_option_families = FontFamily::ALL.to_vec();
         let mut fps_rasterizer = TextRasterizer::new(font.clone(), BASE_FONT);
         fps_rasterizer.set_text("— FPS".to_string());
         let mut scale_rasterizer = TextRasterizer::new(font.clone(), BASE_FONT);
         scale_rasterizer.set_text("Scale: 100%".to_string());
-        let mut font_header_rasterizer = TextRasterizer::new(font, FONT_HEADER_BASE_SIZE);
+        let mut font_header_rasterizer = TextRasterizer::new(font.clone(), FONT_HEADER_BASE_SIZE);
         font_header_rasterizer.set_text("Default font".to_string());
-        let mut font_option_rasterizers = Vec::with_capacity(font_option_families.len());
-        for family in &font_option_families {
-            let option_font = match family {
-                FontFamily::JetBrainsMono => fonts::jetbrains_mono(),
-                FontFamily::Mplus1 => fonts::mplus1(),
-                FontFamily::DotGothic16 => fonts::dot_gothic_16(),
-            };
-            let mut rasterizer = TextRasterizer::new(option_font, FONT_OPTION_BASE_SIZE);
-            rasterizer.set_text(family.label().to_string());
-            font_option_rasterizers.push(rasterizer);
-        }
+        let profile_rasterizer = TextRasterizer::new(font, PROFILE_LABEL_BASE_SIZE);
+        let font_options_version = fonts::registry_version();
+        let (font_option_families, font_option_rasterizers) = build_font_options();
         #[cfg(target_os = "ios")]
         let mut ios_mesh_toggle_rasterizer =
             TextRasterizer::new(fonts::default_font(), IOS_MESH_TOGGLE_LABEL_BASE_SIZE);
@@ -90,8 +104,10 @@ impl F3Menu {
             fps_rasterizer,
             scale_rasterizer,
             font_header_rasterizer,
+            profile_rasterizer,
             font_option_rasterizers,
             font_option_families,
+            font_options_version,
             active_font_family,
             #[cfg(target_os = "ios")]
             ios_mesh_toggle_rasterizer,
@@ -159,6 +175,10 @@ struct PanelGeom {
     step_right: f32,
     step_top: f32,
     step_bottom: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    rec_left: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    rec_right: f32,
     minimap_left: f32,
     minimap_right: f32,
     minimap_top: f32,
@@ -229,6 +249,8 @@ fn panel_geom(
     let button_bottom = button_top + button_size;
     let step_top = button_top;
     let step_bottom = button_bottom;
+    #[cfg(not(target_arch = "wasm32"))]
+    let rec_right = button_left - button_gap;
     let slider_left = panel_left + pad;
     let slider_right = panel_left + panel_w - pad;
     let slider_top = panel_top + pad + line_h + line_gap + line_h + line_gap;
@@ -284,6 +306,10 @@ fn panel_geom(
         step_right,
         step_top,
         step_bottom,
+        #[cfg(not(target_arch = "wasm32"))]
+        rec_left: rec_right - button_size,
+        #[cfg(not(target_arch = "wasm32"))]
+        rec_right,
         minimap_left,
         minimap_right,
         minimap_top,
@@ -333,6 +359,32 @@ fn font_option_rect(geom: &PanelGeom, idx: usize) -> Option<(f32, f32, f32, f32)
     Some((geom.slider_left, geom.slider_right, y0, y1))
 }
 
+/// One label per family (built-ins, then registered user fonts), each drawn in its own face.
+fn build_font_options() -> (Vec<FontFamily>, Vec<TextRasterizer>) {
+    let families = fonts::all_families();
+    let rasterizers = families
+        .iter()
+        .map(|family| {
+            let mut rasterizer =
+                TextRasterizer::new(fonts::font_for_family(*family), FONT_OPTION_BASE_SIZE);
+            rasterizer.set_text(family.label());
+            rasterizer
+        })
+        .collect();
+    (families, rasterizers)
+}
+
+fn sync_f3_font_options(menu: &mut F3Menu) {
+    let version = fonts::registry_version();
+    if version == menu.font_options_version {
+        return;
+    }
+    menu.font_options_version = version;
+    let (families, rasterizers) = build_font_options();
+    menu.font_option_families = families;
+    menu.font_option_rasterizers = rasterizers;
+}
+
 fn sync_f3_default_font(menu: &mut F3Menu) {
     let current = fonts::default_font_family();
     if current == menu.active_font_family {
@@ -345,9 +397,13 @@ fn sync_f3_default_font(menu: &mut F3Menu) {
     let mut scale = TextRasterizer::new(active_font.clone(), menu.scale_rasterizer.font_size);
     scale.set_text(menu.scale_rasterizer.text.clone());
     menu.scale_rasterizer = scale;
-    let mut header = TextRasterizer::new(active_font, menu.font_header_rasterizer.font_size);
+    let mut header =
+        TextRasterizer::new(active_font.clone(), menu.font_header_rasterizer.font_size);
     header.set_text(menu.font_header_rasterizer.text.clone());
     menu.font_header_rasterizer = header;
+    let mut profile = TextRasterizer::new(active_font, menu.profile_rasterizer.font_size);
+    profile.set_text(menu.profile_rasterizer.text.clone());
+    menu.profile_rasterizer = profile;
     menu.active_font_family = current;
 }
 
@@ -419,7 +475,34 @@ pub fn f3_menu_handle_frame_zoom_scroll(state: &mut EngineState, wheel_delta_y:
     let h = (shape[0].max(1)) as f32;
     let nx = (state.mouse.x / w).clamp(0.0, 1.0);
     let ny = (state.mouse.y / h).clamp(0.0, 1.0);
+    zoom_frame_view_about(state, old_zoom, new_zoom, nx, ny);
+    true
+}
 
+/// Two-finger pinch on the frame while the F3 menu is open: zoom about the finger midpoint and pan
+/// with it (touch counterpart of Shift+Cmd/Ctrl + wheel / drag). Returns `true` when consumed.
+pub fn f3_menu_handle_frame_pinch(state: &mut EngineState, gesture: &GestureEvent) -> bool {
+    if !state.f3_menu.visible {
+        return false;
+    }
+    f3_menu_boost_interaction_fade(state);
+
+    let shape = state.frame.shape();
+    let w = (shape[1].max(1)) as f32;
+    let h = (shape[0].max(1)) as f32;
+    let old_zoom = state
+        .frame_view_zoom_target
+        .clamp(FRAME_VIEW_ZOOM_MIN, FRAME_VIEW_ZOOM_MAX);
+    let new_zoom = (old_zoom * gesture.delta_scale).clamp(FRAME_VIEW_ZOOM_MIN, FRAME_VIEW_ZOOM_MAX);
+    let nx = (gesture.center_x / w).clamp(0.0, 1.0);
+    let ny = (gesture.center_y / h).clamp(0.0, 1.0);
+    zoom_frame_view_about(state, old_zoom, new_zoom, nx, ny);
+    frame_view_pan_by_pixels(state, gesture.pan_dx, gesture.pan_dy, w, h);
+    true
+}
+
+/// Change frame zoom keeping the source point under output `(nx, ny)` (normalized) fixed.
+fn zoom_frame_view_about(state: &mut EngineState, old_zoom: f32, new_zoom: f32, nx: f32, ny: f32) {
     let old_view = 1.0 / old_zoom;
     let old_half = old_view * 0.5;
     let old_cx = state.frame_view_center_x.clamp(old_half, 1.0 - old_half);
@@ -443,7 +526,6 @@ pub fn f3_menu_handle_frame_zoom_scroll(state: &mut EngineState, wheel_delta_y:
     state.frame_view_zoom = new_zoom;
     state.frame_view_zoom_target = new_zoom;
     state.frame_view_zoom_velocity = 0.0;
-    true
 }
 
 /// Match rasterized label widths to [`tick_f3_menu`] so hit-testing uses the same panel and slider as drawing.
@@ -466,6 +548,7 @@ fn measure_f3_panel(state: &mut EngineState) -> Option<(PanelGeom, f32)> {
     {
         let menu = &mut state.f3_menu;
         sync_f3_default_font(menu);
+        sync_f3_font_options(menu);
         menu.fps_rasterizer.set_font_size(font_px);
         menu.fps_rasterizer.set_text(format!("{fps_display} FPS"));
         menu.fps_rasterizer.tick(width, height);
@@ -484,7 +567,7 @@ fn measure_f3_panel(state: &mut EngineState) -> Option<(PanelGeom, f32)> {
         for (idx, r) in menu.font_option_rasterizers.iter_mut().enumerate() {
             r.set_font_size(FONT_OPTION_BASE_SIZE * ui_scale);
             if let Some(family) = menu.font_option_families.get(idx) {
-                r.set_text(family.label().to_string());
+                r.set_text(family.label());
             }
             r.tick(width, height);
         }
@@ -516,7 +599,14 @@ fn measure_f3_panel(state: &mut EngineState) -> Option<(PanelGeom, f32)> {
     let button_gap = (6.0 * ui_scale).max(3.0);
     let slider_w = (200.0 * ui_scale).max(120.0);
     // Keep panel width stable so FPS/scale text width changes don't make the slider jitter horizontally.
-    let content_w = slider_w.max(button_size * 2.0 + button_gap + 240.0 * ui_scale);
+    // Record, play/pause, step (no record button on web: nowhere to write the file).
+    let button_count = if cfg!(target_arch = "wasm32") {
+        2.0
+    } else {
+        3.0
+    };
+    let content_w = slider_w
+        .max(button_size * button_count + button_gap * (button_count - 1.0) + 240.0 * ui_scale);
     let (_, _, vw, vh) = frame_view_rect_norm(state);
     let show_minimap = vw < 0.999 || vh < 0.999;
     let geom = panel_geom(
@@ -576,6 +666,20 @@ pub fn f3_menu_handle_mouse_down(state: &mut EngineState) -> bool {
         state.f3_menu.pointer_captured = true;
         return true;
     }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let on_rec = mx >= geom.rec_left
+            && mx <= geom.rec_right
+            && my >= geom.button_top
+            && my <= geom.button_bottom;
+        if on_rec {
+            f3_menu_boost_interaction_fade(state);
+            state.capture.toggle();
+            state.f3_menu.scale_dragging = false;
+            state.f3_menu.pointer_captured = true;
+            return true;
+        }
+    }
     #[cfg(target_os = "ios")]
     {
         let on_toggle = mx >= geom.toggle_left
@@ -642,6 +746,7 @@ pub fn f3_menu_handle_mouse_up(state: &mut EngineState) -> bool {
 /// Update smoothed FPS and composite the F3 menu into the frame (after app + keyboard).
 pub fn tick_f3_menu(state: &mut EngineState) {
     tick_scale_zoom_smoothing(state);
+    profiler::set_overlay_active(state.f3_menu.visible);
 
     let dt = state.delta_time_seconds.clamp(1.0 / 240.0, 0.25);
     state.f3_menu.interaction_fade *= (-F3_INTERACTION_FADE_DECAY * dt).exp();
@@ -721,6 +826,62 @@ pub fn tick_f3_menu(state: &mut EngineState) {
         (btn_bg.0, btn_bg.1, btn_bg.2, (255.0 * overlay_alpha) as u8),
     );
 
+    // Record button: red dot when idle, stop square while capturing.
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let rec_x0 = geom.rec_left.floor() as i32;
+        let rec_x1 = geom.rec_right.ceil() as i32;
+        let recording = state.capture.is_active();
+        let rec_bg = if recording {
+            (168, 36, 36, 0xff)
+        } else {
+            (70, 70, 70, 0xff)
+        };
+        blend_rect(
+            buffer,
+            fw,
+            fh,
+            rec_x0,
+            btn_y0,
+            rec_x1,
+            btn_y1,
+            (rec_bg.0, rec_bg.1, rec_bg.2, (255.0 * overlay_alpha) as u8),
+        );
+        let rw = (rec_x1 - rec_x0).max(4) as f32;
+        let rh = (btn_y1 - btn_y0).max(4) as f32;
+        let cx = rec_x0 as f32 + rw * 0.5;
+        let cy = btn_y0 as f32 + rh * 0.5;
+        if recording {
+            let half = (rw.min(rh) * 0.2).round().max(1.0);
+            blend_rect(
+                buffer,
+                fw,
+                fh,
+                (cx - half) as i32,
+                (cy - half) as i32,
+                (cx + half) as i32,
+                (cy + half) as i32,
+                (245, 245, 245, (255.0 * overlay_alpha) as u8),
+            );
+        } else {
+            let r = rw.min(rh) * 0.26;
+            for y in (cy - r).floor() as i32..(cy + r).ceil() as i32 {
+                let dy = y as f32 + 0.5 - cy;
+                let span = (r * r - dy * dy).max(0.0).sqrt();
+                blend_rect(
+                    buffer,
+                    fw,
+                    fh,
+                    (cx - span).round() as i32,
+                    y,
+                    (cx + span).round() as i32,
+                    y + 1,
+                    (230, 48, 48, (255.0 * overlay_alpha) as u8),
+                );
+            }
+        }
+    }
+
     // Step button (active only while paused).
     let step_x0 = geom.step_left.floor() as i32;
     let step_y0 = geom.step_top.floor() as i32;
@@ -1071,6 +1232,165 @@ pub fn tick_f3_menu(state: &mut EngineState) {
             overlay_alpha,
         );
     }
+
+    if state.f3_menu.visible {
+        draw_profile_timeline(state);
+    }
+}
+
+#[inline]
+fn profile_color(name: &str) -> (u8, u8, u8) {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    PROFILE_PALETTE[hash as usize % PROFILE_PALETTE.len()]
+}
+
+/// `16.7 ms | tick 9.1 · present 2.0 · …`: the last frame and its top-level scopes.
+fn profile_summary(frame: &FrameProfile) -> String {
+    let scopes: Vec<String> = frame
+        .top_level_totals()
+        .into_iter()
+        .map(|(name, us)| format!("{name} {:.1}", us / 1000.0))
+        .collect();
+    format!(
+        "{:.1} ms | {}   (Shift+F3: save trace)",
+        frame.duration_us / 1000.0,
+        scopes.join(" · ")
+    )
+}
+
+/// Profiler strip along the bottom edge: frame-time history (newest right, stacked by top-level
+/// scope over the whole frame in grey, line at 16.7 ms) above a flame graph of the last frame.
+fn draw_profile_timeline(state: &mut EngineState) {
+    let shape = state.frame.shape();
+    let width = shape[1] as f32;
+    let height = shape[0] as f32;
+    let us = F3Menu::ui_scale(width.max(height));
+    let pad = F3Menu::padding_scaled(us);
+    let gap = 6.0 * us;
+    let label_h = (PROFILE_LABEL_BASE_SIZE * 1.5 * us).max(12.0);
+    let history_h = (72.0 * us).max(36.0);
+    let row_h = (12.0 * us).max(6.0);
+    let panel_h = pad + label_h + gap + history_h + gap + row_h * PROFILE_FLAME_ROWS as f32 + pad;
+    let panel_left = pad;
+    let panel_w = width - pad * 2.0;
+    let panel_top = state.frame.safe_region_boundaries.y2 * height - pad - panel_h;
+    if panel_w < 60.0 || panel_top < 0.0 {
+        return;
+    }
+    let graph_left = panel_left + pad;
+    let graph_w = panel_w - pad * 2.0;
+    let history_top = panel_top + pad + label_h + gap;
+    let history_bottom = history_top + history_h;
+    let flame_top = history_bottom + gap;
+
+    let summary = profiler::with_frames(|frames| frames.back().map(profile_summary))
+        .unwrap_or_else(|| "Profiling…   (Shift+F3: save trace)".to_string());
+    {
+        let menu = &mut state.f3_menu;
+        menu.profile_rasterizer
+            .set_font_size(PROFILE_LABEL_BASE_SIZE * us);
+        menu.profile_rasterizer.set_text(summary);
+        menu.profile_rasterizer.tick(width, height);
+    }
+
+    let fw = width as usize;
+    let fh = height as usize;
+    let buffer = state.frame.buffer_mut();
+    blend_rect(
+        buffer,
+        fw,
+        fh,
+        panel_left as i32,
+        panel_top as i32,
+        (panel_left + panel_w).ceil() as i32,
+        (panel_top + panel_h).ceil() as i32,
+        (0, 0, 0, 220),
+    );
+    blend_text(
+        buffer,
+        width,
+        height,
+        &state.f3_menu.profile_rasterizer,
+        graph_left,
+        panel_top + pad,
+        (235, 235, 235),
+        1.0,
+    );
+
+    profiler::with_frames(|frames| {
+        let slot_w = graph_w / PROFILE_HISTORY_FRAMES as f32;
+        let first_slot = PROFILE_HISTORY_FRAMES - frames.len();
+        let bar_h = |us: f64| ((us / 1000.0 / PROFILE_GRAPH_MAX_MS).min(1.0) as f32) * history_h;
+        for (i, frame) in frames.iter().enumerate() {
+            let x0 = (graph_left + (first_slot + i) as f32 * slot_w) as i32;
+            let x1 = ((graph_left + (first_slot + i + 1) as f32 * slot_w) as i32).max(x0 + 1);
+            let frame_h = bar_h(frame.duration_us);
+            blend_rect(
+                buffer,
+                fw,
+                fh,
+                x0,
+                (history_bottom - frame_h) as i32,
+                x1,
+                history_bottom as i32,
+                (90, 90, 90, 255),
+            );
+            let mut y = history_bottom;
+            for (name, total) in frame.top_level_totals() {
+                let h = bar_h(total).min(y - history_bottom + frame_h);
+                let (r, g, b) = profile_color(name);
+                blend_rect(
+                    buffer,
+                    fw,
+                    fh,
+                    x0,
+                    (y - h) as i32,
+                    x1,
+                    y as i32,
+                    (r, g, b, 255),
+                );
+                y -= h;
+            }
+        }
+        let target_y = history_bottom - bar_h(1_000_000.0 / 60.0);
+        blend_rect(
+            buffer,
+            fw,
+            fh,
+            graph_left as i32,
+            target_y as i32,
+            (graph_left + graph_w) as i32,
+            target_y as i32 + 1,
+            (240, 240, 240, 120),
+        );
+
+        let Some(frame) = frames.back() else {
+            return;
+        };
+        let scale = graph_w as f64 / frame.duration_us.max(1.0);
+        for span in &frame.spans {
+            let depth = span.depth as usize;
+            if depth >= PROFILE_FLAME_ROWS {
+                continue;
+            }
+            let x0 = graph_left + ((span.start_us - frame.start_us) * scale) as f32;
+            let x1 = (x0 + (span.duration_us * scale) as f32).max(x0 + 1.0);
+            let y0 = flame_top + depth as f32 * row_h;
+            let (r, g, b) = profile_color(&span.name);
+            blend_rect(
+                buffer,
+                fw,
+                fh,
+                x0 as i32,
+                y0 as i32,
+                x1.ceil() as i32,
+                (y0 + row_h - 1.0) as i32,
+                (r, g, b, 255),
+            );
+        }
+    });
 }
 
 fn blend_text(
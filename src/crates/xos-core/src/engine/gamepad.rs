@@ -0,0 +1,618 @@
+//! Gamepad / controller state (`EngineState::gamepads`).
+//!
+//! Hosts drain a [`GamepadSource`] (gilrs on desktop) each tick through
+//! [`crate::engine::poll_gamepads`]; [`VirtualGamepad`] feeds the same [`GamepadEvent`]s from code,
+//! so headless runs and tests can drive input without hardware.
+//!
+//! Buttons follow the W3C "standard gamepad" layout (face buttons by position: `south` is Xbox A /
+//! PlayStation cross). Stick axes are `-1..1` with **+y down**, like frame coordinates.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+/// A button counts as held at or above this value (analog triggers report `0..1`).
+pub const BUTTON_PRESS_THRESHOLD: f32 = 0.5;
+/// Radial stick deadzone applied by [`Gamepad::left_stick`] / [`Gamepad::right_stick`].
+pub const DEFAULT_STICK_DEADZONE: f32 = 0.15;
+/// Ids at or above this belong to [`VirtualGamepad`]s; host ids stay below it.
+pub const VIRTUAL_GAMEPAD_ID_BASE: u32 = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Mode,
+}
+
+impl GamepadButton {
+    /// Standard-layout order (index = W3C `Gamepad.buttons` index).
+    pub const ALL: [GamepadButton; 17] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::LeftTrigger,
+        GamepadButton::RightTrigger,
+        GamepadButton::Select,
+        GamepadButton::Start,
+        GamepadButton::LeftStick,
+        GamepadButton::RightStick,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+        GamepadButton::Mode,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GamepadButton::South => "south",
+            GamepadButton::East => "east",
+            GamepadButton::West => "west",
+            GamepadButton::North => "north",
+            GamepadButton::LeftBumper => "left_bumper",
+            GamepadButton::RightBumper => "right_bumper",
+            GamepadButton::LeftTrigger => "left_trigger",
+            GamepadButton::RightTrigger => "right_trigger",
+            GamepadButton::Select => "select",
+            GamepadButton::Start => "start",
+            GamepadButton::LeftStick => "left_stick",
+            GamepadButton::RightStick => "right_stick",
+            GamepadButton::DPadUp => "dpad_up",
+            GamepadButton::DPadDown => "dpad_down",
+            GamepadButton::DPadLeft => "dpad_left",
+            GamepadButton::DPadRight => "dpad_right",
+            GamepadButton::Mode => "mode",
+        }
+    }
+
+    /// Parse a canonical name or a common alias (`"a"`, `"cross"`, `"lb"`, `"r2"`, `"up"`, `"home"`, …).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = name.trim().to_lowercase().replace(['-', ' '], "_");
+        if let Some(b) = Self::ALL.iter().find(|b| b.name() == key) {
+            return Some(*b);
+        }
+        Some(match key.as_str() {
+            "a" | "cross" => GamepadButton::South,
+            "b" | "circle" => GamepadButton::East,
+            "x" | "square" => GamepadButton::West,
+            "y" | "triangle" => GamepadButton::North,
+            "lb" | "l1" => GamepadButton::LeftBumper,
+            "rb" | "r1" => GamepadButton::RightBumper,
+            "lt" | "l2" => GamepadButton::LeftTrigger,
+            "rt" | "r2" => GamepadButton::RightTrigger,
+            "back" | "view" | "share" => GamepadButton::Select,
+            "menu" | "options" => GamepadButton::Start,
+            "ls" | "l3" => GamepadButton::LeftStick,
+            "rs" | "r3" => GamepadButton::RightStick,
+            "up" => GamepadButton::DPadUp,
+            "down" => GamepadButton::DPadDown,
+            "left" => GamepadButton::DPadLeft,
+            "right" => GamepadButton::DPadRight,
+            "home" | "guide" | "ps" => GamepadButton::Mode,
+            _ => return None,
+        })
+    }
+
+    fn bit(self) -> u32 {
+        1 << self.index()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+}
+
+impl GamepadAxis {
+    pub const ALL: [GamepadAxis; 4] = [
+        GamepadAxis::LeftX,
+        GamepadAxis::LeftY,
+        GamepadAxis::RightX,
+        GamepadAxis::RightY,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GamepadAxis::LeftX => "left_x",
+            GamepadAxis::LeftY => "left_y",
+            GamepadAxis::RightX => "right_x",
+            GamepadAxis::RightY => "right_y",
+        }
+    }
+
+    /// Canonical name or short alias (`"lx"`, `"ly"`, `"rx"`, `"ry"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = name.trim().to_lowercase().replace(['-', ' '], "_");
+        Some(match key.as_str() {
+            "left_x" | "lx" => GamepadAxis::LeftX,
+            "left_y" | "ly" => GamepadAxis::LeftY,
+            "right_x" | "rx" => GamepadAxis::RightX,
+            "right_y" | "ry" => GamepadAxis::RightY,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEventKind {
+    Connected {
+        name: String,
+        is_virtual: bool,
+        supports_rumble: bool,
+    },
+    Disconnected,
+    /// Raw button value from a source; [`Gamepads::apply`] turns threshold crossings into
+    /// `ButtonDown` / `ButtonUp`.
+    ButtonChanged(GamepadButton, f32),
+    ButtonDown(GamepadButton),
+    ButtonUp(GamepadButton),
+    AxisChanged(GamepadAxis, f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadEvent {
+    pub id: u32,
+    pub kind: GamepadEventKind,
+}
+
+impl GamepadEvent {
+    pub fn new(id: u32, kind: GamepadEventKind) -> Self {
+        Self { id, kind }
+    }
+}
+
+/// Force-feedback request; magnitudes are `0..1`, both zero stops the motors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleRequest {
+    pub id: u32,
+    /// Low-frequency (heavy) motor.
+    pub strong: f32,
+    /// High-frequency (light) motor.
+    pub weak: f32,
+    pub duration_ms: u32,
+}
+
+/// Where a host gets controller input from.
+pub trait GamepadSource {
+    /// Append every event since the last call.
+    fn poll(&mut self, out: &mut Vec<GamepadEvent>);
+    /// Start (or stop) force feedback; ignore pads without motors.
+    fn rumble(&mut self, request: &RumbleRequest);
+}
+
+#[derive(Debug, Clone)]
+pub struct Gamepad {
+    pub id: u32,
+    pub name: String,
+    pub connected: bool,
+    pub is_virtual: bool,
+    pub supports_rumble: bool,
+    /// Radial deadzone for the stick helpers (raw [`Gamepad::axis`] is unfiltered).
+    pub deadzone: f32,
+    /// Last accepted [`Gamepads::rumble`] request, so virtual pads can be asserted on.
+    pub last_rumble: Option<RumbleRequest>,
+    values: [f32; GamepadButton::ALL.len()],
+    axes: [f32; GamepadAxis::ALL.len()],
+    down: u32,
+    pressed: u32,
+    released: u32,
+}
+
+impl Gamepad {
+    fn new(id: u32, name: String, is_virtual: bool, supports_rumble: bool) -> Self {
+        Self {
+            id,
+            name,
+            connected: true,
+            is_virtual,
+            supports_rumble,
+            deadzone: DEFAULT_STICK_DEADZONE,
+            last_rumble: None,
+            values: [0.0; GamepadButton::ALL.len()],
+            axes: [0.0; GamepadAxis::ALL.len()],
+            down: 0,
+            pressed: 0,
+            released: 0,
+        }
+    }
+
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.down & button.bit() != 0
+    }
+
+    /// Went down since the previous tick (still `true` for a tap released within the same tick).
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed & button.bit() != 0
+    }
+
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.released & button.bit() != 0
+    }
+
+    /// Analog value `0..1` (digital buttons are `0` or `1`).
+    pub fn value(&self, button: GamepadButton) -> f32 {
+        self.values[button.index()]
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis.index()]
+    }
+
+    pub fn left_stick(&self) -> (f32, f32) {
+        self.stick(GamepadAxis::LeftX, GamepadAxis::LeftY)
+    }
+
+    pub fn right_stick(&self) -> (f32, f32) {
+        self.stick(GamepadAxis::RightX, GamepadAxis::RightY)
+    }
+
+    /// Buttons currently held, in standard-layout order.
+    pub fn buttons_down(&self) -> impl Iterator<Item = GamepadButton> + '_ {
+        GamepadButton::ALL.into_iter().filter(|b| self.is_down(*b))
+    }
+
+    /// Stick with a radial deadzone, rescaled so output still spans the full `0..1` magnitude.
+    fn stick(&self, x_axis: GamepadAxis, y_axis: GamepadAxis) -> (f32, f32) {
+        let (x, y) = (self.axis(x_axis), self.axis(y_axis));
+        let len = (x * x + y * y).sqrt();
+        let dz = self.deadzone.clamp(0.0, 0.99);
+        if len <= dz {
+            return (0.0, 0.0);
+        }
+        let scaled = ((len - dz) / (1.0 - dz)).min(1.0);
+        (x / len * scaled, y / len * scaled)
+    }
+
+    fn set_button(&mut self, button: GamepadButton, value: f32) -> Option<GamepadEventKind> {
+        let value = if value.is_finite() {
+            value.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.values[button.index()] = value;
+        let now_down = value >= BUTTON_PRESS_THRESHOLD;
+        if now_down == self.is_down(button) {
+            return None;
+        }
+        if now_down {
+            self.down |= button.bit();
+            self.pressed |= button.bit();
+            Some(GamepadEventKind::ButtonDown(button))
+        } else {
+            self.down &= !button.bit();
+            self.released |= button.bit();
+            Some(GamepadEventKind::ButtonUp(button))
+        }
+    }
+
+    /// Release everything (disconnect), recording edges so `just_released` still fires.
+    fn clear_inputs(&mut self) {
+        self.released |= self.down;
+        self.down = 0;
+        self.values = [0.0; GamepadButton::ALL.len()];
+        self.axes = [0.0; GamepadAxis::ALL.len()];
+    }
+}
+
+/// Every gamepad seen this session; disconnected pads keep their slot so ids stay stable on reconnect.
+#[derive(Debug, Default)]
+pub struct Gamepads {
+    pads: Vec<Gamepad>,
+    rumble_queue: Vec<RumbleRequest>,
+}
+
+impl Gamepads {
+    /// Clear the per-tick `just_pressed` / `just_released` edges. Called before applying a tick's events.
+    pub fn begin_frame(&mut self) {
+        for pad in &mut self.pads {
+            pad.pressed = 0;
+            pad.released = 0;
+        }
+    }
+
+    /// Fold one source event into the table. Returns the event apps should see, or `None` when
+    /// nothing observable changed (duplicate connect, analog wiggle below the press threshold).
+    pub fn apply(&mut self, event: &GamepadEvent) -> Option<GamepadEvent> {
+        let id = event.id;
+        let delivered = match &event.kind {
+            GamepadEventKind::Connected {
+                name,
+                is_virtual,
+                supports_rumble,
+            } => {
+                match self.pads.iter_mut().find(|p| p.id == id) {
+                    Some(pad) if pad.connected => return None,
+                    Some(pad) => {
+                        pad.connected = true;
+                        pad.name = name.clone();
+                        pad.supports_rumble = *supports_rumble;
+                    }
+                    None => self.pads.push(Gamepad::new(
+                        id,
+                        name.clone(),
+                        *is_virtual,
+                        *supports_rumble,
+                    )),
+                }
+                event.kind.clone()
+            }
+            GamepadEventKind::Disconnected => {
+                let pad = self.connected_mut(id)?;
+                pad.connected = false;
+                pad.clear_inputs();
+                GamepadEventKind::Disconnected
+            }
+            GamepadEventKind::ButtonChanged(button, value) => {
+                self.connected_mut(id)?.set_button(*button, *value)?
+            }
+            GamepadEventKind::ButtonDown(button) => {
+                self.connected_mut(id)?.set_button(*button, 1.0)?
+            }
+            GamepadEventKind::ButtonUp(button) => {
+                self.connected_mut(id)?.set_button(*button, 0.0)?
+            }
+            GamepadEventKind::AxisChanged(axis, value) => {
+                let pad = self.connected_mut(id)?;
+                let value = if value.is_finite() {
+                    value.clamp(-1.0, 1.0)
+                } else {
+                    0.0
+                };
+                if pad.axes[axis.index()] == value {
+                    return None;
+                }
+                pad.axes[axis.index()] = value;
+                GamepadEventKind::AxisChanged(*axis, value)
+            }
+        };
+        Some(GamepadEvent::new(id, delivered))
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.pads.iter_mut().find(|p| p.id == id)
+    }
+
+    /// Connected pads in connection order.
+    pub fn connected(&self) -> impl Iterator<Item = &Gamepad> {
+        self.pads.iter().filter(|p| p.connected)
+    }
+
+    /// First connected pad — the usual "player one".
+    pub fn first(&self) -> Option<&Gamepad> {
+        self.connected().next()
+    }
+
+    /// Queue force feedback for the host; returns `false` for unknown pads or pads without motors.
+    pub fn rumble(&mut self, id: u32, strong: f32, weak: f32, duration_ms: u32) -> bool {
+        let Some(pad) = self
+            .get_mut(id)
+            .filter(|p| p.connected && p.supports_rumble)
+        else {
+            return false;
+        };
+        let request = RumbleRequest {
+            id,
+            strong: strong.clamp(0.0, 1.0),
+            weak: weak.clamp(0.0, 1.0),
+            duration_ms,
+        };
+        pad.last_rumble = Some(request);
+        if !pad.is_virtual {
+            self.rumble_queue.push(request);
+        }
+        true
+    }
+
+    /// Rumble requests for host pads since the last call.
+    pub fn take_rumble_requests(&mut self) -> Vec<RumbleRequest> {
+        std::mem::take(&mut self.rumble_queue)
+    }
+
+    fn connected_mut(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.pads.iter_mut().find(|p| p.id == id && p.connected)
+    }
+}
+
+static VIRTUAL_EVENTS: Mutex<Vec<GamepadEvent>> = Mutex::new(Vec::new());
+static NEXT_VIRTUAL_ID: AtomicU32 = AtomicU32::new(VIRTUAL_GAMEPAD_ID_BASE);
+
+/// Software gamepad: its input is queued process-wide and reaches `EngineState::gamepads` on the
+/// next [`crate::engine::poll_gamepads`], exactly like a physical pad. Handles are cheap copies;
+/// call [`VirtualGamepad::disconnect`] to unplug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VirtualGamepad {
+    id: u32,
+}
+
+impl VirtualGamepad {
+    pub fn connect(name: &str) -> Self {
+        let pad = Self {
+            id: NEXT_VIRTUAL_ID.fetch_add(1, Ordering::Relaxed),
+        };
+        pad.push(GamepadEventKind::Connected {
+            name: name.to_string(),
+            is_virtual: true,
+            supports_rumble: true,
+        });
+        pad
+    }
+
+    /// Handle for an id previously returned by [`VirtualGamepad::id`].
+    pub fn from_id(id: u32) -> Option<Self> {
+        (id >= VIRTUAL_GAMEPAD_ID_BASE).then_some(Self { id })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn press(&self, button: GamepadButton) {
+        self.set_button(button, 1.0);
+    }
+
+    pub fn release(&self, button: GamepadButton) {
+        self.set_button(button, 0.0);
+    }
+
+    pub fn set_button(&self, button: GamepadButton, value: f32) {
+        self.push(GamepadEventKind::ButtonChanged(button, value));
+    }
+
+    pub fn set_axis(&self, axis: GamepadAxis, value: f32) {
+        self.push(GamepadEventKind::AxisChanged(axis, value));
+    }
+
+    pub fn disconnect(&self) {
+        self.push(GamepadEventKind::Disconnected);
+    }
+
+    fn push(&self, kind: GamepadEventKind) {
+        if let Ok(mut queue) = VIRTUAL_EVENTS.lock() {
+            queue.push(GamepadEvent::new(self.id, kind));
+        }
+    }
+}
+
+/// Take every queued [`VirtualGamepad`] event, oldest first.
+pub fn drain_virtual_gamepad_events(out: &mut Vec<GamepadEvent>) {
+    if let Ok(mut queue) = VIRTUAL_EVENTS.lock() {
+        out.append(&mut queue);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect(pads: &mut Gamepads, id: u32) {
+        let ev = GamepadEvent::new(
+            id,
+            GamepadEventKind::Connected {
+                name: "Pad".into(),
+                is_virtual: false,
+                supports_rumble: true,
+            },
+        );
+        assert!(pads.apply(&ev).is_some());
+        assert!(pads.apply(&ev).is_none());
+    }
+
+    #[test]
+    fn buttons_edges_and_triggers() {
+        let mut pads = Gamepads::default();
+        connect(&mut pads, 3);
+        let south = GamepadEvent::new(
+            3,
+            GamepadEventKind::ButtonChanged(GamepadButton::South, 1.0),
+        );
+        assert_eq!(
+            pads.apply(&south).map(|e| e.kind),
+            Some(GamepadEventKind::ButtonDown(GamepadButton::South))
+        );
+        let pad = pads.get(3).unwrap();
+        assert!(pad.is_down(GamepadButton::South) && pad.just_pressed(GamepadButton::South));
+
+        pads.begin_frame();
+        assert!(!pads.get(3).unwrap().just_pressed(GamepadButton::South));
+
+        // Analog trigger: no transition until it crosses the threshold.
+        let lt = |v| {
+            GamepadEvent::new(
+                3,
+                GamepadEventKind::ButtonChanged(GamepadButton::LeftTrigger, v),
+            )
+        };
+        assert!(pads.apply(&lt(0.3)).is_none());
+        assert_eq!(pads.get(3).unwrap().value(GamepadButton::LeftTrigger), 0.3);
+        assert!(pads.apply(&lt(0.8)).is_some());
+
+        assert!(pads
+            .apply(&GamepadEvent::new(3, GamepadEventKind::Disconnected))
+            .is_some());
+        let pad = pads.get(3).unwrap();
+        assert!(!pad.connected && pad.just_released(GamepadButton::South));
+        assert!(pads.first().is_none());
+        assert!(pads.apply(&south).is_none());
+    }
+
+    #[test]
+    fn stick_deadzone_and_rumble() {
+        let mut pads = Gamepads::default();
+        connect(&mut pads, 0);
+        let axis = |a, v| GamepadEvent::new(0, GamepadEventKind::AxisChanged(a, v));
+        pads.apply(&axis(GamepadAxis::LeftX, 0.1));
+        assert_eq!(pads.get(0).unwrap().left_stick(), (0.0, 0.0));
+        pads.apply(&axis(GamepadAxis::LeftX, 1.0));
+        let (x, y) = pads.get(0).unwrap().left_stick();
+        assert!((x - 1.0).abs() < 1e-6 && y == 0.0);
+
+        assert!(pads.rumble(0, 2.0, 0.25, 100));
+        assert!(!pads.rumble(9, 1.0, 1.0, 100));
+        let queued = pads.take_rumble_requests();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].strong, 1.0);
+    }
+
+    #[test]
+    fn virtual_pad_round_trip_and_names() {
+        let pad = VirtualGamepad::connect("Test Pad");
+        pad.press(GamepadButton::from_name("A").unwrap());
+        pad.set_axis(GamepadAxis::from_name("ly").unwrap(), 0.5);
+        let mut events = Vec::new();
+        drain_virtual_gamepad_events(&mut events);
+        let mut pads = Gamepads::default();
+        for ev in events.iter().filter(|e| e.id == pad.id()) {
+            pads.apply(ev);
+        }
+        let state = pads.get(pad.id()).unwrap();
+        assert!(state.is_virtual && state.is_down(GamepadButton::South));
+        assert_eq!(state.axis(GamepadAxis::LeftY), 0.5);
+        assert!(pads.rumble(pad.id(), 1.0, 0.0, 50));
+        assert!(pads.take_rumble_requests().is_empty());
+        assert!(pads.get(pad.id()).unwrap().last_rumble.is_some());
+
+        assert_eq!(
+            GamepadButton::from_name("dpad-up"),
+            Some(GamepadButton::DPadUp)
+        );
+        assert_eq!(
+            GamepadButton::from_name("R2"),
+            Some(GamepadButton::RightTrigger)
+        );
+        assert_eq!(GamepadButton::from_name("turbo"), None);
+        assert!(VirtualGamepad::from_id(0).is_none());
+    }
+}
@@ -0,0 +1,202 @@
+//! Desktop [`GamepadSource`] backed by gilrs (XInput / evdev / IOKit), including force feedback.
+
+use crate::engine::gamepad::{
+    GamepadAxis, GamepadButton, GamepadEvent, GamepadEventKind, GamepadSource, RumbleRequest,
+};
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Repeat, Replay, Ticks};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+
+pub struct GilrsSource {
+    gilrs: Gilrs,
+    /// Pads already connected at startup, reported on the first poll.
+    initial: Vec<GamepadEvent>,
+    /// Playing effects by pad id; dropping an `Effect` stops it.
+    effects: Vec<(u32, Effect)>,
+}
+
+impl GilrsSource {
+    /// `None` when the platform has no gamepad backend (or it failed to start).
+    pub fn new() -> Option<Self> {
+        let gilrs = match Gilrs::new() {
+            Ok(g) => g,
+            Err(gilrs::Error::NotImplemented(_)) => return None,
+            Err(e) => {
+                eprintln!("⚠️  gamepad input unavailable: {}", e);
+                return None;
+            }
+        };
+        let initial = gilrs
+            .gamepads()
+            .map(|(id, pad)| connected_event(id, pad.name(), pad.is_ff_supported()))
+            .collect();
+        Some(Self {
+            gilrs,
+            initial,
+            effects: Vec::new(),
+        })
+    }
+
+    fn push_dpad_axis(
+        out: &mut Vec<GamepadEvent>,
+        id: u32,
+        value: f32,
+        negative: GamepadButton,
+        positive: GamepadButton,
+    ) {
+        let held = |on: bool| if on { 1.0 } else { 0.0 };
+        out.push(GamepadEvent::new(
+            id,
+            GamepadEventKind::ButtonChanged(negative, held(value < -0.5)),
+        ));
+        out.push(GamepadEvent::new(
+            id,
+            GamepadEventKind::ButtonChanged(positive, held(value > 0.5)),
+        ));
+    }
+}
+
+impl GamepadSource for GilrsSource {
+    fn poll(&mut self, out: &mut Vec<GamepadEvent>) {
+        out.append(&mut self.initial);
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let pad = pad_id(id);
+            match event {
+                EventType::Connected => {
+                    let gp = self.gilrs.gamepad(id);
+                    out.push(connected_event(id, gp.name(), gp.is_ff_supported()));
+                }
+                EventType::Disconnected => {
+                    self.effects.retain(|(i, _)| *i != pad);
+                    out.push(GamepadEvent::new(pad, GamepadEventKind::Disconnected));
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    if let Some(b) = map_button(button) {
+                        out.push(GamepadEvent::new(
+                            pad,
+                            GamepadEventKind::ButtonChanged(b, value),
+                        ));
+                    }
+                }
+                EventType::AxisChanged(axis, value, _) => match axis {
+                    // gilrs sticks are +y up; the engine is +y down like the frame.
+                    Axis::LeftStickX => out.push(axis_event(pad, GamepadAxis::LeftX, value)),
+                    Axis::LeftStickY => out.push(axis_event(pad, GamepadAxis::LeftY, -value)),
+                    Axis::RightStickX => out.push(axis_event(pad, GamepadAxis::RightX, value)),
+                    Axis::RightStickY => out.push(axis_event(pad, GamepadAxis::RightY, -value)),
+                    // Hat-style d-pads report as axes on some drivers.
+                    Axis::DPadX => Self::push_dpad_axis(
+                        out,
+                        pad,
+                        value,
+                        GamepadButton::DPadLeft,
+                        GamepadButton::DPadRight,
+                    ),
+                    Axis::DPadY => Self::push_dpad_axis(
+                        out,
+                        pad,
+                        -value,
+                        GamepadButton::DPadUp,
+                        GamepadButton::DPadDown,
+                    ),
+                    _ => {}
+                },
+                EventType::ForceFeedbackEffectCompleted => {
+                    self.effects.retain(|(i, _)| *i != pad);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn rumble(&mut self, request: &RumbleRequest) {
+        self.effects.retain(|(i, _)| *i != request.id);
+        let Some(id) = self
+            .gilrs
+            .gamepads()
+            .map(|(id, _)| id)
+            .find(|id| pad_id(*id) == request.id)
+        else {
+            return;
+        };
+        let duration = Ticks::from_ms(request.duration_ms.max(1));
+        let scheduling = Replay {
+            play_for: duration,
+            ..Default::default()
+        };
+        let mut builder = EffectBuilder::new();
+        let mut any = false;
+        for (kind, level) in [
+            (BaseEffectType::Strong { magnitude: 0 }, request.strong),
+            (BaseEffectType::Weak { magnitude: 0 }, request.weak),
+        ] {
+            let magnitude = (level.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+            if magnitude == 0 {
+                continue;
+            }
+            let kind = match kind {
+                BaseEffectType::Strong { .. } => BaseEffectType::Strong { magnitude },
+                _ => BaseEffectType::Weak { magnitude },
+            };
+            builder.add_effect(BaseEffect {
+                kind,
+                scheduling,
+                ..Default::default()
+            });
+            any = true;
+        }
+        if !any {
+            return;
+        }
+        builder.gamepads(&[id]).repeat(Repeat::For(duration));
+        match builder.finish(&mut self.gilrs) {
+            Ok(effect) => {
+                if effect.play().is_ok() {
+                    self.effects.push((request.id, effect));
+                }
+            }
+            Err(e) => eprintln!("⚠️  gamepad rumble failed: {}", e),
+        }
+    }
+}
+
+fn pad_id(id: GamepadId) -> u32 {
+    usize::from(id) as u32
+}
+
+fn connected_event(id: GamepadId, name: &str, supports_rumble: bool) -> GamepadEvent {
+    GamepadEvent::new(
+        pad_id(id),
+        GamepadEventKind::Connected {
+            name: name.to_string(),
+            is_virtual: false,
+            supports_rumble,
+        },
+    )
+}
+
+fn axis_event(id: u32, axis: GamepadAxis, value: f32) -> GamepadEvent {
+    GamepadEvent::new(id, GamepadEventKind::AxisChanged(axis, value))
+}
+
+fn map_button(button: Button) -> Option<GamepadButton> {
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::West => GamepadButton::West,
+        Button::North => GamepadButton::North,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftStick,
+        Button::RightThumb => GamepadButton::RightStick,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
@@ -0,0 +1,354 @@
+//! Golden-image comparison: check a rendered RGBA frame against a stored PNG with a perceptual
+//! tolerance, writing `actual` and `diff` images next to the goldens when they disagree.
+//!
+//! Colour distance is measured in YIQ space (as in `pixelmatch`), so small anti-aliasing or
+//! rounding shifts pass while visible changes in shape or colour fail. Alpha is ignored: frames
+//! are composited onto opaque black before comparing.
+
+use std::path::{Path, PathBuf};
+
+/// Set to `1` to rewrite goldens from the current output instead of comparing.
+pub const UPDATE_GOLDENS_ENV: &str = "XOS_UPDATE_GOLDENS";
+
+/// Largest possible squared YIQ distance (normalizes deltas to `0..1`, as in `pixelmatch`).
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Per-pixel colour threshold `0..1`; `0.1` ignores anti-aliasing noise, `0.0` is exact.
+    pub threshold: f32,
+    /// Fraction of pixels allowed to exceed `threshold` (`0.0` = none).
+    pub max_diff_ratio: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            threshold: 0.1,
+            max_diff_ratio: 0.0,
+        }
+    }
+}
+
+impl Tolerance {
+    pub fn exact() -> Self {
+        Self {
+            threshold: 0.0,
+            max_diff_ratio: 0.0,
+        }
+    }
+}
+
+/// RGBA8 image owned by the comparison (frames are copied out of [`FrameState`](crate::engine::FrameState)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, String> {
+        if pixels.len() != (width as usize) * (height as usize) * 4 {
+            return Err(format!(
+                "{}x{} image needs {} bytes, got {}",
+                width,
+                height,
+                width as usize * height as usize * 4,
+                pixels.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn load_png(path: &Path) -> Result<Self, String> {
+        let img = image::open(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?
+            .to_rgba8();
+        let (width, height) = img.dimensions();
+        Self::new(width, height, img.into_raw())
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+        }
+        crate::engine::capture::write_png(path, &self.pixels, self.width, self.height)
+    }
+}
+
+/// Result of [`compare`].
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    /// Largest per-pixel distance seen, `0..1` on the same scale as [`Tolerance::threshold`].
+    pub max_delta: f32,
+    /// Faded grayscale of `expected` with differing pixels in red (empty when sizes differ).
+    pub diff: Option<RgbaImage>,
+    pub size_mismatch: bool,
+}
+
+impl Comparison {
+    pub fn passed(&self, tolerance: &Tolerance) -> bool {
+        !self.size_mismatch
+            && self.differing_pixels as f32 <= tolerance.max_diff_ratio * self.total_pixels as f32
+    }
+}
+
+#[inline]
+fn opaque_rgb(px: &[u8]) -> (f32, f32, f32) {
+    let a = px[3] as f32 / 255.0;
+    (px[0] as f32 * a, px[1] as f32 * a, px[2] as f32 * a)
+}
+
+#[inline]
+fn yiq(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    (
+        r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_23,
+        r * 0.595_977_7 - g * 0.274_176_1 - b * 0.321_801_6,
+        r * 0.211_470_2 - g * 0.522_617_5 + b * 0.311_147_3,
+    )
+}
+
+/// Perceptual distance between two pixels, `0` (same) to `1` (black vs. white is ~0.97).
+pub fn pixel_delta(a: &[u8], b: &[u8]) -> f32 {
+    let (ar, ag, ab) = opaque_rgb(a);
+    let (br, bg, bb) = opaque_rgb(b);
+    let (y1, i1, q1) = yiq(ar, ag, ab);
+    let (y2, i2, q2) = yiq(br, bg, bb);
+    let (dy, di, dq) = (y1 - y2, i1 - i2, q1 - q2);
+    let delta = 0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq;
+    (delta / MAX_YIQ_DELTA).sqrt().min(1.0)
+}
+
+pub fn compare(expected: &RgbaImage, actual: &RgbaImage, tolerance: &Tolerance) -> Comparison {
+    let total_pixels = (expected.width as usize) * (expected.height as usize);
+    if expected.width != actual.width || expected.height != actual.height {
+        return Comparison {
+            differing_pixels: total_pixels,
+            total_pixels,
+            max_delta: 1.0,
+            diff: None,
+            size_mismatch: true,
+        };
+    }
+    let mut differing_pixels = 0;
+    let mut max_delta = 0.0_f32;
+    let mut diff = Vec::with_capacity(expected.pixels.len());
+    for (e, a) in expected
+        .pixels
+        .chunks_exact(4)
+        .zip(actual.pixels.chunks_exact(4))
+    {
+        let delta = pixel_delta(e, a);
+        max_delta = max_delta.max(delta);
+        if delta > tolerance.threshold {
+            differing_pixels += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let (r, g, b) = opaque_rgb(e);
+            let luma = yiq(r, g, b).0;
+            let faded = (255.0 - 0.1 * (255.0 - luma)).clamp(0.0, 255.0) as u8;
+            diff.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+    Comparison {
+        differing_pixels,
+        total_pixels,
+        max_delta,
+        diff: Some(RgbaImage {
+            width: expected.width,
+            height: expected.height,
+            pixels: diff,
+        }),
+        size_mismatch: false,
+    }
+}
+
+/// What [`GoldenStore::check`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldenOutcome {
+    Matched,
+    /// No golden existed (or updating was requested); the current output was saved as the golden.
+    Written(PathBuf),
+}
+
+/// A directory of `<name>.png` goldens. Mismatches write `<name>.actual.png` and
+/// `<name>.diff.png` into `failures/` under it.
+#[derive(Debug, Clone)]
+pub struct GoldenStore {
+    pub dir: PathBuf,
+    pub tolerance: Tolerance,
+    /// Overwrite goldens instead of comparing (defaults to [`UPDATE_GOLDENS_ENV`]).
+    pub update: bool,
+    /// Save a missing golden from this run instead of failing (off when `CI` is set, where a
+    /// missing golden means it wasn't committed).
+    pub create_missing: bool,
+}
+
+impl GoldenStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tolerance: Tolerance::default(),
+            update: std::env::var(UPDATE_GOLDENS_ENV).is_ok_and(|v| v != "0" && !v.is_empty()),
+            create_missing: std::env::var_os("CI").is_none(),
+        }
+    }
+
+    pub fn golden_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.png"))
+    }
+
+    pub fn failure_paths(&self, name: &str) -> (PathBuf, PathBuf) {
+        let failures = self.dir.join("failures");
+        (
+            failures.join(format!("{name}.actual.png")),
+            failures.join(format!("{name}.diff.png")),
+        )
+    }
+
+    /// Compare `actual` with the golden called `name`, creating it when missing.
+    pub fn check(&self, name: &str, actual: &RgbaImage) -> Result<GoldenOutcome, String> {
+        self.check_with(name, actual, &self.tolerance)
+    }
+
+    pub fn check_with(
+        &self,
+        name: &str,
+        actual: &RgbaImage,
+        tolerance: &Tolerance,
+    ) -> Result<GoldenOutcome, String> {
+        let golden = self.golden_path(name);
+        let (actual_path, diff_path) = self.failure_paths(name);
+        if !self.update && !self.create_missing && !golden.exists() {
+            return Err(format!(
+                "'{}': no golden at {} (run locally to create it)",
+                name,
+                golden.display()
+            ));
+        }
+        if self.update || !golden.exists() {
+            actual.save_png(&golden)?;
+            let _ = std::fs::remove_file(&actual_path);
+            let _ = std::fs::remove_file(&diff_path);
+            return Ok(GoldenOutcome::Written(golden));
+        }
+        let expected = RgbaImage::load_png(&golden)?;
+        let result = compare(&expected, actual, tolerance);
+        if result.passed(tolerance) {
+            let _ = std::fs::remove_file(&actual_path);
+            let _ = std::fs::remove_file(&diff_path);
+            return Ok(GoldenOutcome::Matched);
+        }
+        actual.save_png(&actual_path)?;
+        if result.size_mismatch {
+            return Err(format!(
+                "'{}': size {}x{} differs from golden {}x{} (actual: {})",
+                name,
+                actual.width,
+                actual.height,
+                expected.width,
+                expected.height,
+                actual_path.display()
+            ));
+        }
+        if let Some(diff) = &result.diff {
+            diff.save_png(&diff_path)?;
+        }
+        Err(format!(
+            "'{}': {} of {} pixels differ (max delta {:.3}, threshold {:.3}); see {} and {}",
+            name,
+            result.differing_pixels,
+            result.total_pixels,
+            result.max_delta,
+            tolerance.threshold,
+            actual_path.display(),
+            diff_path.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(w: u32, h: u32, rgba: [u8; 4]) -> RgbaImage {
+        RgbaImage::new(w, h, rgba.repeat((w * h) as usize)).unwrap()
+    }
+
+    #[test]
+    fn identical_images_match_exactly() {
+        let a = solid(4, 3, [10, 200, 30, 255]);
+        let result = compare(&a, &a.clone(), &Tolerance::exact());
+        assert_eq!(result.differing_pixels, 0);
+        assert_eq!(result.max_delta, 0.0);
+        assert!(result.passed(&Tolerance::exact()));
+    }
+
+    #[test]
+    fn small_shifts_pass_and_visible_changes_fail() {
+        let a = solid(2, 2, [120, 120, 120, 255]);
+        let mut b = a.clone();
+        b.pixels[0] = 123;
+        assert!(compare(&a, &b, &Tolerance::default()).passed(&Tolerance::default()));
+        b.pixels[4..8].copy_from_slice(&[255, 0, 0, 255]);
+        let result = compare(&a, &b, &Tolerance::default());
+        assert_eq!(result.differing_pixels, 1);
+        assert!(!result.passed(&Tolerance::default()));
+        let diff = result.diff.unwrap();
+        assert_eq!(&diff.pixels[4..8], &[255, 0, 0, 255]);
+        let loose = Tolerance {
+            max_diff_ratio: 0.25,
+            ..Tolerance::default()
+        };
+        assert!(compare(&a, &b, &loose).passed(&loose));
+    }
+
+    #[test]
+    fn black_and_white_are_far_apart() {
+        let d = pixel_delta(&[0, 0, 0, 255], &[255, 255, 255, 255]);
+        assert!(d > 0.95 && d <= 1.0, "{d}");
+        // Transparent pixels count as black.
+        assert_eq!(pixel_delta(&[255, 255, 255, 0], &[0, 0, 0, 255]), 0.0);
+    }
+
+    #[test]
+    fn size_mismatch_fails() {
+        let result = compare(
+            &solid(2, 2, [0, 0, 0, 255]),
+            &solid(3, 2, [0, 0, 0, 255]),
+            &Tolerance::default(),
+        );
+        assert!(result.size_mismatch);
+        assert!(!result.passed(&Tolerance::default()));
+    }
+
+    #[test]
+    fn store_writes_missing_goldens_then_reports_diffs() {
+        let dir = std::env::temp_dir().join(format!("xos-golden-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut store = GoldenStore::new(&dir);
+        store.update = false;
+        store.create_missing = true;
+        let a = solid(3, 3, [0, 0, 255, 255]);
+        assert!(matches!(
+            store.check("blue", &a),
+            Ok(GoldenOutcome::Written(_))
+        ));
+        assert_eq!(store.check("blue", &a), Ok(GoldenOutcome::Matched));
+        let err = store
+            .check("blue", &solid(3, 3, [255, 255, 0, 255]))
+            .unwrap_err();
+        assert!(err.contains("9 of 9 pixels differ"), "{err}");
+        let (actual, diff) = store.failure_paths("blue");
+        assert!(actual.exists() && diff.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
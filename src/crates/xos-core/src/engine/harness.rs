@@ -0,0 +1,341 @@
+//! Offscreen test harness: run any [`Application`] against a headless [`EngineState`], drive it
+//! with scripted input through the same routing the hosts use, advance a fixed frame time, and
+//! compare frames with goldens ([`crate::engine::golden`]).
+//!
+//! ```ignore
+//! let mut h = AppHarness::new(Box::new(MyApp::default()), 320, 240)?;
+//! let goldens = GoldenStore::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"));
+//! h.frames(2);
+//! h.click(40.0, 30.0);
+//! h.frames(1);
+//! h.expect(&goldens, "after_click")?;
+//! ```
+
+use crate::engine::engine::{
+    advance_app_frame_by, poll_gamepads, route_key_event, route_pointer_event, Application,
+    EngineState, ScrollWheelUnit,
+};
+use crate::engine::golden::{GoldenOutcome, GoldenStore, RgbaImage, Tolerance};
+use crate::engine::keyboard::keys::{normalize_key_name, KeyEvent};
+use crate::engine::keyboard::shortcuts::{NamedSpecialKey, SpecialKeyEvent};
+use crate::engine::pointer::{PointerButton, PointerEvent, PointerPhase};
+use crate::engine::profiler;
+use crate::engine::windows::discard_window_requests;
+
+/// Simulated time per harness frame.
+pub const DEFAULT_FRAME_SECONDS: f32 = 1.0 / 60.0;
+
+/// One scripted action for [`AppHarness::run`] (used by `xos test` for Python apps).
+#[derive(Debug, Clone, PartialEq)]
+pub enum HarnessStep {
+    Frames(u32),
+    MouseMove {
+        x: f32,
+        y: f32,
+    },
+    MouseDown,
+    MouseUp,
+    Click {
+        x: f32,
+        y: f32,
+    },
+    Scroll {
+        dx: f32,
+        dy: f32,
+    },
+    /// Press and release a key by name (`"enter"`, `"left"`, `"a"`, …).
+    Key(String),
+    /// Type text one character at a time.
+    Type(String),
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    /// Compare the current frame with the golden called `name`.
+    Expect {
+        name: String,
+        tolerance: Option<Tolerance>,
+    },
+}
+
+/// Outcome of one [`HarnessStep::Expect`].
+#[derive(Debug, Clone)]
+pub struct ExpectResult {
+    pub name: String,
+    pub outcome: Result<GoldenOutcome, String>,
+}
+
+pub struct AppHarness<A: Application + ?Sized = dyn Application> {
+    app: Box<A>,
+    pub state: EngineState,
+    /// Time advanced by each [`AppHarness::frame`]; wall-clock time is never used.
+    pub frame_seconds: f32,
+}
+
+impl<A: Application + ?Sized> AppHarness<A> {
+    /// Headless `width`×`height` state; runs `setup` immediately.
+    pub fn new(app: Box<A>, width: u32, height: u32) -> Result<Self, String> {
+        let mut harness = Self::without_setup(app, width, height);
+        harness.setup()?;
+        Ok(harness)
+    }
+
+    /// Like [`AppHarness::new`] but leaves calling [`AppHarness::setup`] to the caller, so the app
+    /// can still be recovered with [`AppHarness::into_app`] when setup fails.
+    pub fn without_setup(app: Box<A>, width: u32, height: u32) -> Self {
+        Self {
+            app,
+            state: EngineState::headless(width, height),
+            frame_seconds: DEFAULT_FRAME_SECONDS,
+        }
+    }
+
+    pub fn setup(&mut self) -> Result<(), String> {
+        self.app.setup(&mut self.state)
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        self.app.as_mut()
+    }
+
+    pub fn into_app(self) -> Box<A> {
+        discard_window_requests();
+        self.app
+    }
+
+    /// One host frame: gamepads, the frame's ticks, `render`, then any running capture. Requests for
+    /// extra windows and window changes (`xos.window`) are dropped.
+    pub fn frame(&mut self) {
+        profiler::frame_boundary();
+        poll_gamepads(self.app.as_mut(), &mut self.state, None);
+        advance_app_frame_by(self.app.as_mut(), &mut self.state, self.frame_seconds);
+        self.app.render(&mut self.state);
+        self.state.capture.on_frame(&mut self.state.frame);
+        self.state.window.discard_commands();
+        discard_window_requests();
+    }
+
+    pub fn frames(&mut self, count: u32) {
+        for _ in 0..count {
+            self.frame();
+        }
+    }
+
+    fn route_mouse(&mut self, phase: PointerPhase, button: Option<PointerButton>) {
+        let event = PointerEvent::mouse(phase, self.state.mouse.x, self.state.mouse.y, button);
+        route_pointer_event(self.app.as_mut(), &mut self.state, event);
+    }
+
+    pub fn mouse_move(&mut self, x: f32, y: f32) {
+        let mouse = &mut self.state.mouse;
+        mouse.dx = x - mouse.x;
+        mouse.dy = y - mouse.y;
+        mouse.x = x;
+        mouse.y = y;
+        self.app.on_mouse_move(&mut self.state);
+        self.route_mouse(PointerPhase::Move, None);
+    }
+
+    pub fn mouse_down(&mut self) {
+        self.state.mouse.is_left_clicking = true;
+        self.app.on_mouse_down(&mut self.state);
+        self.route_mouse(PointerPhase::Down, Some(PointerButton::Left));
+    }
+
+    pub fn mouse_up(&mut self) {
+        self.state.mouse.is_left_clicking = false;
+        self.app.on_mouse_up(&mut self.state);
+        self.route_mouse(PointerPhase::Up, Some(PointerButton::Left));
+    }
+
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.mouse_move(x, y);
+        self.mouse_down();
+        self.mouse_up();
+    }
+
+    pub fn scroll(&mut self, dx: f32, dy: f32) {
+        self.app
+            .on_scroll(&mut self.state, dx, dy, ScrollWheelUnit::Pixel);
+    }
+
+    /// Press and release `name`; Enter / Tab / Backspace / Escape / arrows also go through
+    /// `on_special_key`, like the native host.
+    pub fn key(&mut self, name: &str) {
+        route_key_event(
+            self.app.as_mut(),
+            &mut self.state,
+            KeyEvent::down(name, "", false),
+        );
+        if let Some(named) = named_special_key(&normalize_key_name(name)) {
+            let modifiers = self.state.keyboard.modifiers;
+            self.app.on_special_key(
+                &mut self.state,
+                SpecialKeyEvent {
+                    named_key: Some(named),
+                    physical_key: None,
+                    character: None,
+                    command_held: modifiers.command,
+                    shift_held: modifiers.shift,
+                    alt_held: modifiers.alt,
+                },
+            );
+        }
+        route_key_event(self.app.as_mut(), &mut self.state, KeyEvent::up(name, ""));
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            match ch {
+                '\n' => self.key("enter"),
+                '\t' => self.key("tab"),
+                _ => {
+                    let key = ch.to_string();
+                    route_key_event(
+                        self.app.as_mut(),
+                        &mut self.state,
+                        KeyEvent::down(&key, "", false),
+                    );
+                    self.app.on_key_char(&mut self.state, ch);
+                    route_key_event(self.app.as_mut(), &mut self.state, KeyEvent::up(&key, ""));
+                }
+            }
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        self.state.resize_frame(width, height);
+        self.app
+            .on_screen_size_change(&mut self.state, width, height);
+    }
+
+    /// Copy of the current frame.
+    pub fn snapshot(&mut self) -> RgbaImage {
+        let shape = self.state.frame.shape();
+        let (width, height) = (shape[1] as u32, shape[0] as u32);
+        let pixels = self.state.frame.buffer_mut().to_vec();
+        RgbaImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn expect(&mut self, goldens: &GoldenStore, name: &str) -> Result<GoldenOutcome, String> {
+        let image = self.snapshot();
+        goldens.check(name, &image)
+    }
+
+    /// Apply `steps` in order; every `Expect` is checked (later steps still run after a mismatch).
+    pub fn run(&mut self, steps: &[HarnessStep], goldens: &GoldenStore) -> Vec<ExpectResult> {
+        let mut results = Vec::new();
+        for step in steps {
+            match step {
+                HarnessStep::Frames(n) => self.frames(*n),
+                HarnessStep::MouseMove { x, y } => self.mouse_move(*x, *y),
+                HarnessStep::MouseDown => self.mouse_down(),
+                HarnessStep::MouseUp => self.mouse_up(),
+                HarnessStep::Click { x, y } => self.click(*x, *y),
+                HarnessStep::Scroll { dx, dy } => self.scroll(*dx, *dy),
+                HarnessStep::Key(name) => self.key(name),
+                HarnessStep::Type(text) => self.type_text(text),
+                HarnessStep::Resize { width, height } => self.resize(*width, *height),
+                HarnessStep::Expect { name, tolerance } => {
+                    let image = self.snapshot();
+                    let tolerance = tolerance.unwrap_or(goldens.tolerance);
+                    results.push(ExpectResult {
+                        name: name.clone(),
+                        outcome: goldens.check_with(name, &image, &tolerance),
+                    });
+                }
+            }
+        }
+        results
+    }
+}
+
+fn named_special_key(name: &str) -> Option<NamedSpecialKey> {
+    Some(match name {
+        "backspace" => NamedSpecialKey::Backspace,
+        "enter" => NamedSpecialKey::Enter,
+        "escape" => NamedSpecialKey::Escape,
+        "tab" => NamedSpecialKey::Tab,
+        "arrowleft" => NamedSpecialKey::ArrowLeft,
+        "arrowright" => NamedSpecialKey::ArrowRight,
+        "arrowup" => NamedSpecialKey::ArrowUp,
+        "arrowdown" => NamedSpecialKey::ArrowDown,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fills the frame grey, turning red once clicked.
+    struct Clicker {
+        clicks: u32,
+    }
+
+    impl Application for Clicker {
+        fn setup(&mut self, _state: &mut EngineState) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn tick(&mut self, state: &mut EngineState) {
+            let color = if self.clicks > 0 {
+                [220, 30, 30, 255]
+            } else {
+                [90, 90, 90, 255]
+            };
+            for px in state.frame.buffer_mut().chunks_exact_mut(4) {
+                px.copy_from_slice(&color);
+            }
+        }
+
+        fn on_mouse_down(&mut self, _state: &mut EngineState) {
+            self.clicks += 1;
+        }
+    }
+
+    #[test]
+    fn scripted_steps_drive_the_app_and_check_goldens() {
+        let dir = std::env::temp_dir().join(format!("xos-harness-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut goldens = GoldenStore::new(&dir);
+        goldens.update = false;
+        goldens.create_missing = true;
+
+        let mut h = AppHarness::new(Box::new(Clicker { clicks: 0 }), 8, 6).unwrap();
+        let steps = [
+            HarnessStep::Frames(2),
+            HarnessStep::Expect {
+                name: "idle".to_string(),
+                tolerance: None,
+            },
+            HarnessStep::Click { x: 3.0, y: 2.0 },
+            HarnessStep::Frames(1),
+            HarnessStep::Expect {
+                name: "clicked".to_string(),
+                tolerance: None,
+            },
+        ];
+        let results = h.run(&steps, &goldens);
+        assert!(results
+            .iter()
+            .all(|r| matches!(r.outcome, Ok(GoldenOutcome::Written(_)))));
+        assert_eq!((h.state.mouse.x, h.state.mouse.y), (3.0, 2.0));
+        assert!(!h.state.mouse.is_left_clicking);
+
+        // Same script again matches; expecting "idle" after the click does not.
+        let mut again = AppHarness::new(Box::new(Clicker { clicks: 0 }), 8, 6).unwrap();
+        let results = again.run(&steps, &goldens);
+        assert!(results
+            .iter()
+            .all(|r| r.outcome == Ok(GoldenOutcome::Matched)));
+        let err = again.expect(&goldens, "idle").unwrap_err();
+        assert!(err.contains("48 of 48 pixels differ"), "{err}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,199 @@
+//! Raw key press / release events and the held-keys table (`EngineState::keyboard.keys_down`).
+//!
+//! Hosts report both names for every transition:
+//! - `key`: logical key (layout-aware), normalized by [`normalize_key_name`] — `"w"`, `"space"`, `"arrowleft"`
+//! - `code`: physical key in W3C `KeyboardEvent.code` form — `"KeyW"`, `"Space"`, `"ShiftLeft"`
+//!
+//! [`KeysDown::is_down`] accepts either form, plus generic modifier names (`"shift"` matches both sides),
+//! so WASD-style movement works regardless of keyboard layout.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyEvent {
+    /// Normalized logical key name.
+    pub key: String,
+    /// Physical key code (`"KeyW"`); empty when the host has none.
+    pub code: String,
+    pub pressed: bool,
+    /// Auto-repeat while held (presses only). Set by [`KeysDown::apply`] if the host doesn't report it.
+    pub repeat: bool,
+}
+
+impl KeyEvent {
+    pub fn down(key: &str, code: &str, repeat: bool) -> Self {
+        Self {
+            key: normalize_key_name(key),
+            code: code.to_string(),
+            pressed: true,
+            repeat,
+        }
+    }
+
+    pub fn up(key: &str, code: &str) -> Self {
+        Self {
+            key: normalize_key_name(key),
+            code: code.to_string(),
+            pressed: false,
+            repeat: false,
+        }
+    }
+}
+
+/// Lowercase logical key name with common aliases folded (`" "` → `"space"`, `"esc"` → `"escape"`,
+/// `"left"` → `"arrowleft"`, `"cmd"` / `"super"` → `"meta"`, `"ctrl"` → `"control"`).
+pub fn normalize_key_name(name: &str) -> String {
+    if name == " " {
+        return "space".to_string();
+    }
+    let lower = name.trim().to_lowercase();
+    let folded = match lower.as_str() {
+        "spacebar" => "space",
+        "esc" => "escape",
+        "return" => "enter",
+        "del" => "delete",
+        "left" => "arrowleft",
+        "right" => "arrowright",
+        "up" => "arrowup",
+        "down" => "arrowdown",
+        "ctrl" => "control",
+        "option" => "alt",
+        "cmd" | "command" | "super" | "os" => "meta",
+        _ => return lower,
+    };
+    folded.to_string()
+}
+
+/// Logical name a physical code stands for on a US layout (`"KeyW"` → `"w"`, `"ShiftRight"` → `"shift"`).
+fn code_to_name(code: &str) -> String {
+    if let Some(letter) = code.strip_prefix("Key") {
+        return letter.to_lowercase();
+    }
+    if let Some(digit) = code.strip_prefix("Digit") {
+        return digit.to_string();
+    }
+    let side_less = code
+        .strip_suffix("Left")
+        .or_else(|| code.strip_suffix("Right"))
+        .filter(|base| !base.is_empty() && *base != "Arrow");
+    normalize_key_name(side_less.unwrap_or(code))
+}
+
+/// Keys currently held, as `(key, code)` pairs in press order.
+#[derive(Debug, Clone, Default)]
+pub struct KeysDown {
+    held: Vec<(String, String)>,
+}
+
+impl KeysDown {
+    /// Record a transition. Presses of an already-held key are marked `repeat`; returns `false` for a
+    /// release of a key that wasn't held (e.g. pressed before the window gained focus).
+    pub fn apply(&mut self, event: &mut KeyEvent) -> bool {
+        let pos = self.held.iter().position(|(k, c)| {
+            if event.code.is_empty() || c.is_empty() {
+                *k == event.key
+            } else {
+                *c == event.code
+            }
+        });
+        match (event.pressed, pos) {
+            (true, Some(_)) => event.repeat = true,
+            (true, None) => self.held.push((event.key.clone(), event.code.clone())),
+            (false, Some(i)) => {
+                self.held.remove(i);
+            }
+            (false, None) => return false,
+        }
+        true
+    }
+
+    /// `true` while a key matching `name` is held: logical (`"w"`, `"space"`, `"shift"`) or
+    /// physical (`"KeyW"`, `"ShiftLeft"`, case-insensitive).
+    pub fn is_down(&self, name: &str) -> bool {
+        let wanted = normalize_key_name(name);
+        self.held.iter().any(|(k, c)| {
+            *k == wanted
+                || (!c.is_empty()
+                    && (c.eq_ignore_ascii_case(name.trim()) || code_to_name(c) == wanted))
+        })
+    }
+
+    /// Held `(key, code)` pairs in press order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.held.iter().map(|(k, c)| (k.as_str(), c.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    /// Drop every held key (focus loss) and return the matching release events.
+    pub fn release_all(&mut self) -> Vec<KeyEvent> {
+        self.held
+            .drain(..)
+            .map(|(key, code)| KeyEvent {
+                key,
+                code,
+                pressed: false,
+                repeat: false,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn press_release_and_repeat() {
+        let mut keys = KeysDown::default();
+        let mut w = KeyEvent::down("W", "KeyW", false);
+        keys.apply(&mut w);
+        assert!(!w.repeat);
+        assert!(keys.is_down("w"));
+        assert!(keys.is_down("KeyW"));
+
+        let mut again = KeyEvent::down("w", "KeyW", false);
+        keys.apply(&mut again);
+        assert!(again.repeat);
+        assert_eq!(keys.len(), 1);
+
+        assert!(keys.apply(&mut KeyEvent::up("w", "KeyW")));
+        assert!(!keys.is_down("w"));
+        assert!(!keys.apply(&mut KeyEvent::up("w", "KeyW")));
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn physical_code_matches_across_layouts() {
+        let mut keys = KeysDown::default();
+        // AZERTY: the key in the QWERTY "W" spot types "z".
+        keys.apply(&mut KeyEvent::down("z", "KeyW", false));
+        keys.apply(&mut KeyEvent::down("Shift", "ShiftRight", false));
+        keys.apply(&mut KeyEvent::down(" ", "Space", false));
+        assert!(keys.is_down("w"));
+        assert!(keys.is_down("z"));
+        assert!(keys.is_down("shift"));
+        assert!(keys.is_down("space"));
+        assert!(!keys.is_down("arrowleft"));
+
+        let released = keys.release_all();
+        assert_eq!(released.len(), 3);
+        assert!(released.iter().all(|e| !e.pressed));
+        assert!(keys.is_empty());
+    }
+
+    #[test]
+    fn normalizes_aliases() {
+        assert_eq!(normalize_key_name("Esc"), "escape");
+        assert_eq!(normalize_key_name("ArrowLeft"), "arrowleft");
+        assert_eq!(normalize_key_name("cmd"), "meta");
+        assert_eq!(code_to_name("ArrowLeft"), "arrowleft");
+        assert_eq!(code_to_name("Digit7"), "7");
+    }
+}
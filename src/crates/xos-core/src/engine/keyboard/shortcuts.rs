@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// Keyboard shortcuts system for desktop platforms (macOS, Windows, Linux)
 ///
 /// Handles common text editing shortcuts like:
@@ -8,7 +10,7 @@
 /// - Undo: Cmd+Z (Mac) / Ctrl+Z (Windows/Linux)
 /// - Redo: Cmd+Shift+Z (Mac) / Ctrl+Y (Windows/Linux)
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ShortcutAction {
     Copy,
     Cut,
@@ -18,7 +20,7 @@ pub enum ShortcutAction {
     Redo,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NamedSpecialKey {
     Backspace,
     Enter,
@@ -30,7 +32,7 @@ pub enum NamedSpecialKey {
     ArrowDown,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PhysicalSpecialKey {
     Digit1,
     Digit2,
@@ -41,9 +43,10 @@ pub enum PhysicalSpecialKey {
     KeyF,
     KeyR,
     KeyT,
+    KeyO,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SpecialKeyEvent {
     pub named_key: Option<NamedSpecialKey>,
     pub physical_key: Option<PhysicalSpecialKey>,
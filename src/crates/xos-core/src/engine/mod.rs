@@ -5,8 +5,20 @@ pub mod engine;
 pub mod audio {
     pub use xos_audio::*;
 }
+pub mod capture;
+pub mod gamepad;
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+pub mod gamepad_gilrs;
+pub mod golden;
+pub mod harness;
 pub mod keyboard;
+pub mod pointer;
+pub mod profiler;
+pub mod replay;
 pub mod sensors;
+pub mod timestep;
+pub mod window_control;
+pub mod windows;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod native_engine;
@@ -31,14 +43,16 @@ pub use wasm_engine::run_web;
 pub use crate::py_engine::PyApplicationWrapper;
 
 pub use engine::{
-    apply_frame_view_zoom, f3_ui_scale_multiplier, frame_view_pan_by_pixels, frame_view_rect_norm,
-    tick_frame_delta, tick_frame_view_zoom, Application, CursorStyleSetter, EngineState,
-    FrameState, KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle,
-    ScrollWheelUnit, F3_UI_SCALE_DEFAULT_PERCENT, F3_UI_SCALE_MAX_PERCENT, F3_UI_SCALE_MIN_PERCENT,
+    advance_app_frame, advance_app_frame_by, apply_frame_view_zoom, cancel_all_pointers,
+    f3_ui_scale_multiplier, frame_view_pan_by_pixels, frame_view_rect_norm, poll_gamepads,
+    release_all_keys, route_key_event, route_pointer_event, step_app_tick, tick_frame_delta,
+    tick_frame_view_zoom, Application, CursorStyleSetter, EngineState, FrameState,
+    KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle, ScrollWheelUnit,
+    F3_UI_SCALE_DEFAULT_PERCENT, F3_UI_SCALE_MAX_PERCENT, F3_UI_SCALE_MIN_PERCENT,
     FRAME_VIEW_ZOOM_MAX, FRAME_VIEW_ZOOM_MIN,
 };
 pub use f3_menu::{
-    f3_menu_boost_interaction_fade, f3_menu_handle_frame_zoom_scroll, f3_menu_handle_mouse_down,
-    f3_menu_handle_mouse_move, f3_menu_handle_mouse_up, f3_menu_handle_zoom_scroll, tick_f3_menu,
-    F3Menu,
+    f3_menu_boost_interaction_fade, f3_menu_handle_frame_pinch, f3_menu_handle_frame_zoom_scroll,
+    f3_menu_handle_mouse_down, f3_menu_handle_mouse_move, f3_menu_handle_mouse_up,
+    f3_menu_handle_zoom_scroll, tick_f3_menu, F3Menu,
 };
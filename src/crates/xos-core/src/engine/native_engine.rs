@@ -1,6 +1,7 @@
 use super::engine::{
-    tick_frame_delta, Application, CursorStyle, CursorStyleSetter, EngineState, FrameState,
-    KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle,
+    advance_app_frame, cancel_all_pointers, poll_gamepads, release_all_keys, route_key_event,
+    route_pointer_event, step_app_tick, Application, CursorStyle, CursorStyleSetter, EngineState,
+    FrameState, KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle,
 };
 use super::{
     apply_frame_view_zoom, f3_menu_boost_interaction_fade, f3_menu_handle_frame_zoom_scroll,
@@ -8,14 +9,28 @@ use super::{
     f3_menu_handle_zoom_scroll, frame_view_pan_by_pixels, tick_f3_menu, tick_frame_view_zoom,
     F3Menu,
 };
+use crate::engine::gamepad::GamepadSource;
+use crate::engine::keyboard::keys::KeyEvent;
 use crate::engine::keyboard::shortcuts::{
     detect_shortcut, NamedSpecialKey, PhysicalSpecialKey, SpecialKeyEvent,
 };
+use crate::engine::pointer::{PointerButton, PointerEvent, PointerKind, PointerPhase};
+use crate::engine::profiler;
+use crate::engine::window_control::WindowCommand;
+use crate::engine::windows::{
+    discard_window_requests, mark_window_closed, take_window_requests, WindowHandle, WindowRequest,
+    WindowSpec,
+};
+use crate::monitors::MonitorDescriptor;
 use crate::rasterizer::RasterCache;
 use crate::time::Instant;
 #[cfg(not(target_arch = "wasm32"))]
 use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
 #[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
 use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(not(target_arch = "wasm32"))]
 use std::sync::{Arc, Mutex};
@@ -25,10 +40,14 @@ use winit::platform::windows::WindowExtWindows;
 use winit::{
     application::ApplicationHandler,
     dpi::{PhysicalPosition, PhysicalSize},
-    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    event::{
+        DeviceEvent, DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+    },
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
     keyboard::{Key, KeyCode, NamedKey, PhysicalKey},
-    window::{CursorIcon, Fullscreen, Window, WindowAttributes, WindowId, WindowLevel},
+    window::{
+        CursorGrabMode, CursorIcon, Fullscreen, Window, WindowAttributes, WindowId, WindowLevel,
+    },
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -90,6 +109,60 @@ fn window_attributes_windowed() -> WindowAttributes {
     Window::default_attributes().with_title("XOS Game")
 }
 
+/// Desktop monitors in `xos.system.monitors` order; falls back to winit's list where no monitor
+/// hooks are registered (e.g. Linux).
+#[cfg(not(target_arch = "wasm32"))]
+fn host_monitors(event_loop: &ActiveEventLoop) -> Vec<MonitorDescriptor> {
+    let registered = crate::monitors::system_monitors();
+    if !registered.is_empty() {
+        return registered;
+    }
+    let primary_pos = event_loop.primary_monitor().map(|m| m.position());
+    event_loop
+        .available_monitors()
+        .map(|m| {
+            let (pos, size) = (m.position(), m.size());
+            MonitorDescriptor {
+                native_width: size.width,
+                native_height: size.height,
+                origin_x: pos.x,
+                origin_y: pos.y,
+                refresh_rate_hz: m.refresh_rate_millihertz().unwrap_or(0) as f64 / 1000.0,
+                is_primary: primary_pos == Some(pos),
+                name: m.name().unwrap_or_default(),
+                native_id: String::new(),
+                stream_width: size.width,
+                stream_height: size.height,
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn window_attributes_for_spec(event_loop: &ActiveEventLoop, spec: &WindowSpec) -> WindowAttributes {
+    let monitors = host_monitors(event_loop);
+    let mut attrs = Window::default_attributes()
+        .with_title(spec.title.clone())
+        .with_inner_size(PhysicalSize::new(spec.width, spec.height))
+        .with_decorations(spec.decorations);
+    if spec.always_on_top {
+        attrs = attrs.with_window_level(WindowLevel::AlwaysOnTop);
+    }
+    if let Some((x, y)) = spec.origin(&monitors) {
+        attrs = attrs.with_position(PhysicalPosition::new(x, y));
+    }
+    if spec.fullscreen {
+        // Match the descriptor to winit's handle by origin; `None` means the current monitor.
+        let target = spec.monitor(&monitors).and_then(|m| {
+            event_loop
+                .available_monitors()
+                .find(|h| h.position() == PhysicalPosition::new(m.origin_x, m.origin_y))
+        });
+        attrs = attrs.with_fullscreen(Some(Fullscreen::Borderless(target)));
+    }
+    attrs
+}
+
 /// Windows often reports 0×0 when minimized. Keep the last non-zero size for buffers so the app
 /// keeps simulating and `pixels` / `EngineState` stay the same length (avoids copy_from_slice panic).
 #[cfg(not(target_arch = "wasm32"))]
@@ -122,6 +195,15 @@ struct AppState {
     paused_base_frame: Vec<u8>,
     paused_base_w: usize,
     paused_base_h: usize,
+    gamepad_source: Option<Box<dyn GamepadSource>>,
+    /// `None` for the main window; secondary windows close on their own instead of exiting.
+    handle: Option<WindowHandle>,
+    closed: bool,
+    /// winit reports one event per file; collected here and handed to the app once per frame.
+    hovered_files: Vec<PathBuf>,
+    dropped_files: Vec<PathBuf>,
+    /// The cursor lock fell back to `Confined` (Windows, X11): park the pointer mid-window each frame.
+    cursor_recenter: bool,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -133,6 +215,17 @@ impl AppState {
         self.engine_state.keyboard.modifiers.alt = self.alt_held;
     }
 
+    /// Mouse transition at the current cursor as a pointer event (id [`crate::engine::pointer::MOUSE_POINTER_ID`]).
+    fn route_mouse_pointer(&mut self, phase: PointerPhase, button: Option<PointerButton>) {
+        let event = PointerEvent::mouse(
+            phase,
+            self.engine_state.mouse.x,
+            self.engine_state.mouse.y,
+            button,
+        );
+        route_pointer_event(self.app.as_mut(), &mut self.engine_state, event);
+    }
+
     fn restore_paused_base_frame(&mut self) {
         if self.paused_base_frame.is_empty() || self.paused_base_w == 0 || self.paused_base_h == 0 {
             return;
@@ -171,23 +264,113 @@ impl AppState {
         }
     }
 
+    /// Apply this frame's [`WindowCommand`]s and refresh what the app reads back.
+    fn apply_window_commands(&mut self) {
+        let main_window = self.handle.is_none();
+        for command in self.engine_state.window.take_commands(main_window) {
+            let info = &mut self.engine_state.window.info;
+            match command {
+                WindowCommand::SetTitle(title) => {
+                    self.window.set_title(&title);
+                    info.title = title;
+                }
+                WindowCommand::SetSize { width, height } => {
+                    let _ = self
+                        .window
+                        .request_inner_size(PhysicalSize::new(width.max(1), height.max(1)));
+                }
+                WindowCommand::SetFullscreen(on) => {
+                    if on != self.window.fullscreen().is_some() {
+                        self.toggle_borderless_fullscreen();
+                    }
+                }
+                WindowCommand::SetAlwaysOnTop(on) => {
+                    self.window.set_window_level(if on {
+                        WindowLevel::AlwaysOnTop
+                    } else {
+                        WindowLevel::Normal
+                    });
+                    info.always_on_top = on;
+                }
+                WindowCommand::SetCursorVisible(visible) => info.cursor_visible = visible,
+                WindowCommand::SetCursorLocked(locked) => self.set_cursor_locked(locked),
+            }
+        }
+        let info = &mut self.engine_state.window.info;
+        info.fullscreen = self.window.fullscreen().is_some();
+        info.scale_factor = self.window.scale_factor();
+        if main_window {
+            self.engine_state.window.publish();
+        }
+
+        if self.cursor_recenter && self.engine_state.window.info.cursor_locked {
+            let center = PhysicalPosition::new(self.size.width / 2, self.size.height / 2);
+            let _ = self.window.set_cursor_position(center);
+        }
+    }
+
+    fn set_cursor_locked(&mut self, locked: bool) {
+        self.cursor_recenter = false;
+        let applied = if locked {
+            match self.window.set_cursor_grab(CursorGrabMode::Locked) {
+                Ok(()) => true,
+                Err(_) => {
+                    self.cursor_recenter = self
+                        .window
+                        .set_cursor_grab(CursorGrabMode::Confined)
+                        .is_ok();
+                    self.cursor_recenter
+                }
+            }
+        } else {
+            let _ = self.window.set_cursor_grab(CursorGrabMode::None);
+            false
+        };
+        if locked && !applied {
+            eprintln!("⚠️ cursor lock is not supported by this window system");
+        }
+        self.engine_state.window.info.cursor_locked = applied;
+    }
+
+    /// Raw device motion while the cursor is locked (`CursorMoved` stops or only reports the
+    /// recentering then).
+    fn on_locked_mouse_motion(&mut self, dx: f32, dy: f32) {
+        self.engine_state.window.add_mouse_motion(dx, dy);
+        self.engine_state.mouse.dx = dx;
+        self.engine_state.mouse.dy = dy;
+        self.app.on_mouse_move(&mut self.engine_state);
+    }
+
     fn render_pixels(&mut self) -> Result<(), pixels::Error> {
+        crate::profile_scope!("present");
         self.pixels.render_with(|encoder, render_target, context| {
-            crate::rasterizer::render_pending_gpu_passes(
-                &mut self.raster_cache,
-                encoder,
-                &context.device,
-                &context.queue,
-                &context.texture,
-                context.texture_extent,
-                context.texture_format,
-            );
+            {
+                crate::profile_scope!("gpu passes");
+                crate::rasterizer::render_pending_gpu_passes(
+                    &mut self.raster_cache,
+                    encoder,
+                    &context.device,
+                    &context.queue,
+                    &context.texture,
+                    context.texture_extent,
+                    context.texture_format,
+                );
+            }
             context.scaling_renderer.render(encoder, render_target);
             Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
         })
     }
 
+    fn render_app(&mut self) {
+        crate::profile_scope!("render");
+        self.app.render(&mut self.engine_state);
+    }
+
     fn tick_and_render_frame(&mut self) {
+        // One profiler frame per main-window frame; extra windows record into it.
+        if self.handle.is_none() {
+            profiler::frame_boundary();
+        }
         let expected_len = (self.size.width * self.size.height * 4) as usize;
         let mut mirror_ok = false;
         {
@@ -202,13 +385,23 @@ impl AppState {
             }
         }
 
+        poll_gamepads(
+            self.app.as_mut(),
+            &mut self.engine_state,
+            self.gamepad_source.as_deref_mut(),
+        );
+
         if self.engine_state.paused {
             if self.engine_state.pending_step_ticks > 0 {
                 self.engine_state.pending_step_ticks =
                     self.engine_state.pending_step_ticks.saturating_sub(1);
-                tick_frame_delta(&mut self.engine_state, &mut self.last_tick_instant);
-                let _ = self.app.tick(&mut self.engine_state);
+                step_app_tick(
+                    self.app.as_mut(),
+                    &mut self.engine_state,
+                    &mut self.last_tick_instant,
+                );
                 self.capture_paused_base_frame();
+                self.render_app();
             } else {
                 self.last_tick_instant = Some(Instant::now());
                 if self.paused_base_frame.is_empty() {
@@ -217,9 +410,24 @@ impl AppState {
                 self.restore_paused_base_frame();
             }
         } else {
-            tick_frame_delta(&mut self.engine_state, &mut self.last_tick_instant);
-            let _ = self.app.tick(&mut self.engine_state);
-            self.capture_paused_base_frame();
+            let steps = advance_app_frame(
+                self.app.as_mut(),
+                &mut self.engine_state,
+                &mut self.last_tick_instant,
+            );
+            if steps > 0 || self.paused_base_frame.is_empty() {
+                self.capture_paused_base_frame();
+            } else {
+                // Fixed timestep with no tick due: start from the last simulated frame.
+                self.restore_paused_base_frame();
+            }
+            self.render_app();
+        }
+        // Capture requests target the main window.
+        if self.handle.is_none() {
+            self.engine_state
+                .capture
+                .on_frame(&mut self.engine_state.frame);
         }
 
         tick_frame_view_zoom(&mut self.engine_state);
@@ -255,7 +463,10 @@ impl AppState {
             );
         }
 
-        tick_f3_menu(&mut self.engine_state);
+        {
+            crate::profile_scope!("f3 overlay");
+            tick_f3_menu(&mut self.engine_state);
+        }
 
         if mirror_ok {
             self.engine_state.frame.clear_pixels_mirror_buffer();
@@ -271,6 +482,29 @@ impl AppState {
     }
 }
 
+/// Raw down/up transition for [`Application::on_key_down`] / [`Application::on_key_up`]: named keys
+/// use their winit variant name (`"ArrowLeft"`), physical codes the `KeyCode` name (`"KeyW"`).
+#[cfg(not(target_arch = "wasm32"))]
+fn raw_key_event(event: &winit::event::KeyEvent) -> Option<KeyEvent> {
+    let key = match &event.logical_key {
+        Key::Character(s) => s.to_string(),
+        Key::Named(named) => format!("{named:?}"),
+        _ => String::new(),
+    };
+    let code = match event.physical_key {
+        PhysicalKey::Code(code) => format!("{code:?}"),
+        PhysicalKey::Unidentified(_) => String::new(),
+    };
+    if key.is_empty() && code.is_empty() {
+        return None;
+    }
+    Some(if event.state == ElementState::Pressed {
+        KeyEvent::down(&key, &code, event.repeat)
+    } else {
+        KeyEvent::up(&key, &code)
+    })
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl ApplicationHandler for AppState {
     fn resumed(&mut self, _event_loop: &ActiveEventLoop) {
@@ -291,6 +525,10 @@ impl ApplicationHandler for AppState {
         }
 
         match event {
+            WindowEvent::CloseRequested if self.handle.is_some() => {
+                self.app.prepare_shutdown(&mut self.engine_state);
+                self.closed = true;
+            }
             WindowEvent::CloseRequested => {
                 self.app.prepare_shutdown(&mut self.engine_state);
                 event_loop.exit();
@@ -369,9 +607,15 @@ impl ApplicationHandler for AppState {
 
                 self.engine_state.mouse.x = position.x as f32;
                 self.engine_state.mouse.y = position.y as f32;
+                if self.engine_state.window.info.cursor_locked {
+                    // Motion arrives through `device_event` while locked.
+                    return;
+                }
 
                 self.engine_state.mouse.dx = self.engine_state.mouse.x - prev_x;
                 self.engine_state.mouse.dy = self.engine_state.mouse.y - prev_y;
+                let (dx, dy) = (self.engine_state.mouse.dx, self.engine_state.mouse.dy);
+                self.engine_state.window.add_mouse_motion(dx, dy);
 
                 if self.frame_pan_dragging {
                     if !self.engine_state.mouse.is_left_clicking
@@ -400,6 +644,7 @@ impl ApplicationHandler for AppState {
 
                 if !f3_menu_handle_mouse_move(&mut self.engine_state) {
                     let _ = self.app.on_mouse_move(&mut self.engine_state);
+                    self.route_mouse_pointer(PointerPhase::Move, None);
                 }
                 if self.engine_state.paused {
                     self.window.request_redraw();
@@ -425,6 +670,7 @@ impl ApplicationHandler for AppState {
                     }
                     if !f3_menu_handle_mouse_down(&mut self.engine_state) {
                         let _ = self.app.on_mouse_down(&mut self.engine_state);
+                        self.route_mouse_pointer(PointerPhase::Down, Some(PointerButton::Left));
                     }
                     if self.engine_state.paused {
                         self.window.request_redraw();
@@ -442,6 +688,8 @@ impl ApplicationHandler for AppState {
                     if !f3_menu_handle_mouse_up(&mut self.engine_state) {
                         let _ = self.app.on_mouse_up(&mut self.engine_state);
                     }
+                    // Released even when F3 took the up: a stray release is dropped by the pointer table.
+                    self.route_mouse_pointer(PointerPhase::Up, Some(PointerButton::Left));
                     if self.engine_state.paused {
                         self.window.request_redraw();
                     }
@@ -454,11 +702,53 @@ impl ApplicationHandler for AppState {
             } => match button_state {
                 ElementState::Pressed => {
                     self.engine_state.mouse.is_right_clicking = true;
+                    self.route_mouse_pointer(PointerPhase::Down, Some(PointerButton::Right));
                 }
                 ElementState::Released => {
                     self.engine_state.mouse.is_right_clicking = false;
+                    self.route_mouse_pointer(PointerPhase::Up, Some(PointerButton::Right));
                 }
             },
+            WindowEvent::MouseInput {
+                state: button_state,
+                button,
+                ..
+            } => {
+                // Other buttons fall through so the shared post-event bookkeeping still runs.
+                let button = match button {
+                    MouseButton::Middle => Some(PointerButton::Middle),
+                    MouseButton::Back => Some(PointerButton::Back),
+                    MouseButton::Forward => Some(PointerButton::Forward),
+                    _ => None,
+                };
+                if let Some(button) = button {
+                    let phase = match button_state {
+                        ElementState::Pressed => PointerPhase::Down,
+                        ElementState::Released => PointerPhase::Up,
+                    };
+                    self.route_mouse_pointer(phase, Some(button));
+                }
+            }
+            WindowEvent::Touch(touch) => {
+                let phase = match touch.phase {
+                    TouchPhase::Started => PointerPhase::Down,
+                    TouchPhase::Moved => PointerPhase::Move,
+                    TouchPhase::Ended => PointerPhase::Up,
+                    TouchPhase::Cancelled => PointerPhase::Cancel,
+                };
+                let event = PointerEvent::contact(
+                    touch.id + 1,
+                    PointerKind::Touch,
+                    phase,
+                    touch.location.x as f32,
+                    touch.location.y as f32,
+                    touch.force.map(|f| f.normalized() as f32).unwrap_or(0.0),
+                );
+                route_pointer_event(self.app.as_mut(), &mut self.engine_state, event);
+                if self.engine_state.paused {
+                    self.window.request_redraw();
+                }
+            }
             WindowEvent::MouseWheel { delta, .. } => {
                 use crate::engine::ScrollWheelUnit;
                 let (dx, dy, unit) = match delta {
@@ -514,9 +804,34 @@ impl ApplicationHandler for AppState {
                     self.frame_pan_dragging = false;
                 }
             }
+            WindowEvent::HoveredFile(path) => {
+                self.hovered_files.push(path);
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.hovered_files.clear();
+                self.app.on_file_hover_cancel(&mut self.engine_state);
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.dropped_files.push(path);
+            }
+            WindowEvent::Focused(true) if self.engine_state.window.info.cursor_locked => {
+                // Some platforms drop the grab while unfocused.
+                self.set_cursor_locked(true);
+            }
+            WindowEvent::Focused(false) => {
+                release_all_keys(self.app.as_mut(), &mut self.engine_state);
+                cancel_all_pointers(self.app.as_mut(), &mut self.engine_state);
+            }
             WindowEvent::KeyboardInput { event, .. } => {
+                self.sync_modifiers_to_engine();
+                if let Some(raw) = raw_key_event(&event) {
+                    route_key_event(self.app.as_mut(), &mut self.engine_state, raw);
+                }
                 if event.state == ElementState::Pressed {
-                    self.sync_modifiers_to_engine();
+                    if matches!(event.logical_key, Key::Named(NamedKey::F3)) && self.shift_held {
+                        profiler::export_default_trace();
+                        return;
+                    }
                     if matches!(event.logical_key, Key::Named(NamedKey::F3)) {
                         self.engine_state.f3_menu.toggle_visible();
                         if self.engine_state.paused {
@@ -555,6 +870,7 @@ impl ApplicationHandler for AppState {
                         PhysicalKey::Code(KeyCode::KeyF) => Some(PhysicalSpecialKey::KeyF),
                         PhysicalKey::Code(KeyCode::KeyR) => Some(PhysicalSpecialKey::KeyR),
                         PhysicalKey::Code(KeyCode::KeyT) => Some(PhysicalSpecialKey::KeyT),
+                        PhysicalKey::Code(KeyCode::KeyO) => Some(PhysicalSpecialKey::KeyO),
                         _ => None,
                     };
 
@@ -667,10 +983,15 @@ impl ApplicationHandler for AppState {
                 CursorIcon::Grab
             });
         } else {
+            let window_info = &self.engine_state.window.info;
+            let hidden_by_app = !window_info.cursor_visible || window_info.cursor_locked;
             match self.engine_state.mouse.style.get() {
                 CursorStyle::Hidden => {
                     self.window.set_cursor_visible(false);
                 }
+                _ if hidden_by_app => {
+                    self.window.set_cursor_visible(false);
+                }
                 other => {
                     self.window.set_cursor_visible(true);
                     let icon = match other {
@@ -689,6 +1010,17 @@ impl ApplicationHandler for AppState {
             }
         }
 
+        if !self.hovered_files.is_empty() {
+            let paths = std::mem::take(&mut self.hovered_files);
+            self.app.on_file_hover(&mut self.engine_state, &paths);
+        }
+        if !self.dropped_files.is_empty() {
+            let paths = std::mem::take(&mut self.dropped_files);
+            self.app.on_file_drop(&mut self.engine_state, &paths);
+        }
+
+        self.apply_window_commands();
+
         // Stepping + GPU present directly here so frame delivery is not tied to sporadic compositor
         // `RedrawRequested` cadence (~30–60 Hz on many platforms despite vsync-disabled surfaces).
         if !self.engine_state.paused {
@@ -697,11 +1029,151 @@ impl ApplicationHandler for AppState {
     }
 }
 
+/// Open a window with its own `pixels` surface and [`EngineState`], then run `app.setup`.
+#[cfg(not(target_arch = "wasm32"))]
+fn create_app_state(
+    event_loop: &ActiveEventLoop,
+    attrs: WindowAttributes,
+    mut app: Box<dyn Application>,
+    handle: Option<WindowHandle>,
+) -> Result<AppState, String> {
+    let always_on_top = attrs.window_level == WindowLevel::AlwaysOnTop;
+    let window = event_loop
+        .create_window(attrs)
+        .map_err(|e| format!("Failed to create window: {}", e))?;
+    // Enable IME for text input
+    window.set_ime_allowed(true);
+
+    let size = window.inner_size();
+    let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
+    let pixels = match PixelsBuilder::new(size.width, size.height, surface_texture)
+        .enable_vsync(false)
+        .build()
+    {
+        Ok(p) => unsafe { std::mem::transmute(p) }, // SAFETY: window outlives pixels
+        Err(e) => return Err(format!("Failed to create pixels: {}", e)),
+    };
+
+    let safe_region = SafeRegionBoundingRectangle::full_screen();
+    let mut engine_state = EngineState {
+        frame: FrameState::new(size.width, size.height, safe_region),
+        mouse: MouseState {
+            x: 0.0,
+            y: 0.0,
+            dx: 0.0,
+            dy: 0.0,
+            is_left_clicking: false,
+            is_right_clicking: false,
+            style: CursorStyleSetter::new(),
+        },
+        pointers: crate::engine::pointer::PointerState::default(),
+        keyboard: KeyboardState {
+            onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard::new(),
+            modifiers: KeyboardModifiers::default(),
+            keys_down: crate::engine::keyboard::keys::KeysDown::default(),
+        },
+        gamepads: crate::engine::gamepad::Gamepads::default(),
+        f3_menu: F3Menu::new(),
+        ui_scale_percent: 100,
+        delta_time_seconds: 1.0 / 60.0,
+        paused: false,
+        pending_step_ticks: 0,
+        timestep: crate::engine::timestep::FixedTimestep::default(),
+        capture: crate::engine::capture::FrameCapture::default(),
+        window: crate::engine::window_control::WindowControl::default(),
+        frame_view_zoom: 1.0,
+        frame_view_zoom_target: 1.0,
+        frame_view_zoom_velocity: 0.0,
+        frame_view_center_x: 0.5,
+        frame_view_center_y: 0.5,
+        f3_fps_label_override: None,
+        embed_last_plain_click_screen: None,
+        embed_synthetic_click_screen: None,
+    };
+    engine_state.window.info.title = window.title();
+    engine_state.window.info.always_on_top = always_on_top;
+    engine_state.window.info.scale_factor = window.scale_factor();
+
+    app.setup(&mut engine_state)
+        .map_err(|e| format!("Failed to setup app: {}", e))?;
+
+    Ok(AppState {
+        window,
+        pixels,
+        engine_state,
+        app,
+        size,
+        raster_cache: RasterCache::new(),
+        last_tick_instant: None,
+        command_held: false,
+        shift_held: false,
+        alt_held: false,
+        frame_pan_dragging: false,
+        paused_base_frame: Vec::new(),
+        paused_base_w: 0,
+        paused_base_h: 0,
+        // Physical controllers feed the main window only.
+        gamepad_source: if handle.is_none() {
+            native_gamepad_source()
+        } else {
+            None
+        },
+        handle,
+        closed: false,
+        hovered_files: Vec::new(),
+        dropped_files: Vec::new(),
+        cursor_recenter: false,
+    })
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 struct AppStateWrapper {
     app_state: Option<AppState>,
     app: Box<dyn Application>,
     launch_mode: NativeLaunchMode,
+    /// Windows opened with [`crate::engine::windows::open_window`].
+    secondary: HashMap<WindowId, AppState>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AppStateWrapper {
+    /// Apply queued open / close requests and drop windows the user closed.
+    fn sync_secondary_windows(&mut self, event_loop: &ActiveEventLoop) {
+        self.secondary.retain(|_, st| {
+            if st.closed {
+                if let Some(handle) = st.handle {
+                    mark_window_closed(handle);
+                }
+            }
+            !st.closed
+        });
+        for request in take_window_requests() {
+            match request {
+                WindowRequest::Open { handle, spec, app } => {
+                    let attrs = window_attributes_for_spec(event_loop, &spec);
+                    match create_app_state(event_loop, attrs, app, Some(handle)) {
+                        Ok(st) => {
+                            st.window.request_redraw();
+                            self.secondary.insert(st.window.id(), st);
+                        }
+                        Err(e) => {
+                            eprintln!("{} ({})", e, spec.title);
+                            mark_window_closed(handle);
+                        }
+                    }
+                }
+                WindowRequest::Close(handle) => {
+                    self.secondary.retain(|_, st| {
+                        if st.handle != Some(handle) {
+                            return true;
+                        }
+                        st.app.prepare_shutdown(&mut st.engine_state);
+                        false
+                    });
+                }
+            }
+        }
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -715,94 +1187,22 @@ impl ApplicationHandler for AppStateWrapper {
                 NativeLaunchMode::Windowed => window_attributes_windowed(),
                 NativeLaunchMode::Overlay => overlay_window_attributes(event_loop),
             };
-            let window = match event_loop.create_window(attrs) {
-                Ok(w) => {
-                    // Enable IME for text input
-                    w.set_ime_allowed(true);
+            let app = std::mem::replace(&mut self.app, Box::new(crate::blank_app::BlankApp::new()));
+            match create_app_state(event_loop, attrs, app, None) {
+                Ok(st) => {
                     #[cfg(target_os = "windows")]
                     if matches!(self.launch_mode, NativeLaunchMode::Overlay) {
-                        w.set_skip_taskbar(true);
+                        st.window.set_skip_taskbar(true);
                     }
-                    w
+                    // Paint ASAP so the compositor gets a framebuffer right after startup.
+                    st.window.request_redraw();
+                    self.app_state = Some(st);
                 }
                 Err(e) => {
-                    eprintln!("Failed to create window: {}", e);
-                    return;
+                    eprintln!("{}", e);
+                    SHOULD_EXIT.store(true, Ordering::Relaxed);
+                    event_loop.exit();
                 }
-            };
-
-            let size = window.inner_size();
-            let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
-            let pixels = match PixelsBuilder::new(size.width, size.height, surface_texture)
-                .enable_vsync(false)
-                .build()
-            {
-                Ok(p) => unsafe { std::mem::transmute(p) }, // SAFETY: window outlives pixels
-                Err(e) => {
-                    eprintln!("Failed to create pixels: {}", e);
-                    return;
-                }
-            };
-
-            let safe_region = SafeRegionBoundingRectangle::full_screen();
-            let mut engine_state = EngineState {
-                frame: FrameState::new(size.width, size.height, safe_region),
-                mouse: MouseState {
-                    x: 0.0,
-                    y: 0.0,
-                    dx: 0.0,
-                    dy: 0.0,
-                    is_left_clicking: false,
-                    is_right_clicking: false,
-                    style: CursorStyleSetter::new(),
-                },
-                keyboard: KeyboardState {
-                    onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard::new(),
-                    modifiers: KeyboardModifiers::default(),
-                },
-                f3_menu: F3Menu::new(),
-                ui_scale_percent: 100,
-                delta_time_seconds: 1.0 / 60.0,
-                paused: false,
-                pending_step_ticks: 0,
-                frame_view_zoom: 1.0,
-                frame_view_zoom_target: 1.0,
-                frame_view_zoom_velocity: 0.0,
-                frame_view_center_x: 0.5,
-                frame_view_center_y: 0.5,
-                f3_fps_label_override: None,
-                embed_last_plain_click_screen: None,
-                embed_synthetic_click_screen: None,
-            };
-
-            if let Err(e) = self.app.setup(&mut engine_state) {
-                eprintln!("Failed to setup app: {}", e);
-                SHOULD_EXIT.store(true, Ordering::Relaxed);
-                event_loop.exit();
-                return;
-            }
-
-            let app =
-                std::mem::replace(&mut self.app, Box::new(crate::blank_app::BlankApp::new()));
-            self.app_state = Some(AppState {
-                window,
-                pixels,
-                engine_state,
-                app,
-                size,
-                raster_cache: RasterCache::new(),
-                last_tick_instant: None,
-                command_held: false,
-                shift_held: false,
-                alt_held: false,
-                frame_pan_dragging: false,
-                paused_base_frame: Vec::new(),
-                paused_base_w: 0,
-                paused_base_h: 0,
-            });
-            if let Some(st) = &self.app_state {
-                // Paint ASAP so the compositor gets a framebuffer right after startup.
-                st.window.request_redraw();
             }
         }
     }
@@ -813,11 +1213,31 @@ impl ApplicationHandler for AppStateWrapper {
         window_id: WindowId,
         event: WindowEvent,
     ) {
-        if let Some(ref mut app_state) = self.app_state {
+        if let Some(st) = self.secondary.get_mut(&window_id) {
+            st.window_event(event_loop, window_id, event);
+        } else if let Some(ref mut app_state) = self.app_state {
             app_state.window_event(event_loop, window_id, event);
         }
     }
 
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        if let DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+            let locked = self
+                .app_state
+                .iter_mut()
+                .chain(self.secondary.values_mut())
+                .filter(|st| st.engine_state.window.info.cursor_locked);
+            for st in locked {
+                st.on_locked_mouse_motion(dx as f32, dy as f32);
+            }
+        }
+    }
+
     fn user_event(&mut self, event_loop: &ActiveEventLoop, _event: ()) {
         if SHOULD_EXIT.load(Ordering::Relaxed) {
             if let Some(ref mut app_state) = self.app_state {
@@ -831,6 +1251,22 @@ impl ApplicationHandler for AppStateWrapper {
         if let Some(ref mut app_state) = self.app_state {
             app_state.about_to_wait(event_loop);
         }
+        for st in self.secondary.values_mut().filter(|st| !st.closed) {
+            st.about_to_wait(event_loop);
+        }
+        self.sync_secondary_windows(event_loop);
+    }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        for (_, mut st) in self.secondary.drain() {
+            if !st.closed {
+                st.app.prepare_shutdown(&mut st.engine_state);
+            }
+            if let Some(handle) = st.handle {
+                mark_window_closed(handle);
+            }
+        }
+        discard_window_requests();
     }
 }
 
@@ -861,6 +1297,7 @@ fn run_native_event_loop(
         app_state: None,
         app,
         launch_mode,
+        secondary: HashMap::new(),
     };
 
     event_loop.run_app(&mut wrapper)?;
@@ -871,6 +1308,21 @@ fn run_native_event_loop(
     Ok(())
 }
 
+/// Host controllers for the native loops (`None` on iOS, where only virtual pads exist, or when
+/// gilrs has no backend).
+#[cfg(not(target_arch = "wasm32"))]
+fn native_gamepad_source() -> Option<Box<dyn GamepadSource>> {
+    #[cfg(not(target_os = "ios"))]
+    {
+        crate::engine::gamepad_gilrs::GilrsSource::new()
+            .map(|source| Box::new(source) as Box<dyn GamepadSource>)
+    }
+    #[cfg(target_os = "ios")]
+    {
+        None
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn start_native(app: Box<dyn Application>) -> Result<(), Box<dyn std::error::Error>> {
     run_native_event_loop(app, NativeLaunchMode::Windowed)
@@ -891,58 +1343,40 @@ pub fn start_headless_native(
     })
     .map_err(|e| format!("Error setting Ctrl+C handler: {}", e))?;
 
-    let safe_region = SafeRegionBoundingRectangle::full_screen();
-    let mut engine_state = EngineState {
-        frame: FrameState::new(width.max(1), height.max(1), safe_region),
-        mouse: MouseState {
-            x: 0.0,
-            y: 0.0,
-            dx: 0.0,
-            dy: 0.0,
-            is_left_clicking: false,
-            is_right_clicking: false,
-            style: CursorStyleSetter::new(),
-        },
-        keyboard: KeyboardState {
-            onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard::new(),
-            modifiers: KeyboardModifiers::default(),
-        },
-        f3_menu: F3Menu::new(),
-        ui_scale_percent: 100,
-        delta_time_seconds: 1.0 / 60.0,
-        paused: false,
-        pending_step_ticks: 0,
-        frame_view_zoom: 1.0,
-        frame_view_zoom_target: 1.0,
-        frame_view_zoom_velocity: 0.0,
-        frame_view_center_x: 0.5,
-        frame_view_center_y: 0.5,
-        f3_fps_label_override: None,
-        embed_last_plain_click_screen: None,
-        embed_synthetic_click_screen: None,
-    };
+    let mut engine_state = EngineState::headless(width, height);
 
     if let Err(e) = app.setup(&mut engine_state) {
         return Err(format!("Failed to setup app: {}", e).into());
     }
 
+    let mut gamepad_source = native_gamepad_source();
     let mut last_tick_instant: Option<Instant> = None;
     while !SHOULD_EXIT.load(Ordering::Relaxed) {
+        profiler::frame_boundary();
+        poll_gamepads(
+            app.as_mut(),
+            &mut engine_state,
+            gamepad_source.as_deref_mut(),
+        );
         if engine_state.paused {
             if engine_state.pending_step_ticks > 0 {
                 engine_state.pending_step_ticks = engine_state.pending_step_ticks.saturating_sub(1);
-                tick_frame_delta(&mut engine_state, &mut last_tick_instant);
-                app.tick(&mut engine_state);
+                step_app_tick(app.as_mut(), &mut engine_state, &mut last_tick_instant);
+                app.render(&mut engine_state);
             } else {
                 std::thread::sleep(std::time::Duration::from_millis(16));
                 last_tick_instant = Some(Instant::now());
             }
         } else {
-            tick_frame_delta(&mut engine_state, &mut last_tick_instant);
-            app.tick(&mut engine_state);
+            advance_app_frame(app.as_mut(), &mut engine_state, &mut last_tick_instant);
+            app.render(&mut engine_state);
         }
+        engine_state.capture.on_frame(&mut engine_state.frame);
         tick_f3_menu(&mut engine_state);
+        engine_state.window.discard_commands();
+        discard_window_requests();
     }
+    engine_state.capture.stop();
     SHOULD_EXIT.store(false, Ordering::Relaxed);
     Ok(())
 }
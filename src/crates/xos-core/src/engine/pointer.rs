@@ -0,0 +1,496 @@
+//! Pointer events: concurrent mouse / touch / pen contacts with ids, pressure and buttons, plus
+//! two-finger pinch / rotate recognition.
+//!
+//! Hosts feed [`PointerEvent`]s through [`super::engine::route_pointer_event`], which keeps
+//! [`PointerState`] (`EngineState::pointers`) current and calls `Application::on_pointer_*` /
+//! `Application::on_gesture`. The single-cursor [`super::engine::MouseState`] callbacks keep firing
+//! for the primary pointer so existing apps are unaffected.
+
+use serde::{Deserialize, Serialize};
+
+/// Pointer id used for the (single) system mouse; touch / pen contacts use host ids offset past it.
+pub const MOUSE_POINTER_ID: u64 = 0;
+
+/// Minimum finger spread (px) before pinch scale / rotation are tracked (avoids jitter on near-taps).
+const MIN_GESTURE_SPAN: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+impl PointerKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            PointerKind::Mouse => "mouse",
+            PointerKind::Touch => "touch",
+            PointerKind::Pen => "pen",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointerButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+}
+
+impl PointerButton {
+    /// Bit in [`PointerEvent::buttons`] (same layout as DOM `PointerEvent.buttons`).
+    #[inline]
+    pub fn bit(self) -> u8 {
+        match self {
+            PointerButton::Left => 1,
+            PointerButton::Right => 2,
+            PointerButton::Middle => 4,
+            PointerButton::Back => 8,
+            PointerButton::Forward => 16,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PointerButton::Left => "left",
+            PointerButton::Right => "right",
+            PointerButton::Middle => "middle",
+            PointerButton::Back => "back",
+            PointerButton::Forward => "forward",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
+    /// Contact lost without a release (system gesture, focus loss); delivered as `on_pointer_up`.
+    Cancel,
+}
+
+/// One pointer transition in frame pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PointerEvent {
+    pub id: u64,
+    pub kind: PointerKind,
+    pub phase: PointerPhase,
+    pub x: f32,
+    pub y: f32,
+    /// `0..=1`; mice report `0.5` while a button is held and `0.0` otherwise (DOM convention).
+    pub pressure: f32,
+    /// Button that changed (down / up only).
+    pub button: Option<PointerButton>,
+    /// Held buttons after this event ([`PointerButton::bit`] mask). Filled in by [`PointerState::apply`].
+    pub buttons: u8,
+    /// First contact of its kind (the one mirrored into `MouseState`). Filled in by [`PointerState::apply`].
+    pub is_primary: bool,
+}
+
+impl PointerEvent {
+    pub fn mouse(phase: PointerPhase, x: f32, y: f32, button: Option<PointerButton>) -> Self {
+        Self {
+            id: MOUSE_POINTER_ID,
+            kind: PointerKind::Mouse,
+            phase,
+            x,
+            y,
+            pressure: 0.0,
+            button,
+            buttons: 0,
+            is_primary: true,
+        }
+    }
+
+    /// Touch or pen contact; `pressure` is normalized force (`0.0` when the host has none).
+    pub fn contact(
+        id: u64,
+        kind: PointerKind,
+        phase: PointerPhase,
+        x: f32,
+        y: f32,
+        pressure: f32,
+    ) -> Self {
+        Self {
+            id,
+            kind,
+            phase,
+            x,
+            y,
+            pressure: pressure.clamp(0.0, 1.0),
+            button: matches!(phase, PointerPhase::Down | PointerPhase::Up)
+                .then_some(PointerButton::Left),
+            buttons: 0,
+            is_primary: false,
+        }
+    }
+}
+
+/// A contact currently held down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pointer {
+    pub id: u64,
+    pub kind: PointerKind,
+    pub x: f32,
+    pub y: f32,
+    pub start_x: f32,
+    pub start_y: f32,
+    pub pressure: f32,
+    pub buttons: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GesturePhase {
+    Began,
+    Changed,
+    Ended,
+}
+
+/// Two-finger pinch / rotate / pan, in frame pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GestureEvent {
+    pub phase: GesturePhase,
+    pub center_x: f32,
+    pub center_y: f32,
+    /// Finger spread relative to the start of the gesture (`1.0` = unchanged).
+    pub scale: f32,
+    /// Rotation since the start of the gesture, radians (clockwise on screen is positive).
+    pub rotation: f32,
+    /// Scale factor since the previous gesture event.
+    pub delta_scale: f32,
+    /// Rotation since the previous gesture event, radians.
+    pub delta_rotation: f32,
+    /// Center movement since the previous gesture event.
+    pub pan_dx: f32,
+    pub pan_dy: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GestureTrack {
+    ids: (u64, u64),
+    start_span: f32,
+    start_angle: f32,
+    last_span: f32,
+    last_angle: f32,
+    last_center: (f32, f32),
+}
+
+/// Active pointers (`EngineState::pointers`) plus the pinch / rotate recognizer.
+#[derive(Debug, Clone, Default)]
+pub struct PointerState {
+    active: Vec<Pointer>,
+    gesture: Option<GestureTrack>,
+}
+
+impl PointerState {
+    /// Record `event`, filling in `buttons` / `is_primary`. Returns `false` when the event should be
+    /// dropped (release or move of a contact that never went down, repeated mouse button press).
+    /// A completed two-finger update is written to `gesture`.
+    pub fn apply(&mut self, event: &mut PointerEvent, gesture: &mut Option<GestureEvent>) -> bool {
+        let pos = self.active.iter().position(|p| p.id == event.id);
+        event.is_primary = self
+            .active
+            .iter()
+            .find(|p| p.kind == event.kind)
+            .is_none_or(|p| p.id == event.id);
+        match event.phase {
+            PointerPhase::Down => {
+                let bit = event.button.map(PointerButton::bit).unwrap_or(1);
+                match pos {
+                    Some(i) => {
+                        let p = &mut self.active[i];
+                        if p.buttons & bit != 0 {
+                            return false;
+                        }
+                        p.buttons |= bit;
+                        p.x = event.x;
+                        p.y = event.y;
+                        event.buttons = p.buttons;
+                    }
+                    None => {
+                        self.active.push(Pointer {
+                            id: event.id,
+                            kind: event.kind,
+                            x: event.x,
+                            y: event.y,
+                            start_x: event.x,
+                            start_y: event.y,
+                            pressure: event.pressure,
+                            buttons: bit,
+                        });
+                        event.buttons = bit;
+                    }
+                }
+            }
+            PointerPhase::Move => match pos {
+                Some(i) => {
+                    let p = &mut self.active[i];
+                    p.x = event.x;
+                    p.y = event.y;
+                    p.pressure = event.pressure;
+                    event.buttons = p.buttons;
+                }
+                // Hover: only the mouse (and hovering pens) move without a contact.
+                None if event.kind == PointerKind::Touch => return false,
+                None => event.buttons = 0,
+            },
+            PointerPhase::Up | PointerPhase::Cancel => {
+                let Some(i) = pos else {
+                    return false;
+                };
+                let p = &mut self.active[i];
+                p.x = event.x;
+                p.y = event.y;
+                let bit = event.button.map(PointerButton::bit).unwrap_or(p.buttons);
+                p.buttons &= !bit;
+                event.buttons = p.buttons;
+                if p.buttons == 0 || event.phase == PointerPhase::Cancel {
+                    event.buttons = 0;
+                    self.active.remove(i);
+                }
+            }
+        }
+        if event.kind == PointerKind::Mouse && event.buttons != 0 {
+            event.pressure = event.pressure.max(0.5);
+        }
+        *gesture = self.update_gesture();
+        true
+    }
+
+    /// Pinch / rotate over the first two touch contacts.
+    fn update_gesture(&mut self) -> Option<GestureEvent> {
+        let mut touches = self.active.iter().filter(|p| p.kind == PointerKind::Touch);
+        let pair = match (touches.next(), touches.next()) {
+            (Some(a), Some(b)) => Some((*a, *b)),
+            _ => None,
+        };
+
+        match (self.gesture, pair) {
+            (None, Some((a, b))) => {
+                let (span, angle, center) = spread(&a, &b);
+                self.gesture = Some(GestureTrack {
+                    ids: (a.id, b.id),
+                    start_span: span,
+                    start_angle: angle,
+                    last_span: span,
+                    last_angle: angle,
+                    last_center: center,
+                });
+                Some(GestureEvent {
+                    phase: GesturePhase::Began,
+                    center_x: center.0,
+                    center_y: center.1,
+                    scale: 1.0,
+                    rotation: 0.0,
+                    delta_scale: 1.0,
+                    delta_rotation: 0.0,
+                    pan_dx: 0.0,
+                    pan_dy: 0.0,
+                })
+            }
+            (Some(track), Some((a, b))) if track.ids == (a.id, b.id) => {
+                let (span, angle, center) = spread(&a, &b);
+                let pan = (
+                    center.0 - track.last_center.0,
+                    center.1 - track.last_center.1,
+                );
+                let delta_rotation = wrap_angle(angle - track.last_angle);
+                let delta_scale = if track.last_span >= MIN_GESTURE_SPAN && span >= MIN_GESTURE_SPAN
+                {
+                    span / track.last_span
+                } else {
+                    1.0
+                };
+                if pan == (0.0, 0.0) && delta_scale == 1.0 && delta_rotation == 0.0 {
+                    return None;
+                }
+                let g = self.gesture.as_mut()?;
+                g.last_span = span;
+                g.last_angle = angle;
+                g.last_center = center;
+                Some(GestureEvent {
+                    phase: GesturePhase::Changed,
+                    center_x: center.0,
+                    center_y: center.1,
+                    scale: if track.start_span >= MIN_GESTURE_SPAN {
+                        span / track.start_span
+                    } else {
+                        1.0
+                    },
+                    rotation: wrap_angle(angle - track.start_angle),
+                    delta_scale,
+                    delta_rotation,
+                    pan_dx: pan.0,
+                    pan_dy: pan.1,
+                })
+            }
+            (Some(track), _) => {
+                self.gesture = None;
+                Some(GestureEvent {
+                    phase: GesturePhase::Ended,
+                    center_x: track.last_center.0,
+                    center_y: track.last_center.1,
+                    scale: if track.start_span >= MIN_GESTURE_SPAN {
+                        track.last_span / track.start_span
+                    } else {
+                        1.0
+                    },
+                    rotation: wrap_angle(track.last_angle - track.start_angle),
+                    delta_scale: 1.0,
+                    delta_rotation: 0.0,
+                    pan_dx: 0.0,
+                    pan_dy: 0.0,
+                })
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// `true` while two touch contacts are being tracked as a pinch / rotate.
+    #[inline]
+    pub fn gesture_active(&self) -> bool {
+        self.gesture.is_some()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Pointer> {
+        self.active.iter().find(|p| p.id == id)
+    }
+
+    /// Held contacts in press order.
+    pub fn iter(&self) -> impl Iterator<Item = &Pointer> {
+        self.active.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Cancel events for every held contact (focus loss). The table itself is not touched: routing each
+    /// event back through [`Self::apply`] (see `cancel_all_pointers`) removes the contact and ends any
+    /// gesture, which draining here would skip.
+    pub fn cancel_all(&self) -> Vec<PointerEvent> {
+        self.active
+            .iter()
+            .map(|p| PointerEvent {
+                id: p.id,
+                kind: p.kind,
+                phase: PointerPhase::Cancel,
+                x: p.x,
+                y: p.y,
+                pressure: 0.0,
+                button: None,
+                buttons: 0,
+                is_primary: false,
+            })
+            .collect()
+    }
+}
+
+fn spread(a: &Pointer, b: &Pointer) -> (f32, f32, (f32, f32)) {
+    let dx = b.x - a.x;
+    let dy = b.y - a.y;
+    (
+        (dx * dx + dy * dy).sqrt(),
+        dy.atan2(dx),
+        ((a.x + b.x) * 0.5, (a.y + b.y) * 0.5),
+    )
+}
+
+fn wrap_angle(a: f32) -> f32 {
+    use std::f32::consts::PI;
+    let mut a = a % (2.0 * PI);
+    if a > PI {
+        a -= 2.0 * PI;
+    } else if a < -PI {
+        a += 2.0 * PI;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: u64, phase: PointerPhase, x: f32, y: f32) -> PointerEvent {
+        PointerEvent::contact(id, PointerKind::Touch, phase, x, y, 0.0)
+    }
+
+    #[test]
+    fn mouse_buttons_accumulate_and_release() {
+        let mut st = PointerState::default();
+        let mut g = None;
+        let mut down = PointerEvent::mouse(PointerPhase::Down, 1.0, 1.0, Some(PointerButton::Left));
+        assert!(st.apply(&mut down, &mut g));
+        let mut mid =
+            PointerEvent::mouse(PointerPhase::Down, 1.0, 1.0, Some(PointerButton::Middle));
+        assert!(st.apply(&mut mid, &mut g));
+        assert_eq!(mid.buttons, 5);
+        assert_eq!(st.len(), 1);
+
+        let mut up = PointerEvent::mouse(PointerPhase::Up, 2.0, 2.0, Some(PointerButton::Left));
+        assert!(st.apply(&mut up, &mut g));
+        assert_eq!(up.buttons, 4);
+        let mut up = PointerEvent::mouse(PointerPhase::Up, 2.0, 2.0, Some(PointerButton::Middle));
+        assert!(st.apply(&mut up, &mut g));
+        assert!(st.is_empty());
+
+        // Stray release (press was consumed elsewhere) is dropped.
+        let mut stray = PointerEvent::mouse(PointerPhase::Up, 2.0, 2.0, Some(PointerButton::Left));
+        assert!(!st.apply(&mut stray, &mut g));
+    }
+
+    #[test]
+    fn two_touches_pinch_and_rotate() {
+        let mut st = PointerState::default();
+        let mut g = None;
+        assert!(st.apply(&mut touch(1, PointerPhase::Down, 0.0, 0.0), &mut g));
+        assert!(g.is_none());
+        let mut second = touch(2, PointerPhase::Down, 100.0, 0.0);
+        st.apply(&mut second, &mut g);
+        assert!(!second.is_primary);
+        assert_eq!(g.map(|g| g.phase), Some(GesturePhase::Began));
+
+        // Spread to 200px and rotate a quarter turn about the first finger.
+        st.apply(&mut touch(2, PointerPhase::Move, 0.0, 200.0), &mut g);
+        let ev = g.expect("changed");
+        assert_eq!(ev.phase, GesturePhase::Changed);
+        assert!((ev.scale - 2.0).abs() < 1e-4);
+        assert!((ev.rotation - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+        assert!(st.gesture_active());
+
+        st.apply(&mut touch(1, PointerPhase::Up, 0.0, 0.0), &mut g);
+        assert_eq!(g.map(|g| g.phase), Some(GesturePhase::Ended));
+        assert!(!st.gesture_active());
+        assert_eq!(st.len(), 1);
+    }
+
+    #[test]
+    fn routed_cancels_empty_the_table_and_end_the_gesture() {
+        let mut st = PointerState::default();
+        let mut g = None;
+        st.apply(&mut touch(1, PointerPhase::Down, 0.0, 0.0), &mut g);
+        st.apply(&mut touch(2, PointerPhase::Down, 50.0, 0.0), &mut g);
+        assert!(st.gesture_active());
+
+        let cancels = st.cancel_all();
+        assert_eq!(cancels.len(), 2);
+        assert_eq!(st.len(), 2, "cancel_all only builds the events");
+        let mut ended = false;
+        for mut event in cancels {
+            assert!(st.apply(&mut event, &mut g));
+            assert_eq!(event.buttons, 0);
+            ended |= g.is_some_and(|g| g.phase == GesturePhase::Ended);
+        }
+        assert!(st.is_empty());
+        assert!(ended);
+        assert!(st.cancel_all().is_empty());
+    }
+}
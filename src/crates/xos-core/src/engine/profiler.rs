@@ -0,0 +1,331 @@
+//! Per-frame profiler: named, nested scopes recorded into a ring of recent frames, drawn as a
+//! timeline in the F3 overlay and exportable as Chrome trace JSON (`chrome://tracing`, Perfetto).
+//!
+//! Rust opens a scope with [`profile_scope!`](crate::profile_scope), Python with
+//! `with xos.profile("physics"):`. The engine itself records tick, Python callbacks, rasterizer
+//! passes, GPU passes and present. Scopes only record while the F3 overlay is open or after
+//! [`set_recording`]; otherwise they cost one thread-local flag check.
+//!
+//! Hosts call [`frame_boundary`] once per main-window frame, so a frame's duration is the wall time
+//! between boundaries (idle wait included) and extra windows land in the frame they ran in.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// Frames kept for the overlay and for export (~4 s at 60 FPS).
+pub const PROFILE_HISTORY_FRAMES: usize = 240;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileSpan {
+    pub name: Cow<'static, str>,
+    /// Microseconds on the profiler clock ([`crate::time::perf_counter_seconds_f64`]).
+    pub start_us: f64,
+    pub duration_us: f64,
+    /// Nesting level inside the frame (0 = top level).
+    pub depth: u16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameProfile {
+    pub index: u64,
+    pub start_us: f64,
+    pub duration_us: f64,
+    /// In start order, so parents precede their children.
+    pub spans: Vec<ProfileSpan>,
+}
+
+impl FrameProfile {
+    /// Summed time per top-level scope name, in first-seen order.
+    pub fn top_level_totals(&self) -> Vec<(&str, f64)> {
+        let mut totals: Vec<(&str, f64)> = Vec::new();
+        for span in self.spans.iter().filter(|span| span.depth == 0) {
+            match totals.iter_mut().find(|(name, _)| *name == span.name) {
+                Some((_, total)) => *total += span.duration_us,
+                None => totals.push((&span.name, span.duration_us)),
+            }
+        }
+        totals
+    }
+}
+
+#[derive(Default)]
+struct Profiler {
+    overlay: bool,
+    recording: bool,
+    frames: VecDeque<FrameProfile>,
+    current: Option<FrameProfile>,
+    /// Indices into `current.spans` of scopes not closed yet, innermost last.
+    open: Vec<usize>,
+    next_index: u64,
+}
+
+// Hosts, apps and the Python VM all run on the engine thread.
+thread_local! {
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::default());
+}
+
+fn now_us() -> f64 {
+    crate::time::perf_counter_seconds_f64() * 1_000_000.0
+}
+
+/// Record from the next frame on even while the F3 overlay is closed (`xos.profiler.start()`).
+pub fn set_recording(recording: bool) {
+    PROFILER.with(|p| p.borrow_mut().recording = recording);
+}
+
+pub fn is_recording() -> bool {
+    PROFILER.with(|p| {
+        let p = p.borrow();
+        p.overlay || p.recording
+    })
+}
+
+/// F3 overlay visibility; the overlay records whenever it is shown.
+pub(crate) fn set_overlay_active(active: bool) {
+    PROFILER.with(|p| p.borrow_mut().overlay = active);
+}
+
+/// Close the current frame (and any scope left open in it) and start the next one.
+pub fn frame_boundary() {
+    let now = now_us();
+    PROFILER.with(|p| {
+        let mut p = p.borrow_mut();
+        let open = std::mem::take(&mut p.open);
+        if let Some(mut frame) = p.current.take() {
+            for index in open {
+                let span = &mut frame.spans[index];
+                span.duration_us = now - span.start_us;
+            }
+            frame.duration_us = now - frame.start_us;
+            if p.frames.len() == PROFILE_HISTORY_FRAMES {
+                p.frames.pop_front();
+            }
+            p.frames.push_back(frame);
+        }
+        if p.overlay || p.recording {
+            let index = p.next_index;
+            p.next_index += 1;
+            p.current = Some(FrameProfile {
+                index,
+                start_us: now,
+                ..FrameProfile::default()
+            });
+        }
+    });
+}
+
+/// Handle from [`begin_scope`], passed back to [`end_scope`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScopeToken {
+    frame: u64,
+    span: usize,
+}
+
+impl ScopeToken {
+    /// `(frame, span)`, for bindings that can't hold the token itself (Python).
+    pub fn into_parts(self) -> (u64, usize) {
+        (self.frame, self.span)
+    }
+
+    pub fn from_parts(frame: u64, span: usize) -> Self {
+        Self { frame, span }
+    }
+}
+
+/// Open a scope in the current frame; `None` while not recording. Prefer [`scope`] in Rust.
+pub fn begin_scope(name: impl Into<Cow<'static, str>>) -> Option<ScopeToken> {
+    PROFILER.with(|p| {
+        let mut p = p.borrow_mut();
+        let depth = p.open.len() as u16;
+        let frame = p.current.as_mut()?;
+        let token = ScopeToken {
+            frame: frame.index,
+            span: frame.spans.len(),
+        };
+        frame.spans.push(ProfileSpan {
+            name: name.into(),
+            start_us: now_us(),
+            duration_us: 0.0,
+            depth,
+        });
+        p.open.push(token.span);
+        Some(token)
+    })
+}
+
+/// Close a scope (and any scope opened inside it and left open). Tokens from an earlier frame are
+/// ignored: [`frame_boundary`] already closed them.
+pub fn end_scope(token: ScopeToken) {
+    let now = now_us();
+    PROFILER.with(|p| {
+        let mut p = p.borrow_mut();
+        let p = &mut *p;
+        let Some(frame) = p.current.as_mut() else {
+            return;
+        };
+        if frame.index != token.frame || !p.open.contains(&token.span) {
+            return;
+        }
+        while let Some(index) = p.open.pop() {
+            let span = &mut frame.spans[index];
+            span.duration_us = now - span.start_us;
+            if index == token.span {
+                break;
+            }
+        }
+    });
+}
+
+/// Scope guard from [`scope`] / [`profile_scope!`](crate::profile_scope); closes on drop.
+#[must_use = "the scope closes when this guard is dropped"]
+pub struct ProfileScope(Option<ScopeToken>);
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            end_scope(token);
+        }
+    }
+}
+
+pub fn scope(name: impl Into<Cow<'static, str>>) -> ProfileScope {
+    ProfileScope(begin_scope(name))
+}
+
+/// [`scope`] with a name that is only built while recording (for `format!`ted names on hot paths).
+pub fn scope_with(name: impl FnOnce() -> String) -> ProfileScope {
+    let recording = PROFILER.with(|p| p.borrow().current.is_some());
+    ProfileScope(if recording { begin_scope(name()) } else { None })
+}
+
+/// Profile the rest of the enclosing block under `name`:
+/// `xos_core::profile_scope!("physics");`
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _xos_profile_scope = $crate::engine::profiler::scope($name);
+    };
+}
+
+/// Run `f` over the completed frames, oldest first.
+pub fn with_frames<R>(f: impl FnOnce(&VecDeque<FrameProfile>) -> R) -> R {
+    PROFILER.with(|p| f(&p.borrow().frames))
+}
+
+pub fn clear() {
+    PROFILER.with(|p| p.borrow_mut().frames.clear());
+}
+
+/// Chrome trace event JSON: one complete (`"X"`) event per frame with its scopes nested inside.
+pub fn chrome_trace_json<'a>(frames: impl IntoIterator<Item = &'a FrameProfile>) -> String {
+    let mut events = Vec::new();
+    let event = |name: &str, cat: &str, ts: f64, dur: f64, frame: u64| {
+        format!(
+            "{{\"name\":\"{}\",\"cat\":\"{cat}\",\"ph\":\"X\",\"ts\":{ts:.3},\"dur\":{dur:.3},\"pid\":1,\"tid\":1,\"args\":{{\"frame\":{frame}}}}}",
+            json_escape(name)
+        )
+    };
+    for frame in frames {
+        events.push(event(
+            &format!("frame {}", frame.index),
+            "frame",
+            frame.start_us,
+            frame.duration_us,
+            frame.index,
+        ));
+        for span in &frame.spans {
+            events.push(event(
+                &span.name,
+                "scope",
+                span.start_us,
+                span.duration_us,
+                frame.index,
+            ));
+        }
+    }
+    format!(
+        "{{\"traceEvents\":[{}],\"displayTimeUnit\":\"ms\"}}",
+        events.join(",")
+    )
+}
+
+fn json_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Write the recorded frames to `path` as Chrome trace JSON; returns how many frames were written.
+pub fn export_chrome_trace(path: &str) -> Result<usize, String> {
+    let (count, json) = with_frames(|frames| (frames.len(), chrome_trace_json(frames)));
+    if count == 0 {
+        return Err("no profiled frames yet (open the F3 overlay or call start() first)".into());
+    }
+    crate::fs::write(path, json.as_bytes())?;
+    Ok(count)
+}
+
+/// Default output for Shift+F3: `<data dir>/profiles/trace-<unix secs>.json`.
+pub fn default_trace_path() -> String {
+    let base = crate::fs::data_dir_string().unwrap_or_else(|_| ".".to_string());
+    let stamp = crate::time::unix_seconds_f64() as u64;
+    format!("{base}/profiles/trace-{stamp}.json")
+}
+
+/// Shift+F3: save the recorded frames to [`default_trace_path`] and report where.
+pub fn export_default_trace() {
+    let path = default_trace_path();
+    match export_chrome_trace(&path) {
+        Ok(count) => crate::print(&format!("📊 Saved Chrome trace ({count} frames) to {path}")),
+        Err(e) => crate::print(&format!("❌ Trace export failed: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_scopes_record_depth_and_close_at_frame_end() {
+        clear();
+        set_recording(true);
+        frame_boundary();
+        {
+            let _outer = scope("tick");
+            let _inner = scope(String::from("physics"));
+        }
+        let dangling = begin_scope("present");
+        assert!(dangling.is_some());
+        set_recording(false);
+        frame_boundary();
+        assert!(begin_scope("ignored").is_none());
+
+        with_frames(|frames| {
+            assert_eq!(frames.len(), 1);
+            let names: Vec<(&str, u16)> = frames[0]
+                .spans
+                .iter()
+                .map(|span| (span.name.as_ref(), span.depth))
+                .collect();
+            assert_eq!(names, [("tick", 0), ("physics", 1), ("present", 0)]);
+            assert!(frames[0].spans.iter().all(|span| span.duration_us >= 0.0));
+            assert_eq!(frames[0].top_level_totals().len(), 2);
+        });
+        let json = with_frames(|frames| chrome_trace_json(frames));
+        assert!(json.starts_with("{\"traceEvents\":[{\"name\":\"frame 0\""));
+        assert!(json.contains("\"name\":\"physics\",\"cat\":\"scope\",\"ph\":\"X\""));
+    }
+
+    #[test]
+    fn json_escape_quotes_and_control_characters() {
+        assert_eq!(json_escape("a\"b\\c\n"), "a\\\"b\\\\c\\u000a");
+    }
+}
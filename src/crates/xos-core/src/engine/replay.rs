@@ -0,0 +1,874 @@
+//! Deterministic input recording and replay at the [`Application`] boundary.
+//!
+//! [`InputRecorder`] wraps an app and writes every mouse / pointer / gesture / gamepad / keyboard /
+//! scroll / resize event it receives, plus each tick's `delta_time_seconds` and mouse state, as JSON
+//! lines:
+//!
+//! ```text
+//! {"t":0,"ev":"header","version":2,"script":"/path/app.py","args":[],"width":800,"height":600}
+//! {"t":0,"ev":"tick","dt":0.016666668,"mouse":{"x":0.0,"y":0.0,"dx":0.0,"dy":0.0,"left":false,"right":false}}
+//! {"t":1,"ev":"mouse_down","mouse":{"x":120.0,"y":80.0,"dx":0.0,"dy":0.0,"left":true,"right":false}}
+//! {"t":1,"ev":"tick","dt":0.016,"mouse":{...}}
+//! ```
+//!
+//! `t` is the number of ticks that ran before the event. [`InputReplayer`] ignores live input and
+//! feeds the recorded events and timings back, so the app sees the same run tick for tick, with
+//! `state.pointers` / `state.gamepads` rebuilt from the recording. F3 menu actions are not recorded.
+
+use crate::engine::engine::{Application, EngineState, KeyboardModifiers, ScrollWheelUnit};
+use crate::engine::gamepad::{GamepadEvent, Gamepads};
+use crate::engine::keyboard::keys::{KeyEvent, KeysDown};
+use crate::engine::keyboard::shortcuts::{ShortcutAction, SpecialKeyEvent};
+use crate::engine::pointer::{GestureEvent, PointerEvent, PointerPhase, PointerState};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// v2 added pointer, gesture and gamepad events.
+pub const REPLAY_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MouseSnapshot {
+    pub x: f32,
+    pub y: f32,
+    pub dx: f32,
+    pub dy: f32,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl MouseSnapshot {
+    pub fn capture(state: &EngineState) -> Self {
+        Self {
+            x: state.mouse.x,
+            y: state.mouse.y,
+            dx: state.mouse.dx,
+            dy: state.mouse.dy,
+            left: state.mouse.is_left_clicking,
+            right: state.mouse.is_right_clicking,
+        }
+    }
+
+    pub fn apply(&self, state: &mut EngineState) {
+        state.mouse.x = self.x;
+        state.mouse.y = self.y;
+        state.mouse.dx = self.dx;
+        state.mouse.dy = self.dy;
+        state.mouse.is_left_clicking = self.left;
+        state.mouse.is_right_clicking = self.right;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "ev", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    Header {
+        version: u32,
+        #[serde(default)]
+        script: Option<String>,
+        /// Script flags the app was started with (`xos.flags`).
+        #[serde(default)]
+        args: Vec<String>,
+        width: u32,
+        height: u32,
+    },
+    Tick {
+        dt: f32,
+        mouse: MouseSnapshot,
+    },
+    MouseDown {
+        mouse: MouseSnapshot,
+    },
+    MouseUp {
+        mouse: MouseSnapshot,
+    },
+    MouseMove {
+        mouse: MouseSnapshot,
+    },
+    Scroll {
+        dx: f32,
+        dy: f32,
+        unit: ScrollWheelUnit,
+    },
+    KeyChar {
+        ch: char,
+    },
+    SpecialKey {
+        key: SpecialKeyEvent,
+    },
+    Shortcut {
+        action: ShortcutAction,
+    },
+    Key {
+        key: KeyEvent,
+        modifiers: KeyboardModifiers,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    /// `on_pointer_down` / `on_pointer_move` / `on_pointer_up`, as delivered.
+    Pointer {
+        pointer: PointerEvent,
+    },
+    Gesture {
+        gesture: GestureEvent,
+    },
+    Gamepad {
+        gamepad: GamepadEvent,
+    },
+}
+
+/// One line of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayRecord {
+    pub t: u64,
+    #[serde(flatten)]
+    pub event: ReplayEvent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayHeader {
+    pub script: Option<String>,
+    pub args: Vec<String>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A parsed recording: its header and the remaining events in order.
+#[derive(Debug, Clone)]
+pub struct ReplayLog {
+    pub header: ReplayHeader,
+    pub records: VecDeque<ReplayRecord>,
+}
+
+impl ReplayLog {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Failed to open recording {}: {}", path.display(), e))?;
+        let mut lines = Vec::new();
+        for line in BufReader::new(file).lines() {
+            lines.push(line.map_err(|e| format!("Failed to read recording: {}", e))?);
+        }
+        Self::parse(lines.iter().map(String::as_str))
+    }
+
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let mut header = None;
+        let mut records = VecDeque::new();
+        for (n, line) in lines.into_iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let record: ReplayRecord = serde_json::from_str(line)
+                .map_err(|e| format!("Recording line {}: {}", n + 1, e))?;
+            match record.event {
+                ReplayEvent::Header {
+                    version,
+                    script,
+                    args,
+                    width,
+                    height,
+                } if header.is_none() => {
+                    if version > REPLAY_FORMAT_VERSION {
+                        return Err(format!(
+                            "Recording format v{} is newer than supported v{}",
+                            version, REPLAY_FORMAT_VERSION
+                        ));
+                    }
+                    header = Some(ReplayHeader {
+                        script,
+                        args,
+                        width,
+                        height,
+                    });
+                }
+                ReplayEvent::Header { .. } => {
+                    return Err(format!("Recording line {}: duplicate header", n + 1));
+                }
+                _ if header.is_none() => {
+                    return Err("Recording is missing its header line".to_string());
+                }
+                _ => records.push_back(record),
+            }
+        }
+        let header = header.ok_or_else(|| "Recording is empty".to_string())?;
+        Ok(Self { header, records })
+    }
+
+    /// Number of ticks the recording covers.
+    pub fn tick_count(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| matches!(r.event, ReplayEvent::Tick { .. }))
+            .count()
+    }
+}
+
+/// Passes everything through to `inner` while logging it to a recording file.
+pub struct InputRecorder {
+    inner: Box<dyn Application>,
+    out: Option<BufWriter<File>>,
+    script: Option<String>,
+    args: Vec<String>,
+    ticks: u64,
+}
+
+impl InputRecorder {
+    pub fn create(
+        inner: Box<dyn Application>,
+        path: &Path,
+        script: Option<String>,
+        args: Vec<String>,
+    ) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create recording {}: {}", path.display(), e))?;
+        Ok(Self {
+            inner,
+            out: Some(BufWriter::new(file)),
+            script,
+            args,
+            ticks: 0,
+        })
+    }
+
+    fn write(&mut self, event: ReplayEvent) {
+        let Some(out) = self.out.as_mut() else {
+            return;
+        };
+        let record = ReplayRecord {
+            t: self.ticks,
+            event,
+        };
+        let written = serde_json::to_string(&record)
+            .map_err(|e| e.to_string())
+            .and_then(|line| writeln!(out, "{}", line).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("⚠️  input recording stopped: {}", e);
+            self.out = None;
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(out) = self.out.as_mut() {
+            let _ = out.flush();
+        }
+    }
+}
+
+impl Application for InputRecorder {
+    fn setup(&mut self, state: &mut EngineState) -> Result<(), String> {
+        let shape = state.frame.shape();
+        self.write(ReplayEvent::Header {
+            version: REPLAY_FORMAT_VERSION,
+            script: self.script.clone(),
+            args: self.args.clone(),
+            width: shape[1] as u32,
+            height: shape[0] as u32,
+        });
+        self.inner.setup(state)
+    }
+
+    fn tick(&mut self, state: &mut EngineState) {
+        self.write(ReplayEvent::Tick {
+            dt: state.delta_time_seconds,
+            mouse: MouseSnapshot::capture(state),
+        });
+        self.inner.tick(state);
+        self.ticks += 1;
+        // Keep the file usable if the app crashes or is killed mid-run.
+        self.flush();
+    }
+
+    fn render(&mut self, state: &mut EngineState) {
+        self.inner.render(state);
+    }
+
+    fn on_mouse_down(&mut self, state: &mut EngineState) {
+        self.write(ReplayEvent::MouseDown {
+            mouse: MouseSnapshot::capture(state),
+        });
+        self.inner.on_mouse_down(state);
+    }
+
+    fn on_mouse_up(&mut self, state: &mut EngineState) {
+        self.write(ReplayEvent::MouseUp {
+            mouse: MouseSnapshot::capture(state),
+        });
+        self.inner.on_mouse_up(state);
+    }
+
+    fn on_mouse_move(&mut self, state: &mut EngineState) {
+        self.write(ReplayEvent::MouseMove {
+            mouse: MouseSnapshot::capture(state),
+        });
+        self.inner.on_mouse_move(state);
+    }
+
+    fn on_scroll(&mut self, state: &mut EngineState, dx: f32, dy: f32, unit: ScrollWheelUnit) {
+        self.write(ReplayEvent::Scroll { dx, dy, unit });
+        self.inner.on_scroll(state, dx, dy, unit);
+    }
+
+    fn on_key_char(&mut self, state: &mut EngineState, ch: char) {
+        self.write(ReplayEvent::KeyChar { ch });
+        self.inner.on_key_char(state, ch);
+    }
+
+    fn on_special_key(&mut self, state: &mut EngineState, key: SpecialKeyEvent) {
+        self.write(ReplayEvent::SpecialKey { key });
+        self.inner.on_special_key(state, key);
+    }
+
+    fn on_key_shortcut(&mut self, state: &mut EngineState, action: ShortcutAction) {
+        self.write(ReplayEvent::Shortcut { action });
+        self.inner.on_key_shortcut(state, action);
+    }
+
+    fn on_key_down(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        self.write(ReplayEvent::Key {
+            key: event.clone(),
+            modifiers: state.keyboard.modifiers,
+        });
+        self.inner.on_key_down(state, event);
+    }
+
+    fn on_key_up(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        self.write(ReplayEvent::Key {
+            key: event.clone(),
+            modifiers: state.keyboard.modifiers,
+        });
+        self.inner.on_key_up(state, event);
+    }
+
+    fn on_pointer_down(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.write(ReplayEvent::Pointer { pointer: *event });
+        self.inner.on_pointer_down(state, event);
+    }
+
+    fn on_pointer_move(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.write(ReplayEvent::Pointer { pointer: *event });
+        self.inner.on_pointer_move(state, event);
+    }
+
+    fn on_pointer_up(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.write(ReplayEvent::Pointer { pointer: *event });
+        self.inner.on_pointer_up(state, event);
+    }
+
+    fn on_gesture(&mut self, state: &mut EngineState, gesture: &GestureEvent) {
+        self.write(ReplayEvent::Gesture { gesture: *gesture });
+        self.inner.on_gesture(state, gesture);
+    }
+
+    fn on_gamepad(&mut self, state: &mut EngineState, event: &GamepadEvent) {
+        self.write(ReplayEvent::Gamepad {
+            gamepad: event.clone(),
+        });
+        self.inner.on_gamepad(state, event);
+    }
+
+    fn on_file_hover(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        self.inner.on_file_hover(state, paths);
+    }
+
+    fn on_file_hover_cancel(&mut self, state: &mut EngineState) {
+        self.inner.on_file_hover_cancel(state);
+    }
+
+    fn on_file_drop(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        self.inner.on_file_drop(state, paths);
+    }
+
+    fn on_screen_size_change(&mut self, state: &mut EngineState, width: u32, height: u32) {
+        self.write(ReplayEvent::Resize { width, height });
+        self.inner.on_screen_size_change(state, width, height);
+    }
+
+    fn prepare_shutdown(&mut self, state: &mut EngineState) {
+        self.flush();
+        self.inner.prepare_shutdown(state);
+    }
+}
+
+/// Drives `inner` from a [`ReplayLog`] instead of live input. Once the log is exhausted the app
+/// either keeps running on live input or, with [`InputReplayer::exit_when_done`], asks the host
+/// to quit.
+pub struct InputReplayer {
+    inner: Box<dyn Application>,
+    log: ReplayLog,
+    mouse: MouseSnapshot,
+    modifiers: KeyboardModifiers,
+    keys_down: KeysDown,
+    pointers: PointerState,
+    gamepads: Gamepads,
+    ticks: u64,
+    finished: bool,
+    exit_when_done: bool,
+    resize_frame: bool,
+    warned_size: bool,
+}
+
+impl InputReplayer {
+    pub fn new(inner: Box<dyn Application>, log: ReplayLog) -> Self {
+        Self {
+            inner,
+            log,
+            mouse: MouseSnapshot::default(),
+            modifiers: KeyboardModifiers::default(),
+            keys_down: KeysDown::default(),
+            pointers: PointerState::default(),
+            gamepads: Gamepads::default(),
+            ticks: 0,
+            finished: false,
+            exit_when_done: false,
+            resize_frame: false,
+            warned_size: false,
+        }
+    }
+
+    /// Request host exit after the last recorded tick (regression runs).
+    pub fn exit_when_done(mut self, exit: bool) -> Self {
+        self.exit_when_done = exit;
+        self
+    }
+
+    /// Resize the frame to match recorded resizes. Only safe for headless hosts, where the frame
+    /// isn't tied to a window surface.
+    pub fn resize_frame(mut self, resize: bool) -> Self {
+        self.resize_frame = resize;
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Run `f` with the recorded mouse / modifier / held-key / pointer / gamepad state installed,
+    /// then put the live state back so host overlays (F3 menu, on-screen keyboard) keep tracking
+    /// the real cursor.
+    fn with_replayed_input(
+        &mut self,
+        state: &mut EngineState,
+        f: impl FnOnce(&mut dyn Application, &mut EngineState),
+    ) {
+        let live_mouse = MouseSnapshot::capture(state);
+        let live_modifiers = state.keyboard.modifiers;
+        self.mouse.apply(state);
+        state.keyboard.modifiers = self.modifiers;
+        std::mem::swap(&mut state.keyboard.keys_down, &mut self.keys_down);
+        std::mem::swap(&mut state.pointers, &mut self.pointers);
+        std::mem::swap(&mut state.gamepads, &mut self.gamepads);
+        f(self.inner.as_mut(), state);
+        std::mem::swap(&mut state.keyboard.keys_down, &mut self.keys_down);
+        std::mem::swap(&mut state.pointers, &mut self.pointers);
+        std::mem::swap(&mut state.gamepads, &mut self.gamepads);
+        self.mouse = MouseSnapshot::capture(state);
+        live_mouse.apply(state);
+        state.keyboard.modifiers = live_modifiers;
+    }
+
+    fn frame_matches(&mut self, state: &mut EngineState, width: u32, height: u32) -> bool {
+        let shape = state.frame.shape();
+        if shape[1] as u32 == width && shape[0] as u32 == height {
+            return true;
+        }
+        if self.resize_frame {
+            state.resize_frame(width, height);
+            return true;
+        }
+        if !self.warned_size {
+            eprintln!(
+                "⚠️  replay: window is {}x{} but the recording was {}x{}; results may diverge",
+                shape[1], shape[0], width, height
+            );
+            self.warned_size = true;
+        }
+        false
+    }
+
+    fn deliver(&mut self, state: &mut EngineState, event: ReplayEvent) {
+        match event {
+            ReplayEvent::Header { .. } | ReplayEvent::Tick { .. } => {}
+            ReplayEvent::MouseDown { mouse } => {
+                self.mouse = mouse;
+                self.with_replayed_input(state, |app, s| app.on_mouse_down(s));
+            }
+            ReplayEvent::MouseUp { mouse } => {
+                self.mouse = mouse;
+                self.with_replayed_input(state, |app, s| app.on_mouse_up(s));
+            }
+            ReplayEvent::MouseMove { mouse } => {
+                self.mouse = mouse;
+                self.with_replayed_input(state, |app, s| app.on_mouse_move(s));
+            }
+            ReplayEvent::Scroll { dx, dy, unit } => {
+                self.with_replayed_input(state, |app, s| app.on_scroll(s, dx, dy, unit));
+            }
+            ReplayEvent::KeyChar { ch } => {
+                self.with_replayed_input(state, |app, s| app.on_key_char(s, ch));
+            }
+            ReplayEvent::SpecialKey { key } => {
+                self.with_replayed_input(state, |app, s| app.on_special_key(s, key));
+            }
+            ReplayEvent::Shortcut { action } => {
+                self.with_replayed_input(state, |app, s| app.on_key_shortcut(s, action));
+            }
+            ReplayEvent::Key { key, modifiers } => {
+                self.modifiers = modifiers;
+                self.with_replayed_input(state, |app, s| {
+                    crate::engine::engine::route_key_event(app, s, key)
+                });
+            }
+            ReplayEvent::Resize { width, height } => {
+                if self.frame_matches(state, width, height) {
+                    self.inner.on_screen_size_change(state, width, height);
+                }
+            }
+            ReplayEvent::Pointer { pointer } => {
+                // Rebuild the pointer table; the recorded gesture events follow on their own lines.
+                let mut replayed = pointer;
+                self.pointers.apply(&mut replayed, &mut None);
+                self.with_replayed_input(state, |app, s| match pointer.phase {
+                    PointerPhase::Down => app.on_pointer_down(s, &pointer),
+                    PointerPhase::Move => app.on_pointer_move(s, &pointer),
+                    PointerPhase::Up | PointerPhase::Cancel => app.on_pointer_up(s, &pointer),
+                });
+            }
+            ReplayEvent::Gesture { gesture } => {
+                self.with_replayed_input(state, |app, s| app.on_gesture(s, &gesture));
+            }
+            ReplayEvent::Gamepad { gamepad } => {
+                self.gamepads.apply(&gamepad);
+                self.with_replayed_input(state, |app, s| app.on_gamepad(s, &gamepad));
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        eprintln!("⏹  replay finished after {} ticks", self.ticks);
+        if self.exit_when_done {
+            #[cfg(not(target_arch = "wasm32"))]
+            crate::engine::native_engine::request_exit();
+        }
+    }
+}
+
+impl Application for InputReplayer {
+    fn setup(&mut self, state: &mut EngineState) -> Result<(), String> {
+        let (width, height) = (self.log.header.width, self.log.header.height);
+        self.frame_matches(state, width, height);
+        self.inner.setup(state)
+    }
+
+    fn tick(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.tick(state);
+            return;
+        }
+        // Same per-tick edge reset the host does in `poll_gamepads`, before this tick's events.
+        self.gamepads.begin_frame();
+        while let Some(record) = self.log.records.pop_front() {
+            if let ReplayEvent::Tick { dt, mouse } = record.event {
+                self.mouse = mouse;
+                let live_dt = state.delta_time_seconds;
+                state.delta_time_seconds = dt;
+                self.with_replayed_input(state, |app, s| app.tick(s));
+                state.delta_time_seconds = live_dt;
+                self.ticks += 1;
+                if self.log.records.is_empty() {
+                    self.finish();
+                }
+                return;
+            }
+            self.deliver(state, record.event);
+        }
+        self.finish();
+    }
+
+    fn render(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.render(state);
+        } else {
+            self.with_replayed_input(state, |app, s| app.render(s));
+        }
+    }
+
+    fn on_mouse_down(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.on_mouse_down(state);
+        }
+    }
+
+    fn on_mouse_up(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.on_mouse_up(state);
+        }
+    }
+
+    fn on_mouse_move(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.on_mouse_move(state);
+        }
+    }
+
+    fn on_scroll(&mut self, state: &mut EngineState, dx: f32, dy: f32, unit: ScrollWheelUnit) {
+        if self.finished {
+            self.inner.on_scroll(state, dx, dy, unit);
+        }
+    }
+
+    fn on_key_char(&mut self, state: &mut EngineState, ch: char) {
+        if self.finished {
+            self.inner.on_key_char(state, ch);
+        }
+    }
+
+    fn on_special_key(&mut self, state: &mut EngineState, key: SpecialKeyEvent) {
+        if self.finished {
+            self.inner.on_special_key(state, key);
+        }
+    }
+
+    fn on_key_shortcut(&mut self, state: &mut EngineState, action: ShortcutAction) {
+        if self.finished {
+            self.inner.on_key_shortcut(state, action);
+        }
+    }
+
+    fn on_key_down(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        if self.finished {
+            self.inner.on_key_down(state, event);
+        }
+    }
+
+    fn on_key_up(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        if self.finished {
+            self.inner.on_key_up(state, event);
+        }
+    }
+
+    fn on_pointer_down(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if self.finished {
+            self.inner.on_pointer_down(state, event);
+        }
+    }
+
+    fn on_pointer_move(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if self.finished {
+            self.inner.on_pointer_move(state, event);
+        }
+    }
+
+    fn on_pointer_up(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if self.finished {
+            self.inner.on_pointer_up(state, event);
+        }
+    }
+
+    fn on_gesture(&mut self, state: &mut EngineState, gesture: &GestureEvent) {
+        if self.finished {
+            self.inner.on_gesture(state, gesture);
+        }
+    }
+
+    fn on_gamepad(&mut self, state: &mut EngineState, event: &GamepadEvent) {
+        if self.finished {
+            self.inner.on_gamepad(state, event);
+        }
+    }
+
+    fn on_file_hover(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        if self.finished {
+            self.inner.on_file_hover(state, paths);
+        }
+    }
+
+    fn on_file_hover_cancel(&mut self, state: &mut EngineState) {
+        if self.finished {
+            self.inner.on_file_hover_cancel(state);
+        }
+    }
+
+    fn on_file_drop(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        if self.finished {
+            self.inner.on_file_drop(state, paths);
+        }
+    }
+
+    fn on_screen_size_change(&mut self, state: &mut EngineState, width: u32, height: u32) {
+        // The frame really changed size, so the app has to hear about it even mid-replay.
+        self.inner.on_screen_size_change(state, width, height);
+    }
+
+    fn prepare_shutdown(&mut self, state: &mut EngineState) {
+        self.inner.prepare_shutdown(state);
+    }
+}
+
+/// What `--record-input` / `--replay` asked for; wraps the app before it is handed to a host.
+pub enum InputCapture {
+    Record {
+        path: std::path::PathBuf,
+        script: Option<String>,
+        args: Vec<String>,
+    },
+    Replay(ReplayLog),
+}
+
+impl InputCapture {
+    /// `headless` replays resize the frame to the recording and exit when it ends.
+    pub fn wrap(
+        self,
+        app: Box<dyn Application>,
+        headless: bool,
+    ) -> Result<Box<dyn Application>, String> {
+        let wrapped: Box<dyn Application> = match self {
+            InputCapture::Record { path, script, args } => {
+                Box::new(InputRecorder::create(app, &path, script, args)?)
+            }
+            InputCapture::Replay(log) => Box::new(
+                InputReplayer::new(app, log)
+                    .exit_when_done(headless)
+                    .resize_frame(headless),
+            ),
+        };
+        Ok(wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::harness::AppHarness;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Logs what it is handed, including the pointer table it sees on each tick.
+    struct DragLog {
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl DragLog {
+        fn pointer(&self, event: &PointerEvent) {
+            self.log.borrow_mut().push(format!(
+                "{:?} ({}, {}) buttons={} primary={}",
+                event.phase, event.x, event.y, event.buttons, event.is_primary
+            ));
+        }
+    }
+
+    impl Application for DragLog {
+        fn setup(&mut self, _state: &mut EngineState) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn tick(&mut self, state: &mut EngineState) {
+            self.log.borrow_mut().push(format!(
+                "tick ({}, {}) pointers={}",
+                state.mouse.x,
+                state.mouse.y,
+                state.pointers.len()
+            ));
+        }
+
+        fn on_pointer_down(&mut self, _state: &mut EngineState, event: &PointerEvent) {
+            self.pointer(event);
+        }
+
+        fn on_pointer_move(&mut self, _state: &mut EngineState, event: &PointerEvent) {
+            self.pointer(event);
+        }
+
+        fn on_pointer_up(&mut self, _state: &mut EngineState, event: &PointerEvent) {
+            self.pointer(event);
+        }
+    }
+
+    #[test]
+    fn pointer_drag_replays_on_its_recorded_ticks() {
+        let path =
+            std::env::temp_dir().join(format!("xos-replay-test-{}.jsonl", std::process::id()));
+        let recorded = Rc::new(RefCell::new(Vec::new()));
+        let app = DragLog {
+            log: recorded.clone(),
+        };
+        let recorder = InputRecorder::create(Box::new(app), &path, None, Vec::new()).unwrap();
+        let mut h = AppHarness::new(Box::new(recorder), 8, 6).unwrap();
+        h.frames(1);
+        h.mouse_move(2.0, 2.0);
+        h.mouse_down();
+        h.frames(1);
+        h.mouse_move(5.0, 3.0);
+        h.frames(1);
+        h.mouse_up();
+        h.frames(1);
+        drop(h);
+
+        let log = ReplayLog::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let pointer_ticks: Vec<u64> = log
+            .records
+            .iter()
+            .filter(|r| matches!(r.event, ReplayEvent::Pointer { .. }))
+            .map(|r| r.t)
+            .collect();
+        assert_eq!(pointer_ticks, [1, 1, 2, 3]);
+        assert!(recorded
+            .borrow()
+            .contains(&"tick (5, 3) pointers=1".to_string()));
+
+        let replayed = Rc::new(RefCell::new(Vec::new()));
+        let app = DragLog {
+            log: replayed.clone(),
+        };
+        let mut h =
+            AppHarness::new(Box::new(InputReplayer::new(Box::new(app), log)), 8, 6).unwrap();
+        // Live input is ignored while the recording plays.
+        h.mouse_move(7.0, 5.0);
+        h.mouse_down();
+        h.frames(4);
+        assert_eq!(*replayed.borrow(), *recorded.borrow());
+        assert_eq!(
+            h.state.pointers.len(),
+            1,
+            "the live press stays in the live table"
+        );
+    }
+
+    #[test]
+    fn records_round_trip_as_json_lines() {
+        let record = ReplayRecord {
+            t: 3,
+            event: ReplayEvent::Scroll {
+                dx: 0.0,
+                dy: -2.5,
+                unit: ScrollWheelUnit::Line,
+            },
+        };
+        let line = serde_json::to_string(&record).unwrap();
+        assert!(line.contains("\"ev\":\"scroll\""));
+        assert_eq!(serde_json::from_str::<ReplayRecord>(&line).unwrap(), record);
+    }
+
+    #[test]
+    fn parse_requires_header_first() {
+        let tick = r#"{"t":0,"ev":"tick","dt":0.016,"mouse":{"x":0,"y":0,"dx":0,"dy":0,"left":false,"right":false}}"#;
+        assert!(ReplayLog::parse([tick]).is_err());
+
+        let header = r#"{"t":0,"ev":"header","version":1,"script":null,"width":320,"height":240}"#;
+        let log = ReplayLog::parse([header, "", tick, tick]).unwrap();
+        assert_eq!(log.header.width, 320);
+        assert_eq!(log.tick_count(), 2);
+    }
+
+    #[test]
+    fn parse_rejects_newer_format() {
+        let header = r#"{"t":0,"ev":"header","version":99,"width":1,"height":1}"#;
+        assert!(ReplayLog::parse([header]).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Optional fixed-timestep simulation: an accumulator turns variable frame time into a whole number
+//! of constant-size `tick`s, leaving an interpolation `alpha` for drawing between the last two.
+
+/// Upper bound on catch-up ticks per displayed frame (avoids the "spiral of death" after a stall).
+pub const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 8;
+
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    hz: Option<f32>,
+    accumulator: f32,
+    /// Fraction of a fixed step left in the accumulator after this frame's ticks (`0.0..1.0`).
+    /// `1.0` in variable mode, where the last tick is always current.
+    pub alpha: f32,
+    pub max_steps_per_frame: u32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self {
+            hz: None,
+            accumulator: 0.0,
+            alpha: 1.0,
+            max_steps_per_frame: DEFAULT_MAX_STEPS_PER_FRAME,
+        }
+    }
+}
+
+impl FixedTimestep {
+    /// Ticks per second in fixed mode, `None` for one variable-length tick per frame.
+    pub fn hz(&self) -> Option<f32> {
+        self.hz
+    }
+
+    /// Switch modes; non-positive or non-finite rates fall back to variable mode.
+    pub fn set_hz(&mut self, hz: Option<f32>) {
+        let hz = hz.filter(|h| h.is_finite() && *h > 0.0);
+        if hz != self.hz {
+            self.hz = hz;
+            self.accumulator = 0.0;
+            self.alpha = 1.0;
+        }
+    }
+
+    /// Length of one fixed tick in seconds.
+    pub fn step_seconds(&self) -> Option<f32> {
+        self.hz.map(|h| 1.0 / h)
+    }
+
+    /// Feed one displayed frame's wall-clock time; returns how many ticks to run for it.
+    /// Variable mode always returns 1.
+    pub fn advance(&mut self, frame_seconds: f32) -> u32 {
+        let Some(step) = self.step_seconds() else {
+            self.alpha = 1.0;
+            return 1;
+        };
+        self.accumulator += frame_seconds.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= step && steps < self.max_steps_per_frame {
+            self.accumulator -= step;
+            steps += 1;
+        }
+        if self.accumulator >= step {
+            // Too far behind: drop the backlog rather than ticking ever more per frame.
+            self.accumulator %= step;
+        }
+        self.alpha = (self.accumulator / step).clamp(0.0, 1.0);
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_mode_ticks_once_per_frame() {
+        let mut ts = FixedTimestep::default();
+        assert_eq!(ts.advance(0.5), 1);
+        assert_eq!(ts.advance(0.0), 1);
+        assert_eq!(ts.alpha, 1.0);
+    }
+
+    #[test]
+    fn fixed_mode_accumulates_and_reports_alpha() {
+        let mut ts = FixedTimestep::default();
+        ts.set_hz(Some(50.0));
+        assert_eq!(ts.advance(0.01), 0);
+        assert!((ts.alpha - 0.5).abs() < 1e-4);
+        assert_eq!(ts.advance(0.015), 1);
+        assert!((ts.alpha - 0.25).abs() < 1e-4);
+        assert_eq!(ts.advance(0.04), 2);
+    }
+
+    #[test]
+    fn fixed_mode_caps_catch_up() {
+        let mut ts = FixedTimestep::default();
+        ts.set_hz(Some(100.0));
+        assert_eq!(ts.advance(5.0), DEFAULT_MAX_STEPS_PER_FRAME);
+        assert!(ts.alpha < 1.0);
+        assert_eq!(ts.advance(0.0), 0);
+    }
+
+    #[test]
+    fn invalid_rate_means_variable() {
+        let mut ts = FixedTimestep::default();
+        ts.set_hz(Some(0.0));
+        assert_eq!(ts.hz(), None);
+        ts.set_hz(Some(f32::NAN));
+        assert_eq!(ts.step_seconds(), None);
+    }
+}
@@ -5,8 +5,10 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
 use super::engine::{
-    tick_frame_delta, Application, CursorStyle, CursorStyleSetter, EngineState, FrameState,
-    KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle, ScrollWheelUnit,
+    advance_app_frame, cancel_all_pointers, poll_gamepads, release_all_keys, route_key_event,
+    route_pointer_event, step_app_tick, Application, CursorStyle, CursorStyleSetter, EngineState,
+    FrameState, KeyboardModifiers, KeyboardState, MouseState, SafeRegionBoundingRectangle,
+    ScrollWheelUnit,
 };
 use super::{
     apply_frame_view_zoom, f3_menu_boost_interaction_fade, f3_menu_handle_frame_zoom_scroll,
@@ -14,7 +16,10 @@ use super::{
     f3_menu_handle_zoom_scroll, frame_view_pan_by_pixels, tick_f3_menu, tick_frame_view_zoom,
     F3Menu,
 };
+use crate::engine::keyboard::keys::KeyEvent;
 use crate::engine::keyboard::shortcuts::detect_shortcut;
+use crate::engine::pointer::{PointerButton, PointerEvent, PointerKind, PointerPhase};
+use crate::engine::window_control::WindowCommand;
 
 #[cfg(target_arch = "wasm32")]
 fn canvas_as_html(canvas: &web_sys::HtmlCanvasElement) -> &web_sys::HtmlElement {
@@ -69,6 +74,86 @@ fn canvas_backing_scale(canvas: &web_sys::HtmlCanvasElement) -> f32 {
     canvas.width() as f32 / css_width
 }
 
+/// `xos.window` in the browser: title, fullscreen and pointer lock. Size and always-on-top belong to
+/// the page and are ignored; fullscreen and pointer lock need a recent click or key press.
+#[cfg(target_arch = "wasm32")]
+fn apply_window_commands(state: &mut EngineState, canvas: &web_sys::HtmlCanvasElement) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    for command in state.window.take_commands(true) {
+        match command {
+            WindowCommand::SetTitle(title) => {
+                document.set_title(&title);
+                state.window.info.title = title;
+            }
+            WindowCommand::SetFullscreen(true) => {
+                let _ = canvas_as_element(canvas).request_fullscreen();
+            }
+            WindowCommand::SetFullscreen(false) => {
+                if document.fullscreen_element().is_some() {
+                    document.exit_fullscreen();
+                }
+            }
+            WindowCommand::SetCursorVisible(visible) => state.window.info.cursor_visible = visible,
+            WindowCommand::SetCursorLocked(true) => {
+                canvas_as_element(canvas).request_pointer_lock()
+            }
+            WindowCommand::SetCursorLocked(false) => document.exit_pointer_lock(),
+            WindowCommand::SetSize { .. } | WindowCommand::SetAlwaysOnTop(_) => {}
+        }
+    }
+    let info = &mut state.window.info;
+    if info.title.is_empty() {
+        info.title = document.title();
+    }
+    info.fullscreen = document.fullscreen_element().is_some();
+    info.cursor_locked = document.pointer_lock_element().is_some();
+    info.scale_factor = canvas_backing_scale(canvas) as f64;
+    state.window.publish();
+}
+
+/// DOM `MouseEvent.button` → pointer button.
+#[cfg(target_arch = "wasm32")]
+fn dom_pointer_button(button: i16) -> Option<PointerButton> {
+    match button {
+        0 => Some(PointerButton::Left),
+        1 => Some(PointerButton::Middle),
+        2 => Some(PointerButton::Right),
+        3 => Some(PointerButton::Back),
+        4 => Some(PointerButton::Forward),
+        _ => None,
+    }
+}
+
+/// Route every contact in `event.changedTouches` as a touch pointer (ids offset past the mouse).
+#[cfg(target_arch = "wasm32")]
+fn route_changed_touches(
+    app: &mut dyn Application,
+    state: &mut EngineState,
+    canvas: &web_sys::HtmlCanvasElement,
+    event: &web_sys::TouchEvent,
+    phase: PointerPhase,
+) {
+    let rect = canvas_as_element(canvas).get_bounding_client_rect();
+    let scale = canvas_backing_scale(canvas) as f64;
+    let touches = event.changed_touches();
+    for i in 0..touches.length() {
+        let Some(touch) = touches.get(i) else {
+            continue;
+        };
+        let pointer = PointerEvent::contact(
+            touch.identifier() as u32 as u64 + 1,
+            PointerKind::Touch,
+            phase,
+            ((touch.client_x() as f64 - rect.left()) * scale) as f32,
+            ((touch.client_y() as f64 - rect.top()) * scale) as f32,
+            touch.force(),
+        );
+        route_pointer_event(app, state, pointer);
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 fn set_mouse_from_client_point(
     state: &mut EngineState,
@@ -116,6 +201,8 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         paused_base_frame: Vec<u8>,
         paused_base_w: usize,
         paused_base_h: usize,
+        /// A drag with files is over the canvas (the browser only reveals names on drop).
+        file_hovering: bool,
     }
 
     let state_ptr = Box::into_raw(Box::new(WasmState {
@@ -133,15 +220,21 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                 is_right_clicking: false,
                 style: CursorStyleSetter::new(),
             },
+            pointers: crate::engine::pointer::PointerState::default(),
             keyboard: KeyboardState {
                 onscreen: crate::ui::onscreen_keyboard::OnScreenKeyboard::new(),
                 modifiers: KeyboardModifiers::default(),
+                keys_down: crate::engine::keyboard::keys::KeysDown::default(),
             },
+            gamepads: crate::engine::gamepad::Gamepads::default(),
             f3_menu: F3Menu::new(),
             ui_scale_percent: (dpr * 100.0).round().clamp(25.0, 500.0) as u16,
             delta_time_seconds: 1.0 / 60.0,
             paused: false,
             pending_step_ticks: 0,
+            timestep: crate::engine::timestep::FixedTimestep::default(),
+            capture: crate::engine::capture::FrameCapture::default(),
+            window: crate::engine::window_control::WindowControl::default(),
             frame_view_zoom: 1.0,
             frame_view_zoom_target: 1.0,
             frame_view_zoom_velocity: 0.0,
@@ -158,6 +251,7 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         paused_base_frame: Vec::new(),
         paused_base_w: 0,
         paused_base_h: 0,
+        file_hovering: false,
     }));
 
     // Setup the app
@@ -176,6 +270,19 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
             let state = &mut *state_ptr_clone;
 
             let scale = canvas_backing_scale(&canvas_clone);
+            let motion_x = event.movement_x() as f32 * scale;
+            let motion_y = event.movement_y() as f32 * scale;
+            state
+                .engine_state
+                .window
+                .add_mouse_motion(motion_x, motion_y);
+            if state.engine_state.window.info.cursor_locked {
+                // Under pointer lock the position is frozen; only the motion is meaningful.
+                state.engine_state.mouse.dx = motion_x;
+                state.engine_state.mouse.dy = motion_y;
+                state.app.on_mouse_move(&mut state.engine_state);
+                return;
+            }
             let new_x = event.offset_x() as f32 * scale;
             let new_y = event.offset_y() as f32 * scale;
 
@@ -214,10 +321,16 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
 
             if !f3_menu_handle_mouse_move(&mut state.engine_state) {
                 state.app.on_mouse_move(&mut state.engine_state);
+                route_pointer_event(
+                    state.app.as_mut(),
+                    &mut state.engine_state,
+                    PointerEvent::mouse(PointerPhase::Move, new_x, new_y, None),
+                );
             }
 
             let cursor_style = state.engine_state.mouse.style.get();
             let style = match cursor_style {
+                _ if !state.engine_state.window.info.cursor_visible => "none",
                 CursorStyle::Default => "default",
                 CursorStyle::Text => "text",
                 CursorStyle::ResizeHorizontal => "ew-resize",
@@ -315,14 +428,23 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                         }
                         if !f3_menu_handle_mouse_down(&mut state.engine_state) {
                             state.app.on_mouse_down(&mut state.engine_state);
+                        } else {
+                            return;
                         }
                     }
                     2 => {
                         state.engine_state.mouse.is_right_clicking = true;
-                        // Optionally call a separate handler here if needed
                     }
                     _ => {}
                 }
+                let (x, y) = (state.engine_state.mouse.x, state.engine_state.mouse.y);
+                if let Some(button) = dom_pointer_button(event.button()) {
+                    route_pointer_event(
+                        state.app.as_mut(),
+                        &mut state.engine_state,
+                        PointerEvent::mouse(PointerPhase::Down, x, y, Some(button)),
+                    );
+                }
             }
         }) as Box<dyn FnMut(_)>);
         canvas.add_event_listener_with_callback(
@@ -358,10 +480,17 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                     }
                     2 => {
                         state.engine_state.mouse.is_right_clicking = false;
-                        // Optionally call a separate handler here too
                     }
                     _ => {}
                 }
+                let (x, y) = (state.engine_state.mouse.x, state.engine_state.mouse.y);
+                if let Some(button) = dom_pointer_button(event.button()) {
+                    route_pointer_event(
+                        state.app.as_mut(),
+                        &mut state.engine_state,
+                        PointerEvent::mouse(PointerPhase::Up, x, y, Some(button)),
+                    );
+                }
             }
         }) as Box<dyn FnMut(_)>);
         canvas.add_event_listener_with_callback("mouseup", up_callback.as_ref().unchecked_ref())?;
@@ -396,6 +525,15 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                 }
                 event.prevent_default();
             }
+            // Released outside the canvas; a no-op when the canvas listener already saw it.
+            let (x, y) = (state.engine_state.mouse.x, state.engine_state.mouse.y);
+            if let Some(button) = dom_pointer_button(event.button()) {
+                route_pointer_event(
+                    state.app.as_mut(),
+                    &mut state.engine_state,
+                    PointerEvent::mouse(PointerPhase::Up, x, y, Some(button)),
+                );
+            }
         }) as Box<dyn FnMut(_)>);
         window.add_event_listener_with_callback(
             "mouseup",
@@ -417,6 +555,8 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
             state.shift_held = false;
             state.engine_state.keyboard.modifiers.command = false;
             state.engine_state.keyboard.modifiers.shift = false;
+            release_all_keys(state.app.as_mut(), &mut state.engine_state);
+            cancel_all_pointers(state.app.as_mut(), &mut state.engine_state);
             if had_left || had_right {
                 if !f3_menu_handle_mouse_up(&mut state.engine_state) {
                     state.app.on_mouse_up(&mut state.engine_state);
@@ -458,6 +598,13 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         let touch_move_callback = Closure::wrap(Box::new(move |event: TouchEvent| {
             unsafe {
                 let state = &mut *state_ptr_clone;
+                route_changed_touches(
+                    state.app.as_mut(),
+                    &mut state.engine_state,
+                    &canvas_clone,
+                    &event,
+                    PointerPhase::Move,
+                );
                 if let Some(touch) = event.touches().get(0) {
                     let rect = canvas_as_element(&canvas_clone).get_bounding_client_rect();
                     let scale = canvas_backing_scale(&canvas_clone) as f64;
@@ -502,6 +649,18 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         let touch_start_callback = Closure::wrap(Box::new(move |event: TouchEvent| {
             unsafe {
                 let state = &mut *state_ptr_clone;
+                route_changed_touches(
+                    state.app.as_mut(),
+                    &mut state.engine_state,
+                    &canvas_clone,
+                    &event,
+                    PointerPhase::Down,
+                );
+                // Legacy single cursor follows the first finger only.
+                if event.touches().length() != 1 {
+                    event.prevent_default();
+                    return;
+                }
                 if let Some(touch) = event.touches().get(0) {
                     let rect = canvas_as_element(&canvas_clone).get_bounding_client_rect();
                     let scale = canvas_backing_scale(&canvas_clone) as f64;
@@ -524,21 +683,34 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         touch_start_callback.forget();
     }
 
-    // Touch end
-    {
+    // Touch end / cancel
+    for (name, phase) in [
+        ("touchend", PointerPhase::Up),
+        ("touchcancel", PointerPhase::Cancel),
+    ] {
         use web_sys::TouchEvent;
         let state_ptr_clone = state_ptr;
+        let canvas_clone = canvas.clone();
 
         let touch_end_callback = Closure::wrap(Box::new(move |event: TouchEvent| unsafe {
             let state = &mut *state_ptr_clone;
-            state.engine_state.mouse.is_left_clicking = false;
-            if !f3_menu_handle_mouse_up(&mut state.engine_state) {
-                state.app.on_mouse_up(&mut state.engine_state);
+            route_changed_touches(
+                state.app.as_mut(),
+                &mut state.engine_state,
+                &canvas_clone,
+                &event,
+                phase,
+            );
+            if event.touches().length() == 0 && state.engine_state.mouse.is_left_clicking {
+                state.engine_state.mouse.is_left_clicking = false;
+                if !f3_menu_handle_mouse_up(&mut state.engine_state) {
+                    state.app.on_mouse_up(&mut state.engine_state);
+                }
             }
             event.prevent_default();
         }) as Box<dyn FnMut(_)>);
         canvas.add_event_listener_with_callback(
-            "touchend",
+            name,
             touch_end_callback.as_ref().unchecked_ref(),
         )?;
         touch_end_callback.forget();
@@ -557,6 +729,11 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                 state.engine_state.keyboard.modifiers.command =
                     event.ctrl_key() || event.meta_key();
                 state.engine_state.keyboard.modifiers.alt = event.alt_key();
+                route_key_event(
+                    state.app.as_mut(),
+                    &mut state.engine_state,
+                    KeyEvent::down(&key, &event.code(), event.repeat()),
+                );
 
                 if key.len() == 1 {
                     if let Some(ch) = key.chars().next() {
@@ -607,6 +784,10 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                         state.app.on_key_char(&mut state.engine_state, '\u{2193}'); // ↓
                         event.prevent_default();
                     }
+                    "F3" if event.shift_key() => {
+                        crate::engine::profiler::export_default_trace();
+                        event.prevent_default();
+                    }
                     "F3" => {
                         state.engine_state.f3_menu.toggle_visible();
                         event.prevent_default();
@@ -678,7 +859,54 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
         paste_callback.forget();
     }
 
-    // Key up (modifier release for pan gesture)
+    // File drag-and-drop: dropped files are copied into the VFS by `dialog`, then handed to
+    // `on_file_drop` from the frame loop once their reads finish.
+    {
+        use web_sys::DragEvent;
+
+        let state_ptr_clone = state_ptr;
+        let dragover_callback = Closure::wrap(Box::new(move |event: DragEvent| unsafe {
+            // Without this the browser opens the file in place of the page.
+            event.prevent_default();
+            let state = &mut *state_ptr_clone;
+            if !state.file_hovering {
+                state.file_hovering = true;
+                state.app.on_file_hover(&mut state.engine_state, &[]);
+            }
+        }) as Box<dyn FnMut(_)>);
+        canvas.add_event_listener_with_callback(
+            "dragover",
+            dragover_callback.as_ref().unchecked_ref(),
+        )?;
+        dragover_callback.forget();
+
+        let state_ptr_clone = state_ptr;
+        let dragleave_callback = Closure::wrap(Box::new(move |_event: DragEvent| unsafe {
+            let state = &mut *state_ptr_clone;
+            if state.file_hovering {
+                state.file_hovering = false;
+                state.app.on_file_hover_cancel(&mut state.engine_state);
+            }
+        }) as Box<dyn FnMut(_)>);
+        canvas.add_event_listener_with_callback(
+            "dragleave",
+            dragleave_callback.as_ref().unchecked_ref(),
+        )?;
+        dragleave_callback.forget();
+
+        let state_ptr_clone = state_ptr;
+        let drop_callback = Closure::wrap(Box::new(move |event: DragEvent| unsafe {
+            event.prevent_default();
+            (*state_ptr_clone).file_hovering = false;
+            if let Some(files) = event.data_transfer().and_then(|d| d.files()) {
+                crate::dialog::import_browser_files(&files);
+            }
+        }) as Box<dyn FnMut(_)>);
+        canvas.add_event_listener_with_callback("drop", drop_callback.as_ref().unchecked_ref())?;
+        drop_callback.forget();
+    }
+
+    // Key up (raw key release + modifier release for pan gesture)
     {
         use web_sys::KeyboardEvent;
         let state_ptr_clone = state_ptr;
@@ -693,6 +921,11 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
             state.engine_state.keyboard.modifiers.shift = event.shift_key();
             state.engine_state.keyboard.modifiers.command = event.ctrl_key() || event.meta_key();
             state.engine_state.keyboard.modifiers.alt = event.alt_key();
+            route_key_event(
+                state.app.as_mut(),
+                &mut state.engine_state,
+                KeyEvent::up(&event.key(), &event.code()),
+            );
             if !(state.command_held && state.shift_held) {
                 state.frame_pan_dragging = false;
             }
@@ -761,19 +994,31 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                         .on_screen_size_change(&mut state.engine_state, width, height);
                 }
 
+                crate::engine::profiler::frame_boundary();
+                apply_window_commands(&mut state.engine_state, &anim_state.canvas);
+
+                let dropped = crate::dialog::take_imported_files();
+                if !dropped.is_empty() {
+                    state.app.on_file_drop(&mut state.engine_state, &dropped);
+                }
+
+                // No browser Gamepad API source yet; this delivers virtual pads.
+                poll_gamepads(state.app.as_mut(), &mut state.engine_state, None);
+
                 if state.engine_state.paused {
                     if state.engine_state.pending_step_ticks > 0 {
                         state.engine_state.pending_step_ticks =
                             state.engine_state.pending_step_ticks.saturating_sub(1);
-                        tick_frame_delta(
+                        step_app_tick(
+                            state.app.as_mut(),
                             &mut state.engine_state,
                             &mut anim_state.last_tick_instant,
                         );
-                        state.app.tick(&mut state.engine_state);
                         let shape = state.engine_state.frame.shape();
                         state.paused_base_w = shape[1];
                         state.paused_base_h = shape[0];
                         state.paused_base_frame = state.engine_state.frame.buffer_mut().to_vec();
+                        state.app.render(&mut state.engine_state);
                     } else {
                         anim_state.last_tick_instant = Some(crate::time::Instant::now());
                         if state.paused_base_frame.is_empty() {
@@ -807,13 +1052,26 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                         }
                     }
                 } else {
-                    tick_frame_delta(&mut state.engine_state, &mut anim_state.last_tick_instant);
                     // Tick the app first
-                    state.app.tick(&mut state.engine_state);
+                    let steps = advance_app_frame(
+                        state.app.as_mut(),
+                        &mut state.engine_state,
+                        &mut anim_state.last_tick_instant,
+                    );
                     let shape = state.engine_state.frame.shape();
-                    state.paused_base_w = shape[1];
-                    state.paused_base_h = shape[0];
-                    state.paused_base_frame = state.engine_state.frame.buffer_mut().to_vec();
+                    if steps > 0 || state.paused_base_frame.is_empty() {
+                        state.paused_base_w = shape[1];
+                        state.paused_base_h = shape[0];
+                        state.paused_base_frame = state.engine_state.frame.buffer_mut().to_vec();
+                    } else if state.paused_base_w == shape[1] && state.paused_base_h == shape[0] {
+                        // Fixed timestep with no tick due: start from the last simulated frame.
+                        state
+                            .engine_state
+                            .frame
+                            .buffer_mut()
+                            .copy_from_slice(&state.paused_base_frame);
+                    }
+                    state.app.render(&mut state.engine_state);
                 }
 
                 tick_frame_view_zoom(&mut state.engine_state);
@@ -849,20 +1107,32 @@ pub fn run_web(app: Box<dyn Application>) -> Result<(), JsValue> {
                     );
                 }
 
-                tick_f3_menu(&mut state.engine_state);
+                {
+                    crate::profile_scope!("f3 overlay");
+                    tick_f3_menu(&mut state.engine_state);
+                }
+                crate::engine::windows::discard_window_requests();
 
                 // Render to canvas. During live browser resizes, the browser can briefly reject a
                 // transient backing store; keep RAF alive and try again next frame.
-                let buffer = state.engine_state.frame_buffer_mut();
-                let data = wasm_bindgen::Clamped(&buffer[..]);
-                match ImageData::new_with_u8_clamped_array_and_sh(data, width, height) {
-                    Ok(image_data) => {
-                        if let Err(err) = anim_state.context.put_image_data(&image_data, 0.0, 0.0) {
-                            crate::print(&format!("xos wasm: put_image_data failed: {:?}", err));
+                {
+                    crate::profile_scope!("present");
+                    let buffer = state.engine_state.frame_buffer_mut();
+                    let data = wasm_bindgen::Clamped(&buffer[..]);
+                    match ImageData::new_with_u8_clamped_array_and_sh(data, width, height) {
+                        Ok(image_data) => {
+                            if let Err(err) =
+                                anim_state.context.put_image_data(&image_data, 0.0, 0.0)
+                            {
+                                crate::print(&format!(
+                                    "xos wasm: put_image_data failed: {:?}",
+                                    err
+                                ));
+                            }
+                        }
+                        Err(err) => {
+                            crate::print(&format!("xos wasm: ImageData failed: {:?}", err));
                         }
-                    }
-                    Err(err) => {
-                        crate::print(&format!("xos wasm: ImageData failed: {:?}", err));
                     }
                 }
 
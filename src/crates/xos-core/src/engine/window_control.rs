@@ -0,0 +1,143 @@
+//! Runtime control of the app's own OS window: title, size, fullscreen, always-on-top, cursor
+//! visibility / lock, plus the DPI scale and raw mouse motion read back from the host.
+//!
+//! Apps push [`WindowCommand`]s onto [`EngineState::window`]; the host applies them once per frame
+//! and writes what actually took effect into [`WindowControl::info`]. Hosts apply what they can:
+//! the desktop host everything, the web host title / fullscreen / cursor, iOS and headless nothing.
+//! Commands issued outside the engine (Python module top level) go through
+//! [`request_window_command`] and reach the main window on its next frame.
+//!
+//! [`EngineState::window`]: crate::engine::EngineState::window
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowCommand {
+    SetTitle(String),
+    /// Inner size in physical pixels (ignored while fullscreen).
+    SetSize {
+        width: u32,
+        height: u32,
+    },
+    /// Borderless fullscreen on the window's current monitor.
+    SetFullscreen(bool),
+    SetAlwaysOnTop(bool),
+    SetCursorVisible(bool),
+    /// Keep the pointer inside the window and hidden; motion keeps arriving through
+    /// [`WindowInfo::mouse_motion`] (FPS-style mouse look).
+    SetCursorLocked(bool),
+}
+
+/// What the host last applied or observed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowInfo {
+    pub title: String,
+    pub fullscreen: bool,
+    pub always_on_top: bool,
+    pub cursor_visible: bool,
+    pub cursor_locked: bool,
+    /// Physical pixels per logical pixel (`devicePixelRatio` on the web).
+    pub scale_factor: f64,
+    /// Raw mouse motion in physical pixels since the previous tick. Unlike `mouse.dx` it keeps
+    /// counting while the cursor is locked.
+    pub mouse_motion: (f32, f32),
+}
+
+impl Default for WindowInfo {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            fullscreen: false,
+            always_on_top: false,
+            cursor_visible: true,
+            cursor_locked: false,
+            scale_factor: 1.0,
+            mouse_motion: (0.0, 0.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WindowControl {
+    commands: Vec<WindowCommand>,
+    pub info: WindowInfo,
+}
+
+// Requests come from the engine thread (Python runs there), like the host that applies them.
+thread_local! {
+    static REQUESTED_COMMANDS: RefCell<Vec<WindowCommand>> = const { RefCell::new(Vec::new()) };
+}
+static MAIN_WINDOW_INFO: Mutex<Option<WindowInfo>> = Mutex::new(None);
+
+/// Queue a command for the main window from outside the engine (applied on its next frame).
+pub fn request_window_command(command: WindowCommand) {
+    REQUESTED_COMMANDS.with(|queue| queue.borrow_mut().push(command));
+}
+
+/// The main window's [`WindowInfo`] as of its last frame, readable from any thread; `None` before
+/// a host has started.
+pub fn main_window_info() -> Option<WindowInfo> {
+    MAIN_WINDOW_INFO.lock().ok().and_then(|info| info.clone())
+}
+
+impl WindowControl {
+    /// Applied by the host on its next frame.
+    pub fn push(&mut self, command: WindowCommand) {
+        self.commands.push(command);
+    }
+
+    /// Commands to apply this frame. The main window also takes [`request_window_command`]s
+    /// (queued first, so they act as defaults the app can override from `setup`).
+    pub fn take_commands(&mut self, main_window: bool) -> Vec<WindowCommand> {
+        let mut commands = Vec::new();
+        if main_window {
+            commands = REQUESTED_COMMANDS.with(|queue| std::mem::take(&mut *queue.borrow_mut()));
+        }
+        commands.append(&mut self.commands);
+        commands
+    }
+
+    /// Hosts without a desktop or browser window (headless, iOS, the test harness): drop this
+    /// frame's commands, leaving `info` at its defaults.
+    pub fn discard_commands(&mut self) {
+        self.take_commands(true);
+    }
+
+    /// Host side: accumulate raw pointer motion for [`WindowInfo::mouse_motion`].
+    pub fn add_mouse_motion(&mut self, dx: f32, dy: f32) {
+        self.info.mouse_motion.0 += dx;
+        self.info.mouse_motion.1 += dy;
+    }
+
+    /// Host side, main window: make `info` visible to [`main_window_info`].
+    pub fn publish(&self) {
+        if let Ok(mut info) = MAIN_WINDOW_INFO.lock() {
+            *info = Some(self.info.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn main_window_takes_requests_before_its_own_commands() {
+        let mut control = WindowControl::default();
+        request_window_command(WindowCommand::SetTitle("outside".to_string()));
+        control.push(WindowCommand::SetFullscreen(true));
+
+        let mut secondary = WindowControl::default();
+        assert!(secondary.take_commands(false).is_empty());
+
+        assert_eq!(
+            control.take_commands(true),
+            vec![
+                WindowCommand::SetTitle("outside".to_string()),
+                WindowCommand::SetFullscreen(true),
+            ]
+        );
+        assert!(control.take_commands(true).is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Secondary OS windows opened from a running app. Each window gets its own [`EngineState`] (frame,
+//! mouse, keyboard, F3 menu) and its own [`Application`], so an inspector or control panel can sit
+//! beside a fullscreen visualization in the same process.
+//!
+//! Apps queue requests with [`open_window`] / [`close_window`] from `setup`, `tick` or any input
+//! callback; the desktop windowed host applies them once per frame. Hosts with a single surface
+//! (headless, web, iOS, the test harness) drop them.
+//!
+//! [`EngineState`]: crate::engine::EngineState
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+
+use crate::engine::Application;
+use crate::monitors::MonitorDescriptor;
+
+/// Where a new window goes on the desktop.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum WindowPlacement {
+    /// Let the OS pick.
+    #[default]
+    Auto,
+    /// Centered on monitor `index` (the order of [`crate::monitors::system_monitors`], i.e.
+    /// `xos.system.monitors`).
+    Monitor(usize),
+    /// Outer top-left corner at desktop pixel coordinates.
+    At { x: i32, y: i32 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowSpec {
+    pub title: String,
+    /// Inner size in physical pixels (ignored when `fullscreen`).
+    pub width: u32,
+    pub height: u32,
+    pub placement: WindowPlacement,
+    /// Borderless fullscreen on the monitor picked by `placement`.
+    pub fullscreen: bool,
+    pub decorations: bool,
+    pub always_on_top: bool,
+}
+
+impl WindowSpec {
+    pub fn new(title: impl Into<String>, width: u32, height: u32) -> Self {
+        Self {
+            title: title.into(),
+            width: width.max(1),
+            height: height.max(1),
+            placement: WindowPlacement::Auto,
+            fullscreen: false,
+            decorations: true,
+            always_on_top: false,
+        }
+    }
+
+    /// Outer position for `placement`, or `None` to let the OS decide (also for an unknown monitor).
+    pub fn origin(&self, monitors: &[MonitorDescriptor]) -> Option<(i32, i32)> {
+        match self.placement {
+            WindowPlacement::Auto => None,
+            WindowPlacement::At { x, y } => Some((x, y)),
+            WindowPlacement::Monitor(index) => {
+                let m = monitors.get(index)?;
+                let free_w = m.native_width.saturating_sub(self.width) as i32;
+                let free_h = m.native_height.saturating_sub(self.height) as i32;
+                Some((m.origin_x + free_w / 2, m.origin_y + free_h / 2))
+            }
+        }
+    }
+
+    /// The monitor this window lands on: the requested one, the one containing an explicit
+    /// position, else the primary.
+    pub fn monitor<'a>(&self, monitors: &'a [MonitorDescriptor]) -> Option<&'a MonitorDescriptor> {
+        let primary = || monitors.iter().find(|m| m.is_primary).or(monitors.first());
+        match self.placement {
+            WindowPlacement::Auto => primary(),
+            WindowPlacement::Monitor(index) => monitors.get(index),
+            WindowPlacement::At { x, y } => monitors
+                .iter()
+                .find(|m| {
+                    x >= m.origin_x
+                        && y >= m.origin_y
+                        && x < m.origin_x + m.native_width as i32
+                        && y < m.origin_y + m.native_height as i32
+                })
+                .or_else(primary),
+        }
+    }
+}
+
+/// Identifies a window opened with [`open_window`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WindowHandle(pub u64);
+
+pub enum WindowRequest {
+    Open {
+        handle: WindowHandle,
+        spec: WindowSpec,
+        app: Box<dyn Application>,
+    },
+    Close(WindowHandle),
+}
+
+// Apps are not `Send` (Python apps own interpreter objects); requests stay on the engine thread.
+thread_local! {
+    static REQUESTS: RefCell<Vec<WindowRequest>> = const { RefCell::new(Vec::new()) };
+    static OPEN: RefCell<HashSet<WindowHandle>> = RefCell::new(HashSet::new());
+    static NEXT_HANDLE: Cell<u64> = const { Cell::new(1) };
+    static WARNED_UNSUPPORTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Queue a new window running `app`; it is created (and `app.setup` called) on the next frame.
+pub fn open_window(spec: WindowSpec, app: Box<dyn Application>) -> WindowHandle {
+    let handle = reserve_window_handle();
+    open_reserved_window(handle, spec, app);
+    handle
+}
+
+/// A handle for a window whose app is supplied later with [`open_reserved_window`]; it already
+/// counts as open (the Python bindings build the app on the next frame).
+pub fn reserve_window_handle() -> WindowHandle {
+    let handle = WindowHandle(NEXT_HANDLE.with(|n| n.replace(n.get() + 1)));
+    OPEN.with(|open| open.borrow_mut().insert(handle));
+    handle
+}
+
+/// [`open_window`] for a reserved handle; does nothing if it was closed in the meantime.
+pub fn open_reserved_window(handle: WindowHandle, spec: WindowSpec, app: Box<dyn Application>) {
+    if is_window_open(handle) {
+        REQUESTS.with(|r| {
+            r.borrow_mut()
+                .push(WindowRequest::Open { handle, spec, app })
+        });
+    }
+}
+
+/// Close a window from [`open_window`]; its app gets `prepare_shutdown`. Unknown handles are ignored.
+pub fn close_window(handle: WindowHandle) {
+    OPEN.with(|open| open.borrow_mut().remove(&handle));
+    REQUESTS.with(|r| r.borrow_mut().push(WindowRequest::Close(handle)));
+}
+
+/// False once the window was closed by the app or the user (or dropped by the host).
+pub fn is_window_open(handle: WindowHandle) -> bool {
+    OPEN.with(|open| open.borrow().contains(&handle))
+}
+
+/// Requests queued since the last call (host side).
+pub fn take_window_requests() -> Vec<WindowRequest> {
+    REQUESTS.with(|r| std::mem::take(&mut *r.borrow_mut()))
+}
+
+/// Host side: the user closed `handle` (or it failed to open).
+pub fn mark_window_closed(handle: WindowHandle) {
+    OPEN.with(|open| open.borrow_mut().remove(&handle));
+}
+
+/// For hosts that cannot open windows: drop pending requests (and their apps), warning once.
+pub fn discard_window_requests() {
+    for request in take_window_requests() {
+        if let WindowRequest::Open { handle, .. } = request {
+            mark_window_closed(handle);
+            if !WARNED_UNSUPPORTED.with(|w| w.replace(true)) {
+                eprintln!("⚠️ extra windows need the desktop windowed host; ignoring open_window");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32, w: u32, h: u32, primary: bool) -> MonitorDescriptor {
+        MonitorDescriptor {
+            native_width: w,
+            native_height: h,
+            origin_x: x,
+            origin_y: y,
+            refresh_rate_hz: 60.0,
+            is_primary: primary,
+            name: String::new(),
+            native_id: String::new(),
+            stream_width: w,
+            stream_height: h,
+        }
+    }
+
+    #[test]
+    fn placement_picks_origin_and_monitor() {
+        let monitors = [
+            monitor(0, 0, 1920, 1080, true),
+            monitor(1920, -200, 1280, 1024, false),
+        ];
+        let mut spec = WindowSpec::new("inspector", 400, 300);
+        assert_eq!(spec.origin(&monitors), None);
+        assert_eq!(spec.monitor(&monitors).unwrap().origin_x, 0);
+
+        spec.placement = WindowPlacement::Monitor(1);
+        assert_eq!(spec.origin(&monitors), Some((1920 + 440, -200 + 362)));
+        assert_eq!(spec.monitor(&monitors).unwrap().origin_x, 1920);
+
+        spec.placement = WindowPlacement::Monitor(5);
+        assert_eq!(spec.origin(&monitors), None);
+        assert!(spec.monitor(&monitors).is_none());
+
+        spec.placement = WindowPlacement::At { x: 2000, y: 10 };
+        assert_eq!(spec.origin(&monitors), Some((2000, 10)));
+        assert_eq!(spec.monitor(&monitors).unwrap().origin_x, 1920);
+        spec.placement = WindowPlacement::At { x: -50, y: 10 };
+        assert!(spec.monitor(&monitors).unwrap().is_primary);
+    }
+}
@@ -101,6 +101,8 @@ mod wasm {
     const KEY_PREFIX: &str = "xos.fs.v1";
     const KIND_FILE: &str = "file";
     const KIND_DIR: &str = "dir";
+    /// Non-UTF-8 files (dropped images, audio), base64-encoded.
+    const KIND_BIN: &str = "bin";
 
     fn normalize(path: &str) -> String {
         let replaced = path.replace('\\', "/");
@@ -143,6 +145,49 @@ mod wasm {
             .map_err(|e| format!("xos.fs: write localStorage failed: {e:?}"))
     }
 
+    fn remove_item(kind: &str, path: &str) {
+        if let Ok(storage) = storage() {
+            let _ = storage.remove_item(&key(kind, path));
+        }
+    }
+
+    const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    fn base64_encode(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let n = (chunk[0] as u32) << 16
+                | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+                | *chunk.get(2).unwrap_or(&0) as u32;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    fn base64_decode(text: &str) -> Result<Vec<u8>, String> {
+        let mut out = Vec::with_capacity(text.len() / 4 * 3);
+        let (mut acc, mut bits) = (0u32, 0u32);
+        for c in text.bytes().filter(|&c| c != b'=') {
+            let value = BASE64
+                .iter()
+                .position(|&b| b == c)
+                .ok_or_else(|| "xos.fs: corrupt binary entry in localStorage".to_string())?;
+            acc = acc << 6 | value as u32;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((acc >> bits) as u8);
+            }
+        }
+        Ok(out)
+    }
+
     fn parent_dirs(path: &str) -> Vec<String> {
         let norm = normalize(path);
         let mut dirs = Vec::new();
@@ -165,7 +210,9 @@ mod wasm {
     }
 
     pub fn exists(path: &str) -> bool {
-        get_item(KIND_FILE, path).is_some() || get_item(KIND_DIR, path).is_some()
+        get_item(KIND_FILE, path).is_some()
+            || get_item(KIND_BIN, path).is_some()
+            || get_item(KIND_DIR, path).is_some()
     }
 
     pub fn is_dir(path: &str) -> bool {
@@ -175,7 +222,7 @@ mod wasm {
 
     pub fn create_dir_all(path: &str) -> Result<(), String> {
         let norm = normalize(path);
-        if get_item(KIND_FILE, &norm).is_some() {
+        if get_item(KIND_FILE, &norm).is_some() || get_item(KIND_BIN, &norm).is_some() {
             return Err(format!(
                 "cannot makedirs {path:?}: exists and is not a directory"
             ));
@@ -198,6 +245,9 @@ mod wasm {
     }
 
     pub fn read(path: &str) -> Result<Vec<u8>, String> {
+        if let Some(value) = get_item(KIND_BIN, path) {
+            return base64_decode(&value);
+        }
         read_to_string(path).map(|s| s.into_bytes())
     }
 
@@ -205,18 +255,29 @@ mod wasm {
         if let Some(value) = get_item(KIND_FILE, path) {
             return Ok(value);
         }
+        if let Some(value) = get_item(KIND_BIN, path) {
+            return String::from_utf8(base64_decode(&value)?)
+                .map_err(|_| format!("xos.fs: {path:?} is not UTF-8 text"));
+        }
         let norm = normalize(path);
         let url = format!("/{norm}");
         fetch_text_blocking(&url)
     }
 
     pub fn write(path: &str, bytes: &[u8]) -> Result<(), String> {
-        let text = std::str::from_utf8(bytes)
-            .map_err(|_| format!("xos.fs: wasm storage currently expects UTF-8 for {path:?}"))?;
         for dir in parent_dirs(path) {
             create_dir_all(&dir)?;
         }
-        set_item(KIND_FILE, path, text)
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                remove_item(KIND_BIN, path);
+                set_item(KIND_FILE, path, text)
+            }
+            Err(_) => {
+                remove_item(KIND_FILE, path);
+                set_item(KIND_BIN, path, &base64_encode(bytes))
+            }
+        }
     }
 
     pub fn fetch_text_blocking(url: &str) -> Result<String, String> {
@@ -15,6 +15,7 @@ pub mod ai;
 pub mod blank_app;
 pub mod burn_raster;
 pub mod coder_log;
+pub mod dialog;
 pub mod engine;
 pub mod fs;
 pub mod ios_log;
@@ -355,6 +355,7 @@ pub fn blur_rgba_framebuffer(
     percent: f32,
     scratch: &mut [u8],
 ) {
+    crate::profile_scope!("raster:blur");
     if width == 0 || height == 0 {
         return;
     }
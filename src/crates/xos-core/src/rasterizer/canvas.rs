@@ -0,0 +1,831 @@
+//! Canvas state stack over an RGBA8 framebuffer: `save` / `restore`, affine transforms,
+//! rectangular and path clips, and offscreen layers composited with opacity.
+//!
+//! Shape helpers in [`super::shapes`] stay pure `(&mut [u8], width, height, …)` functions in
+//! absolute pixels. A [`Canvas`] sits in front of them: local points go through
+//! [`Canvas::transform`], and drawing is redirected into layer buffers ([`Canvas::target`]) whose
+//! clip mask / opacity are applied when the layer is composited back on [`Canvas::restore`].
+
+use super::shapes::circles::draw_circle_cpu;
+use super::shapes::rectangles::fill_rect_buffer;
+
+/// Vertical sub-scanlines per pixel row for path coverage (horizontal coverage is exact).
+const COVERAGE_SUBSAMPLES: usize = 4;
+/// Segments used when flattening one quadratic / cubic curve.
+const CURVE_SEGMENTS: usize = 16;
+/// Segments used when flattening a full ellipse.
+const ELLIPSE_SEGMENTS: usize = 48;
+
+/// 2D affine transform `(x, y) → (a·x + c·y + e, b·x + d·y + f)` (HTML canvas layout).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translation(tx: f32, ty: f32) -> Self {
+        Self {
+            e: tx,
+            f: ty,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scaling(sx: f32, sy: f32) -> Self {
+        Self {
+            a: sx,
+            d: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Clockwise rotation in screen space (y points down), in radians.
+    pub fn rotation(radians: f32) -> Self {
+        let (s, c) = radians.sin_cos();
+        Self {
+            a: c,
+            b: s,
+            c: -s,
+            d: c,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// `self ∘ local`: `local` is applied first, then `self` (canvas `translate` / `scale` / `rotate`).
+    pub fn concat(&self, local: &Affine) -> Affine {
+        Affine {
+            a: self.a * local.a + self.c * local.b,
+            b: self.b * local.a + self.d * local.b,
+            c: self.a * local.c + self.c * local.d,
+            d: self.b * local.c + self.d * local.d,
+            e: self.a * local.e + self.c * local.f + self.e,
+            f: self.b * local.e + self.d * local.f + self.f,
+        }
+    }
+
+    #[inline]
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    pub fn invert(&self) -> Option<Affine> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv = 1.0 / det;
+        Some(Affine {
+            a: self.d * inv,
+            b: -self.b * inv,
+            c: -self.c * inv,
+            d: self.a * inv,
+            e: (self.c * self.f - self.d * self.e) * inv,
+            f: (self.b * self.e - self.a * self.f) * inv,
+        })
+    }
+
+    #[inline]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// No rotation / skew: rectangles stay axis-aligned.
+    #[inline]
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0
+    }
+
+    /// Rotation + uniform scale only: circles stay circles.
+    #[inline]
+    pub fn is_similarity(&self) -> bool {
+        (self.a - self.d).abs() < 1e-6 && (self.b + self.c).abs() < 1e-6
+    }
+
+    /// Geometric-mean scale factor `sqrt(|det|)` — used for radii, stroke widths and font sizes.
+    #[inline]
+    pub fn mean_scale(&self) -> f32 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+}
+
+/// Polygonal path; curves are flattened when added. Subpaths are implicitly closed when filled.
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    subpaths: Vec<Vec<(f32, f32)>>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polygon from a point list (one subpath).
+    pub fn polygon(points: &[(f32, f32)]) -> Self {
+        Self {
+            subpaths: vec![points.to_vec()],
+        }
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) -> &mut Self {
+        self.subpaths.push(vec![(x, y)]);
+        self
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) -> &mut Self {
+        match self.subpaths.last_mut() {
+            Some(sp) => sp.push((x, y)),
+            None => self.subpaths.push(vec![(x, y)]),
+        }
+        self
+    }
+
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) -> &mut Self {
+        let (x0, y0) = self.current_point();
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f32 / CURVE_SEGMENTS as f32;
+            let mt = 1.0 - t;
+            self.line_to(
+                mt * mt * x0 + 2.0 * mt * t * cx + t * t * x,
+                mt * mt * y0 + 2.0 * mt * t * cy + t * t * y,
+            );
+        }
+        self
+    }
+
+    pub fn cubic_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> &mut Self {
+        let (x0, y0) = self.current_point();
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f32 / CURVE_SEGMENTS as f32;
+            let mt = 1.0 - t;
+            let w0 = mt * mt * mt;
+            let w1 = 3.0 * mt * mt * t;
+            let w2 = 3.0 * mt * t * t;
+            let w3 = t * t * t;
+            self.line_to(
+                w0 * x0 + w1 * c1x + w2 * c2x + w3 * x,
+                w0 * y0 + w1 * c1y + w2 * c2y + w3 * y,
+            );
+        }
+        self
+    }
+
+    /// Close the current subpath; the next `line_to` without `move_to` starts a new one here.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(&first) = self.subpaths.last().and_then(|sp| sp.first()) {
+            self.subpaths.push(vec![first]);
+        }
+        self
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, w: f32, h: f32) -> &mut Self {
+        self.move_to(x, y)
+            .line_to(x + w, y)
+            .line_to(x + w, y + h)
+            .line_to(x, y + h)
+    }
+
+    pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) -> &mut Self {
+        self.move_to(cx + rx, cy);
+        for i in 1..ELLIPSE_SEGMENTS {
+            let t = i as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+            self.line_to(cx + rx * t.cos(), cy + ry * t.sin());
+        }
+        self
+    }
+
+    pub fn circle(&mut self, cx: f32, cy: f32, r: f32) -> &mut Self {
+        self.ellipse(cx, cy, r, r)
+    }
+
+    pub fn subpaths(&self) -> &[Vec<(f32, f32)>] {
+        &self.subpaths
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subpaths.iter().all(|sp| sp.len() < 3)
+    }
+
+    pub fn transformed(&self, t: &Affine) -> Path {
+        Path {
+            subpaths: self
+                .subpaths
+                .iter()
+                .map(|sp| sp.iter().map(|&(x, y)| t.apply(x, y)).collect())
+                .collect(),
+        }
+    }
+
+    fn current_point(&self) -> (f32, f32) {
+        self.subpaths
+            .last()
+            .and_then(|sp| sp.last().copied())
+            .unwrap_or((0.0, 0.0))
+    }
+}
+
+/// Antialiased coverage (0..=255) over a device-space bounding box; zero outside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipMask {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    coverage: Vec<u8>,
+}
+
+impl ClipMask {
+    pub fn empty() -> Self {
+        Self {
+            x0: 0,
+            y0: 0,
+            x1: 0,
+            y1: 0,
+            coverage: Vec::new(),
+        }
+    }
+
+    /// Nonzero-winding fill of a device-space `path`, clipped to `width × height`.
+    pub fn from_path(path: &Path, width: usize, height: usize) -> Self {
+        let mut min_x = f32::INFINITY;
+        let mut min_y = f32::INFINITY;
+        let mut max_x = f32::NEG_INFINITY;
+        let mut max_y = f32::NEG_INFINITY;
+        let mut edges: Vec<(f32, f32, f32, f32)> = Vec::new();
+        for sp in path.subpaths() {
+            if sp.len() < 3 {
+                continue;
+            }
+            for (i, &(xa, ya)) in sp.iter().enumerate() {
+                let (xb, yb) = sp[(i + 1) % sp.len()];
+                if !(xa.is_finite() && ya.is_finite() && xb.is_finite() && yb.is_finite()) {
+                    continue;
+                }
+                min_x = min_x.min(xa);
+                max_x = max_x.max(xa);
+                min_y = min_y.min(ya);
+                max_y = max_y.max(ya);
+                if ya != yb {
+                    edges.push((xa, ya, xb, yb));
+                }
+            }
+        }
+        if edges.is_empty() || width == 0 || height == 0 {
+            return Self::empty();
+        }
+        let x0 = (min_x.floor().max(0.0) as usize).min(width);
+        let x1 = (max_x.ceil().max(0.0) as usize).min(width);
+        let y0 = (min_y.floor().max(0.0) as usize).min(height);
+        let y1 = (max_y.ceil().max(0.0) as usize).min(height);
+        if x0 >= x1 || y0 >= y1 {
+            return Self::empty();
+        }
+
+        let bw = x1 - x0;
+        let bh = y1 - y0;
+        let mut acc = vec![0.0f32; bw * bh];
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        let sub_weight = 1.0 / COVERAGE_SUBSAMPLES as f32;
+        for row in 0..bh {
+            let py = (y0 + row) as f32;
+            let acc_row = &mut acc[row * bw..(row + 1) * bw];
+            for s in 0..COVERAGE_SUBSAMPLES {
+                let sy = py + (s as f32 + 0.5) * sub_weight;
+                crossings.clear();
+                for &(xa, ya, xb, yb) in &edges {
+                    let (lo, hi, dir) = if ya < yb { (ya, yb, 1) } else { (yb, ya, -1) };
+                    if sy < lo || sy >= hi {
+                        continue;
+                    }
+                    let t = (sy - ya) / (yb - ya);
+                    crossings.push((xa + t * (xb - xa), dir));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut winding = 0;
+                let mut span_start = 0.0f32;
+                for &(x, dir) in &crossings {
+                    let prev = winding;
+                    winding += dir;
+                    if prev == 0 && winding != 0 {
+                        span_start = x;
+                    } else if prev != 0 && winding == 0 {
+                        add_span_coverage(acc_row, x0, x1, span_start, x, sub_weight);
+                    }
+                }
+            }
+        }
+
+        Self {
+            x0,
+            y0,
+            x1,
+            y1,
+            coverage: acc
+                .into_iter()
+                .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+                .collect(),
+        }
+    }
+
+    /// `(x0, y0, x1, y1)` device-space bounds (exclusive max).
+    pub fn bounds(&self) -> (usize, usize, usize, usize) {
+        (self.x0, self.y0, self.x1, self.y1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x0 >= self.x1 || self.y0 >= self.y1
+    }
+
+    #[inline]
+    pub fn coverage(&self, x: usize, y: usize) -> u8 {
+        if x < self.x0 || x >= self.x1 || y < self.y0 || y >= self.y1 {
+            return 0;
+        }
+        self.coverage[(y - self.y0) * (self.x1 - self.x0) + (x - self.x0)]
+    }
+
+    /// Pixelwise product of both coverages over the shared bounds.
+    pub fn intersect(&self, other: &ClipMask) -> ClipMask {
+        let x0 = self.x0.max(other.x0);
+        let y0 = self.y0.max(other.y0);
+        let x1 = self.x1.min(other.x1);
+        let y1 = self.y1.min(other.y1);
+        if x0 >= x1 || y0 >= y1 {
+            return Self::empty();
+        }
+        let mut coverage = Vec::with_capacity((x1 - x0) * (y1 - y0));
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let a = self.coverage(x, y) as u32;
+                let b = other.coverage(x, y) as u32;
+                coverage.push(((a * b + 127) / 255) as u8);
+            }
+        }
+        Self {
+            x0,
+            y0,
+            x1,
+            y1,
+            coverage,
+        }
+    }
+}
+
+/// Accumulate the horizontal overlap of `[start, end)` with each pixel column of one sub-scanline.
+fn add_span_coverage(row: &mut [f32], x0: usize, x1: usize, start: f32, end: f32, weight: f32) {
+    let start = start.max(x0 as f32);
+    let end = end.min(x1 as f32);
+    if start >= end {
+        return;
+    }
+    let first = start.floor() as usize;
+    let last = (end.ceil() as usize).min(x1);
+    for px in first..last {
+        let overlap = end.min(px as f32 + 1.0) - start.max(px as f32);
+        if overlap > 0.0 {
+            row[px - x0] += overlap * weight;
+        }
+    }
+}
+
+/// Source-over blend `color` into `buffer` through `mask` coverage.
+pub fn fill_mask_buffer(buffer: &mut [u8], width: usize, mask: &ClipMask, color: (u8, u8, u8, u8)) {
+    let (x0, y0, x1, y1) = mask.bounds();
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let cov = mask.coverage(x, y) as u32;
+            if cov == 0 {
+                continue;
+            }
+            let alpha = (cov * color.3 as u32 + 127) / 255;
+            let idx = (y * width + x) * 4;
+            if idx + 3 >= buffer.len() {
+                continue;
+            }
+            blend_px(&mut buffer[idx..idx + 4], [color.0, color.1, color.2], alpha);
+        }
+    }
+}
+
+#[inline]
+fn blend_px(dst: &mut [u8], rgb: [u8; 3], alpha: u32) {
+    if alpha >= 255 {
+        dst[0] = rgb[0];
+        dst[1] = rgb[1];
+        dst[2] = rgb[2];
+        dst[3] = 255;
+        return;
+    }
+    let inv = 255 - alpha;
+    dst[0] = ((rgb[0] as u32 * alpha + dst[0] as u32 * inv + 127) / 255) as u8;
+    dst[1] = ((rgb[1] as u32 * alpha + dst[1] as u32 * inv + 127) / 255) as u8;
+    dst[2] = ((rgb[2] as u32 * alpha + dst[2] as u32 * inv + 127) / 255) as u8;
+    dst[3] = (alpha + (dst[3] as u32 * inv + 127) / 255).min(255) as u8;
+}
+
+#[derive(Debug)]
+enum LayerKind {
+    /// Starts as a copy of its parent; composited back through the mask (outside stays untouched).
+    Clip(ClipMask),
+    /// Starts transparent; composited source-over with `opacity`.
+    Group { opacity: f32 },
+}
+
+#[derive(Debug)]
+struct Layer {
+    kind: LayerKind,
+    pixels: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct SavedState {
+    transform: Affine,
+    clip: Option<ClipMask>,
+    layer_depth: usize,
+}
+
+/// Transform / clip / layer stack for one `width × height` RGBA8 framebuffer.
+///
+/// The framebuffer itself is passed into each call that reads or composites pixels, so a canvas
+/// can live beside a [`crate::engine::FrameState`] (or a raw Python frame pointer) without owning it.
+#[derive(Debug)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    transform: Affine,
+    /// Effective device-space clip (intersection of every active clip), for hit tests / queries.
+    clip: Option<ClipMask>,
+    saved: Vec<SavedState>,
+    layers: Vec<Layer>,
+    /// Released layer buffers reused by the next `clip_*` / `save_layer`.
+    pool: Vec<Vec<u8>>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            transform: Affine::IDENTITY,
+            clip: None,
+            saved: Vec::new(),
+            layers: Vec::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Drop all state (without compositing) and adopt new framebuffer dimensions.
+    pub fn reset(&mut self, width: usize, height: usize) {
+        if width != self.width || height != self.height {
+            self.pool.clear();
+        }
+        for layer in self.layers.drain(..) {
+            self.pool.push(layer.pixels);
+        }
+        self.width = width;
+        self.height = height;
+        self.transform = Affine::IDENTITY;
+        self.clip = None;
+        self.saved.clear();
+    }
+
+    /// Number of unmatched `save` / `save_layer` calls.
+    pub fn depth(&self) -> usize {
+        self.saved.len()
+    }
+
+    /// True while drawing is redirected into an offscreen layer.
+    pub fn has_layers(&self) -> bool {
+        !self.layers.is_empty()
+    }
+
+    pub fn transform(&self) -> Affine {
+        self.transform
+    }
+
+    pub fn set_transform(&mut self, t: Affine) {
+        self.transform = t;
+    }
+
+    pub fn reset_transform(&mut self) {
+        self.transform = Affine::IDENTITY;
+    }
+
+    pub fn concat(&mut self, local: &Affine) {
+        self.transform = self.transform.concat(local);
+    }
+
+    pub fn translate(&mut self, tx: f32, ty: f32) {
+        self.concat(&Affine::translation(tx, ty));
+    }
+
+    pub fn scale(&mut self, sx: f32, sy: f32) {
+        self.concat(&Affine::scaling(sx, sy));
+    }
+
+    pub fn rotate(&mut self, radians: f32) {
+        self.concat(&Affine::rotation(radians));
+    }
+
+    /// Local → device pixel coordinates.
+    #[inline]
+    pub fn map_point(&self, x: f32, y: f32) -> (f32, f32) {
+        self.transform.apply(x, y)
+    }
+
+    /// Device pixel → local coordinates (e.g. for pointer hit tests inside a transformed widget).
+    pub fn unmap_point(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        self.transform.invert().map(|inv| inv.apply(x, y))
+    }
+
+    /// Current effective clip in device space (`None` = unclipped).
+    pub fn clip(&self) -> Option<&ClipMask> {
+        self.clip.as_ref()
+    }
+
+    pub fn save(&mut self) {
+        self.saved.push(SavedState {
+            transform: self.transform,
+            clip: self.clip.clone(),
+            layer_depth: self.layers.len(),
+        });
+    }
+
+    /// Pop the last `save` / `save_layer`, compositing any layers opened since. Returns `false`
+    /// when there is nothing to restore.
+    pub fn restore(&mut self, frame: &mut [u8]) -> bool {
+        let Some(state) = self.saved.pop() else {
+            return false;
+        };
+        while self.layers.len() > state.layer_depth {
+            self.pop_layer(frame);
+        }
+        self.transform = state.transform;
+        self.clip = state.clip;
+        true
+    }
+
+    /// Composite every open layer and return to an empty stack with the identity transform.
+    pub fn flush(&mut self, frame: &mut [u8]) {
+        while !self.layers.is_empty() {
+            self.pop_layer(frame);
+        }
+        self.saved.clear();
+        self.transform = Affine::IDENTITY;
+        self.clip = None;
+    }
+
+    /// Intersect the clip with a local-space rectangle (transformed by the current matrix).
+    pub fn clip_rect(&mut self, frame: &[u8], x: f32, y: f32, w: f32, h: f32) {
+        let mut path = Path::new();
+        path.rect(x, y, w, h);
+        self.clip_path(frame, &path);
+    }
+
+    /// Intersect the clip with a local-space path. Everything drawn until the matching
+    /// [`Canvas::restore`] lands in a clip layer and only its covered pixels reach the parent.
+    pub fn clip_path(&mut self, frame: &[u8], path: &Path) {
+        let device = path.transformed(&self.transform);
+        let mask = ClipMask::from_path(&device, self.width, self.height);
+        self.clip = Some(match &self.clip {
+            Some(current) => current.intersect(&mask),
+            None => mask.clone(),
+        });
+        let pixels = self.alloc_layer_pixels(Some(frame));
+        self.layers.push(Layer {
+            kind: LayerKind::Clip(mask),
+            pixels,
+        });
+    }
+
+    /// `save()` plus a transparent offscreen layer composited with `opacity` on the matching `restore()`.
+    pub fn save_layer(&mut self, opacity: f32) {
+        self.save();
+        let pixels = self.alloc_layer_pixels(None);
+        self.layers.push(Layer {
+            kind: LayerKind::Group {
+                opacity: opacity.clamp(0.0, 1.0),
+            },
+            pixels,
+        });
+    }
+
+    /// Buffer that drawing should go to right now: the top layer, or `frame` when none is open.
+    pub fn target<'a>(&'a mut self, frame: &'a mut [u8]) -> &'a mut [u8] {
+        match self.layers.last_mut() {
+            Some(layer) => &mut layer.pixels,
+            None => frame,
+        }
+    }
+
+    /// Fill a local-space rectangle. Axis-aligned opaque fills take the row-copy fast path.
+    pub fn fill_rect(&mut self, frame: &mut [u8], x: f32, y: f32, w: f32, h: f32, color: (u8, u8, u8, u8)) {
+        if self.transform.is_axis_aligned() && color.3 == 255 {
+            let (xa, ya) = self.transform.apply(x, y);
+            let (xb, yb) = self.transform.apply(x + w, y + h);
+            let (width, height) = (self.width, self.height);
+            fill_rect_buffer(
+                self.target(frame),
+                width,
+                height,
+                xa.min(xb).round() as i32,
+                ya.min(yb).round() as i32,
+                xa.max(xb).round() as i32,
+                ya.max(yb).round() as i32,
+                color,
+            );
+            return;
+        }
+        let mut path = Path::new();
+        path.rect(x, y, w, h);
+        self.fill_path(frame, &path, color);
+    }
+
+    /// Antialiased nonzero fill of a local-space path.
+    pub fn fill_path(&mut self, frame: &mut [u8], path: &Path, color: (u8, u8, u8, u8)) {
+        let device = path.transformed(&self.transform);
+        let mask = ClipMask::from_path(&device, self.width, self.height);
+        let width = self.width;
+        fill_mask_buffer(self.target(frame), width, &mask, color);
+    }
+
+    /// Filled local-space circle (an ellipse path under non-uniform scale or skew).
+    pub fn fill_circle(&mut self, frame: &mut [u8], cx: f32, cy: f32, r: f32, color: (u8, u8, u8, u8)) {
+        if self.transform.is_similarity() && color.3 == 255 {
+            let (dx, dy) = self.transform.apply(cx, cy);
+            let radius = r * self.transform.mean_scale();
+            let (width, height) = (self.width, self.height);
+            draw_circle_cpu(self.target(frame), width, height, dx, dy, radius, color);
+            return;
+        }
+        let mut path = Path::new();
+        path.circle(cx, cy, r);
+        self.fill_path(frame, &path, color);
+    }
+
+    fn alloc_layer_pixels(&mut self, copy_from_frame: Option<&[u8]>) -> Vec<u8> {
+        let len = self.width * self.height * 4;
+        let mut pixels = self.pool.pop().unwrap_or_default();
+        pixels.clear();
+        match copy_from_frame {
+            Some(frame) => {
+                let parent: &[u8] = match self.layers.last() {
+                    Some(layer) => &layer.pixels,
+                    None => frame,
+                };
+                pixels.extend_from_slice(&parent[..len.min(parent.len())]);
+                pixels.resize(len, 0);
+            }
+            None => pixels.resize(len, 0),
+        }
+        pixels
+    }
+
+    fn pop_layer(&mut self, frame: &mut [u8]) {
+        let Some(layer) = self.layers.pop() else {
+            return;
+        };
+        let width = self.width;
+        let dst: &mut [u8] = match self.layers.last_mut() {
+            Some(parent) => &mut parent.pixels,
+            None => frame,
+        };
+        composite_layer(&layer, dst, width);
+        self.pool.push(layer.pixels);
+    }
+}
+
+fn composite_layer(layer: &Layer, dst: &mut [u8], width: usize) {
+    let src = &layer.pixels;
+    match &layer.kind {
+        LayerKind::Clip(mask) => {
+            let (x0, y0, x1, y1) = mask.bounds();
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let cov = mask.coverage(x, y) as u32;
+                    if cov == 0 {
+                        continue;
+                    }
+                    let idx = (y * width + x) * 4;
+                    if idx + 3 >= dst.len() || idx + 3 >= src.len() {
+                        continue;
+                    }
+                    if cov == 255 {
+                        dst[idx..idx + 4].copy_from_slice(&src[idx..idx + 4]);
+                    } else {
+                        let inv = 255 - cov;
+                        for c in 0..4 {
+                            dst[idx + c] =
+                                ((src[idx + c] as u32 * cov + dst[idx + c] as u32 * inv + 127) / 255) as u8;
+                        }
+                    }
+                }
+            }
+        }
+        LayerKind::Group { opacity } => {
+            let op = (opacity * 255.0).round() as u32;
+            if op == 0 {
+                return;
+            }
+            let n = src.len().min(dst.len());
+            for (s, d) in src[..n].chunks_exact(4).zip(dst[..n].chunks_exact_mut(4)) {
+                if s[3] == 0 {
+                    continue;
+                }
+                let alpha = (s[3] as u32 * op + 127) / 255;
+                blend_px(d, [s[0], s[1], s[2]], alpha);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opaque_black(w: usize, h: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; w * h * 4];
+        for px in buf.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+        buf
+    }
+
+    fn px(buf: &[u8], w: usize, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * w + x) * 4;
+        [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]
+    }
+
+    #[test]
+    fn clip_rect_confines_drawing_until_restore() {
+        let (w, h) = (32, 32);
+        let mut frame = opaque_black(w, h);
+        let mut canvas = Canvas::new(w, h);
+        canvas.save();
+        canvas.clip_rect(&frame, 8.0, 8.0, 8.0, 8.0);
+        canvas.fill_rect(&mut frame, 0.0, 0.0, 32.0, 32.0, (255, 0, 0, 255));
+        // Nothing reaches the frame before the clip layer is composited.
+        assert_eq!(px(&frame, w, 10, 10), [0, 0, 0, 255]);
+        assert!(canvas.restore(&mut frame));
+        assert_eq!(px(&frame, w, 10, 10), [255, 0, 0, 255]);
+        assert_eq!(px(&frame, w, 2, 2), [0, 0, 0, 255]);
+        assert_eq!(px(&frame, w, 20, 20), [0, 0, 0, 255]);
+        assert!(!canvas.restore(&mut frame));
+    }
+
+    #[test]
+    fn transform_stack_restores_and_maps_points() {
+        let mut canvas = Canvas::new(100, 100);
+        canvas.save();
+        canvas.translate(10.0, 20.0);
+        canvas.scale(2.0, 2.0);
+        assert_eq!(canvas.map_point(1.0, 1.0), (12.0, 22.0));
+        let (lx, ly) = canvas.unmap_point(12.0, 22.0).unwrap();
+        assert!((lx - 1.0).abs() < 1e-5 && (ly - 1.0).abs() < 1e-5);
+        canvas.restore(&mut vec![0u8; 100 * 100 * 4]);
+        assert!(canvas.transform().is_identity());
+    }
+
+    #[test]
+    fn group_layer_composites_with_opacity() {
+        let (w, h) = (4, 4);
+        let mut frame = opaque_black(w, h);
+        let mut canvas = Canvas::new(w, h);
+        canvas.save_layer(0.5);
+        canvas.fill_rect(&mut frame, 0.0, 0.0, 2.0, 4.0, (200, 200, 200, 255));
+        canvas.restore(&mut frame);
+        assert_eq!(px(&frame, w, 0, 0), [100, 100, 100, 255]);
+        assert_eq!(px(&frame, w, 3, 0), [0, 0, 0, 255]);
+    }
+}
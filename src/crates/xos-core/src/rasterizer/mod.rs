@@ -1,16 +1,19 @@
 //! Framebuffer raster helpers (`fill`, `circles`, …): **pure functions** `(&mut FrameState, …) -> …`
 //! with no hidden engine state. Filled circles are CPU-rasterized into staging; solid fill and
 //! other shapes may use Burn on [`xos_tensor::XosBackend`] (wgpu); CPU staging is synced for
-//! keyboard / FPS overlay and `pixels` upload.
+//! keyboard / FPS overlay and `pixels` upload. [`canvas::Canvas`] layers a `save`/`restore`
+//! transform, clip and offscreen-layer stack on top of those primitives.
 
 use crate::engine::FrameState;
 use crate::burn_raster;
 
 mod cache;
 pub mod blur;
+pub mod canvas;
 pub mod shapes;
 pub mod text;
 pub use cache::RasterCache;
+pub use canvas::{Affine, Canvas, ClipMask, Path};
 
 pub use shapes::{
     circles, draw_circle_cpu, draw_circles_cpu, draw_circles_cpu_instances, fill_rect,
@@ -20,6 +23,7 @@ pub use shapes::{
 /// Fill `frame` with a solid RGBA color. Matches Python: `xos.rasterizer.fill(frame, (r, g, b, a))`.
 #[inline]
 pub fn fill(frame: &mut FrameState, color: (u8, u8, u8, u8)) {
+    crate::profile_scope!("raster:fill");
     burn_raster::fill_solid(frame, color);
 }
 
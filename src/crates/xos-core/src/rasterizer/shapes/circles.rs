@@ -12,6 +12,7 @@ pub fn circles(
     radii: &[f32],
     colors: &[[u8; 4]],
 ) -> Result<(), String> {
+    crate::profile_scope!("raster:circles");
     let n = centers.len();
     if n == 0 {
         return Ok(());
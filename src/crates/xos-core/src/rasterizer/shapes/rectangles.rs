@@ -46,6 +46,7 @@ pub fn fill_rect(
     y1: i32,
     color: (u8, u8, u8, u8),
 ) {
+    crate::profile_scope!("raster:fill_rect");
     let shape = frame.shape();
     let h = shape[0];
     let w = shape[1];
@@ -136,5 +136,6 @@ pub fn triangles(
     points: &[(f32, f32)],
     colors: &[[u8; 4]],
 ) -> Result<(), String> {
+    crate::profile_scope!("raster:triangles");
     burn_raster::triangles(frame, points, colors)
 }
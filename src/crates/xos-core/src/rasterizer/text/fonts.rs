@@ -1,26 +1,52 @@
 use fontdue::{Font, FontSettings};
-use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
-use std::sync::OnceLock;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FontFamily {
-    JetBrainsMono = 0,
-    Mplus1 = 1,
-    DotGothic16 = 3,
+    JetBrainsMono,
+    Mplus1,
+    DotGothic16,
+    /// Registered at runtime via [`register_font_bytes`] / [`load_font_file`] (index into the user registry).
+    User(u16),
 }
 
 impl FontFamily {
+    /// Families embedded in the binary (always available).
     pub const ALL: [FontFamily; 3] = [
         FontFamily::JetBrainsMono,
         FontFamily::Mplus1,
         FontFamily::DotGothic16,
     ];
 
-    pub fn label(self) -> &'static str {
+    pub fn label(self) -> String {
         match self {
-            FontFamily::JetBrainsMono => "JetBrains Mono",
-            FontFamily::Mplus1 => "MPLUS1",
-            FontFamily::DotGothic16 => "DotGothic16",
+            FontFamily::JetBrainsMono => "JetBrains Mono".to_string(),
+            FontFamily::Mplus1 => "MPLUS1".to_string(),
+            FontFamily::DotGothic16 => "DotGothic16".to_string(),
+            FontFamily::User(idx) => read_user_fonts()
+                .get(idx as usize)
+                .map(|f| f.name.clone())
+                .unwrap_or_else(|| "?".to_string()),
+        }
+    }
+
+    /// Stable numeric id (built-ins keep their historical values; user fonts start at 256).
+    pub fn id(self) -> u32 {
+        match self {
+            FontFamily::JetBrainsMono => 0,
+            FontFamily::Mplus1 => 1,
+            FontFamily::DotGothic16 => 3,
+            FontFamily::User(idx) => 256 + idx as u32,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Self {
+        match id {
+            0..=255 => FontFamily::from(id as u8),
+            _ => FontFamily::User((id - 256) as u16),
         }
     }
 }
@@ -37,55 +63,364 @@ impl From<u8> for FontFamily {
     }
 }
 
-static DEFAULT_FONT_FAMILY: AtomicU8 = AtomicU8::new(FontFamily::Mplus1 as u8);
+/// Raw font file: embedded in the binary, or owned by the registry and shared with every rasterizer
+/// still using it (a replaced font is freed once the last holder drops it).
+#[derive(Clone, Debug)]
+pub enum FontBytes {
+    Embedded(&'static [u8]),
+    Registered(Arc<[u8]>),
+}
+
+impl Deref for FontBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FontBytes::Embedded(bytes) => bytes,
+            FontBytes::Registered(bytes) => bytes,
+        }
+    }
+}
+
+/// A font registered at runtime; replacing or clearing an entry drops the registry's references.
+struct UserFont {
+    name: String,
+    font: Arc<Font>,
+    bytes: Arc<[u8]>,
+}
+
+static DEFAULT_FONT_FAMILY: AtomicU32 = AtomicU32::new(1);
 static DEFAULT_FONT_VERSION: AtomicU64 = AtomicU64::new(1);
-static FONT_CACHE_JETBRAINS_MONO: OnceLock<Font> = OnceLock::new();
-static FONT_CACHE_MPLUS1: OnceLock<Font> = OnceLock::new();
-static FONT_CACHE_DOT_GOTHIC_16: OnceLock<Font> = OnceLock::new();
+/// Bumped on every registry / fallback-chain change; text rasterizers re-resolve fallbacks when it moves.
+static FONT_REGISTRY_VERSION: AtomicU64 = AtomicU64::new(1);
+static FONT_CACHE_JETBRAINS_MONO: OnceLock<Arc<Font>> = OnceLock::new();
+static FONT_CACHE_MPLUS1: OnceLock<Arc<Font>> = OnceLock::new();
+static FONT_CACHE_DOT_GOTHIC_16: OnceLock<Arc<Font>> = OnceLock::new();
+/// fontdue file hash of every bundled family — lets shaping recover bytes from a [`Font`].
+static FONT_FILE_HASHES: OnceLock<Vec<usize>> = OnceLock::new();
+static USER_FONTS: RwLock<Vec<UserFont>> = RwLock::new(Vec::new());
+/// Per-family fallback chains set with [`set_fallback_chain`]; others use [`default_fallbacks`].
+static FALLBACK_CHAINS: RwLock<Vec<(FontFamily, Vec<FontFamily>)>> = RwLock::new(Vec::new());
+/// `None` → every other built-in family, in [`FontFamily::ALL`] order.
+static DEFAULT_FALLBACKS: RwLock<Option<Vec<FontFamily>>> = RwLock::new(None);
+
+const JETBRAINS_MONO_BYTES: &[u8] = include_bytes!("../../../assets/JetBrainsMono-Regular.ttf");
+const MPLUS1_BYTES: &[u8] = include_bytes!("../../../assets/MPLUS1.ttf");
+const DOT_GOTHIC_16_BYTES: &[u8] = include_bytes!("../../../assets/DotGothic16-Regular.ttf");
 
 fn load_font_from_bytes(font_bytes: &'static [u8]) -> Font {
     Font::from_bytes(font_bytes, FontSettings::default()).unwrap()
 }
 
+fn read_user_fonts() -> std::sync::RwLockReadGuard<'static, Vec<UserFont>> {
+    USER_FONTS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn cached_font(family: FontFamily) -> Arc<Font> {
+    let (cache, bytes) = match family {
+        FontFamily::JetBrainsMono => (&FONT_CACHE_JETBRAINS_MONO, JETBRAINS_MONO_BYTES),
+        FontFamily::Mplus1 => (&FONT_CACHE_MPLUS1, MPLUS1_BYTES),
+        FontFamily::DotGothic16 => (&FONT_CACHE_DOT_GOTHIC_16, DOT_GOTHIC_16_BYTES),
+        FontFamily::User(idx) => {
+            return read_user_fonts()
+                .get(idx as usize)
+                .map(|f| Arc::clone(&f.font))
+                .unwrap_or_else(|| cached_font(FontFamily::JetBrainsMono));
+        }
+    };
+    Arc::clone(cache.get_or_init(|| Arc::new(load_font_from_bytes(bytes))))
+}
+
+/// Raw font file for a family (used by the shaping stage).
+pub fn font_bytes(family: FontFamily) -> FontBytes {
+    match family {
+        FontFamily::JetBrainsMono => FontBytes::Embedded(JETBRAINS_MONO_BYTES),
+        FontFamily::Mplus1 => FontBytes::Embedded(MPLUS1_BYTES),
+        FontFamily::DotGothic16 => FontBytes::Embedded(DOT_GOTHIC_16_BYTES),
+        FontFamily::User(idx) => read_user_fonts()
+            .get(idx as usize)
+            .map(|f| FontBytes::Registered(Arc::clone(&f.bytes)))
+            .unwrap_or(FontBytes::Embedded(JETBRAINS_MONO_BYTES)),
+    }
+}
+
+/// Family whose file backs `font` (built-in or registered), matched by fontdue's file hash.
+pub fn family_of(font: &Font) -> Option<FontFamily> {
+    let hashes = FONT_FILE_HASHES.get_or_init(|| {
+        FontFamily::ALL
+            .iter()
+            .map(|&f| cached_font(f).file_hash())
+            .collect()
+    });
+    let hash = font.file_hash();
+    if let Some(i) = hashes.iter().position(|h| *h == hash) {
+        return Some(FontFamily::ALL[i]);
+    }
+    read_user_fonts()
+        .iter()
+        .position(|f| f.font.file_hash() == hash)
+        .map(|i| FontFamily::User(i as u16))
+}
+
+/// Raw font file backing `font`, when it is a built-in or registered family.
+pub fn font_bytes_for(font: &Font) -> Option<FontBytes> {
+    family_of(font).map(font_bytes)
+}
+
+/// Shared handle to a family's parsed font (no deep clone).
+pub fn font_ref(family: FontFamily) -> Arc<Font> {
+    cached_font(family)
+}
+
+pub fn font_for_family(family: FontFamily) -> Font {
+    Font::clone(&cached_font(family))
+}
+
 pub fn jetbrains_mono() -> Font {
-    FONT_CACHE_JETBRAINS_MONO
-        .get_or_init(|| {
-            let font_bytes = include_bytes!("../../../assets/JetBrainsMono-Regular.ttf") as &[u8];
-            load_font_from_bytes(font_bytes)
-        })
-        .clone()
+    font_for_family(FontFamily::JetBrainsMono)
 }
 
 pub fn mplus1() -> Font {
-    FONT_CACHE_MPLUS1
-        .get_or_init(|| {
-            let font_bytes = include_bytes!("../../../assets/MPLUS1.ttf") as &[u8];
-            load_font_from_bytes(font_bytes)
-        })
-        .clone()
+    font_for_family(FontFamily::Mplus1)
 }
 
 pub fn dot_gothic_16() -> Font {
-    FONT_CACHE_DOT_GOTHIC_16
-        .get_or_init(|| {
-            let font_bytes = include_bytes!("../../../assets/DotGothic16-Regular.ttf") as &[u8];
-            load_font_from_bytes(font_bytes)
-        })
+    font_for_family(FontFamily::DotGothic16)
+}
+
+/// Built-in families followed by every registered user font (F3 picker order).
+pub fn all_families() -> Vec<FontFamily> {
+    let user = read_user_fonts().len();
+    FontFamily::ALL
+        .iter()
+        .copied()
+        .chain((0..user).map(|i| FontFamily::User(i as u16)))
+        .collect()
+}
+
+/// Case-insensitive lookup over built-in labels and registered names.
+pub fn family_by_name(name: &str) -> Option<FontFamily> {
+    let want = name.trim();
+    all_families()
+        .into_iter()
+        .find(|f| f.label().eq_ignore_ascii_case(want))
+}
+
+#[inline]
+pub fn registry_version() -> u64 {
+    FONT_REGISTRY_VERSION.load(Ordering::Relaxed)
+}
+
+/// Register a TTF/OTF (first face of a collection) under `name`. Registering an existing name replaces
+/// that entry in place, so the returned [`FontFamily`] stays valid; the old file is freed once no
+/// rasterizer still holds it.
+pub fn register_font_bytes(name: &str, bytes: Vec<u8>) -> Result<FontFamily, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("font name must not be empty".to_string());
+    }
+    if let Some(builtin) = FontFamily::ALL
+        .iter()
+        .find(|f| f.label().eq_ignore_ascii_case(name))
+    {
+        return Err(format!("'{}' is a built-in font family", builtin.label()));
+    }
+    let bytes: Arc<[u8]> = bytes.into();
+    let font = Font::from_bytes(&*bytes, FontSettings::default())
+        .map_err(|e| format!("could not parse font '{name}': {e}"))?;
+    if font.horizontal_line_metrics(16.0).is_none() {
+        return Err(format!("font '{name}' has no horizontal metrics"));
+    }
+    let entry = UserFont {
+        name: name.to_string(),
+        font: Arc::new(font),
+        bytes,
+    };
+
+    let family = {
+        let mut fonts = USER_FONTS.write().unwrap_or_else(|e| e.into_inner());
+        match fonts.iter().position(|f| f.name.eq_ignore_ascii_case(name)) {
+            Some(i) => {
+                fonts[i] = entry;
+                FontFamily::User(i as u16)
+            }
+            None => {
+                if fonts.len() >= u16::MAX as usize {
+                    return Err("too many registered fonts".to_string());
+                }
+                fonts.push(entry);
+                FontFamily::User((fonts.len() - 1) as u16)
+            }
+        }
+    };
+    FONT_REGISTRY_VERSION.fetch_add(1, Ordering::Relaxed);
+    if default_font_family() == family {
+        DEFAULT_FONT_VERSION.fetch_add(1, Ordering::Relaxed);
+    }
+    Ok(family)
+}
+
+/// Read and register a font file. `name` defaults to the font's own family name, then the file stem.
+pub fn load_font_file(path: &Path, name: Option<&str>) -> Result<FontFamily, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("could not read font {}: {e}", path.display()))?;
+    let name = match name {
+        Some(n) if !n.trim().is_empty() => n.trim().to_string(),
+        _ => Font::from_bytes(bytes.as_slice(), FontSettings::default())
+            .ok()
+            .and_then(|f| f.name().map(str::to_string))
+            .or_else(|| {
+                path.file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "font".to_string()),
+    };
+    register_font_bytes(&name, bytes)
+}
+
+/// Font directories searched by [`scan_system_fonts`] (Linux/BSD only; empty elsewhere).
+pub fn system_font_dirs() -> Vec<PathBuf> {
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+    {
+        let mut dirs = vec![
+            PathBuf::from("/usr/share/fonts"),
+            PathBuf::from("/usr/local/share/fonts"),
+        ];
+        if let Some(data) = std::env::var_os("XDG_DATA_HOME") {
+            dirs.push(PathBuf::from(data).join("fonts"));
+        }
+        if let Some(home) = std::env::var_os("HOME") {
+            let home = PathBuf::from(home);
+            dirs.push(home.join(".local/share/fonts"));
+            dirs.push(home.join(".fonts"));
+        }
+        dirs.dedup();
+        dirs
+    }
+    #[cfg(not(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")))))]
+    {
+        Vec::new()
+    }
+}
+
+/// A font file found under [`system_font_dirs`]; `name` is the file stem.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemFont {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Recursively list `.ttf` / `.otf` / `.ttc` files in the system font directories (sorted by name).
+pub fn scan_system_fonts() -> Vec<SystemFont> {
+    fn walk(dir: &Path, depth: usize, out: &mut Vec<SystemFont>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth < 6 {
+                    walk(&path, depth + 1, out);
+                }
+                continue;
+            }
+            let ext = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            if matches!(ext.as_str(), "ttf" | "otf" | "ttc") {
+                if let Some(stem) = path.file_stem() {
+                    out.push(SystemFont {
+                        name: stem.to_string_lossy().into_owned(),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    for dir in system_font_dirs() {
+        walk(&dir, 0, &mut out);
+    }
+    out.sort_by_key(|a| a.name.to_lowercase());
+    out.dedup_by(|a, b| a.path == b.path);
+    out
+}
+
+/// Register a system font by file stem (exact, case-insensitive, else first stem containing `query`).
+pub fn load_system_font(query: &str, name: Option<&str>) -> Result<FontFamily, String> {
+    let q = query.trim().to_lowercase();
+    let fonts = scan_system_fonts();
+    let hit = fonts
+        .iter()
+        .find(|f| f.name.to_lowercase() == q)
+        .or_else(|| fonts.iter().find(|f| f.name.to_lowercase().contains(&q)))
+        .ok_or_else(|| format!("no system font matching '{query}'"))?;
+    load_font_file(&hit.path, name.or(Some(hit.name.as_str())))
+}
+
+/// Fallback order for glyphs missing from `family` (does not include `family` itself).
+pub fn fallback_chain(family: FontFamily) -> Vec<FontFamily> {
+    if let Some((_, chain)) = FALLBACK_CHAINS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(f, _)| *f == family)
+    {
+        return chain.iter().copied().filter(|f| *f != family).collect();
+    }
+    default_fallbacks()
+        .into_iter()
+        .filter(|f| *f != family)
+        .collect()
+}
+
+/// Chain used by families without their own [`set_fallback_chain`] entry.
+pub fn default_fallbacks() -> Vec<FontFamily> {
+    DEFAULT_FALLBACKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
         .clone()
+        .unwrap_or_else(|| FontFamily::ALL.to_vec())
+}
+
+pub fn set_fallback_chain(family: FontFamily, chain: Vec<FontFamily>) {
+    let mut chains = FALLBACK_CHAINS.write().unwrap_or_else(|e| e.into_inner());
+    chains.retain(|(f, _)| *f != family);
+    chains.push((family, chain));
+    FONT_REGISTRY_VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn set_default_fallbacks(chain: Vec<FontFamily>) {
+    *DEFAULT_FALLBACKS.write().unwrap_or_else(|e| e.into_inner()) = Some(chain);
+    FONT_REGISTRY_VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Fallback fonts (with their raw bytes for shaping) for whatever family backs `font`.
+pub fn fallback_fonts_for(font: &Font) -> Vec<(Arc<Font>, FontBytes)> {
+    let chain = match family_of(font) {
+        Some(family) => fallback_chain(family),
+        None => default_fallbacks(),
+    };
+    chain
+        .into_iter()
+        .map(|f| (font_ref(f), font_bytes(f)))
+        .collect()
 }
 
 pub fn default_font_family() -> FontFamily {
-    FontFamily::from(DEFAULT_FONT_FAMILY.load(Ordering::Relaxed))
+    FontFamily::from_id(DEFAULT_FONT_FAMILY.load(Ordering::Relaxed))
 }
 
-pub fn default_font_name() -> &'static str {
+pub fn default_font_name() -> String {
     default_font_family().label()
 }
 
 pub fn set_default_font_family(family: FontFamily) {
     let prev = default_font_family();
     if prev != family {
-        DEFAULT_FONT_FAMILY.store(family as u8, Ordering::Relaxed);
+        DEFAULT_FONT_FAMILY.store(family.id(), Ordering::Relaxed);
         DEFAULT_FONT_VERSION.fetch_add(1, Ordering::Relaxed);
     }
 }
@@ -95,9 +430,63 @@ pub fn default_font_version() -> u64 {
 }
 
 pub fn default_font() -> Font {
-    match default_font_family() {
-        FontFamily::JetBrainsMono => jetbrains_mono(),
-        FontFamily::Mplus1 => mplus1(),
-        FontFamily::DotGothic16 => dot_gothic_16(),
+    font_for_family(default_font_family())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_replace_and_lookup() {
+        let family = register_font_bytes("Test Gothic", DOT_GOTHIC_16_BYTES.to_vec()).unwrap();
+        assert!(matches!(family, FontFamily::User(_)));
+        assert_eq!(family_by_name("test gothic"), Some(family));
+        assert_eq!(family.label(), "Test Gothic");
+        assert_eq!(family_of(&font_ref(family)), Some(FontFamily::DotGothic16));
+        assert!(all_families().contains(&family));
+
+        let old = match font_bytes(family) {
+            FontBytes::Registered(bytes) => Arc::downgrade(&bytes),
+            FontBytes::Embedded(_) => panic!("registered font should own its bytes"),
+        };
+        let replaced = register_font_bytes("TEST GOTHIC", JETBRAINS_MONO_BYTES.to_vec()).unwrap();
+        assert_eq!(replaced, family);
+        assert_eq!(&*font_bytes(family), JETBRAINS_MONO_BYTES);
+        assert!(old.upgrade().is_none(), "replaced font bytes should be freed");
+
+        assert!(register_font_bytes("  ", JETBRAINS_MONO_BYTES.to_vec()).is_err());
+        assert!(register_font_bytes("mplus1", JETBRAINS_MONO_BYTES.to_vec()).is_err());
+        assert!(register_font_bytes("Broken", vec![0; 64]).is_err());
+        assert_eq!(family_by_name("Broken"), None);
+    }
+
+    #[test]
+    fn fallback_chains_keep_order_and_skip_self() {
+        assert_eq!(
+            fallback_chain(FontFamily::Mplus1),
+            vec![FontFamily::JetBrainsMono, FontFamily::DotGothic16]
+        );
+
+        let family = register_font_bytes("Fallback Probe", MPLUS1_BYTES.to_vec()).unwrap();
+        set_fallback_chain(
+            family,
+            vec![FontFamily::DotGothic16, family, FontFamily::JetBrainsMono],
+        );
+        assert_eq!(
+            fallback_chain(family),
+            vec![FontFamily::DotGothic16, FontFamily::JetBrainsMono]
+        );
+        let hashes: Vec<usize> = fallback_fonts_for(&font_ref(FontFamily::Mplus1))
+            .iter()
+            .map(|(font, _)| font.file_hash())
+            .collect();
+        assert_eq!(
+            hashes,
+            vec![
+                font_ref(FontFamily::JetBrainsMono).file_hash(),
+                font_ref(FontFamily::DotGothic16).file_hash()
+            ]
+        );
     }
 }
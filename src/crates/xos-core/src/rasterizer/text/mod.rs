@@ -1,3 +1,6 @@
 pub mod fonts;
+pub mod rich_text;
+pub mod sdf;
+pub mod shaping;
 pub mod text_rasterization;
 pub mod ui_markup;
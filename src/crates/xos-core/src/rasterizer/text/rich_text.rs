@@ -0,0 +1,1117 @@
+//! Rich text: attributed runs (font, size, weight, color, underline, background) laid out into wrapped,
+//! aligned, hit-testable lines.
+//!
+//! Each run is shaped on its own through [`shaping::shape_paragraph`] (so kerning / fallbacks / bidi
+//! levels apply inside a run), then lines are filled greedily using word and CJK break opportunities.
+//! A word wider than the box falls back to grapheme breaks. `max_lines` truncates with an ellipsis.
+
+use fontdue::Metrics;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use super::fonts::{self, FontFamily};
+use super::shaping::{self, ShapedGlyph, ShapingFont};
+use super::ui_markup;
+
+const ELLIPSIS: char = '\u{2026}';
+
+/// Glyph rasters shared by every rich layout: `(family id, glyph, size bits, bold)`.
+type RichGlyphKey = (u32, u16, u32, bool);
+type RichGlyphMap = HashMap<RichGlyphKey, (Metrics, Arc<Vec<u8>>)>;
+static RICH_GLYPHS: OnceLock<Mutex<RichGlyphMap>> = OnceLock::new();
+const RICH_GLYPHS_MAX: usize = 16_384;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    pub family: FontFamily,
+    pub size: f32,
+    /// CSS-style weight; `>= 600` is drawn with a synthetic bold (the bundled fonts have one weight).
+    pub weight: u16,
+    pub color: (u8, u8, u8, u8),
+    pub underline: bool,
+    pub background: Option<(u8, u8, u8, u8)>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            family: fonts::default_font_family(),
+            size: 24.0,
+            weight: 400,
+            color: (255, 255, 255, 255),
+            underline: false,
+            background: None,
+        }
+    }
+}
+
+impl TextStyle {
+    #[inline]
+    pub fn is_bold(&self) -> bool {
+        self.weight >= 600
+    }
+
+    /// Horizontal dilation (px) used for synthetic bold.
+    #[inline]
+    fn embolden_px(&self) -> usize {
+        if self.is_bold() {
+            (self.size / 24.0).round().max(1.0) as usize
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub style: TextStyle,
+}
+
+impl TextRun {
+    pub fn new(text: impl Into<String>, style: TextStyle) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+/// `[label](color=NAME size=…)` inline markup → runs, so markup-styled text can use this layout too.
+pub fn runs_from_markup(raw: &str, base: &TextStyle) -> Vec<TextRun> {
+    let (text, color_spans, scale_spans) = ui_markup::strip_inline_ui_markup(raw, base.size);
+    let mut runs: Vec<TextRun> = Vec::new();
+    for (i, ch) in text.chars().enumerate() {
+        let (r, g, b) = ui_markup::glyph_rgb_with_spans(
+            i,
+            (base.color.0, base.color.1, base.color.2),
+            &color_spans,
+        );
+        let scale = scale_spans
+            .iter()
+            .rev()
+            .find(|(s, e, _)| i >= *s && i < *e)
+            .map(|span| span.2)
+            .unwrap_or(1.0);
+        let style = TextStyle {
+            color: (r, g, b, base.color.3),
+            size: base.size * scale,
+            ..base.clone()
+        };
+        match runs.last_mut() {
+            Some(run) if run.style == style => run.text.push(ch),
+            _ => runs.push(TextRun::new(ch.to_string(), style)),
+        }
+    }
+    runs
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RichAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Stretch inter-word spaces to fill the box (last line of each paragraph stays left-aligned).
+    Justify,
+}
+
+impl RichAlign {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "left" | "start" => Some(Self::Left),
+            "center" | "centre" | "middle" => Some(Self::Center),
+            "right" | "end" => Some(Self::Right),
+            "justify" | "justified" => Some(Self::Justify),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RichLayoutOptions {
+    /// Wrap width in px (`f32::INFINITY` = only explicit newlines break).
+    pub max_width: f32,
+    pub align: RichAlign,
+    pub max_lines: Option<usize>,
+    /// Replace the tail of the last kept line with `…` when `max_lines` cuts text.
+    pub ellipsis: bool,
+    /// Multiplier on each line's natural height.
+    pub line_spacing: f32,
+}
+
+impl Default for RichLayoutOptions {
+    fn default() -> Self {
+        Self {
+            max_width: f32::INFINITY,
+            align: RichAlign::Left,
+            max_lines: None,
+            ellipsis: true,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+/// One positioned glyph; `x`/`y` are the bitmap's top-left in layout space.
+#[derive(Clone, Debug)]
+pub struct RichGlyph {
+    /// Char index of the glyph's cluster (`usize::MAX` for the synthetic ellipsis).
+    pub char_index: usize,
+    pub run: usize,
+    pub line: usize,
+    pub x: f32,
+    pub y: f32,
+    pub color: (u8, u8, u8, u8),
+    pub metrics: Metrics,
+    pub bitmap: Arc<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RichLine {
+    /// Char range `[start, end)` (excludes the paragraph's `\n`).
+    pub start: usize,
+    pub end: usize,
+    pub top: f32,
+    pub baseline_y: f32,
+    pub bottom: f32,
+    /// Left edge of the aligned content.
+    pub x: f32,
+    pub width: f32,
+}
+
+/// Caret-space box of one char: `x0..x1` on `line`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RichCharBox {
+    pub char_index: usize,
+    pub line: usize,
+    pub x0: f32,
+    pub x1: f32,
+    pub rtl: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RichRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: (u8, u8, u8, u8),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RichTextLayout {
+    pub glyphs: Vec<RichGlyph>,
+    pub lines: Vec<RichLine>,
+    /// Boxes for every laid-out char (logical order); chars cut by `max_lines` have none.
+    pub char_boxes: Vec<RichCharBox>,
+    pub backgrounds: Vec<RichRect>,
+    pub underlines: Vec<RichRect>,
+    pub width: f32,
+    pub height: f32,
+    /// `max_lines` dropped some text.
+    pub truncated: bool,
+}
+
+/// Per-char data gathered from shaping before line filling.
+struct CharInfo {
+    ch: char,
+    advance: f32,
+    grapheme_break: bool,
+}
+
+/// A shaped glyph resolved to a concrete font family, with its cluster as a global char index.
+struct PlacedGlyph {
+    shaped: ShapedGlyph,
+    family: FontFamily,
+}
+
+fn rich_glyph(
+    family: FontFamily,
+    glyph_id: u16,
+    size: f32,
+    embolden: usize,
+) -> (Metrics, Arc<Vec<u8>>) {
+    let cache = RICH_GLYPHS.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (family.id(), glyph_id, size.to_bits(), embolden > 0);
+    let mut map = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((m, arc)) = map.get(&key) {
+        return (*m, Arc::clone(arc));
+    }
+    if map.len() >= RICH_GLYPHS_MAX {
+        map.clear();
+    }
+    let (mut metrics, mut bitmap) = fonts::font_ref(family).rasterize_indexed(glyph_id, size);
+    if embolden > 0 && metrics.width > 0 {
+        bitmap = embolden_bitmap(&bitmap, metrics.width, metrics.height, embolden);
+        metrics.width += embolden;
+    }
+    let arc = Arc::new(bitmap);
+    map.insert(key, (metrics, Arc::clone(&arc)));
+    (metrics, arc)
+}
+
+/// Synthetic bold: max-dilate coverage `k` px to the right.
+fn embolden_bitmap(src: &[u8], w: usize, h: usize, k: usize) -> Vec<u8> {
+    let ow = w + k;
+    let mut out = vec![0u8; ow * h];
+    for y in 0..h {
+        let row = &src[y * w..(y + 1) * w];
+        for (x, dst) in out[y * ow..(y + 1) * ow].iter_mut().enumerate() {
+            let lo = x.saturating_sub(k);
+            let hi = x.min(w - 1);
+            if lo <= hi {
+                *dst = row[lo..=hi].iter().copied().max().unwrap_or(0);
+            }
+        }
+    }
+    out
+}
+
+#[inline]
+fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32,
+        0x1100..=0x11FF
+        | 0x2E80..=0x303F
+        | 0x3040..=0x30FF
+        | 0x3100..=0x31FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA960..=0xA97F
+        | 0xAC00..=0xD7FF
+        | 0xF900..=0xFAFF
+        | 0xFF00..=0xFFEF
+        | 0x20000..=0x2FFFF)
+}
+
+/// Kinsoku: may not start a line.
+#[inline]
+fn is_no_break_before(ch: char) -> bool {
+    "、。，．・：；？！）」』】〕〉》ーぁぃぅぇぉっゃゅょァィゥェォッャュョ,.)]}!?:;".contains(ch)
+}
+
+/// Kinsoku: may not end a line.
+#[inline]
+fn is_no_break_after(ch: char) -> bool {
+    "（「『【〔〈《([{".contains(ch)
+}
+
+/// Line may break before `cur` (given the char before it).
+fn is_break_opportunity(prev: char, cur: char) -> bool {
+    if cur.is_whitespace() || is_no_break_before(cur) || is_no_break_after(prev) {
+        return false;
+    }
+    prev.is_whitespace() || prev == '-' || is_cjk(prev) || is_cjk(cur)
+}
+
+/// Shape every run of one paragraph (`chars[p0..p1]`) and append per-char info + glyphs.
+fn shape_paragraph_runs(
+    runs: &[TextRun],
+    chars: &[char],
+    char_run: &[usize],
+    p0: usize,
+    p1: usize,
+    infos: &mut [CharInfo],
+    glyphs: &mut Vec<PlacedGlyph>,
+) {
+    let mut s = p0;
+    while s < p1 {
+        let run = char_run[s];
+        let mut e = s + 1;
+        while e < p1 && char_run[e] == run {
+            e += 1;
+        }
+        let style = &runs[run].style;
+        let mut families = vec![style.family];
+        families.extend(fonts::fallback_chain(style.family));
+        let loaded: Vec<_> = families
+            .iter()
+            .map(|&f| (fonts::font_ref(f), fonts::font_bytes(f)))
+            .collect();
+        let shaping_fonts: Vec<ShapingFont> = loaded
+            .iter()
+            .map(|(font, bytes)| ShapingFont {
+                font,
+                bytes: Some(bytes),
+            })
+            .collect();
+        let text: String = chars[s..e].iter().collect();
+        let shaped = shaping::shape_paragraph(&shaping_fonts, &text, style.size);
+        let bold = style.embolden_px() as f32;
+        for (k, info) in infos[s..e].iter_mut().enumerate() {
+            let head = shaped.char_advances.get(k).copied().unwrap_or(0.0);
+            info.advance = head + if head > 0.0 { bold } else { 0.0 };
+            info.grapheme_break = shaped.break_before.get(k).copied().unwrap_or(true);
+        }
+        for g in shaped.glyphs {
+            glyphs.push(PlacedGlyph {
+                family: families[g.font as usize],
+                shaped: ShapedGlyph {
+                    cluster: s + g.cluster,
+                    x_advance: g.x_advance + bold,
+                    ..g
+                },
+            });
+        }
+        s = e;
+    }
+}
+
+/// Greedy fill of one paragraph: returns `(start, end)` char ranges.
+fn wrap_paragraph(infos: &[CharInfo], p0: usize, p1: usize, max_width: f32) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut line_start = p0;
+    let mut x = 0.0f32;
+    let mut last_break: Option<usize> = None;
+    for i in p0..p1 {
+        let info = &infos[i];
+        if i > line_start && is_break_opportunity(infos[i - 1].ch, info.ch) && info.grapheme_break {
+            last_break = Some(i);
+        }
+        // Spaces hang past the edge instead of forcing a wrap.
+        if !info.ch.is_whitespace() && i > line_start && x + info.advance > max_width {
+            let brk = match last_break {
+                Some(b) if b > line_start => b,
+                _ => (line_start + 1..=i)
+                    .rev()
+                    .find(|&b| infos[b].grapheme_break)
+                    .unwrap_or(i),
+            };
+            out.push((line_start, brk));
+            line_start = brk;
+            last_break = None;
+            x = infos[brk..i].iter().map(|c| c.advance).sum();
+        }
+        x += info.advance;
+    }
+    out.push((line_start, p1));
+    out
+}
+
+/// Line-box metrics `(ascent, descent, gap)` for the runs touched by `start..end` (or `run` if empty).
+fn line_metrics(
+    runs: &[TextRun],
+    char_run: &[usize],
+    start: usize,
+    end: usize,
+    run: usize,
+) -> (f32, f32, f32) {
+    let mut asc = 0.0f32;
+    let mut desc = 0.0f32;
+    let mut gap = 0.0f32;
+    let mut visit = |r: usize| {
+        let style = &runs[r].style;
+        if let Some(m) = fonts::font_ref(style.family).horizontal_line_metrics(style.size) {
+            asc = asc.max(m.ascent);
+            desc = desc.max(-m.descent);
+            gap = gap.max(m.line_gap);
+        } else {
+            asc = asc.max(style.size * 0.8);
+            desc = desc.max(style.size * 0.2);
+        }
+    };
+    if start == end {
+        visit(run);
+    }
+    let mut last = usize::MAX;
+    for &r in &char_run[start..end] {
+        if r != last {
+            visit(r);
+            last = r;
+        }
+    }
+    (asc, desc, gap)
+}
+
+/// Lay out `runs` with `options`. Coordinates are relative to the layout's top-left.
+pub fn layout_rich_text(runs: &[TextRun], options: &RichLayoutOptions) -> RichTextLayout {
+    let mut chars: Vec<char> = Vec::new();
+    let mut char_run: Vec<usize> = Vec::new();
+    for (ri, run) in runs.iter().enumerate() {
+        for ch in run.text.chars() {
+            chars.push(ch);
+            char_run.push(ri);
+        }
+    }
+    if runs.is_empty() {
+        return RichTextLayout::default();
+    }
+    let max_width = if options.max_width > 0.0 {
+        options.max_width
+    } else {
+        f32::INFINITY
+    };
+
+    let mut infos: Vec<CharInfo> = chars
+        .iter()
+        .map(|&ch| CharInfo {
+            ch,
+            advance: 0.0,
+            grapheme_break: true,
+        })
+        .collect();
+    let mut glyphs: Vec<PlacedGlyph> = Vec::with_capacity(chars.len());
+
+    // (start, end, last line of its paragraph)
+    let mut ranges: Vec<(usize, usize, bool)> = Vec::new();
+    let mut p0 = 0usize;
+    for pi in 0..=chars.len() {
+        if pi < chars.len() && chars[pi] != '\n' {
+            continue;
+        }
+        shape_paragraph_runs(runs, &chars, &char_run, p0, pi, &mut infos, &mut glyphs);
+        let lines = wrap_paragraph(&infos, p0, pi, max_width);
+        let n = lines.len();
+        ranges.extend(
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(k, (s, e))| (s, e, k + 1 == n)),
+        );
+        p0 = pi + 1;
+    }
+    glyphs.sort_by_key(|g| g.shaped.cluster);
+
+    let mut truncated = false;
+    if let Some(max) = options.max_lines {
+        if ranges.len() > max.max(1) {
+            ranges.truncate(max.max(1));
+            truncated = true;
+        }
+    }
+
+    // Ellipsis: shape `…` in the style of the last kept char, then trim the line until it fits.
+    let mut ellipsis: Option<(usize, Vec<PlacedGlyph>, f32)> = None;
+    if truncated && options.ellipsis {
+        if let Some(last) = ranges.last_mut() {
+            let run = char_run
+                .get(last.1.saturating_sub(1).max(last.0))
+                .copied()
+                .unwrap_or(0);
+            let style = &runs[run].style;
+            let mut families = vec![style.family];
+            families.extend(fonts::fallback_chain(style.family));
+            let loaded: Vec<_> = families
+                .iter()
+                .map(|&f| (fonts::font_ref(f), fonts::font_bytes(f)))
+                .collect();
+            let shaping_fonts: Vec<ShapingFont> = loaded
+                .iter()
+                .map(|(font, bytes)| ShapingFont {
+                    font,
+                    bytes: Some(bytes),
+                })
+                .collect();
+            let shaped =
+                shaping::shape_paragraph(&shaping_fonts, &ELLIPSIS.to_string(), style.size);
+            let width: f32 = shaped.char_advances.iter().sum();
+            let placed: Vec<PlacedGlyph> = shaped
+                .glyphs
+                .into_iter()
+                .map(|g| PlacedGlyph {
+                    family: families[g.font as usize],
+                    shaped: ShapedGlyph {
+                        cluster: usize::MAX,
+                        ..g
+                    },
+                })
+                .collect();
+            let trimmed_width = |s: usize, e: usize| -> f32 {
+                let mut e = e;
+                while e > s && infos[e - 1].ch.is_whitespace() {
+                    e -= 1;
+                }
+                infos[s..e].iter().map(|c| c.advance).sum()
+            };
+            while last.1 > last.0 && trimmed_width(last.0, last.1) + width > max_width {
+                last.1 -= 1;
+                while last.1 > last.0 && !infos[last.1].grapheme_break {
+                    last.1 -= 1;
+                }
+            }
+            while last.1 > last.0 && infos[last.1 - 1].ch.is_whitespace() {
+                last.1 -= 1;
+            }
+            last.2 = true;
+            ellipsis = Some((run, placed, width));
+        }
+    }
+
+    // Natural (unjustified) widths, trailing whitespace excluded.
+    let content_end = |s: usize, e: usize| -> usize {
+        let mut e = e;
+        while e > s && infos[e - 1].ch.is_whitespace() {
+            e -= 1;
+        }
+        e
+    };
+    let n_lines = ranges.len();
+    let mut widths: Vec<f32> = ranges
+        .iter()
+        .map(|&(s, e, _)| infos[s..content_end(s, e)].iter().map(|c| c.advance).sum())
+        .collect();
+    if let (Some((_, _, w)), Some(last)) = (&ellipsis, widths.last_mut()) {
+        *last += *w;
+    }
+    let box_width = if max_width.is_finite() {
+        max_width
+    } else {
+        widths.iter().copied().fold(0.0, f32::max)
+    };
+
+    let mut layout = RichTextLayout {
+        truncated,
+        ..Default::default()
+    };
+    let mut top = 0.0f32;
+    let mut gi = 0usize;
+    // (start, text) of the paragraph the current line belongs to.
+    let mut para: (usize, String) = (0, String::new());
+    for (li, &(start, end, para_last)) in ranges.iter().enumerate() {
+        let fallback_run = char_run
+            .get(start)
+            .or(char_run.last())
+            .copied()
+            .unwrap_or(0);
+        let (asc, desc, gap) = line_metrics(runs, &char_run, start, end, fallback_run);
+        let baseline_y = top + asc;
+        let bottom = top + (asc + desc + gap) * options.line_spacing.max(0.0);
+
+        let c_end = content_end(start, end);
+        let is_ellipsis_line = li + 1 == n_lines && ellipsis.is_some();
+        let justify = options.align == RichAlign::Justify
+            && !para_last
+            && !is_ellipsis_line
+            && max_width.is_finite();
+        let spaces = infos[start..c_end].iter().filter(|c| c.ch == ' ').count();
+        let space_extra = if justify && spaces > 0 {
+            ((max_width - widths[li]) / spaces as f32).max(0.0)
+        } else {
+            0.0
+        };
+        let line_width = if space_extra > 0.0 {
+            max_width
+        } else {
+            widths[li]
+        };
+        let x0 = match options.align {
+            RichAlign::Left | RichAlign::Justify => 0.0,
+            RichAlign::Center => ((box_width - line_width) * 0.5).max(0.0),
+            RichAlign::Right => (box_width - line_width).max(0.0),
+        };
+
+        // Glyphs of this line in visual order (UAX #9 L2 over the shaped levels).
+        while gi < glyphs.len() && glyphs[gi].shaped.cluster < start {
+            gi += 1;
+        }
+        let g0 = gi;
+        while gi < glyphs.len() && glyphs[gi].shaped.cluster < end {
+            gi += 1;
+        }
+        let line_glyphs: Vec<ShapedGlyph> = glyphs[g0..gi].iter().map(|g| g.shaped).collect();
+        // Levels come from the whole paragraph (runs are shaped apart) with rule L1 for this line.
+        let para_start = chars[..start]
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map_or(0, |i| i + 1);
+        if para.0 != para_start || para.1.is_empty() {
+            let para_end = chars[para_start..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |i| para_start + i);
+            para = (para_start, chars[para_start..para_end].iter().collect());
+        }
+        let levels = shaping::line_levels(&para.1, start - para_start, end - para_start);
+        let order = shaping::visual_order(&line_glyphs, start, &levels);
+        let rtl_at = |ci: usize| levels.get(ci - start).is_some_and(|l| l % 2 == 1);
+
+        let mut pen = x0;
+        let mut head_boxes: HashMap<usize, (f32, f32, bool)> = HashMap::new();
+        let place = |layout: &mut RichTextLayout, g: &PlacedGlyph, run: usize, pen: f32| {
+            let style = &runs[run].style;
+            let (metrics, bitmap) =
+                rich_glyph(g.family, g.shaped.glyph_id, style.size, style.embolden_px());
+            layout.glyphs.push(RichGlyph {
+                char_index: g.shaped.cluster,
+                run,
+                line: li,
+                x: pen + g.shaped.x_offset + metrics.xmin as f32,
+                y: baseline_y - metrics.height as f32 - metrics.ymin as f32 - g.shaped.y_offset,
+                color: style.color,
+                metrics,
+                bitmap,
+            });
+        };
+        for idx in order {
+            let g = &glyphs[g0 + idx];
+            let ci = g.shaped.cluster;
+            let mut adv = g.shaped.x_advance;
+            if infos[ci].ch == ' ' && ci < c_end {
+                adv += space_extra;
+            }
+            place(&mut layout, g, char_run[ci], pen);
+            let entry = head_boxes
+                .entry(ci)
+                .or_insert((pen, pen, rtl_at(ci)));
+            entry.0 = entry.0.min(pen);
+            entry.1 = entry.1.max(pen + adv);
+            pen += adv;
+        }
+        if is_ellipsis_line {
+            if let Some((run, placed, _)) = &ellipsis {
+                for g in placed {
+                    place(&mut layout, g, *run, pen);
+                    pen += g.shaped.x_advance;
+                }
+            }
+        }
+
+        // Char boxes: a cluster's box is split evenly across its chars (ligature carets).
+        let mut i = start;
+        while i < end {
+            let (bx0, bx1, rtl) = head_boxes.get(&i).copied().unwrap_or((pen, pen, false));
+            let mut j = i + 1;
+            while j < end && !head_boxes.contains_key(&j) {
+                j += 1;
+            }
+            let n = (j - i) as f32;
+            let step = (bx1 - bx0) / n;
+            for k in i..j {
+                let slot = (k - i) as f32;
+                let (a, b) = if rtl {
+                    (bx1 - step * (slot + 1.0), bx1 - step * slot)
+                } else {
+                    (bx0 + step * slot, bx0 + step * (slot + 1.0))
+                };
+                layout.char_boxes.push(RichCharBox {
+                    char_index: k,
+                    line: li,
+                    x0: a,
+                    x1: b,
+                    rtl,
+                });
+            }
+            i = j;
+        }
+
+        // Decorations follow the visible content (trailing spaces excluded), merged per run.
+        let mut spans: Vec<(usize, f32, f32)> = layout
+            .char_boxes
+            .iter()
+            .filter(|b| b.line == li && b.char_index < c_end)
+            .map(|b| (char_run[b.char_index], b.x0, b.x1))
+            .collect();
+        if let (true, Some((run, _, w))) = (is_ellipsis_line, &ellipsis) {
+            spans.push((*run, pen - w, pen));
+        }
+        spans.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let mut merged: Vec<(usize, f32, f32)> = Vec::new();
+        for (run, a, b) in spans {
+            match merged.last_mut() {
+                Some(m) if m.0 == run && (a - m.2).abs() < 0.5 => m.2 = m.2.max(b),
+                _ => merged.push((run, a, b)),
+            }
+        }
+        for (run, a, b) in merged {
+            let style = &runs[run].style;
+            if let Some(bg) = style.background {
+                layout.backgrounds.push(RichRect {
+                    x: a,
+                    y: top,
+                    width: b - a,
+                    height: bottom - top,
+                    color: bg,
+                });
+            }
+            if style.underline {
+                let thickness = (style.size / 16.0).max(1.0);
+                layout.underlines.push(RichRect {
+                    x: a,
+                    y: baseline_y + (style.size * 0.08).max(1.0),
+                    width: b - a,
+                    height: thickness,
+                    color: style.color,
+                });
+            }
+        }
+
+        layout.lines.push(RichLine {
+            start,
+            end,
+            top,
+            baseline_y,
+            bottom,
+            x: x0,
+            width: line_width,
+        });
+        top = bottom;
+    }
+    layout.char_boxes.sort_by_key(|b| b.char_index);
+    layout.width = box_width;
+    layout.height = top;
+    layout
+}
+
+impl RichTextLayout {
+    fn line_for_index(&self, index: usize) -> Option<usize> {
+        let mut found = None;
+        for (li, line) in self.lines.iter().enumerate() {
+            if index >= line.start && index <= line.end {
+                found = Some(li);
+                if index < line.end {
+                    break;
+                }
+            }
+        }
+        found
+    }
+
+    /// Caret index nearest to layout-space point `(x, y)`.
+    pub fn hit_test(&self, x: f32, y: f32) -> usize {
+        let Some(li) = self
+            .lines
+            .iter()
+            .position(|l| y < l.bottom)
+            .or_else(|| self.lines.len().checked_sub(1))
+        else {
+            return 0;
+        };
+        let line = self.lines[li];
+        let boxes = self.char_boxes_on_line(li);
+        if boxes.is_empty() {
+            return line.start;
+        }
+        let mut best = line.end;
+        let mut best_dist = f32::INFINITY;
+        for b in boxes {
+            let mid = (b.x0 + b.x1) * 0.5;
+            let dist = if x < b.x0 {
+                b.x0 - x
+            } else if x > b.x1 {
+                x - b.x1
+            } else {
+                0.0
+            };
+            if dist < best_dist {
+                best_dist = dist;
+                best = if (x < mid) != b.rtl {
+                    b.char_index
+                } else {
+                    b.char_index + 1
+                };
+            }
+        }
+        best.clamp(line.start, line.end)
+    }
+
+    /// Caret at char `index`: `(x, line top, line bottom)`.
+    pub fn caret_position(&self, index: usize) -> (f32, f32, f32) {
+        let Some(li) = self.line_for_index(index) else {
+            return match self.lines.last() {
+                Some(l) => (l.x + l.width, l.top, l.bottom),
+                None => (0.0, 0.0, 0.0),
+            };
+        };
+        let line = self.lines[li];
+        let boxes = self.char_boxes_on_line(li);
+        let x = if let Some(b) = boxes.iter().find(|b| b.char_index == index) {
+            if b.rtl {
+                b.x1
+            } else {
+                b.x0
+            }
+        } else if let Some(b) = boxes.iter().rev().find(|b| b.char_index < index) {
+            if b.rtl {
+                b.x0
+            } else {
+                b.x1
+            }
+        } else {
+            line.x
+        };
+        (x, line.top, line.bottom)
+    }
+
+    fn char_boxes_on_line(&self, li: usize) -> &[RichCharBox] {
+        let line = self.lines[li];
+        let s = self
+            .char_boxes
+            .partition_point(|b| b.char_index < line.start);
+        let e = self.char_boxes.partition_point(|b| b.char_index < line.end);
+        &self.char_boxes[s..e]
+    }
+
+    /// Paint backgrounds, glyphs, then underlines into an RGBA `frame` with the layout's top-left at
+    /// `origin`; nothing is drawn outside `clip` (`x0, y0, x1, y1` in frame pixels).
+    pub fn render(
+        &self,
+        frame: &mut [u8],
+        frame_width: usize,
+        frame_height: usize,
+        origin: (f32, f32),
+        clip: Option<(i32, i32, i32, i32)>,
+    ) {
+        let (cx0, cy0, cx1, cy1) = clip.unwrap_or((0, 0, frame_width as i32, frame_height as i32));
+        let cx0 = cx0.max(0);
+        let cy0 = cy0.max(0);
+        let cx1 = cx1.min(frame_width as i32);
+        let cy1 = cy1.min(frame_height as i32);
+        if cx0 >= cx1 || cy0 >= cy1 || frame.len() < frame_width * frame_height * 4 {
+            return;
+        }
+        let fill = |frame: &mut [u8], r: &RichRect| {
+            let x0 = ((origin.0 + r.x).round() as i32).max(cx0);
+            let y0 = ((origin.1 + r.y).round() as i32).max(cy0);
+            let x1 = ((origin.0 + r.x + r.width).round() as i32).min(cx1);
+            let y1 = ((origin.1 + r.y + r.height).round() as i32).min(cy1);
+            for py in y0..y1 {
+                for px in x0..x1 {
+                    let idx = (py as usize * frame_width + px as usize) * 4;
+                    blend_px(&mut frame[idx..idx + 4], r.color, 255);
+                }
+            }
+        };
+        for r in &self.backgrounds {
+            fill(frame, r);
+        }
+        for g in &self.glyphs {
+            let color = g.color;
+            let gx = (origin.0 + g.x).round() as i32;
+            let gy = (origin.1 + g.y).round() as i32;
+            for y in 0..g.metrics.height {
+                let py = gy + y as i32;
+                if py < cy0 || py >= cy1 {
+                    continue;
+                }
+                for x in 0..g.metrics.width {
+                    let px = gx + x as i32;
+                    if px < cx0 || px >= cx1 {
+                        continue;
+                    }
+                    let coverage = g.bitmap[y * g.metrics.width + x] as u32;
+                    if coverage == 0 {
+                        continue;
+                    }
+                    let idx = (py as usize * frame_width + px as usize) * 4;
+                    blend_px(&mut frame[idx..idx + 4], color, coverage);
+                }
+            }
+        }
+        for r in &self.underlines {
+            fill(frame, r);
+        }
+    }
+}
+
+#[inline]
+fn blend_px(dst: &mut [u8], color: (u8, u8, u8, u8), coverage: u32) {
+    let alpha = coverage * color.3 as u32 / 255;
+    if alpha == 0 {
+        return;
+    }
+    if alpha >= 255 {
+        dst[0] = color.0;
+        dst[1] = color.1;
+        dst[2] = color.2;
+        dst[3] = 255;
+        return;
+    }
+    let inv = 255 - alpha;
+    dst[0] = ((color.0 as u32 * alpha + dst[0] as u32 * inv + 127) / 255) as u8;
+    dst[1] = ((color.1 as u32 * alpha + dst[1] as u32 * inv + 127) / 255) as u8;
+    dst[2] = ((color.2 as u32 * alpha + dst[2] as u32 * inv + 127) / 255) as u8;
+    dst[3] = (alpha + (dst[3] as u32 * inv + 127) / 255).min(255) as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infos(text: &str, advance: f32) -> Vec<CharInfo> {
+        text.chars()
+            .map(|ch| CharInfo {
+                ch,
+                advance,
+                grapheme_break: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wraps_at_spaces_and_cjk() {
+        let latin = infos("aa bb cc", 10.0);
+        assert_eq!(wrap_paragraph(&latin, 0, 8, 55.0), vec![(0, 6), (6, 8)]);
+        let cjk = infos("漢字。漢字", 10.0);
+        // `。` may not start a line, so the break moves in front of the preceding ideograph.
+        assert_eq!(
+            wrap_paragraph(&cjk, 0, 5, 25.0),
+            vec![(0, 1), (1, 3), (3, 5)]
+        );
+        let long = infos("abcdef", 10.0);
+        assert_eq!(
+            wrap_paragraph(&long, 0, 6, 25.0),
+            vec![(0, 2), (2, 4), (4, 6)]
+        );
+    }
+
+    #[test]
+    fn max_lines_ellipsis_and_hit_test() {
+        let style = TextStyle {
+            family: FontFamily::JetBrainsMono,
+            size: 20.0,
+            ..TextStyle::default()
+        };
+        let runs = vec![
+            TextRun::new(
+                "hello world ",
+                TextStyle {
+                    underline: true,
+                    ..style.clone()
+                },
+            ),
+            TextRun::new("again and again", style),
+        ];
+        let options = RichLayoutOptions {
+            max_width: 80.0,
+            max_lines: Some(2),
+            ..RichLayoutOptions::default()
+        };
+        let layout = layout_rich_text(&runs, &options);
+        assert_eq!(layout.lines.len(), 2);
+        assert!(layout.truncated);
+        assert!(layout.glyphs.iter().any(|g| g.char_index == usize::MAX));
+        assert!(layout.lines.iter().all(|l| l.width <= 80.0 + 0.5));
+        assert!(!layout.underlines.is_empty());
+
+        let (x, top, bottom) = layout.caret_position(3);
+        assert_eq!(layout.hit_test(x + 0.1, (top + bottom) * 0.5), 3);
+        let second = layout.lines[1];
+        assert_eq!(layout.hit_test(-5.0, second.top + 1.0), second.start);
+    }
+
+    /// JetBrains Mono advances are 0.6 em (12px at 20px); notdef (uncovered Hebrew) is the same width.
+    fn mono(size: f32) -> TextStyle {
+        TextStyle {
+            family: FontFamily::JetBrainsMono,
+            size,
+            ..TextStyle::default()
+        }
+    }
+
+    fn box_of(layout: &RichTextLayout, index: usize) -> (f32, f32) {
+        let b = layout
+            .char_boxes
+            .iter()
+            .find(|b| b.char_index == index)
+            .unwrap();
+        (b.x0, b.x1)
+    }
+
+    #[test]
+    fn justify_stretches_spaces_except_on_the_last_line() {
+        let options = RichLayoutOptions {
+            max_width: 120.0,
+            align: RichAlign::Justify,
+            ..RichLayoutOptions::default()
+        };
+        let layout = layout_rich_text(&[TextRun::new("aa bb cc dd", mono(20.0))], &options);
+        assert_eq!(layout.lines.len(), 2);
+        // 96px of content + two spaces sharing the remaining 24px.
+        assert_eq!(layout.lines[0].width, 120.0);
+        assert_eq!(box_of(&layout, 2), (24.0, 48.0));
+        assert_eq!(box_of(&layout, 6), (96.0, 108.0));
+        assert_eq!(layout.lines[1].width, 24.0);
+        assert_eq!(box_of(&layout, 9), (0.0, 12.0));
+    }
+
+    #[test]
+    fn backgrounds_cover_their_run_and_render_under_glyphs() {
+        let red = (200, 0, 0, 255);
+        let runs = vec![
+            TextRun::new(
+                "ab",
+                TextStyle {
+                    background: Some(red),
+                    color: (0, 0, 0, 0),
+                    ..mono(20.0)
+                },
+            ),
+            TextRun::new("cd", mono(20.0)),
+        ];
+        let layout = layout_rich_text(&runs, &RichLayoutOptions::default());
+        let line = layout.lines[0];
+        assert_eq!(
+            layout.backgrounds,
+            vec![RichRect {
+                x: 0.0,
+                y: line.top,
+                width: 24.0,
+                height: line.bottom - line.top,
+                color: red,
+            }]
+        );
+
+        let (w, h) = (60usize, 40usize);
+        let mut frame = vec![0u8; w * h * 4];
+        layout.render(&mut frame, w, h, (0.0, 0.0), None);
+        let px = |x: usize, y: usize| &frame[(y * w + x) * 4..(y * w + x) * 4 + 4];
+        let mid = ((line.top + line.bottom) * 0.5) as usize;
+        assert_eq!(px(5, mid), &[200, 0, 0, 255]);
+        assert_eq!(px(30, 1), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn mixed_sizes_share_one_baseline() {
+        let runs = vec![
+            TextRun::new("ab", mono(20.0)),
+            TextRun::new("CD", mono(40.0)),
+        ];
+        let layout = layout_rich_text(&runs, &RichLayoutOptions::default());
+        assert_eq!(layout.lines.len(), 1);
+        let line = layout.lines[0];
+        let big = fonts::font_ref(FontFamily::JetBrainsMono)
+            .horizontal_line_metrics(40.0)
+            .unwrap();
+        assert!((line.baseline_y - big.ascent).abs() < 1e-3);
+        assert_eq!(box_of(&layout, 2), (24.0, 48.0));
+        assert_eq!(line.width, 72.0);
+        for g in &layout.glyphs {
+            let bottom = g.y + g.metrics.height as f32 + g.metrics.ymin as f32;
+            assert!((bottom - line.baseline_y).abs() < 1e-3, "glyph {}", g.char_index);
+        }
+    }
+
+    #[test]
+    fn rtl_hit_test_and_carets_mirror() {
+        // "ab " then a right-to-left word: its first logical char is drawn rightmost, at 72..84.
+        let layout = layout_rich_text(
+            &[TextRun::new("ab \u{5e9}\u{5dc}\u{5d5}\u{5dd}", mono(20.0))],
+            &RichLayoutOptions::default(),
+        );
+        assert_eq!(box_of(&layout, 3), (72.0, 84.0));
+        assert_eq!(box_of(&layout, 6), (36.0, 48.0));
+        let y = layout.lines[0].baseline_y;
+        assert_eq!(layout.hit_test(82.0, y), 3);
+        assert_eq!(layout.hit_test(74.0, y), 4);
+        assert_eq!(layout.hit_test(37.0, y), 7);
+        assert_eq!(layout.caret_position(3).0, 84.0);
+        assert_eq!(layout.caret_position(4).0, 72.0);
+    }
+
+    #[test]
+    fn wrapped_rtl_line_moves_trailing_space_to_the_end() {
+        // Rule L1: the space ending the first line takes the paragraph (LTR) level.
+        let layout = layout_rich_text(
+            &[TextRun::new(
+                "ab \u{5e9}\u{5dc}\u{5d5}\u{5dd} \u{5e2}\u{5d5}\u{5dc}\u{5dd}",
+                mono(20.0),
+            )],
+            &RichLayoutOptions {
+                max_width: 100.0,
+                ..RichLayoutOptions::default()
+            },
+        );
+        assert_eq!(layout.lines[0].end, 8);
+        assert_eq!(box_of(&layout, 7), (84.0, 96.0));
+        assert_eq!(box_of(&layout, 3), (72.0, 84.0));
+    }
+}
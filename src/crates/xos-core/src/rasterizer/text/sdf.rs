@@ -0,0 +1,472 @@
+//! Signed-distance-field glyph atlas: every glyph is rasterized **once** per font at [`SDF_BASE_SIZE`]
+//! and any pixel size is resampled from the distance field, so smooth zooms never re-rasterize
+//! outlines. The same field drives outline / glow / drop-shadow effects ([`TextEffects`]).
+//!
+//! Distance encoding: `0.5` sits on the glyph edge, `> 0.5` inside, and one unit spans
+//! `2 * SDF_SPREAD` base pixels.
+
+use super::text_rasterization::{Character, TextRasterizer};
+use fontdue::{Font, Metrics};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Em size glyphs are rasterized at before the distance transform.
+pub const SDF_BASE_SIZE: f32 = 48.0;
+/// Distance (base pixels) encoded on each side of the edge; also the padding around every glyph.
+pub const SDF_SPREAD: usize = 8;
+const ATLAS_WIDTH: usize = 1024;
+
+/// Placement of one glyph inside an [`SdfAtlas`] plus its base-size raster metrics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdfGlyph {
+    pub x: usize,
+    pub y: usize,
+    /// Field size in atlas pixels (base bitmap + `2 * SDF_SPREAD` padding).
+    pub w: usize,
+    pub h: usize,
+    /// fontdue metrics of the unpadded glyph at [`SDF_BASE_SIZE`].
+    pub base_xmin: f32,
+    pub base_ymin: f32,
+    pub base_width: f32,
+    pub base_height: f32,
+}
+
+/// Single-channel distance atlas for one font (shelf-packed, grows downward).
+pub struct SdfAtlas {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+    glyphs: HashMap<u16, SdfGlyph>,
+    shelf_x: usize,
+    shelf_y: usize,
+    shelf_h: usize,
+}
+
+impl Default for SdfAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SdfAtlas {
+    pub fn new() -> Self {
+        Self {
+            width: ATLAS_WIDTH,
+            height: 0,
+            data: Vec::new(),
+            glyphs: HashMap::new(),
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_h: 0,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Raw field (`width * height`, row-major) — e.g. for uploading as a GPU texture.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    #[inline]
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Atlas slot for `glyph_id`, generating its field on first use.
+    pub fn glyph(&mut self, font: &Font, glyph_id: u16) -> SdfGlyph {
+        if let Some(g) = self.glyphs.get(&glyph_id) {
+            return *g;
+        }
+        let (metrics, coverage) = font.rasterize_indexed(glyph_id, SDF_BASE_SIZE);
+        let field = distance_field(&coverage, metrics.width, metrics.height, SDF_SPREAD);
+        let w = metrics.width + 2 * SDF_SPREAD;
+        let h = metrics.height + 2 * SDF_SPREAD;
+        let (x, y) = self.allocate(w, h);
+        for row in 0..h {
+            let dst = (y + row) * self.width + x;
+            self.data[dst..dst + w].copy_from_slice(&field[row * w..(row + 1) * w]);
+        }
+        let g = SdfGlyph {
+            x,
+            y,
+            w,
+            h,
+            base_xmin: metrics.xmin as f32,
+            base_ymin: metrics.ymin as f32,
+            base_width: metrics.width as f32,
+            base_height: metrics.height as f32,
+        };
+        self.glyphs.insert(glyph_id, g);
+        g
+    }
+
+    fn allocate(&mut self, w: usize, h: usize) -> (usize, usize) {
+        let w = w.min(self.width);
+        if self.shelf_x + w > self.width {
+            self.shelf_y += self.shelf_h;
+            self.shelf_x = 0;
+            self.shelf_h = 0;
+        }
+        let (x, y) = (self.shelf_x, self.shelf_y);
+        self.shelf_x += w;
+        self.shelf_h = self.shelf_h.max(h);
+        let needed = y + h;
+        if needed > self.height {
+            let new_height = needed.max(self.height * 2).max(64);
+            self.data.resize(self.width * new_height, 0);
+            self.height = new_height;
+        }
+        (x, y)
+    }
+
+    /// Bilinear field sample at glyph-local atlas coordinates (pixel centers at `i + 0.5`); `0` outside.
+    #[inline]
+    pub fn sample(&self, g: &SdfGlyph, u: f32, v: f32) -> f32 {
+        let fx = u - 0.5;
+        let fy = v - 0.5;
+        let x0 = fx.floor();
+        let y0 = fy.floor();
+        let tx = fx - x0;
+        let ty = fy - y0;
+        let at = |ix: f32, iy: f32| -> f32 {
+            if ix < 0.0 || iy < 0.0 || ix >= g.w as f32 || iy >= g.h as f32 {
+                return 0.0;
+            }
+            self.data[(g.y + iy as usize) * self.width + g.x + ix as usize] as f32 / 255.0
+        };
+        let top = at(x0, y0) * (1.0 - tx) + at(x0 + 1.0, y0) * tx;
+        let bottom = at(x0, y0 + 1.0) * (1.0 - tx) + at(x0 + 1.0, y0 + 1.0) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Field value at a font-space point (target pixels, y up from the baseline) for a glyph drawn at
+    /// `scale = px_size / SDF_BASE_SIZE`.
+    #[inline]
+    fn sample_target(&self, g: &SdfGlyph, scale: f32, font_x: f32, font_y_up: f32) -> f32 {
+        let base_x = font_x / scale;
+        let base_y_up = font_y_up / scale;
+        let u = base_x - g.base_xmin + SDF_SPREAD as f32;
+        let v = g.base_ymin + g.base_height - base_y_up + SDF_SPREAD as f32;
+        self.sample(g, u, v)
+    }
+}
+
+/// One atlas per font file, shared by every rasterizer / renderer in the process.
+pub fn atlas_for(font: &Font) -> Arc<Mutex<SdfAtlas>> {
+    static ATLASES: OnceLock<Mutex<HashMap<usize, Arc<Mutex<SdfAtlas>>>>> = OnceLock::new();
+    let mut map = ATLASES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    Arc::clone(map.entry(font.file_hash()).or_default())
+}
+
+/// Exact squared Euclidean distance transform (Felzenszwalb & Huttenlocher) of a 1-D row.
+fn edt_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+    let parabola_cut = |q: usize, p: usize| -> f32 {
+        ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * q as f32 - 2.0 * p as f32)
+    };
+    let mut k = 0usize;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+    for q in 1..n {
+        // `z[0]` is -inf, so this never walks below the first parabola.
+        let mut s = parabola_cut(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = parabola_cut(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+    k = 0;
+    for (q, out) in d.iter_mut().enumerate().take(n) {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let p = v[k];
+        let dq = q as f32 - p as f32;
+        *out = dq * dq + f[p];
+    }
+}
+
+/// Squared distance from every cell to the nearest cell where `seed` is true.
+fn edt_2d(seed: &[bool], w: usize, h: usize) -> Vec<f32> {
+    const INF: f32 = 1e20;
+    let mut grid: Vec<f32> = seed.iter().map(|&s| if s { 0.0 } else { INF }).collect();
+    let n = w.max(h);
+    let mut f = vec![0.0f32; n];
+    let mut d = vec![0.0f32; n];
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0f32; n + 1];
+    for x in 0..w {
+        for y in 0..h {
+            f[y] = grid[y * w + x];
+        }
+        edt_1d(&f[..h], &mut d[..h], &mut v, &mut z);
+        for y in 0..h {
+            grid[y * w + x] = d[y];
+        }
+    }
+    for y in 0..h {
+        f[..w].copy_from_slice(&grid[y * w..(y + 1) * w]);
+        edt_1d(&f[..w], &mut d[..w], &mut v, &mut z);
+        grid[y * w..(y + 1) * w].copy_from_slice(&d[..w]);
+    }
+    grid
+}
+
+/// Padded distance field for an alpha `coverage` bitmap (`spread` pixels of padding on each side).
+pub fn distance_field(coverage: &[u8], width: usize, height: usize, spread: usize) -> Vec<u8> {
+    let w = width + 2 * spread;
+    let h = height + 2 * spread;
+    let mut alpha = vec![0u8; w * h];
+    for y in 0..height {
+        let src = &coverage[y * width..(y + 1) * width];
+        let dst = (y + spread) * w + spread;
+        alpha[dst..dst + width].copy_from_slice(src);
+    }
+    let inside: Vec<bool> = alpha.iter().map(|&a| a >= 128).collect();
+    let outside: Vec<bool> = inside.iter().map(|&i| !i).collect();
+    let to_inside = edt_2d(&inside, w, h);
+    let to_outside = edt_2d(&outside, w, h);
+
+    let range = 2.0 * spread as f32;
+    alpha
+        .iter()
+        .enumerate()
+        .map(|(i, &a)| {
+            // Pixel-center distances, nudged by coverage so anti-aliased edges stay sub-pixel accurate.
+            let coverage_bias = a as f32 / 255.0 - 0.5;
+            let signed = if inside[i] {
+                to_outside[i].sqrt() - 0.5 + coverage_bias.min(0.0)
+            } else {
+                -(to_inside[i].sqrt() - 0.5) + coverage_bias.max(0.0)
+            };
+            ((0.5 + signed / range).clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect()
+}
+
+/// Alpha bitmap for `glyph_id` at `px`, resampled from the font's atlas. Metrics match fontdue's
+/// [`Font::metrics_indexed`] so SDF and bitmap layouts are interchangeable.
+pub fn coverage_bitmap(font: &Font, glyph_id: u16, px: f32) -> (Metrics, Vec<u8>) {
+    let metrics = font.metrics_indexed(glyph_id, px);
+    if metrics.width == 0 || metrics.height == 0 {
+        return (metrics, Vec::new());
+    }
+    let atlas = atlas_for(font);
+    let mut atlas = atlas.lock().unwrap_or_else(|e| e.into_inner());
+    let g = atlas.glyph(font, glyph_id);
+    let scale = px / SDF_BASE_SIZE;
+    let edge_width = 1.0 / (2.0 * SDF_SPREAD as f32 * scale);
+    let top = metrics.ymin as f32 + metrics.height as f32;
+    let mut out = vec![0u8; metrics.width * metrics.height];
+    for y in 0..metrics.height {
+        let fy = top - (y as f32 + 0.5);
+        for x in 0..metrics.width {
+            let fx = metrics.xmin as f32 + x as f32 + 0.5;
+            let d = atlas.sample_target(&g, scale, fx, fy);
+            out[y * metrics.width + x] = (coverage_from_distance(d, edge_width) * 255.0) as u8;
+        }
+    }
+    (metrics, out)
+}
+
+#[inline]
+fn coverage_from_distance(d: f32, edge_width: f32) -> f32 {
+    ((d - 0.5) / edge_width.max(1e-4) + 0.5).clamp(0.0, 1.0)
+}
+
+/// Effects rendered from the distance field by [`draw_text_sdf`]. Radii are in target pixels and are
+/// capped by the encoded spread (`SDF_SPREAD * px / SDF_BASE_SIZE`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    /// `(width_px, rgba)` stroke hugging the outside of the glyph.
+    pub outline: Option<(f32, [u8; 4])>,
+    /// `(radius_px, rgba)` soft halo fading out from the edge.
+    pub glow: Option<(f32, [u8; 4])>,
+    /// `(dx, dy, softness_px, rgba)` offset copy drawn beneath everything else.
+    pub shadow: Option<(f32, f32, f32, [u8; 4])>,
+}
+
+impl TextEffects {
+    /// Extra pixels an effect can paint beyond the glyph box.
+    fn margin(&self) -> f32 {
+        let outline = self.outline.map(|(w, _)| w).unwrap_or(0.0);
+        let glow = self.glow.map(|(r, _)| r).unwrap_or(0.0);
+        let shadow = self
+            .shadow
+            .map(|(dx, dy, soft, _)| dx.abs().max(dy.abs()) + soft)
+            .unwrap_or(0.0);
+        outline.max(glow).max(shadow).ceil() + 1.0
+    }
+}
+
+#[inline]
+fn blend(dst: &mut [u8], rgba: [u8; 4], coverage: f32) {
+    let a = coverage * rgba[3] as f32 / 255.0;
+    if a <= 0.0 {
+        return;
+    }
+    let inv = 1.0 - a;
+    dst[0] = (rgba[0] as f32 * a + dst[0] as f32 * inv) as u8;
+    dst[1] = (rgba[1] as f32 * a + dst[1] as f32 * inv) as u8;
+    dst[2] = (rgba[2] as f32 * a + dst[2] as f32 * inv) as u8;
+    dst[3] = 255;
+}
+
+/// Draw one laid-out glyph (from [`TextRasterizer::characters`]) straight from the atlas with effects.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_character_sdf(
+    frame: &mut [u8],
+    frame_width: usize,
+    frame_height: usize,
+    font: &Font,
+    character: &Character,
+    font_px: f32,
+    origin: (f32, f32),
+    color: [u8; 4],
+    effects: &TextEffects,
+) {
+    let m = &character.metrics;
+    if m.width == 0 || m.height == 0 || frame_width == 0 {
+        return;
+    }
+    let atlas = atlas_for(font);
+    let mut atlas = atlas.lock().unwrap_or_else(|e| e.into_inner());
+    let g = atlas.glyph(font, character.glyph_id);
+    let scale = font_px / SDF_BASE_SIZE;
+    let spread_px = SDF_SPREAD as f32 * scale;
+    let edge_width = 1.0 / (2.0 * spread_px);
+
+    let margin = effects.margin();
+    // Glyph box top-left in frame pixels, and the font-space coordinates of that corner.
+    let box_x = origin.0 + character.x;
+    let box_y = origin.1 + character.y;
+    let top = m.ymin as f32 + m.height as f32;
+    let x0 = (box_x - margin).floor().max(0.0) as usize;
+    let y0 = (box_y - margin).floor().max(0.0) as usize;
+    let x1 = ((box_x + m.width as f32 + margin).ceil() as usize).min(frame_width);
+    let y1 = ((box_y + m.height as f32 + margin).ceil() as usize).min(frame_height);
+
+    let field = |px: f32, py: f32| -> f32 {
+        let fx = m.xmin as f32 + (px - box_x);
+        let fy = top - (py - box_y);
+        atlas.sample_target(&g, scale, fx, fy)
+    };
+    let outline = effects
+        .outline
+        .map(|(w, c)| (w.min(spread_px) * edge_width, c));
+    let glow = effects
+        .glow
+        .map(|(r, c)| (r.min(spread_px) * edge_width, c));
+
+    for py in y0..y1 {
+        let cy = py as f32 + 0.5;
+        for px in x0..x1 {
+            let cx = px as f32 + 0.5;
+            let idx = (py * frame_width + px) * 4;
+            let dst = &mut frame[idx..idx + 4];
+
+            if let Some((dx, dy, soft, c)) = effects.shadow {
+                let d = field(cx - dx, cy - dy);
+                let soft_w = edge_width * (1.0 + soft.min(spread_px));
+                blend(dst, c, coverage_from_distance(d, soft_w));
+            }
+            let d = field(cx, cy);
+            if let Some((reach, c)) = glow {
+                if d < 0.5 && reach > 0.0 {
+                    let t = (1.0 - (0.5 - d) / reach).clamp(0.0, 1.0);
+                    blend(dst, c, t * t);
+                }
+            }
+            if let Some((reach, c)) = outline {
+                blend(dst, c, coverage_from_distance(d + reach, edge_width));
+            }
+            blend(dst, color, coverage_from_distance(d, edge_width));
+        }
+    }
+}
+
+/// Draw every glyph of a ticked [`TextRasterizer`] at `origin` using the SDF path.
+pub fn draw_text_sdf(
+    frame: &mut [u8],
+    frame_width: usize,
+    frame_height: usize,
+    rasterizer: &TextRasterizer,
+    origin: (f32, f32),
+    color: [u8; 4],
+    effects: &TextEffects,
+) {
+    let scales = &rasterizer.glyph_scale_spans;
+    for c in &rasterizer.characters {
+        let scale = scales
+            .iter()
+            .rev()
+            .find(|(s, e, _)| c.char_index >= *s && c.char_index < *e)
+            .map(|(_, _, m)| *m)
+            .unwrap_or(1.0)
+            .clamp(0.125, 16.0);
+        draw_character_sdf(
+            frame,
+            frame_width,
+            frame_height,
+            rasterizer.font_for_character(c),
+            c,
+            rasterizer.font_size * scale,
+            origin,
+            color,
+            effects,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_field_edge_is_half() {
+        // 4x4 solid square: inside well above 0.5, far outside near 0, edge around 0.5.
+        let coverage = vec![255u8; 16];
+        let field = distance_field(&coverage, 4, 4, 4);
+        let w = 12;
+        let center = field[6 * w + 6];
+        let corner = field[0];
+        let edge_inside = field[4 * w + 6];
+        let edge_outside = field[3 * w + 6];
+        assert!(center > 150, "center {center}");
+        assert!(corner < 40, "corner {corner}");
+        assert!(edge_inside > 128 && edge_outside < 128);
+        assert!((edge_inside as i32 - 128).abs() < 24);
+    }
+
+    #[test]
+    fn atlas_shelves_wrap_and_grow() {
+        let mut atlas = SdfAtlas::new();
+        let (x0, y0) = atlas.allocate(600, 40);
+        let (x1, y1) = atlas.allocate(600, 30);
+        assert_eq!((x0, y0), (0, 0));
+        assert_eq!((x1, y1), (0, 40));
+        assert!(atlas.height() >= 70);
+        assert_eq!(atlas.data().len(), atlas.width() * atlas.height());
+    }
+}
@@ -0,0 +1,318 @@
+//! Shaping stage for [`super::text_rasterization::TextRasterizer`]: bidi levels, grapheme-safe break
+//! points and positioned glyph runs (kerning, ligatures, Arabic joining, Indic reordering via rustybuzz).
+//!
+//! Work is done per *paragraph* (newline-free slice). Glyphs are kept in **logical** order so line
+//! wrapping can slice them by char index; [`visual_order`] applies UAX #9 L1/L2 per wrapped line.
+//! Chars the primary font lacks are itemized onto the first fallback font that covers them.
+
+use fontdue::Font;
+use rustybuzz::{Direction, Face, UnicodeBuffer};
+use unicode_bidi::{Level, ParagraphBidiInfo};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A font offered to the shaper: fontdue for coverage/metrics, raw bytes (if known) for rustybuzz.
+#[derive(Clone, Copy)]
+pub struct ShapingFont<'a> {
+    pub font: &'a Font,
+    pub bytes: Option<&'a [u8]>,
+}
+
+/// One positioned glyph in font pixels (before per-char scale spans / spacing are applied).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapedGlyph {
+    pub glyph_id: u16,
+    /// Index into the `fonts` slice passed to [`shape_paragraph`] (`0` = primary).
+    pub font: u8,
+    /// Paragraph-relative char index of the first char in this glyph's cluster.
+    pub cluster: usize,
+    pub x_advance: f32,
+    pub x_offset: f32,
+    /// Positive = up (font convention).
+    pub y_offset: f32,
+    /// Bidi embedding level of the cluster (odd = right-to-left).
+    pub level: u8,
+}
+
+impl ShapedGlyph {
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+}
+
+/// Shaped paragraph: logical glyph order plus per-char data for wrapping.
+#[derive(Clone, Debug, Default)]
+pub struct ShapedParagraph {
+    /// Glyphs in logical order (RTL runs un-reversed); multiple glyphs may share a cluster.
+    pub glyphs: Vec<ShapedGlyph>,
+    /// Per char: summed advance of the cluster it starts (`0.0` for non-initial cluster chars).
+    pub char_advances: Vec<f32>,
+    /// Per char: `true` when a line may start before this char (grapheme + shaping cluster boundary).
+    pub break_before: Vec<bool>,
+    /// Per char bidi level (resolved, before line-level rule L1).
+    pub levels: Vec<u8>,
+    /// Paragraph base level (`0` = LTR, `1` = RTL).
+    pub base_level: u8,
+}
+
+impl ShapedParagraph {
+    #[inline]
+    pub fn is_pure_ltr(&self) -> bool {
+        self.base_level == 0 && self.levels.iter().all(|&l| l == 0)
+    }
+}
+
+/// Maps byte offsets to char indices for `text`.
+fn byte_to_char_map(text: &str) -> Vec<usize> {
+    let mut map = vec![0usize; text.len() + 1];
+    let mut ci = 0usize;
+    for (bi, ch) in text.char_indices() {
+        for slot in map.iter_mut().skip(bi).take(ch.len_utf8()) {
+            *slot = ci;
+        }
+        ci += 1;
+    }
+    map[text.len()] = ci;
+    map
+}
+
+/// Resolves bidi levels per char (`None` base → first strong char decides, default LTR).
+fn char_levels(text: &str) -> (Vec<u8>, u8, bool) {
+    let info = ParagraphBidiInfo::new(text, None);
+    let levels = text
+        .char_indices()
+        .map(|(bi, _)| info.levels[bi].number())
+        .collect();
+    (levels, info.paragraph_level.number(), info.is_pure_ltr)
+}
+
+/// Grapheme-cluster starts as a per-char flag.
+fn grapheme_starts(text: &str, byte_to_char: &[usize], n_chars: usize) -> Vec<bool> {
+    let mut starts = vec![false; n_chars];
+    for (bi, _) in text.grapheme_indices(true) {
+        if let Some(slot) = starts.get_mut(byte_to_char[bi]) {
+            *slot = true;
+        }
+    }
+    starts
+}
+
+/// Per-char font choice: first font covering the char; grapheme continuations (marks, ZWJ sequences,
+/// variation selectors) and whitespace stay with the preceding char's font to avoid splitting runs.
+fn itemize_fonts(fonts: &[ShapingFont], chars: &[char], grapheme_start: &[bool]) -> Vec<u8> {
+    let mut out = vec![0u8; chars.len()];
+    if fonts.len() <= 1 {
+        return out;
+    }
+    let mut prev = 0u8;
+    for (ci, &ch) in chars.iter().enumerate() {
+        let pick = if ci > 0 && (!grapheme_start[ci] || ch.is_whitespace()) {
+            prev
+        } else {
+            fonts
+                .iter()
+                .position(|f| f.font.has_glyph(ch))
+                .unwrap_or(0)
+                .min(u8::MAX as usize) as u8
+        };
+        out[ci] = pick;
+        prev = pick;
+    }
+    out
+}
+
+/// Shape a newline-free paragraph with `fonts[0]` as the primary font and the rest as fallbacks.
+///
+/// Runs whose font has no raw bytes (or unparsable ones) map each char to one glyph with fontdue's
+/// advance — bidi reordering and grapheme-safe breaks still apply.
+pub fn shape_paragraph(fonts: &[ShapingFont], text: &str, font_size: f32) -> ShapedParagraph {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    if n == 0 || fonts.is_empty() {
+        return ShapedParagraph::default();
+    }
+
+    let byte_to_char = byte_to_char_map(text);
+    let (levels, base_level, pure_ltr) = char_levels(text);
+    let graphemes = grapheme_starts(text, &byte_to_char, n);
+    let font_of = itemize_fonts(fonts, &chars, &graphemes);
+    let mut break_before = graphemes;
+    let faces: Vec<Option<Face>> = fonts
+        .iter()
+        .map(|f| f.bytes.and_then(|b| Face::from_slice(b, 0)))
+        .collect();
+    let char_byte: Vec<usize> = text.char_indices().map(|(bi, _)| bi).collect();
+
+    let mut glyphs: Vec<ShapedGlyph> = Vec::with_capacity(n);
+    // Runs: maximal char ranges with one embedding level and one font, shaped in their own direction.
+    let mut run_start = 0usize;
+    for ci in 1..=n {
+        if ci < n
+            && font_of[ci] == font_of[run_start]
+            && (pure_ltr || levels[ci] == levels[run_start])
+        {
+            continue;
+        }
+        let level = levels[run_start];
+        let rtl = level % 2 == 1;
+        let fi = font_of[run_start];
+        let start = glyphs.len();
+        match &faces[fi as usize] {
+            Some(face) => {
+                let b0 = char_byte[run_start];
+                let b1 = char_byte.get(ci).copied().unwrap_or(text.len());
+                let scale = font_size / face.units_per_em().max(1) as f32;
+
+                let mut buffer = UnicodeBuffer::new();
+                buffer.push_str(&text[b0..b1]);
+                buffer.guess_segment_properties();
+                buffer.set_direction(if rtl {
+                    Direction::RightToLeft
+                } else {
+                    Direction::LeftToRight
+                });
+                let out = rustybuzz::shape(face, &[], buffer);
+
+                for (info, pos) in out.glyph_infos().iter().zip(out.glyph_positions()) {
+                    glyphs.push(ShapedGlyph {
+                        glyph_id: info.glyph_id as u16,
+                        font: fi,
+                        cluster: byte_to_char[b0 + info.cluster as usize],
+                        x_advance: pos.x_advance as f32 * scale,
+                        x_offset: pos.x_offset as f32 * scale,
+                        y_offset: pos.y_offset as f32 * scale,
+                        level,
+                    });
+                }
+                if rtl {
+                    // Shaper emits RTL runs visually; store logically so lines can be sliced by char index.
+                    glyphs[start..].reverse();
+                }
+            }
+            None => {
+                let font = fonts[fi as usize].font;
+                for (k, &ch) in chars[run_start..ci].iter().enumerate() {
+                    let glyph_id = font.lookup_glyph_index(ch);
+                    glyphs.push(ShapedGlyph {
+                        glyph_id,
+                        font: fi,
+                        cluster: run_start + k,
+                        x_advance: font.metrics_indexed(glyph_id, font_size).advance_width,
+                        x_offset: 0.0,
+                        y_offset: 0.0,
+                        level,
+                    });
+                }
+            }
+        }
+        run_start = ci;
+    }
+
+    // A char is a cluster head when some glyph points at it; ligature tails may not start a line.
+    let mut is_head = vec![false; n];
+    let mut char_advances = vec![0.0f32; n];
+    for g in &glyphs {
+        if let Some(h) = is_head.get_mut(g.cluster) {
+            *h = true;
+            char_advances[g.cluster] += g.x_advance;
+        }
+    }
+    for (brk, head) in break_before.iter_mut().zip(&is_head) {
+        *brk &= *head;
+    }
+    break_before[0] = true;
+
+    ShapedParagraph {
+        glyphs,
+        char_advances,
+        break_before,
+        levels,
+        base_level,
+    }
+}
+
+/// Indices into `glyphs` (all belonging to one wrapped line) in left-to-right display order.
+///
+/// `line_levels` are per-char levels for the line after rule L1 (trailing whitespace reset), indexed by
+/// `cluster - line_start`.
+pub fn visual_order(glyphs: &[ShapedGlyph], line_start: usize, line_levels: &[u8]) -> Vec<usize> {
+    let levels: Vec<Level> = glyphs
+        .iter()
+        .map(|g| {
+            let l = line_levels
+                .get(g.cluster.saturating_sub(line_start))
+                .copied()
+                .unwrap_or(g.level);
+            Level::new(l).unwrap_or_else(|_| Level::ltr())
+        })
+        .collect();
+    if levels.iter().all(|l| l.is_ltr() && l.number() == 0) {
+        return (0..glyphs.len()).collect();
+    }
+    ParagraphBidiInfo::reorder_visual(&levels)
+}
+
+/// Per-char levels for `paragraph[line_start..line_end)` with rule L1 applied (trailing whitespace and
+/// separators take the paragraph level).
+pub fn line_levels(paragraph: &str, line_start: usize, line_end: usize) -> Vec<u8> {
+    if line_start >= line_end {
+        return Vec::new();
+    }
+    let info = ParagraphBidiInfo::new(paragraph, None);
+    if info.is_pure_ltr {
+        return vec![0; line_end - line_start];
+    }
+    let char_byte: Vec<usize> = paragraph
+        .char_indices()
+        .map(|(bi, _)| bi)
+        .chain(std::iter::once(paragraph.len()))
+        .collect();
+    let b0 = char_byte[line_start.min(char_byte.len() - 1)];
+    let b1 = char_byte[line_end.min(char_byte.len() - 1)];
+    // Levels come back for the whole paragraph; only the line slice has L1 applied.
+    info.reordered_levels_per_char(b0..b1)[line_start..line_end]
+        .iter()
+        .map(|l| l.number())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visual_order_reverses_rtl_run_only() {
+        let mk = |cluster, level| ShapedGlyph {
+            glyph_id: 0,
+            font: 0,
+            cluster,
+            x_advance: 1.0,
+            x_offset: 0.0,
+            y_offset: 0.0,
+            level,
+        };
+        // "ab" LTR, then three RTL clusters, then "c".
+        let glyphs = vec![mk(0, 0), mk(1, 0), mk(2, 1), mk(3, 1), mk(4, 1), mk(5, 0)];
+        let levels = [0, 0, 1, 1, 1, 0];
+        assert_eq!(visual_order(&glyphs, 0, &levels), vec![0, 1, 4, 3, 2, 5]);
+    }
+
+    #[test]
+    fn byte_map_handles_multibyte() {
+        let m = byte_to_char_map("aé漢");
+        assert_eq!(m[0], 0);
+        assert_eq!(m[1], 1);
+        assert_eq!(m[2], 1);
+        assert_eq!(m[3], 2);
+        assert_eq!(m[6], 3);
+    }
+
+    #[test]
+    fn levels_detect_rtl_paragraph() {
+        let (levels, base, pure) = char_levels("שלום abc");
+        assert_eq!(base, 1);
+        assert!(!pure);
+        assert_eq!(levels[0], 1);
+        assert_eq!(levels[5], 2);
+    }
+}
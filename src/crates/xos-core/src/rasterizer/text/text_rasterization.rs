@@ -1,6 +1,8 @@
 use fontdue::{Font, Metrics};
 
-use super::fonts;
+use super::fonts::{self, FontBytes};
+use super::sdf;
+use super::shaping::{self, ShapedParagraph, ShapingFont};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -14,16 +16,44 @@ pub struct Character {
     pub height: f32,
     pub line_index: usize,
     pub char_index: usize,
+    /// Raster metrics; `advance_width` is the shaped advance (kerning / ligatures included).
     pub metrics: Metrics,
-    /// Grayscale alpha bitmap; shared across identical `(char, font_size)` via [`GlyphCache`].
+    /// Grayscale alpha bitmap; shared across identical `(glyph, font_size)` via [`GlyphCache`] (SDF mode
+    /// resamples it from the atlas on each layout).
     pub bitmap: Arc<Vec<u8>>,
+    /// Glyph sits in a right-to-left bidi run (caret *before* it is on its right edge).
+    pub rtl: bool,
+    /// Font glyph index (after shaping) — used to sample the SDF atlas directly.
+    pub glyph_id: u16,
+    /// `0` = [`TextRasterizer::font`], `1..` = fallbacks (see [`TextRasterizer::font_for_character`]).
+    pub font_slot: u8,
+    /// Left / right edge of this char's caret box (spacing included). Chars of one shaping cluster
+    /// (ligature tails, combining marks) split the cluster's box evenly; a tail with no glyph of its
+    /// own gets an empty bitmap.
+    pub box_x0: f32,
+    pub box_x1: f32,
 }
 
-/// Caches fontdue raster output keyed by `(char, font_size_bits)` — avoids re-rasterizing every frame.
+/// How [`GlyphCache`] produces glyph bitmaps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GlyphRenderMode {
+    /// fontdue raster per `(glyph, size)` — crispest for static UI text.
+    #[default]
+    Bitmap,
+    /// Resampled from the per-font [`sdf::SdfAtlas`]; outlines are rasterized once at any zoom level.
+    Sdf,
+    /// Bitmap while the font size is stable, SDF while it keeps changing (smooth zoom / pinch).
+    Auto,
+}
+
+/// Caches fontdue raster output keyed by `(font slot, glyph_id, font_size_bits)` — avoids re-rasterizing
+/// every frame. Slot `0` is the rasterizer's primary font, `1..` its fallbacks. In SDF mode nothing is
+/// cached per size: the font's [`sdf::SdfAtlas`] keeps one field per glyph and bitmaps are resampled.
 #[derive(Debug)]
 pub struct GlyphCache {
-    map: HashMap<(char, u32), (Metrics, Arc<Vec<u8>>)>,
+    map: HashMap<(u8, u16, u32), (Metrics, Arc<Vec<u8>>)>,
     max_entries: usize,
+    sdf: bool,
 }
 
 impl GlyphCache {
@@ -31,6 +61,7 @@ impl GlyphCache {
         Self {
             map: HashMap::new(),
             max_entries: 16_384,
+            sdf: false,
         }
     }
 
@@ -38,19 +69,37 @@ impl GlyphCache {
         self.map.clear();
     }
 
-    fn get_or_insert(&mut self, font: &Font, ch: char, font_size: f32) -> (Metrics, Arc<Vec<u8>>) {
-        let key = (ch, font_size.to_bits());
+    fn get_or_insert(
+        &mut self,
+        font: &Font,
+        slot: u8,
+        glyph_id: u16,
+        font_size: f32,
+    ) -> (Metrics, Arc<Vec<u8>>) {
+        if self.sdf {
+            let (metrics, bitmap) = sdf::coverage_bitmap(font, glyph_id, font_size);
+            return (metrics, Arc::new(bitmap));
+        }
+        let key = (slot, glyph_id, font_size.to_bits());
         if let Some((m, arc)) = self.map.get(&key) {
             return (*m, Arc::clone(arc));
         }
         if self.map.len() >= self.max_entries {
             self.map.clear();
         }
-        let (metrics, bitmap) = font.rasterize(ch, font_size);
+        let (metrics, bitmap) = font.rasterize_indexed(glyph_id, font_size);
         let arc = Arc::new(bitmap);
         self.map.insert(key, (metrics, Arc::clone(&arc)));
         (metrics, arc)
     }
+
+    /// Raster metrics only; SDF mode skips resampling the bitmap.
+    fn metrics(&mut self, font: &Font, slot: u8, glyph_id: u16, font_size: f32) -> Metrics {
+        if self.sdf {
+            return font.metrics_indexed(glyph_id, font_size);
+        }
+        self.get_or_insert(font, slot, glyph_id, font_size).0
+    }
 }
 
 #[derive(Debug)]
@@ -141,6 +190,17 @@ pub struct TextRasterizer {
     pub descent: f32,
     pub line_gap: f32,
     pub font: Font,
+    /// Raw file behind [`Self::font`] for the shaping stage (`None` → one glyph per char, no kerning).
+    shaping_bytes: Option<FontBytes>,
+    shaping_enabled: bool,
+    /// Fonts tried in order for chars [`Self::font`] lacks (see [`fonts::fallback_chain`]).
+    fallback_fonts: Vec<(Arc<Font>, FontBytes)>,
+    /// [`fonts::registry_version`] the fallbacks were resolved against.
+    font_registry_seen: u64,
+    render_mode: GlyphRenderMode,
+    /// [`GlyphRenderMode::Auto`] bookkeeping: recent size changes and ticks since the last one.
+    size_churn: u32,
+    stable_ticks: u32,
     glyph_cache: GlyphCache,
     /// When fingerprint matches [`Self::tick`] inputs unchanged, reuse [`Self::characters`]/[`Self::lines`].
     last_layout_quick_fp: Option<u64>,
@@ -166,6 +226,13 @@ impl TextRasterizer {
             ascent: metrics.ascent,
             descent: metrics.descent.abs(),
             line_gap: metrics.line_gap,
+            shaping_bytes: fonts::font_bytes_for(&font),
+            shaping_enabled: true,
+            fallback_fonts: fonts::fallback_fonts_for(&font),
+            font_registry_seen: fonts::registry_version(),
+            render_mode: GlyphRenderMode::Bitmap,
+            size_churn: 0,
+            stable_ticks: 0,
             font,
             glyph_cache: GlyphCache::new(),
             last_layout_quick_fp: None,
@@ -256,6 +323,8 @@ impl TextRasterizer {
         }
         self.last_layout_quick_fp = None;
         self.font_size = font_size;
+        self.size_churn = self.size_churn.saturating_add(1);
+        self.stable_ticks = 0;
         self.glyph_cache.clear();
         let metrics = self
             .font
@@ -277,6 +346,70 @@ impl TextRasterizer {
         self.last_layout_quick_fp = None;
     }
 
+    /// Toggle complex shaping (kerning, ligatures, contextual forms). Bidi reordering stays on either way.
+    pub fn set_shaping_enabled(&mut self, enabled: bool) {
+        if self.shaping_enabled != enabled {
+            self.shaping_enabled = enabled;
+            self.last_layout_quick_fp = None;
+        }
+    }
+
+    /// Choose between per-size bitmaps and the shared SDF atlas (see [`GlyphRenderMode`]).
+    pub fn set_render_mode(&mut self, mode: GlyphRenderMode) {
+        if self.render_mode == mode {
+            return;
+        }
+        self.render_mode = mode;
+        self.size_churn = 0;
+        self.stable_ticks = 0;
+        self.apply_sdf(mode == GlyphRenderMode::Sdf);
+    }
+
+    #[inline]
+    pub fn render_mode(&self) -> GlyphRenderMode {
+        self.render_mode
+    }
+
+    /// Whether glyph bitmaps currently come from the SDF atlas.
+    #[inline]
+    pub fn uses_sdf(&self) -> bool {
+        self.glyph_cache.sdf
+    }
+
+    fn apply_sdf(&mut self, sdf: bool) {
+        if self.glyph_cache.sdf != sdf {
+            self.glyph_cache.sdf = sdf;
+            self.glyph_cache.clear();
+            self.last_layout_quick_fp = None;
+        }
+    }
+
+    /// [`GlyphRenderMode::Auto`]: switch to SDF after a few consecutive size changes, back to bitmaps
+    /// once the size has held still for about a second and a half of ticks.
+    fn update_auto_render_mode(&mut self) {
+        const CHURN_TO_SDF: u32 = 3;
+        const STABLE_TICKS_TO_BITMAP: u32 = 90;
+        if self.render_mode != GlyphRenderMode::Auto {
+            return;
+        }
+        self.stable_ticks = self.stable_ticks.saturating_add(1);
+        if self.stable_ticks > STABLE_TICKS_TO_BITMAP {
+            self.size_churn = 0;
+        }
+        self.apply_sdf(self.size_churn >= CHURN_TO_SDF);
+    }
+
+    /// Font that produced `c` (primary or one of the fallbacks).
+    #[inline]
+    pub fn font_for_character(&self, c: &Character) -> &Font {
+        Self::font_in_slot(&self.font, &self.fallback_fonts, c.font_slot)
+    }
+
+    #[inline]
+    pub fn shaping_enabled(&self) -> bool {
+        self.shaping_enabled && self.shaping_bytes.is_some()
+    }
+
     #[inline]
     pub fn spacing(&self) -> (f32, f32) {
         self.spacing
@@ -287,6 +420,26 @@ impl TextRasterizer {
         advance_width * self.spacing.0.max(0.0)
     }
 
+    /// Caret X *before* `c` in logical order: left edge for LTR glyphs, right edge for RTL glyphs.
+    #[inline]
+    pub fn caret_x_before(&self, c: &Character) -> f32 {
+        if c.rtl {
+            c.box_x1
+        } else {
+            c.box_x0
+        }
+    }
+
+    /// Caret X *after* `c` in logical order (mirror of [`Self::caret_x_before`]).
+    #[inline]
+    pub fn caret_x_after(&self, c: &Character) -> f32 {
+        if c.rtl {
+            c.box_x0
+        } else {
+            c.box_x1
+        }
+    }
+
     pub fn tick(&mut self, window_width: f32, window_height: f32) {
         self.tick_aligned(window_width, window_height, TextLayoutAlign::default());
     }
@@ -306,6 +459,57 @@ impl TextRasterizer {
         h
     }
 
+    /// Re-resolve shaping bytes and fallbacks after fonts are registered or chains change.
+    fn refresh_font_registry(&mut self) {
+        let version = fonts::registry_version();
+        if version == self.font_registry_seen {
+            return;
+        }
+        self.font_registry_seen = version;
+        self.shaping_bytes = fonts::font_bytes_for(&self.font);
+        self.fallback_fonts = fonts::fallback_fonts_for(&self.font);
+        self.glyph_cache.clear();
+        self.last_layout_quick_fp = None;
+    }
+
+    /// Font for a [`GlyphCache`] slot / [`shaping::ShapedGlyph::font`] index.
+    #[inline]
+    fn font_in_slot<'a>(
+        primary: &'a Font,
+        fallbacks: &'a [(Arc<Font>, FontBytes)],
+        slot: u8,
+    ) -> &'a Font {
+        match slot {
+            0 => primary,
+            k => fallbacks
+                .get(k as usize - 1)
+                .map(|(f, _)| &**f)
+                .unwrap_or(primary),
+        }
+    }
+
+    /// Shape every newline-delimited paragraph: `(first char index, paragraph text, shaped runs)`.
+    fn shape_paragraphs(&self) -> Vec<(usize, String, ShapedParagraph)> {
+        let shaping = self.shaping_enabled;
+        let mut chain = vec![ShapingFont {
+            font: &self.font,
+            bytes: self.shaping_bytes.as_deref().filter(|_| shaping),
+        }];
+        chain.extend(self.fallback_fonts.iter().map(|(font, bytes)| ShapingFont {
+            font,
+            bytes: Some(&**bytes).filter(|_| shaping),
+        }));
+        let mut out = Vec::new();
+        let mut start = 0usize;
+        for para in self.text.split('\n') {
+            let n = para.chars().count();
+            let shaped = shaping::shape_paragraph(&chain, para, self.font_size);
+            out.push((start, para.to_string(), shaped));
+            start += n + 1;
+        }
+        out
+    }
+
     /// Word-wrap in `window_width`, optional per-line horizontal centering and vertical centering
     /// when the laid-out block is shorter than `window_height`.
     pub fn tick_aligned(&mut self, window_width: f32, window_height: f32, align: TextLayoutAlign) {
@@ -316,6 +520,9 @@ impl TextRasterizer {
             self.last_layout_quick_fp = None;
         }
 
+        self.refresh_font_registry();
+        self.update_auto_render_mode();
+
         let align = align.normalized();
         let base_fp = if self.text.is_empty() {
             Self::empty_layout_fp(window_width, self.font_size)
@@ -334,6 +541,7 @@ impl TextRasterizer {
         self.lines.clear();
 
         let font = &self.font;
+        let fallbacks = &self.fallback_fonts;
         let fs = self.font_size;
         let sx = self.spacing.0.max(0.0);
         let sy = self.spacing.1.max(0.0);
@@ -361,48 +569,26 @@ impl TextRasterizer {
                 .unwrap_or(body_line_gap)
         };
 
-        let mut proto_lines: Vec<ProtoLine> = Vec::new();
-        let mut line_start = 0usize;
-        let mut x = 0.0f32;
-        let mut line_ascent = 0.0f32;
-        let mut line_descent = 0.0f32;
-        let mut line_max_scale = 1.0f32;
-
-        if chars.is_empty() {
-            proto_lines.push(ProtoLine {
-                start: 0,
-                end_excl: 0,
-                ascent: 0.0,
-                descent: 0.0,
-                max_scale: 1.0,
-            });
-        } else {
-            let mut i = 0usize;
-            while i < chars.len() {
-                let ch = chars[i];
-                if ch == '\n' {
-                    proto_lines.push(ProtoLine {
-                        start: line_start,
-                        end_excl: i,
-                        ascent: line_ascent,
-                        descent: line_descent,
-                        max_scale: line_max_scale,
-                    });
-                    line_start = i + 1;
-                    line_ascent = 0.0;
-                    line_descent = 0.0;
-                    line_max_scale = 1.0;
-                    x = 0.0;
-                    i += 1;
-                    continue;
-                }
+        let paragraphs = self.shape_paragraphs();
 
+        let mut proto_lines: Vec<ProtoLine> = Vec::new();
+        for (para_start, _, shaped) in &paragraphs {
+            let para_start = *para_start;
+            let mut line_start = para_start;
+            let mut x = 0.0f32;
+            let mut line_ascent = 0.0f32;
+            let mut line_descent = 0.0f32;
+            let mut line_max_scale = 1.0f32;
+            let mut gi = 0usize;
+
+            for (local, &advance) in shaped.char_advances.iter().enumerate() {
+                let i = para_start + local;
                 let scale_i = Self::scale_at_char(scales, i);
                 let fs_i = fs * scale_i;
-                let (metrics, _) = self.glyph_cache.get_or_insert(font, ch, fs_i);
-                let advance_step = metrics.advance_width * sx;
+                let advance_step = advance * scale_i * sx;
 
-                if x + advance_step > window_width {
+                // Only break where a grapheme and a shaping cluster both start.
+                if shaped.break_before[local] && x + advance_step > window_width {
                     proto_lines.push(ProtoLine {
                         start: line_start,
                         end_excl: i,
@@ -417,19 +603,32 @@ impl TextRasterizer {
                     x = 0.0;
                 }
 
-                let ink_above = metrics.height as f32 + metrics.ymin as f32;
-                let ink_below = (-metrics.ymin as f32).max(0.0);
-                line_ascent = line_ascent.max(ink_above);
-                line_descent = line_descent.max(ink_below);
+                // Glyphs are sorted by cluster, so walk the ones owned by this char.
+                while let Some(g) = shaped.glyphs.get(gi) {
+                    if g.cluster > local {
+                        break;
+                    }
+                    let metrics = self.glyph_cache.metrics(
+                        Self::font_in_slot(font, fallbacks, g.font),
+                        g.font,
+                        g.glyph_id,
+                        fs_i,
+                    );
+                    let lift = g.y_offset * scale_i;
+                    let ink_above = metrics.height as f32 + metrics.ymin as f32 + lift;
+                    let ink_below = (-(metrics.ymin as f32) - lift).max(0.0);
+                    line_ascent = line_ascent.max(ink_above);
+                    line_descent = line_descent.max(ink_below);
+                    gi += 1;
+                }
                 line_max_scale = line_max_scale.max(scale_i);
 
                 x += advance_step;
-                i += 1;
             }
 
             proto_lines.push(ProtoLine {
                 start: line_start,
-                end_excl: chars.len(),
+                end_excl: para_start + shaped.char_advances.len(),
                 ascent: line_ascent,
                 descent: line_descent,
                 max_scale: line_max_scale,
@@ -462,6 +661,9 @@ impl TextRasterizer {
         }
 
         self.lines.reserve(proto_lines.len());
+        let mut para_idx = 0usize;
+        let mut line_glyphs: Vec<Character> = Vec::new();
+        let empty_bitmap: Arc<Vec<u8>> = Arc::new(Vec::new());
         for (li, proto) in proto_lines.iter().enumerate() {
             let baseline_y = baselines[li];
             let line_index = li;
@@ -471,29 +673,123 @@ impl TextRasterizer {
                 end_index: proto.end_excl,
             });
 
+            while para_idx + 1 < paragraphs.len() && paragraphs[para_idx + 1].0 <= proto.start {
+                para_idx += 1;
+            }
+            let (para_start, para_text, shaped) = &paragraphs[para_idx];
+            let local_start = proto.start - para_start;
+            let local_end = proto.end_excl - para_start;
+            let g0 = shaped.glyphs.partition_point(|g| g.cluster < local_start);
+            let g1 = shaped.glyphs.partition_point(|g| g.cluster < local_end);
+            let glyphs = &shaped.glyphs[g0..g1];
+
+            // Visual order for this line (UAX #9 L1/L2); pure-LTR paragraphs skip the bidi pass.
+            let order: Vec<usize> = if shaped.is_pure_ltr() {
+                (0..glyphs.len()).collect()
+            } else {
+                let levels = shaping::line_levels(para_text, local_start, local_end);
+                shaping::visual_order(glyphs, local_start, &levels)
+            };
+
+            // Pen position per glyph in display order, and each cluster's box (`x0`, `x1`, rtl).
+            let mut pens = vec![0.0f32; glyphs.len()];
+            let mut head_boxes: Vec<Option<(f32, f32, bool)>> = vec![None; local_end - local_start];
             let mut line_x = 0.0f32;
-            for i in proto.start..proto.end_excl {
-                let ch = chars[i];
-                let scale_i = Self::scale_at_char(scales, i);
-                let fs_i = fs * scale_i;
-                let (metrics, bitmap) = self.glyph_cache.get_or_insert(font, ch, fs_i);
-                let advance_step = metrics.advance_width * sx;
-
-                let y = baseline_y - metrics.height as f32 - metrics.ymin as f32;
-                self.characters.push(Character {
-                    ch,
-                    x: line_x,
-                    y,
-                    width: metrics.width as f32,
-                    height: metrics.height as f32,
-                    line_index,
-                    char_index: i,
-                    metrics,
-                    bitmap,
-                });
-
-                line_x += advance_step;
+            for gi in order {
+                let g = &glyphs[gi];
+                let step = g.x_advance * Self::scale_at_char(scales, para_start + g.cluster) * sx;
+                pens[gi] = line_x;
+                let entry =
+                    head_boxes[g.cluster - local_start].get_or_insert((line_x, line_x, g.is_rtl()));
+                entry.0 = entry.0.min(line_x);
+                entry.1 = entry.1.max(line_x + step);
+                line_x += step;
+            }
+
+            // One `Character` per char, in logical order: a cluster's glyphs go to its chars in turn
+            // (base, then marks); chars left over (ligature tails) get an empty glyph. Surplus glyphs
+            // (more glyphs than chars) stay on the cluster's last char.
+            line_glyphs.clear();
+            let mut gi = 0usize;
+            let mut local = local_start;
+            while local < local_end {
+                let (bx0, bx1, rtl) =
+                    head_boxes[local - local_start].unwrap_or((line_x, line_x, false));
+                let mut tail_end = local + 1;
+                while tail_end < local_end && head_boxes[tail_end - local_start].is_none() {
+                    tail_end += 1;
+                }
+                let n = tail_end - local;
+                let slot_w = (bx1 - bx0) / n as f32;
+                let mut k = 0usize;
+                while k < n || glyphs.get(gi).is_some_and(|g| g.cluster == local) {
+                    let slot = k.min(n - 1);
+                    let i = para_start + local + slot;
+                    let (a, b) = if rtl {
+                        (bx1 - slot_w * (slot + 1) as f32, bx1 - slot_w * slot as f32)
+                    } else {
+                        (bx0 + slot_w * slot as f32, bx0 + slot_w * (slot + 1) as f32)
+                    };
+                    let scale_i = Self::scale_at_char(scales, i);
+                    let slot_advance = if sx > 0.0 { (b - a) / sx } else { 0.0 };
+                    match glyphs.get(gi).filter(|g| g.cluster == local) {
+                        Some(g) => {
+                            let (mut metrics, bitmap) = self.glyph_cache.get_or_insert(
+                                Self::font_in_slot(font, fallbacks, g.font),
+                                g.font,
+                                g.glyph_id,
+                                fs * scale_i,
+                            );
+                            metrics.advance_width = slot_advance;
+                            line_glyphs.push(Character {
+                                ch: chars[i],
+                                x: pens[gi] + g.x_offset * scale_i,
+                                y: baseline_y
+                                    - metrics.height as f32
+                                    - metrics.ymin as f32
+                                    - g.y_offset * scale_i,
+                                width: metrics.width as f32,
+                                height: metrics.height as f32,
+                                line_index,
+                                char_index: i,
+                                metrics,
+                                bitmap,
+                                rtl: g.is_rtl(),
+                                glyph_id: g.glyph_id,
+                                font_slot: g.font,
+                                box_x0: a,
+                                box_x1: b,
+                            });
+                            gi += 1;
+                        }
+                        None => {
+                            let font_slot = line_glyphs.last().map(|c| c.font_slot).unwrap_or(0);
+                            line_glyphs.push(Character {
+                                ch: chars[i],
+                                x: a,
+                                y: baseline_y,
+                                width: 0.0,
+                                height: 0.0,
+                                line_index,
+                                char_index: i,
+                                metrics: Metrics {
+                                    advance_width: slot_advance,
+                                    ..Metrics::default()
+                                },
+                                bitmap: empty_bitmap.clone(),
+                                rtl,
+                                glyph_id: 0,
+                                font_slot,
+                                box_x0: a,
+                                box_x1: b,
+                            });
+                        }
+                    }
+                    k += 1;
+                }
+                local = tail_end;
             }
+            self.characters.append(&mut line_glyphs);
         }
 
         // --- Horizontal centering (per wrapped line) ---
@@ -525,6 +821,8 @@ impl TextRasterizer {
                 let li = c.line_index;
                 if li < line_dx.len() {
                     c.x += line_dx[li];
+                    c.box_x0 += line_dx[li];
+                    c.box_x1 += line_dx[li];
                 }
             }
         }
@@ -566,13 +864,20 @@ impl TextRasterizer {
         for li in 0..self.lines.len() {
             let mut min_x = f32::INFINITY;
             let mut any = false;
+            let mut first_rtl: Option<f32> = None;
             for c in &self.characters {
                 if c.line_index == li {
+                    if !any && c.rtl {
+                        first_rtl = Some(c.box_x1);
+                    }
                     any = true;
-                    min_x = min_x.min(c.x);
+                    min_x = min_x.min(c.box_x0);
                 }
             }
-            let cx = if any {
+            // A line that opens with right-to-left text starts its caret on that glyph's right edge.
+            let cx = if let Some(x) = first_rtl {
+                x
+            } else if any {
                 min_x
             } else if align.x > 0.0 {
                 window_width * align.x
@@ -602,6 +907,9 @@ impl TextRasterizer {
         }
         *seen_version = ver;
         self.font = fonts::default_font();
+        self.shaping_bytes = fonts::font_bytes_for(&self.font);
+        self.fallback_fonts = fonts::fallback_fonts_for(&self.font);
+        self.font_registry_seen = fonts::registry_version();
         self.glyph_cache.clear();
         self.last_layout_quick_fp = None;
 
@@ -615,3 +923,81 @@ impl TextRasterizer {
         self.line_gap = metrics.line_gap;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// DotGothic16 at 20px: 10px advances, an `fi` ligature and no Hebrew (notdef, 20px, RTL level).
+    fn laid_out(text: &str) -> TextRasterizer {
+        let mut r = TextRasterizer::new(fonts::dot_gothic_16(), 20.0);
+        r.set_text(text.to_string());
+        r.tick(1000.0, 100.0);
+        r
+    }
+
+    fn carets(r: &TextRasterizer) -> Vec<(usize, f32, f32)> {
+        r.characters
+            .iter()
+            .map(|c| (c.char_index, r.caret_x_before(c), r.caret_x_after(c)))
+            .collect()
+    }
+
+    #[test]
+    fn ligature_tail_gets_half_the_cluster_box() {
+        let r = laid_out("fi");
+        assert_eq!(carets(&r), vec![(0, 0.0, 5.0), (1, 5.0, 10.0)]);
+        assert!(!r.characters[0].bitmap.is_empty());
+        assert!(r.characters[1].bitmap.is_empty());
+    }
+
+    #[test]
+    fn combining_mark_gets_its_own_char_index() {
+        let r = laid_out("q\u{301}x");
+        assert_eq!(
+            carets(&r),
+            vec![(0, 0.0, 5.0), (1, 5.0, 10.0), (2, 10.0, 20.0)]
+        );
+        // The mark keeps its own glyph, drawn over the base.
+        assert!(!r.characters[1].bitmap.is_empty());
+        assert_ne!(r.characters[1].glyph_id, r.characters[0].glyph_id);
+    }
+
+    #[test]
+    fn rtl_run_carets_run_right_to_left() {
+        let r = laid_out("ab שלום");
+        assert_eq!(
+            carets(&r),
+            vec![
+                (0, 0.0, 10.0),
+                (1, 10.0, 20.0),
+                (2, 20.0, 30.0),
+                (3, 110.0, 90.0),
+                (4, 90.0, 70.0),
+                (5, 70.0, 50.0),
+                (6, 50.0, 30.0),
+            ]
+        );
+        assert!(r.characters[3..].iter().all(|c| c.rtl));
+        assert_eq!(r.line_leading_caret_x(0), 0.0);
+    }
+
+    #[test]
+    fn sdf_zoom_adds_no_cache_entries() {
+        let mut r = TextRasterizer::new(fonts::jetbrains_mono(), 16.0);
+        r.set_render_mode(GlyphRenderMode::Sdf);
+        r.set_text("Zoom {quux}".to_string());
+        r.tick(1000.0, 100.0);
+        let atlas = sdf::atlas_for(&r.font);
+        let fields = atlas.lock().unwrap().glyph_count();
+        for size in [18.0, 23.5, 31.0, 64.0] {
+            r.set_font_size(size);
+            r.tick(1000.0, 100.0);
+            assert!(r.glyph_cache.map.is_empty());
+            assert_eq!(atlas.lock().unwrap().glyph_count(), fields);
+            let z = &r.characters[0];
+            assert_eq!(z.bitmap.len(), z.metrics.width * z.metrics.height);
+            assert!(z.bitmap.iter().any(|&a| a > 128));
+        }
+    }
+}
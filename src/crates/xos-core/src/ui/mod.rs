@@ -2,6 +2,7 @@ pub mod audio_selector;
 pub mod button;
 pub mod onscreen_keyboard;
 pub mod selector;
+pub mod styled_text;
 pub mod text;
 pub mod transcribe_lang;
 
@@ -1,10 +1,13 @@
-//! Whole-document Markdown-ish layouts `[caption](props)` per logical newline-separated row,
-//! composing Rust-backed raster passes efficiently across heterogeneous fonts/colours.
+//! Whole-document Markdown-ish layouts `[caption](props)` per logical newline-separated row.
+//! Rows resolve to rich-text runs, so wrapping, alignment and hit-testing come from
+//! [`layout_rich_text`] like any other attributed text.
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use super::text::{UiText, UiTextRenderState};
+use crate::rasterizer::text::rich_text::{
+    layout_rich_text, RichLayoutOptions, RichTextLayout, TextRun, TextStyle,
+};
 
 static STYLED_LINE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^\[(.*)\]\((.*)\)$").expect("styled markdown line regex"));
@@ -208,190 +211,76 @@ pub fn resolve_markdown_document_lines(
     Some(out)
 }
 
-/// Layout reference lines at [`reference_font_size`] to recover stable vertical bands, then paint each resolved row in its band.
-pub fn composite_render_resolved_lines(
-    resolved: &[ResolvedTextLine],
-    x1_norm: f32,
-    y1_norm: f32,
-    x2_norm: f32,
-    y2_norm: f32,
-    reference_font_size: f32,
-    default_glyph_color: (u8, u8, u8, u8),
-    hitboxes: bool,
-    baselines: bool,
-    align_xy: (f32, f32),
-    spacing_xy: (f32, f32),
-    viewport_scroll_y: f32,
-    show_cursor: bool,
-    cursor_position: usize,
-    selection_start: Option<usize>,
-    selection_end: Option<usize>,
-    trackpad_pointer_px: Option<(f32, f32)>,
-    buffer: &mut [u8],
-    frame_width: usize,
-    frame_height: usize,
-    should_paint: bool,
-    scratch: &mut Vec<u8>,
-) -> Result<UiTextRenderState, String> {
-    let mut merged = UiTextRenderState::default();
-    if frame_width == 0 || frame_height == 0 || resolved.is_empty() {
-        return Ok(merged);
-    }
-
-    let ref_join = resolved.iter().map(|r| r.text.as_str()).collect::<Vec<_>>().join("\n");
-    let ref_ui = UiText {
-        text: ref_join,
-        x1_norm,
-        y1_norm,
-        x2_norm,
-        y2_norm,
-        color: default_glyph_color,
-        hitboxes: false,
-        baselines: false,
-        size_px: reference_font_size.max(1.0),
-        show_cursor,
-        cursor_position,
-        selection_start,
-        selection_end,
-        trackpad_pointer_px,
-        viewport_scroll_y,
-        color_spans: Vec::new(),
-        scale_spans: Vec::new(),
-        hitbox_spans: Vec::new(),
-        baseline_spans: Vec::new(),
-        alignment: align_xy,
-        spacing: spacing_xy,
-    };
-
-    let need_px = frame_width.saturating_mul(frame_height).saturating_mul(4);
-    scratch_resize(scratch, need_px)?;
-    scratch[..need_px].fill(0);
-
-    let reference_layout_state =
-        ref_ui.render(scratch.as_mut_slice(), frame_width, frame_height)?;
-
-    let mut mids: Vec<f32> = Vec::new();
-    for b in &reference_layout_state.baselines {
-        mids.push((b[0][1] + b[1][1]) * 0.5);
-    }
-
-    let y_lo_n = y1_norm.clamp(0.0, 1.0);
-    let y_hi_n = y2_norm.clamp(0.0, 1.0);
-    let n = resolved.len();
-    let wrapped_counts = &reference_layout_state.lines;
-    let m = mids.len();
-
-    let eps_norm_y = (16_f64.max(frame_height as f64).sqrt() / frame_height as f64).min(1e-3_f64);
-    let eps = eps_norm_y as f32;
-
-    // Map each logical line to the wrapped reference-line span it occupies.
-    // This prevents overlap when the viewport narrows and early lines wrap.
-    let mut spans: Vec<(usize, usize)> = vec![(0, 0); n];
-    if !wrapped_counts.is_empty() {
-        let mut wi = 0usize;
-        for (li, row) in resolved.iter().enumerate() {
-            let start = wi.min(wrapped_counts.len());
-            if wi >= wrapped_counts.len() {
-                spans[li] = (start, start);
-                continue;
-            }
-            let logical_chars = row.text.chars().count().max(1);
-            let mut consumed = 0usize;
-            while wi < wrapped_counts.len() {
-                consumed = consumed.saturating_add(wrapped_counts[wi] as usize);
-                wi += 1;
-                if consumed >= logical_chars {
-                    break;
-                }
-            }
-            let mut end = wi.min(wrapped_counts.len());
-            if end <= start {
-                end = (start + 1).min(wrapped_counts.len());
-            }
-            spans[li] = (start, end);
-        }
-        if let Some(last) = spans.last_mut() {
-            if wi < wrapped_counts.len() {
-                last.1 = wrapped_counts.len();
-            }
-        }
-    }
-
-    let ax = align_xy.0.clamp(0.0, 1.0);
-
-    for (i, row) in resolved.iter().enumerate() {
-        let (span_start, span_end) = spans.get(i).copied().unwrap_or((0, 0));
-        let top_n = if m == 0 {
-            y_lo_n + (y_hi_n - y_lo_n) * (i as f32 / n.max(1) as f32)
-        } else if span_start == 0 {
-            y_lo_n
-        } else if span_start < m {
-            ((mids[span_start - 1] + mids[span_start]) * 0.5).clamp(y_lo_n, y_hi_n)
-        } else {
-            y_hi_n
-        };
-        let mut bot_n = if m == 0 {
-            y_lo_n + (y_hi_n - y_lo_n) * ((i + 1) as f32 / n.max(1) as f32)
-        } else if span_end >= m {
-            y_hi_n
-        } else if span_end > 0 {
-            ((mids[span_end - 1] + mids[span_end]) * 0.5).clamp(y_lo_n, y_hi_n)
-        } else {
-            y_lo_n
-        };
-        if bot_n <= top_n {
-            bot_n = (top_n + eps).min(y_hi_n);
-            if bot_n <= top_n {
-                continue;
+/// Rich-text runs for resolved rows: one run per row (joined by `\n`) carrying its size and colour.
+pub fn runs_from_resolved_lines(resolved: &[ResolvedTextLine], base: &TextStyle) -> Vec<TextRun> {
+    let last = resolved.len().saturating_sub(1);
+    resolved
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut text = row.text.clone();
+            if i < last {
+                text.push('\n');
             }
-        }
-
-        let chunk = UiText {
-            text: row.text.clone(),
-            x1_norm,
-            y1_norm: top_n,
-            x2_norm,
-            y2_norm: bot_n,
-            color: row.color,
-            hitboxes,
-            baselines,
-            size_px: row.font_size.max(1.0),
-            show_cursor,
-            cursor_position,
-            selection_start,
-            selection_end,
-            trackpad_pointer_px,
-            viewport_scroll_y,
-            color_spans: Vec::new(),
-            scale_spans: Vec::new(),
-            hitbox_spans: Vec::new(),
-            baseline_spans: Vec::new(),
-            alignment: (ax, 0.5),
-            spacing: spacing_xy,
-        };
-
-        let chunk_state = if should_paint {
-            chunk.render(buffer, frame_width, frame_height)?
-        } else {
-            scratch[..need_px].fill(0);
-            chunk.render(scratch.as_mut_slice(), frame_width, frame_height)?
-        };
-
-        merged.lines.extend(chunk_state.lines.iter().copied());
-        merged.hitboxes.extend(chunk_state.hitboxes.iter().copied());
-        merged.baselines.extend(chunk_state.baselines.iter().copied());
-    }
+            TextRun::new(
+                text,
+                TextStyle {
+                    size: row.font_size,
+                    color: row.color,
+                    ..base.clone()
+                },
+            )
+        })
+        .collect()
+}
 
-    Ok(merged)
+/// Lay out a markdown-ish document through [`layout_rich_text`] with `base` as the plain-row style.
+///
+/// Returns `None` when no explicit `[…](…)` lines are present so callers can fall
+/// back to simple `UiText` rendering.
+pub fn layout_markdown_document(
+    raw: &str,
+    base: &TextStyle,
+    options: &RichLayoutOptions,
+) -> Option<RichTextLayout> {
+    let resolved = resolve_markdown_document_lines(raw, base.size, base.color)?;
+    Some(layout_rich_text(
+        &runs_from_resolved_lines(&resolved, base),
+        options,
+    ))
 }
 
-fn scratch_resize(scratch: &mut Vec<u8>, needed: usize) -> Result<(), String> {
-    if scratch.len() < needed {
-        scratch.clear();
-        scratch
-            .try_reserve_exact(needed)
-            .map_err(|e| format!("styled-text scratch allocation failed: {}", e))?;
-        scratch.resize(needed, 0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::text::fonts::FontFamily;
+
+    #[test]
+    fn styled_rows_become_rich_runs() {
+        let plain =
+            layout_markdown_document("plain\ntext", &TextStyle::default(), &Default::default());
+        assert!(plain.is_none());
+
+        let base = TextStyle {
+            family: FontFamily::JetBrainsMono,
+            size: 20.0,
+            ..TextStyle::default()
+        };
+        let raw = "[Title](font_size=40, color=RED)\nbody";
+        let resolved = resolve_markdown_document_lines(raw, base.size, base.color).unwrap();
+        let runs = runs_from_resolved_lines(&resolved, &base);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].text, "Title\n");
+        assert_eq!(runs[0].style.size, 40.0);
+        assert_eq!(runs[0].style.color, (176, 46, 38, 255));
+        assert_eq!(runs[1].text, "body");
+        assert_eq!(runs[1].style.size, 20.0);
+
+        let layout = layout_markdown_document(raw, &base, &RichLayoutOptions::default()).unwrap();
+        assert_eq!(layout.lines.len(), 2);
+        let (title, body) = (layout.lines[0], layout.lines[1]);
+        assert!(title.bottom - title.top > body.bottom - body.top);
+        assert_eq!(title.width, 120.0);
+        assert_eq!(body.width, 48.0);
     }
-    Ok(())
 }
@@ -1,8 +1,8 @@
 use crate::rasterizer::fill_rect_buffer;
 use crate::rasterizer::text::fonts::{self, FontFamily};
 use crate::rasterizer::text::text_rasterization::{
-    character_may_appear_in_viewport, line_band_intersects_doc_viewport, TextLayoutAlign,
-    TextRasterizer,
+    character_may_appear_in_viewport, line_band_intersects_doc_viewport, Character,
+    TextLayoutAlign, TextRasterizer,
 };
 use crate::rasterizer::text::ui_markup;
 use fontdue::Font;
@@ -39,6 +39,14 @@ fn shared_font() -> Result<Font, String> {
     Ok(font)
 }
 
+/// `xos.ui.Text` drawn from Python without a native editor behind it.
+///
+/// Lays out with [`TextRasterizer`], not [`crate::rasterizer::text::rich_text::layout_rich_text`]:
+/// an editable `Text` is blitted from its `TextApp` (xos-app) through
+/// [`collect_ui_text_render_state`], and both paths must report the same lines, hitboxes and caret
+/// positions (and honour `spacing` / vertical `alignment`, which the rich layout lacks). Markup
+/// colours are applied per glyph from [`Self::color_spans`]; `xos.ui.text(rich=...)` and styled
+/// markdown go through the rich layout.
 #[derive(Clone, Debug)]
 pub struct UiText {
     pub text: String,
@@ -83,6 +91,16 @@ fn glyph_bool_with_spans(char_index: usize, base: bool, spans: &[(usize, usize,
         .unwrap_or(base)
 }
 
+/// Caret after `c`. `UiText` has always ended a line at the glyph's unspaced advance, so only
+/// right-to-left glyphs (whose logical end is their left edge) use the rasterizer's caret box.
+fn caret_x_after_unspaced(r: &TextRasterizer, c: &Character) -> f32 {
+    if c.rtl {
+        r.caret_x_after(c)
+    } else {
+        c.x + c.metrics.advance_width
+    }
+}
+
 /// Caret x and baseline y in the same layout space as [`TextRasterizer::characters`] (after `tick`).
 fn cursor_xy_in_layout(r: &TextRasterizer, cursor_position: usize) -> (f32, f32) {
     let line_info_with_idx =
@@ -117,14 +135,11 @@ fn cursor_xy_in_layout(r: &TextRasterizer, cursor_position: usize) -> (f32, f32)
             }
 
             if let Some(char_at_cursor) = found_char {
-                (char_at_cursor.x, line.baseline_y)
+                (r.caret_x_before(char_at_cursor), line.baseline_y)
             } else if let Some(char_after_cursor) = char_after {
-                (char_after_cursor.x, line.baseline_y)
+                (r.caret_x_before(char_after_cursor), line.baseline_y)
             } else if let Some(last_in_line) = chars_in_line.last() {
-                (
-                    last_in_line.x + last_in_line.metrics.advance_width,
-                    line.baseline_y,
-                )
+                (caret_x_after_unspaced(r, last_in_line), line.baseline_y)
             } else {
                 (r.line_leading_caret_x(line_idx), line.baseline_y)
             }
@@ -147,16 +162,13 @@ fn cursor_xy_in_layout(r: &TextRasterizer, cursor_position: usize) -> (f32, f32)
             if chars_in_last_line.is_empty() {
                 (r.line_leading_caret_x(last_line_idx), last_line.baseline_y)
             } else if let Some(last_char) = chars_in_last_line.last() {
-                (
-                    last_char.x + last_char.metrics.advance_width,
-                    last_line.baseline_y,
-                )
+                (caret_x_after_unspaced(r, last_char), last_line.baseline_y)
             } else {
                 (r.line_leading_caret_x(last_line_idx), last_line.baseline_y)
             }
         } else if let Some(last) = r.characters.last() {
             (
-                last.x + last.metrics.advance_width,
+                caret_x_after_unspaced(r, last),
                 r.lines
                     .last()
                     .map(|line| line.baseline_y)
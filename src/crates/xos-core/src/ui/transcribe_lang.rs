@@ -1,7 +1,10 @@
 //! Language picker for the transcribe app: small **lang** control to the right of the capture button,
-//! hold-to-open menu (same timing as the audio input menu), `en` / `ja` rows.
+//! hold-to-open menu (same timing as the audio input menu), an `auto` row then every Whisper
+//! language; the wheel scrolls the rows. The menu title toggles **+ English**: the live decode also
+//! translates each stretch of speech, shown beside the original caption.
 
-use crate::engine::EngineState;
+use crate::ai::transcription::languages::{AUTO_DETECT, WHISPER_LANGUAGES};
+use crate::engine::{EngineState, ScrollWheelUnit};
 use crate::rasterizer::text::text_rasterization::TextRasterizer;
 use fontdue::Font;
 use std::time::{Duration, Instant};
@@ -10,25 +13,50 @@ const HOLD_DURATION: Duration = Duration::from_millis(250);
 const MENU_TOP_PAD: f32 = 20.0;
 const MENU_ITEM_HEIGHT: f32 = 50.0;
 const MENU_COLUMN_WIDTH_RATIO: f32 = 0.45;
+/// Menu rows per mouse-wheel notch.
+const MENU_ROWS_PER_LINE: f32 = 3.0;
 
-/// `(code, label)` — Whisper language codes the engine accepts.
-pub const TRANSCRIBE_LANGUAGES: &[(&str, &str)] = &[("en", "English"), ("ja", "Japanese")];
+/// Menu rows: `auto` (language-ID per decode), then [`WHISPER_LANGUAGES`] in token order.
+pub const TRANSCRIBE_LANGUAGE_COUNT: usize = WHISPER_LANGUAGES.len() + 1;
+
+/// `(code, label)` for menu row `index` (see [`TRANSCRIBE_LANGUAGE_COUNT`]).
+pub fn transcribe_language(index: usize) -> Option<(&'static str, &'static str)> {
+    match index {
+        0 => Some((AUTO_DETECT, "Detect")),
+        i => WHISPER_LANGUAGES.get(i - 1).copied(),
+    }
+}
+
+/// Row selected by default: English.
+const DEFAULT_LANGUAGE_INDEX: usize = 1;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TranscribeLangMenuDown {
     Dismiss,
     DismissInColumn,
-    /// Index into [`TRANSCRIBE_LANGUAGES`].
+    /// Row index for [`transcribe_language`].
     Pick {
         index: usize,
         changed: bool,
     },
+    /// Title row tapped: [`TranscribeLanguageSelector::translate`] flipped to `translate`.
+    ToggleTranslate {
+        translate: bool,
+    },
 }
 
 pub struct TranscribeLanguageSelector {
     pub show_menu: bool,
-    /// Index into [`TRANSCRIBE_LANGUAGES`].
+    /// Row index for [`transcribe_language`].
     pub selected_index: usize,
+    /// Also translate the speech to English (Whisper `task=translate`).
+    pub translate: bool,
+    /// First menu row drawn (the list is longer than the screen).
+    scroll_row: usize,
+    /// Rows that fit under the title at the last draw.
+    visible_rows: usize,
+    /// Fractional rows of trackpad scrolling not applied yet.
+    scroll_accum: f32,
     /// Hit-test: **lang** button (or menu column while open, handled separately).
     pub last_button_rect: (f32, f32, f32, f32),
     mouse_down_time: Option<Instant>,
@@ -45,7 +73,11 @@ impl TranscribeLanguageSelector {
     pub fn new() -> Self {
         Self {
             show_menu: false,
-            selected_index: 0,
+            selected_index: DEFAULT_LANGUAGE_INDEX,
+            translate: false,
+            scroll_row: 0,
+            visible_rows: 1,
+            scroll_accum: 0.0,
             last_button_rect: (0.0, 0.0, 0.0, 0.0),
             mouse_down_time: None,
             tap_candidate: false,
@@ -59,24 +91,55 @@ impl TranscribeLanguageSelector {
         btn_size.max(48.0).min((vm * 0.16).min(120.0))
     }
 
+    /// Whisper code for the selected row, or `"auto"`.
     pub fn current_language_code(&self) -> &'static str {
-        TRANSCRIBE_LANGUAGES
-            .get(self.selected_index)
-            .map(|(c, _)| *c)
+        transcribe_language(self.selected_index)
+            .map(|(c, _)| c)
             .unwrap_or("en")
     }
 
     pub fn set_selected_index(&mut self, i: usize) {
-        if i < TRANSCRIBE_LANGUAGES.len() {
+        if i < TRANSCRIBE_LANGUAGE_COUNT {
             self.selected_index = i;
         }
     }
 
+    /// Wheel over the open menu; same sign as `Application::on_scroll` (positive `delta_y` moves
+    /// toward the top of the list).
+    pub fn on_menu_scroll(&mut self, delta_y: f32, unit: ScrollWheelUnit) {
+        if !delta_y.is_finite() {
+            return;
+        }
+        self.scroll_accum -= match unit {
+            ScrollWheelUnit::Line => delta_y * MENU_ROWS_PER_LINE,
+            ScrollWheelUnit::Pixel => delta_y / (MENU_ITEM_HEIGHT + 5.0),
+        };
+        let whole = self.scroll_accum.trunc();
+        self.scroll_accum -= whole;
+        self.scroll_by(whole as i64);
+    }
+
+    fn scroll_by(&mut self, rows: i64) {
+        let max_first = TRANSCRIBE_LANGUAGE_COUNT.saturating_sub(self.visible_rows.max(1));
+        self.scroll_row = (self.scroll_row as i64 + rows).clamp(0, max_first as i64) as usize;
+    }
+
+    /// Keep the selected row on screen when the menu opens.
+    fn scroll_selection_into_view(&mut self) {
+        let visible = self.visible_rows.max(1);
+        if self.selected_index < self.scroll_row {
+            self.scroll_row = self.selected_index;
+        } else if self.selected_index >= self.scroll_row + visible {
+            self.scroll_row = self.selected_index + 1 - visible;
+        }
+    }
+
     /// Match hold-open behavior to [`AudioInputSelector::tick_hold_opens_menu`].
     pub fn tick_hold_opens_menu(&mut self) {
         if let Some(t) = self.mouse_down_time {
             if !self.show_menu && t.elapsed() >= HOLD_DURATION {
                 self.show_menu = true;
+                self.scroll_selection_into_view();
                 self.mouse_down_time = None;
                 self.tap_candidate = false;
             }
@@ -102,6 +165,7 @@ impl TranscribeLanguageSelector {
     pub fn on_pointer_tap_opens_if_closed(&mut self) {
         if !self.show_menu {
             self.show_menu = true;
+            self.scroll_selection_into_view();
         }
     }
 
@@ -129,7 +193,8 @@ impl TranscribeLanguageSelector {
         false
     }
 
-    /// While the language menu is open: hit-test (down = pick, same as audio input menu).
+    /// While the language menu is open: hit-test (down = pick, same as audio input menu; the title
+    /// row toggles translation).
     pub fn on_menu_pointer_down(
         &mut self,
         mx: f32,
@@ -149,9 +214,15 @@ impl TranscribeLanguageSelector {
         }
         let item_height = MENU_ITEM_HEIGHT as usize;
         let title_bottom = menu_y + item_height;
-        if my < title_bottom {
+        if my < menu_y {
             return TranscribeLangMenuDown::DismissInColumn;
         }
+        if my < title_bottom {
+            self.translate = !self.translate;
+            return TranscribeLangMenuDown::ToggleTranslate {
+                translate: self.translate,
+            };
+        }
         let before = self.selected_index;
         if !self.apply_menu_click(my, menu_y, item_height) {
             return TranscribeLangMenuDown::DismissInColumn;
@@ -166,8 +237,9 @@ impl TranscribeLanguageSelector {
     fn apply_menu_click(&mut self, mouse_y: usize, menu_y: usize, item_height: usize) -> bool {
         let first_row_y = menu_y + item_height + 5;
         if mouse_y >= first_row_y {
-            let idx = (mouse_y - first_row_y) / (item_height + 5);
-            if idx < TRANSCRIBE_LANGUAGES.len() {
+            let row = (mouse_y - first_row_y) / (item_height + 5);
+            let idx = self.scroll_row + row;
+            if row < self.visible_rows && idx < TRANSCRIBE_LANGUAGE_COUNT {
                 self.selected_index = idx;
                 return true;
             }
@@ -229,7 +301,12 @@ impl TranscribeLanguageSelector {
             }
         }
         let fs = ((btn.3 - btn.1) * 0.28).max(10.0).min(20.0);
-        let text = "lang";
+        let code = self.current_language_code();
+        let text = if self.translate {
+            format!("{code}+en")
+        } else {
+            code.to_string()
+        };
         let tw = (btn.2 - btn.0) * 0.5 - text.len() as f32 * fs * 0.22;
         let tx = btn.0 + tw.max(4.0);
         let ty = btn.1 + (btn.3 - btn.1) * 0.22;
@@ -238,7 +315,7 @@ impl TranscribeLanguageSelector {
             width,
             height,
             font,
-            text,
+            &text,
             tx as usize,
             ty as usize,
             fs,
@@ -284,10 +361,34 @@ impl TranscribeLanguageSelector {
             20.0,
             (255, 255, 255),
         );
+        let (toggle, toggle_col) = if self.translate {
+            ("+ English: on", (0, 200, 90))
+        } else {
+            ("+ English: off", (150, 154, 164))
+        };
+        self.draw_text_pixels(
+            buffer,
+            width,
+            height,
+            font,
+            toggle,
+            (left_x + 110).max((left_x + column_width).saturating_sub(140)),
+            menu_y + 17,
+            16.0,
+            toggle_col,
+        );
 
-        for (i, (code, label)) in TRANSCRIBE_LANGUAGES.iter().enumerate() {
-            let item_y = menu_y + item_h + 5 + i * (item_h + 5);
-            if item_y + item_h > safe_bottom_px.min(height) {
+        let first_row_y = menu_y + item_h + 5;
+        let rows_bottom = safe_bottom_px.min(height);
+        self.visible_rows = (rows_bottom.saturating_sub(first_row_y) / (item_h + 5)).max(1);
+        self.scroll_by(0);
+        for row in 0..self.visible_rows {
+            let i = self.scroll_row + row;
+            let Some((code, label)) = transcribe_language(i) else {
+                break;
+            };
+            let item_y = first_row_y + row * (item_h + 5);
+            if item_y + item_h > rows_bottom {
                 break;
             }
             let sel = i == self.selected_index;
@@ -398,3 +499,48 @@ impl TranscribeLanguageSelector {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 800×600 layout: a 360 px menu column from x = 220, title row y 20..70, rows from y 75.
+    const LAYOUT: (f32, f32, f32, f32) = (0.0, 0.0, 800.0, 600.0);
+
+    #[test]
+    fn title_row_toggles_translation_and_rows_pick_languages() {
+        let mut sel = TranscribeLanguageSelector::new();
+        assert!(!sel.translate);
+        assert_eq!(
+            sel.on_menu_pointer_down(400.0, 40.0, LAYOUT),
+            TranscribeLangMenuDown::ToggleTranslate { translate: true }
+        );
+        assert!(sel.translate);
+        assert_eq!(
+            sel.on_menu_pointer_down(400.0, 40.0, LAYOUT),
+            TranscribeLangMenuDown::ToggleTranslate { translate: false }
+        );
+        assert!(!sel.translate);
+
+        sel.translate = true;
+        assert_eq!(
+            sel.on_menu_pointer_down(400.0, 80.0, LAYOUT),
+            TranscribeLangMenuDown::Pick {
+                index: 0,
+                changed: true
+            }
+        );
+        assert_eq!(sel.current_language_code(), AUTO_DETECT);
+        assert!(sel.translate, "picking a language keeps the toggle");
+
+        assert_eq!(
+            sel.on_menu_pointer_down(100.0, 40.0, LAYOUT),
+            TranscribeLangMenuDown::Dismiss
+        );
+        assert_eq!(
+            sel.on_menu_pointer_down(400.0, 10.0, LAYOUT),
+            TranscribeLangMenuDown::DismissInColumn
+        );
+        assert!(sel.translate);
+    }
+}
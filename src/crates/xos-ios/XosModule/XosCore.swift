@@ -28,6 +28,19 @@ func xos_engine_mouse_down() -> Int32
 @_silgen_name("xos_engine_mouse_up")
 func xos_engine_mouse_up() -> Int32
 
+/// `kind`: 0 touch, 1 pencil. `phase`: 0 began, 1 moved, 2 ended, 3 cancelled.
+@_silgen_name("xos_engine_pointer_event")
+func xos_engine_pointer_event(_ id: UInt64, _ kind: UInt8, _ phase: UInt8, _ x: Float, _ y: Float, _ pressure: Float) -> Int32
+
+@_silgen_name("xos_engine_key_down")
+func xos_engine_key_down(_ key: UnsafePointer<CChar>?, _ code: UnsafePointer<CChar>?, _ isRepeat: Bool) -> Int32
+
+@_silgen_name("xos_engine_key_up")
+func xos_engine_key_up(_ key: UnsafePointer<CChar>?, _ code: UnsafePointer<CChar>?) -> Int32
+
+@_silgen_name("xos_engine_release_all_keys")
+func xos_engine_release_all_keys() -> Int32
+
 @_silgen_name("xos_engine_resize")
 func xos_engine_resize(_ width: UInt32, _ height: UInt32) -> Int32
 
@@ -134,6 +147,30 @@ public func xosEngineMouseUp() -> Bool {
     return xos_engine_mouse_up() == 0
 }
 
+/// One touch / pencil transition for the multi-pointer model (frame px; `pressure` 0...1).
+@discardableResult
+public func xosEnginePointerEvent(id: UInt64, isPencil: Bool, phase: UInt8, x: Float, y: Float, pressure: Float) -> Bool {
+    return xos_engine_pointer_event(id, isPencil ? 1 : 0, phase, x, y, pressure) == 0
+}
+
+/// Hardware key press; `key` is the logical name (`"w"`, `"ArrowLeft"`), `code` the physical `KeyboardEvent.code` (`"KeyW"`).
+@discardableResult
+public func xosEngineKeyDown(key: String, code: String, isRepeat: Bool = false) -> Bool {
+    return key.withCString { k in code.withCString { c in xos_engine_key_down(k, c, isRepeat) } } == 0
+}
+
+/// Hardware key release.
+@discardableResult
+public func xosEngineKeyUp(key: String, code: String) -> Bool {
+    return key.withCString { k in code.withCString { c in xos_engine_key_up(k, c) } } == 0
+}
+
+/// Releases every held key (focus lost / app backgrounded) so apps never see a stuck key.
+@discardableResult
+public func xosEngineReleaseAllKeys() -> Bool {
+    return xos_engine_release_all_keys() == 0
+}
+
 /// Swift wrapper for resizing the frame
 @discardableResult
 public func xosEngineResize(width: UInt32, height: UInt32) -> Bool {
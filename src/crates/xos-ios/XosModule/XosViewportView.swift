@@ -106,6 +106,8 @@ public class XosViewportView: UIView {
     private var isEngineInitialized = false
     private var pendingAppName: String?
     private var crashOverlay: UIView?
+    /// Touch mirrored into the legacy single cursor (`xos_engine_mouse_*`); other fingers are pointer-only.
+    private weak var primaryTouch: UITouch?
     
     public required init?(coder: NSCoder) {
         let errorMsg = "ERROR: init(coder:) has not been implemented for XosViewportView. Use init(frame:) instead."
@@ -120,6 +122,7 @@ public class XosViewportView: UIView {
         super.init(frame: frame)
         clipsToBounds = true
         backgroundColor = UIColor.black
+        isMultipleTouchEnabled = true
         
         // Create Metal layer for GPU rendering
         guard let device = metalRenderer.getDevice() else {
@@ -446,9 +449,30 @@ public class XosViewportView: UIView {
         }
     }
     
+    /// Forwards every touch in `touches` to the multi-pointer model (ids are stable per `UITouch`).
+    private func sendPointerEvents(_ touches: Set<UITouch>, phase: UInt8) {
+        let scale = UIScreen.main.scale
+        for touch in touches {
+            let location = touch.location(in: self)
+            let maxForce = touch.maximumPossibleForce
+            let pressure = maxForce > 0 ? Float(touch.force / maxForce) : 0
+            let id = UInt64(UInt(bitPattern: ObjectIdentifier(touch).hashValue))
+            xosEnginePointerEvent(
+                id: id,
+                isPencil: touch.type == .pencil,
+                phase: phase,
+                x: Float(location.x * scale),
+                y: Float(location.y * scale),
+                pressure: pressure
+            )
+        }
+    }
+
     public override func touchesBegan(_ touches: Set<UITouch>, with event: UIEvent?) {
         super.touchesBegan(touches, with: event)
-        if let touch = touches.first {
+        sendPointerEvents(touches, phase: 0)
+        if primaryTouch == nil, let touch = touches.first {
+            primaryTouch = touch
             let location = touch.location(in: self)
             let scale = UIScreen.main.scale
             if !xosEngineUpdateMouse(x: Float(location.x * scale), y: Float(location.y * scale)) {
@@ -462,7 +486,8 @@ public class XosViewportView: UIView {
     
     public override func touchesMoved(_ touches: Set<UITouch>, with event: UIEvent?) {
         super.touchesMoved(touches, with: event)
-        if let touch = touches.first {
+        sendPointerEvents(touches, phase: 1)
+        if let touch = primaryTouch, touches.contains(touch) {
             let location = touch.location(in: self)
             let scale = UIScreen.main.scale
             if !xosEngineUpdateMouse(x: Float(location.x * scale), y: Float(location.y * scale)) {
@@ -473,18 +498,116 @@ public class XosViewportView: UIView {
     
     public override func touchesEnded(_ touches: Set<UITouch>, with event: UIEvent?) {
         super.touchesEnded(touches, with: event)
-        if !xosEngineMouseUp() {
-            ConsoleManager.shared.addLog("WARNING: Failed to handle mouse up")
+        sendPointerEvents(touches, phase: 2)
+        if let touch = primaryTouch, touches.contains(touch) {
+            primaryTouch = nil
+            if !xosEngineMouseUp() {
+                ConsoleManager.shared.addLog("WARNING: Failed to handle mouse up")
+            }
         }
     }
     
     public override func touchesCancelled(_ touches: Set<UITouch>, with event: UIEvent?) {
         super.touchesCancelled(touches, with: event)
-        if !xosEngineMouseUp() {
-            ConsoleManager.shared.addLog("WARNING: Failed to handle mouse up")
+        sendPointerEvents(touches, phase: 3)
+        if let touch = primaryTouch, touches.contains(touch) {
+            primaryTouch = nil
+            if !xosEngineMouseUp() {
+                ConsoleManager.shared.addLog("WARNING: Failed to handle mouse up")
+            }
         }
     }
     
+    // MARK: - Hardware keyboard (raw key down / up → `Application::on_key_down` / `on_key_up`)
+
+    public override var canBecomeFirstResponder: Bool { true }
+
+    public override func didMoveToWindow() {
+        super.didMoveToWindow()
+        if window != nil {
+            becomeFirstResponder()
+        }
+    }
+
+    @discardableResult
+    public override func resignFirstResponder() -> Bool {
+        xosEngineReleaseAllKeys()
+        return super.resignFirstResponder()
+    }
+
+    public override func pressesBegan(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        var handled = false
+        for press in presses {
+            guard let key = press.key else { continue }
+            let (name, code) = Self.keyNames(key)
+            handled = xosEngineKeyDown(key: name, code: code) || handled
+        }
+        if !handled {
+            super.pressesBegan(presses, with: event)
+        }
+    }
+
+    public override func pressesEnded(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        releasePresses(presses)
+        super.pressesEnded(presses, with: event)
+    }
+
+    public override func pressesCancelled(_ presses: Set<UIPress>, with event: UIPressesEvent?) {
+        releasePresses(presses)
+        super.pressesCancelled(presses, with: event)
+    }
+
+    private func releasePresses(_ presses: Set<UIPress>) {
+        for press in presses {
+            guard let key = press.key else { continue }
+            let (name, code) = Self.keyNames(key)
+            xosEngineKeyUp(key: name, code: code)
+        }
+    }
+
+    /// Logical name + `KeyboardEvent.code`-style physical name for a `UIKey` (US HID usage table).
+    private static func keyNames(_ key: UIKey) -> (String, String) {
+        let usage = key.keyCode.rawValue
+        var code = ""
+        switch usage {
+        case 0x04...0x1D:
+            code = "Key" + String(UnicodeScalar(UInt8(65 + usage - 0x04)))
+        case 0x1E...0x26:
+            code = "Digit\(usage - 0x1D)"
+        case 0x27: code = "Digit0"
+        case 0x28: code = "Enter"
+        case 0x29: code = "Escape"
+        case 0x2A: code = "Backspace"
+        case 0x2B: code = "Tab"
+        case 0x2C: code = "Space"
+        case 0x4F: code = "ArrowRight"
+        case 0x50: code = "ArrowLeft"
+        case 0x51: code = "ArrowDown"
+        case 0x52: code = "ArrowUp"
+        case 0xE0: code = "ControlLeft"
+        case 0xE1: code = "ShiftLeft"
+        case 0xE2: code = "AltLeft"
+        case 0xE3: code = "MetaLeft"
+        case 0xE4: code = "ControlRight"
+        case 0xE5: code = "ShiftRight"
+        case 0xE6: code = "AltRight"
+        case 0xE7: code = "MetaRight"
+        default: break
+        }
+        let chars = key.charactersIgnoringModifiers
+        let name: String
+        if chars.count == 1, let scalar = chars.unicodeScalars.first, !CharacterSet.controlCharacters.contains(scalar) {
+            name = chars
+        } else if code.hasSuffix("Left") && !code.hasPrefix("Arrow") {
+            name = String(code.dropLast(4))
+        } else if code.hasSuffix("Right") && !code.hasPrefix("Arrow") {
+            name = String(code.dropLast(5))
+        } else {
+            name = code
+        }
+        return (name, code)
+    }
+
     deinit {
         NotificationCenter.default.removeObserver(self)
         stopAnimation()
@@ -12,9 +12,9 @@ use jni::JNIEnv;
 use std::cell::RefCell;
 use xos::apps::coder::CoderApp;
 use xos::engine::{
-    apply_frame_view_zoom,
-    f3_menu_handle_mouse_down, f3_menu_handle_mouse_move, f3_menu_handle_mouse_up, tick_f3_menu,
-    tick_frame_delta, tick_frame_view_zoom, Application, CursorStyleSetter, EngineState, F3Menu,
+    advance_app_frame, apply_frame_view_zoom,
+    f3_menu_handle_mouse_down, f3_menu_handle_mouse_move, f3_menu_handle_mouse_up, step_app_tick,
+    tick_f3_menu, tick_frame_view_zoom, Application, CursorStyleSetter, EngineState, F3Menu,
     FrameState, KeyboardState, MouseState, SafeRegionBoundingRectangle,
 };
 
@@ -122,15 +122,21 @@ pub extern "system" fn Java_ai_xlate_xos_XosNative_init(
                 is_right_clicking: false,
                 style: CursorStyleSetter::new(),
             },
+            pointers: xos::engine::pointer::PointerState::default(),
             keyboard: KeyboardState {
                 onscreen: xos::ui::onscreen_keyboard::OnScreenKeyboard::new(),
                 modifiers: xos::engine::KeyboardModifiers::default(),
+                keys_down: xos::engine::keyboard::keys::KeysDown::default(),
             },
+            gamepads: xos::engine::gamepad::Gamepads::default(),
             f3_menu: F3Menu::new(),
             ui_scale_percent: 100,
             delta_time_seconds: 1.0 / 60.0,
             paused: false,
             pending_step_ticks: 0,
+            timestep: xos::engine::timestep::FixedTimestep::default(),
+            capture: xos::engine::capture::FrameCapture::default(),
+            window: xos::engine::window_control::WindowControl::default(),
             frame_view_zoom: 1.0,
             frame_view_zoom_target: 1.0,
             frame_view_zoom_velocity: 0.0,
@@ -187,15 +193,16 @@ pub extern "system" fn Java_ai_xlate_xos_XosNative_tick(mut env: JNIEnv, _class:
         if host.engine.paused {
             if host.engine.pending_step_ticks > 0 {
                 host.engine.pending_step_ticks = host.engine.pending_step_ticks.saturating_sub(1);
-                tick_frame_delta(&mut host.engine, &mut host.last_tick_instant);
-                host.app.tick(&mut host.engine);
+                step_app_tick(host.app.as_mut(), &mut host.engine, &mut host.last_tick_instant);
+                host.app.render(&mut host.engine);
             } else {
                 host.last_tick_instant = Some(std::time::Instant::now());
             }
         } else {
-            tick_frame_delta(&mut host.engine, &mut host.last_tick_instant);
-            host.app.tick(&mut host.engine);
+            advance_app_frame(host.app.as_mut(), &mut host.engine, &mut host.last_tick_instant);
+            host.app.render(&mut host.engine);
         }
+        host.engine.capture.on_frame(&mut host.engine.frame);
 
         tick_frame_view_zoom(&mut host.engine);
         apply_frame_view_zoom(&mut host.engine);
@@ -13,7 +13,7 @@ use crate::tensor_buf::tensor_flat_data_list;
 
 /// Mono `f32` samples — same contract as in-tree `whisper_burn::transcribe`: one contiguous buffer,
 /// values typically in ~`[-1, 1]`, `sample_rate` Hz (Whisper expects 16 kHz).
-fn waveform_vec_from_py(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<f32>> {
+pub(crate) fn waveform_vec_from_py(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<f32>> {
     match tensor_flat_data_list(obj, vm) {
         Ok(v) if !v.is_empty() => Ok(v),
         Ok(_) => Err(vm.new_value_error(
@@ -69,6 +69,26 @@ fn parse_string_arg(
     }
 }
 
+/// `None` / `"transcribe"` or `"translate"` (speech → English).
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_task_arg(
+    obj: Option<&PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<xos_core::ai::transcription::WhisperTask> {
+    use xos_core::ai::transcription::WhisperTask;
+    match obj {
+        Some(v) if !vm.is_none(v) => {
+            let s: String = v.clone().try_into_value(vm)?;
+            WhisperTask::from_str(&s).ok_or_else(|| {
+                vm.new_value_error(format!(
+                    "unknown whisper task '{s}' (use 'transcribe' or 'translate')"
+                ))
+            })
+        }
+        _ => Ok(WhisperTask::Transcribe),
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn summarize(values: &[f32]) -> (usize, usize, usize, f32, f32, f32) {
     let mut finite = 0usize;
@@ -335,7 +355,7 @@ fn whisper_forward_native(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         Some(v) => waveform_vec_from_py(v, vm)?,
         None => {
             return Err(vm.new_type_error(
-                "_forward_native(model, waveform, sample_rate=16000) requires waveform".to_string(),
+                "_forward_native(model, waveform, sample_rate=16000, backend, language=None, task=None) requires waveform".to_string(),
             ));
         }
     };
@@ -356,14 +376,260 @@ fn whisper_forward_native(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
             ))
         })?;
 
-    let text = xos_core::ai::transcription::transcribe_waveform_once(
+    let language: Option<String> = match av.get(4) {
+        Some(v) if !vm.is_none(v) => Some(v.clone().try_into_value(vm)?),
+        _ => None,
+    };
+
+    let options = xos_core::ai::transcription::TranscribeOptions {
+        language: language.as_deref(),
+        task: parse_task_arg(av.get(5), vm)?,
+        ..Default::default()
+    };
+    let out = xos_core::ai::transcription::transcribe_waveform_once_detailed(
+        Some(&model),
+        &waveform,
+        sample_rate as u32,
+        backend,
+        &options,
+    )
+    .map_err(|e| to_py_err(vm, e))?;
+    let (code, probability) = match out.detected_language {
+        Some(det) => (
+            vm.ctx.new_str(det.code).into(),
+            vm.ctx.new_float(det.probability as f64).into(),
+        ),
+        None => (vm.ctx.none(), vm.ctx.none()),
+    };
+    Ok(vm
+        .ctx
+        .new_tuple(vec![vm.ctx.new_str(out.text).into(), code, probability])
+        .into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn whisper_transcribe_native(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Err(to_py_err(
+        vm,
+        "Whisper native inference is unavailable in the wasm build (use native xos for transcription)",
+    ))
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "whisper"))]
+thread_local! {
+    /// Speaker-embedding model for `transcribe(..., diarize=True)`, loaded on first use.
+    static DIARIZER: std::cell::RefCell<Option<xos_core::ai::transcription::diarization::Diarizer>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Label `segments` by speaker, enrolling `speakers` (name, clip at `sample_rate`) first. Voices
+/// from earlier calls are forgotten so labels restart at `Speaker 1`.
+#[cfg(all(not(target_arch = "wasm32"), feature = "whisper"))]
+fn diarize_segments(
+    waveform: &[f32],
+    sample_rate: u32,
+    speakers: &[(String, Vec<f32>)],
+    segments: &mut [xos_core::ai::transcription::timestamps::TranscriptSegment],
+) -> Result<(), String> {
+    use xos_core::ai::transcription::diarization::Diarizer;
+
+    DIARIZER.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        let diarizer = match slot.as_mut() {
+            Some(diarizer) => diarizer,
+            None => slot.insert(Diarizer::new()?),
+        };
+        diarizer.clear_speakers();
+        for (name, clip) in speakers {
+            diarizer.enroll(name, clip, sample_rate)?;
+        }
+        diarizer.label_segments(waveform, sample_rate, segments)
+    })
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "whisper")))]
+fn diarize_segments(
+    _waveform: &[f32],
+    _sample_rate: u32,
+    _speakers: &[(String, Vec<f32>)],
+    _segments: &mut [xos_core::ai::transcription::timestamps::TranscriptSegment],
+) -> Result<(), String> {
+    Err("Speaker diarization is unavailable in this build (enable diarization)".to_string())
+}
+
+/// `_transcribe_native(model, waveform, sample_rate, backend, language, word_timestamps, task,
+/// diarize, speakers, tokens)` - result dict with `text`, `language`, `language_probability` and
+/// timed `segments` (each with a `speaker` when `diarize`; `speakers` is `[(name, waveform), ...]`;
+/// `tokens` fills each segment's `tokens` without aligning them).
+#[cfg(not(target_arch = "wasm32"))]
+fn whisper_transcribe_native(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    use xos_core::ai::transcription::{
+        transcribe_waveform_once_detailed, TranscribeOptions, WhisperBackend,
+    };
+
+    let av = args.args;
+    let model = parse_string_arg(av.first(), "tiny", vm)?;
+    let waveform: Vec<f32> = match av.get(1) {
+        Some(v) => waveform_vec_from_py(v, vm)?,
+        None => {
+            return Err(vm.new_type_error(
+                "_transcribe_native(model, waveform, sample_rate, backend, language, word_timestamps, task) requires waveform".to_string(),
+            ));
+        }
+    };
+    let sample_rate: i64 = match av.get(2) {
+        Some(v) => v.clone().try_into_value(vm)?,
+        None => 16_000,
+    };
+    if sample_rate <= 0 {
+        return Err(vm.new_value_error("sample_rate must be > 0".to_string()));
+    }
+    let backend_s = parse_string_arg(av.get(3), "ct2", vm)?;
+    let backend = WhisperBackend::from_str(&backend_s).ok_or_else(|| {
+        vm.new_value_error(format!(
+            "unknown whisper backend '{backend_s}' (use 'burn' or 'ct2')"
+        ))
+    })?;
+    let language: Option<String> = match av.get(4) {
+        Some(v) if !vm.is_none(v) => Some(v.clone().try_into_value(vm)?),
+        _ => None,
+    };
+    let word_timestamps = parse_bool_flag(av.get(5), vm)?;
+
+    let options = TranscribeOptions {
+        language: language.as_deref(),
+        task: parse_task_arg(av.get(6), vm)?,
+        timestamps: true,
+        word_timestamps,
+        tokens: parse_bool_flag(av.get(9), vm)?,
+    };
+    let diarize = parse_bool_flag(av.get(7), vm)?;
+    let mut speakers = Vec::new();
+    if let Some(v) = av.get(8).filter(|v| !vm.is_none(v)) {
+        let pairs: Vec<PyObjectRef> = v.clone().try_into_value(vm)?;
+        for pair in pairs {
+            let pair: Vec<PyObjectRef> = pair.try_into_value(vm)?;
+            let [name, clip] = <[PyObjectRef; 2]>::try_from(pair).map_err(|_| {
+                vm.new_value_error("speakers must map names to waveforms".to_string())
+            })?;
+            let name: String = name.try_into_value(vm)?;
+            speakers.push((name, waveform_vec_from_py(&clip, vm)?));
+        }
+    }
+    let mut out = transcribe_waveform_once_detailed(
         Some(&model),
         &waveform,
         sample_rate as u32,
         backend,
+        &options,
     )
     .map_err(|e| to_py_err(vm, e))?;
-    Ok(vm.ctx.new_str(text).into())
+    if diarize || !speakers.is_empty() {
+        diarize_segments(&waveform, sample_rate as u32, &speakers, &mut out.segments)
+            .map_err(|e| to_py_err(vm, e))?;
+    }
+
+    let result = vm.ctx.new_dict();
+    result.set_item("text", vm.ctx.new_str(out.text).into(), vm)?;
+    let (code, probability) = match out.detected_language {
+        Some(det) => (
+            vm.ctx.new_str(det.code).into(),
+            vm.ctx.new_float(det.probability as f64).into(),
+        ),
+        None => (vm.ctx.none(), vm.ctx.none()),
+    };
+    result.set_item("language", code, vm)?;
+    result.set_item("language_probability", probability, vm)?;
+    let mut segments = Vec::with_capacity(out.segments.len());
+    for segment in out.segments {
+        let d = vm.ctx.new_dict();
+        d.set_item("start", vm.ctx.new_float(segment.start).into(), vm)?;
+        d.set_item("end", vm.ctx.new_float(segment.end).into(), vm)?;
+        d.set_item("text", vm.ctx.new_str(segment.text).into(), vm)?;
+        let speaker = match segment.speaker {
+            Some(name) => vm.ctx.new_str(name).into(),
+            None => vm.ctx.none(),
+        };
+        d.set_item("speaker", speaker, vm)?;
+        d.set_item(
+            "no_speech_prob",
+            vm.ctx.new_float(segment.no_speech_prob as f64).into(),
+            vm,
+        )?;
+        let mut tokens = Vec::with_capacity(segment.tokens.len());
+        for token in segment.tokens {
+            let t = vm.ctx.new_dict();
+            t.set_item("id", vm.ctx.new_int(token.id).into(), vm)?;
+            t.set_item("text", vm.ctx.new_str(token.text).into(), vm)?;
+            t.set_item("start", vm.ctx.new_float(token.start).into(), vm)?;
+            t.set_item("end", vm.ctx.new_float(token.end).into(), vm)?;
+            t.set_item(
+                "probability",
+                vm.ctx.new_float(token.probability as f64).into(),
+                vm,
+            )?;
+            tokens.push(t.into());
+        }
+        d.set_item("tokens", vm.ctx.new_list(tokens).into(), vm)?;
+        let mut words = Vec::with_capacity(segment.words.len());
+        for word in segment.words {
+            let w = vm.ctx.new_dict();
+            w.set_item("text", vm.ctx.new_str(word.text).into(), vm)?;
+            w.set_item("start", vm.ctx.new_float(word.start).into(), vm)?;
+            w.set_item("end", vm.ctx.new_float(word.end).into(), vm)?;
+            w.set_item(
+                "probability",
+                vm.ctx.new_float(word.probability as f64).into(),
+                vm,
+            )?;
+            words.push(w.into());
+        }
+        d.set_item("words", vm.ctx.new_list(words).into(), vm)?;
+        segments.push(d.into());
+    }
+    result.set_item("segments", vm.ctx.new_list(segments).into(), vm)?;
+    Ok(result.into())
+}
+
+/// `_captions_native([(start, end, text[, speaker]), ...], "srt" | "vtt")` - caption file text.
+fn whisper_captions_native(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    use xos_core::ai::transcription::timestamps::{to_srt, to_vtt, TranscriptSegment};
+
+    let (Some(items), Some(format)) = (args.args.first(), args.args.get(1)) else {
+        return Err(vm.new_type_error(
+            "_captions_native(cues, format) requires cues and format".to_string(),
+        ));
+    };
+    let items: Vec<PyObjectRef> = items.clone().try_into_value(vm)?;
+    let format: String = format.clone().try_into_value(vm)?;
+    let mut segments = Vec::with_capacity(items.len());
+    for item in items {
+        let mut cue: Vec<PyObjectRef> = item.try_into_value(vm)?;
+        let speaker = match cue.len() {
+            4 => cue.pop().filter(|v| !vm.is_none(v)),
+            _ => None,
+        };
+        let [start, end, text] = <[PyObjectRef; 3]>::try_from(cue).map_err(|_| {
+            vm.new_value_error("caption cues must be (start, end, text[, speaker])".to_string())
+        })?;
+        segments.push(TranscriptSegment {
+            start: start.try_into_value(vm)?,
+            end: end.try_into_value(vm)?,
+            text: text.try_into_value(vm)?,
+            speaker: speaker.map(|v| v.try_into_value(vm)).transpose()?,
+            ..Default::default()
+        });
+    }
+    let out = match format.as_str() {
+        "srt" => to_srt(&segments),
+        "vtt" => to_vtt(&segments),
+        other => {
+            return Err(vm.new_value_error(format!(
+                "unknown caption format '{other}' (use 'srt' or 'vtt')"
+            )))
+        }
+    };
+    Ok(vm.ctx.new_str(out).into())
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -498,6 +764,20 @@ pub fn make_ai_module(vm: &VirtualMachine) -> PyRef<PyModule> {
             vm,
         )
         .ok();
+    whisper
+        .set_attr(
+            "_transcribe_native",
+            vm.new_function("_transcribe_native", whisper_transcribe_native),
+            vm,
+        )
+        .ok();
+    whisper
+        .set_attr(
+            "_captions_native",
+            vm.new_function("_captions_native", whisper_captions_native),
+            vm,
+        )
+        .ok();
     whisper
         .set_attr(
             "_forward_layer_by_layer_native",
@@ -519,6 +799,11 @@ pub fn make_ai_module(vm: &VirtualMachine) -> PyRef<PyModule> {
             .set_item("_forward_native", forward_native, vm)
             .ok();
     }
+    for name in ["_transcribe_native", "_captions_native"] {
+        if let Ok(native) = whisper.get_attr(name, vm) {
+            scope.globals.set_item(name, native, vm).ok();
+        }
+    }
     if let Ok(fwd_lbl) = whisper.get_attr("_forward_layer_by_layer_native", vm) {
         scope
             .globals
@@ -529,6 +814,8 @@ pub fn make_ai_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let glue = r#"
 BURN = "burn"
 CT2 = "ct2"
+TRANSCRIBE = "transcribe"
+TRANSLATE = "translate"
 
 def _mk_parameter(payload):
     xos = __import__("xos")
@@ -569,6 +856,57 @@ def _waveform_to_list(x):
             return [float(v) for v in d]
     return [float(x)]
 
+def _forward_text(model, wave, sample_rate, backend, language, task):
+    # Returns (text, detected) where detected is (code, probability) for language="auto", else None.
+    text, code, prob = _forward_native(model, [float(v) for v in wave], int(sample_rate), backend, language, task)
+    return text, (None if code is None else (code, prob))
+
+def _forward_batch(owner, x, sample_rate, backend, language, task):
+    wave = _flatten_batch_dim1(_waveform_to_list(x))
+    if wave and isinstance(wave[0], (list, tuple)):
+        outs = [_forward_text(owner._model, row, sample_rate, backend, language, task) for row in wave]
+        owner.detected_language = [d for _, d in outs]
+        return [t for t, _ in outs]
+    text, owner.detected_language = _forward_text(owner._model, wave, sample_rate, backend, language, task)
+    return text
+
+def _transcribe_batch(owner, x, sample_rate, backend, language, word_timestamps, task, diarize, speakers, tokens):
+    wave = _flatten_batch_dim1(_waveform_to_list(x))
+    rows = wave if wave and isinstance(wave[0], (list, tuple)) else None
+    clips = None
+    if speakers:
+        clips = [(str(name), [float(v) for v in _flatten_batch_dim1(_waveform_to_list(clip))]) for name, clip in speakers.items()]
+    outs = [
+        _transcribe_native(owner._model, [float(v) for v in row], int(sample_rate), backend, language, bool(word_timestamps), task, bool(diarize), clips, bool(tokens))
+        for row in (rows if rows is not None else [wave])
+    ]
+    detected = [None if r["language"] is None else (r["language"], r["language_probability"]) for r in outs]
+    if rows is None:
+        owner.detected_language = detected[0]
+        return outs[0]
+    owner.detected_language = detected
+    return outs
+
+def _caption_cues(result):
+    # A transcribe() result, a list of them (one per batch row) or a list of segment dicts.
+    if isinstance(result, dict):
+        result = result.get("segments", [])
+    cues = []
+    for seg in result:
+        if isinstance(seg, dict) and "segments" in seg:
+            cues.extend(_caption_cues(seg))
+        else:
+            cues.append((float(seg["start"]), float(seg["end"]), str(seg["text"]), seg.get("speaker")))
+    return cues
+
+def to_srt(result):
+    """SubRip (.srt) captions for a ``transcribe()`` result or its ``segments`` list."""
+    return _captions_native(_caption_cues(result), "srt")
+
+def to_vtt(result):
+    """WebVTT (.vtt) captions for a ``transcribe()`` result or its ``segments`` list."""
+    return _captions_native(_caption_cues(result), "vtt")
+
 def _flatten_batch_dim1(wave):
     # Shape (1, N) from xos often appears as one row: match whisper_burn's flat &[f32].
     if (
@@ -584,6 +922,7 @@ class _WhisperCt2Model:
     """CTranslate2 backend: no Burnpack weights in Python; inference is native CT2."""
     def __init__(self, model):
         self._model = model
+        self.detected_language = None
     def named_parameters(self):
         return iter(())
     def parameters(self):
@@ -596,11 +935,19 @@ class _WhisperCt2Model:
     @property
     def weights_file(self):
         return None
-    def forward(self, x, sample_rate=16000):
-        wave = _flatten_batch_dim1(_waveform_to_list(x))
-        if wave and isinstance(wave[0], (list, tuple)):
-            return [_forward_native(self._model, [float(v) for v in row], int(sample_rate), CT2) for row in wave]
-        return _forward_native(self._model, [float(v) for v in wave], int(sample_rate), CT2)
+    def forward(self, x, sample_rate=16000, language=None, task=TRANSCRIBE):
+        # language: code ("ja"), English name ("Japanese") or "auto"; default English.
+        # After language="auto", detected_language is (code, probability) (a list for batches).
+        # task=TRANSLATE outputs English whatever the spoken language (multilingual models only).
+        return _forward_batch(self, x, sample_rate, CT2, language, task)
+    def transcribe(self, x, sample_rate=16000, language=None, word_timestamps=False, task=TRANSCRIBE, diarize=False, speakers=None, tokens=False):
+        # Dict with "text", "language", "language_probability" and "segments": [{"start", "end",
+        # "text", "speaker", "no_speech_prob", "tokens", "words"}] (times in seconds).
+        # word_timestamps=True aligns each token ({"id", "text", "start", "end", "probability"}) and
+        # fills "words"; tokens=True fills "tokens" without aligning them (each spans its segment).
+        # diarize=True sets each segment's "speaker" ("Speaker 1", ...); speakers=
+        # {"Alice": waveform, ...} enrolls known voices (clips at sample_rate) and implies diarize.
+        return _transcribe_batch(self, x, sample_rate, CT2, language, word_timestamps, task, diarize, speakers, tokens)
     def forward_layer_by_layer(self, x, sample_rate=16000):
         raise NotImplementedError("forward_layer_by_layer requires backend=BURN")
 
@@ -608,6 +955,7 @@ class _WhisperModel:
     def __init__(self, payload):
         self._payload = payload
         self._model = payload.get("model", "tiny")
+        self.detected_language = None
     def named_parameters(self):
         for p in self._payload["parameters"]:
             param = _mk_parameter(p)
@@ -626,11 +974,12 @@ class _WhisperModel:
     @property
     def weights_file(self):
         return self._payload["weights_file"]
-    def forward(self, x, sample_rate=16000):
-        wave = _flatten_batch_dim1(_waveform_to_list(x))
-        if wave and isinstance(wave[0], (list, tuple)):
-            return [_forward_native(self._model, [float(v) for v in row], int(sample_rate), BURN) for row in wave]
-        return _forward_native(self._model, [float(v) for v in wave], int(sample_rate), BURN)
+    def forward(self, x, sample_rate=16000, language=None, task=TRANSCRIBE):
+        # Same language / task / detected_language contract as the CT2 model.
+        return _forward_batch(self, x, sample_rate, BURN, language, task)
+    def transcribe(self, x, sample_rate=16000, language=None, word_timestamps=False, task=TRANSCRIBE, diarize=False, speakers=None, tokens=False):
+        # Same result dict and tokens / diarize / speakers options as the CT2 model.
+        return _transcribe_batch(self, x, sample_rate, BURN, language, word_timestamps, task, diarize, speakers, tokens)
     def forward_layer_by_layer(self, x, sample_rate=16000):
         wave = _flatten_batch_dim1(_waveform_to_list(x))
         if wave and isinstance(wave[0], (list, tuple)):
@@ -685,8 +1034,21 @@ def load(model="tiny", full_values=False, max_values=128, weights_path=None, bac
         if let Ok(v) = scope.globals.get_item("CT2", vm) {
             whisper.set_attr("CT2", v, vm).ok();
         }
+        for name in ["to_srt", "to_vtt", "TRANSCRIBE", "TRANSLATE"] {
+            if let Ok(f) = scope.globals.get_item(name, vm) {
+                whisper.set_attr(name, f, vm).ok();
+            }
+        }
     }
 
     ai.set_attr("whisper", whisper, vm).ok();
+
+    let llama = crate::llama::make_llama_module(vm);
+    if let Ok(chat) = llama.get_attr("chat", vm) {
+        ai.set_attr("chat", chat, vm).ok();
+    }
+    ai.set_attr("llama", llama, vm).ok();
+    ai.set_attr("ocr", crate::ocr::make_ocr_module(vm), vm).ok();
+    ai.set_attr("tts", crate::tts::make_tts_module(vm), vm).ok();
     ai
 }
@@ -0,0 +1,630 @@
+//! `xos.audio.dsp` — the shared signal-processing toolkit (`xos_audio::dsp`) for scripts:
+//! band-limited resampling, biquad / FIR filters, STFT / iSTFT, mel spectrograms, normalization
+//! and AGC. Signals are mono `xos.Tensor`s or lists of floats; results come back as float32
+//! tensors. Stateful processors (`Resampler`, `Biquad`, `FIR`, `AGC`) filter a live stream chunk
+//! by chunk.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use rustpython_vm::{
+    builtins::PyModule, function::FuncArgs, PyObjectRef, PyRef, PyResult, VirtualMachine,
+};
+use xos_core::engine::audio::dsp::{
+    self, mel::MelOptions, window, Agc, Biquad, BiquadKind, Fir, MelSpectrogram, Resampler,
+    Spectrogram, Stft,
+};
+
+use crate::dtypes::DType;
+use crate::tensor_buf::{tensor_flat_data_list, tensor_shape_tuple};
+use crate::tensors::create_tensor_from_data;
+
+enum Processor {
+    Resampler(Resampler),
+    Biquad(Biquad),
+    Fir(Fir),
+    Agc(Agc),
+}
+
+thread_local! {
+    static PROCESSORS: RefCell<(u64, HashMap<u64, Processor>)> =
+        RefCell::new((0, HashMap::new()));
+}
+
+fn arg<T: rustpython_vm::TryFromObject>(
+    av: &[PyObjectRef],
+    i: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Option<T>> {
+    match av.get(i) {
+        Some(v) if !vm.is_none(v) => Ok(Some(v.clone().try_into_value(vm)?)),
+        _ => Ok(None),
+    }
+}
+
+fn required<T: rustpython_vm::TryFromObject>(
+    av: &[PyObjectRef],
+    i: usize,
+    name: &str,
+    vm: &VirtualMachine,
+) -> PyResult<T> {
+    arg(av, i, vm)?.ok_or_else(|| vm.new_type_error(format!("missing argument '{name}'")))
+}
+
+fn signal(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<Vec<f32>> {
+    let v = av
+        .get(i)
+        .ok_or_else(|| vm.new_type_error("missing signal argument".to_string()))?;
+    tensor_flat_data_list(v, vm)
+}
+
+fn value_err(vm: &VirtualMachine, e: String) -> rustpython_vm::builtins::PyBaseExceptionRef {
+    vm.new_value_error(e)
+}
+
+fn tensor(vm: &VirtualMachine, data: Vec<f32>, shape: Vec<usize>) -> PyResult {
+    let t = create_tensor_from_data(data, shape, DType::Float32);
+    super::wrap_tensor_dict(t.to_py_dict(vm, DType::Float32)?, vm)
+}
+
+fn mono(vm: &VirtualMachine, data: Vec<f32>) -> PyResult {
+    let n = data.len();
+    tensor(vm, data, vec![n])
+}
+
+fn floats(vm: &VirtualMachine, values: impl IntoIterator<Item = f64>) -> PyObjectRef {
+    vm.ctx
+        .new_list(
+            values
+                .into_iter()
+                .map(|v| vm.ctx.new_float(v).into())
+                .collect(),
+        )
+        .into()
+}
+
+fn window_arg(
+    av: &[PyObjectRef],
+    i: usize,
+    n_fft: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<f32>> {
+    let name = arg::<String>(av, i, vm)?.unwrap_or_else(|| "hann".to_string());
+    window::by_name(&name, n_fft, true).map_err(|e| value_err(vm, e))
+}
+
+fn kind_arg(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<BiquadKind> {
+    let name: String = required(av, i, "kind", vm)?;
+    BiquadKind::from_name(&name).ok_or_else(|| {
+        vm.new_value_error(format!(
+            "unknown filter kind '{name}' (lowpass, highpass, bandpass, notch, allpass, peaking, \
+             low_shelf, high_shelf)"
+        ))
+    })
+}
+
+fn register(p: Processor) -> u64 {
+    PROCESSORS.with(|t| {
+        let mut t = t.borrow_mut();
+        t.0 += 1;
+        let id = t.0;
+        t.1.insert(id, p);
+        id
+    })
+}
+
+fn with_processor<T>(
+    av: &[PyObjectRef],
+    vm: &VirtualMachine,
+    f: impl FnOnce(&mut Processor) -> T,
+) -> PyResult<T> {
+    let id: u64 = required(av, 0, "handle", vm)?;
+    PROCESSORS.with(|t| {
+        t.borrow_mut()
+            .1
+            .get_mut(&id)
+            .map(f)
+            .ok_or_else(|| vm.new_runtime_error("dsp processor is closed".to_string()))
+    })
+}
+
+/// `_resample(x, from_hz, to_hz)` → tensor.
+fn resample(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let x = signal(&av, 0, vm)?;
+    let from: u32 = required(&av, 1, "from_hz", vm)?;
+    let to: u32 = required(&av, 2, "to_hz", vm)?;
+    mono(vm, dsp::resample(&x, from, to))
+}
+
+/// `_new_resampler(from_hz, to_hz)` → handle.
+fn new_resampler(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let from: u32 = required(&args.args, 0, "from_hz", vm)?;
+    let to: u32 = required(&args.args, 1, "to_hz", vm)?;
+    let id = register(Processor::Resampler(Resampler::new(from, to)));
+    Ok(vm.ctx.new_int(id).into())
+}
+
+/// `_new_biquad(kind, freq_hz, sample_rate, q, gain_db)` → handle.
+fn new_biquad(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let kind = kind_arg(&av, 0, vm)?;
+    let freq: f64 = required(&av, 1, "freq_hz", vm)?;
+    let rate: u32 = required(&av, 2, "sample_rate", vm)?;
+    let q = arg::<f64>(&av, 3, vm)?.unwrap_or(dsp::filter::BUTTERWORTH_Q);
+    let gain_db = arg::<f64>(&av, 4, vm)?.unwrap_or(0.0);
+    let biquad = Biquad::design(kind, freq, rate, q, gain_db).map_err(|e| value_err(vm, e))?;
+    Ok(vm.ctx.new_int(register(Processor::Biquad(biquad))).into())
+}
+
+/// `_new_fir(taps)` → handle.
+fn new_fir(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let taps = signal(&args.args, 0, vm)?;
+    let fir = Fir::new(taps).map_err(|e| value_err(vm, e))?;
+    Ok(vm.ctx.new_int(register(Processor::Fir(fir))).into())
+}
+
+/// `_new_agc(sample_rate, target_db, max_gain_db, attack_ms, release_ms, gate_db)` → handle.
+fn new_agc(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let rate: u32 = required(&av, 0, "sample_rate", vm)?;
+    let target = arg::<f64>(&av, 1, vm)?.unwrap_or(-20.0) as f32;
+    let max_gain = arg::<f64>(&av, 2, vm)?.unwrap_or(30.0) as f32;
+    let attack = arg::<f64>(&av, 3, vm)?.unwrap_or(10.0) as f32;
+    let release = arg::<f64>(&av, 4, vm)?.unwrap_or(500.0) as f32;
+    let gate = arg::<f64>(&av, 5, vm)?.unwrap_or(-60.0) as f32;
+    let agc = Agc::new(rate, target, max_gain)
+        .with_times(rate, attack, release)
+        .with_gate(gate);
+    Ok(vm.ctx.new_int(register(Processor::Agc(agc))).into())
+}
+
+/// `_process(handle, x)` → tensor (a resampler may return fewer samples; see `_flush`).
+fn process(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let mut x = signal(&args.args, 1, vm)?;
+    let out = with_processor(&args.args, vm, |p| match p {
+        Processor::Resampler(r) => r.process(&x),
+        Processor::Biquad(b) => {
+            b.process(&mut x);
+            std::mem::take(&mut x)
+        }
+        Processor::Fir(f) => {
+            f.process(&mut x);
+            std::mem::take(&mut x)
+        }
+        Processor::Agc(a) => {
+            a.process(&mut x);
+            std::mem::take(&mut x)
+        }
+    })?;
+    mono(vm, out)
+}
+
+/// `_flush(handle)` → the resampler's remaining output (empty for filters); the stream restarts.
+fn flush(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let out = with_processor(&args.args, vm, |p| match p {
+        Processor::Resampler(r) => r.flush(),
+        _ => Vec::new(),
+    })?;
+    mono(vm, out)
+}
+
+/// `_reset(handle)`: forget the stream so far.
+fn reset(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    with_processor(&args.args, vm, |p| match p {
+        Processor::Resampler(r) => r.reset(),
+        Processor::Biquad(b) => b.reset(),
+        Processor::Fir(f) => f.reset(),
+        Processor::Agc(a) => a.reset(),
+    })?;
+    Ok(vm.ctx.none())
+}
+
+/// `_info(handle)` → `{"kind", ...}` (coefficients, taps, rates or current gain).
+fn info(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let d = vm.ctx.new_dict();
+    let entries: Vec<(&str, PyObjectRef)> = with_processor(&args.args, vm, |p| match p {
+        Processor::Resampler(r) => vec![
+            ("kind", vm.ctx.new_str("resampler").into()),
+            ("from_hz", vm.ctx.new_int(r.rates().0).into()),
+            ("to_hz", vm.ctx.new_int(r.rates().1).into()),
+        ],
+        Processor::Biquad(b) => vec![
+            ("kind", vm.ctx.new_str("biquad").into()),
+            ("coefficients", floats(vm, b.coefficients())),
+        ],
+        Processor::Fir(f) => vec![
+            ("kind", vm.ctx.new_str("fir").into()),
+            ("taps", floats(vm, f.taps().iter().map(|&t| t as f64))),
+        ],
+        Processor::Agc(a) => vec![
+            ("kind", vm.ctx.new_str("agc").into()),
+            ("gain_db", vm.ctx.new_float(a.gain_db() as f64).into()),
+        ],
+    })?;
+    for (k, v) in entries {
+        d.set_item(k, v, vm)?;
+    }
+    Ok(d.into())
+}
+
+/// `_close(handle)`.
+fn close(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let id: u64 = required(&args.args, 0, "handle", vm)?;
+    PROCESSORS.with(|t| t.borrow_mut().1.remove(&id));
+    Ok(vm.ctx.none())
+}
+
+/// `_design_fir(kind, num_taps, low_hz, high_hz, sample_rate)` → list of taps.
+fn design_fir(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let kind = kind_arg(&av, 0, vm)?;
+    let num_taps: usize = required(&av, 1, "num_taps", vm)?;
+    let low: f64 = required(&av, 2, "cutoff_hz", vm)?;
+    let high = arg::<f64>(&av, 3, vm)?;
+    let rate: u32 = required(&av, 4, "sample_rate", vm)?;
+    let taps = dsp::design_fir(kind, num_taps, low, high, rate).map_err(|e| value_err(vm, e))?;
+    Ok(floats(vm, taps.iter().map(|&t| t as f64)))
+}
+
+/// `_stft(x, n_fft, hop, window, center)` → `(real, imag)`, each `[frames, n_fft // 2 + 1]`.
+fn stft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let x = signal(&av, 0, vm)?;
+    let n_fft: usize = required(&av, 1, "n_fft", vm)?;
+    let hop: usize = required(&av, 2, "hop", vm)?;
+    let window = window_arg(&av, 3, n_fft, vm)?;
+    let center = arg::<bool>(&av, 4, vm)?.unwrap_or(true);
+    let spec = Stft::new(n_fft, hop, window, center)
+        .map_err(|e| value_err(vm, e))?
+        .forward(&x);
+    let shape = vec![spec.frames, spec.bins];
+    Ok(vm
+        .ctx
+        .new_tuple(vec![
+            tensor(vm, spec.re, shape.clone())?,
+            tensor(vm, spec.im, shape)?,
+        ])
+        .into())
+}
+
+/// `_istft(real, imag, n_fft, hop, window, center, length)` → tensor.
+fn istft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let re = signal(&av, 0, vm)?;
+    let im = signal(&av, 1, vm)?;
+    let n_fft: usize = required(&av, 2, "n_fft", vm)?;
+    let hop: usize = required(&av, 3, "hop", vm)?;
+    let window = window_arg(&av, 4, n_fft, vm)?;
+    let center = arg::<bool>(&av, 5, vm)?.unwrap_or(true);
+    let length = arg::<usize>(&av, 6, vm)?;
+    let bins = n_fft / 2 + 1;
+    if re.len() != im.len() || re.len() % bins != 0 {
+        return Err(vm.new_value_error(format!(
+            "real and imag must both be [frames, {bins}] for n_fft = {n_fft}"
+        )));
+    }
+    let spec = Spectrogram {
+        frames: re.len() / bins,
+        bins,
+        re,
+        im,
+    };
+    let out = Stft::new(n_fft, hop, window, center)
+        .and_then(|s| s.inverse(&spec, length))
+        .map_err(|e| value_err(vm, e))?;
+    mono(vm, out)
+}
+
+fn mel_options(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<MelOptions> {
+    let norm = arg::<String>(av, i + 4, vm)?.unwrap_or_else(|| "slaney".to_string());
+    Ok(MelOptions {
+        n_mels: arg(av, i, vm)?.unwrap_or(80),
+        f_min: arg(av, i + 1, vm)?.unwrap_or(0.0),
+        f_max: arg(av, i + 2, vm)?,
+        htk: arg(av, i + 3, vm)?.unwrap_or(false),
+        slaney_norm: match norm.as_str() {
+            "slaney" => true,
+            "none" | "" => false,
+            other => {
+                return Err(vm.new_value_error(format!(
+                    "unknown mel norm '{other}' (use 'slaney' or 'none')"
+                )))
+            }
+        },
+    })
+}
+
+/// `_mel_filters(sample_rate, n_fft, n_mels, f_min, f_max, htk, norm)` → `[n_mels, n_fft // 2 + 1]`.
+fn mel_filters(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let rate: u32 = required(&av, 0, "sample_rate", vm)?;
+    let n_fft: usize = required(&av, 1, "n_fft", vm)?;
+    let options = mel_options(&av, 2, vm)?;
+    let filters = dsp::mel_filters(rate, n_fft, &options).map_err(|e| value_err(vm, e))?;
+    tensor(vm, filters, vec![options.n_mels, n_fft / 2 + 1])
+}
+
+/// `_mel_spectrogram(x, sample_rate, n_fft, hop, n_mels, f_min, f_max, htk, norm, power, top_db)`
+/// → `[n_mels, frames]`; in dB when `top_db` is given (`inf` for no floor).
+fn mel_spectrogram(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let x = signal(&av, 0, vm)?;
+    let rate: u32 = required(&av, 1, "sample_rate", vm)?;
+    let n_fft: usize = required(&av, 2, "n_fft", vm)?;
+    let hop: usize = required(&av, 3, "hop", vm)?;
+    let options = mel_options(&av, 4, vm)?;
+    let power = arg::<f64>(&av, 9, vm)?.unwrap_or(2.0) as f32;
+    let top_db = arg::<f64>(&av, 10, vm)?;
+    let mel =
+        MelSpectrogram::new(rate, n_fft, hop, &options, power).map_err(|e| value_err(vm, e))?;
+    let (mut values, frames) = mel.compute(&x);
+    if let Some(top) = top_db {
+        dsp::power_to_db(&mut values, top.is_finite().then_some(top as f32));
+    }
+    tensor(vm, values, vec![options.n_mels, frames])
+}
+
+/// `_normalize(x, mode, target_db)` → tensor; mode is `"peak"` or `"rms"`.
+fn normalize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let mut x = signal(&av, 0, vm)?;
+    let mode = arg::<String>(&av, 1, vm)?.unwrap_or_else(|| "peak".to_string());
+    let target = arg::<f64>(&av, 2, vm)?;
+    match mode.as_str() {
+        "peak" => dsp::normalize_peak(&mut x, target.unwrap_or(-1.0) as f32),
+        "rms" => dsp::normalize_rms(&mut x, target.unwrap_or(-20.0) as f32),
+        other => {
+            return Err(vm.new_value_error(format!(
+                "unknown normalize mode '{other}' (use 'peak' or 'rms')"
+            )))
+        }
+    }
+    mono(vm, x)
+}
+
+/// `_downmix(x)`: `[samples, channels]` (interleaved) → `[samples]`.
+fn downmix(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let av = args.args;
+    let x = signal(&av, 0, vm)?;
+    let channels = match av.first().map(|v| tensor_shape_tuple(v, vm)) {
+        Some(Ok(shape)) if shape.len() == 2 => shape[1].max(1),
+        _ => 1,
+    };
+    let split: Vec<Vec<f32>> = (0..channels)
+        .map(|c| x.iter().skip(c).step_by(channels).copied().collect())
+        .collect();
+    mono(vm, dsp::downmix(&split))
+}
+
+/// `_levels(x)` → `(rms, peak)`.
+fn levels(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let x = signal(&args.args, 0, vm)?;
+    Ok(vm
+        .ctx
+        .new_tuple(vec![
+            vm.ctx.new_float(dsp::rms(&x) as f64).into(),
+            vm.ctx.new_float(dsp::peak(&x) as f64).into(),
+        ])
+        .into())
+}
+
+/// `fft(real, imag=None)` / `ifft(real, imag)` → `(real, imag)` lists, any length (`ifft` scales
+/// by `1/n`).
+fn fft_lists(args: FuncArgs, vm: &VirtualMachine, inverse: bool) -> PyResult {
+    let av = args.args;
+    let mut re: Vec<f64> = signal(&av, 0, vm)?.into_iter().map(f64::from).collect();
+    let mut im: Vec<f64> = match av.get(1) {
+        Some(v) if !vm.is_none(v) => tensor_flat_data_list(v, vm)?
+            .into_iter()
+            .map(f64::from)
+            .collect(),
+        _ => vec![0.0; re.len()],
+    };
+    if im.len() != re.len() {
+        return Err(vm.new_value_error("real and imag must have the same length".to_string()));
+    }
+    let plan = dsp::Fft::new(re.len());
+    if inverse {
+        plan.inverse(&mut re, &mut im);
+    } else {
+        plan.forward(&mut re, &mut im);
+    }
+    Ok(vm
+        .ctx
+        .new_tuple(vec![floats(vm, re), floats(vm, im)])
+        .into())
+}
+
+fn fft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    fft_lists(args, vm, false)
+}
+
+fn ifft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    fft_lists(args, vm, true)
+}
+
+const GLUE: &str = r#"
+def _float(v):
+    return None if v is None else float(v)
+
+class _Processor:
+    def __init__(self, handle):
+        self._handle = handle
+    def __del__(self):
+        try:
+            _close(self._handle)
+        except Exception:
+            pass
+    def process(self, x):
+        """Filter the next chunk; state carries over from the previous call."""
+        return _process(self._handle, x)
+    def __call__(self, x):
+        return self.process(x)
+    def reset(self):
+        _reset(self._handle)
+
+class Resampler(_Processor):
+    """Streaming band-limited resampler. ``process`` holds back a few milliseconds of
+    look-ahead; ``flush()`` returns them at the end of the stream."""
+    def __init__(self, from_hz, to_hz):
+        super().__init__(_new_resampler(int(from_hz), int(to_hz)))
+        self.from_hz = int(from_hz)
+        self.to_hz = int(to_hz)
+    def flush(self):
+        return _flush(self._handle)
+
+class Biquad(_Processor):
+    """Second-order IIR section (RBJ cookbook). ``kind``: lowpass, highpass, bandpass, notch,
+    allpass, peaking, low_shelf, high_shelf; ``gain_db`` only for peaking / shelves."""
+    def __init__(self, kind, freq_hz, sample_rate, q=0.7071067811865476, gain_db=0.0):
+        super().__init__(_new_biquad(str(kind), float(freq_hz), int(sample_rate), float(q), float(gain_db)))
+    @property
+    def coefficients(self):
+        # (b0, b1, b2, a1, a2) with a0 = 1.
+        return tuple(_info(self._handle)["coefficients"])
+
+class FIR(_Processor):
+    """FIR filter from ``taps`` (e.g. ``design_fir(...)``)."""
+    def __init__(self, taps):
+        super().__init__(_new_fir(taps))
+    @property
+    def taps(self):
+        return _info(self._handle)["taps"]
+
+class AGC(_Processor):
+    """Automatic gain control toward ``target_db`` dBFS RMS, boosting at most ``max_gain_db``;
+    the gain holds while the input is below ``gate_db``."""
+    def __init__(self, sample_rate, target_db=-20.0, max_gain_db=30.0, attack_ms=10.0, release_ms=500.0, gate_db=-60.0):
+        super().__init__(_new_agc(int(sample_rate), float(target_db), float(max_gain_db), float(attack_ms), float(release_ms), float(gate_db)))
+    @property
+    def gain_db(self):
+        return _info(self._handle)["gain_db"]
+
+def resample(x, from_hz, to_hz):
+    """Band-limited (windowed-sinc) resampling of a whole mono signal."""
+    return _resample(x, int(from_hz), int(to_hz))
+
+def biquad(x, kind, freq_hz, sample_rate, q=0.7071067811865476, gain_db=0.0):
+    return Biquad(kind, freq_hz, sample_rate, q, gain_db).process(x)
+
+def lowpass(x, freq_hz, sample_rate, q=0.7071067811865476):
+    return biquad(x, "lowpass", freq_hz, sample_rate, q)
+
+def highpass(x, freq_hz, sample_rate, q=0.7071067811865476):
+    return biquad(x, "highpass", freq_hz, sample_rate, q)
+
+def design_fir(kind, num_taps, cutoff_hz, sample_rate):
+    """Linear-phase FIR taps (Kaiser-windowed sinc, ~80 dB stopband). ``kind``: lowpass,
+    highpass, bandpass or bandstop; band kinds take ``cutoff_hz=(low, high)``."""
+    if isinstance(cutoff_hz, (tuple, list)):
+        low, high = cutoff_hz
+        return _design_fir(str(kind), int(num_taps), float(low), float(high), int(sample_rate))
+    return _design_fir(str(kind), int(num_taps), float(cutoff_hz), None, int(sample_rate))
+
+def fir(x, taps):
+    return FIR(taps).process(x)
+
+def stft(x, n_fft=400, hop=160, window="hann", center=True):
+    """``(real, imag)`` tensors of shape ``[frames, n_fft // 2 + 1]`` (torch.stft conventions)."""
+    return _stft(x, int(n_fft), int(hop), window, bool(center))
+
+def istft(real, imag, n_fft=400, hop=160, window="hann", center=True, length=None):
+    return _istft(real, imag, int(n_fft), int(hop), window, bool(center), None if length is None else int(length))
+
+def mel_filters(sample_rate=16000, n_fft=400, n_mels=80, f_min=0.0, f_max=None, htk=False, norm="slaney"):
+    """``[n_mels, n_fft // 2 + 1]`` triangular filterbank (librosa defaults: Whisper's bank)."""
+    return _mel_filters(int(sample_rate), int(n_fft), int(n_mels), float(f_min), _float(f_max), bool(htk), norm)
+
+def mel_spectrogram(x, sample_rate=16000, n_fft=400, hop=160, n_mels=80, f_min=0.0, f_max=None,
+                    htk=False, norm="slaney", power=2.0, db=False, top_db=80.0):
+    """``[n_mels, frames]`` mel spectrogram; ``db=True`` converts to decibels, floored
+    ``top_db`` below the peak (``top_db=None`` for no floor)."""
+    floor = (float("inf") if top_db is None else float(top_db)) if db else None
+    return _mel_spectrogram(x, int(sample_rate), int(n_fft), int(hop), int(n_mels), float(f_min),
+                            _float(f_max), bool(htk), norm, float(power), floor)
+
+def normalize(x, target_db=None, mode="peak"):
+    """Scale to a peak of ``target_db`` dBFS (default -1) or, with ``mode="rms"``, an RMS of
+    ``target_db`` (default -20) without clipping."""
+    return _normalize(x, mode, _float(target_db))
+
+def agc(x, sample_rate, target_db=-20.0, max_gain_db=30.0, **options):
+    return AGC(sample_rate, target_db, max_gain_db, **options).process(x)
+
+def downmix(x):
+    """``[samples, channels]`` → mono, skipping channels that are silent next to the loudest."""
+    return _downmix(x)
+
+def rms(x):
+    return _levels(x)[0]
+
+def peak(x):
+    return _levels(x)[1]
+"#;
+
+const EXPORTS: [&str; 21] = [
+    "Resampler",
+    "Biquad",
+    "FIR",
+    "AGC",
+    "resample",
+    "biquad",
+    "lowpass",
+    "highpass",
+    "design_fir",
+    "fir",
+    "stft",
+    "istft",
+    "mel_filters",
+    "mel_spectrogram",
+    "normalize",
+    "agc",
+    "downmix",
+    "rms",
+    "peak",
+    "fft",
+    "ifft",
+];
+
+type Native = fn(FuncArgs, &VirtualMachine) -> PyResult;
+
+pub fn make_dsp_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.audio.dsp", vm.ctx.new_dict(), None);
+    let natives: [(&str, Native); 20] = [
+        ("_resample", resample),
+        ("_new_resampler", new_resampler),
+        ("_new_biquad", new_biquad),
+        ("_new_fir", new_fir),
+        ("_new_agc", new_agc),
+        ("_process", process),
+        ("_flush", flush),
+        ("_reset", reset),
+        ("_info", info),
+        ("_close", close),
+        ("_design_fir", design_fir),
+        ("_stft", stft),
+        ("_istft", istft),
+        ("_mel_filters", mel_filters),
+        ("_mel_spectrogram", mel_spectrogram),
+        ("_normalize", normalize),
+        ("_downmix", downmix),
+        ("_levels", levels),
+        ("fft", fft),
+        ("ifft", ifft),
+    ];
+    let scope = vm.new_scope_with_builtins();
+    for (name, f) in natives {
+        let func: PyObjectRef = vm.new_function(name, f).into();
+        scope.globals.set_item(name, func, vm).ok();
+    }
+    if let Err(e) = vm.run_code_string(scope.clone(), GLUE, "<xos.audio.dsp>".to_string()) {
+        eprintln!("Failed to create xos.audio.dsp: {:?}", e);
+    }
+    for name in EXPORTS {
+        if let Ok(v) = scope.globals.get_item(name, vm) {
+            module.set_attr(name, v, vm).ok();
+        }
+    }
+    module
+}
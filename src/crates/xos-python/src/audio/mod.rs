@@ -1,6 +1,7 @@
 use rustpython_vm::{builtins::PyModule, PyRef, VirtualMachine};
 use rustpython_vm::{function::FuncArgs, PyResult};
 
+mod dsp;
 mod microphone;
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
 mod recording;
@@ -11,8 +12,7 @@ mod transcription;
 pub use microphone::cleanup_all_microphones_rust;
 pub use speakers::cleanup_all_speakers_rust;
 
-#[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
-fn wrap_tensor_dict(dict: rustpython_vm::PyObjectRef, vm: &VirtualMachine) -> PyResult {
+pub(crate) fn wrap_tensor_dict(dict: rustpython_vm::PyObjectRef, vm: &VirtualMachine) -> PyResult {
     if let Ok(wrapper_class) = vm.builtins.get_attr("Tensor", vm) {
         if let Ok(wrapped) = wrapper_class.call((dict.clone(),), vm) {
             return Ok(wrapped);
@@ -21,25 +21,6 @@ fn wrap_tensor_dict(dict: rustpython_vm::PyObjectRef, vm: &VirtualMachine) -> Py
     Ok(dict)
 }
 
-#[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
-fn resample_linear(input: &[f32], src_rate: u32, dst_rate: u32) -> Vec<f32> {
-    if input.is_empty() || src_rate == 0 || dst_rate == 0 || src_rate == dst_rate {
-        return input.to_vec();
-    }
-    let out_len = ((input.len() as u64) * (dst_rate as u64) / (src_rate as u64)).max(1) as usize;
-    let mut out = Vec::with_capacity(out_len);
-    let scale = src_rate as f32 / dst_rate as f32;
-    for i in 0..out_len {
-        let pos = i as f32 * scale;
-        let idx = pos.floor() as usize;
-        let frac = pos - idx as f32;
-        let a = input.get(idx).copied().unwrap_or(0.0);
-        let b = input.get(idx + 1).copied().unwrap_or(a);
-        out.push(a + (b - a) * frac);
-    }
-    out
-}
-
 /// Load audio to mono **f32** PCM, default **16_000 Hz** — the rate Whisper / in-tree `whisper_burn`
 /// expect for `transcribe(..., sample_rate, ...)`. Samples are roughly **[-1, 1]** after decode.
 #[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
@@ -68,7 +49,7 @@ fn audio_load(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let (src_rate, _duration, mono) = decode_path_to_mono_f32(Path::new(&path))
         .map_err(|e| vm.new_runtime_error(format!("decode audio file: {e}")))?;
 
-    let mono = resample_linear(&mono, src_rate, target_sample_rate as u32);
+    let mono = xos_core::engine::audio::dsp::resample(&mono, src_rate, target_sample_rate as u32);
     let shape = vec![mono.len()];
     let py_tensor: Tensor = create_tensor_from_data(mono, shape, DType::Float32);
     wrap_tensor_dict(py_tensor.to_py_dict(vm, DType::Float32)?, vm)
@@ -93,6 +74,17 @@ fn recording_stub_new(
     ))
 }
 
+const SPEAK_PY_CODE: &str = r#"
+def speak(text, voice="mms-tts-eng", speaker=None, speed=1.0, **options):
+    """Say ``text`` with a local TTS voice (see ``xos.ai.tts``; loaded on first use and kept).
+
+    ``speaker``: an ``xos.audio.Speaker`` to stream into, sentence by sentence; without one the
+    audio is returned as a mono float32 tensor at the voice's rate (or ``sample_rate=``).
+    ``speed`` scales the speaking rate; other options (``speaker_id``, ``noise_scale``,
+    ``sentence_pause``, ``seed``, ``device``) are those of ``xos.ai.tts.speak``."""
+    return __import__("xos").ai.tts.speak(text, voice, speaker, speed, **options)
+"#;
+
 /// Create the audio module with both microphone and speaker support
 pub fn make_audio_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let module = vm.new_module("xos.audio", vm.ctx.new_dict(), None);
@@ -268,6 +260,16 @@ pub fn make_audio_module(vm: &VirtualMachine) -> PyRef<PyModule> {
             vm,
         )
         .unwrap();
+    module
+        .set_attr(
+            "_transcriber_detected_language",
+            vm.new_function(
+                "_transcriber_detected_language",
+                transcription::transcriber_detected_language,
+            ),
+            vm,
+        )
+        .unwrap();
     module
         .set_attr(
             "_transcriber_buffered_seconds",
@@ -362,6 +364,22 @@ pub fn make_audio_module(vm: &VirtualMachine) -> PyRef<PyModule> {
         )
         .unwrap();
 
+    // --- Signal processing (`xos.audio.dsp`) ---
+    module
+        .set_attr("dsp", dsp::make_dsp_module(vm), vm)
+        .unwrap();
+
+    // --- Text-to-speech (`xos.ai.tts`) ---
+    let scope = vm.new_scope_with_builtins();
+    match vm.run_code_string(scope.clone(), SPEAK_PY_CODE, "<audio.speak>".to_string()) {
+        Ok(_) => {
+            if let Ok(f) = scope.globals.get_item("speak", vm) {
+                module.set_attr("speak", f, vm).unwrap();
+            }
+        }
+        Err(e) => eprintln!("Failed to create xos.audio.speak: {:?}", e),
+    }
+
     module
 }
 
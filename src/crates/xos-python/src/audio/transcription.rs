@@ -1,6 +1,6 @@
-use xos_core::ai::transcription::{TranscriptionEngine, WhisperBackend};
+use xos_core::ai::transcription::{TranscriptionEngine, WhisperBackend, WhisperTask};
 use xos_core::engine::audio::AudioListener;
-use rustpython_vm::{function::FuncArgs, PyObjectRef, PyResult, VirtualMachine};
+use rustpython_vm::{builtins::PyDict, function::FuncArgs, PyObjectRef, PyResult, VirtualMachine};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Mutex, OnceLock};
 
@@ -29,7 +29,7 @@ pub fn transcription_new(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         obj.clone()
     } else {
         return Err(vm.new_type_error(
-            "xos.audio.transcription(audio, size='tiny|small|base', backend='ct2|burn', language='english|japanese') expects a microphone object"
+            "xos.audio.transcription(audio, size='tiny|small|base', backend='ct2|burn', language='en|japanese|...|auto', task='transcribe|translate', diarize=False, speakers=None) expects a microphone object"
                 .to_string(),
         ));
     };
@@ -65,6 +65,30 @@ pub fn transcription_new(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     } else {
         None
     };
+    let task = match args.kwargs.get("task") {
+        Some(v) => {
+            let s: String = v.clone().try_into_value(vm)?;
+            WhisperTask::from_str(&s).ok_or_else(|| {
+                vm.new_value_error("task must be 'transcribe' or 'translate'".to_string())
+            })?
+        }
+        None => WhisperTask::Transcribe,
+    };
+    let diarize = match args.kwargs.get("diarize") {
+        Some(v) => v.clone().try_into_value::<bool>(vm)?,
+        None => false,
+    };
+    // `speakers={"Alice": waveform, ...}`: 16 kHz clips of known voices; implies `diarize`.
+    let mut speakers: Vec<(String, Vec<f32>)> = Vec::new();
+    if let Some(v) = args.kwargs.get("speakers").filter(|v| !vm.is_none(v)) {
+        let dict = v.clone().downcast::<PyDict>().map_err(|_| {
+            vm.new_type_error("speakers must be a dict of name -> waveform".to_string())
+        })?;
+        for (name, clip) in &dict {
+            let name: String = name.try_into_value(vm)?;
+            speakers.push((name, crate::ai::waveform_vec_from_py(&clip, vm)?));
+        }
+    }
     let listener_ptr_obj = mic_obj.get_attr("_listener_ptr", vm).map_err(|_| {
         vm.new_type_error("xos.audio.transcription expects xos.audio.Microphone".to_string())
     })?;
@@ -77,8 +101,12 @@ pub fn transcription_new(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         size.as_deref(),
         backend,
         language.as_deref(),
+        task,
     )
     .map_err(|e| vm.new_value_error(e))?;
+    if diarize || !speakers.is_empty() {
+        enable_diarization(&mut engine, &speakers).map_err(|e| vm.new_runtime_error(e))?;
+    }
     let listener = unsafe { &*(listener_ptr as *const AudioListener) };
     engine.set_device_hint("python-mic", listener.buffer().sample_rate());
 
@@ -120,6 +148,11 @@ class _Transcriber:
         import xos
         return xos.audio._transcriber_vad_prob(self._ptr)
 
+    def detected_language(self):
+        """With ``language="auto"``: ``(code, probability)`` from the latest decode, else None."""
+        import xos
+        return xos.audio._transcriber_detected_language(self._ptr)
+
     def buffered_seconds(self):
         """Approximate audio seconds currently buffered in the decode segment."""
         import xos
@@ -166,6 +199,28 @@ _transcriber_instance = _Transcriber({})
     scope.globals.get_item("_transcriber_instance", vm)
 }
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "whisper"))]
+fn enable_diarization(
+    engine: &mut TranscriptionEngine,
+    speakers: &[(String, Vec<f32>)],
+) -> Result<(), String> {
+    use xos_core::ai::transcription::diarization::Diarizer;
+    let mut diarizer = Diarizer::new()?;
+    for (name, clip) in speakers {
+        diarizer.enroll(name, clip, 16_000)?;
+    }
+    engine.enable_diarization(diarizer);
+    Ok(())
+}
+
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "whisper")))]
+fn enable_diarization(
+    _engine: &mut TranscriptionEngine,
+    _speakers: &[(String, Vec<f32>)],
+) -> Result<(), String> {
+    Err("Speaker diarization is unavailable in this build (enable diarization)".to_string())
+}
+
 pub fn transcriber_next_events(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let ptr: usize = args.bind(vm)?;
     let mut map = transcribers()
@@ -205,6 +260,26 @@ pub fn transcriber_vad_prob(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         .into())
 }
 
+pub fn transcriber_detected_language(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let ptr: usize = args.bind(vm)?;
+    let map = transcribers()
+        .lock()
+        .map_err(|_| vm.new_runtime_error("transcriber lock poisoned".to_string()))?;
+    let state = map
+        .get(&ptr)
+        .ok_or_else(|| vm.new_runtime_error("Invalid transcriber pointer".to_string()))?;
+    Ok(match state.engine.detected_language() {
+        Some(det) => vm
+            .ctx
+            .new_tuple(vec![
+                vm.ctx.new_str(det.code).into(),
+                vm.ctx.new_float(det.probability as f64).into(),
+            ])
+            .into(),
+        None => vm.ctx.none(),
+    })
+}
+
 pub fn transcriber_buffered_seconds(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let ptr: usize = args.bind(vm)?;
     let map = transcribers()
@@ -0,0 +1,169 @@
+//! `xos.capture` — record the app's frames to a PNG sequence, GIF, MP4 or WebM.
+//!
+//! Inside `tick()` / input callbacks the engine's recorder is driven directly; elsewhere (module
+//! top level, `setup` before the host loop) commands are queued and applied on the next frame.
+
+use rustpython_vm::builtins::PyModule;
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyObjectRef, PyRef, PyResult, VirtualMachine};
+use xos_core::engine::capture::{
+    capture_status, default_capture_path, request_capture, CaptureCommand, CaptureConfig,
+    FrameCapture,
+};
+
+const CAPTURE_PY_CODE: &str = r#"
+class Capture:
+    """Frame recorder for one app (``app.capture``). The file type follows the extension:
+    ``.mp4`` / ``.webm`` (needs ``ffmpeg`` on PATH), ``.gif``, or ``.png`` / a directory for a
+    numbered PNG sequence. Only the app's own drawing is captured, never the F3 menu."""
+
+    def start(self, path=None, every=1, frames=None, fps=60):
+        """Begin recording to ``path`` (default: a timestamped file under the xos data dir).
+        Keeps one of every ``every`` frames and stops by itself after ``frames`` when given."""
+        import xos
+        return xos.capture._start(
+            None if path is None else str(path),
+            int(every),
+            None if frames is None else int(frames),
+            float(fps),
+        )
+
+    def stop(self):
+        """Finish the file; returns its path (``None`` if nothing was recording)."""
+        import xos
+        return xos.capture._stop()
+
+    def toggle(self):
+        if self.is_recording:
+            return self.stop()
+        return self.start()
+
+    @property
+    def is_recording(self):
+        import xos
+        return xos.capture._status()["active"]
+
+    @property
+    def frames(self):
+        """Frames written by the current (or last) capture."""
+        import xos
+        return xos.capture._status()["frames"]
+
+    @property
+    def path(self):
+        import xos
+        return xos.capture._status()["path"]
+
+    @property
+    def error(self):
+        """Why the last capture failed, or ``None``."""
+        import xos
+        return xos.capture._status()["error"]
+
+    def __repr__(self):
+        return "Capture(recording=%r, frames=%d, path=%r)" % (self.is_recording, self.frames, self.path)
+"#;
+
+/// Run `f` against the engine's recorder (input callback or tick); `None` outside the engine.
+fn with_engine_capture<T>(f: impl Fn(&mut FrameCapture) -> T) -> Option<T> {
+    crate::engine::py_engine_tls::with_callback_engine_state_mut(|s| f(&mut s.capture))
+        .or_else(|| crate::engine::py_engine_tls::with_tick_engine_state_mut(|s| f(&mut s.capture)))
+}
+
+fn optional<T: rustpython_vm::TryFromObject>(
+    args: &FuncArgs,
+    index: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Option<T>> {
+    match args.args.get(index) {
+        Some(obj) if !vm.is_none(obj) => Ok(Some(obj.clone().try_into_value(vm)?)),
+        _ => Ok(None),
+    }
+}
+
+fn path_or_none(vm: &VirtualMachine, path: Option<std::path::PathBuf>) -> PyObjectRef {
+    match path {
+        Some(p) => vm.ctx.new_str(p.to_string_lossy().as_ref()).into(),
+        None => vm.ctx.none(),
+    }
+}
+
+/// xos.capture._start(path, every, frames, fps) - begin a capture and return its output path
+fn capture_start(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let path: Option<String> = optional(&args, 0, vm)?;
+    let every: Option<u32> = optional(&args, 1, vm)?;
+    let frames: Option<u32> = optional(&args, 2, vm)?;
+    let fps: Option<f64> = optional(&args, 3, vm)?;
+    if frames == Some(0) {
+        return Err(vm.new_value_error("frames must be at least 1".to_string()));
+    }
+    let mut config = CaptureConfig::new(path.map(Into::into).unwrap_or_else(default_capture_path));
+    config.every = every.unwrap_or(1).max(1);
+    config.max_frames = frames;
+    if let Some(fps) = fps {
+        config.fps = fps as f32;
+    }
+    let out = config.path.clone();
+    let pending = config.clone();
+    if with_engine_capture(|capture| capture.start(pending.clone())).is_none() {
+        request_capture(CaptureCommand::Start(config));
+    }
+    Ok(path_or_none(vm, Some(out)))
+}
+
+/// xos.capture._stop() - finish the current capture; returns its path or None
+fn capture_stop(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    match with_engine_capture(|capture| capture.stop()) {
+        Some(path) => Ok(path_or_none(vm, path)),
+        None => {
+            request_capture(CaptureCommand::Stop);
+            Ok(path_or_none(vm, capture_status().path))
+        }
+    }
+}
+
+/// xos.capture._status() - {"active", "frames", "path", "error"} for the current / last capture
+fn capture_status_dict(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let status = capture_status();
+    let d = vm.ctx.new_dict();
+    d.set_item("active", vm.ctx.new_bool(status.active).into(), vm)?;
+    d.set_item("frames", vm.ctx.new_int(status.frames_written).into(), vm)?;
+    d.set_item("path", path_or_none(vm, status.path), vm)?;
+    let error = match status.error {
+        Some(e) => vm.ctx.new_str(e).into(),
+        None => vm.ctx.none(),
+    };
+    d.set_item("error", error, vm)?;
+    Ok(d.into())
+}
+
+/// Create the capture module
+pub fn make_capture_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.capture", vm.ctx.new_dict(), None);
+
+    // Internal functions used by the Python class below
+    module
+        .set_attr("_start", vm.new_function("_start", capture_start), vm)
+        .unwrap();
+    module
+        .set_attr("_stop", vm.new_function("_stop", capture_stop), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "_status",
+            vm.new_function("_status", capture_status_dict),
+            vm,
+        )
+        .unwrap();
+
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), CAPTURE_PY_CODE, "<capture>".to_string()) {
+        eprintln!("Failed to create Capture class: {:?}", e);
+        return module;
+    }
+    if let Ok(obj) = scope.globals.get_item("Capture", vm) {
+        module.set_attr("Capture", obj, vm).unwrap();
+    }
+
+    module
+}
@@ -0,0 +1,172 @@
+//! `xos.dialog`: OS file pickers (open / save / folder). Desktop calls block until the user answers;
+//! in the browser `open_file` returns `None` and the picked files arrive through
+//! `Application.on_file_drop` instead (see [`xos_core::dialog`]).
+
+use rustpython_vm::builtins::{PyList, PyModule};
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyRef, PyResult, VirtualMachine};
+use xos_core::dialog::{self, FileDialogOptions, FileFilter};
+
+const DIALOG_PY_CODE: &str = r#"
+def _filters(filters):
+    # {"Audio": ["wav", "mp3"]}, [("Audio", ["wav"])] or just ["wav", "mp3"]
+    if filters is None:
+        return []
+    if isinstance(filters, str):
+        filters = [filters]
+    if isinstance(filters, dict):
+        items = list(filters.items())
+    else:
+        filters = list(filters)
+        if all(isinstance(f, str) for f in filters):
+            items = [("Files", filters)]
+        else:
+            items = [(name, exts) for name, exts in filters]
+    out = []
+    for name, exts in items:
+        if isinstance(exts, str):
+            exts = [exts]
+        out.append([str(name)] + [str(e) for e in exts])
+    return out
+
+def _opt(value):
+    return None if value is None else str(value)
+
+def open_file(filters=None, title=None, directory=None, multiple=False):
+    """Ask for a file to open; returns its path, or None if cancelled. ``multiple=True`` returns a
+    list. In the browser this opens the file picker and returns None / []: the files are copied into
+    the virtual filesystem and delivered to ``Application.on_file_drop``."""
+    import xos
+    paths = xos.dialog._open(_filters(filters), _opt(title), _opt(directory), bool(multiple))
+    if multiple:
+        return paths
+    return paths[0] if paths else None
+
+def save_file(filters=None, title=None, directory=None, name=None):
+    """Ask where to save; returns a path or None if cancelled. In the browser this is a path in the
+    virtual filesystem under the data directory."""
+    import xos
+    return xos.dialog._save(_filters(filters), _opt(title), _opt(directory), _opt(name))
+
+def pick_folder(title=None, directory=None):
+    """Ask for a folder; returns its path or None (always None in the browser and on iOS)."""
+    import xos
+    return xos.dialog._pick_folder(_opt(title), _opt(directory))
+"#;
+
+fn optional_string(args: &FuncArgs, index: usize, vm: &VirtualMachine) -> PyResult<Option<String>> {
+    match args.args.get(index) {
+        Some(obj) if !vm.is_none(obj) => Ok(Some(obj.clone().try_into_value(vm)?)),
+        _ => Ok(None),
+    }
+}
+
+/// `title` / `directory` at `title_index`; when that is past 0, argument 0 holds the filters as
+/// `_filters` above builds them (`[[name, ext, ...], ...]`).
+fn options(
+    args: &FuncArgs,
+    title_index: usize,
+    vm: &VirtualMachine,
+) -> PyResult<FileDialogOptions> {
+    let mut options = FileDialogOptions {
+        title: optional_string(args, title_index, vm)?,
+        directory: optional_string(args, title_index + 1, vm)?.map(Into::into),
+        ..Default::default()
+    };
+    if title_index == 0 {
+        return Ok(options);
+    }
+    let Some(list) = args.args.first().and_then(|f| f.downcast_ref::<PyList>()) else {
+        return Ok(options);
+    };
+    for entry in list.borrow_vec().iter() {
+        let parts = entry
+            .downcast_ref::<PyList>()
+            .ok_or_else(|| vm.new_type_error("bad file filter".to_string()))?;
+        let mut strings = Vec::new();
+        for part in parts.borrow_vec().iter() {
+            strings.push(part.clone().try_into_value::<String>(vm)?);
+        }
+        let Some((name, extensions)) = strings.split_first() else {
+            continue;
+        };
+        options.filters.push(FileFilter {
+            name: name.clone(),
+            extensions: extensions
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect(),
+        });
+    }
+    Ok(options)
+}
+
+fn path_or_none(path: Option<std::path::PathBuf>, vm: &VirtualMachine) -> PyResult {
+    Ok(match path {
+        Some(p) => vm.ctx.new_str(p.to_string_lossy().into_owned()).into(),
+        None => vm.ctx.none(),
+    })
+}
+
+/// xos.dialog._open(filters, title, directory, multiple) - list of picked paths
+fn dialog_open(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let options = options(&args, 1, vm)?;
+    let multiple = match args.args.get(3) {
+        Some(obj) => obj.clone().try_into_value::<bool>(vm)?,
+        None => false,
+    };
+    let paths = if multiple {
+        dialog::open_files(&options)
+    } else {
+        dialog::open_file(&options).into_iter().collect()
+    };
+    let items = paths
+        .into_iter()
+        .map(|p| vm.ctx.new_str(p.to_string_lossy().into_owned()).into())
+        .collect();
+    Ok(vm.ctx.new_list(items).into())
+}
+
+/// xos.dialog._save(filters, title, directory, name) - path or None
+fn dialog_save(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let mut options = options(&args, 1, vm)?;
+    options.file_name = optional_string(&args, 3, vm)?;
+    path_or_none(dialog::save_file(&options), vm)
+}
+
+/// xos.dialog._pick_folder(title, directory) - path or None
+fn dialog_pick_folder(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let options = options(&args, 0, vm)?;
+    path_or_none(dialog::pick_folder(&options), vm)
+}
+
+pub fn make_dialog_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.dialog", vm.ctx.new_dict(), None);
+
+    module
+        .set_attr("_open", vm.new_function("_open", dialog_open), vm)
+        .unwrap();
+    module
+        .set_attr("_save", vm.new_function("_save", dialog_save), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "_pick_folder",
+            vm.new_function("_pick_folder", dialog_pick_folder),
+            vm,
+        )
+        .unwrap();
+
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), DIALOG_PY_CODE, "<dialog>".to_string()) {
+        eprintln!("Failed to create dialog functions: {:?}", e);
+        return module;
+    }
+    for name in ["open_file", "save_file", "pick_folder"] {
+        if let Ok(obj) = scope.globals.get_item(name, vm) {
+            module.set_attr(name, obj, vm).unwrap();
+        }
+    }
+
+    module
+}
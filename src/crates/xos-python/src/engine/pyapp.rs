@@ -1,9 +1,17 @@
+use xos_core::engine::gamepad::{GamepadEvent, GamepadEventKind};
+use xos_core::engine::keyboard::keys::KeyEvent;
 use xos_core::engine::keyboard::shortcuts::ShortcutAction;
+use xos_core::engine::pointer::{GestureEvent, GesturePhase, PointerEvent, PointerPhase};
+use xos_core::engine::profiler;
+use xos_core::engine::windows::open_reserved_window;
 use xos_core::engine::{Application, EngineState, SafeRegionBoundingRectangle, ScrollWheelUnit};
 use crate::engine::py_engine_tls::{CallbackEngineStateGuard, TickEngineStateGuard};
 use rustpython_vm::{
-    builtins::PyBaseExceptionRef, AsObject, Interpreter, PyObjectRef, PyResult, VirtualMachine,
+    builtins::{PyBaseExceptionRef, PyDictRef},
+    AsObject, Interpreter, PyObjectRef, PyResult, VirtualMachine,
 };
+use std::path::PathBuf;
+use std::rc::Rc;
 
 /// Format a Python exception with traceback info
 fn format_python_exception(vm: &VirtualMachine, py_exc: &PyBaseExceptionRef) -> String {
@@ -54,6 +62,12 @@ fn log_py_runtime_error(message: &str) {
     eprintln!("{message}");
 }
 
+/// Per-frame draw hook. Named `on_render` like the other callbacks so scripts that already
+/// define their own `render(...)` helper (with whatever signature) are never called by the engine.
+fn call_render_hook(vm: &VirtualMachine, app: &PyObjectRef) -> PyResult<()> {
+    vm.call_method(app, "on_render", ()).map(|_| ())
+}
+
 fn sync_app_safe_region(
     vm: &VirtualMachine,
     app: &PyObjectRef,
@@ -69,7 +83,16 @@ fn sync_app_safe_region(
     Ok(())
 }
 
-#[derive(Clone, Copy)]
+/// Apply the app's `fixed_hz` (None / 0 → variable ticks) to the engine timestep.
+fn sync_fixed_timestep(vm: &VirtualMachine, app: &PyObjectRef, state: &mut EngineState) {
+    let hz = match vm.get_attribute_opt(app.clone(), "fixed_hz") {
+        Ok(Some(obj)) if !vm.is_none(&obj) => obj.try_into_value::<f64>(vm).ok(),
+        _ => None,
+    };
+    state.timestep.set_hz(hz.map(|h| h as f32));
+}
+
+#[derive(Clone)]
 pub(crate) enum RoutedPyEvent {
     MouseDown,
     MouseUp,
@@ -80,7 +103,26 @@ pub(crate) enum RoutedPyEvent {
         unit: ScrollWheelUnit,
     },
     KeyChar(char),
+    /// Raw press / release (`kind` is `key_down` or `key_up`).
+    Key(KeyEvent),
+    /// `kind` is `pointer_down`, `pointer_move` or `pointer_up`.
+    Pointer(PointerEvent),
+    Gesture(GestureEvent),
+    Gamepad(GamepadEvent),
     Shortcut(ShortcutAction),
+    /// `kind` is `file_hover`, `file_hover_cancel` or `file_drop`.
+    Files {
+        kind: &'static str,
+        paths: Vec<PathBuf>,
+    },
+}
+
+fn path_list(vm: &VirtualMachine, paths: &[PathBuf]) -> PyObjectRef {
+    let items = paths
+        .iter()
+        .map(|p| vm.ctx.new_str(p.to_string_lossy().into_owned()).into())
+        .collect();
+    vm.ctx.new_list(items).into()
 }
 
 fn build_routed_event_dict(
@@ -182,6 +224,30 @@ fn build_routed_event_dict(
             d.set_item("kind", vm.ctx.new_str("key_char").into(), vm)?;
             d.set_item("char", vm.ctx.new_str(ch.to_string()).into(), vm)?;
         }
+        RoutedPyEvent::Key(ev) => {
+            let kind = if ev.pressed { "key_down" } else { "key_up" };
+            d.set_item("kind", vm.ctx.new_str(kind).into(), vm)?;
+            d.set_item("key", vm.ctx.new_str(ev.key).into(), vm)?;
+            d.set_item("code", vm.ctx.new_str(ev.code).into(), vm)?;
+            d.set_item("repeat", vm.ctx.new_bool(ev.repeat).into(), vm)?;
+        }
+        RoutedPyEvent::Pointer(ev) => {
+            let kind = match ev.phase {
+                PointerPhase::Down => "pointer_down",
+                PointerPhase::Move => "pointer_move",
+                PointerPhase::Up | PointerPhase::Cancel => "pointer_up",
+            };
+            d.set_item("kind", vm.ctx.new_str(kind).into(), vm)?;
+            fill_pointer_dict(vm, &d, &ev)?;
+        }
+        RoutedPyEvent::Gesture(g) => {
+            d.set_item("kind", vm.ctx.new_str("gesture").into(), vm)?;
+            fill_gesture_dict(vm, &d, &g)?;
+        }
+        RoutedPyEvent::Gamepad(ev) => {
+            d.set_item("kind", vm.ctx.new_str("gamepad").into(), vm)?;
+            fill_gamepad_dict(vm, &d, &ev)?;
+        }
         RoutedPyEvent::Shortcut(sa) => {
             d.set_item("kind", vm.ctx.new_str("shortcut").into(), vm)?;
             let action = match sa {
@@ -194,10 +260,113 @@ fn build_routed_event_dict(
             };
             d.set_item("action", vm.ctx.new_str(action).into(), vm)?;
         }
+        RoutedPyEvent::Files { kind, paths } => {
+            d.set_item("kind", vm.ctx.new_str(kind).into(), vm)?;
+            d.set_item("paths", path_list(vm, &paths), vm)?;
+        }
     }
     Ok(d.into())
 }
 
+fn fill_pointer_dict(vm: &VirtualMachine, d: &PyDictRef, ev: &PointerEvent) -> PyResult<()> {
+    let phase = match ev.phase {
+        PointerPhase::Down => "down",
+        PointerPhase::Move => "move",
+        PointerPhase::Up => "up",
+        PointerPhase::Cancel => "cancel",
+    };
+    d.set_item("id", vm.ctx.new_int(ev.id).into(), vm)?;
+    d.set_item("type", vm.ctx.new_str(ev.kind.name()).into(), vm)?;
+    d.set_item("phase", vm.ctx.new_str(phase).into(), vm)?;
+    d.set_item("x", vm.ctx.new_float(ev.x as f64).into(), vm)?;
+    d.set_item("y", vm.ctx.new_float(ev.y as f64).into(), vm)?;
+    d.set_item("pressure", vm.ctx.new_float(ev.pressure as f64).into(), vm)?;
+    let button = match ev.button {
+        Some(b) => vm.ctx.new_str(b.name()).into(),
+        None => vm.ctx.none(),
+    };
+    d.set_item("button", button, vm)?;
+    d.set_item("buttons", vm.ctx.new_int(ev.buttons).into(), vm)?;
+    d.set_item("is_primary", vm.ctx.new_bool(ev.is_primary).into(), vm)?;
+    Ok(())
+}
+
+fn fill_gesture_dict(vm: &VirtualMachine, d: &PyDictRef, g: &GestureEvent) -> PyResult<()> {
+    let phase = match g.phase {
+        GesturePhase::Began => "began",
+        GesturePhase::Changed => "changed",
+        GesturePhase::Ended => "ended",
+    };
+    d.set_item("phase", vm.ctx.new_str(phase).into(), vm)?;
+    for (key, value) in [
+        ("x", g.center_x),
+        ("y", g.center_y),
+        ("scale", g.scale),
+        ("rotation", g.rotation),
+        ("delta_scale", g.delta_scale),
+        ("delta_rotation", g.delta_rotation),
+        ("pan_dx", g.pan_dx),
+        ("pan_dy", g.pan_dy),
+    ] {
+        d.set_item(key, vm.ctx.new_float(value as f64).into(), vm)?;
+    }
+    Ok(())
+}
+
+fn fill_gamepad_dict(vm: &VirtualMachine, d: &PyDictRef, ev: &GamepadEvent) -> PyResult<()> {
+    let (kind, button, axis, value) = match &ev.kind {
+        GamepadEventKind::Connected { name, .. } => {
+            d.set_item("name", vm.ctx.new_str(name.as_str()).into(), vm)?;
+            ("connected", None, None, None)
+        }
+        GamepadEventKind::Disconnected => ("disconnected", None, None, None),
+        GamepadEventKind::ButtonDown(b) => ("button_down", Some(*b), None, Some(1.0)),
+        GamepadEventKind::ButtonUp(b) => ("button_up", Some(*b), None, Some(0.0)),
+        GamepadEventKind::ButtonChanged(b, v) => ("button_changed", Some(*b), None, Some(*v)),
+        GamepadEventKind::AxisChanged(a, v) => ("axis", None, Some(*a), Some(*v)),
+    };
+    let opt_str = |s: Option<&'static str>| match s {
+        Some(s) => vm.ctx.new_str(s).into(),
+        None => vm.ctx.none(),
+    };
+    d.set_item("id", vm.ctx.new_int(ev.id).into(), vm)?;
+    d.set_item("type", vm.ctx.new_str(kind).into(), vm)?;
+    d.set_item("button", opt_str(button.map(|b| b.name())), vm)?;
+    d.set_item("axis", opt_str(axis.map(|a| a.name())), vm)?;
+    let value = match value {
+        Some(v) => vm.ctx.new_float(v as f64).into(),
+        None => vm.ctx.none(),
+    };
+    d.set_item("value", value, vm)?;
+    Ok(())
+}
+
+/// Call `app.<method>(event)` through `Application._xos_dispatch_event`, which wraps `fields` in
+/// the matching Python event class, with engine state installed for native `xos.ui` hooks.
+fn call_python_event_handler(
+    vm: &VirtualMachine,
+    app_instance: &PyObjectRef,
+    state: &mut EngineState,
+    method: &str,
+    fields: PyDictRef,
+) {
+    let result = {
+        let _guard = CallbackEngineStateGuard::install(state);
+        vm.call_method(
+            app_instance,
+            "_xos_dispatch_event",
+            (vm.ctx.new_str(method), fields),
+        )
+    };
+    if let Err(e) = result {
+        log_py_runtime_error(&format!(
+            "Python {} error:\n{}",
+            method,
+            format_python_exception(vm, &e)
+        ));
+    }
+}
+
 #[inline]
 fn sync_app_mouse_from_engine(
     vm: &VirtualMachine,
@@ -848,6 +1017,73 @@ class Frame:
             if bound:
                 xos.frame._end_standalone()
 
+class Keyboard:
+    """Held-key table read live from the engine (``app.keyboard.is_down("w")``).
+
+    Names may be logical (``"w"``, ``"space"``, ``"shift"``, ``"arrowleft"``) or physical
+    (``"KeyW"``, ``"ShiftLeft"``). Outside ``run()`` no key is ever held.
+    """
+
+    def is_down(self, key):
+        import xos
+        return bool(xos._key_is_down(str(key)))
+
+    @property
+    def keys_down(self):
+        """Logical names of the held keys, in press order."""
+        import xos
+        return [k for k, _ in xos._keys_down()]
+
+
+class PointerEvent:
+    """One mouse / touch / pen transition passed to ``on_pointer_down`` / ``on_pointer_move`` / ``on_pointer_up``.
+
+    ``id`` is stable while the contact is held (``0`` is the mouse), ``type`` is ``"mouse"`` / ``"touch"`` /
+    ``"pen"``, ``phase`` is ``"down"`` / ``"move"`` / ``"up"`` / ``"cancel"``, ``x`` / ``y`` are frame px,
+    ``pressure`` is ``0..1``, ``button`` names the changed button (or ``None``), ``buttons`` is the held
+    bitmask (left 1, right 2, middle 4, back 8, forward 16), and ``is_primary`` marks the first contact.
+    """
+
+    def __init__(self, fields):
+        self.__dict__.update(fields)
+
+    def __repr__(self):
+        return "PointerEvent(id=%d, type=%r, phase=%r, x=%.1f, y=%.1f)" % (
+            self.id, self.type, self.phase, self.x, self.y)
+
+
+class GestureEvent:
+    """Two-finger pinch / rotate / pan passed to ``on_gesture``.
+
+    ``phase`` is ``"began"`` / ``"changed"`` / ``"ended"``; ``x`` / ``y`` is the center in frame px;
+    ``scale`` and ``rotation`` (radians) are cumulative since ``began``, ``delta_scale`` /
+    ``delta_rotation`` / ``pan_dx`` / ``pan_dy`` since the previous update.
+    """
+
+    def __init__(self, fields):
+        self.__dict__.update(fields)
+
+    def __repr__(self):
+        return "GestureEvent(phase=%r, scale=%.3f, rotation=%.3f)" % (
+            self.phase, self.scale, self.rotation)
+
+
+class GamepadEvent:
+    """Controller change passed to ``on_gamepad``.
+
+    ``id`` matches ``xos.gamepad.get(id)``; ``type`` is ``"connected"`` (with ``name``),
+    ``"disconnected"``, ``"button_down"`` / ``"button_up"`` (``button`` e.g. ``"south"``) or ``"axis"``
+    (``axis`` e.g. ``"left_x"``); ``value`` is the new button / axis value.
+    """
+
+    def __init__(self, fields):
+        self.__dict__.update(fields)
+
+    def __repr__(self):
+        return "GamepadEvent(id=%d, type=%r, button=%r, axis=%r, value=%r)" % (
+            self.id, self.type, self.button, self.axis, self.value)
+
+
 class Application:
     """Base class for xos applications. Extend this class and implement __init__() and tick().
 
@@ -858,7 +1094,13 @@ class Application:
     clears it only after your handler returns, so every component sees the same event in one call.
     Pointer kinds ``mouse_down``, ``mouse_up``, and ``mouse_move`` also include ``x``, ``y`` (frame px),
     ``button`` (``\"left\"`` / ``\"right\"``), ``is_left``, and ``is_right`` for parity with host events.
-    Kinds include mouse, scroll, ``key_char``, and desktop ``shortcut`` (e.g. Cmd/Ctrl+C/V/X/A).
+    ``pointer_down`` / ``pointer_move`` / ``pointer_up`` carry every contact (see ``PointerEvent``) and
+    ``gesture`` carries two-finger pinch / rotate (see ``GestureEvent``); ``gamepad`` carries controller
+    changes (see ``GamepadEvent``; poll held state with ``xos.gamepad``).
+    Kinds include mouse, scroll, ``key_char``, ``key_down`` / ``key_up`` (``key``, ``code``, ``repeat``),
+    and desktop ``shortcut`` (e.g. Cmd/Ctrl+C/V/X/A).
+    ``self.keyboard`` starts as a ``Keyboard`` (held keys: ``self.keyboard.is_down("w")``); apps that assign
+    ``xos.ui.onscreen_keyboard()`` keep ``is_down`` on that object too.
     Conventional order is ``self.keyboard.on_events(self)`` then ``self.text.on_events(self)`` for
     pointer, ``key_char``, and shortcuts alike — no special cases per event type are required.
     """
@@ -874,6 +1116,9 @@ class Application:
         self.fps = 0.0  # Frames per second derived from timestep
         self.dt = 0.0  # Last frame delta time in seconds (same source as engine timestep)
         self.t = 0  # Tick index: 0 on first tick(), then increments after each tick completes
+        # Set to e.g. 60 for fixed-rate ticks; None ticks once per frame with a variable dt.
+        self.fixed_hz = None
+        self.alpha = 1.0  # Interpolation factor for on_render() in fixed-timestep mode
         # F3 scale as percent/100 (0.25..5.0 for 25–500%); default 1.0 at 100%.
         # Engine syncs `xos_scale` each tick.
         self.xos_scale = 1.0
@@ -883,6 +1128,9 @@ class Application:
         self._xos_ticks_completed = 0
         # Full viewport until the engine replaces this (see Rust ``sync_app_safe_region``).
         self.safe_region = SafeRegion(0.0, 0.0, 1.0, 1.0)
+        self.keyboard = Keyboard()
+        import xos
+        self.capture = xos.capture.Capture()  # app.capture.start("out.mp4") / .stop()
         if headless is not None:
             self.headless = bool(headless)
 
@@ -1016,10 +1264,97 @@ class Application:
         """Called on mouse wheel / trackpad scroll. Override (optional)."""
         pass
     
+    def on_pointer_down(self, event):
+        """Called when any mouse button, finger or pen goes down (``event`` is a ``PointerEvent``). Override (optional)."""
+        pass
+
+    def on_pointer_move(self, event):
+        """Called when a pointer moves, including hover for the mouse. Override (optional)."""
+        pass
+
+    def on_pointer_up(self, event):
+        """Called when a pointer lifts or is cancelled (``event.phase == "cancel"``). Override (optional)."""
+        pass
+
+    def on_gesture(self, event):
+        """Called while two touches pinch / rotate / pan (``event`` is a ``GestureEvent``). Override (optional)."""
+        pass
+
+    def on_gamepad(self, event):
+        """Called when a controller connects, disconnects, presses / releases a button or moves a stick
+        (``event`` is a ``GamepadEvent``). Override (optional)."""
+        pass
+
+    def on_file_hover(self, paths):
+        """Called when files are dragged over the window (``paths`` is empty in the browser, which
+        only reveals names on drop). Override (optional)."""
+        pass
+
+    def on_file_hover_cancel(self):
+        """Called when a file drag leaves the window without dropping. Override (optional)."""
+        pass
+
+    def on_file_drop(self, paths):
+        """Called with the paths of files dropped on the window, or picked with ``xos.dialog.open_file``
+        in the browser (copied into the virtual filesystem first). Override (optional)."""
+        pass
+
+    def _xos_dispatch_event(self, method, fields):
+        cls = {"on_gesture": GestureEvent, "on_gamepad": GamepadEvent}.get(method, PointerEvent)
+        getattr(self, method)(cls(fields))
+
+    def on_key_down(self, key, code, repeat):
+        """Called on a raw key press (``key`` logical e.g. ``"w"``, ``code`` physical e.g. ``"KeyW"``,
+        ``repeat`` for auto-repeat). Override (optional)."""
+        pass
+
+    def on_key_up(self, key, code):
+        """Called when a held key is released (also for every held key on focus loss). Override (optional)."""
+        pass
+    
     def on_screen_size_change(self, width, height):
         """Called when screen size changes. Override this method (optional)."""
         pass
+
+    def on_render(self):
+        """Called once per displayed frame after that frame's ticks (optional).
+
+        With ``self.fixed_hz`` set, ``tick()`` runs at that constant rate (``self.dt`` is the fixed
+        step) and a frame may see zero or several ticks; draw here blending the last two simulation
+        states by ``self.alpha`` (0..1).
+        """
+        pass
     
+    def open_window(self, app, title=None, width=640, height=480, monitor=None, x=None, y=None,
+                    fullscreen=False, decorations=True, always_on_top=False):
+        """Open another OS window driven by ``app`` (a second ``xos.Application``) with its own
+        ``frame``, mouse, keyboard and callbacks; returns a window id. ``monitor`` indexes
+        ``xos.system.monitors`` (centered there, or fullscreen on it); ``x``/``y`` place it
+        explicitly. The window appears on the next frame; only the desktop window host opens it."""
+        import xos
+        return xos._open_window(
+            app,
+            type(app).__name__ if title is None else str(title),
+            int(width),
+            int(height),
+            None if monitor is None else int(monitor),
+            None if x is None else int(x),
+            None if y is None else int(y),
+            bool(fullscreen),
+            bool(decorations),
+            bool(always_on_top),
+        )
+
+    def close_window(self, window_id):
+        """Close a window from ``open_window`` (no-op if it is already closed)."""
+        import xos
+        xos._close_window(int(window_id))
+
+    def window_is_open(self, window_id):
+        """False once the window was closed, by the app or by the user."""
+        import xos
+        return xos._window_is_open(int(window_id))
+
     def run(self):
         """Run the application with the xos engine."""
         # Store self in builtins so Rust can find it from any scope
@@ -1029,7 +1364,8 @@ class Application:
 
 /// PyApp wraps a Python Application instance and implements the Rust Application trait
 pub struct PyApp {
-    interpreter: Interpreter,
+    /// Shared with the apps of windows opened through `Application.open_window`.
+    interpreter: Rc<Interpreter>,
     app_instance: Option<PyObjectRef>,
     /// Number of `tick()` calls that have fully finished (starts at 0; incremented after each tick).
     ticks_completed: u64,
@@ -1037,18 +1373,93 @@ pub struct PyApp {
 
 impl PyApp {
     pub fn new(interpreter: Interpreter, app_instance: PyObjectRef) -> Self {
+        Self::shared(Rc::new(interpreter), app_instance)
+    }
+
+    fn shared(interpreter: Rc<Interpreter>, app_instance: PyObjectRef) -> Self {
         Self {
             interpreter,
             app_instance: Some(app_instance),
             ticks_completed: 0,
         }
     }
+
+    /// Hand the interpreter back once the app is done (the `xos test` runner reuses it); `None`
+    /// while a window app opened from this one is still alive.
+    pub fn into_interpreter(self) -> Option<Interpreter> {
+        Rc::try_unwrap(self.interpreter).ok()
+    }
+
+    /// Give windows requested with `Application.open_window` their own `PyApp` on this interpreter.
+    fn open_pending_windows(&self) {
+        for pending in crate::windows::take_pending_windows() {
+            let app = PyApp::shared(self.interpreter.clone(), pending.app);
+            open_reserved_window(pending.handle, pending.spec, Box::new(app));
+        }
+    }
+
+    /// Deliver a pointer transition to `app.<method>(event)` and to `on_events` widgets.
+    fn dispatch_pointer(&mut self, state: &mut EngineState, event: &PointerEvent, method: &str) {
+        let _scope = profiler::scope_with(|| format!("py:{method}"));
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let fields = vm.ctx.new_dict();
+                if fill_pointer_dict(vm, &fields, event).is_ok() {
+                    call_python_event_handler(vm, app_instance, state, method, fields);
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Pointer(*event),
+                );
+            });
+        }
+    }
+
+    /// `app.on_<kind>(paths)` (no argument for `file_hover_cancel`), then `on_events` widgets.
+    fn dispatch_file_event(
+        &mut self,
+        state: &mut EngineState,
+        kind: &'static str,
+        paths: &[PathBuf],
+    ) {
+        let _scope = profiler::scope_with(|| format!("py:on_{kind}"));
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let method = format!("on_{kind}");
+                let result = {
+                    let _guard = CallbackEngineStateGuard::install(state);
+                    if kind == "file_hover_cancel" {
+                        vm.call_method(app_instance, &method, ())
+                    } else {
+                        vm.call_method(app_instance, &method, (path_list(vm, paths),))
+                    }
+                };
+                if let Err(e) = result {
+                    log_py_runtime_error(&format!(
+                        "Python {method} error:\n{}",
+                        format_python_exception(vm, &e)
+                    ));
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Files {
+                        kind,
+                        paths: paths.to_vec(),
+                    },
+                );
+            });
+        }
+    }
 }
 
 impl Application for PyApp {
     fn setup(&mut self, state: &mut EngineState) -> Result<(), String> {
         if let Some(ref app_instance) = self.app_instance {
-            self.interpreter.enter(|vm| {
+            let bound = self.interpreter.enter(|vm| {
                 // Create Python frame object from engine state
                 let frame_dict = crate::engine::py_bindings::create_py_frame_state(
                     vm,
@@ -1126,15 +1537,19 @@ impl Application for PyApp {
 
                 sync_app_safe_region(vm, app_instance, &state.frame.safe_region_boundaries)
                     .map_err(|e| format!("Failed to sync safe_region: {:?}", e))?;
+                sync_fixed_timestep(vm, app_instance, state);
 
                 Ok(())
-            })
+            });
+            self.open_pending_windows();
+            bound
         } else {
             Err("No Python app instance".to_string())
         }
     }
 
     fn tick(&mut self, state: &mut EngineState) {
+        xos_core::profile_scope!("py:tick");
         if let Some(app_instance) = self.app_instance.clone() {
             // Set the frame buffer context for the rasterizer
             let shape = state.frame.shape();
@@ -1178,6 +1593,8 @@ Call super().__init__() in your app __init__ before using tick().",
                     let _ = app_instance.set_attr("dt", vm.ctx.new_float(timestep), vm);
                     let _ = app_instance.set_attr("fps", vm.ctx.new_float(1.0 / timestep), vm);
 
+                    sync_fixed_timestep(vm, &app_instance, state);
+
                     // Tick counter: value during tick() is N ticks completed so far (0 on first tick).
                     let _ = app_instance.set_attr("t", vm.ctx.new_int(tick_index as usize), vm);
                     let _ = app_instance.set_attr("xos_scale", vm.ctx.new_float(state.ui_scale_percent as f64 / 100.0), vm);
@@ -1205,6 +1622,7 @@ Call super().__init__() in your app __init__ before using tick().",
             } else {
                 self.ticks_completed = self.ticks_completed.saturating_add(1);
             }
+            self.open_pending_windows();
 
             // Clear the frame buffer context after tick
             crate::rasterizer::clear_frame_buffer_context();
@@ -1212,6 +1630,7 @@ Call super().__init__() in your app __init__ before using tick().",
     }
 
     fn on_mouse_down(&mut self, state: &mut EngineState) {
+        xos_core::profile_scope!("py:on_mouse_down");
         let shape = state.frame.shape();
         let _keyboard_hit = state.keyboard.onscreen.on_mouse_down(
             state.mouse.x,
@@ -1244,6 +1663,7 @@ Call super().__init__() in your app __init__ before using tick().",
     }
 
     fn on_mouse_up(&mut self, state: &mut EngineState) {
+        xos_core::profile_scope!("py:on_mouse_up");
         state.keyboard.onscreen.on_mouse_up();
         if let Some(ref app_instance) = self.app_instance {
             self.interpreter.enter(|vm| {
@@ -1271,6 +1691,7 @@ Call super().__init__() in your app __init__ before using tick().",
     }
 
     fn on_mouse_move(&mut self, state: &mut EngineState) {
+        xos_core::profile_scope!("py:on_mouse_move");
         if let Some(ref app_instance) = self.app_instance {
             self.interpreter.enter(|vm| {
                 let x = state.mouse.x;
@@ -1296,6 +1717,7 @@ Call super().__init__() in your app __init__ before using tick().",
     }
 
     fn on_scroll(&mut self, state: &mut EngineState, dx: f32, dy: f32, unit: ScrollWheelUnit) {
+        xos_core::profile_scope!("py:on_scroll");
         if let Some(ref app_instance) = self.app_instance {
             self.interpreter.enter(|vm| {
                 let mouse_dict = vm.ctx.new_dict();
@@ -1331,6 +1753,116 @@ Call super().__init__() in your app __init__ before using tick().",
         }
     }
 
+    fn on_pointer_down(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.dispatch_pointer(state, event, "on_pointer_down");
+    }
+
+    fn on_pointer_move(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.dispatch_pointer(state, event, "on_pointer_move");
+    }
+
+    fn on_pointer_up(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        self.dispatch_pointer(state, event, "on_pointer_up");
+    }
+
+    fn on_gesture(&mut self, state: &mut EngineState, gesture: &GestureEvent) {
+        xos_core::profile_scope!("py:on_gesture");
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let fields = vm.ctx.new_dict();
+                if fill_gesture_dict(vm, &fields, gesture).is_ok() {
+                    call_python_event_handler(vm, app_instance, state, "on_gesture", fields);
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Gesture(*gesture),
+                );
+            });
+        }
+    }
+
+    fn on_gamepad(&mut self, state: &mut EngineState, event: &GamepadEvent) {
+        xos_core::profile_scope!("py:on_gamepad");
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let fields = vm.ctx.new_dict();
+                if fill_gamepad_dict(vm, &fields, event).is_ok() {
+                    call_python_event_handler(vm, app_instance, state, "on_gamepad", fields);
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Gamepad(event.clone()),
+                );
+            });
+        }
+    }
+
+    fn on_file_hover(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        self.dispatch_file_event(state, "file_hover", paths);
+    }
+
+    fn on_file_hover_cancel(&mut self, state: &mut EngineState) {
+        self.dispatch_file_event(state, "file_hover_cancel", &[]);
+    }
+
+    fn on_file_drop(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        self.dispatch_file_event(state, "file_drop", paths);
+    }
+
+    fn on_key_down(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        xos_core::profile_scope!("py:on_key_down");
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let args = (event.key.clone(), event.code.clone(), event.repeat);
+                let result = {
+                    let _guard = CallbackEngineStateGuard::install(state);
+                    vm.call_method(app_instance, "on_key_down", args)
+                };
+                if let Err(e) = result {
+                    log_py_runtime_error(&format!(
+                        "Python on_key_down error:\n{}",
+                        format_python_exception(vm, &e)
+                    ));
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Key(event.clone()),
+                );
+            });
+        }
+    }
+
+    fn on_key_up(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        xos_core::profile_scope!("py:on_key_up");
+        if let Some(ref app_instance) = self.app_instance {
+            self.interpreter.enter(|vm| {
+                let args = (event.key.clone(), event.code.clone());
+                let result = {
+                    let _guard = CallbackEngineStateGuard::install(state);
+                    vm.call_method(app_instance, "on_key_up", args)
+                };
+                if let Err(e) = result {
+                    log_py_runtime_error(&format!(
+                        "Python on_key_up error:\n{}",
+                        format_python_exception(vm, &e)
+                    ));
+                }
+                try_dispatch_python_on_events(
+                    vm,
+                    app_instance,
+                    state,
+                    RoutedPyEvent::Key(event.clone()),
+                );
+            });
+        }
+    }
+
     fn on_key_shortcut(&mut self, state: &mut EngineState, shortcut: ShortcutAction) {
         if let Some(ref app_instance) = self.app_instance {
             self.interpreter.enter(|vm| {
@@ -1345,6 +1877,7 @@ Call super().__init__() in your app __init__ before using tick().",
     }
 
     fn on_screen_size_change(&mut self, state: &mut EngineState, width: u32, height: u32) {
+        xos_core::profile_scope!("py:on_screen_size_change");
         if let Some(ref app_instance) = self.app_instance {
             // Set the frame buffer context so Python can write to it
             let shape = state.frame.shape();
@@ -1381,4 +1914,95 @@ Call super().__init__() in your app __init__ before using tick().",
             crate::rasterizer::clear_frame_buffer_context();
         }
     }
+
+    fn render(&mut self, state: &mut EngineState) {
+        xos_core::profile_scope!("py:render");
+        let Some(app_instance) = self.app_instance.clone() else {
+            return;
+        };
+        let shape = state.frame.shape();
+        let width = shape[1];
+        let height = shape[0];
+        let buffer = state.frame.buffer_mut();
+        crate::rasterizer::set_frame_buffer_context(buffer, width, height);
+
+        self.interpreter.enter(|vm| {
+            if let Ok(Some(frame_obj)) = vm.get_attribute_opt(app_instance.clone(), "frame") {
+                let _ = crate::engine::py_bindings::update_py_frame_state(
+                    vm,
+                    frame_obj,
+                    &mut state.frame,
+                );
+            }
+            let _ =
+                app_instance.set_attr("alpha", vm.ctx.new_float(state.timestep.alpha as f64), vm);
+            let _tls_guard = TickEngineStateGuard::install(state);
+            if let Err(e) = call_render_hook(vm, &app_instance) {
+                log_py_runtime_error(&format!(
+                    "Python on_render error:\n{}",
+                    format_python_exception(vm, &e)
+                ));
+            }
+        });
+
+        crate::rasterizer::clear_frame_buffer_context();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustpython_vm::Interpreter;
+
+    #[test]
+    fn render_hook_skips_apps_own_render_helpers() {
+        Interpreter::without_stdlib(Default::default()).enter(|vm| {
+            let scope = vm.new_scope_with_builtins();
+            vm.run_code_string(
+                scope.clone(),
+                APPLICATION_CLASS_CODE,
+                "<xos_module>".to_owned(),
+            )
+            .unwrap();
+            let source = r#"
+class Waveform(Application):
+    def __init__(self):
+        self.frames = 0
+        self.helper_calls = 0
+
+    def render(self, width, height):
+        self.helper_calls += 1
+
+    def on_render(self):
+        self.frames += 1
+
+class Menu(Application):
+    def __init__(self):
+        self.helper_calls = 0
+
+    def render(self, app):
+        self.helper_calls += 1
+
+waveform = Waveform()
+menu = Menu()
+"#;
+            vm.run_code_string(scope.clone(), source, "<test>".to_owned())
+                .unwrap();
+            let attr = |name: &str, field: &'static str| -> i64 {
+                let obj = scope.globals.get_item(name, vm).unwrap();
+                obj.get_attr(field, vm).unwrap().try_into_value(vm).unwrap()
+            };
+
+            let waveform = scope.globals.get_item("waveform", vm).unwrap();
+            let menu = scope.globals.get_item("menu", vm).unwrap();
+            for _ in 0..3 {
+                call_render_hook(vm, &waveform).unwrap();
+                // Falls back to the base-class no-op instead of calling render(self, app).
+                call_render_hook(vm, &menu).unwrap();
+            }
+            assert_eq!(attr("waveform", "frames"), 3);
+            assert_eq!(attr("waveform", "helper_calls"), 0);
+            assert_eq!(attr("menu", "helper_calls"), 0);
+        });
+    }
 }
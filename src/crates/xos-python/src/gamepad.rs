@@ -0,0 +1,390 @@
+//! `xos.gamepad` — controller state from `EngineState::gamepads` plus virtual pads for headless runs.
+//!
+//! Reads go through the engine installed for the current `tick()` / input callback; outside the
+//! engine no pad is connected. Virtual pad input is queued and shows up on the next engine tick.
+
+use rustpython_vm::builtins::PyModule;
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyObjectRef, PyRef, PyResult, VirtualMachine};
+use xos_core::engine::gamepad::{Gamepad, GamepadAxis, GamepadButton, Gamepads, VirtualGamepad};
+
+const GAMEPAD_PY_CODE: &str = r#"
+class Gamepad:
+    """Live view of one controller (``xos.gamepad.get()``). Buttons use standard-layout names
+    (``"south"``, ``"dpad_up"``, ``"left_trigger"``, …) or aliases (``"a"``, ``"cross"``, ``"lb"``, ``"r2"``).
+    Sticks are ``-1..1`` with +y down."""
+
+    def __init__(self, id):
+        self.id = int(id)
+
+    def _snap(self):
+        import xos
+        return xos.gamepad._state(self.id)
+
+    @property
+    def connected(self):
+        s = self._snap()
+        return bool(s and s["connected"])
+
+    @property
+    def name(self):
+        s = self._snap()
+        return s["name"] if s else ""
+
+    @property
+    def is_virtual(self):
+        s = self._snap()
+        return bool(s and s["is_virtual"])
+
+    def _has(self, key, button):
+        import xos
+        s = self._snap()
+        return bool(s) and xos.gamepad._button_name(button) in s[key]
+
+    def is_down(self, button):
+        return self._has("down", button)
+
+    def just_pressed(self, button):
+        """Went down since the previous tick."""
+        return self._has("pressed", button)
+
+    def just_released(self, button):
+        return self._has("released", button)
+
+    def value(self, button):
+        """Analog value ``0..1`` (triggers); digital buttons are ``0`` or ``1``."""
+        import xos
+        s = self._snap()
+        return s["buttons"][xos.gamepad._button_name(button)] if s else 0.0
+
+    def axis(self, axis):
+        """Raw axis ``-1..1`` (``"left_x"``, ``"left_y"``, ``"right_x"``, ``"right_y"``), no deadzone."""
+        import xos
+        s = self._snap()
+        return s["axes"][xos.gamepad._axis_name(axis)] if s else 0.0
+
+    @property
+    def left_stick(self):
+        """``(x, y)`` with the radial deadzone applied."""
+        s = self._snap()
+        return s["left_stick"] if s else (0.0, 0.0)
+
+    @property
+    def right_stick(self):
+        s = self._snap()
+        return s["right_stick"] if s else (0.0, 0.0)
+
+    @property
+    def buttons_down(self):
+        s = self._snap()
+        return list(s["down"]) if s else []
+
+    def rumble(self, strong=1.0, weak=None, duration=0.2):
+        """Vibrate for ``duration`` seconds (motor strengths ``0..1``; ``weak`` defaults to ``strong``).
+        Returns ``False`` when the pad has no motors or isn't connected."""
+        import xos
+        if weak is None:
+            weak = strong
+        return xos.gamepad._rumble(self.id, float(strong), float(weak), int(max(0.0, duration) * 1000))
+
+    def __repr__(self):
+        return "Gamepad(id=%d, name=%r, connected=%r)" % (self.id, self.name, self.connected)
+
+
+class VirtualGamepad(Gamepad):
+    """Software controller for headless runs and tests (``xos.gamepad.virtual()``). Input set here
+    reaches ``app.on_gamepad`` and ``xos.gamepad`` state on the next engine tick, like real hardware."""
+
+    def press(self, button):
+        self.set_button(button, 1.0)
+
+    def release(self, button):
+        self.set_button(button, 0.0)
+
+    def set_button(self, button, value):
+        import xos
+        xos.gamepad._virtual_button(self.id, button, float(value))
+
+    def set_axis(self, axis, value):
+        import xos
+        xos.gamepad._virtual_axis(self.id, axis, float(value))
+
+    def set_stick(self, side, x, y):
+        """``side`` is ``"left"`` or ``"right"``."""
+        prefix = "left" if str(side).lower().startswith("l") else "right"
+        self.set_axis(prefix + "_x", x)
+        self.set_axis(prefix + "_y", y)
+
+    def disconnect(self):
+        import xos
+        xos.gamepad._virtual_disconnect(self.id)
+
+
+def gamepads():
+    """Connected controllers, in connection order."""
+    import xos
+    return [Gamepad(i) for i in xos.gamepad._ids()]
+
+
+def get(index=0):
+    """The ``index``-th connected controller (``0`` = player one), or ``None``."""
+    pads = gamepads()
+    return pads[index] if 0 <= index < len(pads) else None
+
+
+def virtual(name="Virtual Gamepad"):
+    """Plug in a ``VirtualGamepad``; it appears among ``gamepads()`` from the next tick."""
+    import xos
+    return VirtualGamepad(xos.gamepad._virtual_connect(str(name)))
+"#;
+
+/// Run `f` against the engine's gamepad table (input callback or tick); `None` outside the engine.
+fn with_engine_gamepads<T>(f: impl Fn(&mut Gamepads) -> T) -> Option<T> {
+    crate::engine::py_engine_tls::with_callback_engine_state_mut(|s| f(&mut s.gamepads)).or_else(
+        || crate::engine::py_engine_tls::with_tick_engine_state_mut(|s| f(&mut s.gamepads)),
+    )
+}
+
+fn arg<T: rustpython_vm::TryFromObject>(
+    args: &FuncArgs,
+    index: usize,
+    usage: &str,
+    vm: &VirtualMachine,
+) -> PyResult<T> {
+    args.args
+        .get(index)
+        .ok_or_else(|| vm.new_type_error(usage.to_string()))?
+        .clone()
+        .try_into_value(vm)
+}
+
+fn parse_button(name: &str, vm: &VirtualMachine) -> PyResult<GamepadButton> {
+    GamepadButton::from_name(name).ok_or_else(|| {
+        let known: Vec<&str> = GamepadButton::ALL.iter().map(|b| b.name()).collect();
+        vm.new_value_error(format!(
+            "unknown gamepad button '{}' (expect {} or a/b/x/y, lb/rb, lt/rt, up/down/left/right)",
+            name,
+            known.join(" | ")
+        ))
+    })
+}
+
+fn parse_axis(name: &str, vm: &VirtualMachine) -> PyResult<GamepadAxis> {
+    GamepadAxis::from_name(name).ok_or_else(|| {
+        vm.new_value_error(format!(
+            "unknown gamepad axis '{}' (expect left_x | left_y | right_x | right_y)",
+            name
+        ))
+    })
+}
+
+fn virtual_pad(args: &FuncArgs, usage: &str, vm: &VirtualMachine) -> PyResult<VirtualGamepad> {
+    let id: u32 = arg(args, 0, usage, vm)?;
+    VirtualGamepad::from_id(id)
+        .ok_or_else(|| vm.new_value_error(format!("{} is not a virtual gamepad id", id)))
+}
+
+fn pair(vm: &VirtualMachine, (x, y): (f32, f32)) -> PyObjectRef {
+    vm.ctx
+        .new_tuple(vec![
+            vm.ctx.new_float(x as f64).into(),
+            vm.ctx.new_float(y as f64).into(),
+        ])
+        .into()
+}
+
+fn names(
+    vm: &VirtualMachine,
+    pad: &Gamepad,
+    pick: impl Fn(&Gamepad, GamepadButton) -> bool,
+) -> PyObjectRef {
+    let items = GamepadButton::ALL
+        .into_iter()
+        .filter(|b| pick(pad, *b))
+        .map(|b| vm.ctx.new_str(b.name()).into())
+        .collect();
+    vm.ctx.new_list(items).into()
+}
+
+fn gamepad_snapshot(vm: &VirtualMachine, pad: &Gamepad) -> PyResult {
+    let d = vm.ctx.new_dict();
+    d.set_item("id", vm.ctx.new_int(pad.id).into(), vm)?;
+    d.set_item("name", vm.ctx.new_str(pad.name.as_str()).into(), vm)?;
+    d.set_item("connected", vm.ctx.new_bool(pad.connected).into(), vm)?;
+    d.set_item("is_virtual", vm.ctx.new_bool(pad.is_virtual).into(), vm)?;
+    d.set_item(
+        "supports_rumble",
+        vm.ctx.new_bool(pad.supports_rumble).into(),
+        vm,
+    )?;
+    let buttons = vm.ctx.new_dict();
+    for b in GamepadButton::ALL {
+        buttons.set_item(b.name(), vm.ctx.new_float(pad.value(b) as f64).into(), vm)?;
+    }
+    d.set_item("buttons", buttons.into(), vm)?;
+    let axes = vm.ctx.new_dict();
+    for a in GamepadAxis::ALL {
+        axes.set_item(a.name(), vm.ctx.new_float(pad.axis(a) as f64).into(), vm)?;
+    }
+    d.set_item("axes", axes.into(), vm)?;
+    d.set_item("down", names(vm, pad, Gamepad::is_down), vm)?;
+    d.set_item("pressed", names(vm, pad, Gamepad::just_pressed), vm)?;
+    d.set_item("released", names(vm, pad, Gamepad::just_released), vm)?;
+    d.set_item("left_stick", pair(vm, pad.left_stick()), vm)?;
+    d.set_item("right_stick", pair(vm, pad.right_stick()), vm)?;
+    Ok(d.into())
+}
+
+/// xos.gamepad._ids() - connected pad ids in connection order
+fn gamepad_ids(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let ids = with_engine_gamepads(|pads| pads.connected().map(|p| p.id).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let items = ids
+        .into_iter()
+        .map(|id| vm.ctx.new_int(id).into())
+        .collect();
+    Ok(vm.ctx.new_list(items).into())
+}
+
+/// xos.gamepad._state(id) - snapshot dict backing `Gamepad`, or None for an unknown pad
+fn gamepad_state(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let id: u32 = arg(&args, 0, "_state(id)", vm)?;
+    match with_engine_gamepads(|pads| pads.get(id).cloned()).flatten() {
+        Some(pad) => gamepad_snapshot(vm, &pad),
+        None => Ok(vm.ctx.none()),
+    }
+}
+
+/// xos.gamepad._button_name(name) - canonical button name (raises ValueError for unknown names)
+fn gamepad_button_name(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let name: String = arg(&args, 0, "_button_name(name)", vm)?;
+    Ok(vm.ctx.new_str(parse_button(&name, vm)?.name()).into())
+}
+
+/// xos.gamepad._axis_name(name) - canonical axis name
+fn gamepad_axis_name(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let name: String = arg(&args, 0, "_axis_name(name)", vm)?;
+    Ok(vm.ctx.new_str(parse_axis(&name, vm)?.name()).into())
+}
+
+/// xos.gamepad._rumble(id, strong, weak, duration_ms) - queue force feedback; False if unsupported
+fn gamepad_rumble(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let usage = "_rumble(id, strong, weak, duration_ms)";
+    let id: u32 = arg(&args, 0, usage, vm)?;
+    let strong: f64 = arg(&args, 1, usage, vm)?;
+    let weak: f64 = arg(&args, 2, usage, vm)?;
+    let duration_ms: u32 = arg(&args, 3, usage, vm)?;
+    let ok = with_engine_gamepads(|pads| pads.rumble(id, strong as f32, weak as f32, duration_ms))
+        .unwrap_or(false);
+    Ok(vm.ctx.new_bool(ok).into())
+}
+
+/// xos.gamepad._virtual_connect(name) - plug in a virtual pad and return its id
+fn gamepad_virtual_connect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let name: String = arg(&args, 0, "_virtual_connect(name)", vm)?;
+    Ok(vm.ctx.new_int(VirtualGamepad::connect(&name).id()).into())
+}
+
+/// xos.gamepad._virtual_button(id, name, value)
+fn gamepad_virtual_button(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let usage = "_virtual_button(id, button, value)";
+    let pad = virtual_pad(&args, usage, vm)?;
+    let name: String = arg(&args, 1, usage, vm)?;
+    let value: f64 = arg(&args, 2, usage, vm)?;
+    pad.set_button(parse_button(&name, vm)?, value as f32);
+    Ok(vm.ctx.none())
+}
+
+/// xos.gamepad._virtual_axis(id, name, value)
+fn gamepad_virtual_axis(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let usage = "_virtual_axis(id, axis, value)";
+    let pad = virtual_pad(&args, usage, vm)?;
+    let name: String = arg(&args, 1, usage, vm)?;
+    let value: f64 = arg(&args, 2, usage, vm)?;
+    pad.set_axis(parse_axis(&name, vm)?, value as f32);
+    Ok(vm.ctx.none())
+}
+
+/// xos.gamepad._virtual_disconnect(id)
+fn gamepad_virtual_disconnect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    virtual_pad(&args, "_virtual_disconnect(id)", vm)?.disconnect();
+    Ok(vm.ctx.none())
+}
+
+/// Create the gamepad module
+pub fn make_gamepad_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.gamepad", vm.ctx.new_dict(), None);
+
+    // Internal functions used by the Python classes below
+    module
+        .set_attr("_ids", vm.new_function("_ids", gamepad_ids), vm)
+        .unwrap();
+    module
+        .set_attr("_state", vm.new_function("_state", gamepad_state), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "_button_name",
+            vm.new_function("_button_name", gamepad_button_name),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "_axis_name",
+            vm.new_function("_axis_name", gamepad_axis_name),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("_rumble", vm.new_function("_rumble", gamepad_rumble), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "_virtual_connect",
+            vm.new_function("_virtual_connect", gamepad_virtual_connect),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "_virtual_button",
+            vm.new_function("_virtual_button", gamepad_virtual_button),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "_virtual_axis",
+            vm.new_function("_virtual_axis", gamepad_virtual_axis),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "_virtual_disconnect",
+            vm.new_function("_virtual_disconnect", gamepad_virtual_disconnect),
+            vm,
+        )
+        .unwrap();
+
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), GAMEPAD_PY_CODE, "<gamepad>".to_string()) {
+        eprintln!("Failed to create Gamepad classes: {:?}", e);
+        return module;
+    }
+    for name in ["Gamepad", "VirtualGamepad", "gamepads", "get", "virtual"] {
+        if let Ok(obj) = scope.globals.get_item(name, vm) {
+            module.set_attr(name, obj, vm).unwrap();
+        }
+    }
+    let button_names = GamepadButton::ALL
+        .iter()
+        .map(|b| vm.ctx.new_str(b.name()).into())
+        .collect();
+    module
+        .set_attr("BUTTONS", vm.ctx.new_list(button_names), vm)
+        .unwrap();
+
+    module
+}
@@ -225,8 +225,17 @@ fn is_builtin_frame(vm: &VirtualMachine, obj: &PyObjectRef) -> PyResult<bool> {
     Ok(obj.fast_isinstance(cls))
 }
 
-/// Snapshot framebuffer pixels into owned RGBA (see mesh / rasterizer docs).
+/// Frame pixels as the mesh wire value (see mesh / rasterizer docs).
 pub(crate) fn frame_rgba_to_json_value(vm: &VirtualMachine, obj: &PyObjectRef) -> PyResult<Value> {
+    let (w, h, rgba) = frame_rgba_pixels(vm, obj)?;
+    Ok(frame_rgba_to_mesh_wire_value(w, h, &rgba))
+}
+
+/// Snapshot framebuffer pixels into owned `(width, height, rgba)`, wherever they currently live.
+pub(crate) fn frame_rgba_pixels(
+    vm: &VirtualMachine,
+    obj: &PyObjectRef,
+) -> PyResult<(usize, usize, Vec<u8>)> {
     let Some(inner) = vm.get_attribute_opt(obj.clone(), "_data")? else {
         return Err(type_error(vm, "Frame missing _data"));
     };
@@ -257,7 +266,7 @@ pub(crate) fn frame_rgba_to_json_value(vm: &VirtualMachine, obj: &PyObjectRef) -
         if let Some(bytes) = blob.downcast_ref::<PyBytes>() {
             let s = bytes.as_bytes();
             if s.len() == need {
-                return Ok(frame_rgba_to_mesh_wire_value(w, h, s));
+                return Ok((w, h, s.to_vec()));
             }
         }
     }
@@ -269,7 +278,7 @@ pub(crate) fn frame_rgba_to_json_value(vm: &VirtualMachine, obj: &PyObjectRef) -
                 crate::xos_module::standalone_frame_buffer_copy(vid.max(0) as u64)
             {
                 if buf.len() == need {
-                    return Ok(frame_rgba_to_mesh_wire_value(w, h, &buf));
+                    return Ok((w, h, buf));
                 }
             }
         }
@@ -278,7 +287,7 @@ pub(crate) fn frame_rgba_to_json_value(vm: &VirtualMachine, obj: &PyObjectRef) -
     // 3) Active raster tick buffer (dimensions must match this Frame)
     if let Some(buf) = crate::rasterizer::copy_active_frame_rgba_if_match(w, h) {
         if buf.len() == need {
-            return Ok(frame_rgba_to_mesh_wire_value(w, h, &buf));
+            return Ok((w, h, buf));
         }
     }
 
@@ -292,13 +301,13 @@ pub(crate) fn frame_rgba_to_json_value(vm: &VirtualMachine, obj: &PyObjectRef) -
                     let v: i32 = item.clone().try_into_value(vm)?;
                     raw.push(v.clamp(0, 255) as u8);
                 }
-                return Ok(frame_rgba_to_mesh_wire_value(w, h, &raw));
+                return Ok((w, h, raw));
             }
         }
     }
 
     Err(vm.new_runtime_error(
-        "cannot serialize Frame: need RGBA (tensor._data bytes), standalone buffer, matching active framebuffer, or tensor data list".into(),
+        "cannot read Frame pixels: need RGBA (tensor._data bytes), standalone buffer, matching active framebuffer, or tensor data list".into(),
     ))
 }
 
@@ -2,23 +2,29 @@ pub mod ai;
 pub mod audio;
 pub mod auth;
 pub mod burn_train;
+pub mod capture;
 pub mod coordinates;
 pub mod colors;
 pub mod csv_api;
 pub mod data;
+pub mod dialog;
 pub mod dialoguer;
 pub mod dtypes;
 pub mod engine;
+pub mod gamepad;
 pub mod geom;
 pub(crate) mod json_codec;
 pub mod json_api;
+pub mod llama;
 pub mod manager;
 pub mod math;
 pub mod mesh;
 pub mod mouse;
 pub mod nn;
+pub mod ocr;
 pub mod ops;
 pub mod path;
+pub mod profiler;
 pub mod python_whiteboard;
 pub mod whiteboard_kernel;
 pub mod random;
@@ -33,14 +39,20 @@ pub mod system;
 pub mod tensor_buf;
 pub mod tensors;
 pub mod terminal;
+pub mod testing;
+pub mod tts;
 pub mod ui_events;
+pub mod vision;
+pub mod window;
+pub mod windows;
 pub mod xos_module;
 
 use rustpython_vm::{builtins::PyModule, PyRef, VirtualMachine};
 
 pub use json_codec::decode_mesh_jpeg_bytes_best_effort;
 pub use runtime::{
-    parse_script_cli_flags, run_python_app, run_python_file, run_python_interactive,
+    parse_script_cli_flags, run_python_app, run_python_app_with_options, run_python_file,
+    run_python_interactive, PythonAppOptions,
 };
 
 pub fn make_tensors_module(vm: &VirtualMachine) -> PyRef<PyModule> {
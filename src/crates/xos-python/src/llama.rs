@@ -0,0 +1,515 @@
+//! `xos.ai.llama` — local Llama-family chat models on Burn (`xos.ai.llama.load`), and the
+//! `xos.ai.chat(...)` shortcut that keeps one loaded model per (model, device).
+//!
+//! Loaded models live in a per-thread table keyed by an integer handle; the Python `LlamaModel`
+//! class only holds its handle.
+
+use rustpython_vm::{
+    builtins::PyModule, function::FuncArgs, PyObjectRef, PyRef, PyResult, VirtualMachine,
+};
+
+#[cfg(all(feature = "llama", not(target_arch = "wasm32"), not(target_os = "ios")))]
+mod native {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use rustpython_vm::{function::FuncArgs, PyObjectRef, PyResult, VirtualMachine};
+    use xos_core::ai::llama::{
+        ChatMessage, ChatTemplate, GenerateOptions, LlamaDevice, LlamaModel, SamplingOptions,
+        DEFAULT_MODEL,
+    };
+    use xos_core::ai::transcription::TensorDebugStats;
+
+    thread_local! {
+        static MODELS: RefCell<(u64, HashMap<u64, LlamaModel>)> = RefCell::new((0, HashMap::new()));
+    }
+
+    fn err(
+        vm: &VirtualMachine,
+        msg: impl Into<String>,
+    ) -> rustpython_vm::builtins::PyBaseExceptionRef {
+        vm.new_runtime_error(msg.into())
+    }
+
+    fn arg<T: rustpython_vm::TryFromObject>(
+        av: &[PyObjectRef],
+        i: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<T>> {
+        match av.get(i) {
+            Some(v) if !vm.is_none(v) => Ok(Some(v.clone().try_into_value(vm)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle(av: &[PyObjectRef], vm: &VirtualMachine) -> PyResult<u64> {
+        arg(av, 0, vm)?.ok_or_else(|| vm.new_type_error("missing model handle".to_string()))
+    }
+
+    /// Run `f` on the model behind `id`. The model is taken out of the table meanwhile, so a
+    /// Python callback that calls back into `xos.ai.llama` cannot alias it.
+    fn with_model<T>(
+        id: u64,
+        vm: &VirtualMachine,
+        f: impl FnOnce(&mut LlamaModel) -> PyResult<T>,
+    ) -> PyResult<T> {
+        let mut model = MODELS
+            .with(|m| m.borrow_mut().1.remove(&id))
+            .ok_or_else(|| err(vm, "llama model is closed or busy"))?;
+        let out = f(&mut model);
+        MODELS.with(|m| m.borrow_mut().1.insert(id, model));
+        out
+    }
+
+    fn ids_arg(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<Vec<u32>> {
+        arg::<Vec<i64>>(av, i, vm)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| {
+                u32::try_from(id).map_err(|_| vm.new_value_error(format!("bad token id {id}")))
+            })
+            .collect()
+    }
+
+    fn ints(vm: &VirtualMachine, values: &[usize]) -> PyObjectRef {
+        vm.ctx
+            .new_list(values.iter().map(|&v| vm.ctx.new_int(v).into()).collect())
+            .into()
+    }
+
+    fn floats(vm: &VirtualMachine, values: &[f32]) -> PyObjectRef {
+        vm.ctx
+            .new_list(
+                values
+                    .iter()
+                    .map(|&v| vm.ctx.new_float(v as f64).into())
+                    .collect(),
+            )
+            .into()
+    }
+
+    fn stats_dict(vm: &VirtualMachine, stats: Option<&TensorDebugStats>, n: usize) -> PyResult {
+        let d = vm.ctx.new_dict();
+        d.set_item("num_values", vm.ctx.new_int(n).into(), vm)?;
+        if let Some(s) = stats {
+            d.set_item("full_mean", vm.ctx.new_float(s.mean as f64).into(), vm)?;
+            d.set_item("full_std", vm.ctx.new_float(s.std as f64).into(), vm)?;
+            d.set_item("full_min", vm.ctx.new_float(s.min as f64).into(), vm)?;
+            d.set_item("full_max", vm.ctx.new_float(s.max as f64).into(), vm)?;
+        }
+        Ok(d.into())
+    }
+
+    /// `_load(model=None, device="cpu", template=None)` → handle.
+    pub fn load(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let source = arg::<String>(&av, 0, vm)?.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let device_s = arg::<String>(&av, 1, vm)?.unwrap_or_else(|| "cpu".to_string());
+        let device = LlamaDevice::from_name(&device_s).ok_or_else(|| {
+            vm.new_value_error(format!("unknown device '{device_s}' (use 'cpu' or 'gpu')"))
+        })?;
+        let template = arg::<String>(&av, 2, vm)?
+            .map(|t| ChatTemplate::from_name(&t))
+            .transpose()
+            .map_err(|e| vm.new_value_error(e))?;
+        let mut model = LlamaModel::load(&source, device).map_err(|e| err(vm, e))?;
+        if let Some(t) = template {
+            model.set_template(t);
+        }
+        let id = MODELS.with(|m| {
+            let mut m = m.borrow_mut();
+            m.0 += 1;
+            let id = m.0;
+            m.1.insert(id, model);
+            id
+        });
+        Ok(vm.ctx.new_int(id).into())
+    }
+
+    /// `_info(handle)` → `{"config", "template", "context_len", "weights_file", "parameters"}`.
+    pub fn info(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        with_model(id, vm, |model| {
+            let c = model.config();
+            let config = vm.ctx.new_dict();
+            for (key, value) in [
+                ("vocab_size", c.vocab_size),
+                ("hidden_size", c.hidden_size),
+                ("intermediate_size", c.intermediate_size),
+                ("num_layers", c.num_layers),
+                ("num_heads", c.num_heads),
+                ("num_kv_heads", c.num_kv_heads),
+                ("max_seq_len", c.max_seq_len),
+            ] {
+                config.set_item(key, vm.ctx.new_int(value).into(), vm)?;
+            }
+            config.set_item("rope_theta", vm.ctx.new_float(c.rope_theta).into(), vm)?;
+            config.set_item("rms_norm_eps", vm.ctx.new_float(c.rms_norm_eps).into(), vm)?;
+            let mut params = Vec::with_capacity(model.parameters().len());
+            for p in model.parameters() {
+                let d = vm.ctx.new_dict();
+                d.set_item("name", vm.ctx.new_str(p.name.as_str()).into(), vm)?;
+                d.set_item("shape", ints(vm, &p.shape), vm)?;
+                d.set_item("dtype", vm.ctx.new_str(p.dtype.as_str()).into(), vm)?;
+                d.set_item("values", floats(vm, &p.values), vm)?;
+                let numel = p.shape.iter().product();
+                d.set_item("stats", stats_dict(vm, p.stats.as_ref(), numel)?, vm)?;
+                params.push(d.into());
+            }
+            let weights = model
+                .weights_files()
+                .first()
+                .map(|p| vm.ctx.new_str(p.display().to_string()).into())
+                .unwrap_or_else(|| vm.ctx.none());
+            let out = vm.ctx.new_dict();
+            out.set_item("config", config.into(), vm)?;
+            out.set_item(
+                "template",
+                vm.ctx.new_str(model.template().name()).into(),
+                vm,
+            )?;
+            out.set_item(
+                "context_len",
+                vm.ctx.new_int(model.context_len()).into(),
+                vm,
+            )?;
+            out.set_item("weights_file", weights, vm)?;
+            out.set_item("parameters", vm.ctx.new_list(params).into(), vm)?;
+            Ok(out.into())
+        })
+    }
+
+    /// `_generate(handle, prompt, messages, max_tokens, temperature, top_p, top_k,
+    /// repetition_penalty, seed, stop, on_token)`: `prompt` is raw text or `None` with `messages`
+    /// as `[(role, content), ...]`. `on_token(piece)` returning `False` stops generation.
+    pub fn generate(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let prompt = arg::<String>(&av, 1, vm)?;
+        let messages =
+            arg::<Vec<PyObjectRef>>(&av, 2, vm)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|m| {
+                    let pair: Vec<String> = m.try_into_value(vm)?;
+                    match &pair[..] {
+                        [role, content] => Ok(ChatMessage::new(role, content)),
+                        _ => Err(vm
+                            .new_value_error("messages must be (role, content) pairs".to_string())),
+                    }
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+        let defaults = GenerateOptions::default();
+        let sampling = SamplingOptions {
+            temperature: arg(&av, 4, vm)?.unwrap_or(defaults.sampling.temperature),
+            top_p: arg(&av, 5, vm)?.unwrap_or(defaults.sampling.top_p),
+            top_k: arg(&av, 6, vm)?.unwrap_or(defaults.sampling.top_k),
+            repetition_penalty: arg(&av, 7, vm)?.unwrap_or(defaults.sampling.repetition_penalty),
+            seed: arg(&av, 8, vm)?,
+        };
+        let options = GenerateOptions {
+            max_tokens: arg(&av, 3, vm)?.unwrap_or(defaults.max_tokens),
+            sampling,
+            stop: arg(&av, 9, vm)?.unwrap_or_default(),
+        };
+        let callback = av.get(10).filter(|c| !vm.is_none(c)).cloned();
+
+        let mut callback_error = None;
+        let generation = with_model(id, vm, |model| {
+            let mut on_token = |piece: &str| -> bool {
+                let Some(cb) = &callback else {
+                    return true;
+                };
+                match cb.call((vm.ctx.new_str(piece),), vm) {
+                    Ok(ret) => !ret.is(&vm.ctx.false_value),
+                    Err(e) => {
+                        callback_error = Some(e);
+                        false
+                    }
+                }
+            };
+            let result = match &prompt {
+                Some(text) => model.generate(text, &options, &mut on_token),
+                None => model.chat(&messages, &options, &mut on_token),
+            };
+            result.map_err(|e| err(vm, e))
+        })?;
+        if let Some(e) = callback_error {
+            return Err(e);
+        }
+        let out = vm.ctx.new_dict();
+        out.set_item("text", vm.ctx.new_str(generation.text).into(), vm)?;
+        let tokens: Vec<usize> = generation.tokens.iter().map(|&t| t as usize).collect();
+        out.set_item("tokens", ints(vm, &tokens), vm)?;
+        out.set_item(
+            "prompt_tokens",
+            vm.ctx.new_int(generation.prompt_tokens).into(),
+            vm,
+        )?;
+        out.set_item(
+            "finish_reason",
+            vm.ctx.new_str(generation.finish_reason.as_str()).into(),
+            vm,
+        )?;
+        Ok(out.into())
+    }
+
+    /// `_forward(handle, ids)` → `(shape, flat_logits)` for every position.
+    pub fn forward(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let ids = ids_arg(&av, 1, vm)?;
+        with_model(id, vm, |model| {
+            let logits = model.forward(&ids).map_err(|e| err(vm, e))?;
+            let shape = [ids.len(), model.config().vocab_size];
+            Ok(vm
+                .ctx
+                .new_tuple(vec![ints(vm, &shape), floats(vm, &logits)])
+                .into())
+        })
+    }
+
+    /// `_forward_layer_by_layer(handle, ids)` → `[{"name", "shape", "values", "stats"}, ...]`.
+    pub fn forward_layer_by_layer(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let ids = ids_arg(&av, 1, vm)?;
+        with_model(id, vm, |model| {
+            let steps = model.forward_layer_by_layer(&ids).map_err(|e| err(vm, e))?;
+            let mut out = Vec::with_capacity(steps.len());
+            for s in steps {
+                let d = vm.ctx.new_dict();
+                let name = s.name.unwrap_or_default();
+                d.set_item("name", vm.ctx.new_str(name).into(), vm)?;
+                d.set_item("shape", ints(vm, &s.shape), vm)?;
+                d.set_item("values", floats(vm, &s.values), vm)?;
+                d.set_item(
+                    "stats",
+                    stats_dict(vm, s.full_stats.as_ref(), s.values.len())?,
+                    vm,
+                )?;
+                out.push(d.into());
+            }
+            Ok(vm.ctx.new_list(out).into())
+        })
+    }
+
+    /// `_tokenize(handle, text, add_bos=False)` → token ids.
+    pub fn tokenize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let text = arg::<String>(&av, 1, vm)?.unwrap_or_default();
+        let add_bos = arg::<bool>(&av, 2, vm)?.unwrap_or(false);
+        with_model(id, vm, |model| {
+            let ids = model.tokenize(&text, add_bos).map_err(|e| err(vm, e))?;
+            let ids: Vec<usize> = ids.iter().map(|&t| t as usize).collect();
+            Ok(ints(vm, &ids))
+        })
+    }
+
+    /// `_detokenize(handle, ids)` → text.
+    pub fn detokenize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let ids = ids_arg(&av, 1, vm)?;
+        with_model(id, vm, |model| {
+            let text = model.detokenize(&ids).map_err(|e| err(vm, e))?;
+            Ok(vm.ctx.new_str(text).into())
+        })
+    }
+
+    /// `_reset(handle)`: forget the cached conversation.
+    pub fn reset(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        with_model(id, vm, |model| {
+            model.reset();
+            Ok(vm.ctx.none())
+        })
+    }
+
+    /// `_close(handle)`: free the model's weights.
+    pub fn close(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        MODELS.with(|m| m.borrow_mut().1.remove(&id));
+        Ok(vm.ctx.none())
+    }
+}
+
+#[cfg(not(all(feature = "llama", not(target_arch = "wasm32"), not(target_os = "ios"))))]
+mod native {
+    use rustpython_vm::{function::FuncArgs, PyResult, VirtualMachine};
+
+    pub fn unavailable(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        Err(vm.new_runtime_error(
+            "Local chat models are unavailable in this build (enable llama)".to_string(),
+        ))
+    }
+
+    pub use unavailable as close;
+    pub use unavailable as detokenize;
+    pub use unavailable as forward;
+    pub use unavailable as forward_layer_by_layer;
+    pub use unavailable as generate;
+    pub use unavailable as info;
+    pub use unavailable as load;
+    pub use unavailable as reset;
+    pub use unavailable as tokenize;
+}
+
+const GLUE: &str = r#"
+CPU = "cpu"
+GPU = "gpu"
+DEFAULT_MODEL = "smollm2-135m-instruct"
+
+def _mk_parameter(payload):
+    xos = __import__("xos")
+    return xos.nn.Parameter(
+        payload["name"],
+        payload["shape"],
+        payload["dtype"],
+        payload.get("values", []),
+        payload.get("stats", {}),
+    )
+
+def _messages(messages):
+    # Accept a string (one user turn), dicts {"role", "content"} or (role, content) pairs.
+    if isinstance(messages, str):
+        return [("user", messages)]
+    out = []
+    for m in messages:
+        if isinstance(m, dict):
+            out.append((str(m["role"]), str(m["content"])))
+        else:
+            role, content = m
+            out.append((str(role), str(content)))
+    return out
+
+def _token_ids(x):
+    if isinstance(x, str):
+        raise TypeError("pass token ids; use model.tokenize(text) first")
+    return [int(v) for v in x]
+
+class LlamaModel:
+    """A loaded Llama-family checkpoint. Keeps a KV cache between calls, so a growing chat only
+    runs its new tokens; ``reset()`` drops it."""
+    def __init__(self, handle):
+        self._handle = handle
+        self._info = _info(handle)
+    def __del__(self):
+        try:
+            _close(self._handle)
+        except Exception:
+            pass
+    @property
+    def config(self):
+        return dict(self._info["config"])
+    @property
+    def template(self):
+        return self._info["template"]
+    @property
+    def context_len(self):
+        return self._info["context_len"]
+    def named_parameters(self):
+        for p in self._info["parameters"]:
+            yield p["name"], _mk_parameter(p)
+    @property
+    def parameters(self):
+        return [_mk_parameter(p) for p in self._info["parameters"]]
+    def get_parameter(self, name):
+        for p in self._info["parameters"]:
+            if p["name"] == name:
+                return _mk_parameter(p)
+        return None
+    @property
+    def parameter_count(self):
+        return len(self._info["parameters"])
+    @property
+    def weights_file(self):
+        return self._info["weights_file"]
+    def tokenize(self, text, add_bos=False):
+        return _tokenize(self._handle, str(text), bool(add_bos))
+    def detokenize(self, ids):
+        return _detokenize(self._handle, _token_ids(ids))
+    def forward(self, ids):
+        # Logits for every position of the token ids: a list of rows, one per token.
+        shape, flat = _forward(self._handle, _token_ids(ids))
+        vocab = shape[1]
+        return [flat[i * vocab:(i + 1) * vocab] for i in range(shape[0])]
+    def forward_layer_by_layer(self, ids):
+        for step in _forward_layer_by_layer(self._handle, _token_ids(ids)):
+            yield step["name"], _mk_parameter({
+                "name": step["name"],
+                "shape": step["shape"],
+                "dtype": "float32",
+                "values": step["values"],
+                "stats": step["stats"],
+            })
+    def _run(self, prompt, messages, max_tokens, temperature, top_p, top_k, repetition_penalty, seed, stop, on_token):
+        if isinstance(stop, str):
+            stop = [stop]
+        return _generate(
+            self._handle, prompt, messages, int(max_tokens), float(temperature), float(top_p),
+            int(top_k), float(repetition_penalty), None if seed is None else int(seed),
+            [str(s) for s in (stop or [])], on_token,
+        )
+    def generate(self, prompt, max_tokens=256, temperature=0.7, top_p=0.9, top_k=40, repetition_penalty=1.1, seed=None, stop=None, on_token=None):
+        """Continue raw ``prompt`` text. Returns {"text", "tokens", "prompt_tokens", "finish_reason"}
+        ("stop", "length" or "cancelled"). ``on_token(piece)`` streams the reply; returning False
+        stops early. ``temperature=0`` is greedy."""
+        return self._run(str(prompt), None, max_tokens, temperature, top_p, top_k, repetition_penalty, seed, stop, on_token)
+    def chat(self, messages, max_tokens=256, temperature=0.7, top_p=0.9, top_k=40, repetition_penalty=1.1, seed=None, stop=None, on_token=None):
+        """Reply to ``messages`` ([{"role": "system"|"user"|"assistant", "content": ...}], or a
+        string for a single user turn) using the checkpoint's chat template. Same result and
+        options as ``generate``."""
+        return self._run(None, _messages(messages), max_tokens, temperature, top_p, top_k, repetition_penalty, seed, stop, on_token)
+    def reset(self):
+        _reset(self._handle)
+
+def load(model=DEFAULT_MODEL, weights_path=None, device=CPU, template=None):
+    # model: registry name (`xos models list`, downloaded on first use); weights_path: a .gguf file
+    # or a safetensors folder instead. device: CPU (NdArray) or GPU (WGPU).
+    # template: "chatml" | "llama2" | "llama3" | "zephyr" to override the detected chat format.
+    return LlamaModel(_load(weights_path or model, device, template))
+
+_loaded = {}
+
+def chat(messages, model=DEFAULT_MODEL, device=CPU, stream=None, **options):
+    """One-call chat: loads ``model`` once (kept for later calls) and returns the reply text.
+    ``stream(piece)`` receives the reply as it is generated; other keyword arguments are those of
+    ``LlamaModel.chat``."""
+    key = (model, device)
+    if key not in _loaded:
+        _loaded[key] = load(model, device=device)
+    return _loaded[key].chat(messages, on_token=stream, **options)["text"]
+"#;
+
+pub fn make_llama_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let llama = vm.new_module("xos.ai.llama", vm.ctx.new_dict(), None);
+    let natives: [(&str, fn(FuncArgs, &VirtualMachine) -> PyResult); 9] = [
+        ("_load", native::load),
+        ("_info", native::info),
+        ("_generate", native::generate),
+        ("_forward", native::forward),
+        ("_forward_layer_by_layer", native::forward_layer_by_layer),
+        ("_tokenize", native::tokenize),
+        ("_detokenize", native::detokenize),
+        ("_reset", native::reset),
+        ("_close", native::close),
+    ];
+    let scope = vm.new_scope_with_builtins();
+    for (name, f) in natives {
+        let func: PyObjectRef = vm.new_function(name, f).into();
+        scope.globals.set_item(name, func, vm).ok();
+    }
+    if vm
+        .run_code_string(scope.clone(), GLUE, "<xos.ai.llama>".to_string())
+        .is_ok()
+    {
+        for name in ["load", "chat", "LlamaModel", "CPU", "GPU", "DEFAULT_MODEL"] {
+            if let Ok(v) = scope.globals.get_item(name, vm) {
+                llama.set_attr(name, v, vm).ok();
+            }
+        }
+    }
+    llama
+}
@@ -75,7 +75,7 @@ fn ceil(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     Ok(vm.ctx.new_float(result).into())
 }
 
-/// xos.math.fft(samples) - Fast Fourier Transform
+/// xos.math.fft(samples) - Fast Fourier Transform (any length)
 /// Returns tuple of (real_parts, imag_parts) for complex FFT result
 fn fft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let args_vec = args.args;
@@ -108,68 +108,14 @@ fn fft(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         return Err(vm.new_type_error("fft() requires a list or array".to_string()));
     };
 
-    let n = samples.len();
-
-    // Cooley-Tukey FFT (radix-2, requires power of 2)
-    if n == 0 || (n & (n - 1)) != 0 {
-        return Err(vm.new_value_error(format!("FFT requires power-of-2 length, got {}", n)));
+    if samples.is_empty() {
+        return Err(vm.new_value_error("fft() requires at least one sample".to_string()));
     }
 
-    // Convert real samples to complex (real, imag)
+    // Any length: radix-2 for powers of two, Bluestein otherwise (`xos.audio.dsp`'s plans)
     let mut real: Vec<f64> = samples;
-    let mut imag: Vec<f64> = vec![0.0; n];
-
-    // Bit-reversal permutation
-    let mut j = 0;
-    for i in 0..n - 1 {
-        if i < j {
-            real.swap(i, j);
-            imag.swap(i, j);
-        }
-        let mut k = n / 2;
-        while k <= j {
-            j -= k;
-            k /= 2;
-        }
-        j += k;
-    }
-
-    // Cooley-Tukey decimation-in-time
-    let mut length = 2;
-    while length <= n {
-        let angle = -2.0 * std::f64::consts::PI / length as f64;
-        let wlen_r = angle.cos();
-        let wlen_i = angle.sin();
-
-        let mut i = 0;
-        while i < n {
-            let mut w_r = 1.0;
-            let mut w_i = 0.0;
-
-            for j in 0..length / 2 {
-                let u_r = real[i + j];
-                let u_i = imag[i + j];
-                let v_r = real[i + j + length / 2];
-                let v_i = imag[i + j + length / 2];
-
-                let t_r = w_r * v_r - w_i * v_i;
-                let t_i = w_r * v_i + w_i * v_r;
-
-                real[i + j] = u_r + t_r;
-                imag[i + j] = u_i + t_i;
-                real[i + j + length / 2] = u_r - t_r;
-                imag[i + j + length / 2] = u_i - t_i;
-
-                let w_r_tmp = w_r;
-                w_r = w_r * wlen_r - w_i * wlen_i;
-                w_i = w_r_tmp * wlen_i + w_i * wlen_r;
-            }
-
-            i += length;
-        }
-
-        length *= 2;
-    }
+    let mut imag: Vec<f64> = vec![0.0; real.len()];
+    xos_core::engine::audio::dsp::fft(&mut real, &mut imag);
 
     // Convert to Python lists
     let real_list: Vec<PyObjectRef> = real.iter().map(|&r| vm.ctx.new_float(r).into()).collect();
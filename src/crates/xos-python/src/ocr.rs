@@ -0,0 +1,449 @@
+//! `xos.ai.ocr` — on-device text detection + recognition on Burn (`xos.ai.ocr.load`), and the
+//! `read(image)` shortcut behind `xos.vision.ocr` that keeps one loaded model per (model, device).
+//!
+//! Images are `Frame`s (app frames, `Monitor.get_frame()`, `xos.vision.webcam_frame()`) or
+//! `[h, w]` / `[h, w, channels]` tensors with values in `0..255` (or `0..1`).
+
+use rustpython_vm::{
+    builtins::PyModule, function::FuncArgs, PyObjectRef, PyRef, PyResult, VirtualMachine,
+};
+
+#[cfg(all(feature = "ocr", not(target_arch = "wasm32"), not(target_os = "ios")))]
+mod native {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use rustpython_vm::{
+        builtins::{PyBytes, PyDict},
+        function::FuncArgs,
+        PyObjectRef, PyResult, VirtualMachine,
+    };
+    use xos_core::ai::ocr::{Image, OcrDevice, OcrModel, OcrOptions, DEFAULT_MODEL};
+    use xos_core::ai::transcription::TensorDebugStats;
+
+    use crate::tensor_buf::{tensor_flat_data_list, tensor_shape_tuple};
+
+    thread_local! {
+        static MODELS: RefCell<(u64, HashMap<u64, OcrModel>)> = RefCell::new((0, HashMap::new()));
+    }
+
+    fn err(
+        vm: &VirtualMachine,
+        msg: impl Into<String>,
+    ) -> rustpython_vm::builtins::PyBaseExceptionRef {
+        vm.new_runtime_error(msg.into())
+    }
+
+    fn arg<T: rustpython_vm::TryFromObject>(
+        av: &[PyObjectRef],
+        i: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<T>> {
+        match av.get(i) {
+            Some(v) if !vm.is_none(v) => Ok(Some(v.clone().try_into_value(vm)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle(av: &[PyObjectRef], vm: &VirtualMachine) -> PyResult<u64> {
+        arg(av, 0, vm)?.ok_or_else(|| vm.new_type_error("missing model handle".to_string()))
+    }
+
+    fn with_model<T>(
+        id: u64,
+        vm: &VirtualMachine,
+        f: impl FnOnce(&OcrModel) -> PyResult<T>,
+    ) -> PyResult<T> {
+        MODELS.with(|m| match m.borrow().1.get(&id) {
+            Some(model) => f(model),
+            None => Err(err(vm, "ocr model is closed")),
+        })
+    }
+
+    /// A `Frame` or an image tensor (`[h, w]` gray or `[h, w, 3|4]`).
+    fn image_arg(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<Image> {
+        let obj = av
+            .get(i)
+            .filter(|o| !vm.is_none(o))
+            .ok_or_else(|| vm.new_type_error("missing image (a Frame or tensor)".to_string()))?;
+        let inner = vm.get_attribute_opt(obj.clone(), "_data")?;
+        let is_frame = inner
+            .as_ref()
+            .and_then(|d| d.downcast_ref::<PyDict>())
+            .is_some_and(|d| d.contains_key("width", vm) && d.contains_key("tensor", vm));
+        if is_frame {
+            let (w, h, rgba) = crate::json_codec::frame_rgba_pixels(vm, obj)?;
+            return Image::from_rgba(w, h, &rgba).map_err(|e| vm.new_value_error(e));
+        }
+        let shape = tensor_shape_tuple(obj, vm)?;
+        let (h, w, channels) = match shape[..] {
+            [h, w] => (h, w, 1),
+            [h, w, c] => (h, w, c),
+            _ => {
+                return Err(vm.new_value_error(format!(
+                    "image tensor must be [h, w] or [h, w, channels], got {shape:?}"
+                )))
+            }
+        };
+        // Frame tensors keep their RGBA as bytes.
+        let bytes = inner
+            .as_ref()
+            .and_then(|d| d.downcast_ref::<PyDict>())
+            .and_then(|d| d.get_item("_data", vm).ok())
+            .and_then(|b| b.downcast_ref::<PyBytes>().map(|b| b.as_bytes().to_vec()));
+        let pixels = match bytes {
+            Some(bytes) => bytes,
+            None => {
+                let values = tensor_flat_data_list(obj, vm)?;
+                let unit = values.iter().all(|&v| v <= 1.0);
+                let scale = if unit { 255.0 } else { 1.0 };
+                values
+                    .iter()
+                    .map(|&v| (v * scale).round().clamp(0.0, 255.0) as u8)
+                    .collect()
+            }
+        };
+        Image::from_pixels(w, h, channels, &pixels).map_err(|e| vm.new_value_error(e))
+    }
+
+    fn options_arg(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<OcrOptions> {
+        let mut o = OcrOptions::default();
+        let Some(d) = av.get(i).and_then(|d| d.downcast_ref::<PyDict>()) else {
+            return Ok(o);
+        };
+        let float = |key: &str, into: &mut f32| -> PyResult<()> {
+            if let Some(v) = d.get_item_opt(key, vm)? {
+                *into = v.try_into_value::<f64>(vm)? as f32;
+            }
+            Ok(())
+        };
+        float("text_threshold", &mut o.text_threshold)?;
+        float("link_threshold", &mut o.link_threshold)?;
+        float("low_text", &mut o.low_text)?;
+        float("margin", &mut o.margin)?;
+        float("min_confidence", &mut o.min_confidence)?;
+        if let Some(v) = d.get_item_opt("canvas_size", vm)? {
+            o.canvas_size = v.try_into_value(vm)?;
+        }
+        if let Some(v) = d.get_item_opt("merge_lines", vm)? {
+            o.merge_lines = v.try_to_bool(vm)?;
+        }
+        Ok(o)
+    }
+
+    fn bbox_obj(vm: &VirtualMachine, b: [f32; 4]) -> PyObjectRef {
+        vm.ctx
+            .new_tuple(
+                b.iter()
+                    .map(|&v| vm.ctx.new_float(v as f64).into())
+                    .collect(),
+            )
+            .into()
+    }
+
+    fn ints(vm: &VirtualMachine, values: &[usize]) -> PyObjectRef {
+        vm.ctx
+            .new_list(values.iter().map(|&v| vm.ctx.new_int(v).into()).collect())
+            .into()
+    }
+
+    fn floats(vm: &VirtualMachine, values: &[f32]) -> PyObjectRef {
+        vm.ctx
+            .new_list(
+                values
+                    .iter()
+                    .map(|&v| vm.ctx.new_float(v as f64).into())
+                    .collect(),
+            )
+            .into()
+    }
+
+    fn stats_dict(vm: &VirtualMachine, stats: Option<&TensorDebugStats>, n: usize) -> PyResult {
+        let d = vm.ctx.new_dict();
+        d.set_item("num_values", vm.ctx.new_int(n).into(), vm)?;
+        if let Some(s) = stats {
+            d.set_item("full_mean", vm.ctx.new_float(s.mean as f64).into(), vm)?;
+            d.set_item("full_std", vm.ctx.new_float(s.std as f64).into(), vm)?;
+            d.set_item("full_min", vm.ctx.new_float(s.min as f64).into(), vm)?;
+            d.set_item("full_max", vm.ctx.new_float(s.max as f64).into(), vm)?;
+        }
+        Ok(d.into())
+    }
+
+    /// `_load(model=None, device="cpu")` → handle.
+    pub fn load(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let source = arg::<String>(&av, 0, vm)?.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let device_s = arg::<String>(&av, 1, vm)?.unwrap_or_else(|| "cpu".to_string());
+        let device = OcrDevice::from_name(&device_s).ok_or_else(|| {
+            vm.new_value_error(format!("unknown device '{device_s}' (use 'cpu' or 'gpu')"))
+        })?;
+        let model = OcrModel::load(&source, device).map_err(|e| err(vm, e))?;
+        let id = MODELS.with(|m| {
+            let mut m = m.borrow_mut();
+            m.0 += 1;
+            let id = m.0;
+            m.1.insert(id, model);
+            id
+        });
+        Ok(vm.ctx.new_int(id).into())
+    }
+
+    /// `_info(handle)` → `{"charset", "weights_files", "parameters"}`.
+    pub fn info(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        with_model(id, vm, |model| {
+            let mut params = Vec::with_capacity(model.parameters().len());
+            for p in model.parameters() {
+                let d = vm.ctx.new_dict();
+                d.set_item("name", vm.ctx.new_str(p.name.as_str()).into(), vm)?;
+                d.set_item("shape", ints(vm, &p.shape), vm)?;
+                d.set_item("dtype", vm.ctx.new_str(p.dtype.as_str()).into(), vm)?;
+                d.set_item("values", floats(vm, &p.values), vm)?;
+                let numel = p.shape.iter().product();
+                d.set_item("stats", stats_dict(vm, p.stats.as_ref(), numel)?, vm)?;
+                params.push(d.into());
+            }
+            let files = model
+                .weights_files()
+                .iter()
+                .map(|p| vm.ctx.new_str(p.display().to_string()).into())
+                .collect();
+            let out = vm.ctx.new_dict();
+            out.set_item("charset", vm.ctx.new_str(model.charset()).into(), vm)?;
+            out.set_item("weights_files", vm.ctx.new_list(files).into(), vm)?;
+            out.set_item("parameters", vm.ctx.new_list(params).into(), vm)?;
+            Ok(out.into())
+        })
+    }
+
+    /// `_read(handle, image, options)` → `[{"box": (x1, y1, x2, y2), "text", "confidence"}, ...]`.
+    pub fn read(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let image = image_arg(&av, 1, vm)?;
+        let options = options_arg(&av, 2, vm)?;
+        with_model(id, vm, |model| {
+            let found = model.read(&image, &options).map_err(|e| err(vm, e))?;
+            let mut out = Vec::with_capacity(found.len());
+            for t in found {
+                let d = vm.ctx.new_dict();
+                d.set_item("box", bbox_obj(vm, t.bbox), vm)?;
+                d.set_item("text", vm.ctx.new_str(t.text).into(), vm)?;
+                d.set_item(
+                    "confidence",
+                    vm.ctx.new_float(t.confidence as f64).into(),
+                    vm,
+                )?;
+                out.push(d.into());
+            }
+            Ok(vm.ctx.new_list(out).into())
+        })
+    }
+
+    /// `_detect(handle, image, options)` → `[(x1, y1, x2, y2), ...]`.
+    pub fn detect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let image = image_arg(&av, 1, vm)?;
+        let options = options_arg(&av, 2, vm)?;
+        with_model(id, vm, |model| {
+            let boxes = model.detect(&image, &options).map_err(|e| err(vm, e))?;
+            Ok(vm
+                .ctx
+                .new_list(boxes.into_iter().map(|b| bbox_obj(vm, b)).collect())
+                .into())
+        })
+    }
+
+    /// `_recognize(handle, image, box)` → `(text, confidence)`.
+    pub fn recognize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let image = image_arg(&av, 1, vm)?;
+        let bbox = match arg::<Vec<f64>>(&av, 2, vm)? {
+            Some(b) if b.len() == 4 => [b[0] as f32, b[1] as f32, b[2] as f32, b[3] as f32],
+            Some(_) => return Err(vm.new_value_error("box must be (x1, y1, x2, y2)".to_string())),
+            None => [0.0, 0.0, image.width as f32, image.height as f32],
+        };
+        with_model(id, vm, |model| {
+            let (text, confidence) = model.recognize(&image, bbox).map_err(|e| err(vm, e))?;
+            Ok(vm
+                .ctx
+                .new_tuple(vec![
+                    vm.ctx.new_str(text).into(),
+                    vm.ctx.new_float(confidence as f64).into(),
+                ])
+                .into())
+        })
+    }
+
+    /// `_forward_layer_by_layer(handle, image, options)` → `[{"name", "shape", "values", "stats"}, ...]`.
+    pub fn forward_layer_by_layer(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let image = image_arg(&av, 1, vm)?;
+        let options = options_arg(&av, 2, vm)?;
+        with_model(id, vm, |model| {
+            let steps = model
+                .forward_layer_by_layer(&image, &options)
+                .map_err(|e| err(vm, e))?;
+            let mut out = Vec::with_capacity(steps.len());
+            for s in steps {
+                let d = vm.ctx.new_dict();
+                let name = s.name.unwrap_or_default();
+                d.set_item("name", vm.ctx.new_str(name).into(), vm)?;
+                d.set_item("shape", ints(vm, &s.shape), vm)?;
+                d.set_item("values", floats(vm, &s.values), vm)?;
+                d.set_item(
+                    "stats",
+                    stats_dict(vm, s.full_stats.as_ref(), s.values.len())?,
+                    vm,
+                )?;
+                out.push(d.into());
+            }
+            Ok(vm.ctx.new_list(out).into())
+        })
+    }
+
+    /// `_close(handle)`: free the model's weights.
+    pub fn close(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        MODELS.with(|m| m.borrow_mut().1.remove(&id));
+        Ok(vm.ctx.none())
+    }
+}
+
+#[cfg(not(all(feature = "ocr", not(target_arch = "wasm32"), not(target_os = "ios"))))]
+mod native {
+    use rustpython_vm::{function::FuncArgs, PyResult, VirtualMachine};
+
+    pub fn unavailable(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        Err(vm.new_runtime_error(
+            "On-device OCR is unavailable in this build (enable ocr)".to_string(),
+        ))
+    }
+
+    pub use unavailable as close;
+    pub use unavailable as detect;
+    pub use unavailable as forward_layer_by_layer;
+    pub use unavailable as info;
+    pub use unavailable as load;
+    pub use unavailable as read;
+    pub use unavailable as recognize;
+}
+
+const GLUE: &str = r#"
+CPU = "cpu"
+GPU = "gpu"
+DEFAULT_MODEL = "easyocr-en"
+
+def _mk_parameter(payload):
+    xos = __import__("xos")
+    return xos.nn.Parameter(
+        payload["name"],
+        payload["shape"],
+        payload["dtype"],
+        payload.get("values", []),
+        payload.get("stats", {}),
+    )
+
+class OcrModel:
+    """A loaded detector + recognizer pair (CRAFT and EasyOCR's english_g2).
+
+    Options accepted by ``forward`` / ``detect`` / ``forward_layer_by_layer``: ``canvas_size``
+    (long side the image is scaled down to, default 1280), ``text_threshold`` (0.7),
+    ``link_threshold`` (0.4), ``low_text`` (0.4), ``merge_lines`` (True: read lines, False:
+    words), ``margin`` (0.1) and ``min_confidence`` (0.0)."""
+    def __init__(self, handle):
+        self._handle = handle
+        self._info = _info(handle)
+    def __del__(self):
+        try:
+            _close(self._handle)
+        except Exception:
+            pass
+    @property
+    def charset(self):
+        return self._info["charset"]
+    def named_parameters(self):
+        for p in self._info["parameters"]:
+            yield p["name"], _mk_parameter(p)
+    @property
+    def parameters(self):
+        return [_mk_parameter(p) for p in self._info["parameters"]]
+    def get_parameter(self, name):
+        for p in self._info["parameters"]:
+            if p["name"] == name:
+                return _mk_parameter(p)
+        return None
+    @property
+    def parameter_count(self):
+        return len(self._info["parameters"])
+    @property
+    def weights_files(self):
+        return list(self._info["weights_files"])
+    def forward(self, image, **options):
+        """All text in ``image``, top to bottom: [{"box": (x1, y1, x2, y2), "text", "confidence"}]
+        with the box in image pixels."""
+        return _read(self._handle, image, options)
+    def detect(self, image, **options):
+        return _detect(self._handle, image, options)
+    def recognize(self, image, box=None):
+        """Read the text in ``box`` (default: the whole image). Returns (text, confidence)."""
+        return _recognize(self._handle, image, None if box is None else [float(v) for v in box])
+    def forward_layer_by_layer(self, image, **options):
+        # Detector stages on the image, then recognizer stages on the first box found.
+        for step in _forward_layer_by_layer(self._handle, image, options):
+            yield step["name"], _mk_parameter({
+                "name": step["name"],
+                "shape": step["shape"],
+                "dtype": "float32",
+                "values": step["values"],
+                "stats": step["stats"],
+            })
+
+def load(model=DEFAULT_MODEL, weights_path=None, device=CPU):
+    # model: registry name (`xos models list`, downloaded on first use); weights_path: a folder
+    # with craft_mlt_25k.pth and english_g2.pth instead. device: CPU (NdArray) or GPU (WGPU).
+    return OcrModel(_load(weights_path or model, device))
+
+_loaded = {}
+
+def read(image, model=DEFAULT_MODEL, device=CPU, **options):
+    """One-call OCR: loads ``model`` once (kept for later calls) and returns ``OcrModel.forward``'s
+    result for ``image`` (a Frame or an image tensor)."""
+    key = (model, device)
+    if key not in _loaded:
+        _loaded[key] = load(model, device=device)
+    return _loaded[key].forward(image, **options)
+"#;
+
+pub fn make_ocr_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let ocr = vm.new_module("xos.ai.ocr", vm.ctx.new_dict(), None);
+    let natives: [(&str, fn(FuncArgs, &VirtualMachine) -> PyResult); 7] = [
+        ("_load", native::load),
+        ("_info", native::info),
+        ("_read", native::read),
+        ("_detect", native::detect),
+        ("_recognize", native::recognize),
+        ("_forward_layer_by_layer", native::forward_layer_by_layer),
+        ("_close", native::close),
+    ];
+    let scope = vm.new_scope_with_builtins();
+    for (name, f) in natives {
+        let func: PyObjectRef = vm.new_function(name, f).into();
+        scope.globals.set_item(name, func, vm).ok();
+    }
+    if vm
+        .run_code_string(scope.clone(), GLUE, "<xos.ai.ocr>".to_string())
+        .is_ok()
+    {
+        for name in ["load", "read", "OcrModel", "CPU", "GPU", "DEFAULT_MODEL"] {
+            if let Ok(v) = scope.globals.get_item(name, vm) {
+                ocr.set_attr(name, v, vm).ok();
+            }
+        }
+    }
+    ocr
+}
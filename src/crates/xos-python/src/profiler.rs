@@ -0,0 +1,220 @@
+//! `xos.profile` / `xos.profiler` — named scopes in the engine's per-frame profiler (see
+//! [`xos_core::engine::profiler`]): shown in the F3 timeline and exportable as Chrome trace JSON.
+
+use rustpython_vm::builtins::PyModule;
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyRef, PyResult, VirtualMachine};
+use xos_core::engine::profiler::{self, ScopeToken};
+
+const PROFILER_PY_CODE: &str = r#"
+class profile:
+    """Time a block or a function as a named scope of the current frame::
+
+        with xos.profile("physics"):
+            step()
+
+        @xos.profile("collide")
+        def collide(a, b): ...
+
+    Scopes show in the F3 timeline and in ``xos.profiler.export()``; they cost almost nothing
+    while the profiler isn't recording."""
+
+    def __init__(self, name):
+        self.name = str(name)
+        self._tokens = []
+
+    def __enter__(self):
+        import xos
+        self._tokens.append(xos.profiler._begin(self.name))
+        return self
+
+    def __exit__(self, *exc):
+        import xos
+        token = self._tokens.pop()
+        if token is not None:
+            xos.profiler._end(token[0], token[1])
+        return False
+
+    def __call__(self, func):
+        def profiled(*args, **kwargs):
+            with self:
+                return func(*args, **kwargs)
+        profiled.__name__ = getattr(func, "__name__", "profiled")
+        profiled.__doc__ = getattr(func, "__doc__", None)
+        return profiled
+
+def start():
+    """Record every frame, not only while the F3 overlay is open."""
+    import xos
+    xos.profiler._set_recording(True)
+
+def stop():
+    import xos
+    xos.profiler._set_recording(False)
+
+def export(path=None):
+    """Write the recorded frames (the last ~4 s) as Chrome trace JSON, for ``chrome://tracing`` or
+    https://ui.perfetto.dev. Defaults to a timestamped file under the xos data dir; returns the path."""
+    import xos
+    return xos.profiler._export(None if path is None else str(path))
+
+def _profile_gc():
+    # RustPython frees by reference counting; only explicit collections are worth a scope.
+    try:
+        import gc
+        collect = gc.collect
+        if getattr(collect, "_xos_profiled", False):
+            return
+
+        def profiled_collect(*args):
+            with profile("gc"):
+                return collect(*args)
+        profiled_collect._xos_profiled = True
+        gc.collect = profiled_collect
+    except Exception:
+        pass
+"#;
+
+/// xos.profiler._begin(name) - (frame, span) token, or None while not recording
+fn profiler_begin(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let name: String = args
+        .args
+        .first()
+        .ok_or_else(|| vm.new_type_error("_begin(name)".to_string()))?
+        .clone()
+        .try_into_value(vm)?;
+    Ok(match profiler::begin_scope(name) {
+        Some(token) => {
+            let (frame, span) = token.into_parts();
+            vm.ctx
+                .new_tuple(vec![
+                    vm.ctx.new_int(frame).into(),
+                    vm.ctx.new_int(span).into(),
+                ])
+                .into()
+        }
+        None => vm.ctx.none(),
+    })
+}
+
+/// xos.profiler._end(frame, span)
+fn profiler_end(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let (Some(frame), Some(span)) = (args.args.first(), args.args.get(1)) else {
+        return Err(vm.new_type_error("_end(frame, span)".to_string()));
+    };
+    let frame: u64 = frame.clone().try_into_value(vm)?;
+    let span: usize = span.clone().try_into_value(vm)?;
+    profiler::end_scope(ScopeToken::from_parts(frame, span));
+    Ok(vm.ctx.none())
+}
+
+/// xos.profiler._set_recording(on)
+fn profiler_set_recording(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let on = match args.args.first() {
+        Some(obj) => obj.clone().try_to_bool(vm)?,
+        None => true,
+    };
+    profiler::set_recording(on);
+    Ok(vm.ctx.none())
+}
+
+/// xos.profiler.is_recording() - True while the F3 overlay is open or after start()
+fn profiler_is_recording(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bool(profiler::is_recording()).into())
+}
+
+/// xos.profiler._export(path) - write a Chrome trace, return its path
+fn profiler_export(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let path = match args.args.first() {
+        Some(obj) if !vm.is_none(obj) => obj.clone().try_into_value::<String>(vm)?,
+        _ => profiler::default_trace_path(),
+    };
+    profiler::export_chrome_trace(&path).map_err(|e| vm.new_runtime_error(e))?;
+    Ok(vm.ctx.new_str(path).into())
+}
+
+/// xos.profiler.frames() - recorded frames, oldest first: [{"index", "ms", "scopes": [(name,
+/// start_ms, ms, depth), ...]}] with scope starts relative to their frame
+fn profiler_frames(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let frames = profiler::with_frames(|frames| {
+        frames
+            .iter()
+            .map(|frame| {
+                let scopes = frame
+                    .spans
+                    .iter()
+                    .map(|span| {
+                        vm.ctx
+                            .new_tuple(vec![
+                                vm.ctx.new_str(span.name.as_ref()).into(),
+                                vm.ctx
+                                    .new_float((span.start_us - frame.start_us) / 1000.0)
+                                    .into(),
+                                vm.ctx.new_float(span.duration_us / 1000.0).into(),
+                                vm.ctx.new_int(span.depth).into(),
+                            ])
+                            .into()
+                    })
+                    .collect();
+                (frame.index, frame.duration_us / 1000.0, scopes)
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut items = Vec::with_capacity(frames.len());
+    for (index, ms, scopes) in frames {
+        let d = vm.ctx.new_dict();
+        d.set_item("index", vm.ctx.new_int(index).into(), vm)?;
+        d.set_item("ms", vm.ctx.new_float(ms).into(), vm)?;
+        d.set_item("scopes", vm.ctx.new_list(scopes).into(), vm)?;
+        items.push(d.into());
+    }
+    Ok(vm.ctx.new_list(items).into())
+}
+
+/// Create the profiler module (`xos.profile` is its `profile` class)
+pub fn make_profiler_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.profiler", vm.ctx.new_dict(), None);
+
+    module
+        .set_attr("_begin", vm.new_function("_begin", profiler_begin), vm)
+        .unwrap();
+    module
+        .set_attr("_end", vm.new_function("_end", profiler_end), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "_set_recording",
+            vm.new_function("_set_recording", profiler_set_recording),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("_export", vm.new_function("_export", profiler_export), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "is_recording",
+            vm.new_function("is_recording", profiler_is_recording),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("frames", vm.new_function("frames", profiler_frames), vm)
+        .unwrap();
+
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), PROFILER_PY_CODE, "<profiler>".to_string()) {
+        eprintln!("Failed to create profiler functions: {:?}", e);
+        return module;
+    }
+    for name in ["profile", "start", "stop", "export"] {
+        if let Ok(obj) = scope.globals.get_item(name, vm) {
+            module.set_attr(name, obj, vm).unwrap();
+        }
+    }
+    if let Ok(profile_gc) = scope.globals.get_item("_profile_gc", vm) {
+        let _ = profile_gc.call((), vm);
+    }
+
+    module
+}
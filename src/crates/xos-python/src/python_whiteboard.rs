@@ -136,6 +136,7 @@ pub fn dispatch_whiteboard_widget(id: u64, kind: PyUiEventKind, state: &mut Engi
                 w.on_scroll(state, dx, dy, unit);
             }
         }
+        PyUiEventKind::Gesture(g) => w.on_gesture(&g),
         _ => {}
     }
 }
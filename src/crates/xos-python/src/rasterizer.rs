@@ -1,6 +1,8 @@
 use crate::tensors::{tensor_flat_data_list, tensor_shape_tuple};
+use xos_core::rasterizer::canvas::{Affine, Canvas, Path};
 use xos_core::rasterizer::shapes::lines::draw_line_direct;
 use xos_core::rasterizer::text::fonts::{self, FontFamily};
+use xos_core::rasterizer::text::sdf::{self, TextEffects};
 use xos_core::rasterizer::text::text_rasterization::TextRasterizer;
 use fontdue::Font;
 use rustpython_vm::{
@@ -252,6 +254,12 @@ pub static CURRENT_FRAME_BUFFER: Mutex<Option<FrameBufferPtr>> = Mutex::new(None
 pub static CURRENT_FRAME_WIDTH: Mutex<usize> = Mutex::new(0);
 pub static CURRENT_FRAME_HEIGHT: Mutex<usize> = Mutex::new(0);
 
+// Engine-owned framebuffer for the active context. `CURRENT_FRAME_BUFFER` points into a canvas
+// layer instead while `clip_*` / `save_layer` redirect drawing offscreen.
+static ROOT_FRAME_BUFFER: Mutex<Option<FrameBufferPtr>> = Mutex::new(None);
+// `save()` / `restore()` state behind `xos.rasterizer`; reset whenever a frame context is bound.
+static CANVAS: Mutex<Option<Canvas>> = Mutex::new(None);
+
 // Global font for text rasterization (lazy loaded)
 static GLOBAL_FONT: Mutex<Option<Font>> = Mutex::new(None);
 static GLOBAL_FONT_FAMILY: Mutex<FontFamily> = Mutex::new(FontFamily::JetBrainsMono);
@@ -268,13 +276,108 @@ pub(crate) fn fill_buffer_solid_rgba(buffer: &mut [u8], r: u8, g: u8, b: u8, a:
 /// Called by PyApp before tick to set the frame buffer pointer
 pub fn set_frame_buffer_context(buffer: &mut [u8], width: usize, height: usize) {
     *CURRENT_FRAME_BUFFER.lock().unwrap() = Some(FrameBufferPtr(buffer.as_mut_ptr()));
+    *ROOT_FRAME_BUFFER.lock().unwrap() = Some(FrameBufferPtr(buffer.as_mut_ptr()));
     *CURRENT_FRAME_WIDTH.lock().unwrap() = width;
     *CURRENT_FRAME_HEIGHT.lock().unwrap() = height;
+    *CANVAS.lock().unwrap() = None;
 }
 
-/// Called by PyApp after tick to clear the frame buffer pointer
+/// Called by PyApp after tick to clear the frame buffer pointer.
+/// Layers left open by unbalanced `save_layer()` / `clip_*()` calls are composited first.
 pub fn clear_frame_buffer_context() {
+    if let Some(mut canvas) = CANVAS.lock().unwrap().take() {
+        if let Some(root) = ROOT_FRAME_BUFFER.lock().unwrap().as_ref() {
+            let len = canvas.width() * canvas.height() * 4;
+            let frame = unsafe { std::slice::from_raw_parts_mut(root.as_ptr(), len) };
+            canvas.flush(frame);
+        }
+    }
     *CURRENT_FRAME_BUFFER.lock().unwrap() = None;
+    *ROOT_FRAME_BUFFER.lock().unwrap() = None;
+}
+
+/// Current `xos.rasterizer` transform, or `None` when it is the identity (fast path).
+fn active_transform() -> Option<Affine> {
+    CANVAS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|c| c.transform())
+        .filter(|t| !t.is_identity())
+}
+
+/// Run `f` on the canvas for the bound frame (created on first use) plus the root framebuffer,
+/// then point [`CURRENT_FRAME_BUFFER`] at whatever buffer drawing should go to next.
+fn with_canvas<R>(
+    vm: &VirtualMachine,
+    name: &str,
+    f: impl FnOnce(&mut Canvas, &mut [u8]) -> R,
+) -> PyResult<R> {
+    let root_ptr = ROOT_FRAME_BUFFER
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|ptr| ptr.as_ptr())
+        .ok_or_else(|| {
+            vm.new_runtime_error(format!(
+                "No frame buffer context set. {name}() must be called during tick()."
+            ))
+        })?;
+    let width = *CURRENT_FRAME_WIDTH.lock().unwrap();
+    let height = *CURRENT_FRAME_HEIGHT.lock().unwrap();
+    let root = unsafe { std::slice::from_raw_parts_mut(root_ptr, width * height * 4) };
+
+    let mut guard = CANVAS.lock().unwrap();
+    let canvas = guard.get_or_insert_with(|| Canvas::new(width, height));
+    if canvas.width() != width || canvas.height() != height {
+        canvas.reset(width, height);
+    }
+    let out = f(&mut *canvas, &mut *root);
+    let target = canvas.target(root).as_mut_ptr();
+    *CURRENT_FRAME_BUFFER.lock().unwrap() = Some(FrameBufferPtr(target));
+    Ok(out)
+}
+
+/// Fill local-space `(x, y, w, h, color)` rectangles through the active transform. Callers keep
+/// their own untransformed fast path and only come here when [`active_transform`] is `Some`.
+fn fill_local_rects(
+    vm: &VirtualMachine,
+    name: &str,
+    rects: &[(f32, f32, f32, f32, (u8, u8, u8, u8))],
+) -> PyResult<()> {
+    with_canvas(vm, name, |canvas, frame| {
+        for &(x, y, w, h, color) in rects {
+            canvas.fill_rect(frame, x, y, w, h, color);
+        }
+    })
+}
+
+/// Reject a `frame` argument that is not the framebuffer drawing currently goes to, instead of
+/// silently drawing into the bound one. Accepts an `xos.Frame` or its backing dict.
+fn check_frame_arg(frame: &PyObjectRef, vm: &VirtualMachine, name: &str) -> PyResult<()> {
+    if ROOT_FRAME_BUFFER.lock().unwrap().is_none() {
+        // Nothing bound yet: `with_canvas` reports that.
+        return Ok(());
+    }
+    let data = vm
+        .get_attribute_opt(frame.clone(), "_data")?
+        .unwrap_or_else(|| frame.clone());
+    let dict = data
+        .downcast_ref::<PyDict>()
+        .ok_or_else(|| vm.new_type_error(format!("{name}(): frame must be an xos.Frame")))?;
+    let width: usize = dict.get_item("width", vm)?.try_into_value(vm)?;
+    let height: usize = dict.get_item("height", vm)?.try_into_value(vm)?;
+    let bound = (
+        *CURRENT_FRAME_WIDTH.lock().unwrap(),
+        *CURRENT_FRAME_HEIGHT.lock().unwrap(),
+    );
+    if (width, height) != bound {
+        return Err(vm.new_value_error(format!(
+            "{name}(): frame is {width}x{height} but the framebuffer being drawn is {}x{}",
+            bound.0, bound.1
+        )));
+    }
+    Ok(())
 }
 
 /// Copy active RGBA framebuffer when `width` / `height` match the bound context (used by `xos.json` / mesh).
@@ -391,6 +494,14 @@ fn circles(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         }
     }
 
+    if let Some(t) = active_transform() {
+        let scale = t.mean_scale();
+        for c in circles_to_draw.iter_mut() {
+            let (x, y) = t.apply(c.0, c.1);
+            *c = (x, y, c.2 * scale);
+        }
+    }
+
     // Get mutable buffer slice
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
@@ -515,6 +626,12 @@ fn triangles_py(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     drop(positions_vec);
     drop(colors_vec);
 
+    if let Some(t) = active_transform() {
+        for p in points_flat.iter_mut() {
+            *p = t.apply(p.0, p.1);
+        }
+    }
+
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
 
@@ -650,6 +767,15 @@ fn lines(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         }
     }
 
+    if let Some(t) = active_transform() {
+        let scale = t.mean_scale();
+        for l in lines_to_draw.iter_mut() {
+            let (x1, y1) = t.apply(l.0, l.1);
+            let (x2, y2) = t.apply(l.2, l.3);
+            *l = (x1, y1, x2, y2, l.4 * scale);
+        }
+    }
+
     // Get mutable buffer slice
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
@@ -795,6 +921,19 @@ fn lines_batched(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     drop(thicknesses_vec);
     drop(colors_vec);
 
+    if let Some(t) = active_transform() {
+        let scale = t.mean_scale();
+        for l in lines_to_draw.iter_mut() {
+            let (x1, y1) = t.apply(l.0, l.1);
+            let (x2, y2) = t.apply(l.2, l.3);
+            l.0 = x1;
+            l.1 = y1;
+            l.2 = x2;
+            l.3 = y2;
+            l.4 *= scale;
+        }
+    }
+
     // Get mutable buffer slice
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
@@ -835,6 +974,7 @@ fn clear(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
 }
 
 /// xos.rasterizer.fill() - fill the entire frame buffer with a solid color
+/// (the frame-sized local rectangle when a transform is active)
 ///
 /// Usage: xos.rasterizer.fill(frame, color)
 /// - frame: frame object (ignored, we use the global context)
@@ -879,6 +1019,14 @@ fn fill(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let b: i32 = color_vec[2].clone().try_into_value(vm)?;
     let a: i32 = color_vec[3].clone().try_into_value(vm)?;
 
+    // Under a transform the frame is a local-space rectangle like any other.
+    if active_transform().is_some() {
+        let color = (r as u8, g as u8, b as u8, a as u8);
+        let rect = (0.0, 0.0, width as f32, height as f32, color);
+        fill_local_rects(vm, "fill", &[rect])?;
+        return Ok(vm.ctx.none());
+    }
+
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
 
@@ -997,6 +1145,10 @@ fn rects_filled(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let buffer_len = width * height * 4;
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
 
+    // Under a transform every mode collects local-space rects and fills them through the canvas.
+    let transformed = active_transform().is_some();
+    let mut local_rects = Vec::new();
+
     // Check mode based on argument count
     if args_vec.len() == 6
         && args_vec[1]
@@ -1048,6 +1200,17 @@ fn rects_filled(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
                 let x_start = (bin_idx as usize * width) / num_bins as usize;
                 let x_end = ((bin_idx + 1) as usize * width) / num_bins as usize;
 
+                if transformed {
+                    local_rects.push((
+                        x_start as f32,
+                        y_start as f32,
+                        (x_end - x_start) as f32,
+                        (y_end - y_start) as f32,
+                        (r as u8, g as u8, b as u8, a as u8),
+                    ));
+                    continue;
+                }
+
                 // Fill rectangle (optimized: fill row by row)
                 for y in y_start..y_end.min(height) {
                     let row_start = (y * width + x_start) * 4;
@@ -1086,33 +1249,46 @@ fn rects_filled(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         let b: i32 = color_vec[2].clone().try_into_value(vm)?;
         let a: i32 = color_vec[3].clone().try_into_value(vm)?;
 
-        // Clamp and draw
-        let x_start = x1.max(0).min(width as i32) as usize;
-        let x_end = x2.max(0).min(width as i32) as usize;
-        let y_start = y1.max(0).min(height as i32) as usize;
-        let y_end = y2.max(0).min(height as i32) as usize;
-
-        for y in y_start..y_end {
-            let row_start = (y * width + x_start) * 4;
-            let row_end = (y * width + x_end) * 4;
-            let mut idx = row_start;
-            while idx < row_end && idx + 3 < buffer.len() {
-                let src_a = (a.clamp(0, 255) as f32) / 255.0;
-                let inv_a = 1.0 - src_a;
-                let rr = r.clamp(0, 255) as f32;
-                let gg = g.clamp(0, 255) as f32;
-                let bb = b.clamp(0, 255) as f32;
-                buffer[idx] = (rr * src_a + buffer[idx] as f32 * inv_a)
-                    .round()
-                    .clamp(0.0, 255.0) as u8;
-                buffer[idx + 1] = (gg * src_a + buffer[idx + 1] as f32 * inv_a)
-                    .round()
-                    .clamp(0.0, 255.0) as u8;
-                buffer[idx + 2] = (bb * src_a + buffer[idx + 2] as f32 * inv_a)
-                    .round()
-                    .clamp(0.0, 255.0) as u8;
-                buffer[idx + 3] = a as u8;
-                idx += 4;
+        if transformed {
+            if x2 > x1 && y2 > y1 {
+                let color = (
+                    r.clamp(0, 255) as u8,
+                    g.clamp(0, 255) as u8,
+                    b.clamp(0, 255) as u8,
+                    a.clamp(0, 255) as u8,
+                );
+                let (w, h) = ((x2 - x1) as f32, (y2 - y1) as f32);
+                local_rects.push((x1 as f32, y1 as f32, w, h, color));
+            }
+        } else {
+            // Clamp and draw
+            let x_start = x1.max(0).min(width as i32) as usize;
+            let x_end = x2.max(0).min(width as i32) as usize;
+            let y_start = y1.max(0).min(height as i32) as usize;
+            let y_end = y2.max(0).min(height as i32) as usize;
+
+            for y in y_start..y_end {
+                let row_start = (y * width + x_start) * 4;
+                let row_end = (y * width + x_end) * 4;
+                let mut idx = row_start;
+                while idx < row_end && idx + 3 < buffer.len() {
+                    let src_a = (a.clamp(0, 255) as f32) / 255.0;
+                    let inv_a = 1.0 - src_a;
+                    let rr = r.clamp(0, 255) as f32;
+                    let gg = g.clamp(0, 255) as f32;
+                    let bb = b.clamp(0, 255) as f32;
+                    buffer[idx] = (rr * src_a + buffer[idx] as f32 * inv_a)
+                        .round()
+                        .clamp(0.0, 255.0) as u8;
+                    buffer[idx + 1] = (gg * src_a + buffer[idx + 1] as f32 * inv_a)
+                        .round()
+                        .clamp(0.0, 255.0) as u8;
+                    buffer[idx + 2] = (bb * src_a + buffer[idx + 2] as f32 * inv_a)
+                        .round()
+                        .clamp(0.0, 255.0) as u8;
+                    buffer[idx + 3] = a as u8;
+                    idx += 4;
+                }
             }
         }
     } else if args_vec.len() >= 2 && args_vec.len() <= 3 {
@@ -1184,6 +1360,17 @@ fn rects_filled(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
             if xa >= xb || ya >= yb {
                 return;
             }
+            if transformed {
+                let color = (
+                    rr.round() as u8,
+                    gg.round() as u8,
+                    bb.round() as u8,
+                    (alpha * 255.0).round() as u8,
+                );
+                let (w, h) = ((xb - xa) as f32, (yb - ya) as f32);
+                local_rects.push((xa as f32, ya as f32, w, h, color));
+                return;
+            }
             let inv_a = 1.0 - alpha;
             for y in ya..yb {
                 let row_start = (y * width + xa) * 4;
@@ -1232,6 +1419,10 @@ fn rects_filled(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         )));
     }
 
+    if !local_rects.is_empty() {
+        fill_local_rects(vm, "rects_filled", &local_rects)?;
+    }
+
     Ok(vm.ctx.none())
 }
 
@@ -1298,6 +1489,10 @@ fn rectangles(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let flat = tensor_flat_data_list(boxes_obj, vm)?;
     let shape = tensor_shape_tuple(boxes_obj, vm).unwrap_or_default();
 
+    // Corners go through the transform like `lines()` endpoints; the stroke scales with it.
+    let transform = active_transform().unwrap_or_default();
+    let thickness = thickness * transform.mean_scale();
+
     let mut draw_box = |x1n: f32, y1n: f32, x2n: f32, y2n: f32| {
         let x1 = (x1n.clamp(0.0, 1.0) * width as f32) as f32;
         let y1 = (y1n.clamp(0.0, 1.0) * height as f32) as f32;
@@ -1306,10 +1501,12 @@ fn rectangles(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         let (xa, xb) = if x1 <= x2 { (x1, x2) } else { (x2, x1) };
         let (ya, yb) = if y1 <= y2 { (y1, y2) } else { (y2, y1) };
 
-        draw_line_direct(buffer, width, height, xa, ya, xb, ya, thickness, color);
-        draw_line_direct(buffer, width, height, xb, ya, xb, yb, thickness, color);
-        draw_line_direct(buffer, width, height, xb, yb, xa, yb, thickness, color);
-        draw_line_direct(buffer, width, height, xa, yb, xa, ya, thickness, color);
+        let corners = [(xa, ya), (xb, ya), (xb, yb), (xa, yb)].map(|(x, y)| transform.apply(x, y));
+        for i in 0..4 {
+            let (sx, sy) = corners[i];
+            let (ex, ey) = corners[(i + 1) % 4];
+            draw_line_direct(buffer, width, height, sx, sy, ex, ey, thickness, color);
+        }
     };
 
     if shape == vec![2, 2] && flat.len() >= 4 {
@@ -1348,7 +1545,12 @@ fn rectangles(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
 /// - font_size: font size in pixels
 /// - color: (r, g, b) or (r, g, b, a) tuple
 /// - max_width: optional maximum width for text wrapping (defaults to screen width)
+///
+/// Keyword-only (any of these switches to the SDF glyph atlas path):
+/// - outline=(width_px, color), glow=(radius_px, color), shadow=(dx, dy, blur_px, color)
+/// - sdf=True: render from the SDF atlas without effects (smooth for animated sizes)
 fn text(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let (use_sdf, effects) = parse_text_effects(&args, vm)?;
     let args_vec = args.args;
     if args_vec.len() < 5 || args_vec.len() > 6 {
         return Err(vm.new_type_error(format!(
@@ -1410,6 +1612,16 @@ fn text(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
         width as f32
     };
 
+    // Transformed text keeps upright glyphs: the origin moves and the size follows the scale.
+    let (x, y, font_size, max_width) = match active_transform() {
+        Some(t) => {
+            let (tx, ty) = t.apply(x as f32, y as f32);
+            let scale = t.mean_scale();
+            (tx as f64, ty as f64, font_size * scale as f64, max_width * scale)
+        }
+        None => (x, y, font_size, max_width),
+    };
+
     // Load font if not already loaded
     let mut font_lock = GLOBAL_FONT.lock().unwrap();
     let mut family_lock = GLOBAL_FONT_FAMILY.lock().unwrap();
@@ -1431,6 +1643,25 @@ fn text(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     // Draw characters to buffer
     let buffer = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, width * height * 4) };
 
+    if use_sdf {
+        let color = [
+            r.clamp(0, 255) as u8,
+            g.clamp(0, 255) as u8,
+            b.clamp(0, 255) as u8,
+            a.clamp(0, 255) as u8,
+        ];
+        sdf::draw_text_sdf(
+            buffer,
+            width,
+            height,
+            &rasterizer,
+            (x as f32, y as f32),
+            color,
+            &effects,
+        );
+        return Ok(vm.ctx.none());
+    }
+
     for character in &rasterizer.characters {
         let char_x = x as i32 + character.x as i32;
         let char_y = y as i32 + character.y as i32;
@@ -1624,8 +1855,69 @@ fn blit_rgba_resize_into_rect(
     }
 }
 
+/// Nearest-neighbour blit of `src` into the local rect `fit` (x, y, w, h) under transform `t`,
+/// letterboxing the rest of `bounds` in opaque black. Walks the device pixels covered by
+/// `bounds` and inverse-maps each centre, so rotations and flips leave no holes.
+/// Returns the device-space bounding box of `fit`.
+fn blit_rgba_affine(
+    src: &[u8],
+    (sw, sh): (usize, usize),
+    dst: &mut [u8],
+    (frame_w, frame_h): (usize, usize),
+    t: &Affine,
+    bounds: (f32, f32, f32, f32),
+    fit: (f32, f32, f32, f32),
+) -> (f32, f32, f32, f32) {
+    let device_box = |(x, y, w, h): (f32, f32, f32, f32)| {
+        let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h)].map(|(x, y)| t.apply(x, y));
+        let (mut x0, mut y0) = (f32::INFINITY, f32::INFINITY);
+        let (mut x1, mut y1) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for (cx, cy) in corners {
+            x0 = x0.min(cx);
+            y0 = y0.min(cy);
+            x1 = x1.max(cx);
+            y1 = y1.max(cy);
+        }
+        (x0, y0, x1 - x0, y1 - y0)
+    };
+    let fit_box = device_box(fit);
+    let Some(inv) = t.invert() else {
+        return fit_box;
+    };
+    if src.len() != sw * sh * 4 || sw == 0 || sh == 0 {
+        return fit_box;
+    }
+    let (bx, by, bw, bh) = device_box(bounds);
+    let x_range = (bx.floor().max(0.0) as usize)..((bx + bw).ceil().max(0.0) as usize).min(frame_w);
+    let y_range = (by.floor().max(0.0) as usize)..((by + bh).ceil().max(0.0) as usize).min(frame_h);
+    let (fx, fy, fw, fh) = fit;
+    let (ox, oy, ow, oh) = bounds;
+    for y in y_range {
+        for x in x_range.clone() {
+            let (lx, ly) = inv.apply(x as f32 + 0.5, y as f32 + 0.5);
+            if lx < ox || ly < oy || lx >= ox + ow || ly >= oy + oh {
+                continue;
+            }
+            let di = (y * frame_w + x) * 4;
+            if di + 3 >= dst.len() {
+                continue;
+            }
+            if lx < fx || ly < fy || lx >= fx + fw || ly >= fy + fh {
+                dst[di..di + 4].copy_from_slice(&[0, 0, 0, 255]);
+                continue;
+            }
+            let sx = (((lx - fx) / fw * sw as f32) as usize).min(sw - 1);
+            let sy = (((ly - fy) / fh * sh as f32) as usize).min(sh - 1);
+            let si = (sy * sw + sx) * 4;
+            dst[di..di + 4].copy_from_slice(&src[si..si + 4]);
+        }
+    }
+    fit_box
+}
+
 /// Aspect-fit an ``xos.Frame`` or ``xos.Tensor`` (uint8 RGBA) into a normalized sub-rectangle of the active framebuffer.
-/// Returns ``(fit_x, fit_y, fit_w, fit_h)`` in **pixel** coordinates for pointer mapping.
+/// Returns ``(fit_x, fit_y, fit_w, fit_h)`` in **pixel** coordinates for pointer mapping (the
+/// device-space bounding box of the fitted rect when a transform is active).
 fn frame_blit_aspect_fit_norm_rect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     let args_vec = args.args;
     if args_vec.len() != 5 {
@@ -1661,17 +1953,42 @@ fn frame_blit_aspect_fit_norm_rect(args: FuncArgs, vm: &VirtualMachine) -> PyRes
     let dst = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, dst_len) };
 
     let (bx0, by0, bw, bh) = norm_xyxy_to_px(nx1, ny1, nx2, ny2, nw_u32, nh_u32);
-    fill_rgba_rect(dst, fw, fh, bx0, by0, bw, bh, [0, 0, 0, 255]);
     let (ox, oy, fit_w, fit_h) = aspect_fit_wh(sw, sh, bw, bh);
     let ax0 = bx0.saturating_add(ox);
     let ay0 = by0.saturating_add(oy);
-    blit_rgba_resize_into_rect(src_px.as_slice(), sw, sh, dst, fw, fh, ax0, ay0, fit_w, fit_h);
+    let placed = match active_transform() {
+        Some(t) => blit_rgba_affine(
+            src_px.as_slice(),
+            (sw, sh),
+            dst,
+            (fw, fh),
+            &t,
+            (bx0 as f32, by0 as f32, bw as f32, bh as f32),
+            (ax0 as f32, ay0 as f32, fit_w as f32, fit_h as f32),
+        ),
+        None => {
+            fill_rgba_rect(dst, fw, fh, bx0, by0, bw, bh, [0, 0, 0, 255]);
+            blit_rgba_resize_into_rect(
+                src_px.as_slice(),
+                sw,
+                sh,
+                dst,
+                fw,
+                fh,
+                ax0,
+                ay0,
+                fit_w,
+                fit_h,
+            );
+            (ax0 as f32, ay0 as f32, fit_w as f32, fit_h as f32)
+        }
+    };
 
     let tup = vm.ctx.new_tuple(vec![
-        vm.ctx.new_float(ax0 as f64).into(),
-        vm.ctx.new_float(ay0 as f64).into(),
-        vm.ctx.new_float(fit_w as f64).into(),
-        vm.ctx.new_float(fit_h as f64).into(),
+        vm.ctx.new_float(placed.0 as f64).into(),
+        vm.ctx.new_float(placed.1 as f64).into(),
+        vm.ctx.new_float(placed.2 as f64).into(),
+        vm.ctx.new_float(placed.3 as f64).into(),
     ]);
     Ok(tup.into())
 }
@@ -1751,6 +2068,360 @@ fn frame_in_frame(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     Ok(vm.ctx.none())
 }
 
+/// `(r, g, b)` or `(r, g, b, a)` tuple → clamped RGBA8.
+fn parse_rgba_color(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<(u8, u8, u8, u8)> {
+    let color_obj = obj
+        .downcast_ref::<PyTuple>()
+        .ok_or_else(|| vm.new_type_error("color must be a tuple".to_string()))?;
+    let c = color_obj.as_slice();
+    if c.len() != 3 && c.len() != 4 {
+        return Err(vm.new_type_error("color must be (r, g, b) or (r, g, b, a)".to_string()));
+    }
+    let r: i32 = c[0].clone().try_into_value(vm)?;
+    let g: i32 = c[1].clone().try_into_value(vm)?;
+    let b: i32 = c[2].clone().try_into_value(vm)?;
+    let a: i32 = if c.len() == 4 {
+        c[3].clone().try_into_value(vm)?
+    } else {
+        255
+    };
+    Ok((
+        r.clamp(0, 255) as u8,
+        g.clamp(0, 255) as u8,
+        b.clamp(0, 255) as u8,
+        a.clamp(0, 255) as u8,
+    ))
+}
+
+/// `text()` keyword effects → `(use_sdf, effects)`.
+fn parse_text_effects(args: &FuncArgs, vm: &VirtualMachine) -> PyResult<(bool, TextEffects)> {
+    let tuple_items = |key: &str, arity: usize| -> PyResult<Option<Vec<PyObjectRef>>> {
+        let Some(obj) = args.kwargs.get(key) else {
+            return Ok(None);
+        };
+        if vm.is_none(obj) {
+            return Ok(None);
+        }
+        let items = obj
+            .downcast_ref::<PyTuple>()
+            .map(|t| t.as_slice().to_vec())
+            .filter(|items| items.len() == arity)
+            .ok_or_else(|| vm.new_type_error(format!("{key} must be a tuple of {arity} values")))?;
+        Ok(Some(items))
+    };
+    let rgba = |obj: &PyObjectRef| -> PyResult<[u8; 4]> {
+        let (r, g, b, a) = parse_rgba_color(obj, vm)?;
+        Ok([r, g, b, a])
+    };
+
+    let mut effects = TextEffects::default();
+    if let Some(items) = tuple_items("outline", 2)? {
+        effects.outline = Some((
+            py_number_to_f32(items[0].clone(), vm, "outline width")?,
+            rgba(&items[1])?,
+        ));
+    }
+    if let Some(items) = tuple_items("glow", 2)? {
+        effects.glow = Some((
+            py_number_to_f32(items[0].clone(), vm, "glow radius")?,
+            rgba(&items[1])?,
+        ));
+    }
+    if let Some(items) = tuple_items("shadow", 4)? {
+        effects.shadow = Some((
+            py_number_to_f32(items[0].clone(), vm, "shadow dx")?,
+            py_number_to_f32(items[1].clone(), vm, "shadow dy")?,
+            py_number_to_f32(items[2].clone(), vm, "shadow blur")?,
+            rgba(&items[3])?,
+        ));
+    }
+    let sdf_requested = args
+        .kwargs
+        .get("sdf")
+        .and_then(|v| v.clone().try_into_value::<bool>(vm).ok())
+        .unwrap_or(false);
+    Ok((sdf_requested || effects != TextEffects::default(), effects))
+}
+
+/// List of `(x, y)` tuples or an `(N, 2)` tensor → points.
+fn parse_points(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<(f32, f32)>> {
+    if let Some(list) = obj.downcast_ref::<PyList>() {
+        let items = list.borrow_vec();
+        let mut out = Vec::with_capacity(items.len());
+        for item in items.iter() {
+            let t = item
+                .downcast_ref::<PyTuple>()
+                .ok_or_else(|| vm.new_type_error("each point must be (x, y)".to_string()))?;
+            let xy = t.as_slice();
+            if xy.len() != 2 {
+                return Err(vm.new_type_error("each point must be (x, y)".to_string()));
+            }
+            out.push((
+                py_number_to_f32(xy[0].clone(), vm, "x")?,
+                py_number_to_f32(xy[1].clone(), vm, "y")?,
+            ));
+        }
+        return Ok(out);
+    }
+    let flat = tensor_flat_data_list(obj, vm)?;
+    let shape = tensor_shape_tuple(obj, vm)?;
+    if shape.len() != 2 || shape[1] != 2 {
+        return Err(vm.new_type_error("points tensor must be shape (N, 2)".to_string()));
+    }
+    Ok(flat.chunks_exact(2).map(|p| (p[0], p[1])).collect())
+}
+
+fn number_args<const N: usize>(
+    args: &[PyObjectRef],
+    vm: &VirtualMachine,
+    names: [&str; N],
+) -> PyResult<[f32; N]> {
+    let mut out = [0.0f32; N];
+    for (i, name) in names.iter().enumerate() {
+        let v = args
+            .get(i)
+            .ok_or_else(|| vm.new_type_error(format!("missing argument '{name}'")))?;
+        out[i] = py_number_to_f32(v.clone(), vm, name)?;
+    }
+    Ok(out)
+}
+
+/// xos.rasterizer.save() — push the transform / clip state (drawing calls are unaffected until
+/// `translate` / `scale` / `rotate` / `clip_*` / `save_layer` change it).
+fn canvas_save(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    with_canvas(vm, "save", |canvas, _| canvas.save())?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.restore() — pop the last `save()` / `save_layer()`, compositing its layers.
+/// Returns `False` when the stack was already empty.
+fn canvas_restore(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let popped = with_canvas(vm, "restore", |canvas, frame| canvas.restore(frame))?;
+    Ok(vm.ctx.new_bool(popped).into())
+}
+
+/// xos.rasterizer.translate(dx, dy) — pixel offset applied before the current transform.
+fn canvas_translate(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let [dx, dy] = number_args(&args.args, vm, ["dx", "dy"])?;
+    with_canvas(vm, "translate", |canvas, _| canvas.translate(dx, dy))?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.scale(sx, sy=sx)
+fn canvas_scale(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let [sx] = number_args(&args.args, vm, ["sx"])?;
+    let sy = match args.args.get(1) {
+        Some(v) => py_number_to_f32(v.clone(), vm, "sy")?,
+        None => sx,
+    };
+    with_canvas(vm, "scale", |canvas, _| canvas.scale(sx, sy))?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.rotate(radians) — clockwise on screen (y points down).
+fn canvas_rotate(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let [radians] = number_args(&args.args, vm, ["radians"])?;
+    with_canvas(vm, "rotate", |canvas, _| canvas.rotate(radians))?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.reset_transform()
+fn canvas_reset_transform(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    with_canvas(vm, "reset_transform", |canvas, _| canvas.reset_transform())?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.get_transform() -> (a, b, c, d, e, f) with `x' = a·x + c·y + e`, `y' = b·x + d·y + f`.
+fn canvas_get_transform(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let t = with_canvas(vm, "get_transform", |canvas, _| canvas.transform())?;
+    let items: Vec<PyObjectRef> = [t.a, t.b, t.c, t.d, t.e, t.f]
+        .iter()
+        .map(|v| vm.ctx.new_float(*v as f64).into())
+        .collect();
+    Ok(vm.ctx.new_tuple(items).into())
+}
+
+/// xos.rasterizer.clip_rect(x, y, w, h) — local-space rectangle; everything drawn until the
+/// matching `restore()` is confined to it (including `rects_filled`, `text`, `blur`, …).
+fn canvas_clip_rect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let [x, y, w, h] = number_args(&args.args, vm, ["x", "y", "w", "h"])?;
+    with_canvas(vm, "clip_rect", |canvas, frame| {
+        canvas.clip_rect(frame, x, y, w, h)
+    })?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.clip_path(points) — local-space polygon (`[(x, y), …]` or `(N, 2)` tensor).
+fn canvas_clip_path(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let points_obj = args
+        .args
+        .first()
+        .ok_or_else(|| vm.new_type_error("clip_path(points) expects 1 argument".to_string()))?;
+    let path = Path::polygon(&parse_points(points_obj, vm)?);
+    with_canvas(vm, "clip_path", |canvas, frame| canvas.clip_path(frame, &path))?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.save_layer(opacity=1.0) — `save()` plus an offscreen layer that is composited
+/// with `opacity` on the matching `restore()`.
+fn canvas_save_layer(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let opacity = match args.args.first() {
+        Some(v) => py_number_to_f32(v.clone(), vm, "opacity")?,
+        None => 1.0,
+    };
+    with_canvas(vm, "save_layer", |canvas, _| canvas.save_layer(opacity))?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.fill_rect(frame, x, y, w, h, color) — local-space rectangle through the transform.
+fn canvas_fill_rect(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let args_vec = args.args;
+    if args_vec.len() != 6 {
+        return Err(vm.new_type_error(format!(
+            "fill_rect() takes exactly 6 arguments ({} given)",
+            args_vec.len()
+        )));
+    }
+    check_frame_arg(&args_vec[0], vm, "fill_rect")?;
+    let [x, y, w, h] = number_args(&args_vec[1..5], vm, ["x", "y", "w", "h"])?;
+    let color = parse_rgba_color(&args_vec[5], vm)?;
+    with_canvas(vm, "fill_rect", |canvas, frame| {
+        canvas.fill_rect(frame, x, y, w, h, color)
+    })?;
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.fill_path(frame, points, color) — antialiased local-space polygon fill.
+fn canvas_fill_path(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let args_vec = args.args;
+    if args_vec.len() != 3 {
+        return Err(vm.new_type_error(format!(
+            "fill_path() takes exactly 3 arguments ({} given)",
+            args_vec.len()
+        )));
+    }
+    check_frame_arg(&args_vec[0], vm, "fill_path")?;
+    let path = Path::polygon(&parse_points(&args_vec[1], vm)?);
+    let color = parse_rgba_color(&args_vec[2], vm)?;
+    with_canvas(vm, "fill_path", |canvas, frame| {
+        canvas.fill_path(frame, &path, color)
+    })?;
+    Ok(vm.ctx.none())
+}
+
+/// Optional string from `args[idx]` or `kwargs[key]` (`None` → missing).
+fn optional_str_arg(
+    args: &FuncArgs,
+    idx: usize,
+    key: &str,
+    vm: &VirtualMachine,
+) -> PyResult<Option<String>> {
+    let obj = args
+        .args
+        .get(idx)
+        .cloned()
+        .or_else(|| args.kwargs.get(key).cloned());
+    match obj {
+        Some(o) if !vm.is_none(&o) => Ok(Some(o.try_into_value(vm)?)),
+        _ => Ok(None),
+    }
+}
+
+fn family_by_name_py(name: &str, vm: &VirtualMachine) -> PyResult<FontFamily> {
+    fonts::family_by_name(name).ok_or_else(|| {
+        let known: Vec<String> = fonts::all_families().iter().map(|f| f.label()).collect();
+        vm.new_value_error(format!(
+            "unknown font '{name}' (known: {})",
+            known.join(", ")
+        ))
+    })
+}
+
+/// xos.rasterizer.load_font(path, name=None) — register a TTF/OTF file. When `path` is not a file it is
+/// treated as a system font name (see `system_fonts()`). Returns the registered family name.
+fn load_font(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let Some(path) = optional_str_arg(&args, 0, "path", vm)? else {
+        return Err(vm.new_type_error("load_font() requires path".to_string()));
+    };
+    let name = optional_str_arg(&args, 1, "name", vm)?;
+    let file = std::path::Path::new(&path);
+    let family = if file.is_file() {
+        fonts::load_font_file(file, name.as_deref())
+    } else {
+        fonts::load_system_font(&path, name.as_deref())
+    }
+    .map_err(|e| vm.new_runtime_error(e))?;
+    Ok(vm.ctx.new_str(family.label()).into())
+}
+
+/// xos.rasterizer.fonts() — names usable with `set_default_font` / `set_font_fallbacks`.
+fn list_fonts(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let names = fonts::all_families()
+        .into_iter()
+        .map(|f| vm.ctx.new_str(f.label()).into())
+        .collect();
+    Ok(vm.ctx.new_list(names).into())
+}
+
+/// xos.rasterizer.system_fonts() — `[(name, path), ...]` found in the system font directories.
+fn list_system_fonts(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let items = fonts::scan_system_fonts()
+        .into_iter()
+        .map(|f| {
+            vm.ctx
+                .new_tuple(vec![
+                    vm.ctx.new_str(f.name).into(),
+                    vm.ctx.new_str(f.path.to_string_lossy().into_owned()).into(),
+                ])
+                .into()
+        })
+        .collect();
+    Ok(vm.ctx.new_list(items).into())
+}
+
+/// xos.rasterizer.set_default_font(name) — same as picking the family in the F3 menu.
+fn set_default_font(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let Some(name) = optional_str_arg(&args, 0, "name", vm)? else {
+        return Err(vm.new_type_error("set_default_font() requires name".to_string()));
+    };
+    fonts::set_default_font_family(family_by_name_py(&name, vm)?);
+    Ok(vm.ctx.none())
+}
+
+/// xos.rasterizer.set_font_fallbacks(chain, family=None) — fonts tried in order for glyphs the primary
+/// lacks. Without `family` the chain applies to every family that has no chain of its own.
+fn set_font_fallbacks(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let chain_obj = args
+        .args
+        .first()
+        .cloned()
+        .or_else(|| args.kwargs.get("chain").cloned())
+        .ok_or_else(|| vm.new_type_error("set_font_fallbacks() requires chain".to_string()))?;
+    let names: Vec<String> = if let Some(list) = chain_obj.downcast_ref::<PyList>() {
+        list.borrow_vec()
+            .iter()
+            .map(|o| o.clone().try_into_value(vm))
+            .collect::<PyResult<_>>()?
+    } else if let Some(tuple) = chain_obj.downcast_ref::<PyTuple>() {
+        tuple
+            .as_slice()
+            .iter()
+            .map(|o| o.clone().try_into_value(vm))
+            .collect::<PyResult<_>>()?
+    } else {
+        return Err(vm.new_type_error("chain must be a list of font names".to_string()));
+    };
+    let chain = names
+        .iter()
+        .map(|n| family_by_name_py(n, vm))
+        .collect::<PyResult<Vec<_>>>()?;
+    match optional_str_arg(&args, 1, "family", vm)? {
+        Some(family) => fonts::set_fallback_chain(family_by_name_py(&family, vm)?, chain),
+        None => fonts::set_default_fallbacks(chain),
+    }
+    Ok(vm.ctx.none())
+}
+
 pub fn make_rasterizer_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let module = vm.new_module("xos.rasterizer", vm.ctx.new_dict(), None);
     module
@@ -1816,4 +2487,129 @@ pub fn make_rasterizer_module(vm: &VirtualMachine) -> PyRef<PyModule> {
         .set_attr("blur", vm.new_function("blur", blur_framebuffer), vm)
         .unwrap();
     module
+        .set_attr("save", vm.new_function("save", canvas_save), vm)
+        .unwrap();
+    module
+        .set_attr("restore", vm.new_function("restore", canvas_restore), vm)
+        .unwrap();
+    module
+        .set_attr("translate", vm.new_function("translate", canvas_translate), vm)
+        .unwrap();
+    module
+        .set_attr("scale", vm.new_function("scale", canvas_scale), vm)
+        .unwrap();
+    module
+        .set_attr("rotate", vm.new_function("rotate", canvas_rotate), vm)
+        .unwrap();
+    module
+        .set_attr("reset_transform", vm.new_function("reset_transform", canvas_reset_transform), vm)
+        .unwrap();
+    module
+        .set_attr("get_transform", vm.new_function("get_transform", canvas_get_transform), vm)
+        .unwrap();
+    module
+        .set_attr("clip_rect", vm.new_function("clip_rect", canvas_clip_rect), vm)
+        .unwrap();
+    module
+        .set_attr("clip_path", vm.new_function("clip_path", canvas_clip_path), vm)
+        .unwrap();
+    module
+        .set_attr("save_layer", vm.new_function("save_layer", canvas_save_layer), vm)
+        .unwrap();
+    module
+        .set_attr("fill_rect", vm.new_function("fill_rect", canvas_fill_rect), vm)
+        .unwrap();
+    module
+        .set_attr("fill_path", vm.new_function("fill_path", canvas_fill_path), vm)
+        .unwrap();
+    module
+        .set_attr("load_font", vm.new_function("load_font", load_font), vm)
+        .unwrap();
+    module
+        .set_attr("fonts", vm.new_function("fonts", list_fonts), vm)
+        .unwrap();
+    module
+        .set_attr("system_fonts", vm.new_function("system_fonts", list_system_fonts), vm)
+        .unwrap();
+    module
+        .set_attr("set_default_font", vm.new_function("set_default_font", set_default_font), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "set_font_fallbacks",
+            vm.new_function("set_font_fallbacks", set_font_fallbacks),
+            vm,
+        )
+        .unwrap();
+    module
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustpython_vm::Interpreter;
+
+    fn px(buf: &[u8], w: usize, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * w + x) * 4;
+        [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]
+    }
+
+    #[test]
+    fn primitives_follow_the_canvas_transform() {
+        let (w, h) = (16usize, 16usize);
+        let mut buf = vec![0u8; w * h * 4];
+        Interpreter::without_stdlib(Default::default()).enter(|vm| {
+            let num = |v: f64| -> PyObjectRef { vm.ctx.new_float(v).into() };
+            let int = |v: i64| -> PyObjectRef { vm.ctx.new_int(v).into() };
+            let rgba = |c: [i64; 4]| -> PyObjectRef {
+                vm.ctx.new_tuple(c.iter().map(|&v| int(v)).collect()).into()
+            };
+            let frame_of = |fw: usize, fh: usize| -> PyObjectRef {
+                let d = vm.ctx.new_dict();
+                d.set_item("width", vm.ctx.new_int(fw).into(), vm).unwrap();
+                d.set_item("height", vm.ctx.new_int(fh).into(), vm).unwrap();
+                d.into()
+            };
+            let frame = frame_of(w, h);
+            set_frame_buffer_context(&mut buf, w, h);
+
+            // fill() under a translate covers the shifted frame rectangle only.
+            canvas_translate(FuncArgs::from(vec![num(8.0), num(8.0)]), vm).unwrap();
+            fill(FuncArgs::from(vec![frame.clone(), rgba([0, 0, 255, 255])]), vm).unwrap();
+            canvas_reset_transform(FuncArgs::from(vec![]), vm).unwrap();
+
+            // Local (1, 1)–(3, 2) under translate(4, 2) · scale(2) lands on x 6..10, y 4..6.
+            canvas_translate(FuncArgs::from(vec![num(4.0), num(2.0)]), vm).unwrap();
+            canvas_scale(FuncArgs::from(vec![num(2.0)]), vm).unwrap();
+            let red = rgba([255, 0, 0, 255]);
+            let args = vec![frame.clone(), int(1), int(1), int(3), int(2), red];
+            rects_filled(FuncArgs::from(args), vm).unwrap();
+            let args = vec![frame.clone(), num(0.0), num(0.0), num(1.0), num(1.0)];
+            let green = rgba([0, 255, 0, 255]);
+            canvas_fill_rect(FuncArgs::from([args, vec![green.clone()]].concat()), vm).unwrap();
+
+            // A frame that is not the one being drawn is rejected rather than ignored.
+            let wrong = vec![frame_of(8, 8), num(0.0), num(0.0), num(1.0), num(1.0), green];
+            assert!(canvas_fill_rect(FuncArgs::from(wrong), vm).is_err());
+            clear_frame_buffer_context();
+        });
+
+        let (blue, red, green) = ([0, 0, 255, 255], [255, 0, 0, 255], [0, 255, 0, 255]);
+        assert_eq!(px(&buf, w, 12, 12), blue);
+        assert_eq!(px(&buf, w, 8, 8), blue);
+        assert_ne!(px(&buf, w, 7, 12), blue);
+        assert_ne!(px(&buf, w, 0, 0), blue);
+
+        assert_eq!(px(&buf, w, 6, 4), red);
+        assert_eq!(px(&buf, w, 9, 5), red);
+        assert_ne!(px(&buf, w, 10, 5), red);
+        assert_ne!(px(&buf, w, 5, 4), red);
+        assert_ne!(px(&buf, w, 6, 3), red);
+        assert_ne!(px(&buf, w, 6, 6), red);
+
+        assert_eq!(px(&buf, w, 4, 2), green);
+        assert_eq!(px(&buf, w, 5, 3), green);
+        assert_ne!(px(&buf, w, 6, 2), green);
+        assert_ne!(px(&buf, w, 1, 1), green);
+    }
 }
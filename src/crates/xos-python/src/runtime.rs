@@ -6,6 +6,12 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use xos_core::engine::capture::CaptureConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use xos_core::engine::capture::{request_capture, CaptureCommand};
+use xos_core::engine::replay::InputCapture;
+#[cfg(not(target_arch = "wasm32"))]
+use xos_core::engine::window_control::{request_window_command, WindowCommand};
 
 /// `--long-name` → `long_name`; only ASCII letters, digits, underscore after mapping.
 pub(crate) fn cli_flag_to_snake_name(flag: &str) -> Option<String> {
@@ -377,6 +383,70 @@ pub fn run_python_interactive() {
 
 /// Run a Python application with the xos engine
 pub fn run_python_app(file_path: &PathBuf, script_flags: &[String]) {
+    run_python_app_with_options(file_path, script_flags, PythonAppOptions::default());
+}
+
+/// Extra `xpy` run modes layered on [`run_python_app`].
+#[derive(Default)]
+pub struct PythonAppOptions {
+    /// `--record-input` / `--replay`.
+    pub input: Option<InputCapture>,
+    /// `--capture`: runs the app headless and records its frames.
+    pub capture: Option<CaptureConfig>,
+    /// `--size WxH`: the headless frame size (default: the replay's size, else 800x600), or the
+    /// window's initial inner size.
+    pub size: Option<(u32, u32)>,
+}
+
+/// Frame size for a headless run: `--size`, else the replay's recorded size, else 800x600.
+#[cfg(not(target_arch = "wasm32"))]
+fn headless_frame_size(size: Option<(u32, u32)>, input: Option<&InputCapture>) -> (u32, u32) {
+    match (size, input) {
+        (Some(size), _) => size,
+        (None, Some(InputCapture::Replay(log))) => (log.header.width, log.header.height),
+        _ => (800, 600),
+    }
+}
+
+/// Queue `--size` for the main window; it is applied on the first frame, before the app's own
+/// `xos.window` calls, so `setup` can still override it.
+#[cfg(not(target_arch = "wasm32"))]
+fn request_initial_window_size(size: Option<(u32, u32)>) {
+    if let Some((width, height)) = size {
+        request_window_command(WindowCommand::SetSize { width, height });
+    }
+}
+
+/// Wrap the app for `--record-input` / `--replay`, exiting on a bad recording path.
+#[cfg(not(target_arch = "wasm32"))]
+fn wrap_input_capture(
+    app: Box<dyn xos_core::engine::Application>,
+    input: Option<InputCapture>,
+    headless: bool,
+) -> Box<dyn xos_core::engine::Application> {
+    let Some(input) = input else {
+        return app;
+    };
+    match input.wrap(app, headless) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// [`run_python_app`] with input record / replay, frame capture, or a fixed headless size.
+pub fn run_python_app_with_options(
+    file_path: &PathBuf,
+    script_flags: &[String],
+    options: PythonAppOptions,
+) {
+    let PythonAppOptions {
+        input,
+        capture,
+        size,
+    } = options;
     #[cfg(not(target_arch = "wasm32"))]
     use crate::engine::pyapp::PyApp;
     #[cfg(not(target_arch = "wasm32"))]
@@ -404,13 +474,15 @@ pub fn run_python_app(file_path: &PathBuf, script_flags: &[String]) {
     });
 
     #[cfg(not(target_arch = "wasm32"))]
-    if !source_declares_headless_window_app(&code) {
-        match xos_core::engine::start_native(Box::new(StagedNativePythonApp::new(
+    if capture.is_none() && !source_declares_headless_window_app(&code) {
+        let app = Box::new(StagedNativePythonApp::new(
             resolved_file_path.clone(),
             code.clone(),
             script_flags.to_vec(),
             print_cb.clone(),
-        ))) {
+        ));
+        request_initial_window_size(size);
+        match xos_core::engine::start_native(wrap_input_capture(app, input, false)) {
             Ok(()) => return,
             Err(e) => {
                 eprintln!("❌ Engine error: {e}");
@@ -449,13 +521,15 @@ pub fn run_python_app(file_path: &PathBuf, script_flags: &[String]) {
         }
 
         if let Some(app_instance) = app_instance {
-            let headless = interpreter.enter(|vm| {
-                vm.get_attribute_opt(app_instance.clone(), "headless")
-                    .ok()
-                    .flatten()
-                    .and_then(|obj| obj.try_into_value::<bool>(vm).ok())
-                    .unwrap_or(false)
-            });
+            // Capture always runs offscreen so frames come out at a steady, known size.
+            let headless = capture.is_some()
+                || interpreter.enter(|vm| {
+                    vm.get_attribute_opt(app_instance.clone(), "headless")
+                        .ok()
+                        .flatten()
+                        .and_then(|obj| obj.try_into_value::<bool>(vm).ok())
+                        .unwrap_or(false)
+                });
 
             if headless {
                 interpreter.enter(|vm| {
@@ -463,11 +537,20 @@ pub fn run_python_app(file_path: &PathBuf, script_flags: &[String]) {
                 });
             }
 
-            let pyapp = PyApp::new(interpreter, app_instance);
+            let (width, height) = headless_frame_size(size, input.as_ref());
+            if let Some(config) = capture {
+                request_capture(CaptureCommand::Start(config));
+            }
+            let app = wrap_input_capture(
+                Box::new(PyApp::new(interpreter, app_instance)),
+                input,
+                headless,
+            );
             let result = if headless {
-                xos_core::engine::start_headless_native(Box::new(pyapp), 800, 600)
+                xos_core::engine::start_headless_native(app, width, height)
             } else {
-                xos_core::engine::start_native(Box::new(pyapp))
+                request_initial_window_size(size);
+                xos_core::engine::start_native(app)
             };
             if let Err(e) = result {
                 eprintln!("❌ Engine error: {}", e);
@@ -503,8 +586,37 @@ pub fn run_python_app(file_path: &PathBuf, script_flags: &[String]) {
             std::process::exit(1);
         }
 
-        let _ = (interpreter, app_instance, output);
+        let _ = (interpreter, app_instance, output, input, capture, size);
         eprintln!("❌ WASM not supported for Python apps yet");
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xos_core::engine::replay::ReplayLog;
+    use xos_core::engine::window_control::WindowControl;
+
+    #[test]
+    fn size_flag_sets_the_headless_frame_or_the_window() {
+        let header = r#"{"t":0,"ev":"header","version":2,"width":320,"height":240}"#;
+        let replay = InputCapture::Replay(ReplayLog::parse([header]).unwrap());
+        assert_eq!(headless_frame_size(None, None), (800, 600));
+        assert_eq!(headless_frame_size(None, Some(&replay)), (320, 240));
+        assert_eq!(
+            headless_frame_size(Some((1280, 720)), Some(&replay)),
+            (1280, 720)
+        );
+
+        request_initial_window_size(None);
+        request_initial_window_size(Some((640, 360)));
+        assert_eq!(
+            WindowControl::default().take_commands(true),
+            vec![WindowCommand::SetSize {
+                width: 640,
+                height: 360
+            }]
+        );
+    }
+}
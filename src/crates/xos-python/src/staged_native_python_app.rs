@@ -6,8 +6,11 @@ use std::sync::Arc;
 
 use rustpython_vm::Interpreter;
 
+use xos_core::engine::gamepad::GamepadEvent;
 use xos_core::engine::{Application, EngineState, ScrollWheelUnit};
+use xos_core::engine::keyboard::keys::KeyEvent;
 use xos_core::engine::keyboard::shortcuts::{ShortcutAction, SpecialKeyEvent};
+use xos_core::engine::pointer::{GestureEvent, PointerEvent};
 use crate::engine::pyapp::PyApp;
 use crate::runtime::{execute_python_code, PrintCallback};
 
@@ -186,15 +189,81 @@ impl Application for StagedNativePythonApp {
         }
     }
 
+    fn on_pointer_down(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_pointer_down(state, event);
+        }
+    }
+
+    fn on_pointer_move(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_pointer_move(state, event);
+        }
+    }
+
+    fn on_pointer_up(&mut self, state: &mut EngineState, event: &PointerEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_pointer_up(state, event);
+        }
+    }
+
+    fn on_gesture(&mut self, state: &mut EngineState, gesture: &GestureEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_gesture(state, gesture);
+        }
+    }
+
+    fn on_key_down(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_key_down(state, event);
+        }
+    }
+
+    fn on_key_up(&mut self, state: &mut EngineState, event: &KeyEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_key_up(state, event);
+        }
+    }
+
     fn on_key_shortcut(&mut self, state: &mut EngineState, shortcut: ShortcutAction) {
         if let Some(inner) = &mut self.inner {
             inner.on_key_shortcut(state, shortcut);
         }
     }
 
+    fn on_gamepad(&mut self, state: &mut EngineState, event: &GamepadEvent) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_gamepad(state, event);
+        }
+    }
+
+    fn on_file_hover(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_file_hover(state, paths);
+        }
+    }
+
+    fn on_file_hover_cancel(&mut self, state: &mut EngineState) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_file_hover_cancel(state);
+        }
+    }
+
+    fn on_file_drop(&mut self, state: &mut EngineState, paths: &[PathBuf]) {
+        if let Some(inner) = &mut self.inner {
+            inner.on_file_drop(state, paths);
+        }
+    }
+
     fn on_screen_size_change(&mut self, state: &mut EngineState, width: u32, height: u32) {
         if let Some(inner) = &mut self.inner {
             inner.on_screen_size_change(state, width, height);
         }
     }
+
+    fn render(&mut self, state: &mut EngineState) {
+        if let Some(inner) = &mut self.inner {
+            inner.render(state);
+        }
+    }
 }
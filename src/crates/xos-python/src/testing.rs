@@ -0,0 +1,437 @@
+//! `xos.testing` and the `xos test` runner: golden-image tests for Python apps and widgets.
+//!
+//! A test file defines `test_*(t)` functions. Each one starts an app on `t`, scripts input and
+//! frames, and names the frames to compare:
+//!
+//! ```python
+//! def test_counter(t):
+//!     t.start(Counter(), 320, 240)
+//!     t.frames(2)
+//!     t.click(100, 50)
+//!     t.frames(1)
+//!     t.expect("clicked")
+//! ```
+//!
+//! The steps are recorded first, then played through [`AppHarness`] on the real engine path
+//! (`setup`, input routing, `tick`, `render`). Goldens live in `golden/<file stem>/` next to the
+//! test file.
+
+#[cfg(not(target_arch = "wasm32"))]
+use rustpython_vm::builtins::PyList;
+use rustpython_vm::builtins::PyModule;
+#[cfg(not(target_arch = "wasm32"))]
+use rustpython_vm::{PyObjectRef, PyResult};
+use rustpython_vm::{PyRef, VirtualMachine};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use xos_core::engine::golden::{GoldenOutcome, GoldenStore, Tolerance};
+#[cfg(not(target_arch = "wasm32"))]
+use xos_core::engine::harness::{AppHarness, HarnessStep};
+
+const TESTING_PY_CODE: &str = r#"
+class Script:
+    """Input and frame script for one visual test (the ``t`` passed to ``test_*(t)``).
+    Nothing runs until the test function returns; ``xos test`` then plays the steps
+    against the app with a fixed 1/60 s per frame."""
+
+    def __init__(self, name):
+        self.name = name
+        self.app = None
+        self.width = 320
+        self.height = 240
+        self.steps = []
+
+    def start(self, app, width=320, height=240):
+        """Run ``app`` (an ``xos.Application``) headless at ``width`` x ``height``."""
+        self.app = app
+        self.width = int(width)
+        self.height = int(height)
+        return self
+
+    def _add(self, *step):
+        self.steps.append(list(step))
+        return self
+
+    def frames(self, count=1):
+        """Advance ``count`` frames (ticks + render)."""
+        return self._add("frames", int(count))
+
+    def mouse_move(self, x, y):
+        return self._add("mouse_move", float(x), float(y))
+
+    def mouse_down(self):
+        return self._add("mouse_down")
+
+    def mouse_up(self):
+        return self._add("mouse_up")
+
+    def click(self, x, y):
+        return self._add("click", float(x), float(y))
+
+    def drag(self, x0, y0, x1, y1, steps=8):
+        """Press at ``(x0, y0)``, move to ``(x1, y1)`` over ``steps`` frames, release."""
+        self.mouse_move(x0, y0).mouse_down()
+        steps = max(1, int(steps))
+        for i in range(1, steps + 1):
+            f = i / steps
+            self.mouse_move(x0 + (x1 - x0) * f, y0 + (y1 - y0) * f).frames(1)
+        return self.mouse_up()
+
+    def scroll(self, dx, dy):
+        return self._add("scroll", float(dx), float(dy))
+
+    def key(self, name):
+        """Press and release a key by name (``"enter"``, ``"left"``, ``"a"``)."""
+        return self._add("key", str(name))
+
+    def type(self, text):
+        return self._add("type", str(text))
+
+    def resize(self, width, height):
+        return self._add("resize", int(width), int(height))
+
+    def expect(self, name, threshold=None, max_diff_ratio=None):
+        """Compare the current frame with ``golden/<file>/<name>.png``. ``threshold`` is the
+        per-pixel perceptual tolerance (0..1, default 0.1); ``max_diff_ratio`` the share of
+        pixels allowed past it (default 0)."""
+        return self._add(
+            "expect",
+            str(name),
+            None if threshold is None else float(threshold),
+            None if max_diff_ratio is None else float(max_diff_ratio),
+        )
+"#;
+
+/// Create the testing module
+pub fn make_testing_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.testing", vm.ctx.new_dict(), None);
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), TESTING_PY_CODE, "<testing>".to_string()) {
+        eprintln!("Failed to create Script class: {:?}", e);
+        return module;
+    }
+    if let Ok(obj) = scope.globals.get_item("Script", vm) {
+        module.set_attr("Script", obj, vm).unwrap();
+    }
+    module
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn step_arg<T: rustpython_vm::TryFromObject>(
+    vm: &VirtualMachine,
+    items: &[PyObjectRef],
+    index: usize,
+) -> PyResult<T> {
+    items
+        .get(index)
+        .cloned()
+        .ok_or_else(|| vm.new_value_error(format!("step is missing argument {}", index)))?
+        .try_into_value(vm)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn step_opt(vm: &VirtualMachine, items: &[PyObjectRef], index: usize) -> PyResult<Option<f32>> {
+    match items.get(index) {
+        Some(obj) if !vm.is_none(obj) => Ok(Some(obj.clone().try_into_value::<f64>(vm)? as f32)),
+        _ => Ok(None),
+    }
+}
+
+/// `Script.steps` → harness steps.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_steps(vm: &VirtualMachine, steps: &PyObjectRef) -> PyResult<Vec<HarnessStep>> {
+    let list = steps
+        .downcast_ref::<PyList>()
+        .ok_or_else(|| vm.new_type_error("Script.steps must be a list".to_string()))?;
+    let mut out = Vec::new();
+    for step in list.borrow_vec().iter() {
+        let items = step
+            .downcast_ref::<PyList>()
+            .ok_or_else(|| vm.new_type_error("each step must be a list".to_string()))?
+            .borrow_vec()
+            .to_vec();
+        let op: String = step_arg(vm, &items, 0)?;
+        let f = |i| step_arg::<f64>(vm, &items, i).map(|v| v as f32);
+        out.push(match op.as_str() {
+            "frames" => HarnessStep::Frames(step_arg(vm, &items, 1)?),
+            "mouse_move" => HarnessStep::MouseMove { x: f(1)?, y: f(2)? },
+            "mouse_down" => HarnessStep::MouseDown,
+            "mouse_up" => HarnessStep::MouseUp,
+            "click" => HarnessStep::Click { x: f(1)?, y: f(2)? },
+            "scroll" => HarnessStep::Scroll {
+                dx: f(1)?,
+                dy: f(2)?,
+            },
+            "key" => HarnessStep::Key(step_arg(vm, &items, 1)?),
+            "type" => HarnessStep::Type(step_arg(vm, &items, 1)?),
+            "resize" => HarnessStep::Resize {
+                width: step_arg(vm, &items, 1)?,
+                height: step_arg(vm, &items, 2)?,
+            },
+            "expect" => {
+                let threshold = step_opt(vm, &items, 2)?;
+                let max_diff_ratio = step_opt(vm, &items, 3)?;
+                let tolerance = (threshold.is_some() || max_diff_ratio.is_some()).then(|| {
+                    let default = Tolerance::default();
+                    Tolerance {
+                        threshold: threshold.unwrap_or(default.threshold),
+                        max_diff_ratio: max_diff_ratio.unwrap_or(default.max_diff_ratio),
+                    }
+                });
+                HarnessStep::Expect {
+                    name: step_arg(vm, &items, 1)?,
+                    tolerance,
+                }
+            }
+            other => return Err(vm.new_value_error(format!("unknown test step '{}'", other))),
+        });
+    }
+    Ok(out)
+}
+
+/// `xos test` options.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct VisualTestOptions {
+    /// Rewrite goldens from this run instead of comparing.
+    pub update: bool,
+    /// Only run tests whose `file::name` contains this.
+    pub filter: Option<String>,
+    /// Tolerance for `expect` calls that don't pass their own; `None` uses [`Tolerance::default`].
+    pub tolerance: Option<Tolerance>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct VisualTestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    /// Goldens created or rewritten.
+    pub written: usize,
+}
+
+/// Test files under `paths`: files as given, directories searched for `test_*.py`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn collect_visual_test_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if path.is_dir() {
+                if name != "golden" && !name.starts_with('.') && name != "__pycache__" {
+                    walk(&path, out);
+                }
+            } else if name.starts_with("test_") && name.ends_with(".py") {
+                out.push(path);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, &mut out);
+        } else {
+            out.push(path.clone());
+        }
+    }
+    out
+}
+
+/// Run every `test_*(t)` in each file; prints one line per test and returns the tally.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_visual_tests(files: &[PathBuf], options: &VisualTestOptions) -> VisualTestSummary {
+    let mut summary = VisualTestSummary::default();
+    for file in files {
+        run_visual_test_file(file, options, &mut summary);
+    }
+    summary
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_visual_test_file(file: &Path, options: &VisualTestOptions, summary: &mut VisualTestSummary) {
+    use crate::engine::pyapp::PyApp;
+    use crate::runtime::{execute_python_code, format_python_exception, PrintCallback};
+    use rustpython_vm::Interpreter;
+    use std::sync::Arc;
+
+    let display = file.display().to_string();
+    let code = match std::fs::read_to_string(file) {
+        Ok(code) => code,
+        Err(e) => {
+            println!("test {} ... FAILED\n    {}", display, e);
+            summary.failed += 1;
+            return;
+        }
+    };
+    let path = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut goldens = GoldenStore::new(
+        path.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("golden")
+            .join(&stem),
+    );
+    goldens.update |= options.update;
+    if let Some(tolerance) = options.tolerance {
+        goldens.tolerance = tolerance;
+    }
+
+    let mut interpreter = Interpreter::with_init(Default::default(), |vm| {
+        vm.add_native_module("xos".to_owned(), Box::new(crate::xos_module::make_module));
+    });
+    // Test output goes straight to stdout; it is not captured per test.
+    let print_cb: PrintCallback = Arc::new(|s: &str| print!("{}", s));
+    let (result, _, _, scope) = execute_python_code(
+        &interpreter,
+        &code,
+        &path.to_string_lossy(),
+        None,
+        Some(print_cb),
+        &[],
+    );
+    let scope = match (result, scope) {
+        (Ok(()), Some(scope)) => scope,
+        (Err(e), _) => {
+            println!("test {} ... FAILED\n{}", display, e);
+            summary.failed += 1;
+            return;
+        }
+        (Ok(()), None) => return,
+    };
+    // Test functions in definition order.
+    let names: Vec<String> = interpreter.enter(|vm| {
+        let listing = r#"__xos_tests__ = [n for n, f in list(globals().items())
+                 if n.startswith("test_") and callable(f)]"#;
+        let Ok(listed) = vm
+            .run_code_string(scope.clone(), listing, "<tests>".to_string())
+            .and_then(|_| scope.globals.get_item("__xos_tests__", vm))
+        else {
+            return Vec::new();
+        };
+        listed
+            .downcast_ref::<PyList>()
+            .map(|list| {
+                list.borrow_vec()
+                    .iter()
+                    .filter_map(|n| n.clone().try_into_value::<String>(vm).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+
+    for name in names {
+        let id = format!("{}::{}", display, name);
+        if options
+            .filter
+            .as_ref()
+            .is_some_and(|f| !id.contains(f.as_str()))
+        {
+            continue;
+        }
+        let prepared = interpreter.enter(|vm| -> Result<_, String> {
+            // The runner's globals always hold `xos` (see `execute_python_code`).
+            let script_class = scope
+                .globals
+                .get_item("xos", vm)
+                .and_then(|xos| xos.get_attr("testing", vm))
+                .and_then(|testing| testing.get_attr("Script", vm))
+                .map_err(|e| format_python_exception(vm, &e))?;
+            let script = script_class
+                .call((vm.ctx.new_str(name.as_str()),), vm)
+                .map_err(|e| format_python_exception(vm, &e))?;
+            let test = scope
+                .globals
+                .get_item(name.as_str(), vm)
+                .map_err(|e| format_python_exception(vm, &e))?;
+            test.call((script.clone(),), vm)
+                .map_err(|e| format_python_exception(vm, &e))?;
+            let app = script
+                .get_attr("app", vm)
+                .ok()
+                .filter(|app| !vm.is_none(app))
+                .ok_or_else(|| "test never called t.start(app)".to_string())?;
+            let width: u32 = script
+                .get_attr("width", vm)
+                .and_then(|v| v.try_into_value(vm))
+                .map_err(|e| format_python_exception(vm, &e))?;
+            let height: u32 = script
+                .get_attr("height", vm)
+                .and_then(|v| v.try_into_value(vm))
+                .map_err(|e| format_python_exception(vm, &e))?;
+            let steps = script
+                .get_attr("steps", vm)
+                .and_then(|steps| parse_steps(vm, &steps))
+                .map_err(|e| format_python_exception(vm, &e))?;
+            // Headless apps skip the preview window in standalone helpers.
+            let _ = app.set_attr("headless", vm.ctx.new_bool(true), vm);
+            Ok((app, width, height, steps))
+        });
+        let (app, width, height, steps) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                println!("test {} ... FAILED\n{}", id, indent(&e));
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        // The app owns the interpreter while it runs; take it back for the next test.
+        let mut harness =
+            AppHarness::without_setup(Box::new(PyApp::new(interpreter, app)), width, height);
+        let outcome = harness.setup().map(|()| harness.run(&steps, &goldens));
+        // The harness drops window requests, so nothing else holds the interpreter.
+        interpreter = match harness.into_app().into_interpreter() {
+            Some(interpreter) => interpreter,
+            None => {
+                println!("test {} ... FAILED\n    interpreter still in use", id);
+                summary.failed += 1;
+                return;
+            }
+        };
+
+        match outcome {
+            Err(e) => {
+                println!("test {} ... FAILED\n    setup: {}", id, e);
+                summary.failed += 1;
+            }
+            Ok(results) => {
+                let failures: Vec<String> = results
+                    .iter()
+                    .filter_map(|r| r.outcome.as_ref().err().cloned())
+                    .collect();
+                for r in &results {
+                    if let Ok(GoldenOutcome::Written(path)) = &r.outcome {
+                        println!("    wrote golden {}", path.display());
+                        summary.written += 1;
+                    }
+                }
+                if failures.is_empty() {
+                    println!("test {} ... ok", id);
+                    summary.passed += 1;
+                } else {
+                    println!("test {} ... FAILED", id);
+                    for f in failures {
+                        println!("{}", indent(&f));
+                    }
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|l| format!("    {}", l))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,504 @@
+//! `xos.ai.tts` — local text-to-speech (VITS voices on Burn, `xos.ai.tts.load`), and the
+//! `speak(text, ...)` shortcut behind `xos.audio.speak` that keeps one loaded voice per
+//! (voice, device).
+//!
+//! Audio comes back as `xos.Tensor`s resampled to the rate asked for (a `Speaker`'s, when
+//! streaming into one), `[samples]` for mono or `[samples, channels]` interleaved.
+
+use rustpython_vm::{
+    builtins::PyModule, function::FuncArgs, PyObjectRef, PyRef, PyResult, VirtualMachine,
+};
+
+#[cfg(all(feature = "tts", not(target_arch = "wasm32"), not(target_os = "ios")))]
+mod native {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use rustpython_vm::{
+        builtins::PyDict, function::FuncArgs, PyObjectRef, PyResult, VirtualMachine,
+    };
+    use xos_core::ai::transcription::TensorDebugStats;
+    use xos_core::ai::tts::{SpeakOptions, TtsDevice, TtsModel, DEFAULT_MODEL};
+
+    use crate::dtypes::DType;
+    use crate::tensors::create_tensor_from_data;
+
+    thread_local! {
+        static MODELS: RefCell<(u64, HashMap<u64, TtsModel>)> = RefCell::new((0, HashMap::new()));
+    }
+
+    fn err(
+        vm: &VirtualMachine,
+        msg: impl Into<String>,
+    ) -> rustpython_vm::builtins::PyBaseExceptionRef {
+        vm.new_runtime_error(msg.into())
+    }
+
+    fn arg<T: rustpython_vm::TryFromObject>(
+        av: &[PyObjectRef],
+        i: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<Option<T>> {
+        match av.get(i) {
+            Some(v) if !vm.is_none(v) => Ok(Some(v.clone().try_into_value(vm)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle(av: &[PyObjectRef], vm: &VirtualMachine) -> PyResult<u64> {
+        arg(av, 0, vm)?.ok_or_else(|| vm.new_type_error("missing model handle".to_string()))
+    }
+
+    /// Run `f` on the voice behind `id`. The voice is taken out of the table meanwhile, so a
+    /// Python callback that calls back into `xos.ai.tts` cannot alias it.
+    fn with_model<T>(
+        id: u64,
+        vm: &VirtualMachine,
+        f: impl FnOnce(&TtsModel) -> PyResult<T>,
+    ) -> PyResult<T> {
+        let model = MODELS
+            .with(|m| m.borrow_mut().1.remove(&id))
+            .ok_or_else(|| err(vm, "tts model is closed or busy"))?;
+        let out = f(&model);
+        MODELS.with(|m| m.borrow_mut().1.insert(id, model));
+        out
+    }
+
+    fn options_arg(av: &[PyObjectRef], i: usize, vm: &VirtualMachine) -> PyResult<SpeakOptions> {
+        let mut o = SpeakOptions::default();
+        let Some(d) = av.get(i).and_then(|d| d.downcast_ref::<PyDict>()) else {
+            return Ok(o);
+        };
+        let float = |key: &str| -> PyResult<Option<f32>> {
+            match d.get_item_opt(key, vm)? {
+                Some(v) if !vm.is_none(&v) => Ok(Some(v.try_into_value::<f64>(vm)? as f32)),
+                _ => Ok(None),
+            }
+        };
+        let int = |key: &str| -> PyResult<Option<usize>> {
+            match d.get_item_opt(key, vm)? {
+                Some(v) if !vm.is_none(&v) => Ok(Some(v.try_into_value(vm)?)),
+                _ => Ok(None),
+            }
+        };
+        if let Some(speed) = float("speed")? {
+            o.speed = speed;
+        }
+        if let Some(pause) = float("sentence_pause")? {
+            o.sentence_pause = pause;
+        }
+        if let Some(max) = int("max_sentence_chars")? {
+            o.max_sentence_chars = max;
+        }
+        o.noise_scale = float("noise_scale")?;
+        o.noise_scale_duration = float("noise_scale_duration")?;
+        o.speaker = int("speaker_id")?;
+        o.seed = int("seed")?.map(|s| s as u64);
+        Ok(o)
+    }
+
+    /// Where the audio goes: `(sample_rate, channels)`, defaulting to the voice's rate in mono.
+    fn output_arg(
+        av: &[PyObjectRef],
+        i: usize,
+        model_rate: u32,
+        vm: &VirtualMachine,
+    ) -> PyResult<(u32, usize)> {
+        let rate = arg::<u32>(av, i, vm)?.unwrap_or(model_rate);
+        let channels = arg::<usize>(av, i + 1, vm)?.unwrap_or(1);
+        if rate == 0 || channels == 0 {
+            return Err(vm.new_value_error("sample_rate and channels must be > 0".to_string()));
+        }
+        Ok((rate, channels))
+    }
+
+    fn audio_tensor(
+        vm: &VirtualMachine,
+        mono: &[f32],
+        from_hz: u32,
+        (rate, channels): (u32, usize),
+    ) -> PyResult {
+        let mono = xos_core::engine::audio::dsp::resample(mono, from_hz, rate);
+        let (data, shape) = if channels == 1 {
+            let n = mono.len();
+            (mono, vec![n])
+        } else {
+            let data = mono
+                .iter()
+                .flat_map(|&s| std::iter::repeat_n(s, channels))
+                .collect();
+            (data, vec![mono.len(), channels])
+        };
+        let tensor = create_tensor_from_data(data, shape, DType::Float32);
+        crate::audio::wrap_tensor_dict(tensor.to_py_dict(vm, DType::Float32)?, vm)
+    }
+
+    fn ints(vm: &VirtualMachine, values: &[usize]) -> PyObjectRef {
+        vm.ctx
+            .new_list(values.iter().map(|&v| vm.ctx.new_int(v).into()).collect())
+            .into()
+    }
+
+    fn floats(vm: &VirtualMachine, values: &[f32]) -> PyObjectRef {
+        vm.ctx
+            .new_list(
+                values
+                    .iter()
+                    .map(|&v| vm.ctx.new_float(v as f64).into())
+                    .collect(),
+            )
+            .into()
+    }
+
+    fn stats_dict(vm: &VirtualMachine, stats: Option<&TensorDebugStats>, n: usize) -> PyResult {
+        let d = vm.ctx.new_dict();
+        d.set_item("num_values", vm.ctx.new_int(n).into(), vm)?;
+        if let Some(s) = stats {
+            d.set_item("full_mean", vm.ctx.new_float(s.mean as f64).into(), vm)?;
+            d.set_item("full_std", vm.ctx.new_float(s.std as f64).into(), vm)?;
+            d.set_item("full_min", vm.ctx.new_float(s.min as f64).into(), vm)?;
+            d.set_item("full_max", vm.ctx.new_float(s.max as f64).into(), vm)?;
+        }
+        Ok(d.into())
+    }
+
+    /// `_load(model=None, device="cpu")` → handle.
+    pub fn load(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let source = arg::<String>(&av, 0, vm)?.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let device_s = arg::<String>(&av, 1, vm)?.unwrap_or_else(|| "cpu".to_string());
+        let device = TtsDevice::from_name(&device_s).ok_or_else(|| {
+            vm.new_value_error(format!("unknown device '{device_s}' (use 'cpu' or 'gpu')"))
+        })?;
+        let model = TtsModel::load(&source, device).map_err(|e| err(vm, e))?;
+        let id = MODELS.with(|m| {
+            let mut m = m.borrow_mut();
+            m.0 += 1;
+            let id = m.0;
+            m.1.insert(id, model);
+            id
+        });
+        Ok(vm.ctx.new_int(id).into())
+    }
+
+    /// `_info(handle)` → `{"config", "sample_rate", "num_speakers", "weights_files", "parameters"}`.
+    pub fn info(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        with_model(id, vm, |model| {
+            let c = model.config();
+            let config = vm.ctx.new_dict();
+            for (key, value) in [
+                ("vocab_size", c.vocab_size),
+                ("hidden_size", c.hidden_size),
+                ("num_hidden_layers", c.num_hidden_layers),
+                ("num_attention_heads", c.num_attention_heads),
+                ("flow_size", c.flow_size),
+                ("hop_length", c.hop_length()),
+            ] {
+                config.set_item(key, vm.ctx.new_int(value).into(), vm)?;
+            }
+            for (key, value) in [
+                ("speaking_rate", c.speaking_rate),
+                ("noise_scale", c.noise_scale),
+                ("noise_scale_duration", c.noise_scale_duration),
+            ] {
+                config.set_item(key, vm.ctx.new_float(value as f64).into(), vm)?;
+            }
+            config.set_item(
+                "stochastic_duration",
+                vm.ctx.new_bool(c.use_stochastic_duration_prediction).into(),
+                vm,
+            )?;
+            let mut params = Vec::with_capacity(model.parameters().len());
+            for p in model.parameters() {
+                let d = vm.ctx.new_dict();
+                d.set_item("name", vm.ctx.new_str(p.name.as_str()).into(), vm)?;
+                d.set_item("shape", ints(vm, &p.shape), vm)?;
+                d.set_item("dtype", vm.ctx.new_str(p.dtype.as_str()).into(), vm)?;
+                d.set_item("values", floats(vm, &p.values), vm)?;
+                let numel = p.shape.iter().product();
+                d.set_item("stats", stats_dict(vm, p.stats.as_ref(), numel)?, vm)?;
+                params.push(d.into());
+            }
+            let files = model
+                .weights_files()
+                .iter()
+                .map(|p| vm.ctx.new_str(p.display().to_string()).into())
+                .collect();
+            let out = vm.ctx.new_dict();
+            out.set_item("config", config.into(), vm)?;
+            out.set_item(
+                "sample_rate",
+                vm.ctx.new_int(model.sample_rate()).into(),
+                vm,
+            )?;
+            out.set_item(
+                "num_speakers",
+                vm.ctx.new_int(model.num_speakers()).into(),
+                vm,
+            )?;
+            out.set_item("weights_files", vm.ctx.new_list(files).into(), vm)?;
+            out.set_item("parameters", vm.ctx.new_list(params).into(), vm)?;
+            Ok(out.into())
+        })
+    }
+
+    /// `_synthesize(handle, text, options, sample_rate=None, channels=1)` → audio tensor.
+    pub fn synthesize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let text = arg::<String>(&av, 1, vm)?.unwrap_or_default();
+        let options = options_arg(&av, 2, vm)?;
+        with_model(id, vm, |model| {
+            let output = output_arg(&av, 3, model.sample_rate(), vm)?;
+            let wave = model.synthesize(&text, &options).map_err(|e| err(vm, e))?;
+            audio_tensor(vm, &wave, model.sample_rate(), output)
+        })
+    }
+
+    /// `_stream(handle, text, options, sample_rate, channels, on_chunk)`: `on_chunk(tensor)` gets
+    /// each sentence as it is synthesized; returning `False` stops.
+    pub fn stream(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let text = arg::<String>(&av, 1, vm)?.unwrap_or_default();
+        let options = options_arg(&av, 2, vm)?;
+        let callback = av
+            .get(5)
+            .filter(|c| !vm.is_none(c))
+            .cloned()
+            .ok_or_else(|| vm.new_type_error("missing on_chunk callback".to_string()))?;
+        let mut callback_error = None;
+        with_model(id, vm, |model| {
+            let output = output_arg(&av, 3, model.sample_rate(), vm)?;
+            let mut on_chunk = |chunk: &[f32]| -> bool {
+                let sent = audio_tensor(vm, chunk, model.sample_rate(), output)
+                    .and_then(|t| callback.call((t,), vm));
+                match sent {
+                    Ok(ret) => !ret.is(&vm.ctx.false_value),
+                    Err(e) => {
+                        callback_error = Some(e);
+                        false
+                    }
+                }
+            };
+            model
+                .stream(&text, &options, &mut on_chunk)
+                .map_err(|e| err(vm, e))
+        })?;
+        match callback_error {
+            Some(e) => Err(e),
+            None => Ok(vm.ctx.none()),
+        }
+    }
+
+    /// `_tokenize(handle, text)` → token ids.
+    pub fn tokenize(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let text = arg::<String>(&av, 1, vm)?.unwrap_or_default();
+        with_model(id, vm, |model| {
+            let ids: Vec<usize> = model.tokenize(&text).iter().map(|&t| t as usize).collect();
+            Ok(ints(vm, &ids))
+        })
+    }
+
+    /// `_forward_layer_by_layer(handle, text, options)` → `[{"name", "shape", "values", "stats"}, ...]`.
+    pub fn forward_layer_by_layer(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let av = args.args;
+        let id = handle(&av, vm)?;
+        let text = arg::<String>(&av, 1, vm)?.unwrap_or_default();
+        let options = options_arg(&av, 2, vm)?;
+        with_model(id, vm, |model| {
+            let steps = model
+                .forward_layer_by_layer(&text, &options)
+                .map_err(|e| err(vm, e))?;
+            let mut out = Vec::with_capacity(steps.len());
+            for s in steps {
+                let d = vm.ctx.new_dict();
+                let name = s.name.unwrap_or_default();
+                d.set_item("name", vm.ctx.new_str(name).into(), vm)?;
+                d.set_item("shape", ints(vm, &s.shape), vm)?;
+                d.set_item("values", floats(vm, &s.values), vm)?;
+                d.set_item(
+                    "stats",
+                    stats_dict(vm, s.full_stats.as_ref(), s.values.len())?,
+                    vm,
+                )?;
+                out.push(d.into());
+            }
+            Ok(vm.ctx.new_list(out).into())
+        })
+    }
+
+    /// `_close(handle)`: free the voice's weights.
+    pub fn close(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        let id = handle(&args.args, vm)?;
+        MODELS.with(|m| m.borrow_mut().1.remove(&id));
+        Ok(vm.ctx.none())
+    }
+}
+
+#[cfg(not(all(feature = "tts", not(target_arch = "wasm32"), not(target_os = "ios"))))]
+mod native {
+    use rustpython_vm::{function::FuncArgs, PyResult, VirtualMachine};
+
+    pub fn unavailable(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+        Err(vm.new_runtime_error(
+            "Text-to-speech is unavailable in this build (enable tts)".to_string(),
+        ))
+    }
+
+    pub use unavailable as close;
+    pub use unavailable as forward_layer_by_layer;
+    pub use unavailable as info;
+    pub use unavailable as load;
+    pub use unavailable as stream;
+    pub use unavailable as synthesize;
+    pub use unavailable as tokenize;
+}
+
+const GLUE: &str = r#"
+CPU = "cpu"
+GPU = "gpu"
+DEFAULT_MODEL = "mms-tts-eng"
+
+def _mk_parameter(payload):
+    xos = __import__("xos")
+    return xos.nn.Parameter(
+        payload["name"],
+        payload["shape"],
+        payload["dtype"],
+        payload.get("values", []),
+        payload.get("stats", {}),
+    )
+
+def _options(speed, speaker_id, options):
+    out = dict(options)
+    out["speed"] = float(speed)
+    out["speaker_id"] = None if speaker_id is None else int(speaker_id)
+    return out
+
+class TtsModel:
+    """A loaded VITS voice (e.g. MMS-TTS). Text is read sentence by sentence; characters the
+    voice has no token for (often digits) are skipped, so spell numbers out.
+
+    Options accepted by ``forward`` / ``stream`` / ``forward_layer_by_layer``: ``speed`` (1.0;
+    2.0 is twice as fast), ``speaker_id`` (multi-speaker voices), ``noise_scale`` (voice
+    variation, the voice's default when None), ``noise_scale_duration`` (timing variation),
+    ``sentence_pause`` (seconds between sentences, 0.2), ``max_sentence_chars`` (300) and
+    ``seed`` (reproducible audio)."""
+    def __init__(self, handle):
+        self._handle = handle
+        self._info = _info(handle)
+    def __del__(self):
+        try:
+            _close(self._handle)
+        except Exception:
+            pass
+    @property
+    def config(self):
+        return dict(self._info["config"])
+    @property
+    def sample_rate(self):
+        return self._info["sample_rate"]
+    @property
+    def num_speakers(self):
+        return self._info["num_speakers"]
+    def named_parameters(self):
+        for p in self._info["parameters"]:
+            yield p["name"], _mk_parameter(p)
+    @property
+    def parameters(self):
+        return [_mk_parameter(p) for p in self._info["parameters"]]
+    def get_parameter(self, name):
+        for p in self._info["parameters"]:
+            if p["name"] == name:
+                return _mk_parameter(p)
+        return None
+    @property
+    def parameter_count(self):
+        return len(self._info["parameters"])
+    @property
+    def weights_files(self):
+        return list(self._info["weights_files"])
+    def tokenize(self, text):
+        return _tokenize(self._handle, str(text))
+    def forward(self, text, speed=1.0, speaker_id=None, sample_rate=None, **options):
+        """``text`` as one mono float32 tensor in [-1, 1] at ``sample_rate`` (default: the
+        voice's own, ``self.sample_rate``)."""
+        return _synthesize(self._handle, str(text), _options(speed, speaker_id, options), sample_rate, 1)
+    def stream(self, text, on_chunk, speed=1.0, speaker_id=None, sample_rate=None, channels=1, **options):
+        """Call ``on_chunk(tensor)`` with each sentence as soon as it is synthesized (returning
+        False stops). Tensors are ``[samples]``, or ``[samples, channels]`` for channels > 1."""
+        _stream(self._handle, str(text), _options(speed, speaker_id, options), sample_rate, int(channels), on_chunk)
+    def forward_layer_by_layer(self, text, speed=1.0, speaker_id=None, **options):
+        # Text encoder layers, durations, prior, flows, then each vocoder stage.
+        for step in _forward_layer_by_layer(self._handle, str(text), _options(speed, speaker_id, options)):
+            yield step["name"], _mk_parameter({
+                "name": step["name"],
+                "shape": step["shape"],
+                "dtype": "float32",
+                "values": step["values"],
+                "stats": step["stats"],
+            })
+
+def load(model=DEFAULT_MODEL, weights_path=None, device=CPU):
+    # model: registry voice (`xos models list`, downloaded on first use; mms-tts-eng / -deu /
+    # -fra / -spa); weights_path: a Hugging Face VITS folder (config.json, *.safetensors,
+    # vocab.json) instead. device: CPU (NdArray) or GPU (WGPU).
+    return TtsModel(_load(weights_path or model, device))
+
+_loaded = {}
+
+def speak(text, voice=DEFAULT_MODEL, speaker=None, speed=1.0, speaker_id=None, device=CPU, **options):
+    """One-call speech: loads ``voice`` (a registry name or a VITS folder) once and keeps it.
+
+    With a ``speaker`` (``xos.audio.Speaker``) each sentence is queued on it as soon as it is
+    ready, resampled to the speaker's rate, and nothing is returned. Without one, returns the
+    whole utterance as a mono tensor at the voice's rate (or ``sample_rate=``)."""
+    key = (voice, device)
+    if key not in _loaded:
+        _loaded[key] = load(voice, device=device)
+    model = _loaded[key]
+    if speaker is None:
+        return model.forward(text, speed=speed, speaker_id=speaker_id, **options)
+    model.stream(
+        text,
+        lambda chunk: speaker.play_samples(chunk) or True,
+        speed=speed,
+        speaker_id=speaker_id,
+        sample_rate=speaker.sample_rate,
+        channels=speaker.channels,
+        **options,
+    )
+"#;
+
+pub fn make_tts_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let tts = vm.new_module("xos.ai.tts", vm.ctx.new_dict(), None);
+    let natives: [(&str, fn(FuncArgs, &VirtualMachine) -> PyResult); 7] = [
+        ("_load", native::load),
+        ("_info", native::info),
+        ("_synthesize", native::synthesize),
+        ("_stream", native::stream),
+        ("_tokenize", native::tokenize),
+        ("_forward_layer_by_layer", native::forward_layer_by_layer),
+        ("_close", native::close),
+    ];
+    let scope = vm.new_scope_with_builtins();
+    for (name, f) in natives {
+        let func: PyObjectRef = vm.new_function(name, f).into();
+        scope.globals.set_item(name, func, vm).ok();
+    }
+    if vm
+        .run_code_string(scope.clone(), GLUE, "<xos.ai.tts>".to_string())
+        .is_ok()
+    {
+        for name in ["load", "speak", "TtsModel", "CPU", "GPU", "DEFAULT_MODEL"] {
+            if let Ok(v) = scope.globals.get_item(name, vm) {
+                tts.set_attr(name, v, vm).ok();
+            }
+        }
+    }
+    tts
+}
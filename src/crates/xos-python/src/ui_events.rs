@@ -3,6 +3,7 @@
 use rustpython_vm::builtins::PyDict;
 use rustpython_vm::{PyObjectRef, PyResult, VirtualMachine};
 use xos_core::engine::keyboard::shortcuts::ShortcutAction;
+use xos_core::engine::pointer::{GestureEvent, GesturePhase};
 use xos_core::engine::ScrollWheelUnit;
 
 #[derive(Clone, Copy, Debug)]
@@ -17,6 +18,8 @@ pub enum PyUiEventKind {
     },
     Key(char),
     Shortcut(ShortcutAction),
+    /// Two-finger pinch / rotate / pan.
+    Gesture(GestureEvent),
 }
 
 pub fn parse_app_xos_event(vm: &VirtualMachine, app: &PyObjectRef) -> PyResult<Option<PyUiEventKind>> {
@@ -59,6 +62,30 @@ pub fn parse_app_xos_event(vm: &VirtualMachine, app: &PyObjectRef) -> PyResult<O
                 .unwrap_or(ScrollWheelUnit::Pixel);
             PyUiEventKind::Scroll { dx, dy, unit }
         }
+        "gesture" => {
+            let f = |key: &str, default: f64| {
+                dict.get_item(key, vm)
+                    .ok()
+                    .and_then(|o| o.try_into_value::<f64>(vm).ok())
+                    .unwrap_or(default) as f32
+            };
+            let phase = match dict.get_item("phase", vm)?.str(vm)?.as_str() {
+                "began" => GesturePhase::Began,
+                "ended" => GesturePhase::Ended,
+                _ => GesturePhase::Changed,
+            };
+            PyUiEventKind::Gesture(GestureEvent {
+                phase,
+                center_x: f("x", 0.0),
+                center_y: f("y", 0.0),
+                scale: f("scale", 1.0),
+                rotation: f("rotation", 0.0),
+                delta_scale: f("delta_scale", 1.0),
+                delta_rotation: f("delta_rotation", 0.0),
+                pan_dx: f("pan_dx", 0.0),
+                pan_dy: f("pan_dy", 0.0),
+            })
+        }
         "key_char" => {
             let s = dict.get_item("char", vm)?.str(vm)?.to_string();
             let ch = s.chars().next().ok_or_else(|| {
@@ -0,0 +1,69 @@
+//! `xos.vision` — image understanding on frames: `ocr(image)` (text + boxes, see `xos.ai.ocr`)
+//! and `webcam_frame()` to grab camera frames as `Frame`s.
+
+use rustpython_vm::{builtins::PyModule, function::FuncArgs, PyRef, PyResult, VirtualMachine};
+
+const VISION_PY_CODE: &str = r#"
+def ocr(image, model="easyocr-en", device="cpu", **options):
+    """Read all text in ``image`` (a Frame — app frame, ``Monitor.get_frame()``,
+    ``webcam_frame()`` — or an ``[h, w]`` / ``[h, w, 3|4]`` tensor). Returns
+    ``[{"box": (x1, y1, x2, y2), "text": str, "confidence": float}]`` top to bottom.
+
+    The model is loaded on first use and kept; options are those of ``xos.ai.ocr.OcrModel``."""
+    return __import__("xos").ai.ocr.read(image, model, device, **options)
+"#;
+
+/// xos.vision.webcam_frame() - the latest camera frame as an RGBA `Frame` (opens camera 0 once)
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "ios")))]
+fn webcam_frame(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    use std::cell::Cell;
+    use xos_core::video::webcam;
+
+    thread_local! {
+        static OPENED: Cell<bool> = const { Cell::new(false) };
+    }
+    // The camera layer panics when no device can be opened.
+    let grab = || {
+        if !OPENED.get() {
+            webcam::init_camera();
+            OPENED.set(true);
+        }
+        (webcam::get_resolution(), webcam::get_frame())
+    };
+    let ((w, h), rgb) = std::panic::catch_unwind(grab)
+        .map_err(|_| vm.new_runtime_error("no webcam available".to_string()))?;
+    let rgba = rgb
+        .chunks_exact(3)
+        .flat_map(|p| [p[0], p[1], p[2], 255])
+        .collect();
+    crate::json_codec::py_frame_from_rgba_bytes(vm, w as usize, h as usize, rgba)
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "ios"))]
+fn webcam_frame(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Err(vm.new_runtime_error(
+        "xos.vision.webcam_frame is only available on native desktop builds".to_string(),
+    ))
+}
+
+/// Create the vision module
+pub fn make_vision_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.vision", vm.ctx.new_dict(), None);
+    module
+        .set_attr(
+            "webcam_frame",
+            vm.new_function("webcam_frame", webcam_frame),
+            vm,
+        )
+        .unwrap();
+
+    let scope = vm.new_scope_with_builtins();
+    if let Err(e) = vm.run_code_string(scope.clone(), VISION_PY_CODE, "<vision>".to_string()) {
+        eprintln!("Failed to create xos.vision helpers: {:?}", e);
+        return module;
+    }
+    if let Ok(f) = scope.globals.get_item("ocr", vm) {
+        module.set_attr("ocr", f, vm).unwrap();
+    }
+    module
+}
@@ -1,5 +1,6 @@
 //! Native drawing surface for [`xos.ui.whiteboard`] (pan, zoom, strokes).
 
+use xos_core::engine::pointer::GestureEvent;
 use xos_core::engine::{EngineState, ScrollWheelUnit};
 use xos_core::viewport;
 
@@ -264,8 +265,16 @@ impl WhiteboardWidget {
             }
         }
 
+        // A second finger turns the stroke in progress into a pinch / pan (see `on_gesture`).
+        let gesturing = state.pointers.gesture_active();
+        if gesturing && !self.current_stroke.is_empty() {
+            self.current_stroke.clear();
+            self.cache_dirty = true;
+        }
+
         let mut added_point = false;
         if self.editable
+            && !gesturing
             && state.mouse.is_left_clicking
             && (in_viewport || self.was_drawing)
             && self.current_stroke.len() < 10_000
@@ -287,7 +296,7 @@ impl WhiteboardWidget {
             }
         }
 
-        self.was_drawing = self.editable && state.mouse.is_left_clicking;
+        self.was_drawing = self.editable && !gesturing && state.mouse.is_left_clicking;
 
         if self.cache_dirty {
             self.rebuild_cache(width, height);
@@ -376,6 +385,36 @@ impl WhiteboardWidget {
         }
         self.cache_dirty = true;
     }
+
+    /// Two-finger pinch zooms about the gesture center (when `zoomable`) and drags the canvas
+    /// (when scrollable), like right-drag.
+    pub fn on_gesture(&mut self, gesture: &GestureEvent) {
+        if !self.viewport_contains(gesture.center_x, gesture.center_y) {
+            return;
+        }
+        let prev = (self.zoom, self.offset_x, self.offset_y);
+        if self.zoomable && gesture.delta_scale.is_finite() && gesture.delta_scale > 0.0 {
+            let (local_x, local_y) = self.screen_to_local(gesture.center_x, gesture.center_y);
+            let world_before = self.screen_to_world(local_x, local_y);
+            self.zoom *= gesture.delta_scale;
+            let world_after = self.screen_to_world(local_x, local_y);
+            if self.scrollable_x {
+                self.offset_x += (world_after.0 - world_before.0) * self.zoom;
+            }
+            if self.scrollable_y {
+                self.offset_y += (world_after.1 - world_before.1) * self.zoom;
+            }
+        }
+        if self.scrollable_x {
+            self.offset_x += gesture.pan_dx;
+        }
+        if self.scrollable_y {
+            self.offset_y += gesture.pan_dy;
+        }
+        if (self.zoom, self.offset_x, self.offset_y) != prev {
+            self.cache_dirty = true;
+        }
+    }
 }
 
 fn draw_stroke(
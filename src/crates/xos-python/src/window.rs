@@ -0,0 +1,259 @@
+//! `xos.window` — the app's own window: title, size, fullscreen, always-on-top, cursor hide / lock,
+//! DPI scale and raw mouse motion.
+//!
+//! Changes are applied by the host on the next frame; getters report what the host last applied.
+//! Inside `tick()` / input callbacks they act on the window running the callback (extra windows
+//! included); elsewhere on the main window. iOS and headless runs ignore the setters, the browser
+//! applies title, fullscreen and cursor lock only.
+
+use rustpython_vm::builtins::PyModule;
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyRef, PyResult, VirtualMachine};
+use xos_core::engine::window_control::{
+    main_window_info, request_window_command, WindowCommand, WindowControl, WindowInfo,
+};
+
+/// Run `f` against the calling window's controls (input callback or tick); `None` outside the engine.
+fn with_engine_window<T>(f: impl Fn(&mut WindowControl) -> T) -> Option<T> {
+    crate::engine::py_engine_tls::with_callback_engine_state_mut(|s| f(&mut s.window))
+        .or_else(|| crate::engine::py_engine_tls::with_tick_engine_state_mut(|s| f(&mut s.window)))
+}
+
+fn send(command: WindowCommand) {
+    let queued = command.clone();
+    if with_engine_window(|window| window.push(queued.clone())).is_none() {
+        request_window_command(command);
+    }
+}
+
+fn info() -> WindowInfo {
+    with_engine_window(|window| window.info.clone())
+        .or_else(main_window_info)
+        .unwrap_or_default()
+}
+
+fn arg<T: rustpython_vm::TryFromObject>(
+    args: &FuncArgs,
+    index: usize,
+    usage: &str,
+    vm: &VirtualMachine,
+) -> PyResult<T> {
+    args.args
+        .get(index)
+        .ok_or_else(|| vm.new_type_error(usage.to_string()))?
+        .clone()
+        .try_into_value(vm)
+}
+
+/// First argument as a bool, `True` when omitted.
+fn flag(args: &FuncArgs, vm: &VirtualMachine) -> PyResult<bool> {
+    match args.args.first() {
+        Some(obj) => obj.clone().try_to_bool(vm),
+        None => Ok(true),
+    }
+}
+
+/// xos.window.set_title(title)
+fn set_title(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let title: String = arg(&args, 0, "set_title(title)", vm)?;
+    send(WindowCommand::SetTitle(title));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.set_size(width, height) - inner size in physical pixels
+fn set_size(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let usage = "set_size(width, height)";
+    let width: i64 = arg(&args, 0, usage, vm)?;
+    let height: i64 = arg(&args, 1, usage, vm)?;
+    if width < 1 || height < 1 {
+        return Err(vm.new_value_error("window size must be at least 1x1".to_string()));
+    }
+    send(WindowCommand::SetSize {
+        width: width.min(u32::MAX as i64) as u32,
+        height: height.min(u32::MAX as i64) as u32,
+    });
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.set_fullscreen(on=True) - borderless fullscreen on the current monitor
+fn set_fullscreen(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetFullscreen(flag(&args, vm)?));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.toggle_fullscreen()
+fn toggle_fullscreen(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetFullscreen(!info().fullscreen));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.set_always_on_top(on=True)
+fn set_always_on_top(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetAlwaysOnTop(flag(&args, vm)?));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.set_cursor_visible(visible=True)
+fn set_cursor_visible(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetCursorVisible(flag(&args, vm)?));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.lock_cursor(locked=True) - hide the pointer and keep it in the window; read
+/// movement with mouse_motion()
+fn lock_cursor(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetCursorLocked(flag(&args, vm)?));
+    Ok(vm.ctx.none())
+}
+
+/// xos.window.unlock_cursor()
+fn unlock_cursor(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    send(WindowCommand::SetCursorLocked(false));
+    Ok(vm.ctx.none())
+}
+
+fn title(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_str(info().title).into())
+}
+
+fn is_fullscreen(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bool(info().fullscreen).into())
+}
+
+fn is_always_on_top(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bool(info().always_on_top).into())
+}
+
+fn is_cursor_visible(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bool(info().cursor_visible).into())
+}
+
+fn is_cursor_locked(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bool(info().cursor_locked).into())
+}
+
+/// xos.window.scale_factor() - physical pixels per logical pixel (DPI / 96 on desktop)
+fn scale_factor(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_float(info().scale_factor).into())
+}
+
+/// xos.window.mouse_motion() - (dx, dy) raw motion since the previous tick, also while locked
+fn mouse_motion(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let (dx, dy) = info().mouse_motion;
+    Ok(vm
+        .ctx
+        .new_tuple(vec![
+            vm.ctx.new_float(dx as f64).into(),
+            vm.ctx.new_float(dy as f64).into(),
+        ])
+        .into())
+}
+
+/// xos.window.dpi() - scale_factor() * 96
+fn dpi(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_float(info().scale_factor * 96.0).into())
+}
+
+/// Create the window module
+pub fn make_window_module(vm: &VirtualMachine) -> PyRef<PyModule> {
+    let module = vm.new_module("xos.window", vm.ctx.new_dict(), None);
+
+    module
+        .set_attr("set_title", vm.new_function("set_title", set_title), vm)
+        .unwrap();
+    module
+        .set_attr("set_size", vm.new_function("set_size", set_size), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "set_fullscreen",
+            vm.new_function("set_fullscreen", set_fullscreen),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "toggle_fullscreen",
+            vm.new_function("toggle_fullscreen", toggle_fullscreen),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "set_always_on_top",
+            vm.new_function("set_always_on_top", set_always_on_top),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "set_cursor_visible",
+            vm.new_function("set_cursor_visible", set_cursor_visible),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "lock_cursor",
+            vm.new_function("lock_cursor", lock_cursor),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "unlock_cursor",
+            vm.new_function("unlock_cursor", unlock_cursor),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("title", vm.new_function("title", title), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "is_fullscreen",
+            vm.new_function("is_fullscreen", is_fullscreen),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "is_always_on_top",
+            vm.new_function("is_always_on_top", is_always_on_top),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "is_cursor_visible",
+            vm.new_function("is_cursor_visible", is_cursor_visible),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "is_cursor_locked",
+            vm.new_function("is_cursor_locked", is_cursor_locked),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr(
+            "scale_factor",
+            vm.new_function("scale_factor", scale_factor),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("dpi", vm.new_function("dpi", dpi), vm)
+        .unwrap();
+    module
+        .set_attr(
+            "mouse_motion",
+            vm.new_function("mouse_motion", mouse_motion),
+            vm,
+        )
+        .unwrap();
+
+    module
+}
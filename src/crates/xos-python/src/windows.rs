@@ -0,0 +1,113 @@
+//! Extra OS windows for Python apps (`Application.open_window`). Each window runs another
+//! `xos.Application` instance on the same interpreter, with its own `frame`, input and callbacks.
+//!
+//! The natives only queue the request; the running [`crate::engine::pyapp::PyApp`] turns it into a
+//! window app on its next `setup` / `tick`, since that is where the shared interpreter lives.
+
+use std::cell::RefCell;
+
+use rustpython_vm::builtins::PyModule;
+use rustpython_vm::function::FuncArgs;
+use rustpython_vm::{PyObjectRef, PyRef, PyResult, VirtualMachine};
+use xos_core::engine::windows::{
+    close_window, is_window_open, reserve_window_handle, WindowHandle, WindowPlacement, WindowSpec,
+};
+
+pub(crate) struct PendingWindow {
+    pub handle: WindowHandle,
+    pub spec: WindowSpec,
+    pub app: PyObjectRef,
+}
+
+thread_local! {
+    static PENDING: RefCell<Vec<PendingWindow>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Windows requested from Python since the last call.
+pub(crate) fn take_pending_windows() -> Vec<PendingWindow> {
+    PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()))
+}
+
+fn optional<T: rustpython_vm::TryFromObject>(
+    args: &FuncArgs,
+    index: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Option<T>> {
+    match args.args.get(index) {
+        Some(obj) if !vm.is_none(obj) => Ok(Some(obj.clone().try_into_value(vm)?)),
+        _ => Ok(None),
+    }
+}
+
+/// xos._open_window(app, title, width, height, monitor, x, y, fullscreen, decorations,
+/// always_on_top) - queue a window for `app`; returns its id
+fn open_window(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let app = args
+        .args
+        .first()
+        .filter(|app| !vm.is_none(app))
+        .cloned()
+        .ok_or_else(|| vm.new_type_error("open_window() needs an xos.Application".to_string()))?;
+    let title: String = optional(&args, 1, vm)?.unwrap_or_else(|| "xos".to_string());
+    let width: u32 = optional(&args, 2, vm)?.unwrap_or(640);
+    let height: u32 = optional(&args, 3, vm)?.unwrap_or(480);
+    let monitor: Option<usize> = optional(&args, 4, vm)?;
+    let x: Option<i32> = optional(&args, 5, vm)?;
+    let y: Option<i32> = optional(&args, 6, vm)?;
+
+    let mut spec = WindowSpec::new(title, width, height);
+    spec.placement = match (monitor, x, y) {
+        (_, Some(x), Some(y)) => WindowPlacement::At { x, y },
+        (_, Some(_), None) | (_, None, Some(_)) => {
+            return Err(vm.new_value_error("pass both x and y, or neither".to_string()));
+        }
+        (Some(index), None, None) => WindowPlacement::Monitor(index),
+        (None, None, None) => WindowPlacement::Auto,
+    };
+    spec.fullscreen = optional(&args, 7, vm)?.unwrap_or(false);
+    spec.decorations = optional(&args, 8, vm)?.unwrap_or(true);
+    spec.always_on_top = optional(&args, 9, vm)?.unwrap_or(false);
+
+    let handle = reserve_window_handle();
+    PENDING.with(|p| p.borrow_mut().push(PendingWindow { handle, spec, app }));
+    Ok(vm.ctx.new_int(handle.0).into())
+}
+
+fn window_handle_arg(args: &FuncArgs, vm: &VirtualMachine) -> PyResult<WindowHandle> {
+    optional::<u64>(args, 0, vm)?
+        .map(WindowHandle)
+        .ok_or_else(|| vm.new_type_error("expected a window id".to_string()))
+}
+
+/// xos._close_window(id) - close a window from open_window (no-op once closed)
+fn close_window_native(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let handle = window_handle_arg(&args, vm)?;
+    PENDING.with(|p| p.borrow_mut().retain(|w| w.handle != handle));
+    close_window(handle);
+    Ok(vm.ctx.none())
+}
+
+/// xos._window_is_open(id) - False once closed by the app or the user
+fn window_is_open(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let handle = window_handle_arg(&args, vm)?;
+    Ok(vm.ctx.new_bool(is_window_open(handle)).into())
+}
+
+/// Add the window natives used by `Application.open_window` to the `xos` module.
+pub fn register_windows(module: &PyRef<PyModule>, vm: &VirtualMachine) {
+    let _ = module.set_attr(
+        "_open_window",
+        vm.new_function("_open_window", open_window),
+        vm,
+    );
+    let _ = module.set_attr(
+        "_close_window",
+        vm.new_function("_close_window", close_window_native),
+        vm,
+    );
+    let _ = module.set_attr(
+        "_window_is_open",
+        vm.new_function("_window_is_open", window_is_open),
+        vm,
+    );
+}
@@ -152,15 +152,21 @@ impl StandalonePreviewApp {
                         is_right_clicking: false,
                         style: xos_core::engine::CursorStyleSetter::new(),
                     },
+                    pointers: xos_core::engine::pointer::PointerState::default(),
                     keyboard: xos_core::engine::KeyboardState {
                         onscreen: xos_core::ui::onscreen_keyboard::OnScreenKeyboard::new(),
                         modifiers: xos_core::engine::KeyboardModifiers::default(),
+                        keys_down: xos_core::engine::keyboard::keys::KeysDown::default(),
                     },
+                    gamepads: xos_core::engine::gamepad::Gamepads::default(),
                     f3_menu: xos_core::engine::F3Menu::new(),
                     ui_scale_percent: 100,
                     delta_time_seconds: 1.0 / 60.0,
                     paused: false,
                     pending_step_ticks: 0,
+                    timestep: xos_core::engine::timestep::FixedTimestep::default(),
+                    capture: xos_core::engine::capture::FrameCapture::default(),
+                    window: xos_core::engine::window_control::WindowControl::default(),
                     frame_view_zoom: 1.0,
                     frame_view_zoom_target: 1.0,
                     frame_view_zoom_velocity: 0.0,
@@ -561,6 +567,45 @@ fn get_mouse(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
     Ok(dict.into())
 }
 
+/// Run `f` against the engine's held-keys table (tick or input callback); `None` outside the engine.
+fn with_engine_keys<T>(f: impl Fn(&xos_core::engine::keyboard::keys::KeysDown) -> T) -> Option<T> {
+    crate::engine::py_engine_tls::with_callback_engine_state_mut(|s| f(&s.keyboard.keys_down))
+        .or_else(|| {
+            crate::engine::py_engine_tls::with_tick_engine_state_mut(|s| f(&s.keyboard.keys_down))
+        })
+}
+
+/// xos._key_is_down(name) - backs `Application.keyboard.is_down("w")` (logical or physical name)
+fn key_is_down(args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let name: String = args
+        .args
+        .first()
+        .ok_or_else(|| vm.new_type_error("_key_is_down() missing key name".to_string()))?
+        .clone()
+        .try_into_value(vm)?;
+    let down = with_engine_keys(|keys| keys.is_down(&name)).unwrap_or(false);
+    Ok(vm.ctx.new_bool(down).into())
+}
+
+/// xos._keys_down() - list of `(key, code)` tuples currently held, in press order
+fn keys_down(_args: FuncArgs, vm: &VirtualMachine) -> PyResult {
+    let held = with_engine_keys(|keys| {
+        keys.iter()
+            .map(|(k, c)| (k.to_string(), c.to_string()))
+            .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+    let items = held
+        .into_iter()
+        .map(|(k, c)| {
+            vm.ctx
+                .new_tuple(vec![vm.ctx.new_str(k).into(), vm.ctx.new_str(c).into()])
+                .into()
+        })
+        .collect();
+    Ok(vm.ctx.new_list(items).into())
+}
+
 /// xos.print() - alias to builtin print (no longer needed, kept for compatibility)
 /// We'll set this to builtins.print in make_module instead
 
@@ -1092,6 +1137,16 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     module
         .set_attr("get_mouse", vm.new_function("get_mouse", get_mouse), vm)
         .unwrap();
+    module
+        .set_attr(
+            "_key_is_down",
+            vm.new_function("_key_is_down", key_is_down),
+            vm,
+        )
+        .unwrap();
+    module
+        .set_attr("_keys_down", vm.new_function("_keys_down", keys_down), vm)
+        .unwrap();
 
     // Make xos.print an alias to the builtin print function
     if let Ok(builtin_print) = vm.builtins.get_attr("print", vm) {
@@ -1213,6 +1268,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     module.set_attr("frame", frame_module, vm).unwrap();
 
     crate::json_api::register_json(&module, vm);
+    crate::windows::register_windows(&module, vm);
 
     // Add color palette submodule (single source of truth in py/colors.rs)
     let color_module = crate::colors::make_color_module(vm);
@@ -1229,6 +1285,31 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let sensors_module = crate::sensors::make_sensors_module(vm);
     module.set_attr("sensors", sensors_module, vm).unwrap();
 
+    let dialog_module = crate::dialog::make_dialog_module(vm);
+    module.set_attr("dialog", dialog_module, vm).unwrap();
+
+    // Add the gamepad submodule
+    let gamepad_module = crate::gamepad::make_gamepad_module(vm);
+    module.set_attr("gamepad", gamepad_module, vm).unwrap();
+
+    // Add the capture submodule (used by `Application.capture`)
+    let capture_module = crate::capture::make_capture_module(vm);
+    module.set_attr("capture", capture_module, vm).unwrap();
+
+    let window_module = crate::window::make_window_module(vm);
+    module.set_attr("window", window_module, vm).unwrap();
+
+    // Add the profiler submodule; `xos.profile` is its scope class
+    let profiler_module = crate::profiler::make_profiler_module(vm);
+    if let Ok(profile) = profiler_module.get_attr("profile", vm) {
+        module.set_attr("profile", profile, vm).unwrap();
+    }
+    module.set_attr("profiler", profiler_module, vm).unwrap();
+
+    // Add golden-image test scripting (`xos test`)
+    let testing_module = crate::testing::make_testing_module(vm);
+    module.set_attr("testing", testing_module, vm).unwrap();
+
     // Add the audio submodule
     let audio_module = crate::audio::make_audio_module(vm);
     module.set_attr("audio", audio_module, vm).unwrap();
@@ -1253,6 +1334,10 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
     let ai_module = crate::ai::make_ai_module(vm);
     module.set_attr("ai", ai_module, vm).unwrap();
 
+    // Add vision helpers (OCR on frames, webcam frames).
+    let vision_module = crate::vision::make_vision_module(vm);
+    module.set_attr("vision", vision_module, vm).unwrap();
+
     // Add the dialoguer submodule
     let dialoguer_module = crate::dialoguer::make_dialoguer_module(vm);
     module.set_attr("dialoguer", dialoguer_module, vm).unwrap();
@@ -1432,6 +1517,14 @@ pub fn make_module(vm: &VirtualMachine) -> PyRef<PyModule> {
         module.set_attr("Frame", frame_cls.clone(), vm).unwrap();
         let _ = vm.builtins.set_attr("Frame", frame_cls, vm);
     }
+    if let Ok(keyboard_cls) = scope.globals.get_item("Keyboard", vm) {
+        module.set_attr("Keyboard", keyboard_cls, vm).ok();
+    }
+    for name in ["PointerEvent", "GestureEvent", "GamepadEvent"] {
+        if let Ok(cls) = scope.globals.get_item(name, vm) {
+            module.set_attr(name, cls, vm).ok();
+        }
+    }
     if let Ok(sr_cls) = scope.globals.get_item("SafeRegion", vm) {
         module.set_attr("SafeRegion", sr_cls.clone(), vm).ok();
         let _ = vm.builtins.set_attr("__xos_SafeRegion_cls__", sr_cls, vm);
@@ -0,0 +1,24 @@
+"""Golden baseline for the `xos.rasterizer` primitives (`xos test tests`)."""
+
+import xos
+
+
+class Shapes(xos.Application):
+    def tick(self):
+        xos.rasterizer.fill(self.frame, (24, 28, 36, 255))
+        xos.rasterizer.rects_filled(self.frame, 16, 16, 120, 80, (220, 70, 60, 255))
+        xos.rasterizer.circles(self.frame, [(210.0, 56.0)], [40.0], (70, 170, 90, 255))
+        xos.rasterizer.lines(
+            self.frame, [(20.0, 210.0)], [(300.0, 120.0)], [6.0], (240, 200, 60, 255)
+        )
+        xos.rasterizer.triangles(
+            self.frame,
+            [(150.0, 226.0), (225.0, 140.0), (300.0, 226.0)],
+            (80, 140, 230, 255),
+        )
+
+
+def test_primitives(t):
+    t.start(Shapes(), 320, 240)
+    t.frames(1)
+    t.expect("primitives")
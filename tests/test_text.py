@@ -0,0 +1,24 @@
+"""Golden baseline for `xos.rasterizer.text`: sizes, colors and wrapping."""
+
+import xos
+
+
+class Labels(xos.Application):
+    def tick(self):
+        xos.rasterizer.fill(self.frame, (250, 250, 245, 255))
+        xos.rasterizer.text("xos golden baseline", 12.0, 12.0, 28.0, (20, 20, 20), 296.0)
+        xos.rasterizer.text("small 12px", 12.0, 56.0, 12.0, (160, 40, 40))
+        xos.rasterizer.text(
+            "long lines wrap at max_width instead of running off the frame",
+            12.0,
+            90.0,
+            18.0,
+            (40, 60, 140),
+            200.0,
+        )
+
+
+def test_labels(t):
+    t.start(Labels(), 320, 240)
+    t.frames(1)
+    t.expect("labels")
@@ -0,0 +1,47 @@
+"""Golden baseline for `xos.ui` widgets: a rect-backed button that recolors on click, plus a
+read-only `Text` label."""
+
+import xos
+
+
+class Panel(xos.Application):
+    def __init__(self):
+        super().__init__()
+        self.background = xos.ui.UiRect(0.0, 0.0, 1.0, 1.0, color=(30, 34, 44))
+        self.face = xos.ui.UiRect(0.25, 0.6, 0.75, 0.85, color=(70, 90, 160))
+        self.button = xos.ui.UiButton(0.25, 0.6, 0.75, 0.85, self.press)
+        self.label = xos.ui.Text(
+            "press the button",
+            0.05,
+            0.05,
+            0.95,
+            0.5,
+            size=24.0,
+            color=(235, 235, 235),
+            editable=False,
+            selectable=False,
+            scrollable=False,
+            show_cursor=False,
+        )
+
+    def press(self):
+        self.face.color = (200, 120, 40)
+        self.label.text = "pressed"
+
+    def tick(self):
+        self.background.render(self)
+        self.face.render(self)
+        self.label.tick(self)
+        self.label.render(self)
+
+    def on_events(self):
+        self.button.on_events(self)
+
+
+def test_button_press(t):
+    t.start(Panel(), 320, 240)
+    t.frames(1)
+    t.expect("idle")
+    t.click(160, 170)
+    t.frames(1)
+    t.expect("pressed")
@@ -0,0 +1,36 @@
+//! Runs the `test_*.py` golden tests in this directory (what `xos test` does) against the
+//! committed goldens under `golden/`.
+//!
+//! Frames are rasterized on whichever GPU adapter (or CPU fallback) the machine offers; the
+//! adapter is not pinned, so edge pixels can round differently from the machine that recorded
+//! the goldens. The run therefore allows [`MAX_DIFF_RATIO`] of the pixels past the per-pixel
+//! threshold instead of the exact-match default.
+
+use std::path::Path;
+use xos::engine::golden::Tolerance;
+use xos::python_api::testing::{collect_visual_test_files, run_visual_tests, VisualTestOptions};
+
+/// 0.5% of a frame: room for anti-aliased edges, far below a missing or moved shape or label.
+const MAX_DIFF_RATIO: f32 = 0.005;
+
+#[test]
+fn committed_goldens_match() {
+    xos::init_hooks();
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let files = collect_visual_test_files(&[dir]);
+    assert!(!files.is_empty(), "no test_*.py files under tests/");
+
+    let options = VisualTestOptions {
+        tolerance: Some(Tolerance {
+            max_diff_ratio: MAX_DIFF_RATIO,
+            ..Tolerance::default()
+        }),
+        ..VisualTestOptions::default()
+    };
+    let summary = run_visual_tests(&files, &options);
+    assert_eq!(summary.failed, 0, "golden tests failed (see output above)");
+    if std::env::var_os(xos::engine::golden::UPDATE_GOLDENS_ENV).is_none() {
+        assert_eq!(summary.written, 0, "goldens are missing from tests/golden/");
+    }
+    assert_eq!(summary.passed, 3);
+}